@@ -69,12 +69,12 @@ task_worktree="$(jq -r --arg id "$TASK_ID" '(.tasks // [])[] | select(.id == $id
 tool_json="${task_worktree}/.macc/tool.json"
 [[ -f "$tool_json" ]] || { echo "Error: tool.json not found: $tool_json" >&2; exit 1; }
 
-runner="$(jq -r '.performer.runner // ""' "$tool_json")"
-[[ -n "$runner" && "$runner" != "null" ]] || { echo "Error: performer.runner missing in tool.json" >&2; exit 1; }
-if [[ "$runner" != /* ]]; then
-  runner="${REPO_DIR}/${runner}"
-fi
-[[ -x "$runner" ]] || { echo "Error: runner not executable: $runner" >&2; exit 1; }
+command="$(jq -r '.performer.command // ""' "$tool_json")"
+[[ -n "$command" && "$command" != "null" ]] || { echo "Error: performer.command missing in tool.json" >&2; exit 1; }
+command -v "$command" >/dev/null 2>&1 || { echo "Error: tool command not found in PATH: $command" >&2; exit 1; }
+mapfile -t command_args < <(jq -r '(.performer.args // [])[]' "$tool_json")
+prompt_mode="$(jq -r '.performer.prompt.mode // "stdin"' "$tool_json")"
+prompt_arg="$(jq -r '.performer.prompt.arg // ""' "$tool_json")"
 
 current_branch="$(git -C "$REPO_DIR" rev-parse --abbrev-ref HEAD 2>/dev/null || true)"
 status_short="$(git -C "$REPO_DIR" status --short 2>&1 || true)"
@@ -132,14 +132,11 @@ Instructions:
 EOF
 
 set +e
-"$runner" \
-  --prompt-file "$prompt_file" \
-  --tool-json "$tool_json" \
-  --repo "$REPO_DIR" \
-  --worktree "$REPO_DIR" \
-  --task-id "$TASK_ID" \
-  --attempt 1 \
-  --max-attempts 1
+if [[ "$prompt_mode" == "arg" ]]; then
+  (cd "$REPO_DIR" && "$command" "${command_args[@]}" "$prompt_arg" "$(cat "$prompt_file")")
+else
+  (cd "$REPO_DIR" && "$command" "${command_args[@]}" <"$prompt_file")
+fi
 rc=$?
 set -e
 
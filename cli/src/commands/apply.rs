@@ -9,6 +9,7 @@ pub struct ApplyCommand {
     allow_user_scope: bool,
     json: bool,
     explain: bool,
+    all: bool,
 }
 
 impl ApplyCommand {
@@ -19,6 +20,7 @@ impl ApplyCommand {
         allow_user_scope: bool,
         json: bool,
         explain: bool,
+        all: bool,
     ) -> Self {
         Self {
             app,
@@ -27,12 +29,23 @@ impl ApplyCommand {
             allow_user_scope,
             json,
             explain,
+            all,
         }
     }
 }
 
 impl Command for ApplyCommand {
     fn run(&self) -> Result<()> {
+        if self.all {
+            return crate::commands::lifecycle_support::apply_all(
+                &self.app,
+                self.tools.as_deref(),
+                self.dry_run,
+                self.allow_user_scope,
+                self.json,
+                self.explain,
+            );
+        }
         crate::commands::lifecycle_support::apply(
             &self.app,
             self.tools.as_deref(),
@@ -0,0 +1,163 @@
+use crate::commands::AppContext;
+use crate::commands::Command;
+use crate::ConfigCommands;
+use macc_core::config::layers;
+use macc_core::schema::{self, SchemaIssue};
+use macc_core::tool::loader::ToolSpecLoader;
+use macc_core::{MaccError, Result};
+use std::path::{Path, PathBuf};
+
+pub struct ConfigCommand<'a> {
+    app: AppContext,
+    command: &'a ConfigCommands,
+}
+
+impl<'a> ConfigCommand<'a> {
+    pub fn new(app: AppContext, command: &'a ConfigCommands) -> Self {
+        Self { app, command }
+    }
+}
+
+impl<'a> Command for ConfigCommand<'a> {
+    fn run(&self) -> Result<()> {
+        let paths = self.app.project_paths()?;
+        match self.command {
+            ConfigCommands::Show { effective, origin } => {
+                if !*effective {
+                    if !macc_core::monorepo::parent_project_roots(&paths).is_empty() {
+                        // Sub-project files are partial overlays; print them verbatim.
+                        print!("{}", read(&paths.config_path)?);
+                        return Ok(());
+                    }
+                    let canonical = macc_core::load_canonical_config(&paths.config_path)?;
+                    print!("{}", to_yaml(&canonical)?);
+                    return Ok(());
+                }
+
+                let profile = layers::selected_profile(&paths);
+                let effective =
+                    layers::load_effective_config_with_profile(&paths, profile.as_deref())?;
+                println!("# profile: {}", profile.as_deref().unwrap_or("(none)"));
+                for layer in &effective.layers {
+                    println!("# layer {}: {}", layer.name, layer.path.display());
+                }
+                if *origin {
+                    for entry in effective.values() {
+                        println!("{} = {}  [{}]", entry.path, entry.value, entry.origin);
+                    }
+                } else {
+                    print!("{}", to_yaml(&effective.config)?);
+                }
+                Ok(())
+            }
+            ConfigCommands::Validate { schema_out } => {
+                if let Some(dir) = schema_out {
+                    for path in schema::write_schemas(Path::new(dir))? {
+                        println!("Wrote {}", path.display());
+                    }
+                }
+
+                let profile = layers::selected_profile(&paths);
+                if let Some(profile) = profile.as_deref() {
+                    layers::validate_profile_name(profile)?;
+                }
+                let mut checked = 0usize;
+                let mut failures = 0usize;
+                let config_layers = layers::config_layers(&paths, profile.as_deref());
+                // A sub-project's own file only needs to be complete once merged with its parents.
+                let inherits = config_layers
+                    .iter()
+                    .any(|layer| layer.name.starts_with("parent:"));
+                for layer in config_layers {
+                    if !layer.path.exists() {
+                        if layer.required {
+                            println!(
+                                "{}: missing {} config layer",
+                                layer.path.display(),
+                                layer.name
+                            );
+                            failures += 1;
+                        }
+                        continue;
+                    }
+                    let content = read(&layer.path)?;
+                    // Only a standalone project layer must be a complete config on its own.
+                    let partial = layer.name != "project" || inherits;
+                    checked += 1;
+                    failures += report(
+                        &layer.path,
+                        &schema::validate_config_yaml(&content, partial),
+                    );
+                }
+                for file in tool_spec_files(&ToolSpecLoader::default_search_paths(&paths.root)) {
+                    let content = read(&file)?;
+                    checked += 1;
+                    failures += report(&file, &schema::validate_tool_spec_yaml(&content));
+                }
+
+                if failures > 0 {
+                    return Err(MaccError::Validation(format!(
+                        "{} issue(s) found in {} file(s)",
+                        failures, checked
+                    )));
+                }
+                println!("{} file(s) valid", checked);
+                Ok(())
+            }
+        }
+    }
+}
+
+fn read(path: &Path) -> Result<String> {
+    std::fs::read_to_string(path).map_err(|e| MaccError::Io {
+        path: path.to_string_lossy().into(),
+        action: "read file for validation".into(),
+        source: e,
+    })
+}
+
+fn report(path: &Path, issues: &[SchemaIssue]) -> usize {
+    for issue in issues {
+        let location = match issue.line {
+            Some(line) => format!("{}:{}", path.display(), line),
+            None => path.display().to_string(),
+        };
+        let field = if issue.path.is_empty() {
+            "<root>"
+        } else {
+            issue.path.as_str()
+        };
+        println!("{}: {}: {}", location, field, issue.message);
+    }
+    issues.len()
+}
+
+fn tool_spec_files(dirs: &[PathBuf]) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for dir in dirs {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            continue;
+        };
+        let mut found: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                path.is_file()
+                    && path
+                        .file_name()
+                        .and_then(|name| name.to_str())
+                        .is_some_and(|name| {
+                            name.ends_with(".tool.yaml") || name.ends_with(".tool.json")
+                        })
+            })
+            .collect();
+        found.sort();
+        files.extend(found);
+    }
+    files
+}
+
+fn to_yaml(config: &macc_core::config::CanonicalConfig) -> Result<String> {
+    config
+        .to_yaml()
+        .map_err(|e| MaccError::Validation(format!("Failed to serialize config: {}", e)))
+}
@@ -14,7 +14,18 @@ impl macc_core::service::lifecycle::LifecycleFetchMaterializer for CliFetchMater
     }
 }
 
-pub(crate) struct CliLifecycleUi;
+pub(crate) struct CliLifecycleUi {
+    /// Config profile from `--profile`, forwarded to the TUI.
+    profile: Option<String>,
+}
+
+impl CliLifecycleUi {
+    pub(crate) fn new(app: &AppContext) -> Self {
+        Self {
+            profile: app.profile.clone(),
+        }
+    }
+}
 
 impl InteractionHandler for CliLifecycleUi {
     fn info(&self, message: &str) {
@@ -76,11 +87,13 @@ impl macc_core::service::lifecycle::LifecycleUi for CliLifecycleUi {
     }
 
     fn run_tui(&self) -> Result<()> {
-        macc_tui::run_tui().map_err(|e| MaccError::Io {
-            path: "tui".into(),
-            action: "run_tui".into(),
-            source: std::io::Error::other(e.to_string()),
-        })
+        macc_tui::run_tui_with_launch(macc_tui::LaunchMode::Default, self.profile.clone()).map_err(
+            |e| MaccError::Io {
+                path: "tui".into(),
+                action: "run_tui".into(),
+                source: std::io::Error::other(e.to_string()),
+            },
+        )
     }
 
     fn set_current_dir(&self, path: &std::path::Path) -> Result<()> {
@@ -123,21 +136,23 @@ impl macc_core::service::lifecycle::LifecycleUi for CliLifecycleUi {
 pub(crate) fn init(app: &AppContext, force: bool, wizard: bool) -> Result<()> {
     macc_core::service::lifecycle::init(
         &app.cwd,
+        app.profile.as_deref(),
         app.engine.as_ref(),
         force,
         wizard,
-        &CliLifecycleUi,
+        &CliLifecycleUi::new(app),
     )
 }
 
 pub(crate) fn plan(app: &AppContext, tools: Option<&str>, json: bool, explain: bool) -> Result<()> {
     macc_core::service::lifecycle::plan(
         &app.cwd,
+        app.profile.as_deref(),
         app.engine.as_ref(),
         tools,
         json,
         explain,
-        &CliLifecycleUi,
+        &CliLifecycleUi::new(app),
         &CliFetchMaterializer,
     )
 }
@@ -152,17 +167,97 @@ pub(crate) fn apply(
 ) -> Result<()> {
     macc_core::service::lifecycle::apply(
         &app.cwd,
+        app.profile.as_deref(),
         app.engine.as_ref(),
         tools,
         dry_run,
         allow_user_scope,
         json,
         explain,
-        &CliLifecycleUi,
+        &CliLifecycleUi::new(app),
         &CliFetchMaterializer,
     )
 }
 
+/// Runs `step` for every MACC project under the repository (parents first) and reports
+/// which sub-projects failed instead of stopping at the first error.
+pub(crate) fn for_each_project(
+    app: &AppContext,
+    json: bool,
+    step: impl Fn(&std::path::Path) -> Result<()>,
+) -> Result<()> {
+    let paths = app.project_paths()?;
+    let walk_root = macc_core::monorepo::projects_walk_root(&paths);
+    let projects = macc_core::monorepo::discover_projects(&walk_root);
+    let mut failed = Vec::new();
+    for project in &projects {
+        let label = macc_core::monorepo::relative_project_path(&walk_root, &project.root);
+        // Keep stdout parseable when each project prints a JSON document.
+        if json {
+            eprintln!("== project {}", label);
+        } else {
+            println!("== project {}", label);
+        }
+        if let Err(err) = step(&project.root) {
+            eprintln!("Project {} failed: {}", label, err);
+            failed.push(label);
+        }
+    }
+    if !failed.is_empty() {
+        return Err(MaccError::Validation(format!(
+            "{} of {} project(s) failed: {}",
+            failed.len(),
+            projects.len(),
+            failed.join(", ")
+        )));
+    }
+    Ok(())
+}
+
+pub(crate) fn plan_all(
+    app: &AppContext,
+    tools: Option<&str>,
+    json: bool,
+    explain: bool,
+) -> Result<()> {
+    for_each_project(app, json, |root| {
+        macc_core::service::lifecycle::plan(
+            root,
+            app.profile.as_deref(),
+            app.engine.as_ref(),
+            tools,
+            json,
+            explain,
+            &CliLifecycleUi::new(app),
+            &CliFetchMaterializer,
+        )
+    })
+}
+
+pub(crate) fn apply_all(
+    app: &AppContext,
+    tools: Option<&str>,
+    dry_run: bool,
+    allow_user_scope: bool,
+    json: bool,
+    explain: bool,
+) -> Result<()> {
+    for_each_project(app, json, |root| {
+        macc_core::service::lifecycle::apply(
+            root,
+            app.profile.as_deref(),
+            app.engine.as_ref(),
+            tools,
+            dry_run,
+            allow_user_scope,
+            json,
+            explain,
+            &CliLifecycleUi::new(app),
+            &CliFetchMaterializer,
+        )
+    })
+}
+
 pub(crate) fn quickstart(
     app: &AppContext,
     assume_yes: bool,
@@ -171,11 +266,12 @@ pub(crate) fn quickstart(
 ) -> Result<()> {
     macc_core::service::lifecycle::quickstart(
         &app.cwd,
+        app.profile.as_deref(),
         app.engine.as_ref(),
         assume_yes,
         apply,
         no_tui,
-        &CliLifecycleUi,
+        &CliLifecycleUi::new(app),
         &CliFetchMaterializer,
     )
 }
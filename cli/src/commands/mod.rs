@@ -1,5 +1,5 @@
 use macc_core::config::CanonicalConfig;
-use macc_core::{load_canonical_config, ProjectPaths, Result};
+use macc_core::{load_effective_config, ProjectPaths, Result};
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 
@@ -8,6 +8,7 @@ pub mod backups;
 pub mod catalog;
 pub mod catalog_support;
 pub mod clear;
+pub mod config;
 pub mod context;
 pub mod coordinator;
 pub mod doctor;
@@ -16,6 +17,7 @@ pub mod install;
 pub mod lifecycle_support;
 pub mod logs;
 pub mod migrate;
+pub mod performer;
 pub mod plan;
 pub mod quickstart;
 pub mod restore;
@@ -30,6 +32,8 @@ pub trait Command {
 pub struct AppContext {
     pub cwd: PathBuf,
     pub engine: crate::services::engine_provider::SharedEngine,
+    /// Config profile from `--profile`, attached to every [`ProjectPaths`] handed out.
+    pub profile: Option<String>,
     cache: Arc<AppContextCache>,
 }
 
@@ -40,10 +44,15 @@ struct AppContextCache {
 }
 
 impl AppContext {
-    pub fn new(cwd: PathBuf, engine: crate::services::engine_provider::SharedEngine) -> Self {
+    pub fn new(
+        cwd: PathBuf,
+        engine: crate::services::engine_provider::SharedEngine,
+        profile: Option<String>,
+    ) -> Self {
         Self {
             cwd,
             engine,
+            profile,
             cache: Arc::new(AppContextCache::default()),
         }
     }
@@ -59,7 +68,7 @@ impl AppContext {
             return Ok(cached);
         }
 
-        let paths = macc_core::find_project_root(&self.cwd)?;
+        let paths = macc_core::find_project_root(&self.cwd)?.with_profile(self.profile.clone());
         let mut guard =
             self.cache.project_paths.lock().map_err(|_| {
                 macc_core::MaccError::Validation("project cache lock poisoned".into())
@@ -69,7 +78,10 @@ impl AppContext {
     }
 
     pub fn ensure_initialized_paths(&self) -> Result<ProjectPaths> {
-        let paths = self.engine.project_ensure_initialized_paths(&self.cwd)?;
+        let paths = self
+            .engine
+            .project_ensure_initialized_paths(&self.cwd)?
+            .with_profile(self.profile.clone());
         let mut guard =
             self.cache.project_paths.lock().map_err(|_| {
                 macc_core::MaccError::Validation("project cache lock poisoned".into())
@@ -90,7 +102,7 @@ impl AppContext {
         }
 
         let paths = self.project_paths()?;
-        let canonical = load_canonical_config(&paths.config_path)?;
+        let canonical = load_effective_config(&paths)?;
         let mut guard = self.cache.canonical.lock().map_err(|_| {
             macc_core::MaccError::Validation("canonical cache lock poisoned".into())
         })?;
@@ -0,0 +1,51 @@
+use crate::commands::AppContext;
+use crate::commands::Command;
+use crate::PerformerCommands;
+use macc_core::Result;
+use std::path::PathBuf;
+
+pub struct PerformerCommand<'a> {
+    app: AppContext,
+    command: &'a PerformerCommands,
+}
+
+impl<'a> PerformerCommand<'a> {
+    pub fn new(app: AppContext, command: &'a PerformerCommands) -> Self {
+        Self { app, command }
+    }
+}
+
+impl<'a> Command for PerformerCommand<'a> {
+    fn run(&self) -> Result<()> {
+        match self.command {
+            PerformerCommands::Run {
+                worktree,
+                task_id,
+                prd,
+                max_attempts,
+            } => {
+                let paths = self.app.project_paths()?;
+                let mut request = self.app.engine.performer_request(&paths, worktree)?;
+                if let Some(task_id) = task_id {
+                    request.task_id = task_id.clone();
+                }
+                if let Some(prd) = prd {
+                    request.prd = PathBuf::from(prd);
+                }
+                if let Some(max_attempts) = max_attempts {
+                    request.limits.tool_max_attempts = (*max_attempts).max(1);
+                }
+                let outcome = self.app.engine.performer_run(&request)?;
+                println!(
+                    "Performer finished: {} task(s) completed{}",
+                    outcome.completed_tasks.len(),
+                    outcome
+                        .commit
+                        .map(|sha| format!(", commit {}", sha))
+                        .unwrap_or_default()
+                );
+                Ok(())
+            }
+        }
+    }
+}
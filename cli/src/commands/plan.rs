@@ -7,21 +7,37 @@ pub struct PlanCommand {
     tools: Option<String>,
     json: bool,
     explain: bool,
+    all: bool,
 }
 
 impl PlanCommand {
-    pub fn new(app: AppContext, tools: Option<String>, json: bool, explain: bool) -> Self {
+    pub fn new(
+        app: AppContext,
+        tools: Option<String>,
+        json: bool,
+        explain: bool,
+        all: bool,
+    ) -> Self {
         Self {
             app,
             tools,
             json,
             explain,
+            all,
         }
     }
 }
 
 impl Command for PlanCommand {
     fn run(&self) -> Result<()> {
+        if self.all {
+            return crate::commands::lifecycle_support::plan_all(
+                &self.app,
+                self.tools.as_deref(),
+                self.json,
+                self.explain,
+            );
+        }
         crate::commands::lifecycle_support::plan(
             &self.app,
             self.tools.as_deref(),
@@ -1,7 +1,7 @@
 use crate::commands::AppContext;
 use crate::commands::Command;
 use crate::ToolCommands;
-use macc_core::Result;
+use macc_core::{MaccError, Result};
 pub struct ToolCommand<'a> {
     app: AppContext,
     command: &'a ToolCommands,
@@ -54,6 +54,34 @@ impl<'a> Command for ToolCommand<'a> {
                     .tooling_show_outdated(&paths, only.as_deref(), &reporter)?;
                 Ok(())
             }
+            ToolCommands::History {
+                tool_id,
+                limit,
+                json,
+            } => {
+                let mut records = self
+                    .app
+                    .engine
+                    .tooling_history(&paths, tool_id.as_deref())?;
+                if let Some(limit) = limit {
+                    let skip = records.len().saturating_sub(*limit);
+                    records.drain(..skip);
+                }
+                if *json {
+                    let out = serde_json::to_string_pretty(&records).map_err(|e| {
+                        MaccError::Validation(format!("Failed to serialize tool history: {}", e))
+                    })?;
+                    println!("{}", out);
+                } else if records.is_empty() {
+                    println!("No tool updates recorded.");
+                } else {
+                    print!(
+                        "{}",
+                        macc_core::service::tooling::format_tool_history_table(&records)
+                    );
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -1,12 +1,15 @@
 use crate::coordinator::legacy_helpers::{
     coordinator_select_ready_task_action, stop_coordinator_process_groups, NativeCoordinatorLogger,
 };
-use crate::coordinator::render::print_status_summary;
+use crate::coordinator::render::{
+    print_replay, print_run_list, print_simulation, print_status_summary, print_task_list,
+};
 use macc_core::coordinator::engine as coordinator_engine;
+use macc_core::coordinator::operator::TaskCommandOutput;
 use macc_core::coordinator::types::CoordinatorEnvConfig;
 use macc_core::coordinator_storage::CoordinatorStorageMode;
 use macc_core::service::coordinator_workflow::{CoordinatorAction, CoordinatorActionRequest};
-use macc_core::{load_canonical_config, MaccError, Result};
+use macc_core::{load_effective_config, MaccError, Result};
 use std::path::Path;
 
 fn build_native_logger(
@@ -42,6 +45,18 @@ pub struct CoordinatorCommandInput {
     pub graceful: bool,
     pub remove_worktrees: bool,
     pub remove_branches: bool,
+    /// Words after the action (e.g. `export <run-id>` for `trace`).
+    pub operands: Vec<String>,
+    /// Output format for export actions (`--format`).
+    pub format: Option<String>,
+    /// Output path for export actions (`--out`).
+    pub out: Option<String>,
+    /// Approval or operator comment (`--comment`).
+    pub comment: Option<String>,
+    /// Last timestamp or event id taken into account by `replay` (`--until`).
+    pub until: Option<String>,
+    /// Simulation profile of `simulate` (`--profile`).
+    pub profile: Option<String>,
     pub env_cfg: CoordinatorEnvConfig,
     pub extra_args: Vec<String>,
 }
@@ -56,9 +71,12 @@ impl ProjectContext {
     fn load(
         absolute_cwd: &Path,
         engine: &crate::services::engine_provider::SharedEngine,
+        config_profile: Option<String>,
     ) -> Result<Self> {
-        let paths = engine.project_ensure_initialized_paths(absolute_cwd)?;
-        let canonical = load_canonical_config(&paths.config_path)?;
+        let paths = engine
+            .project_ensure_initialized_paths(absolute_cwd)?
+            .with_profile(config_profile);
+        let canonical = load_effective_config(&paths)?;
         let coordinator_cfg = canonical.automation.coordinator.clone();
         Ok(Self {
             paths,
@@ -71,6 +89,7 @@ impl ProjectContext {
 pub fn handle(
     absolute_cwd: &Path,
     engine: &crate::services::engine_provider::SharedEngine,
+    config_profile: Option<String>,
     input: CoordinatorCommandInput,
 ) -> Result<()> {
     let action: CoordinatorAction = input.action.parse()?;
@@ -79,18 +98,27 @@ pub fn handle(
         return Ok(());
     }
 
-    let context = ProjectContext::load(absolute_cwd, engine)?;
+    let context = ProjectContext::load(absolute_cwd, engine, config_profile)?;
     let paths = &context.paths;
     let canonical = &context.canonical;
     let coordinator_cfg = context.coordinator_cfg.as_ref();
 
+    if let Some(listen) = &input.env_cfg.metrics_listen {
+        listen.parse::<std::net::SocketAddr>().map_err(|e| {
+            MaccError::Validation(format!("Invalid --metrics-listen '{}': {}", listen, e))
+        })?;
+    }
     if action == CoordinatorAction::Run && !input.no_tui {
-        return macc_tui::run_tui_with_launch(macc_tui::LaunchMode::CoordinatorRun).map_err(|e| {
-            MaccError::Io {
-                path: "tui".into(),
-                action: "run_tui coordinator live".into(),
-                source: std::io::Error::other(e.to_string()),
-            }
+        return macc_tui::run_tui_with_launch(
+            macc_tui::LaunchMode::CoordinatorRun {
+                metrics_listen: input.env_cfg.metrics_listen.clone(),
+            },
+            paths.profile.clone(),
+        )
+        .map_err(|e| MaccError::Io {
+            path: "tui".into(),
+            action: "run_tui coordinator live".into(),
+            source: std::io::Error::other(e.to_string()),
         });
     }
 
@@ -146,6 +174,21 @@ pub fn handle(
     };
     let logger_adapter = native_logger.as_ref().map(LoggerAdapter);
 
+    let mut action_args = Vec::new();
+    for (flag, value) in [
+        ("--format", &input.format),
+        ("--out", &input.out),
+        ("--comment", &input.comment),
+        ("--until", &input.until),
+        ("--profile", &input.profile),
+    ] {
+        if let Some(value) = value {
+            action_args.push(flag.to_string());
+            action_args.push(value.clone());
+        }
+    }
+    action_args.extend(input.extra_args.iter().cloned());
+
     let response = engine.coordinator_perform_action_workflow(
         paths,
         action,
@@ -153,7 +196,8 @@ pub fn handle(
             canonical: Some(canonical),
             coordinator_cfg,
             env_cfg: &input.env_cfg,
-            extra_args: &input.extra_args,
+            extra_args: &action_args,
+            operands: &input.operands,
             logger: logger_adapter.as_ref().map(|adapter| {
                 adapter as &dyn macc_core::coordinator::control_plane::CoordinatorLog
             }),
@@ -189,6 +233,59 @@ pub fn handle(
     } else if action == CoordinatorAction::StorageVerify {
         println!("Coordinator storage parity OK (json == sqlite).");
     }
+    if let Some(path) = response.metrics_path {
+        println!("Coordinator metrics written: {}", path.display());
+    }
+    if let Some(path) = response.trace_path {
+        println!("Coordinator trace written: {}", path.display());
+    }
+    if let Some(report) = response.report {
+        print!("{}", report);
+    }
+    if let Some(path) = response.report_path {
+        println!("Coordinator report written: {}", path.display());
+    }
+    if let Some(runs) = response.runs {
+        print_run_list(&paths.root, &runs);
+    }
+    if let Some(replay) = &response.replay {
+        print_replay(replay);
+        if let Some((_, divergences)) = &replay.verification {
+            if !divergences.is_empty() {
+                return Err(MaccError::Validation(format!(
+                    "Replayed registry diverges from the live registry on {} task(s)",
+                    divergences.len()
+                )));
+            }
+        }
+    }
+    if let Some((report, out)) = &response.simulation {
+        print_simulation(report, out.as_deref());
+    }
+    if let Some((task_id, approved)) = response.approval {
+        if approved {
+            println!(
+                "Task {} approved; it merges on the next coordinator cycle.",
+                task_id
+            );
+        } else {
+            println!(
+                "Task {} rejected; it returns to the fix phase with the comment as review feedback.",
+                task_id
+            );
+        }
+    }
+    match response.task {
+        Some(TaskCommandOutput::List(tasks)) => print_task_list(&tasks),
+        Some(TaskCommandOutput::Show(task)) => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&task).unwrap_or_default()
+            )
+        }
+        Some(TaskCommandOutput::Changed { message, .. }) => println!("{}", message),
+        None => {}
+    }
     if let Some(removed) = response.removed_worktrees {
         println!("Removed {} worktree(s).", removed);
         println!("Pruned git worktrees.");
@@ -244,6 +244,8 @@ pub(crate) fn coordinator_select_ready_task_action(
             .map_err(|e| MaccError::Validation(format!("Invalid max-parallel value: {}", e)))?,
         default_tool,
         default_base_branch,
+        avoid_scope_overlap: false,
+        touched_files: Default::default(),
     };
 
     if let Some(selected) =
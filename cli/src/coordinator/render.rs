@@ -1,4 +1,7 @@
-use macc_core::service::coordinator_workflow::CoordinatorStatus;
+use macc_core::coordinator::operator::TaskSummary;
+use macc_core::coordinator::runs::RunManifest;
+use macc_core::coordinator::simulate::SimulationReport;
+use macc_core::service::coordinator_workflow::{CoordinatorStatus, ReplayResult};
 use std::path::Path;
 
 pub(crate) fn print_status_summary(repo_root: &Path, status: &CoordinatorStatus) {
@@ -27,6 +30,49 @@ pub(crate) fn print_status_summary(repo_root: &Path, status: &CoordinatorStatus)
     } else {
         println!("Paused: no");
     }
+    if let Some(lease) = &status.lease {
+        println!("Lease: {}", lease.describe());
+    }
+    if !status.usage.is_empty() {
+        println!("Usage: {}", status.usage.summary());
+        if let Some((run_id, run)) = &status.run_usage {
+            println!("  run {}: {}", run_id, run.summary());
+        }
+    }
+    if let Some(budget) = status.budget_usd {
+        let spent = status
+            .run_usage
+            .as_ref()
+            .map(|(_, run)| run.cost_usd)
+            .unwrap_or(0.0);
+        println!("Budget: ${:.2} (run spent ${:.4})", budget, spent);
+    }
+    if !status.tool_cooldowns.is_empty() {
+        println!("Throttled tools:");
+        for cooldown in &status.tool_cooldowns {
+            let window = if cooldown.remaining_seconds > 0 {
+                format!("cooldown {}s", cooldown.remaining_seconds)
+            } else {
+                "cooldown over".to_string()
+            };
+            println!(
+                "  {}: {}, strikes={}, max_parallel={} ({})",
+                cooldown.tool, window, cooldown.strikes, cooldown.max_parallel, cooldown.reason
+            );
+        }
+    }
+    if !status.pending_approvals.is_empty() {
+        println!(
+            "Awaiting approval: {} (macc coordinator approve|reject <task-id>)",
+            status.pending_approvals.join(", ")
+        );
+    }
+    if !status.scope_waits.is_empty() {
+        println!("Held back by scope overlap:");
+        for wait in &status.scope_waits {
+            println!("  {}: {}", wait.task_id, wait.reason);
+        }
+    }
     if let Some(latest_error) = &status.latest_error {
         println!("Latest error: {}", latest_error);
     }
@@ -52,3 +98,206 @@ pub(crate) fn print_status_summary(repo_root: &Path, status: &CoordinatorStatus)
         }
     }
 }
+
+pub(crate) fn print_run_list(repo_root: &Path, runs: &[RunManifest]) {
+    if runs.is_empty() {
+        println!(
+            "No archived coordinator runs in {}.",
+            macc_core::coordinator::runs::runs_dir(repo_root).display()
+        );
+        return;
+    }
+    println!(
+        "{:<40} {:<8} {:<22} {:<22} TASKS",
+        "RUN", "STATUS", "STARTED", "ENDED"
+    );
+    for run in runs {
+        let tasks = run
+            .task_states
+            .iter()
+            .map(|(state, count)| format!("{}={}", state, count))
+            .collect::<Vec<_>>()
+            .join(" ");
+        println!(
+            "{:<40} {:<8} {:<22} {:<22} {}",
+            run.run_id,
+            run.status,
+            run.started_at.as_deref().unwrap_or("-"),
+            run.ended_at.as_deref().unwrap_or("-"),
+            tasks
+        );
+    }
+}
+
+pub(crate) fn print_replay(replay: &ReplayResult) {
+    let outcome = &replay.outcome;
+    match &outcome.last_event {
+        Some((event_id, ts)) => println!(
+            "Replayed {} task mutation(s) up to {} ({}).",
+            outcome.applied, event_id, ts
+        ),
+        None => println!("The coordinator event log is empty."),
+    }
+    if !outcome.errors.is_empty() {
+        println!(
+            "Skipped {} mutation(s) that do not apply:",
+            outcome.errors.len()
+        );
+        for error in &outcome.errors {
+            println!(
+                "  {} task={}: {}",
+                error.event_id, error.task_id, error.message
+            );
+        }
+    }
+    match &replay.verification {
+        Some((checked, divergences)) if divergences.is_empty() => {
+            println!("Replay matches the live registry ({} task(s)).", checked)
+        }
+        Some((checked, divergences)) => {
+            println!(
+                "Replay diverges from the live registry on {} task(s) (live tasks: {}):",
+                divergences.len(),
+                checked
+            );
+            for divergence in divergences {
+                let fields = divergence
+                    .differences
+                    .iter()
+                    .map(|d| format!("{} replayed={} live={}", d.field, d.replayed, d.live))
+                    .collect::<Vec<_>>()
+                    .join("; ");
+                println!("  {}: {}", divergence.task_id, fields);
+            }
+        }
+        None => print_task_list(&macc_core::coordinator::operator::task_summaries(
+            &outcome.registry,
+        )),
+    }
+    if let Some(path) = &replay.out {
+        println!("Replayed registry written: {}", path.display());
+    }
+}
+
+pub(crate) fn print_simulation(report: &SimulationReport, out: Option<&Path>) {
+    let hours = |seconds: f64| seconds / 3600.0;
+    match &report.stopped {
+        None => println!(
+            "Simulation completed in {} cycle(s) (seed {}).",
+            report.cycles, report.seed
+        ),
+        Some(reason) => println!(
+            "Simulation stopped after {} cycle(s) (seed {}): {}",
+            report.cycles, report.seed, reason
+        ),
+    }
+    println!(
+        "Makespan: {:.0}s ({:.2}h)",
+        report.makespan_seconds,
+        hours(report.makespan_seconds)
+    );
+    println!(
+        "Tasks: {} merged, {} blocked, {} unfinished (of {})",
+        report.merged, report.blocked, report.unfinished, report.total
+    );
+    println!(
+        "Utilization: {:.1}% of max_parallel={}",
+        report.utilization * 100.0,
+        report.max_parallel
+    );
+    if !report.tools.is_empty() {
+        println!(
+            "{:<16} {:>8} {:>9} {:>7} {:>10} {:>12}",
+            "TOOL", "CAPACITY", "ATTEMPTS", "FAILED", "BUSY(h)", "UTILIZATION"
+        );
+        for tool in &report.tools {
+            println!(
+                "{:<16} {:>8} {:>9} {:>7} {:>10.2} {:>11.1}%",
+                tool.tool,
+                tool.capacity,
+                tool.attempts,
+                tool.failed_attempts,
+                hours(tool.busy_seconds),
+                tool.utilization * 100.0
+            );
+        }
+    }
+    if !report.error_codes.is_empty() {
+        let codes = report
+            .error_codes
+            .iter()
+            .map(|(code, count)| format!("{}={}", code, count))
+            .collect::<Vec<_>>()
+            .join(" ");
+        println!("Failed attempts: {} (retries: {})", codes, report.retries);
+    }
+    if !report.failure_propagation.is_empty() {
+        println!("Failure propagation:");
+        for failure in &report.failure_propagation {
+            let stranded = if failure.stranded.is_empty() {
+                "-".to_string()
+            } else {
+                failure.stranded.join(",")
+            };
+            println!(
+                "  {} blocked in {} ({}); stranded: {}",
+                failure.task_id, failure.phase, failure.error, stranded
+            );
+        }
+    }
+    let unfinished: Vec<_> = report
+        .tasks
+        .iter()
+        .filter(|task| task.state != "merged" && task.state != "blocked")
+        .collect();
+    if report.stopped.is_some() && !unfinished.is_empty() {
+        println!("Unfinished tasks:");
+        for task in unfinished {
+            println!(
+                "  {} {} ({})",
+                task.id,
+                task.state,
+                task.last_error.as_deref().unwrap_or("not started")
+            );
+        }
+    }
+    if let Some(path) = out {
+        println!("Simulation report written: {}", path.display());
+    }
+}
+
+pub(crate) fn print_task_list(tasks: &[TaskSummary]) {
+    if tasks.is_empty() {
+        println!("No tasks in the coordinator registry.");
+        return;
+    }
+    println!(
+        "{:<16} {:<18} {:<8} {:<12} {:<16} {:<16} TITLE",
+        "TASK", "STATE", "PRIORITY", "TOOL", "RUNTIME", "DEPENDS ON"
+    );
+    for task in tasks {
+        let deps = if task.dependencies.is_empty() {
+            "-".to_string()
+        } else {
+            task.dependencies.join(",")
+        };
+        let dash = |v: &str| {
+            if v.is_empty() {
+                "-".to_string()
+            } else {
+                v.to_string()
+            }
+        };
+        println!(
+            "{:<16} {:<18} {:<8} {:<12} {:<16} {:<16} {}{}",
+            task.id,
+            task.state,
+            dash(&task.priority),
+            dash(&task.tool),
+            task.runtime,
+            deps,
+            task.title,
+            if task.operator { " [operator]" } else { "" }
+        );
+    }
+}
@@ -36,11 +36,17 @@ struct Cli {
     #[arg(short, long, global = true)]
     verbose: bool,
 
+    /// Config profile overlay (.macc/macc.<profile>.yaml); overrides MACC_PROFILE. For
+    /// `coordinator simulate`: the simulation profile (YAML)
+    #[arg(long, global = true)]
+    profile: Option<String>,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
 
 #[derive(Subcommand)]
+#[allow(clippy::large_enum_variant)]
 enum Commands {
     /// Initialize MACC in a project
     Init {
@@ -74,6 +80,9 @@ enum Commands {
         /// Explain why each file operation exists
         #[arg(long)]
         explain: bool,
+        /// Plan every MACC project in the repository (root and nested sub-projects)
+        #[arg(long)]
+        all: bool,
     },
     /// Apply configuration to the project
     Apply {
@@ -94,6 +103,9 @@ enum Commands {
         /// Explain why each file operation exists in preview
         #[arg(long)]
         explain: bool,
+        /// Apply every MACC project in the repository (root and nested sub-projects)
+        #[arg(long)]
+        all: bool,
     },
     /// Catalog management
     Catalog {
@@ -133,6 +145,11 @@ enum Commands {
         #[arg(long)]
         fix: bool,
     },
+    /// Inspect the canonical configuration
+    Config {
+        #[command(subcommand)]
+        config_command: ConfigCommands,
+    },
     /// Migrate legacy configuration to the new format
     Migrate {
         /// Actually write the migrated config to disk
@@ -169,6 +186,11 @@ enum Commands {
         #[command(subcommand)]
         worktree_command: WorktreeCommands,
     },
+    /// Run the native performer for a worktree task
+    Performer {
+        #[command(subcommand)]
+        performer_command: PerformerCommands,
+    },
     /// View coordinator/performer logs
     Logs {
         #[command(subcommand)]
@@ -176,9 +198,23 @@ enum Commands {
     },
     /// Run the project coordinator automation script
     Coordinator {
-        /// Coordinator action (run, control-plane-run, dispatch, advance, resume, sync, status, reconcile, unlock, cleanup, retry-phase, cutover-gate, stop, validate-transition, validate-runtime-transition, runtime-status-from-event, storage-import, storage-export, events-export, storage-verify, storage-sync, select-ready-task, state-apply-transition, state-set-runtime, state-task-field, state-task-exists, state-counts, state-locks, state-set-merge-pending, state-set-merge-processed, state-increment-retries, state-upsert-slo-warning, state-slo-metric)
+        /// Coordinator action (run, control-plane-run, dispatch, advance, resume, sync, status, reconcile, unlock, cleanup, retry-phase, cutover-gate, stop, validate-transition, validate-runtime-transition, runtime-status-from-event, storage-import, storage-export, events-export, metrics, trace, report, runs, replay, simulate, approve, reject, task, storage-verify, storage-sync, select-ready-task, state-apply-transition, state-set-runtime, state-task-field, state-task-exists, state-counts, state-locks, state-set-merge-pending, state-set-merge-processed, state-increment-retries, state-upsert-slo-warning, state-slo-metric)
         #[arg(default_value = "run")]
         action: String,
+        /// Action operands (e.g. `trace export [run-id]`, `report [run-id]`, `replay verify`, `approve <task-id>`, `task set-priority <task-id> p0`)
+        operands: Vec<String>,
+        /// Output format for export actions (trace: otlp-json, chrome; report: md, json, html)
+        #[arg(long)]
+        format: Option<String>,
+        /// Output file for export actions (metrics, trace, report, replay, simulate)
+        #[arg(long)]
+        out: Option<String>,
+        /// For `replay`: stop after this RFC 3339 timestamp or event id
+        #[arg(long)]
+        until: Option<String>,
+        /// Comment for `approve`/`reject` and `task` changes; a rejection comment is fed back as review feedback
+        #[arg(long)]
+        comment: Option<String>,
         /// Disable TUI live view for `macc coordinator run`
         #[arg(long)]
         no_tui: bool,
@@ -245,6 +281,9 @@ enum Commands {
         /// Coordinator storage mode (json, dual-write, sqlite)
         #[arg(long)]
         storage_mode: Option<String>,
+        /// Serve OpenMetrics on http://HOST:PORT/metrics while the coordinator runs (e.g. 127.0.0.1:9464)
+        #[arg(long)]
+        metrics_listen: Option<String>,
         /// Extra args passed directly to coordinator.sh (use after --)
         #[arg(last = true)]
         extra_args: Vec<String>,
@@ -299,7 +338,7 @@ pub enum ToolCommands {
         /// Force update even when already up-to-date
         #[arg(long)]
         force: bool,
-        /// Best-effort rollback to previous version on failure (npm tools only)
+        /// Roll back a failed update with the ToolSpec `rollback` section (required)
         #[arg(long)]
         rollback_on_fail: bool,
     },
@@ -309,6 +348,17 @@ pub enum ToolCommands {
         #[arg(long, value_parser = ["enabled", "installed"])]
         only: Option<String>,
     },
+    /// Show tool update history, including attempted rollbacks
+    History {
+        /// Only show entries for this tool ID
+        tool_id: Option<String>,
+        /// Show only the most recent N entries
+        #[arg(long)]
+        limit: Option<usize>,
+        /// Output machine-readable JSON
+        #[arg(long)]
+        json: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -420,6 +470,25 @@ pub enum CatalogSubCommands {
     },
 }
 
+#[derive(Subcommand)]
+pub enum ConfigCommands {
+    /// Print the configuration
+    Show {
+        /// Merge user, project and profile layers instead of printing .macc/macc.yaml only
+        #[arg(long)]
+        effective: bool,
+        /// Print each value with the layer it came from
+        #[arg(long, requires = "effective")]
+        origin: bool,
+    },
+    /// Validate config layers and ToolSpecs against the generated JSON Schemas
+    Validate {
+        /// Write macc-config.schema.json and toolspec.schema.json to this directory
+        #[arg(long)]
+        schema_out: Option<String>,
+    },
+}
+
 #[derive(Subcommand)]
 pub enum WorktreeCommands {
     /// Create worktrees for parallel runs
@@ -480,7 +549,7 @@ pub enum WorktreeCommands {
         /// Worktree id (folder name under .macc/worktree) or path
         id: String,
     },
-    /// Run performer.sh inside a worktree
+    /// Run the performer inside a worktree
     Run {
         /// Worktree id (folder name under .macc/worktree) or path
         id: String,
@@ -512,6 +581,25 @@ pub enum WorktreeCommands {
     Prune,
 }
 
+#[derive(Subcommand)]
+pub enum PerformerCommands {
+    /// Work through the worktree's pending tasks with its configured tool
+    Run {
+        /// Worktree id (folder name under .macc/worktree) or path
+        #[arg(long)]
+        worktree: String,
+        /// Task id reported in events (default: first task of worktree.prd.json)
+        #[arg(long)]
+        task_id: Option<String>,
+        /// PRD file to work through (default: worktree.prd.json)
+        #[arg(long)]
+        prd: Option<String>,
+        /// Maximum attempts per task (default: PERFORMER_TOOL_MAX_ATTEMPTS or 2)
+        #[arg(long)]
+        max_attempts: Option<usize>,
+    },
+}
+
 #[derive(Subcommand)]
 pub enum LogsCommands {
     /// Tail the latest matching log file
@@ -608,11 +696,28 @@ fn run_with_engine<E: Engine + Send + Sync + 'static>(cli: Cli, engine: E) -> Re
     run_with_engine_provider(cli, provider)
 }
 
+/// The global `--profile` of `macc coordinator simulate`, which names the simulation profile.
+fn simulation_profile(cli: &Cli) -> Option<&str> {
+    match &cli.command {
+        Some(Commands::Coordinator { action, .. }) if action == "simulate" => {
+            cli.profile.as_deref()
+        }
+        _ => None,
+    }
+}
+
 fn run_with_engine_provider(
     cli: Cli,
     provider: services::engine_provider::EngineProvider,
 ) -> Result<()> {
     debug!(cwd = %cli.cwd, "Starting CLI command routing");
+    let config_profile = match (&cli.profile, simulation_profile(&cli)) {
+        (Some(profile), None) => {
+            macc_core::config::layers::validate_profile_name(profile)?;
+            Some(profile.clone())
+        }
+        _ => None,
+    };
     let cwd = std::path::PathBuf::from(&cli.cwd);
     let absolute_cwd = if cwd.is_absolute() {
         cwd
@@ -629,7 +734,8 @@ fn run_with_engine_provider(
     // Try to canonicalize to resolve .. and symlinks if it exists
     let absolute_cwd = absolute_cwd.canonicalize().unwrap_or(absolute_cwd);
     let engine = provider.shared();
-    let app = commands::AppContext::new(absolute_cwd.clone(), engine.clone());
+    let app =
+        commands::AppContext::new(absolute_cwd.clone(), engine.clone(), config_profile.clone());
 
     match &cli.command {
         Some(Commands::Init { force, wizard }) => {
@@ -642,13 +748,16 @@ fn run_with_engine_provider(
             tools,
             json,
             explain,
-        }) => commands::plan::PlanCommand::new(app.clone(), tools.clone(), *json, *explain).run(),
+            all,
+        }) => commands::plan::PlanCommand::new(app.clone(), tools.clone(), *json, *explain, *all)
+            .run(),
         Some(Commands::Apply {
             tools,
             dry_run,
             allow_user_scope,
             json,
             explain,
+            all,
         }) => commands::apply::ApplyCommand::new(
             app.clone(),
             tools.clone(),
@@ -656,6 +765,7 @@ fn run_with_engine_provider(
             *allow_user_scope,
             *json,
             *explain,
+            *all,
         )
         .run(),
         Some(Commands::Catalog { catalog_command }) => {
@@ -671,11 +781,12 @@ fn run_with_engine_provider(
                 action: "set current_dir for tui".into(),
                 source: e,
             })?;
-            macc_tui::run_tui().map_err(|e| MaccError::Io {
-                path: "tui".into(),
-                action: "run_tui".into(),
-                source: std::io::Error::other(e.to_string()),
-            })
+            macc_tui::run_tui_with_launch(macc_tui::LaunchMode::Default, config_profile.clone())
+                .map_err(|e| MaccError::Io {
+                    path: "tui".into(),
+                    action: "run_tui".into(),
+                    source: std::io::Error::other(e.to_string()),
+                })
         }
         Some(Commands::Tool { tool_command }) => {
             commands::tool::ToolCommand::new(app.clone(), tool_command).run()
@@ -696,6 +807,9 @@ fn run_with_engine_provider(
         Some(Commands::Doctor { fix }) => {
             commands::doctor::DoctorCommand::new(app.clone(), *fix).run()
         }
+        Some(Commands::Config { config_command }) => {
+            commands::config::ConfigCommand::new(app.clone(), config_command).run()
+        }
         Some(Commands::Migrate { apply }) => {
             commands::migrate::MigrateCommand::new(app.clone(), *apply).run()
         }
@@ -721,11 +835,19 @@ fn run_with_engine_provider(
         Some(Commands::Worktree { worktree_command }) => {
             commands::worktree::WorktreeCommand::new(app.clone(), worktree_command).run()
         }
+        Some(Commands::Performer { performer_command }) => {
+            commands::performer::PerformerCommand::new(app.clone(), performer_command).run()
+        }
         Some(Commands::Logs { logs_command }) => {
             commands::logs::LogsCommand::new(app.clone(), logs_command).run()
         }
         Some(Commands::Coordinator {
             action,
+            operands,
+            format,
+            out,
+            until,
+            comment,
             no_tui,
             graceful,
             remove_worktrees,
@@ -748,6 +870,7 @@ fn run_with_engine_provider(
             stale_changes_requested_seconds,
             stale_action,
             storage_mode,
+            metrics_listen,
             extra_args,
         }) => commands::coordinator::CoordinatorCommand::new(
             app.clone(),
@@ -757,6 +880,13 @@ fn run_with_engine_provider(
                 graceful: *graceful,
                 remove_worktrees: *remove_worktrees,
                 remove_branches: *remove_branches,
+                operands: operands.clone(),
+                format: format.clone(),
+                out: out.clone(),
+                until: until.clone(),
+                // `simulate --profile` names the simulation profile, not a config overlay.
+                profile: simulation_profile(&cli).map(str::to_string),
+                comment: comment.clone(),
                 env_cfg: CoordinatorEnvConfig {
                     prd: prd.clone(),
                     coordinator_tool: coordinator_tool.clone(),
@@ -780,6 +910,8 @@ fn run_with_engine_provider(
                     error_code_retry_max: std::env::var("ERROR_CODE_RETRY_MAX")
                         .ok()
                         .and_then(|v| v.parse().ok()),
+                    config_profile: config_profile.clone(),
+                    metrics_listen: metrics_listen.clone(),
                 },
                 extra_args: extra_args.clone(),
             },
@@ -792,11 +924,12 @@ fn run_with_engine_provider(
                 action: "set current_dir for tui".into(),
                 source: e,
             })?;
-            macc_tui::run_tui().map_err(|e| MaccError::Io {
-                path: "tui".into(),
-                action: "run_tui".into(),
-                source: std::io::Error::other(e.to_string()),
-            })
+            macc_tui::run_tui_with_launch(macc_tui::LaunchMode::Default, config_profile.clone())
+                .map_err(|e| MaccError::Io {
+                    path: "tui".into(),
+                    action: "run_tui".into(),
+                    source: std::io::Error::other(e.to_string()),
+                })
         }
     }
 }
@@ -1118,7 +1251,7 @@ mod tests {
         validate_coordinator_runtime_transition_action, validate_coordinator_transition_action,
         COORDINATOR_TASK_REGISTRY_REL_PATH,
     };
-    use crate::test_support::run_git_ok;
+    use crate::test_support::{isolate_user_config, run_git_ok};
     use macc_core::service::tooling::{extract_version_token, run_version_command};
     use macc_core::TestEngine;
     use macc_core::{MaccError, McpCatalog, SkillsCatalog};
@@ -1385,6 +1518,7 @@ mod tests {
 
     #[test]
     fn test_cwd_support() -> macc_core::Result<()> {
+        isolate_user_config();
         let temp_base = std::env::temp_dir().join(format!("macc_cli_test_{}", uuid_v4_like()));
         let project_dir = temp_base.join("nested/project");
         // Do not create project_dir, let 'init' handle it (or create its parent)
@@ -1394,6 +1528,7 @@ mod tests {
         let cli = Cli {
             cwd: project_dir.to_string_lossy().into(),
             verbose: true,
+            profile: None,
             command: Some(Commands::Init {
                 force: false,
                 wizard: false,
@@ -1414,6 +1549,7 @@ mod tests {
 
     #[test]
     fn test_init_idempotence_and_force() -> macc_core::Result<()> {
+        isolate_user_config();
         let temp_base = std::env::temp_dir().join(format!("macc_init_test_{}", uuid_v4_like()));
         std::fs::create_dir_all(&temp_base).unwrap();
 
@@ -1421,6 +1557,7 @@ mod tests {
         let cli = Cli {
             cwd: temp_base.to_string_lossy().into(),
             verbose: false,
+            profile: None,
             command: Some(Commands::Init {
                 force: false,
                 wizard: false,
@@ -1441,6 +1578,7 @@ mod tests {
         let cli_idempotent = Cli {
             cwd: temp_base.to_string_lossy().into(),
             verbose: false,
+            profile: None,
             command: Some(Commands::Init {
                 force: false,
                 wizard: false,
@@ -1458,6 +1596,7 @@ mod tests {
         let cli_force = Cli {
             cwd: temp_base.to_string_lossy().into(),
             verbose: false,
+            profile: None,
             command: Some(Commands::Init {
                 force: true,
                 wizard: false,
@@ -1481,8 +1620,68 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_profile_flag_selects_profile_overlay_for_plan_and_apply() {
+        isolate_user_config();
+        let temp_base = std::env::temp_dir().join(format!("macc_profile_test_{}", uuid_v4_like()));
+        std::fs::create_dir_all(temp_base.join(".macc")).unwrap();
+        std::fs::write(temp_base.join(".macc/macc.yaml"), "tools:\n  enabled: []\n").unwrap();
+        // Only `--profile ci` reads this overlay, and it does not parse.
+        std::fs::write(temp_base.join(".macc/macc.ci.yaml"), "not_a_field: true\n").unwrap();
+        let ids = fixture_ids();
+        let plan = |profile: Option<&str>| {
+            run_with_engine(
+                Cli {
+                    cwd: temp_base.to_string_lossy().into(),
+                    verbose: false,
+                    profile: profile.map(str::to_string),
+                    command: Some(Commands::Plan {
+                        tools: None,
+                        json: false,
+                        explain: false,
+                        all: false,
+                    }),
+                },
+                fixture_engine(&ids),
+            )
+        };
+        let apply = |profile: Option<&str>| {
+            run_with_engine(
+                Cli {
+                    cwd: temp_base.to_string_lossy().into(),
+                    verbose: false,
+                    profile: profile.map(str::to_string),
+                    command: Some(Commands::Apply {
+                        tools: None,
+                        dry_run: true,
+                        allow_user_scope: false,
+                        json: false,
+                        explain: false,
+                        all: false,
+                    }),
+                },
+                fixture_engine(&ids),
+            )
+        };
+
+        // Without the flag the overlay is never read (fetching required skills may still
+        // fail offline, so only the error cause is checked).
+        for result in [plan(None), apply(None)] {
+            if let Err(err) = result {
+                assert!(!err.to_string().contains("not_a_field"), "{}", err);
+            }
+        }
+        let err = plan(Some("ci")).unwrap_err().to_string();
+        assert!(err.contains("not_a_field"), "{}", err);
+        let err = apply(Some("ci")).unwrap_err().to_string();
+        assert!(err.contains("not_a_field"), "{}", err);
+
+        std::fs::remove_dir_all(&temp_base).ok();
+    }
+
     #[test]
     fn test_plan_with_tools_override() -> macc_core::Result<()> {
+        isolate_user_config();
         let temp_base = std::env::temp_dir().join(format!("macc_tools_test_{}", uuid_v4_like()));
         std::fs::create_dir_all(&temp_base).unwrap();
         let ids = fixture_ids();
@@ -1494,6 +1693,7 @@ mod tests {
             Cli {
                 cwd: temp_base.to_string_lossy().into(),
                 verbose: false,
+                profile: None,
                 command: Some(Commands::Init {
                     force: false,
                     wizard: false,
@@ -1507,10 +1707,12 @@ mod tests {
             Cli {
                 cwd: temp_base.to_string_lossy().into(),
                 verbose: false,
+                profile: None,
                 command: Some(Commands::Plan {
                     tools: Some(format!("{},{}", tool_one, tool_two)),
                     json: false,
                     explain: false,
+                    all: false,
                 }),
             },
             fixture_engine(&ids),
@@ -1521,10 +1723,12 @@ mod tests {
             Cli {
                 cwd: temp_base.to_string_lossy().into(),
                 verbose: false,
+                profile: None,
                 command: Some(Commands::Plan {
                     tools: Some(format!("{},unknown", tool_one)),
                     json: false,
                     explain: false,
+                    all: false,
                 }),
             },
             fixture_engine(&ids),
@@ -1537,6 +1741,7 @@ mod tests {
 
     #[test]
     fn test_coordinator_run_full_cycle_converges() -> macc_core::Result<()> {
+        isolate_user_config();
         let root = std::env::temp_dir().join(format!("macc_cli_coord_run_{}", uuid_v4_like()));
         std::fs::create_dir_all(&root).unwrap();
         let registry = root.join(COORDINATOR_TASK_REGISTRY_REL_PATH);
@@ -1601,6 +1806,8 @@ mod tests {
             storage_mode: None,
             error_code_retry_list: None,
             error_code_retry_max: None,
+            config_profile: None,
+            metrics_listen: None,
         };
 
         run_coordinator_full_cycle(&root, &canonical, Some(&coordinator_cfg), &env_cfg)?;
@@ -1618,6 +1825,7 @@ mod tests {
 
     #[test]
     fn test_coordinator_run_detects_no_progress() -> macc_core::Result<()> {
+        isolate_user_config();
         let root = std::env::temp_dir().join(format!("macc_cli_coord_stall_{}", uuid_v4_like()));
         std::fs::create_dir_all(&root).unwrap();
         let registry = root.join(COORDINATOR_TASK_REGISTRY_REL_PATH);
@@ -1682,6 +1890,8 @@ mod tests {
             storage_mode: None,
             error_code_retry_list: None,
             error_code_retry_max: None,
+            config_profile: None,
+            metrics_listen: None,
         };
 
         let err = run_coordinator_full_cycle(&root, &canonical, Some(&coordinator_cfg), &env_cfg)
@@ -1698,6 +1908,7 @@ mod tests {
 
     #[test]
     fn test_coordinator_control_plane_same_input_same_final_state() -> macc_core::Result<()> {
+        isolate_user_config();
         fn run_once(
             root: &std::path::Path,
             _script: &std::path::Path,
@@ -1744,6 +1955,8 @@ mod tests {
                 storage_mode: None,
                 error_code_retry_list: None,
                 error_code_retry_max: None,
+                config_profile: None,
+                metrics_listen: None,
             };
 
             let runtime = tokio::runtime::Builder::new_current_thread()
@@ -1810,6 +2023,7 @@ esac
 
     #[test]
     fn test_coordinator_parallel_dispatch_behavior() -> macc_core::Result<()> {
+        isolate_user_config();
         let root =
             std::env::temp_dir().join(format!("macc_cli_parallel_dispatch_{}", uuid_v4_like()));
         std::fs::create_dir_all(&root).unwrap();
@@ -1876,6 +2090,8 @@ fi
             storage_mode: None,
             error_code_retry_list: None,
             error_code_retry_max: None,
+            config_profile: None,
+            metrics_listen: None,
         };
 
         run_coordinator_action(&root, &script, "dispatch", &[], &canonical, None, &env_cfg)?;
@@ -1903,6 +2119,7 @@ fi
 
     #[test]
     fn test_coordinator_retry_phase_behavior() -> macc_core::Result<()> {
+        isolate_user_config();
         let root = std::env::temp_dir().join(format!("macc_cli_retry_phase_{}", uuid_v4_like()));
         std::fs::create_dir_all(&root).unwrap();
         let registry = root.join(COORDINATOR_TASK_REGISTRY_REL_PATH);
@@ -1968,6 +2185,8 @@ fi
             storage_mode: None,
             error_code_retry_list: None,
             error_code_retry_max: None,
+            config_profile: None,
+            metrics_listen: None,
         };
 
         run_coordinator_action(
@@ -1999,6 +2218,7 @@ fi
 
     #[test]
     fn test_coordinator_stop_removes_worktrees_and_branches() -> macc_core::Result<()> {
+        isolate_user_config();
         let root = std::env::temp_dir().join(format!("macc_cli_coord_stop_{}", uuid_v4_like()));
         std::fs::create_dir_all(&root).unwrap();
         let ids = fixture_ids();
@@ -2013,6 +2233,7 @@ fi
             Cli {
                 cwd: root.to_string_lossy().into(),
                 verbose: false,
+                profile: None,
                 command: Some(Commands::Init {
                     force: false,
                     wizard: false,
@@ -2065,8 +2286,14 @@ fi
             Cli {
                 cwd: root.to_string_lossy().into(),
                 verbose: false,
+                profile: None,
                 command: Some(Commands::Coordinator {
                     action: "stop".to_string(),
+                    operands: Vec::new(),
+                    format: None,
+                    out: None,
+                    until: None,
+                    comment: None,
                     no_tui: true,
                     graceful: true,
                     remove_worktrees: true,
@@ -2089,6 +2316,7 @@ fi
                     stale_changes_requested_seconds: None,
                     stale_action: None,
                     storage_mode: None,
+                    metrics_listen: None,
                     extra_args: Vec::new(),
                 }),
             },
@@ -2111,6 +2339,7 @@ fi
 
     #[test]
     fn test_apply_with_test_adapter() -> macc_core::Result<()> {
+        isolate_user_config();
         let temp_base = std::env::temp_dir().join(format!("macc_apply_test_{}", uuid_v4_like()));
         std::fs::create_dir_all(&temp_base).unwrap();
         let ids = fixture_ids();
@@ -2121,6 +2350,7 @@ fi
             Cli {
                 cwd: temp_base.to_string_lossy().into(),
                 verbose: false,
+                profile: None,
                 command: Some(Commands::Init {
                     force: false,
                     wizard: false,
@@ -2134,12 +2364,14 @@ fi
             Cli {
                 cwd: temp_base.to_string_lossy().into(),
                 verbose: false,
+                profile: None,
                 command: Some(Commands::Apply {
                     tools: Some(tool_one.clone()),
                     dry_run: false,
                     allow_user_scope: false,
                     json: false,
                     explain: false,
+                    all: false,
                 }),
             },
             fixture_engine(&ids),
@@ -2160,6 +2392,7 @@ fi
 
     #[test]
     fn test_context_requires_prior_apply() -> macc_core::Result<()> {
+        isolate_user_config();
         let temp_base =
             std::env::temp_dir().join(format!("macc_context_gate_test_{}", uuid_v4_like()));
         std::fs::create_dir_all(&temp_base).unwrap();
@@ -2168,6 +2401,7 @@ fi
             Cli {
                 cwd: temp_base.to_string_lossy().into(),
                 verbose: false,
+                profile: None,
                 command: Some(Commands::Init {
                     force: false,
                     wizard: false,
@@ -2180,6 +2414,7 @@ fi
             Cli {
                 cwd: temp_base.to_string_lossy().into(),
                 verbose: false,
+                profile: None,
                 command: Some(Commands::Context {
                     tool: None,
                     from_files: Vec::new(),
@@ -2204,6 +2439,7 @@ fi
 
     #[test]
     fn test_catalog_skills_workflow() -> macc_core::Result<()> {
+        isolate_user_config();
         let temp_base =
             std::env::temp_dir().join(format!("macc_catalog_cli_test_{}", uuid_v4_like()));
         std::fs::create_dir_all(&temp_base).unwrap();
@@ -2213,6 +2449,7 @@ fi
             Cli {
                 cwd: temp_base.to_string_lossy().into(),
                 verbose: false,
+                profile: None,
                 command: Some(Commands::Init {
                     force: false,
                     wizard: false,
@@ -2226,6 +2463,7 @@ fi
             Cli {
                 cwd: temp_base.to_string_lossy().into(),
                 verbose: false,
+                profile: None,
                 command: Some(Commands::Catalog {
                     catalog_command: CatalogCommands::Skills {
                         skills_command: CatalogSubCommands::Add {
@@ -2253,6 +2491,7 @@ fi
             Cli {
                 cwd: temp_base.to_string_lossy().into(),
                 verbose: false,
+                profile: None,
                 command: Some(Commands::Catalog {
                     catalog_command: CatalogCommands::Skills {
                         skills_command: CatalogSubCommands::List,
@@ -2267,6 +2506,7 @@ fi
             Cli {
                 cwd: temp_base.to_string_lossy().into(),
                 verbose: false,
+                profile: None,
                 command: Some(Commands::Catalog {
                     catalog_command: CatalogCommands::Skills {
                         skills_command: CatalogSubCommands::Search {
@@ -2283,6 +2523,7 @@ fi
             Cli {
                 cwd: temp_base.to_string_lossy().into(),
                 verbose: false,
+                profile: None,
                 command: Some(Commands::Catalog {
                     catalog_command: CatalogCommands::Skills {
                         skills_command: CatalogSubCommands::Remove {
@@ -2304,6 +2545,7 @@ fi
 
     #[test]
     fn test_catalog_mcp_workflow() -> macc_core::Result<()> {
+        isolate_user_config();
         let temp_base = std::env::temp_dir().join(format!("macc_mcp_cli_test_{}", uuid_v4_like()));
         std::fs::create_dir_all(&temp_base).unwrap();
 
@@ -2312,6 +2554,7 @@ fi
             Cli {
                 cwd: temp_base.to_string_lossy().into(),
                 verbose: false,
+                profile: None,
                 command: Some(Commands::Init {
                     force: false,
                     wizard: false,
@@ -2325,6 +2568,7 @@ fi
             Cli {
                 cwd: temp_base.to_string_lossy().into(),
                 verbose: false,
+                profile: None,
                 command: Some(Commands::Catalog {
                     catalog_command: CatalogCommands::Mcp {
                         mcp_command: CatalogSubCommands::Add {
@@ -2352,6 +2596,7 @@ fi
             Cli {
                 cwd: temp_base.to_string_lossy().into(),
                 verbose: false,
+                profile: None,
                 command: Some(Commands::Catalog {
                     catalog_command: CatalogCommands::Mcp {
                         mcp_command: CatalogSubCommands::List,
@@ -2366,6 +2611,7 @@ fi
             Cli {
                 cwd: temp_base.to_string_lossy().into(),
                 verbose: false,
+                profile: None,
                 command: Some(Commands::Catalog {
                     catalog_command: CatalogCommands::Mcp {
                         mcp_command: CatalogSubCommands::Search {
@@ -2382,6 +2628,7 @@ fi
             Cli {
                 cwd: temp_base.to_string_lossy().into(),
                 verbose: false,
+                profile: None,
                 command: Some(Commands::Catalog {
                     catalog_command: CatalogCommands::Mcp {
                         mcp_command: CatalogSubCommands::Remove {
@@ -2403,6 +2650,7 @@ fi
 
     #[test]
     fn test_install_skill_cli() -> macc_core::Result<()> {
+        isolate_user_config();
         let temp_base =
             std::env::temp_dir().join(format!("macc_install_skill_test_{}", uuid_v4_like()));
         std::fs::create_dir_all(&temp_base).unwrap();
@@ -2414,6 +2662,7 @@ fi
             Cli {
                 cwd: temp_base.to_string_lossy().into(),
                 verbose: false,
+                profile: None,
                 command: Some(Commands::Init {
                     force: false,
                     wizard: false,
@@ -2454,6 +2703,7 @@ fi
             Cli {
                 cwd: temp_base.to_string_lossy().into(),
                 verbose: false,
+                profile: None,
                 command: Some(Commands::Catalog {
                     catalog_command: CatalogCommands::Skills {
                         skills_command: CatalogSubCommands::Add {
@@ -2478,6 +2728,7 @@ fi
             Cli {
                 cwd: temp_base.to_string_lossy().into(),
                 verbose: false,
+                profile: None,
                 command: Some(Commands::Install {
                     install_command: InstallCommands::Skill {
                         tool: tool_one.clone(),
@@ -2503,6 +2754,7 @@ fi
 
     #[test]
     fn test_install_mcp_cli() -> macc_core::Result<()> {
+        isolate_user_config();
         let temp_base =
             std::env::temp_dir().join(format!("macc_install_mcp_test_{}", uuid_v4_like()));
         std::fs::create_dir_all(&temp_base).unwrap();
@@ -2512,6 +2764,7 @@ fi
             Cli {
                 cwd: temp_base.to_string_lossy().into(),
                 verbose: false,
+                profile: None,
                 command: Some(Commands::Init {
                     force: false,
                     wizard: false,
@@ -2555,6 +2808,7 @@ fi
             Cli {
                 cwd: temp_base.to_string_lossy().into(),
                 verbose: false,
+                profile: None,
                 command: Some(Commands::Catalog {
                     catalog_command: CatalogCommands::Mcp {
                         mcp_command: CatalogSubCommands::Add {
@@ -2579,6 +2833,7 @@ fi
             Cli {
                 cwd: temp_base.to_string_lossy().into(),
                 verbose: false,
+                profile: None,
                 command: Some(Commands::Install {
                     install_command: InstallCommands::Mcp {
                         id: "remote-mcp".into(),
@@ -2605,6 +2860,7 @@ fi
 
     #[test]
     fn test_catalog_import_url() -> macc_core::Result<()> {
+        isolate_user_config();
         let temp_base =
             std::env::temp_dir().join(format!("macc_catalog_import_test_{}", uuid_v4_like()));
         std::fs::create_dir_all(&temp_base).unwrap();
@@ -2614,6 +2870,7 @@ fi
             Cli {
                 cwd: temp_base.to_string_lossy().into(),
                 verbose: false,
+                profile: None,
                 command: Some(Commands::Init {
                     force: false,
                     wizard: false,
@@ -2627,6 +2884,7 @@ fi
             Cli {
                 cwd: temp_base.to_string_lossy().into(),
                 verbose: false,
+                profile: None,
                 command: Some(Commands::Catalog {
                     catalog_command: CatalogCommands::ImportUrl {
                         kind: "skill".into(),
@@ -2658,6 +2916,7 @@ fi
             Cli {
                 cwd: temp_base.to_string_lossy().into(),
                 verbose: false,
+                profile: None,
                 command: Some(Commands::Catalog {
                     catalog_command: CatalogCommands::ImportUrl {
                         kind: "mcp".into(),
@@ -2690,6 +2949,7 @@ fi
 
     #[test]
     fn test_search_remote_cli() -> macc_core::Result<()> {
+        isolate_user_config();
         use std::io::{BufRead, BufReader, Write};
         use std::thread;
 
@@ -2746,6 +3006,7 @@ fi
             Cli {
                 cwd: temp_base.to_string_lossy().into(),
                 verbose: false,
+                profile: None,
                 command: Some(Commands::Init {
                     force: false,
                     wizard: false,
@@ -2759,6 +3020,7 @@ fi
             Cli {
                 cwd: temp_base.to_string_lossy().into(),
                 verbose: false,
+                profile: None,
                 command: Some(Commands::Catalog {
                     catalog_command: CatalogCommands::SearchRemote {
                         api: server_url,
@@ -2784,6 +3046,7 @@ fi
 
     #[test]
     fn test_install_skill_multi_zip_cli() -> macc_core::Result<()> {
+        isolate_user_config();
         use std::io::{BufRead, BufReader, Write};
         use std::thread;
 
@@ -2875,6 +3138,7 @@ fi
             Cli {
                 cwd: temp_base.to_string_lossy().into(),
                 verbose: false,
+                profile: None,
                 command: Some(Commands::Init {
                     force: false,
                     wizard: false,
@@ -2888,6 +3152,7 @@ fi
             Cli {
                 cwd: temp_base.to_string_lossy().into(),
                 verbose: false,
+                profile: None,
                 command: Some(Commands::Catalog {
                     catalog_command: CatalogCommands::Skills {
                         skills_command: CatalogSubCommands::Add {
@@ -2912,6 +3177,7 @@ fi
             Cli {
                 cwd: temp_base.to_string_lossy().into(),
                 verbose: false,
+                profile: None,
                 command: Some(Commands::Install {
                     install_command: InstallCommands::Skill {
                         tool: tool_one.clone(),
@@ -2941,6 +3207,7 @@ fi
 
     #[test]
     fn test_install_skill_multi_git_cli() -> macc_core::Result<()> {
+        isolate_user_config();
         let temp_base =
             std::env::temp_dir().join(format!("macc_install_multi_git_test_{}", uuid_v4_like()));
         std::fs::create_dir_all(&temp_base).unwrap();
@@ -3009,6 +3276,7 @@ fi
             Cli {
                 cwd: project_path.to_string_lossy().into(),
                 verbose: false,
+                profile: None,
                 command: Some(Commands::Init {
                     force: false,
                     wizard: false,
@@ -3022,6 +3290,7 @@ fi
             Cli {
                 cwd: project_path.to_string_lossy().into(),
                 verbose: false,
+                profile: None,
                 command: Some(Commands::Catalog {
                     catalog_command: CatalogCommands::Skills {
                         skills_command: CatalogSubCommands::Add {
@@ -3045,6 +3314,7 @@ fi
             Cli {
                 cwd: project_path.to_string_lossy().into(),
                 verbose: false,
+                profile: None,
                 command: Some(Commands::Catalog {
                     catalog_command: CatalogCommands::Skills {
                         skills_command: CatalogSubCommands::Add {
@@ -3069,6 +3339,7 @@ fi
             Cli {
                 cwd: project_path.to_string_lossy().into(),
                 verbose: false,
+                profile: None,
                 command: Some(Commands::Install {
                     install_command: InstallCommands::Skill {
                         tool: tool_one.clone(),
@@ -3125,6 +3396,7 @@ fi
     #[test]
     #[cfg(unix)]
     fn test_install_skill_rejects_symlink_cli() -> macc_core::Result<()> {
+        isolate_user_config();
         use std::io::{BufRead, BufReader, Write};
         use std::thread;
 
@@ -3197,6 +3469,7 @@ fi
             Cli {
                 cwd: temp_base.to_string_lossy().into(),
                 verbose: false,
+                profile: None,
                 command: Some(Commands::Init {
                     force: false,
                     wizard: false,
@@ -3210,6 +3483,7 @@ fi
             Cli {
                 cwd: temp_base.to_string_lossy().into(),
                 verbose: false,
+                profile: None,
                 command: Some(Commands::Catalog {
                     catalog_command: CatalogCommands::Skills {
                         skills_command: CatalogSubCommands::Add {
@@ -3234,6 +3508,7 @@ fi
             Cli {
                 cwd: temp_base.to_string_lossy().into(),
                 verbose: false,
+                profile: None,
                 command: Some(Commands::Install {
                     install_command: InstallCommands::Skill {
                         tool: tool_one,
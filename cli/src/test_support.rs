@@ -10,3 +10,9 @@ pub(crate) fn run_git_ok(dir: &std::path::Path, args: &[&str]) {
         );
     }
 }
+
+/// Keeps test results independent of the developer's `~/.config/macc/macc.yaml`.
+#[cfg(test)]
+pub(crate) fn isolate_user_config() {
+    std::env::set_var(macc_core::config::layers::NO_USER_CONFIG_ENV, "1");
+}
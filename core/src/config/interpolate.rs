@@ -0,0 +1,314 @@
+use super::CanonicalConfig;
+use crate::{MaccError, ProjectPaths, Result};
+use regex::Regex;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Comma-separated program names that `${cmd:...}` references may run.
+///
+/// The allowlist is read from the environment rather than `macc.yaml` so a cloned
+/// repository cannot grant itself command execution at plan time.
+pub const CMD_ALLOWLIST_ENV: &str = "MACC_CMD_ALLOWLIST";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigReference {
+    Env(String),
+    File(String),
+    Cmd(String),
+}
+
+impl ConfigReference {
+    fn describe(&self) -> String {
+        match self {
+            ConfigReference::Env(name) => format!("${{env:{}}}", name),
+            ConfigReference::File(path) => format!("${{file:{}}}", path),
+            ConfigReference::Cmd(command) => format!("${{cmd:{}}}", command),
+        }
+    }
+}
+
+fn reference_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"\$\{(env|file|cmd):([^}]*)\}").expect("Invalid config reference regex")
+    })
+}
+
+/// Returns typed references found in `value`. Plain `${VAR}` placeholders are left to tools.
+pub fn parse_references(value: &str) -> Vec<ConfigReference> {
+    reference_regex()
+        .captures_iter(value)
+        .map(|caps| to_reference(&caps[1], caps[2].trim()))
+        .collect()
+}
+
+fn to_reference(kind: &str, body: &str) -> ConfigReference {
+    match kind {
+        "env" => ConfigReference::Env(body.to_string()),
+        "file" => ConfigReference::File(body.to_string()),
+        _ => ConfigReference::Cmd(body.to_string()),
+    }
+}
+
+pub struct ReferenceResolver {
+    root: PathBuf,
+    cmd_allowlist: BTreeSet<String>,
+}
+
+impl ReferenceResolver {
+    pub fn new(root: &Path, cmd_allowlist: BTreeSet<String>) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            cmd_allowlist,
+        }
+    }
+
+    pub fn from_env(root: &Path) -> Self {
+        let allowlist = std::env::var(CMD_ALLOWLIST_ENV)
+            .unwrap_or_default()
+            .split(',')
+            .map(|entry| entry.trim().to_string())
+            .filter(|entry| !entry.is_empty())
+            .collect();
+        Self::new(root, allowlist)
+    }
+
+    /// Substitutes every typed reference in `value` and records the resolved values.
+    pub fn resolve_str(&self, value: &str, sensitive: &mut BTreeSet<String>) -> Result<String> {
+        let re = reference_regex();
+        if !re.is_match(value) {
+            return Ok(value.to_string());
+        }
+        let mut output = String::with_capacity(value.len());
+        let mut cursor = 0;
+        for caps in re.captures_iter(value) {
+            let whole = caps.get(0).expect("capture 0 always present");
+            let reference = to_reference(&caps[1], caps[2].trim());
+            let resolved = self.resolve_reference(&reference)?;
+            if resolved.trim().chars().count() < crate::security::MIN_SENSITIVE_VALUE_LEN {
+                tracing::warn!(
+                    "{} resolves to fewer than {} characters; it is not checked for leaks or redacted",
+                    whole.as_str(),
+                    crate::security::MIN_SENSITIVE_VALUE_LEN
+                );
+            }
+            sensitive.insert(resolved.clone());
+            output.push_str(&value[cursor..whole.start()]);
+            output.push_str(&resolved);
+            cursor = whole.end();
+        }
+        output.push_str(&value[cursor..]);
+        Ok(output)
+    }
+
+    fn resolve_reference(&self, reference: &ConfigReference) -> Result<String> {
+        let fail = |reason: String| {
+            MaccError::Validation(format!(
+                "Config reference {} could not be resolved: {}",
+                reference.describe(),
+                reason
+            ))
+        };
+        match reference {
+            ConfigReference::Env(name) => {
+                if name.is_empty() {
+                    return Err(fail("empty variable name".into()));
+                }
+                std::env::var(name).map_err(|_| fail("variable is not set".into()))
+            }
+            ConfigReference::File(raw) => {
+                let path = self.file_path(raw);
+                let content = std::fs::read_to_string(&path)
+                    .map_err(|e| fail(format!("{} ({})", path.display(), e)))?;
+                Ok(content.trim_end_matches(['\n', '\r']).to_string())
+            }
+            ConfigReference::Cmd(command) => {
+                let mut parts = command.split_whitespace();
+                let program = parts.next().ok_or_else(|| fail("empty command".into()))?;
+                if !self.cmd_allowlist.contains(program) {
+                    return Err(fail(format!(
+                        "'{}' is not allowlisted (set {})",
+                        program, CMD_ALLOWLIST_ENV
+                    )));
+                }
+                let output = std::process::Command::new(program)
+                    .args(parts)
+                    .current_dir(&self.root)
+                    .output()
+                    .map_err(|e| fail(e.to_string()))?;
+                if !output.status.success() {
+                    return Err(fail(format!("command exited with {}", output.status)));
+                }
+                Ok(String::from_utf8_lossy(&output.stdout)
+                    .trim_end_matches(['\n', '\r'])
+                    .to_string())
+            }
+        }
+    }
+
+    fn file_path(&self, raw: &str) -> PathBuf {
+        if let Some(rest) = raw.strip_prefix("~/") {
+            if let Some(home) = crate::find_user_home() {
+                return home.join(rest);
+            }
+        }
+        let path = PathBuf::from(raw);
+        if path.is_absolute() {
+            path
+        } else {
+            self.root.join(path)
+        }
+    }
+}
+
+/// Resolves typed references in values tools cannot expand themselves
+/// (`standards.inline` and tool settings) and returns the resolved values.
+///
+/// MCP template placeholders are left untouched: tools expand them at runtime.
+pub fn interpolate_config(
+    config: &CanonicalConfig,
+    resolver: &ReferenceResolver,
+) -> Result<(CanonicalConfig, BTreeSet<String>)> {
+    let mut sensitive = BTreeSet::new();
+    let mut output = config.clone();
+    for value in output.standards.inline.values_mut() {
+        *value = resolver.resolve_str(value, &mut sensitive)?;
+    }
+    for value in output
+        .tools
+        .config
+        .values_mut()
+        .chain(output.tools.settings.values_mut())
+    {
+        interpolate_json(value, resolver, &mut sensitive)?;
+    }
+    Ok((output, sensitive))
+}
+
+fn interpolate_json(
+    value: &mut serde_json::Value,
+    resolver: &ReferenceResolver,
+    sensitive: &mut BTreeSet<String>,
+) -> Result<()> {
+    match value {
+        serde_json::Value::String(s) => *s = resolver.resolve_str(s, sensitive)?,
+        serde_json::Value::Array(items) => {
+            for item in items {
+                interpolate_json(item, resolver, sensitive)?;
+            }
+        }
+        serde_json::Value::Object(map) => {
+            for item in map.values_mut() {
+                interpolate_json(item, resolver, sensitive)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Plan-time entry point: resolves references and returns the resolved values, which belong
+/// on the [`crate::plan::ActionPlan`] built from this config.
+pub fn prepare_config_for_plan(
+    paths: &ProjectPaths,
+    config: &CanonicalConfig,
+) -> Result<(CanonicalConfig, BTreeSet<String>)> {
+    let resolver = ReferenceResolver::from_env(&paths.root);
+    interpolate_config(config, &resolver)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolver(allow: &[&str]) -> ReferenceResolver {
+        ReferenceResolver::new(
+            &std::env::temp_dir(),
+            allow.iter().map(|s| s.to_string()).collect(),
+        )
+    }
+
+    #[test]
+    fn test_parse_references_ignores_plain_placeholders() {
+        let refs = parse_references("a ${env:TOKEN} b ${file:~/.key} ${cmd:pass show x} ${PLAIN}");
+        assert_eq!(
+            refs,
+            vec![
+                ConfigReference::Env("TOKEN".into()),
+                ConfigReference::File("~/.key".into()),
+                ConfigReference::Cmd("pass show x".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_env_and_file_references_resolve() {
+        let var = "MACC_TEST_INTERPOLATE_ENV_REF";
+        std::env::set_var(var, "value-from-env");
+        let dir = std::env::temp_dir().join(format!(
+            "macc_interpolate_{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("token.txt"), "value-from-file\n").unwrap();
+        let resolver = ReferenceResolver::new(&dir, BTreeSet::new());
+
+        let mut sensitive = BTreeSet::new();
+        let out = resolver
+            .resolve_str(
+                &format!("k=${{env:{}}};f=${{file:token.txt}};${{KEEP}}", var),
+                &mut sensitive,
+            )
+            .unwrap();
+        assert_eq!(out, "k=value-from-env;f=value-from-file;${KEEP}");
+        assert!(sensitive.contains("value-from-env"));
+        assert!(sensitive.contains("value-from-file"));
+
+        std::env::remove_var(var);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_missing_env_reference_is_an_error() {
+        let err = resolver(&[])
+            .resolve_str("${env:MACC_TEST_SURELY_UNSET_VAR}", &mut BTreeSet::new())
+            .unwrap_err();
+        assert!(err.to_string().contains("variable is not set"));
+    }
+
+    #[test]
+    fn test_cmd_reference_requires_allowlist() {
+        let err = resolver(&[])
+            .resolve_str("${cmd:echo hi}", &mut BTreeSet::new())
+            .unwrap_err();
+        assert!(err.to_string().contains("not allowlisted"));
+
+        let out = resolver(&["echo"])
+            .resolve_str("${cmd:echo hi there}", &mut BTreeSet::new())
+            .unwrap();
+        assert_eq!(out, "hi there");
+    }
+
+    #[test]
+    fn test_interpolate_config_touches_standards_and_tool_settings_only() {
+        let var = "MACC_TEST_INTERPOLATE_CONFIG_REF";
+        std::env::set_var(var, "interpolated-secret");
+        let yaml = format!(
+            "tools:\n  enabled: [tool-a]\n  config:\n    tool-a:\n      auth:\n        token: \"${{env:{var}}}\"\nstandards:\n  owner: \"${{env:{var}}}\"\n"
+        );
+        let config = CanonicalConfig::from_yaml(&yaml).unwrap();
+        let (out, sensitive) = interpolate_config(&config, &resolver(&[])).unwrap();
+        assert_eq!(
+            out.tools.config["tool-a"]["auth"]["token"],
+            serde_json::json!("interpolated-secret")
+        );
+        assert_eq!(out.standards.inline["owner"], "interpolated-secret");
+        assert_eq!(out.mcp_templates, config.mcp_templates);
+        assert_eq!(sensitive.len(), 1);
+        std::env::remove_var(var);
+    }
+}
@@ -0,0 +1,562 @@
+use super::CanonicalConfig;
+use crate::{MaccError, ProjectPaths, Result};
+use serde_yaml::{Mapping, Value};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Environment variable selecting the active config profile (`.macc/macc.<profile>.yaml`).
+pub const PROFILE_ENV: &str = "MACC_PROFILE";
+
+/// Environment variable that, when set to a non-empty value other than `0`, skips the user
+/// overlay so results do not depend on the machine (set by the test harnesses).
+pub const NO_USER_CONFIG_ENV: &str = "MACC_NO_USER_CONFIG";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigLayer {
+    /// Short label shown in `macc config show --origin`
+    /// (`user`, `parent:<path>`, `project`, `profile:<name>`).
+    pub name: String,
+    pub path: PathBuf,
+    /// Required layers fail loading when missing; optional layers are skipped.
+    pub required: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct EffectiveConfig {
+    pub config: CanonicalConfig,
+    /// Layers that were actually read, lowest precedence first.
+    pub layers: Vec<ConfigLayer>,
+    /// Dotted YAML path of every leaf value -> name of the layer that set it.
+    pub origins: BTreeMap<String, String>,
+    merged: Value,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EffectiveValue {
+    pub path: String,
+    pub value: String,
+    pub origin: String,
+}
+
+impl EffectiveConfig {
+    /// Flattened leaf values of the merged document, each tagged with its origin layer.
+    pub fn values(&self) -> Vec<EffectiveValue> {
+        let mut leaves = BTreeMap::new();
+        collect_leaves(&self.merged, String::new(), &mut leaves);
+        leaves
+            .into_iter()
+            .map(|(path, value)| {
+                let origin = self
+                    .origins
+                    .get(&path)
+                    .cloned()
+                    .unwrap_or_else(|| "default".to_string());
+                EffectiveValue {
+                    path,
+                    value: serde_json::to_string(&value).unwrap_or_default(),
+                    origin,
+                }
+            })
+            .collect()
+    }
+}
+
+pub fn validate_profile_name(profile: &str) -> Result<()> {
+    let valid = !profile.is_empty()
+        && profile
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(MaccError::Validation(format!(
+            "Invalid config profile '{}': use letters, digits, '-' or '_'",
+            profile
+        )));
+    }
+    Ok(())
+}
+
+/// Profile selected through `MACC_PROFILE`, if any.
+pub fn active_profile() -> Option<String> {
+    std::env::var(PROFILE_ENV)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// Profile of `paths` (`--profile`), falling back to `MACC_PROFILE`.
+pub fn selected_profile(paths: &ProjectPaths) -> Option<String> {
+    paths.profile.clone().or_else(active_profile)
+}
+
+/// User-level overlay: `$XDG_CONFIG_HOME/macc/macc.yaml`, falling back to `~/.config/macc/macc.yaml`.
+/// `None` when `MACC_NO_USER_CONFIG` disables it, and always in this crate's unit tests.
+pub fn user_config_path() -> Option<PathBuf> {
+    if cfg!(test)
+        || std::env::var(NO_USER_CONFIG_ENV).is_ok_and(|v| !v.trim().is_empty() && v.trim() != "0")
+    {
+        return None;
+    }
+    if let Some(dir) = std::env::var_os("XDG_CONFIG_HOME").filter(|v| !v.is_empty()) {
+        return Some(PathBuf::from(dir).join("macc").join("macc.yaml"));
+    }
+    crate::find_user_home().map(|home| home.join(".config").join("macc").join("macc.yaml"))
+}
+
+pub fn profile_config_path(paths: &ProjectPaths, profile: &str) -> PathBuf {
+    paths.macc_dir.join(format!("macc.{}.yaml", profile))
+}
+
+/// Layers in precedence order: user < parent projects (outermost first) < project < profile.
+pub fn config_layers(paths: &ProjectPaths, profile: Option<&str>) -> Vec<ConfigLayer> {
+    let mut layers = Vec::new();
+    if let Some(user_path) = user_config_path() {
+        layers.push(ConfigLayer {
+            name: "user".to_string(),
+            path: user_path,
+            required: false,
+        });
+    }
+    let parents = crate::monorepo::parent_project_roots(paths);
+    if let Some(repo_root) = crate::monorepo::repository_root(&paths.root) {
+        for parent in parents {
+            layers.push(ConfigLayer {
+                name: format!(
+                    "parent:{}",
+                    crate::monorepo::relative_project_path(&repo_root, &parent)
+                ),
+                path: ProjectPaths::from_root(&parent).config_path,
+                required: true,
+            });
+        }
+    }
+    layers.push(ConfigLayer {
+        name: "project".to_string(),
+        path: paths.config_path.clone(),
+        required: true,
+    });
+    if let Some(profile) = profile {
+        layers.push(ConfigLayer {
+            name: format!("profile:{}", profile),
+            path: profile_config_path(paths, profile),
+            required: true,
+        });
+    }
+    layers
+}
+
+/// Loads the effective config for the selected profile (`--profile`, then `MACC_PROFILE`).
+pub fn load_effective_config(paths: &ProjectPaths) -> Result<CanonicalConfig> {
+    Ok(load_effective_config_with_profile(paths, selected_profile(paths).as_deref())?.config)
+}
+
+/// YAML to write to `.macc/macc.yaml` once the effective config has been edited into `edited`.
+///
+/// Without other layers this is `edited` itself. Otherwise only the values that `edited`
+/// changes are written into the project file, so user, parent and profile values stay in
+/// their own layers.
+pub fn project_config_yaml(paths: &ProjectPaths, edited: &CanonicalConfig) -> Result<String> {
+    let serialize =
+        |e: serde_yaml::Error| MaccError::Validation(format!("Failed to serialize config: {}", e));
+    let effective = load_effective_config_with_profile(paths, selected_profile(paths).as_deref())?;
+    if effective.layers.iter().all(|layer| layer.name == "project") {
+        return edited.to_yaml().map_err(serialize);
+    }
+
+    let mut before = Vec::new();
+    collect_leaf_keys(
+        &serde_yaml::to_value(&effective.config).map_err(serialize)?,
+        &mut Vec::new(),
+        &mut before,
+    );
+    let mut after = Vec::new();
+    collect_leaf_keys(
+        &serde_yaml::to_value(edited).map_err(serialize)?,
+        &mut Vec::new(),
+        &mut after,
+    );
+    let mut project = read_layer(&paths.config_path)?;
+    if !project.is_mapping() {
+        project = Value::Mapping(Mapping::new());
+    }
+    for (keys, value) in &after {
+        if !before.iter().any(|(k, v)| k == keys && v == value) {
+            merge_values(&mut project, nested(keys, value.clone()));
+        }
+    }
+    for (keys, _) in &before {
+        if !after.iter().any(|(k, _)| k == keys) {
+            remove_at(&mut project, keys);
+        }
+    }
+    serde_yaml::to_string(&project).map_err(serialize)
+}
+
+pub fn load_effective_config_with_profile(
+    paths: &ProjectPaths,
+    profile: Option<&str>,
+) -> Result<EffectiveConfig> {
+    if let Some(profile) = profile {
+        validate_profile_name(profile)?;
+    }
+    load_layered_config(&config_layers(paths, profile))
+}
+
+/// Deep-merges `layers` (lowest precedence first) and deserializes the result.
+///
+/// Mappings merge key by key; scalars and sequences from a later layer replace earlier values.
+pub fn load_layered_config(layers: &[ConfigLayer]) -> Result<EffectiveConfig> {
+    let mut merged = Value::Mapping(Mapping::new());
+    let mut origins = BTreeMap::new();
+    let mut loaded = Vec::new();
+
+    for layer in layers {
+        if !layer.path.exists() {
+            if layer.required {
+                return Err(MaccError::Io {
+                    path: layer.path.to_string_lossy().into(),
+                    action: format!("read {} config layer", layer.name),
+                    source: std::io::Error::new(
+                        std::io::ErrorKind::NotFound,
+                        "config layer not found",
+                    ),
+                });
+            }
+            continue;
+        }
+        let value = read_layer(&layer.path)?;
+        if value.is_null() {
+            loaded.push(layer.clone());
+            continue;
+        }
+        if !value.is_mapping() {
+            return Err(MaccError::Validation(format!(
+                "Config layer {} must be a YAML mapping",
+                layer.path.display()
+            )));
+        }
+        let mut leaves = BTreeMap::new();
+        collect_leaves(&value, String::new(), &mut leaves);
+        for path in leaves.into_keys() {
+            origins.insert(path, layer.name.clone());
+        }
+        merge_values(&mut merged, value);
+        loaded.push(layer.clone());
+    }
+
+    let mut merged_leaves = BTreeMap::new();
+    collect_leaves(&merged, String::new(), &mut merged_leaves);
+    origins.retain(|path, _| merged_leaves.contains_key(path));
+
+    let config: CanonicalConfig =
+        serde_yaml::from_value(merged.clone()).map_err(|e| MaccError::Config {
+            path: describe_layers(&loaded),
+            source: e,
+        })?;
+    config.validate()?;
+
+    Ok(EffectiveConfig {
+        config,
+        layers: loaded,
+        origins,
+        merged,
+    })
+}
+
+fn read_layer(path: &Path) -> Result<Value> {
+    let content = std::fs::read_to_string(path).map_err(|e| MaccError::Io {
+        path: path.to_string_lossy().into(),
+        action: "read config".into(),
+        source: e,
+    })?;
+    serde_yaml::from_str(&content).map_err(|e| MaccError::Config {
+        path: path.to_string_lossy().into(),
+        source: e,
+    })
+}
+
+fn describe_layers(layers: &[ConfigLayer]) -> String {
+    layers
+        .iter()
+        .map(|layer| layer.path.to_string_lossy().to_string())
+        .collect::<Vec<_>>()
+        .join(" + ")
+}
+
+fn merge_values(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Mapping(base_map), Value::Mapping(overlay_map)) => {
+            for (key, value) in overlay_map {
+                match base_map.get_mut(&key) {
+                    Some(existing) => merge_values(existing, value),
+                    None => {
+                        base_map.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+fn collect_leaves(value: &Value, prefix: String, out: &mut BTreeMap<String, Value>) {
+    match value {
+        Value::Mapping(map) if !map.is_empty() => {
+            for (key, child) in map {
+                let key = match key {
+                    Value::String(s) => s.clone(),
+                    other => serde_yaml::to_string(other)
+                        .unwrap_or_default()
+                        .trim()
+                        .to_string(),
+                };
+                let path = if prefix.is_empty() {
+                    key
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                collect_leaves(child, path, out);
+            }
+        }
+        _ if prefix.is_empty() => {}
+        other => {
+            out.insert(prefix, other.clone());
+        }
+    }
+}
+
+fn collect_leaf_keys(value: &Value, keys: &mut Vec<Value>, out: &mut Vec<(Vec<Value>, Value)>) {
+    match value {
+        Value::Mapping(map) if !map.is_empty() => {
+            for (key, child) in map {
+                keys.push(key.clone());
+                collect_leaf_keys(child, keys, out);
+                keys.pop();
+            }
+        }
+        _ if keys.is_empty() => {}
+        other => out.push((keys.clone(), other.clone())),
+    }
+}
+
+/// Mapping nesting `value` under `keys`, ready to be merged into a document.
+fn nested(keys: &[Value], value: Value) -> Value {
+    keys.iter().rev().fold(value, |inner, key| {
+        let mut map = Mapping::new();
+        map.insert(key.clone(), inner);
+        Value::Mapping(map)
+    })
+}
+
+fn remove_at(document: &mut Value, keys: &[Value]) {
+    let Some((last, parents)) = keys.split_last() else {
+        return;
+    };
+    let mut current = document;
+    for key in parents {
+        match current.get_mut(key) {
+            Some(next) => current = next,
+            None => return,
+        }
+    }
+    if let Value::Mapping(map) = current {
+        map.remove(last);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn temp_dir(label: &str) -> PathBuf {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("macc_layers_{}_{}", label, nanos));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn layer(name: &str, path: PathBuf, required: bool) -> ConfigLayer {
+        ConfigLayer {
+            name: name.to_string(),
+            path,
+            required,
+        }
+    }
+
+    #[test]
+    fn test_profile_overlay_deep_merges_and_tracks_origin() {
+        let dir = temp_dir("merge");
+        let user = dir.join("user.yaml");
+        let project = dir.join("macc.yaml");
+        let profile = dir.join("macc.ci.yaml");
+        fs::write(&user, "standards:\n  language: French\n  style: terse\n").unwrap();
+        fs::write(
+            &project,
+            "tools:\n  enabled: [tool-a]\nstandards:\n  language: English\nautomation:\n  coordinator:\n    max_parallel: 4\n    reference_branch: main\n",
+        )
+        .unwrap();
+        fs::write(
+            &profile,
+            "automation:\n  coordinator:\n    max_parallel: 1\n",
+        )
+        .unwrap();
+
+        let effective = load_layered_config(&[
+            layer("user", user, false),
+            layer("project", project, true),
+            layer("profile:ci", profile, true),
+        ])
+        .unwrap();
+
+        let coordinator = effective.config.automation.coordinator.as_ref().unwrap();
+        assert_eq!(coordinator.max_parallel, Some(1));
+        assert_eq!(coordinator.reference_branch.as_deref(), Some("main"));
+        assert_eq!(
+            effective.config.standards.inline.get("language").unwrap(),
+            "English"
+        );
+        assert_eq!(
+            effective.config.standards.inline.get("style").unwrap(),
+            "terse"
+        );
+
+        let origin = |path: &str| effective.origins.get(path).cloned();
+        assert_eq!(
+            origin("automation.coordinator.max_parallel").as_deref(),
+            Some("profile:ci")
+        );
+        assert_eq!(
+            origin("automation.coordinator.reference_branch").as_deref(),
+            Some("project")
+        );
+        assert_eq!(origin("standards.style").as_deref(), Some("user"));
+        assert_eq!(origin("tools.enabled").as_deref(), Some("project"));
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_missing_optional_layer_is_skipped_and_missing_profile_fails() {
+        let dir = temp_dir("missing");
+        let project = dir.join("macc.yaml");
+        fs::write(&project, "tools:\n  enabled: []\n").unwrap();
+
+        let effective = load_layered_config(&[
+            layer("user", dir.join("absent.yaml"), false),
+            layer("project", project.clone(), true),
+        ])
+        .unwrap();
+        assert_eq!(effective.layers.len(), 1);
+
+        let err = load_layered_config(&[
+            layer("project", project, true),
+            layer("profile:ci", dir.join("macc.ci.yaml"), true),
+        ])
+        .unwrap_err();
+        assert!(err.to_string().contains("macc.ci.yaml"));
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_unknown_field_in_overlay_is_rejected() {
+        let dir = temp_dir("unknown");
+        let project = dir.join("macc.yaml");
+        let profile = dir.join("macc.ci.yaml");
+        fs::write(&project, "tools:\n  enabled: []\n").unwrap();
+        fs::write(&profile, "bogus: true\n").unwrap();
+
+        let err = load_layered_config(&[
+            layer("project", project, true),
+            layer("profile:ci", profile, true),
+        ])
+        .unwrap_err();
+        assert!(err.to_string().contains("unknown field `bogus`"));
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_sub_project_inherits_parent_config() {
+        let dir = temp_dir("nested");
+        fs::create_dir_all(dir.join(".git")).unwrap();
+        fs::create_dir_all(dir.join(".macc")).unwrap();
+        fs::create_dir_all(dir.join("services/api/.macc")).unwrap();
+        fs::write(
+            dir.join(".macc/macc.yaml"),
+            "tools:\n  enabled: [tool-a]\nstandards:\n  language: English\n  style: terse\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("services/api/.macc/macc.yaml"),
+            "standards:\n  language: Rust\n",
+        )
+        .unwrap();
+
+        let paths = ProjectPaths::from_root(dir.join("services/api"));
+        let effective = load_effective_config_with_profile(&paths, None).unwrap();
+        let names: Vec<&str> = effective
+            .layers
+            .iter()
+            .map(|layer| layer.name.as_str())
+            .collect();
+        assert!(names.ends_with(&["parent:.", "project"]), "{:?}", names);
+        assert_eq!(effective.config.tools.enabled, vec!["tool-a".to_string()]);
+        assert_eq!(effective.config.standards.inline["language"], "Rust");
+        assert_eq!(effective.config.standards.inline["style"], "terse");
+        assert_eq!(
+            effective.origins.get("standards.style").map(String::as_str),
+            Some("parent:.")
+        );
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_project_config_yaml_writes_only_edits_over_other_layers() {
+        let dir = temp_dir("write_back");
+        fs::create_dir_all(dir.join(".git")).unwrap();
+        fs::create_dir_all(dir.join(".macc")).unwrap();
+        fs::create_dir_all(dir.join("services/api/.macc")).unwrap();
+        fs::write(
+            dir.join(".macc/macc.yaml"),
+            "tools:\n  enabled: [tool-a]\nstandards:\n  language: English\n  style: terse\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("services/api/.macc/macc.yaml"),
+            "standards:\n  language: Rust\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("services/api/.macc/macc.ci.yaml"),
+            "automation:\n  coordinator:\n    max_parallel: 1\n",
+        )
+        .unwrap();
+
+        let paths =
+            ProjectPaths::from_root(dir.join("services/api")).with_profile(Some("ci".into()));
+        let mut edited = load_effective_config(&paths).unwrap();
+        edited.tools.enabled = vec!["tool-b".to_string()];
+        edited.standards.inline.remove("language");
+        let yaml = project_config_yaml(&paths, &edited).unwrap();
+        let written: Value = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(written["tools"]["enabled"][0], Value::from("tool-b"));
+        assert!(written["standards"].get("language").is_none(), "{}", yaml);
+        assert!(written["standards"].get("style").is_none(), "{}", yaml);
+        assert!(written.get("automation").is_none(), "{}", yaml);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_invalid_profile_name_rejected() {
+        assert!(validate_profile_name("ci").is_ok());
+        assert!(validate_profile_name("local-dev_2").is_ok());
+        assert!(validate_profile_name("../etc").is_err());
+        assert!(validate_profile_name("").is_err());
+    }
+}
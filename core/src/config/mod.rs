@@ -1,10 +1,15 @@
+pub mod interpolate;
+pub mod layers;
 pub mod migrate;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashSet};
 use std::path::Path;
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub use layers::{load_effective_config, EffectiveConfig};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct CanonicalConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -20,7 +25,7 @@ pub struct CanonicalConfig {
     pub mcp_templates: Vec<McpTemplateDefinition>,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default, JsonSchema)]
 pub struct ToolsConfig {
     pub enabled: Vec<String>,
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
@@ -29,7 +34,7 @@ pub struct ToolsConfig {
     pub settings: BTreeMap<String, serde_json::Value>,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default, JsonSchema)]
 pub struct StandardsConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub path: Option<String>,
@@ -37,7 +42,7 @@ pub struct StandardsConfig {
     pub inline: BTreeMap<String, String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct SelectionsConfig {
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
@@ -48,7 +53,7 @@ pub struct SelectionsConfig {
     pub mcp: Vec<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct AutomationConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -57,7 +62,7 @@ pub struct AutomationConfig {
     pub coordinator: Option<CoordinatorConfig>,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct RalphConfig {
     #[serde(default = "default_true")]
@@ -70,7 +75,7 @@ pub struct RalphConfig {
     pub stop_on_failure: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct CoordinatorConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -111,6 +116,229 @@ pub struct CoordinatorConfig {
     pub stale_action: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub storage_mode: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub token_prices: BTreeMap<String, TokenPrice>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub budget_usd: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub worktree: Option<WorktreePoolConfig>,
+    /// Per-tool performer sandbox, overriding the ToolSpec `performer.sandbox`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub sandbox: BTreeMap<String, SandboxConfig>,
+    /// Performer resource limits keyed by tool id; the `default` entry applies to every tool.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub resource_limits: BTreeMap<String, ResourceLimits>,
+    /// Code forge hosting task pull requests; without it PRs stay local and merges run locally.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub forge: Option<ForgeConfig>,
+    /// Tasks that wait in `queued` for `macc coordinator approve` before they merge.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub approval: Option<ApprovalPolicy>,
+    /// Hold back ready tasks whose `scope` overlaps the `scope` of an active task. Scope globs
+    /// follow approval `paths`: a plain name (`Cargo.toml`) is the root-level file, a wildcard
+    /// glob without `/` (`*.md`) matches at any depth.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avoid_scope_overlap: Option<bool>,
+    /// Also hold back tasks whose `scope` matches files already changed on active task branches.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub predict_scope_overlap: Option<bool>,
+    /// How task branches land on their base branch when the coordinator merges locally.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub merge: Option<MergeConfig>,
+    /// Small-PR limits checked on the task branch after the dev and fix phases.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size_guard: Option<SizeGuardConfig>,
+    /// Ask the coordinator tool to split blocked tasks into subtasks.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decompose: Option<DecomposeConfig>,
+}
+
+/// CPU-time, memory and process-count limits for one performer process tree. Unset fields
+/// are unlimited.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Default, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ResourceLimits {
+    /// CPU time in seconds (per process with rlimits, for the whole tree with cgroups).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu_seconds: Option<u64>,
+    /// Memory in MiB (data segment per process with rlimits, the whole tree with cgroups).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_mb: Option<u64>,
+    /// Processes and threads (per user with rlimits, the whole tree with cgroups).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_processes: Option<u64>,
+}
+
+/// Linux sandbox for performer tool processes: the task worktree is the only writable project
+/// path and the repository `.git` is read-only.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Default, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SandboxConfig {
+    /// `bwrap` (bubblewrap), `unshare` (util-linux user namespaces) or `none`.
+    pub mode: String,
+    /// Extra read-only paths such as toolchains (`~/.cargo`, `~/.nvm`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub read_only: Vec<String>,
+    /// Extra writable paths such as the tool's own state directory.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub read_write: Vec<String>,
+    /// Network access: empty (default) gives the tool no network, `["*"]` the host network.
+    /// Per-host lists are rejected because the sandbox cannot enforce them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub network: Vec<String>,
+}
+
+pub const SANDBOX_MODES: &[&str] = &["bwrap", "unshare", "none"];
+
+/// Coordinator worktree pool preparation: pre-warmed slots, a one-time setup hook per slot and
+/// dependency directories shared across slots.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct WorktreePoolConfig {
+    /// Create pool slots up to `max_parallel` when a coordinator run starts.
+    #[serde(default)]
+    pub prewarm: bool,
+    /// Shell command run once in each slot (e.g. `npm ci`, `cargo fetch`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub setup: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub setup_timeout_seconds: Option<u64>,
+    /// Slot-relative directories copied from the shared cache (e.g. `node_modules`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub shared_dirs: Vec<String>,
+    /// Environment for setup hooks and performers; `{cache_dir}` expands to the shared cache
+    /// directory (e.g. `CARGO_TARGET_DIR: "{cache_dir}/cargo-target"`).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+}
+
+/// Forge backend for task pull requests: the coordinator pushes task branches, opens a pull
+/// request after the review phase and follows its review state and merge.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Default, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ForgeConfig {
+    /// `gitea`, `gitlab` or `file` (a directory-backed fake for tests and dry runs).
+    pub kind: String,
+    /// API base URL (e.g. `https://gitea.example.com`); for `file`, the state directory
+    /// (default `.macc/forge`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Repository: `owner/name` on Gitea, project path or numeric id on GitLab.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repo: Option<String>,
+    /// Environment variable holding the API token (default `MACC_FORGE_TOKEN`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_env: Option<String>,
+    /// Git remote task branches are pushed to (default `origin`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote: Option<String>,
+    /// `merge`, `squash` or `rebase` (default `merge`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub merge_method: Option<String>,
+    /// Seconds between review status polls of one pull request (default 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub poll_seconds: Option<u64>,
+}
+
+/// Human approval before merge. A task needs approval when any rule matches it.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Default, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ApprovalPolicy {
+    /// Task categories (PRD `category`), compared case-insensitively.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub categories: Vec<String>,
+    /// Task priorities: `p0`..`p4` or a number.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub priorities: Vec<String>,
+    /// Repository path globs (`*`, `**`, `?`) matched against the files the task branch changes.
+    /// A wildcard glob without `/` (`*.sql`) matches at any depth; a plain name (`Cargo.lock`)
+    /// is the root-level file, as in task `scope`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub paths: Vec<String>,
+}
+
+/// Local merge of task branches. A PRD task `merge_strategy` overrides `strategy`.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Default, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct MergeConfig {
+    /// `no-ff` (default), `squash`, `rebase` (rebase onto the base, then fast-forward) or
+    /// `ff-only`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strategy: Option<String>,
+    /// Merge or squash commit message with `{task_id}`, `{title}` and `{category}` placeholders
+    /// (default `macc: merge task {task_id}`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// Sign the commits the merge creates: `gpg` or `ssh`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signing: Option<String>,
+    /// Signing key (GPG key id or SSH key path); defaults to git `user.signingkey`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signing_key: Option<String>,
+}
+
+/// Diff size limits of a task branch against its base. Unset limits are unlimited.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Default, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SizeGuardConfig {
+    /// Files changed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_files: Option<usize>,
+    /// Lines added plus lines deleted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_lines: Option<usize>,
+    /// `changes_requested` (default) sends the task back to split or reduce the change;
+    /// `blocked` blocks it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<String>,
+    /// Limits per PRD category, replacing the global ones field by field.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub categories: BTreeMap<String, SizeLimits>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Default, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SizeLimits {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_files: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_lines: Option<usize>,
+}
+
+pub const SIZE_GUARD_ACTIONS: &[&str] = &["changes_requested", "blocked"];
+
+/// Decomposition of blocked tasks into subtasks proposed by the coordinator tool.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Default, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct DecomposeConfig {
+    /// Why a blocked task is decomposed: `size_limit` and/or `failure` (default both).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub triggers: Vec<String>,
+    /// Most subtasks one task may be split into (default 5).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_subtasks: Option<usize>,
+}
+
+pub const DECOMPOSE_TRIGGERS: &[&str] = &["size_limit", "failure"];
+pub const DEFAULT_MAX_SUBTASKS: usize = 5;
+
+pub const MERGE_STRATEGIES: &[&str] = &["no-ff", "squash", "rebase", "ff-only"];
+pub const MERGE_SIGNING_FORMATS: &[&str] = &["gpg", "ssh"];
+
+pub const FORGE_KINDS: &[&str] = &["gitea", "gitlab", "file"];
+pub const FORGE_MERGE_METHODS: &[&str] = &["merge", "squash", "rebase"];
+
+/// USD per million tokens for one tool, used to estimate coordinator run cost.
+/// Cached input tokens fall back to the input price when no cached price is set.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct TokenPrice {
+    #[serde(default)]
+    pub input_per_million: f64,
+    #[serde(default)]
+    pub output_per_million: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cached_input_per_million: Option<f64>,
 }
 
 fn default_true() -> bool {
@@ -125,7 +353,7 @@ fn default_ralph_branch() -> String {
     "ralph".to_string()
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct McpTemplateDefinition {
     pub id: String,
@@ -140,7 +368,7 @@ pub struct McpTemplateDefinition {
     pub auth_notes: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct McpEnvPlaceholder {
     pub name: String,
@@ -217,10 +445,255 @@ impl CanonicalConfig {
             }
         }
 
+        if let Some(coordinator) = &self.automation.coordinator {
+            for (tool, sandbox) in &coordinator.sandbox {
+                sandbox.validate(&format!("automation.coordinator.sandbox.{}", tool))?;
+            }
+            for (tool, limits) in &coordinator.resource_limits {
+                limits.validate(&format!("automation.coordinator.resource_limits.{}", tool))?;
+            }
+            if let Some(forge) = &coordinator.forge {
+                forge.validate("automation.coordinator.forge")?;
+            }
+            if let Some(approval) = &coordinator.approval {
+                approval.validate("automation.coordinator.approval")?;
+            }
+            if let Some(merge) = &coordinator.merge {
+                merge.validate("automation.coordinator.merge")?;
+            }
+            if let Some(size_guard) = &coordinator.size_guard {
+                size_guard.validate("automation.coordinator.size_guard")?;
+            }
+            if let Some(decompose) = &coordinator.decompose {
+                decompose.validate("automation.coordinator.decompose")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl SandboxConfig {
+    pub fn validate(&self, location: &str) -> crate::Result<()> {
+        if !SANDBOX_MODES.contains(&self.mode.as_str()) {
+            return Err(crate::MaccError::Validation(format!(
+                "{}: sandbox mode must be one of {} (got '{}')",
+                location,
+                SANDBOX_MODES.join(", "),
+                self.mode
+            )));
+        }
+        if !self.network.is_empty() && self.network != ["*"] {
+            return Err(crate::MaccError::Validation(format!(
+                "{}: sandbox network must be empty (no network) or [\"*\"] (host network); host allowlists are not supported (got {})",
+                location,
+                self.network.join(", ")
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.mode != "none"
+    }
+}
+
+impl ForgeConfig {
+    pub fn validate(&self, location: &str) -> crate::Result<()> {
+        if !FORGE_KINDS.contains(&self.kind.as_str()) {
+            return Err(crate::MaccError::Validation(format!(
+                "{}: kind must be one of {} (got '{}')",
+                location,
+                FORGE_KINDS.join(", "),
+                self.kind
+            )));
+        }
+        if self.kind != "file" {
+            for (name, value) in [("url", &self.url), ("repo", &self.repo)] {
+                if value
+                    .as_deref()
+                    .map(str::trim)
+                    .unwrap_or_default()
+                    .is_empty()
+                {
+                    return Err(crate::MaccError::Validation(format!(
+                        "{}: {} is required for a {} forge",
+                        location, name, self.kind
+                    )));
+                }
+            }
+        }
+        if let Some(method) = &self.merge_method {
+            if !FORGE_MERGE_METHODS.contains(&method.as_str()) {
+                return Err(crate::MaccError::Validation(format!(
+                    "{}: merge_method must be one of {} (got '{}')",
+                    location,
+                    FORGE_MERGE_METHODS.join(", "),
+                    method
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl MergeConfig {
+    pub fn validate(&self, location: &str) -> crate::Result<()> {
+        if let Some(strategy) = &self.strategy {
+            if !MERGE_STRATEGIES.contains(&strategy.as_str()) {
+                return Err(crate::MaccError::Validation(format!(
+                    "{}: strategy must be one of {} (got '{}')",
+                    location,
+                    MERGE_STRATEGIES.join(", "),
+                    strategy
+                )));
+            }
+        }
+        if let Some(signing) = &self.signing {
+            if !MERGE_SIGNING_FORMATS.contains(&signing.as_str()) {
+                return Err(crate::MaccError::Validation(format!(
+                    "{}: signing must be one of {} (got '{}')",
+                    location,
+                    MERGE_SIGNING_FORMATS.join(", "),
+                    signing
+                )));
+            }
+        }
+        if self.signing_key.is_some() && self.signing.is_none() {
+            return Err(crate::MaccError::Validation(format!(
+                "{}: signing_key needs signing (gpg or ssh)",
+                location
+            )));
+        }
         Ok(())
     }
 }
 
+impl SizeGuardConfig {
+    pub fn validate(&self, location: &str) -> crate::Result<()> {
+        if let Some(action) = &self.action {
+            if !SIZE_GUARD_ACTIONS.contains(&action.as_str()) {
+                return Err(crate::MaccError::Validation(format!(
+                    "{}: action must be one of {} (got '{}')",
+                    location,
+                    SIZE_GUARD_ACTIONS.join(", "),
+                    action
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Limits for a task category: the category entry (case-insensitive) over the globals.
+    pub fn limits_for(&self, category: Option<&str>) -> SizeLimits {
+        let specific = category.and_then(|category| {
+            self.categories
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(category.trim()))
+                .map(|(_, limits)| *limits)
+        });
+        SizeLimits {
+            max_files: specific.and_then(|l| l.max_files).or(self.max_files),
+            max_lines: specific.and_then(|l| l.max_lines).or(self.max_lines),
+        }
+    }
+}
+
+impl DecomposeConfig {
+    pub fn validate(&self, location: &str) -> crate::Result<()> {
+        if let Some(trigger) = self
+            .triggers
+            .iter()
+            .find(|t| !DECOMPOSE_TRIGGERS.contains(&t.as_str()))
+        {
+            return Err(crate::MaccError::Validation(format!(
+                "{}: triggers must be among {} (got '{}')",
+                location,
+                DECOMPOSE_TRIGGERS.join(", "),
+                trigger
+            )));
+        }
+        if self.max_subtasks.is_some_and(|max| max < 2) {
+            return Err(crate::MaccError::Validation(format!(
+                "{}: max_subtasks must be at least 2",
+                location
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn triggered_by(&self, trigger: &str) -> bool {
+        self.triggers.is_empty() || self.triggers.iter().any(|t| t == trigger)
+    }
+
+    pub fn max_subtasks(&self) -> usize {
+        self.max_subtasks.unwrap_or(DEFAULT_MAX_SUBTASKS)
+    }
+}
+
+impl ApprovalPolicy {
+    pub fn validate(&self, location: &str) -> crate::Result<()> {
+        if self.categories.is_empty() && self.priorities.is_empty() && self.paths.is_empty() {
+            return Err(crate::MaccError::Validation(format!(
+                "{}: set at least one of categories, priorities or paths",
+                location
+            )));
+        }
+        for priority in &self.priorities {
+            let normalized = priority.trim().to_ascii_lowercase();
+            let valid = normalized
+                .strip_prefix('p')
+                .unwrap_or(&normalized)
+                .parse::<u32>()
+                .is_ok();
+            if !valid {
+                return Err(crate::MaccError::Validation(format!(
+                    "{}: priority must be p0..p4 or a number (got '{}')",
+                    location, priority
+                )));
+            }
+        }
+        if let Some(path) = self.paths.iter().find(|p| p.trim().is_empty()) {
+            return Err(crate::MaccError::Validation(format!(
+                "{}: empty path glob '{}'",
+                location, path
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl ResourceLimits {
+    pub fn validate(&self, location: &str) -> crate::Result<()> {
+        for (name, value) in [
+            ("cpu_seconds", self.cpu_seconds),
+            ("memory_mb", self.memory_mb),
+            ("max_processes", self.max_processes),
+        ] {
+            if value == Some(0) {
+                return Err(crate::MaccError::Validation(format!(
+                    "{}: {} must be greater than 0 (omit it for no limit)",
+                    location, name
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cpu_seconds.is_none() && self.memory_mb.is_none() && self.max_processes.is_none()
+    }
+
+    /// `self` with every field set in `other` replaced.
+    pub fn overlay(&self, other: &ResourceLimits) -> ResourceLimits {
+        ResourceLimits {
+            cpu_seconds: other.cpu_seconds.or(self.cpu_seconds),
+            memory_mb: other.memory_mb.or(self.memory_mb),
+            max_processes: other.max_processes.or(self.max_processes),
+        }
+    }
+}
+
 impl Default for CanonicalConfig {
     fn default() -> Self {
         Self {
@@ -487,6 +960,38 @@ automation:
     stale_changes_requested_seconds: 1800
     stale_action: blocked
     storage_mode: dual-write
+    token_prices:
+      tool-alpha:
+        input_per_million: 3.0
+        output_per_million: 15.0
+    budget_usd: 25.5
+    worktree:
+      prewarm: true
+      setup: npm ci
+      shared_dirs: [node_modules]
+      env:
+        CARGO_TARGET_DIR: "{cache_dir}/cargo-target"
+    sandbox:
+      tool-alpha:
+        mode: bwrap
+        read_write: ["~/.config/tool-alpha"]
+        network: ["*"]
+    resource_limits:
+      default:
+        memory_mb: 4096
+      tool-alpha:
+        cpu_seconds: 1800
+        max_processes: 256
+    forge:
+      kind: gitea
+      url: https://gitea.example.com
+      repo: team/app
+      merge_method: squash
+      poll_seconds: 60
+    approval:
+      categories: [security]
+      priorities: [p0]
+      paths: ["migrations/**", "**/*.sql"]
 "#;
         let config = CanonicalConfig::from_yaml(yaml).expect("Should parse coordinator config");
         let coordinator = config
@@ -519,6 +1024,46 @@ automation:
         assert_eq!(coordinator.stale_changes_requested_seconds, Some(1800));
         assert_eq!(coordinator.stale_action.as_deref(), Some("blocked"));
         assert_eq!(coordinator.storage_mode.as_deref(), Some("dual-write"));
+        assert_eq!(
+            coordinator.token_prices["tool-alpha"].output_per_million,
+            15.0
+        );
+        assert_eq!(coordinator.budget_usd, Some(25.5));
+        let worktree = coordinator.worktree.as_ref().expect("worktree pool config");
+        assert!(worktree.prewarm);
+        assert_eq!(worktree.setup.as_deref(), Some("npm ci"));
+        assert_eq!(worktree.shared_dirs, vec!["node_modules"]);
+        assert_eq!(coordinator.sandbox["tool-alpha"].mode, "bwrap");
+        assert_eq!(coordinator.sandbox["tool-alpha"].network, vec!["*"]);
+        let host_list = SandboxConfig {
+            mode: "bwrap".into(),
+            network: vec!["api.example.com".into()],
+            ..SandboxConfig::default()
+        };
+        assert!(host_list.validate("sandbox").is_err());
+        let limits = coordinator.resource_limits["default"]
+            .overlay(&coordinator.resource_limits["tool-alpha"]);
+        assert_eq!(limits.memory_mb, Some(4096));
+        assert_eq!(limits.cpu_seconds, Some(1800));
+        assert_eq!(limits.max_processes, Some(256));
+        let forge = coordinator.forge.as_ref().expect("forge config");
+        assert_eq!(forge.repo.as_deref(), Some("team/app"));
+        assert_eq!(forge.merge_method.as_deref(), Some("squash"));
+        assert!(config.validate().is_ok());
+        let missing_repo = ForgeConfig {
+            kind: "gitlab".into(),
+            url: Some("https://gitlab.example.com".into()),
+            ..Default::default()
+        };
+        assert!(missing_repo.validate("forge").is_err());
+        let approval = coordinator.approval.as_ref().expect("approval policy");
+        assert_eq!(approval.paths, vec!["migrations/**", "**/*.sql"]);
+        assert!(ApprovalPolicy::default().validate("approval").is_err());
+        let bad_priority = ApprovalPolicy {
+            priorities: vec!["urgent".into()],
+            ..Default::default()
+        };
+        assert!(bad_priority.validate("approval").is_err());
 
         let reserialized = config.to_yaml().expect("Should serialize back to yaml");
         let config2 =
@@ -2,21 +2,39 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::str::FromStr;
 
+pub mod approval;
 pub mod args;
 pub mod control_plane;
+pub mod decompose;
 pub mod engine;
+pub mod forge;
+pub mod glob;
 pub mod helpers;
+pub mod lease;
+pub mod limits;
 pub mod logs;
+pub mod metrics;
 pub mod model;
+pub mod operator;
+pub mod prd;
+pub mod replay;
+pub mod runs;
 pub mod runtime;
 pub mod session_manager;
+pub mod simulate;
+pub mod size_guard;
 pub mod state;
 pub mod state_runtime;
 pub mod task_selector;
+pub mod throttle;
+pub mod trace;
 pub mod types;
+pub mod usage;
+pub mod worktree_pool;
 
 pub const COORDINATOR_TASK_REGISTRY_REL_PATH: &str = ".macc/automation/task/task_registry.json";
 pub const COORDINATOR_PAUSE_FILE_REL_PATH: &str = ".macc/automation/task/coordinator.pause.json";
+pub const COORDINATOR_LEASE_FILE_REL_PATH: &str = ".macc/automation/task/coordinator.lease.json";
 
 pub const COORDINATOR_EVENT_SCHEMA_VERSION: &str = "1";
 pub const COORDINATOR_EVENT_TYPES_V1: &[&str] = &[
@@ -30,6 +48,7 @@ pub const COORDINATOR_EVENT_TYPES_V1: &[&str] = &[
     "dispatch_complete",
     "started",
     "progress",
+    "artifact",
     "phase_result",
     "commit_created",
     "review_done",
@@ -59,6 +78,15 @@ pub const COORDINATOR_EVENT_TYPES_V1: &[&str] = &[
     "local_merge_failed",
     "merge_worker_started",
     "merge_worker_complete",
+    "budget_exceeded",
+    "tool_throttled",
+    "worktree_setup",
+    "resource_limit_exceeded",
+    "resource_limits_downgraded",
+    "task_size_exceeded",
+    "task_decomposed",
+    "task_decomposition_failed",
+    "subtasks_merged",
 ];
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -160,6 +188,8 @@ pub fn is_valid_workflow_transition(from: WorkflowState, to: WorkflowState) -> b
     matches!(
         (from, to),
         (WorkflowState::Todo, WorkflowState::Claimed)
+            | (WorkflowState::Todo, WorkflowState::Blocked)
+            | (WorkflowState::Todo, WorkflowState::Abandoned)
             | (WorkflowState::Claimed, WorkflowState::InProgress)
             | (WorkflowState::Claimed, WorkflowState::Blocked)
             | (WorkflowState::Claimed, WorkflowState::Abandoned)
@@ -176,6 +206,7 @@ pub fn is_valid_workflow_transition(from: WorkflowState, to: WorkflowState) -> b
             | (WorkflowState::ChangesRequested, WorkflowState::Abandoned)
             | (WorkflowState::Queued, WorkflowState::Merged)
             | (WorkflowState::Queued, WorkflowState::PrOpen)
+            | (WorkflowState::Queued, WorkflowState::ChangesRequested)
             | (WorkflowState::Queued, WorkflowState::Blocked)
             | (WorkflowState::Queued, WorkflowState::Abandoned)
             | (WorkflowState::Blocked, WorkflowState::Todo)
@@ -208,6 +239,7 @@ pub fn is_valid_runtime_transition(from: RuntimeStatus, to: RuntimeStatus) -> bo
             | (RuntimeStatus::WaitingForUser, RuntimeStatus::Idle)
             | (RuntimeStatus::PhaseDone, RuntimeStatus::Running)
             | (RuntimeStatus::PhaseDone, RuntimeStatus::Idle)
+            | (RuntimeStatus::PhaseDone, RuntimeStatus::WaitingForUser)
             | (RuntimeStatus::PhaseDone, RuntimeStatus::Failed)
             | (RuntimeStatus::Failed, RuntimeStatus::Dispatched)
             | (RuntimeStatus::Failed, RuntimeStatus::Paused)
@@ -248,6 +280,8 @@ pub fn runtime_status_from_event(event_type: &str, status: &str) -> RuntimeStatu
 pub struct CoordinatorEvent {
     pub schema_version: String,
     pub event_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<String>,
     pub seq: u64,
     pub ts: String,
     pub source: String,
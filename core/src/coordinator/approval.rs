@@ -0,0 +1,553 @@
+//! Human approval gate before merge.
+//!
+//! With `automation.coordinator.approval`, a `queued` task matching the policy (category,
+//! priority or changed paths) is not merged: its runtime goes to `waiting_for_user` with a
+//! pending `approval` record holding an [`InteractionRequest`]. `macc coordinator approve`
+//! releases it to merge; `macc coordinator reject` sends it back to `changes_requested` with the
+//! rejection comment as review feedback for the fix phase.
+//!
+//! A request is about the branch head it was made for: a pending or granted approval is voided
+//! when the head moves or the task leaves `queued`, and the task asks again. Decisions are
+//! stamped like operator commands so a control plane cycle saving an older registry keeps them.
+
+use crate::config::ApprovalPolicy;
+use crate::coordinator::engine as coordinator_engine;
+use crate::coordinator::glob::path_matches;
+use crate::coordinator::helpers::{append_coordinator_event_with_payload, now_iso_coordinator};
+use crate::coordinator::replay::{record_task_mutation_at, TaskMutation};
+use crate::coordinator::state::{coordinator_state_registry_load, coordinator_state_registry_save};
+use crate::service::interaction::{InteractionKind, InteractionRequest, WaitingForUser};
+use crate::{MaccError, Result};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+pub enum ApprovalGate {
+    /// No approval needed, or already approved.
+    Proceed,
+    /// A request is pending.
+    Waiting,
+    /// The task now waits for a decision on this request.
+    Requested {
+        request: InteractionRequest,
+        reasons: Vec<String>,
+    },
+}
+
+/// `pending`, `approved`, `rejected` or `void`; `None` when no approval was ever requested.
+pub fn task_approval_status(task: &Value) -> Option<&str> {
+    task.get("approval")
+        .and_then(|a| a.get("status"))
+        .and_then(Value::as_str)
+}
+
+/// Ids of the tasks waiting for an approval decision, in registry order.
+pub fn pending_approvals(registry: &Value) -> Vec<String> {
+    registry
+        .get("tasks")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter(|task| task_approval_status(task) == Some("pending"))
+        .filter_map(|task| task.get("id").and_then(Value::as_str).map(str::to_string))
+        .collect()
+}
+
+/// A request waiting for a decision, as shown to the operator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingApproval {
+    pub task_id: String,
+    pub prompt: String,
+    pub reasons: Vec<String>,
+}
+
+/// The pending requests of [`pending_approvals`], with their prompt and reasons.
+pub fn pending_approval_requests(registry: &Value) -> Vec<PendingApproval> {
+    registry
+        .get("tasks")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter(|task| task_approval_status(task) == Some("pending"))
+        .filter_map(|task| {
+            let approval = task.get("approval")?;
+            Some(PendingApproval {
+                task_id: task.get("id").and_then(Value::as_str)?.to_string(),
+                prompt: approval
+                    .get("request")
+                    .and_then(|r| r.get("prompt"))
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+                reasons: approval
+                    .get("reasons")
+                    .and_then(Value::as_array)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(Value::as_str)
+                    .map(str::to_string)
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+/// Why `task` needs approval under `policy` (empty when it does not).
+pub fn approval_reasons(
+    policy: &ApprovalPolicy,
+    task: &Value,
+    changed_files: &[String],
+) -> Vec<String> {
+    let mut reasons = Vec::new();
+    if let Some(category) = task.get("category").and_then(Value::as_str) {
+        if policy
+            .categories
+            .iter()
+            .any(|c| c.trim().eq_ignore_ascii_case(category.trim()))
+        {
+            reasons.push(format!("category {}", category));
+        }
+    }
+    if task.get("priority").is_some() {
+        let priority = crate::coordinator::task_selector::parse_priority(task.get("priority"));
+        if policy.priorities.iter().any(|p| {
+            crate::coordinator::task_selector::parse_priority(Some(&Value::String(p.clone())))
+                == priority
+        }) {
+            reasons.push(format!("priority p{}", priority));
+        }
+    }
+    for pattern in &policy.paths {
+        if let Some(path) = changed_files.iter().find(|f| path_matches(pattern, f)) {
+            reasons.push(format!("path {} ({})", path, pattern));
+        }
+    }
+    reasons
+}
+
+/// Files changed on `branch` since it forked from `base`.
+pub fn changed_files(repo_root: &Path, base: &str, branch: &str) -> Result<Vec<String>> {
+    let range = format!("{}...{}", base, branch);
+    let output = crate::git::run_git_output_mapped(
+        repo_root,
+        &["diff", "--name-only", &range],
+        "list task changed files",
+    )?;
+    if !output.status.success() {
+        return Err(MaccError::Validation(format!(
+            "git diff {} failed: {}",
+            range,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// Decides whether a `queued` task may merge. A matching task without a decision gets a
+/// pending request recorded in `registry` (the caller saves it and emits the event).
+pub fn approval_gate(
+    repo_root: &Path,
+    policy: &ApprovalPolicy,
+    registry: &mut Value,
+    task_id: &str,
+    branch: &str,
+    base: &str,
+    now: &str,
+) -> Result<ApprovalGate> {
+    let task = registry
+        .get("tasks")
+        .and_then(Value::as_array)
+        .and_then(|tasks| {
+            tasks
+                .iter()
+                .find(|t| t.get("id").and_then(Value::as_str) == Some(task_id))
+        })
+        .ok_or_else(|| {
+            MaccError::Validation(format!("Task '{}' not found in registry", task_id))
+        })?;
+    let head = crate::coordinator::forge::branch_head(repo_root, branch).ok();
+    let mut moved = None;
+    if let Some(status @ ("approved" | "pending")) = task_approval_status(task) {
+        let recorded = task
+            .get("approval")
+            .and_then(|a| a.get("head"))
+            .and_then(Value::as_str);
+        if recorded == head.as_deref() {
+            return Ok(if status == "approved" {
+                ApprovalGate::Proceed
+            } else {
+                ApprovalGate::Waiting
+            });
+        }
+        // The decision was about other commits.
+        moved = Some(format!(
+            "branch head moved to {} since the request",
+            head.as_deref()
+                .map(short_sha)
+                .unwrap_or("an unknown commit")
+        ));
+    }
+    let mut reasons = if policy.paths.is_empty() {
+        approval_reasons(policy, task, &[])
+    } else {
+        match changed_files(repo_root, base, branch) {
+            Ok(files) => approval_reasons(policy, task, &files),
+            // Fail closed: a path rule that cannot be checked asks for approval.
+            Err(err) => vec![format!("changed files unknown: {}", err)],
+        }
+    };
+    if reasons.is_empty() {
+        return Ok(ApprovalGate::Proceed);
+    }
+    if let Some(moved) = moved {
+        reasons.insert(0, moved);
+    }
+    request_approval(
+        repo_root,
+        registry,
+        task_id,
+        base,
+        head.as_deref(),
+        reasons,
+        now,
+    )
+}
+
+fn short_sha(sha: &str) -> &str {
+    &sha[..sha.len().min(12)]
+}
+
+fn request_approval(
+    repo_root: &Path,
+    registry: &mut Value,
+    task_id: &str,
+    base: &str,
+    head: Option<&str>,
+    reasons: Vec<String>,
+    now: &str,
+) -> Result<ApprovalGate> {
+    let request = InteractionRequest {
+        request_id: format!("approval-{}-{}", task_id, now),
+        prompt: format!(
+            "Approve merging task {} into {}? ({})",
+            task_id,
+            base,
+            reasons.join(", ")
+        ),
+        kind: InteractionKind::ConfirmYesNo,
+        scope: Some("coordinator".into()),
+    };
+    coordinator_engine::apply_approval_request_in_registry(
+        registry, task_id, &request, &reasons, head, now,
+    )?;
+    record_task_mutation_at(
+        repo_root,
+        task_id,
+        &TaskMutation::ApprovalRequested {
+            request: request.clone(),
+            reasons: reasons.clone(),
+            head: head.map(str::to_string),
+        },
+        now,
+    );
+    Ok(ApprovalGate::Requested { request, reasons })
+}
+
+/// Voids the pending or granted approval of `task_id` if the task is no longer `queued` (a
+/// merged task used its approval). Returns the reason when it did; the caller saves the
+/// registry, then calls [`record_voided_approval`].
+pub fn void_departed_approval(
+    registry: &mut Value,
+    task_id: &str,
+    now: &str,
+) -> Result<Option<String>> {
+    let Some(task) = registry
+        .get("tasks")
+        .and_then(Value::as_array)
+        .and_then(|tasks| {
+            tasks
+                .iter()
+                .find(|t| t.get("id").and_then(Value::as_str) == Some(task_id))
+        })
+    else {
+        return Ok(None);
+    };
+    let state = task.get("state").and_then(Value::as_str).unwrap_or("todo");
+    if !matches!(task_approval_status(task), Some("pending" | "approved"))
+        || matches!(state, "queued" | "merged")
+    {
+        return Ok(None);
+    }
+    let reason = format!("task left queued for {}", state);
+    coordinator_engine::apply_approval_void_in_registry(registry, task_id, &reason, now)?;
+    Ok(Some(reason))
+}
+
+/// [`void_departed_approval`] for every task of the registry; returns the voided tasks with
+/// their reason.
+pub fn void_departed_approvals(registry: &mut Value, now: &str) -> Result<Vec<(String, String)>> {
+    let ids: Vec<String> = registry
+        .get("tasks")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|task| task.get("id").and_then(Value::as_str).map(str::to_string))
+        .collect();
+    let mut voided = Vec::new();
+    for id in ids {
+        if let Some(reason) = void_departed_approval(registry, &id, now)? {
+            voided.push((id, reason));
+        }
+    }
+    Ok(voided)
+}
+
+/// Records a voided approval for replay and emits `approval_voided`.
+pub fn record_voided_approval(repo_root: &Path, task_id: &str, reason: &str, now: &str) {
+    record_task_mutation_at(
+        repo_root,
+        task_id,
+        &TaskMutation::ApprovalVoided {
+            reason: reason.to_string(),
+        },
+        now,
+    );
+    let _ = append_coordinator_event_with_payload(
+        repo_root,
+        "approval_voided",
+        task_id,
+        "approval",
+        "void",
+        "info",
+        serde_json::json!({
+            "message": format!("Approval of task {} voided: {}", task_id, reason),
+            "reason": reason,
+        }),
+    );
+}
+
+/// The waiting marker for a pending request, with the policy reasons.
+pub fn waiting_for_user(request: &InteractionRequest, reasons: &[String]) -> WaitingForUser {
+    WaitingForUser {
+        reason: Some(format!("approval required: {}", reasons.join(", "))),
+        ..WaitingForUser::from_request(request)
+    }
+}
+
+/// Name recorded on approval decisions and operator task commands.
+pub fn current_user() -> String {
+    ["MACC_USER", "USER", "USERNAME"]
+        .iter()
+        .find_map(|key| std::env::var(key).ok().filter(|v| !v.trim().is_empty()))
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Approves or rejects the pending approval of `task_id` in the task registry.
+pub fn decide_task_approval(
+    repo_root: &Path,
+    task_id: &str,
+    approved: bool,
+    user: &str,
+    comment: Option<&str>,
+) -> Result<()> {
+    let mut registry = coordinator_state_registry_load(repo_root, &BTreeMap::new())?;
+    let request_id = registry
+        .get("tasks")
+        .and_then(Value::as_array)
+        .and_then(|tasks| {
+            tasks
+                .iter()
+                .find(|t| t.get("id").and_then(Value::as_str) == Some(task_id))
+        })
+        .and_then(|t| t.get("approval"))
+        .and_then(|a| a.get("request"))
+        .and_then(|r| r.get("request_id"))
+        .cloned()
+        .unwrap_or(Value::Null);
+    let now = now_iso_coordinator();
+    coordinator_engine::apply_approval_decision_in_registry(
+        &mut registry,
+        task_id,
+        approved,
+        user,
+        comment,
+        &now,
+    )?;
+    // The control plane may be mid-cycle on a registry loaded before this write: the revision
+    // bump makes it keep the decided task instead of overwriting it.
+    let action = if approved { "approve" } else { "reject" };
+    crate::coordinator::operator::stamp_task(&mut registry, task_id, action, user, &now)?;
+    coordinator_state_registry_save(repo_root, &BTreeMap::new(), &registry)?;
+    record_task_mutation_at(
+        repo_root,
+        task_id,
+        &TaskMutation::ApprovalDecided {
+            approved,
+            user: user.to_string(),
+            comment: comment.map(str::to_string),
+        },
+        &now,
+    );
+    crate::coordinator::operator::record_operator(repo_root, &registry, task_id);
+    let (event, status) = if approved {
+        ("approval_granted", "approved")
+    } else {
+        ("approval_rejected", "rejected")
+    };
+    append_coordinator_event_with_payload(
+        repo_root,
+        event,
+        task_id,
+        "approval",
+        status,
+        "info",
+        serde_json::json!({
+            "message": format!("Merge of task {} {} by {}", task_id, status, user),
+            "request_id": request_id,
+            "user": user,
+            "comment": comment,
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_reasons_cover_category_priority_and_paths() {
+        let policy = ApprovalPolicy {
+            categories: vec!["Security".into()],
+            priorities: vec!["p0".into()],
+            paths: vec!["infra/**".into()],
+        };
+        let task = json!({"id": "T1", "category": "security", "priority": "P0"});
+        let reasons = approval_reasons(&policy, &task, &["infra/main.tf".to_string()]);
+        assert_eq!(
+            reasons,
+            vec![
+                "category security",
+                "priority p0",
+                "path infra/main.tf (infra/**)"
+            ]
+        );
+        let other = json!({"id": "T2", "category": "docs", "priority": 2});
+        assert!(approval_reasons(&policy, &other, &["README.md".to_string()]).is_empty());
+
+        // A plain name is the root-level file, as in task scopes; `*.lock` matches at any depth.
+        let lockfiles = ApprovalPolicy {
+            paths: vec!["Cargo.lock".into()],
+            ..ApprovalPolicy::default()
+        };
+        let nested = ["crates/a/Cargo.lock".to_string()];
+        assert!(approval_reasons(&lockfiles, &other, &nested).is_empty());
+        assert_eq!(
+            approval_reasons(&lockfiles, &other, &["Cargo.lock".to_string()]),
+            vec!["path Cargo.lock (Cargo.lock)"]
+        );
+        let any_depth = ApprovalPolicy {
+            paths: vec!["*.lock".into()],
+            ..ApprovalPolicy::default()
+        };
+        assert_eq!(approval_reasons(&any_depth, &other, &nested).len(), 1);
+    }
+
+    #[test]
+    fn test_decisions_release_or_send_back_the_task() {
+        let request = InteractionRequest {
+            request_id: "approval-T1".into(),
+            prompt: "Approve?".into(),
+            kind: InteractionKind::ConfirmYesNo,
+            scope: None,
+        };
+        let mut registry = json!({"tasks": [
+            {"id": "T1", "state": "queued", "task_runtime": {"status": "phase_done"}}
+        ]});
+        let now = "2026-01-01T00:00:00Z";
+        coordinator_engine::apply_approval_request_in_registry(
+            &mut registry,
+            "T1",
+            &request,
+            &["priority p0".to_string()],
+            Some("abc123"),
+            now,
+        )
+        .unwrap();
+        assert_eq!(pending_approvals(&registry), vec!["T1"]);
+        assert_eq!(
+            pending_approval_requests(&registry),
+            vec![PendingApproval {
+                task_id: "T1".into(),
+                prompt: "Approve?".into(),
+                reasons: vec!["priority p0".into()],
+            }]
+        );
+        assert_eq!(registry["tasks"][0]["approval"]["head"], "abc123");
+        assert_eq!(
+            registry["tasks"][0]["task_runtime"]["status"],
+            "waiting_for_user"
+        );
+
+        let mut rejected = registry.clone();
+        coordinator_engine::apply_approval_decision_in_registry(
+            &mut rejected,
+            "T1",
+            false,
+            "alice",
+            Some("Split the migration"),
+            now,
+        )
+        .unwrap();
+        let task = &rejected["tasks"][0];
+        assert_eq!(task["state"], "changes_requested");
+        assert_eq!(task["review_comments"][0]["body"], "Split the migration");
+        assert_eq!(task_approval_status(task), Some("rejected"));
+
+        coordinator_engine::apply_approval_decision_in_registry(
+            &mut registry,
+            "T1",
+            true,
+            "alice",
+            None,
+            now,
+        )
+        .unwrap();
+        assert_eq!(registry["tasks"][0]["state"], "queued");
+        assert_eq!(
+            task_approval_status(&registry["tasks"][0]),
+            Some("approved")
+        );
+        // A decision needs a pending request.
+        assert!(coordinator_engine::apply_approval_decision_in_registry(
+            &mut registry,
+            "T1",
+            true,
+            "alice",
+            None,
+            now,
+        )
+        .is_err());
+
+        // The approval holds while the task stays queued and is voided once it leaves.
+        assert!(void_departed_approvals(&mut registry, now)
+            .unwrap()
+            .is_empty());
+        // Merging uses the approval up.
+        let mut merged = registry.clone();
+        merged["tasks"][0]["state"] = json!("merged");
+        assert!(void_departed_approvals(&mut merged, now)
+            .unwrap()
+            .is_empty());
+        registry["tasks"][0]["state"] = json!("blocked");
+        let voided = void_departed_approvals(&mut registry, now).unwrap();
+        assert_eq!(voided.len(), 1);
+        assert!(voided[0].1.contains("blocked"), "{:?}", voided);
+        assert_eq!(task_approval_status(&registry["tasks"][0]), Some("void"));
+    }
+}
@@ -22,6 +22,45 @@ pub struct StorageSyncArgs {
     pub direction: CoordinatorStorageTransfer,
 }
 
+pub struct MetricsArgs {
+    pub out: Option<std::path::PathBuf>,
+}
+
+pub struct ReportArgs {
+    pub run_id: Option<String>,
+    pub format: crate::coordinator::runs::ReportFormat,
+    pub out: Option<std::path::PathBuf>,
+}
+
+pub struct ReplayArgs {
+    /// `replay verify`: compare the replayed registry with the live one.
+    pub verify: bool,
+    pub until: Option<crate::coordinator::replay::ReplayUntil>,
+    pub out: Option<std::path::PathBuf>,
+}
+
+pub struct SimulateArgs {
+    pub profile: std::path::PathBuf,
+    /// JSON report destination.
+    pub out: Option<std::path::PathBuf>,
+}
+
+pub struct ApprovalArgs {
+    pub task_id: String,
+    pub comment: Option<String>,
+}
+
+pub struct TaskCommandArgs {
+    pub command: crate::coordinator::operator::TaskCommand,
+    pub comment: Option<String>,
+}
+
+pub struct TraceExportArgs {
+    pub run_id: Option<String>,
+    pub format: crate::coordinator::trace::TraceFormat,
+    pub out: Option<std::path::PathBuf>,
+}
+
 fn parse_flag_kv_pairs(
     args: &[String],
     usage: &str,
@@ -128,6 +167,179 @@ impl TryFrom<&[String]> for StorageSyncArgs {
     }
 }
 
+impl TryFrom<&[String]> for MetricsArgs {
+    type Error = MaccError;
+
+    fn try_from(args: &[String]) -> std::result::Result<Self, Self::Error> {
+        let usage = "macc coordinator metrics [--out <path>]";
+        let map = parse_flag_kv_pairs(args, usage, &["out"])?;
+        Ok(Self {
+            out: map.get("out").map(std::path::PathBuf::from),
+        })
+    }
+}
+
+impl TraceExportArgs {
+    /// `operands` are the words after the action (`export [run-id]`).
+    pub fn parse(operands: &[String], args: &[String]) -> Result<Self> {
+        let usage =
+            "macc coordinator trace export [run-id] --format <otlp-json|chrome> [--out <path>]";
+        match operands {
+            [verb, ..] if verb == "export" && operands.len() <= 2 => {}
+            _ => {
+                return Err(MaccError::Validation(format!(
+                    "Invalid trace command. Usage: {}",
+                    usage
+                )))
+            }
+        }
+        let map = parse_flag_kv_pairs(args, usage, &["format", "out", "run"])?;
+        let format = map
+            .get("format")
+            .map(|raw| raw.parse())
+            .transpose()?
+            .unwrap_or(crate::coordinator::trace::TraceFormat::OtlpJson);
+        Ok(Self {
+            run_id: operands.get(1).or_else(|| map.get("run")).cloned(),
+            format,
+            out: map.get("out").map(std::path::PathBuf::from),
+        })
+    }
+}
+
+impl ReportArgs {
+    /// `operands` is the optional run id.
+    pub fn parse(operands: &[String], args: &[String]) -> Result<Self> {
+        let usage = "macc coordinator report [run-id] [--format <md|json|html>] [--out <path>]";
+        if operands.len() > 1 {
+            return Err(MaccError::Validation(format!(
+                "Unexpected argument '{}'. Usage: {}",
+                operands[1], usage
+            )));
+        }
+        let map = parse_flag_kv_pairs(args, usage, &["format", "out"])?;
+        let format = map
+            .get("format")
+            .map(|raw| raw.parse())
+            .transpose()?
+            .unwrap_or(crate::coordinator::runs::ReportFormat::Markdown);
+        Ok(Self {
+            run_id: operands.first().cloned(),
+            format,
+            out: map.get("out").map(std::path::PathBuf::from),
+        })
+    }
+}
+
+impl ReplayArgs {
+    /// `operands` is empty or `verify`.
+    pub fn parse(operands: &[String], args: &[String]) -> Result<Self> {
+        let usage =
+            "macc coordinator replay [verify] [--until <timestamp|event-id>] [--out <path>]";
+        let verify = match operands {
+            [] => false,
+            [verb] if verb == "verify" => true,
+            _ => {
+                return Err(MaccError::Validation(format!(
+                    "Invalid replay command. Usage: {}",
+                    usage
+                )))
+            }
+        };
+        let map = parse_flag_kv_pairs(args, usage, &["until", "out"])?;
+        let until = map.get("until").map(|raw| raw.parse()).transpose()?;
+        if verify && until.is_some() {
+            return Err(MaccError::Validation(format!(
+                "replay verify compares the full event log with the live registry; drop --until. Usage: {}",
+                usage
+            )));
+        }
+        Ok(Self {
+            verify,
+            until,
+            out: map.get("out").map(std::path::PathBuf::from),
+        })
+    }
+}
+
+impl SimulateArgs {
+    pub fn parse(args: &[String]) -> Result<Self> {
+        let usage = "macc coordinator simulate --profile <sim.yaml> [--prd <prd.json>] [--out <report.json>]";
+        let map = parse_flag_kv_pairs(args, usage, &["profile", "out"])?;
+        let profile = map
+            .get("profile")
+            .ok_or_else(|| MaccError::Validation(format!("Missing --profile. Usage: {}", usage)))?;
+        Ok(Self {
+            profile: std::path::PathBuf::from(profile),
+            out: map.get("out").map(std::path::PathBuf::from),
+        })
+    }
+}
+
+impl ApprovalArgs {
+    /// `operands` is the task id; `action` is `approve` or `reject`.
+    pub fn parse(action: &str, operands: &[String], args: &[String]) -> Result<Self> {
+        let usage = format!("macc coordinator {} <task-id> [--comment <text>]", action);
+        let [task_id] = operands else {
+            return Err(MaccError::Validation(format!(
+                "Expected one task id. Usage: {}",
+                usage
+            )));
+        };
+        let map = parse_flag_kv_pairs(args, &usage, &["comment"])?;
+        Ok(Self {
+            task_id: task_id.clone(),
+            comment: map
+                .get("comment")
+                .map(|c| c.trim().to_string())
+                .filter(|c| !c.is_empty()),
+        })
+    }
+}
+
+impl TaskCommandArgs {
+    /// `operands` is the subcommand followed by the task id and its value, if any.
+    pub fn parse(operands: &[String], args: &[String]) -> Result<Self> {
+        use crate::coordinator::operator::TaskCommand;
+        let usage = "macc coordinator task list | show <task-id> | set-priority <task-id> <priority> | set-tool <task-id> <tool> | requeue|abandon|block|unblock <task-id> | add-dependency <task-id> <dependency-id> [--comment <text>]";
+        let words: Vec<&str> = operands.iter().map(String::as_str).collect();
+        let command = match words.as_slice() {
+            ["list"] => TaskCommand::List,
+            ["show", id] => TaskCommand::Show(id.to_string()),
+            ["set-priority", id, priority] => TaskCommand::SetPriority {
+                task_id: id.to_string(),
+                priority: priority.to_string(),
+            },
+            ["set-tool", id, tool] => TaskCommand::SetTool {
+                task_id: id.to_string(),
+                tool: tool.to_string(),
+            },
+            ["requeue", id] => TaskCommand::Requeue(id.to_string()),
+            ["abandon", id] => TaskCommand::Abandon(id.to_string()),
+            ["block", id] => TaskCommand::Block(id.to_string()),
+            ["unblock", id] => TaskCommand::Unblock(id.to_string()),
+            ["add-dependency", id, dependency] => TaskCommand::AddDependency {
+                task_id: id.to_string(),
+                dependency: dependency.to_string(),
+            },
+            _ => {
+                return Err(MaccError::Validation(format!(
+                    "Invalid task command. Usage: {}",
+                    usage
+                )))
+            }
+        };
+        let map = parse_flag_kv_pairs(args, usage, &["comment"])?;
+        Ok(Self {
+            command,
+            comment: map
+                .get("comment")
+                .map(|c| c.trim().to_string())
+                .filter(|c| !c.is_empty()),
+        })
+    }
+}
+
 pub fn parse_coordinator_extra_kv_args(extra_args: &[String]) -> Result<BTreeMap<String, String>> {
     if !extra_args.len().is_multiple_of(2) {
         return Err(MaccError::Validation(
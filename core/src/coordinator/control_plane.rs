@@ -3,9 +3,16 @@ use crate::coordinator::helpers::{
     count_pool_worktrees, find_reusable_worktree_native, now_iso_coordinator,
     recompute_resource_locks_from_tasks, set_registry_updated_at, write_worktree_prd_for_task,
 };
+use crate::coordinator::limits::{resolve_resource_limits, PerformerLimitGuard};
+use crate::coordinator::replay::{record_task_mutation, record_task_mutation_at, TaskMutation};
 use crate::coordinator::runtime::{CoordinatorJob, CoordinatorMergeJob, CoordinatorRunState};
+use crate::coordinator::throttle::ThrottleState as ToolThrottleState;
 use crate::coordinator::types::CoordinatorEnvConfig;
 use crate::coordinator::{engine as coordinator_engine, runtime as coordinator_runtime};
+use crate::performer::limits::RESOURCE_LIMIT_ERROR_CODE;
+use crate::performer::output::{final_text, usage_from_text, OutputFormat};
+use crate::performer::{invoke_tool, PerformerLimits, ToolInvocation};
+use crate::tool::ToolRuntimeConfig;
 use crate::{MaccError, Result};
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs::File;
@@ -221,6 +228,60 @@ pub fn sync_registry_from_prd_native(
         .cloned()
         .unwrap_or_default();
 
+    let before = prd_definitions(&registry);
+    merge_prd_definitions(&mut registry, &prd_tasks, &now_iso_coordinator());
+    set_registry_updated_at(&mut registry);
+    crate::coordinator::state::coordinator_state_registry_save(
+        repo_root,
+        &BTreeMap::new(),
+        &registry,
+    )?;
+    let definitions = prd_definitions(&registry);
+    if definitions != before {
+        crate::coordinator::replay::record_task_mutation(
+            repo_root,
+            "-",
+            &crate::coordinator::replay::TaskMutation::Synced { definitions },
+        );
+    }
+    if let Some(log) = logger {
+        let count = registry
+            .get("tasks")
+            .and_then(serde_json::Value::as_array)
+            .map(|v| v.len())
+            .unwrap_or(0);
+        let _ = log.note(format!("Registry synced from PRD (tasks={})", count));
+    }
+    Ok(())
+}
+
+/// Task fields owned by the PRD; the sync copies them onto the registry tasks.
+const PRD_DEFINITION_KEYS: [&str; 15] = [
+    "title",
+    "description",
+    "objective",
+    "result",
+    "steps",
+    "notes",
+    "category",
+    "priority",
+    "dependencies",
+    "exclusive_resources",
+    "base_branch",
+    "scope",
+    "project",
+    "resource_limits",
+    "merge_strategy",
+];
+
+/// Replaces the registry task list with `prd_tasks` in PRD order, keeping the coordinator
+/// state of tasks that already exist, the subtasks of decomposed tasks, and re-applying
+/// operator overrides.
+pub fn merge_prd_definitions(
+    registry: &mut serde_json::Value,
+    prd_tasks: &[serde_json::Value],
+    now: &str,
+) {
     if !registry
         .get("tasks")
         .map(serde_json::Value::is_array)
@@ -241,6 +302,10 @@ pub fn sync_registry_from_prd_native(
         }
     }
 
+    let prd_ids: HashSet<String> = prd_tasks
+        .iter()
+        .filter_map(crate::coordinator::prd::prd_task_id)
+        .collect();
     let mut merged = Vec::new();
     for prd_task in prd_tasks {
         let id = if let Some(v) = prd_task.get("id").and_then(serde_json::Value::as_str) {
@@ -253,61 +318,90 @@ pub fn sync_registry_from_prd_native(
         if id.is_empty() {
             continue;
         }
-        let mut task = by_id.remove(&id).unwrap_or_else(|| {
-            serde_json::json!({
-                "id": id,
-                "state": "todo",
-                "dependencies": [],
-                "exclusive_resources": [],
-                "task_runtime": {
-                    "status": "idle",
-                    "pid": null,
-                    "current_phase": null,
-                    "merge_result_pending": false,
-                    "merge_result_file": null
-                }
-            })
-        });
-
-        for key in [
-            "title",
-            "description",
-            "objective",
-            "result",
-            "steps",
-            "notes",
-            "category",
-            "priority",
-            "dependencies",
-            "exclusive_resources",
-            "base_branch",
-            "scope",
-        ] {
+        let mut task = by_id
+            .remove(&id)
+            .unwrap_or_else(|| coordinator_engine::new_registry_task(&id));
+
+        for key in PRD_DEFINITION_KEYS {
             if let Some(v) = prd_task.get(key) {
                 task[key] = v.clone();
             }
         }
+        crate::coordinator::operator::apply_operator_overrides(&mut task);
         coordinator_engine::ensure_runtime_object(&mut task);
-        task["updated_at"] = serde_json::Value::String(now_iso_coordinator());
+        task["updated_at"] = serde_json::Value::String(now.to_string());
+        // Subtasks of a decomposed task are not in the PRD: keep them after their parent.
+        let subtasks: Vec<String> = task
+            .get("subtasks")
+            .and_then(serde_json::Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(serde_json::Value::as_str)
+            .map(str::to_string)
+            .collect();
         merged.push(task);
+        merged.extend(
+            subtasks
+                .iter()
+                .filter(|id| !prd_ids.contains(*id))
+                .filter_map(|id| by_id.remove(id)),
+        );
     }
 
     registry["tasks"] = serde_json::Value::Array(merged);
-    recompute_resource_locks_from_tasks(&mut registry);
-    set_registry_updated_at(&mut registry);
-    crate::coordinator::state::coordinator_state_registry_save(
-        repo_root,
-        &BTreeMap::new(),
-        &registry,
+    recompute_resource_locks_from_tasks(registry);
+}
+
+/// Id and PRD-owned fields of every registry task, as recorded for replay.
+fn prd_definitions(registry: &serde_json::Value) -> Vec<serde_json::Value> {
+    registry
+        .get("tasks")
+        .and_then(serde_json::Value::as_array)
+        .into_iter()
+        .flatten()
+        .map(|task| {
+            let mut definition = serde_json::json!({"id": task["id"]});
+            for key in PRD_DEFINITION_KEYS {
+                if let Some(v) = task.get(key) {
+                    definition[key] = v.clone();
+                }
+            }
+            definition
+        })
+        .collect()
+}
+
+/// Applies a phase outcome with the engine FSM and records it for replay.
+#[allow(clippy::too_many_arguments)]
+fn apply_recorded_phase_outcome(
+    repo_root: &Path,
+    registry: &mut serde_json::Value,
+    task_id: &str,
+    mode: &'static str,
+    transition: coordinator_engine::PhaseTransition,
+    review_verdict: Option<coordinator_engine::ReviewVerdict>,
+    phase_error: Option<&str>,
+    now: &str,
+) -> Result<()> {
+    coordinator_engine::apply_phase_outcome_in_registry(
+        registry,
+        task_id,
+        mode,
+        transition,
+        review_verdict,
+        phase_error,
+        now,
     )?;
-    if let Some(log) = logger {
-        let count = registry
-            .get("tasks")
-            .and_then(serde_json::Value::as_array)
-            .map(|v| v.len())
-            .unwrap_or(0);
-        let _ = log.note(format!("Registry synced from PRD (tasks={})", count));
-    }
+    record_task_mutation_at(
+        repo_root,
+        task_id,
+        &TaskMutation::PhaseOutcome {
+            mode: mode.to_string(),
+            verdict: review_verdict,
+            error: phase_error.map(str::to_string),
+        },
+        now,
+    );
     Ok(())
 }
 
@@ -369,45 +463,47 @@ impl coordinator_runtime::PhaseExecutor for NativePhaseExecutor<'_> {
                 tool_json.display()
             )));
         }
-        let Some(runner_path) =
-            coordinator_runtime::resolve_phase_runner(self.repo_root, &worktree, &phase_tool)?
-        else {
-            return Ok(Err(format!(
-                "phase '{}' cannot run for task {}: missing runner for tool '{}'",
-                mode, task_id, phase_tool
-            )));
+        let runtime = match phase_runtime_config(self.repo_root, &tool_json, &phase_tool) {
+            Ok(runtime) => runtime,
+            Err(err) => {
+                return Ok(Err(format!(
+                    "phase '{}' cannot run for task {}: {}",
+                    mode, task_id, err
+                )))
+            }
         };
-        if !runner_path.exists() {
-            return Ok(Err(format!(
-                "phase '{}' cannot run for task {}: runner path not found {}",
-                mode,
-                task_id,
-                runner_path.display()
-            )));
-        }
         let prompt = coordinator_runtime::build_phase_prompt(mode, task_id, &phase_tool, task)?;
-        let prompt_dir = worktree.join(".macc").join("tmp");
-        std::fs::create_dir_all(&prompt_dir).map_err(|e| MaccError::Io {
-            path: prompt_dir.to_string_lossy().into(),
-            action: "create coordinator phase prompt directory".into(),
-            source: e,
-        })?;
-        let prompt_path = prompt_dir.join(format!(
-            "coordinator-phase-{}-{}.prompt.txt",
-            mode,
-            task_id.replace('/', "-")
-        ));
-        std::fs::write(&prompt_path, prompt).map_err(|e| MaccError::Io {
-            path: prompt_path.to_string_lossy().into(),
-            action: "write coordinator phase prompt".into(),
-            source: e,
-        })?;
         let events_file = self
             .repo_root
             .join(".macc")
             .join("log")
             .join("coordinator")
             .join("events.jsonl");
+        let output_format = OutputFormat::of(&runtime.performer);
+        let activity_events = crate::performer::EventSink::new(
+            Some(events_file),
+            std::env::var("COORDINATOR_RUN_ID").ok(),
+            format!("coordinator-phase:{}:{}:{}", mode, phase_tool, task_id),
+            task_id,
+            mode,
+        )
+        .with_tool(phase_tool.clone());
+        let task_dir = crate::monorepo::task_project_dir(&worktree, task);
+        let lease_ttl_seconds = PerformerLimits::from_env().session_lease_ttl_seconds;
+        let coordinator =
+            crate::config::load_effective_config(&crate::ProjectPaths::from_root(self.repo_root))
+                .ok()
+                .and_then(|config| config.automation.coordinator);
+        let limits = PerformerLimitGuard::prepare(
+            task_id,
+            resolve_resource_limits(coordinator.as_ref(), &runtime.id, task),
+        );
+        limits.report_downgrade(self.repo_root, task_id, mode);
+        let pool_env = coordinator
+            .as_ref()
+            .and_then(|c| c.worktree.as_ref())
+            .map(|pool| crate::coordinator::worktree_pool::pool_env(self.repo_root, pool))
+            .unwrap_or_default();
         let attempts = max_attempts.max(1);
         if let Some(log) = self.logger {
             let _ = log.note(format!(
@@ -417,73 +513,84 @@ impl coordinator_runtime::PhaseExecutor for NativePhaseExecutor<'_> {
         }
         let mut last_reason = String::new();
         for attempt in 1..=attempts {
-            let output = std::process::Command::new(&runner_path)
-                .current_dir(&worktree)
-                .env(
-                    "COORD_EVENTS_FILE",
-                    events_file.to_string_lossy().to_string(),
-                )
-                .env(
-                    "MACC_EVENT_SOURCE",
-                    format!(
-                        "coordinator-phase:{}:{}:{}:{}",
-                        mode,
-                        phase_tool,
-                        task_id,
-                        chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
-                    ),
-                )
-                .env("MACC_EVENT_TASK_ID", task_id)
-                .arg("--prompt-file")
-                .arg(&prompt_path)
-                .arg("--tool-json")
-                .arg(&tool_json)
-                .arg("--repo")
-                .arg(self.repo_root)
-                .arg("--worktree")
-                .arg(&worktree)
-                .arg("--task-id")
-                .arg(task_id)
-                .arg("--attempt")
-                .arg(attempt.to_string())
-                .arg("--max-attempts")
-                .arg(attempts.to_string())
-                .output();
-            let Ok(out) = output else {
-                last_reason = format!(
-                    "phase '{}' failed to execute runner '{}'",
-                    mode,
-                    runner_path.display()
-                );
-                continue;
+            let invocation = ToolInvocation {
+                tool_id: &runtime.id,
+                spec: &runtime.performer,
+                repo: self.repo_root,
+                worktree: &worktree,
+                cwd: &task_dir,
+                task_id,
+                attempt,
+                lease_ttl_seconds,
+                limits: Some(&limits),
+                env: Some(&pool_env),
             };
-            let combined_output = format!(
-                "{}\n{}",
-                String::from_utf8_lossy(&out.stdout),
-                String::from_utf8_lossy(&out.stderr)
-            );
-            if out.status.success() {
-                let _ = std::fs::remove_file(&prompt_path);
+            let cgroup_before = limits.resources().cgroup_events();
+            let result = invoke_tool(&invocation, &prompt, &mut |line| {
+                activity_events.emit_output_activity(output_format, line);
+            });
+            let call = match result {
+                Ok(call) => call,
+                Err(err) => {
+                    last_reason = format!(
+                        "phase '{}' failed to run tool '{}' for task {}: {}",
+                        mode, runtime.id, task_id, err
+                    );
+                    continue;
+                }
+            };
+            if let Some(activity) = runtime
+                .performer
+                .usage
+                .as_ref()
+                .and_then(|usage| usage_from_text(usage, &call.output))
+            {
+                activity_events.emit_activity(&activity);
+            }
+            // Structured formats are reduced to the tool's final answer (e.g. the review verdict).
+            let output = final_text(output_format, &call.output);
+            if call.success {
                 if let Some(log) = self.logger {
                     let _ = log.note(format!(
                         "- Phase {} done task={} attempt={}",
                         mode, task_id, attempt
                     ));
                 }
-                return Ok(Ok(combined_output));
+                return Ok(Ok(output));
+            }
+            if let Some(violation) =
+                limits
+                    .resources()
+                    .detect(&cgroup_before, call.signal, call.exit_code, &call.output)
+            {
+                let mut payload = violation.payload();
+                payload["attempt"] = serde_json::json!(attempt);
+                activity_events.emit("resource_limit_exceeded", "failed", payload);
+                // The next attempt would run under the same limits.
+                last_reason = format!(
+                    "phase '{}' failed for task {} on attempt {}/{}: {} ({})",
+                    mode,
+                    task_id,
+                    attempt,
+                    attempts,
+                    violation.message(),
+                    RESOURCE_LIMIT_ERROR_CODE
+                );
+                break;
             }
             last_reason = format!(
-                "phase '{}' failed for task {} on attempt {}/{}: status={} stdout=\"{}\" stderr=\"{}\"",
+                "phase '{}' failed for task {} on attempt {}/{}: status={} output=\"{}\"",
                 mode,
                 task_id,
                 attempt,
                 attempts,
-                out.status,
-                coordinator_runtime::summarize_output(&String::from_utf8_lossy(&out.stdout)),
-                coordinator_runtime::summarize_output(&String::from_utf8_lossy(&out.stderr))
+                call.exit_code
+                    .map(|code| code.to_string())
+                    .or_else(|| call.signal.map(|signal| format!("signal {}", signal)))
+                    .unwrap_or_else(|| "unknown".to_string()),
+                coordinator_runtime::summarize_output(&output)
             );
         }
-        let _ = std::fs::remove_file(&prompt_path);
         if let Some(log) = self.logger {
             let _ = log.note(format!(
                 "- Phase {} failed task={} reason={}",
@@ -494,6 +601,51 @@ impl coordinator_runtime::PhaseExecutor for NativePhaseExecutor<'_> {
     }
 }
 
+/// The worktree's `tool.json` when it describes `tool`, otherwise the ToolSpec of `tool`
+/// (a coordinator tool override).
+fn phase_runtime_config(
+    repo_root: &Path,
+    tool_json: &Path,
+    tool: &str,
+) -> Result<ToolRuntimeConfig> {
+    let raw = std::fs::read_to_string(tool_json).map_err(|e| MaccError::Io {
+        path: tool_json.to_string_lossy().into(),
+        action: "read tool.json for coordinator phase".into(),
+        source: e,
+    })?;
+    let runtime: ToolRuntimeConfig = serde_json::from_str(&raw).map_err(|e| {
+        MaccError::Validation(format!("invalid tool.json {}: {}", tool_json.display(), e))
+    })?;
+    let mut runtime = if runtime.id == tool {
+        runtime
+    } else {
+        crate::tool_runtime_config(repo_root, tool)?
+    };
+    // Phases run the same AI tool as the dev loop, so they get the same sandbox.
+    if let Some(sandbox) = crate::performer::coordinator_sandbox_override(repo_root, &runtime.id) {
+        runtime.performer.sandbox = Some(sandbox);
+    }
+    Ok(runtime)
+}
+
+/// Whether `task`'s tool is configured to run sandboxed. The merge-fix hook works in the
+/// repository root, which no sandbox allows writing, so it is skipped for such tasks.
+fn task_tool_sandboxed(repo_root: &Path, task: &serde_json::Value) -> bool {
+    let tool = task
+        .get("tool")
+        .and_then(serde_json::Value::as_str)
+        .unwrap_or_default();
+    task.get("worktree")
+        .and_then(|w| w.get("worktree_path"))
+        .and_then(serde_json::Value::as_str)
+        .map(|path| Path::new(path).join(".macc").join("tool.json"))
+        .and_then(|tool_json| phase_runtime_config(repo_root, &tool_json, tool).ok())
+        .map(|runtime| runtime.performer.sandbox.is_some())
+        .unwrap_or_else(|| {
+            crate::performer::coordinator_sandbox_override(repo_root, tool).is_some()
+        })
+}
+
 pub fn run_phase_for_task_native(
     repo_root: &Path,
     task: &serde_json::Value,
@@ -535,6 +687,10 @@ pub async fn advance_tasks_native(
     let mut progressed = false;
     let blocked_merge: Option<(String, String)> = None;
     let now = now_iso_coordinator();
+    // Merge failures and other moves out of `queued` leave approvals behind; void them before
+    // the task can come back to the merge queue.
+    let voided_approvals =
+        crate::coordinator::approval::void_departed_approvals(&mut registry, &now)?;
     let active_merge_ids = state
         .active_merge_jobs
         .keys()
@@ -566,7 +722,58 @@ pub async fn advance_tasks_native(
                             task_id
                         ))
                     })?;
+                // With a forge, `pr_open` waits for the pull request review before integrating.
+                if mode == "integrate" {
+                    if let Some(forge) = state.forge.clone() {
+                        let gate = forge_review_gate(
+                            repo_root,
+                            &forge,
+                            &mut state.forge_polled_at,
+                            &task_snapshot,
+                        )
+                        .await;
+                        match apply_forge_gate(
+                            repo_root,
+                            &mut registry,
+                            &task_id,
+                            &forge,
+                            gate,
+                            transition,
+                            &now,
+                            logger,
+                        )? {
+                            ForgeGateOutcome::Wait => continue,
+                            ForgeGateOutcome::Handled => {
+                                progressed = true;
+                                continue;
+                            }
+                            ForgeGateOutcome::Proceed => {}
+                        }
+                    }
+                }
+                // Review follows dev and integrate follows fix: both see the branch at its
+                // largest, so the small-PR limits are checked there.
+                if let Some(size_guard) = state
+                    .size_guard
+                    .as_ref()
+                    .filter(|_| mode == "review" || mode == "integrate")
+                {
+                    if apply_size_guard(
+                        repo_root,
+                        &mut registry,
+                        size_guard,
+                        &task_snapshot,
+                        &task_id,
+                        &now,
+                        logger,
+                    ) {
+                        progressed = true;
+                        continue;
+                    }
+                }
                 let executor = NativePhaseExecutor { repo_root, logger };
+                let phase_started = std::time::Instant::now();
+                let phase_failed;
                 if mode == "review" {
                     match coordinator_runtime::run_review_phase(
                         &executor,
@@ -575,6 +782,7 @@ pub async fn advance_tasks_native(
                         phase_runner_max_attempts,
                     )? {
                         Ok(verdict) => {
+                            phase_failed = false;
                             let verdict_status = match verdict {
                                 coordinator_engine::ReviewVerdict::Ok => "ok",
                                 coordinator_engine::ReviewVerdict::ChangesRequested => {
@@ -589,7 +797,8 @@ pub async fn advance_tasks_native(
                                 verdict_status,
                                 &format!("Review verdict for task {}: {}", task_id, verdict_status),
                             )?;
-                            coordinator_engine::apply_phase_outcome_in_registry(
+                            apply_recorded_phase_outcome(
+                                repo_root,
                                 &mut registry,
                                 &task_id,
                                 mode,
@@ -599,15 +808,19 @@ pub async fn advance_tasks_native(
                                 &now,
                             )?
                         }
-                        Err(reason) => coordinator_engine::apply_phase_outcome_in_registry(
-                            &mut registry,
-                            &task_id,
-                            mode,
-                            transition,
-                            None,
-                            Some(&reason),
-                            &now,
-                        )?,
+                        Err(reason) => {
+                            phase_failed = true;
+                            apply_recorded_phase_outcome(
+                                repo_root,
+                                &mut registry,
+                                &task_id,
+                                mode,
+                                transition,
+                                None,
+                                Some(&reason),
+                                &now,
+                            )?
+                        }
                     }
                 } else {
                     match coordinator_runtime::run_phase(
@@ -617,26 +830,41 @@ pub async fn advance_tasks_native(
                         coordinator_tool_override,
                         phase_runner_max_attempts,
                     )? {
-                        Ok(_) => coordinator_engine::apply_phase_outcome_in_registry(
-                            &mut registry,
-                            &task_id,
-                            mode,
-                            transition,
-                            None,
-                            None,
-                            &now,
-                        )?,
-                        Err(reason) => coordinator_engine::apply_phase_outcome_in_registry(
-                            &mut registry,
-                            &task_id,
-                            mode,
-                            transition,
-                            None,
-                            Some(&reason),
-                            &now,
-                        )?,
+                        Ok(_) => {
+                            phase_failed = false;
+                            apply_recorded_phase_outcome(
+                                repo_root,
+                                &mut registry,
+                                &task_id,
+                                mode,
+                                transition,
+                                None,
+                                None,
+                                &now,
+                            )?
+                        }
+                        Err(reason) => {
+                            phase_failed = true;
+                            apply_recorded_phase_outcome(
+                                repo_root,
+                                &mut registry,
+                                &task_id,
+                                mode,
+                                transition,
+                                None,
+                                Some(&reason),
+                                &now,
+                            )?
+                        }
                     }
                 }
+                let _ = crate::coordinator::metrics::record_phase_duration(
+                    repo_root,
+                    &task_id,
+                    mode,
+                    if phase_failed { "failed" } else { "done" },
+                    phase_started.elapsed().as_secs_f64(),
+                );
                 progressed = true;
             }
             coordinator_engine::AdvanceTaskAction::QueueMerge {
@@ -644,6 +872,55 @@ pub async fn advance_tasks_native(
                 branch,
                 base,
             } => {
+                if let Some(policy) = &state.approval {
+                    match crate::coordinator::approval::approval_gate(
+                        repo_root,
+                        policy,
+                        &mut registry,
+                        &task_id,
+                        &branch,
+                        &base,
+                        &now,
+                    )? {
+                        crate::coordinator::approval::ApprovalGate::Proceed => {}
+                        crate::coordinator::approval::ApprovalGate::Waiting => continue,
+                        crate::coordinator::approval::ApprovalGate::Requested {
+                            request,
+                            reasons,
+                        } => {
+                            let waiting =
+                                crate::coordinator::approval::waiting_for_user(&request, &reasons);
+                            let _ =
+                                crate::coordinator::helpers::append_coordinator_event_with_payload(
+                                    repo_root,
+                                    "approval_requested",
+                                    &task_id,
+                                    "approval",
+                                    "waiting_for_user",
+                                    "warning",
+                                    serde_json::json!({
+                                        "message": format!(
+                                            "Task {} waits for merge approval ({}): macc coordinator approve|reject {}",
+                                            task_id,
+                                            reasons.join(", "),
+                                            task_id
+                                        ),
+                                        "request": request,
+                                        "waiting": waiting,
+                                    }),
+                                );
+                            if let Some(log) = logger {
+                                let _ = log.note(format!(
+                                    "- Approval requested task={} reasons={}",
+                                    task_id,
+                                    reasons.join("; ")
+                                ));
+                            }
+                            progressed = true;
+                            continue;
+                        }
+                    }
+                }
                 if let Some(log) = logger {
                     let _ = log.note(format!(
                         "- Merge start task={} branch={} base={}",
@@ -654,17 +931,55 @@ pub async fn advance_tasks_native(
                 let task_for_worker = task_id.clone();
                 let branch_for_worker = branch.clone();
                 let base_for_worker = base.clone();
+                let task_entry = registry
+                    .get("tasks")
+                    .and_then(serde_json::Value::as_array)
+                    .and_then(|tasks| {
+                        tasks.iter().find(|t| {
+                            t.get("id").and_then(serde_json::Value::as_str)
+                                == Some(task_id.as_str())
+                        })
+                    })
+                    .cloned()
+                    .unwrap_or_default();
+                // Tasks with a forge pull request merge on the forge; others merge locally.
+                let forge_pr = state.forge.clone().and_then(|forge| {
+                    crate::coordinator::forge::task_pull_request_number(&task_entry)
+                        .map(|number| (forge, number))
+                });
+                let sandboxed = task_tool_sandboxed(repo_root, &task_entry);
+                let merge_options = coordinator_runtime::TaskMergeOptions::resolve(
+                    state.merge.as_ref(),
+                    &task_entry,
+                )
+                .map(|options| coordinator_runtime::TaskMergeOptions {
+                    allow_ai_fix: !sandboxed,
+                    ..options
+                });
                 coordinator_runtime::spawn_merge_job(
                     &task_id,
                     &state.merge_event_tx,
                     &mut state.merge_join_set,
                     resolve_merge_timeout_seconds(),
                     move || {
+                        if let Some((forge, number)) = forge_pr {
+                            return crate::coordinator::forge::merge_task_pull_request(
+                                &forge,
+                                &repo,
+                                number,
+                                &base_for_worker,
+                            );
+                        }
+                        let merge_options = match merge_options {
+                            Ok(options) => options,
+                            Err(reason) => return Ok(Err(reason)),
+                        };
                         coordinator_runtime::merge_task_with_policy_native(
                             &repo,
                             &task_for_worker,
                             &branch_for_worker,
                             &base_for_worker,
+                            &merge_options,
                             |event_type, task_id, phase, status, message, severity| {
                                 let _ = append_coordinator_event_with_severity(
                                     &repo, event_type, task_id, phase, status, message, severity,
@@ -687,17 +1002,494 @@ pub async fn advance_tasks_native(
             }
         }
     }
-    recompute_resource_locks_from_tasks(&mut registry);
-    set_registry_updated_at(&mut registry);
-    crate::coordinator::state::coordinator_state_registry_save(
-        repo_root,
-        &BTreeMap::new(),
-        &registry,
-    )?;
-    Ok(coordinator_engine::AdvanceResult {
-        progressed,
-        blocked_merge,
-    })
+    if settle_tracking_tasks(repo_root, &mut registry, &now, logger) {
+        progressed = true;
+    }
+    // Tasks blocked this cycle are decomposed before the cycle counts them.
+    if let Some(decompose) = state.decompose.clone() {
+        if decompose_blocked_tasks(
+            repo_root,
+            &mut registry,
+            &decompose,
+            coordinator_tool_override,
+            phase_runner_max_attempts,
+            &now,
+            logger,
+        )? {
+            progressed = true;
+        }
+    }
+    // Phases run for minutes: keep operator changes made since the registry was loaded.
+    let latest =
+        crate::coordinator::state::coordinator_state_registry_load(repo_root, &BTreeMap::new())?;
+    crate::coordinator::operator::keep_operator_updates(&mut registry, &latest);
+    recompute_resource_locks_from_tasks(&mut registry);
+    set_registry_updated_at(&mut registry);
+    crate::coordinator::state::coordinator_state_registry_save(
+        repo_root,
+        &BTreeMap::new(),
+        &registry,
+    )?;
+    for (task_id, reason) in &voided_approvals {
+        crate::coordinator::approval::record_voided_approval(repo_root, task_id, reason, &now);
+    }
+    Ok(coordinator_engine::AdvanceResult {
+        progressed,
+        blocked_merge,
+    })
+}
+
+/// Marks tracking tasks merged once all their subtasks have merged.
+fn settle_tracking_tasks(
+    repo_root: &Path,
+    registry: &mut serde_json::Value,
+    now: &str,
+    logger: Option<&dyn CoordinatorLog>,
+) -> bool {
+    let mut settled = false;
+    for task_id in coordinator_engine::tracking_tasks_done(registry) {
+        if coordinator_engine::apply_subtasks_merged_in_registry(registry, &task_id, now).is_err() {
+            continue;
+        }
+        record_task_mutation_at(repo_root, &task_id, &TaskMutation::SubtasksMerged, now);
+        let msg = format!("task {} merged: all subtasks merged", task_id);
+        let _ = append_coordinator_event_with_severity(
+            repo_root,
+            "subtasks_merged",
+            &task_id,
+            "integrate",
+            "merged",
+            &msg,
+            "info",
+        );
+        if let Some(log) = logger {
+            let _ = log.note(format!("- {}", msg));
+        }
+        settled = true;
+    }
+    settled
+}
+
+/// Asks the coordinator tool to split each eligible blocked task into subtasks. A reply that
+/// fails the schema or PRD validation is recorded and leaves the task blocked.
+fn decompose_blocked_tasks(
+    repo_root: &Path,
+    registry: &mut serde_json::Value,
+    config: &crate::config::DecomposeConfig,
+    coordinator_tool_override: Option<&str>,
+    max_attempts: usize,
+    now: &str,
+    logger: Option<&dyn CoordinatorLog>,
+) -> Result<bool> {
+    use crate::coordinator::decompose;
+
+    let candidates: Vec<(serde_json::Value, &'static str)> = registry
+        .get("tasks")
+        .and_then(serde_json::Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|task| {
+            decompose::decomposition_trigger(task, config).map(|trigger| (task.clone(), trigger))
+        })
+        .collect();
+    let mut decomposed = false;
+    for (task, trigger) in candidates {
+        let task_id = task
+            .get("id")
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        let request = decompose::decomposition_request(&task, config, trigger);
+        let executor = NativePhaseExecutor { repo_root, logger };
+        let outcome = match coordinator_runtime::run_phase(
+            &executor,
+            &request,
+            "decompose",
+            coordinator_tool_override,
+            max_attempts,
+        )? {
+            Ok(output) => {
+                decompose::parse_reply(&output, config.max_subtasks()).and_then(|decomposition| {
+                    let existing_ids = registry
+                        .get("tasks")
+                        .and_then(serde_json::Value::as_array)
+                        .into_iter()
+                        .flatten()
+                        .filter_map(crate::coordinator::prd::prd_task_id)
+                        .collect();
+                    decompose::subtask_definitions(&task, &decomposition, &existing_ids)
+                })
+            }
+            Err(reason) => Err(reason),
+        };
+        match outcome {
+            Ok(subtasks) => {
+                coordinator_engine::apply_decomposition_in_registry(
+                    registry, &task_id, &subtasks, now,
+                )?;
+                record_task_mutation_at(
+                    repo_root,
+                    &task_id,
+                    &TaskMutation::Decomposed {
+                        subtasks: subtasks.clone(),
+                    },
+                    now,
+                );
+                let ids: Vec<&str> = subtasks
+                    .iter()
+                    .filter_map(|s| s.get("id").and_then(serde_json::Value::as_str))
+                    .collect();
+                let msg = format!(
+                    "task {} ({}) decomposed into {}",
+                    task_id,
+                    trigger,
+                    ids.join(", ")
+                );
+                let _ = crate::coordinator::helpers::append_coordinator_event_with_payload(
+                    repo_root,
+                    "task_decomposed",
+                    &task_id,
+                    "decompose",
+                    "done",
+                    "info",
+                    serde_json::json!({
+                        "message": msg,
+                        "trigger": trigger,
+                        "subtasks": ids,
+                    }),
+                );
+                if let Some(log) = logger {
+                    let _ = log.note(format!("- {}", msg));
+                }
+                decomposed = true;
+            }
+            Err(error) => {
+                coordinator_engine::apply_decomposition_failure_in_registry(
+                    registry, &task_id, &error, now,
+                )?;
+                record_task_mutation_at(
+                    repo_root,
+                    &task_id,
+                    &TaskMutation::DecompositionFailed {
+                        error: error.clone(),
+                    },
+                    now,
+                );
+                let msg = format!("task {} decomposition failed: {}", task_id, error);
+                let _ = append_coordinator_event_with_severity(
+                    repo_root,
+                    "task_decomposition_failed",
+                    &task_id,
+                    "decompose",
+                    "failed",
+                    &msg,
+                    "warning",
+                );
+                if let Some(log) = logger {
+                    let _ = log.note(format!("- {}", msg));
+                }
+            }
+        }
+    }
+    Ok(decomposed)
+}
+
+/// Runs the size guard on a task about to enter review or integrate. Returns true when the
+/// task was sent back or blocked; a branch that cannot be measured is let through.
+fn apply_size_guard(
+    repo_root: &Path,
+    registry: &mut serde_json::Value,
+    config: &crate::config::SizeGuardConfig,
+    task: &serde_json::Value,
+    task_id: &str,
+    now: &str,
+    logger: Option<&dyn CoordinatorLog>,
+) -> bool {
+    let check = match crate::coordinator::size_guard::check_task(repo_root, config, task) {
+        Ok(Some(check)) => check,
+        Ok(None) => return false,
+        Err(err) => {
+            if let Some(log) = logger {
+                let _ = log.note(format!(
+                    "- Size guard skipped task={} error={}",
+                    task_id, err
+                ));
+            }
+            return false;
+        }
+    };
+    if let Err(err) =
+        coordinator_engine::apply_size_check_in_registry(registry, task_id, &check, now)
+    {
+        tracing::warn!("size guard could not update task {}: {}", task_id, err);
+        if let Some(log) = logger {
+            let _ = log.note(format!(
+                "- Size guard skipped task={} error={}",
+                task_id, err
+            ));
+        }
+        return false;
+    }
+    record_task_mutation_at(
+        repo_root,
+        task_id,
+        &TaskMutation::SizeChecked {
+            check: check.clone(),
+        },
+        now,
+    );
+    if check.violations.is_empty() {
+        return false;
+    }
+    let outcome = if check.block {
+        "blocked"
+    } else {
+        "changes_requested"
+    };
+    let msg = format!(
+        "task {} exceeds size limits: {}; {}",
+        task_id,
+        check.violations.join("; "),
+        outcome
+    );
+    let _ = crate::coordinator::helpers::append_coordinator_event_with_payload(
+        repo_root,
+        "task_size_exceeded",
+        task_id,
+        "size_guard",
+        outcome,
+        "warning",
+        serde_json::json!({
+            "message": msg,
+            "stats": check.stats,
+            "violations": check.violations,
+        }),
+    );
+    if let Some(log) = logger {
+        let _ = log.note(format!("- {}", msg));
+    }
+    true
+}
+
+enum ForgeGate {
+    Wait,
+    Proceed,
+    Published {
+        pr: crate::coordinator::forge::PullRequest,
+        head: String,
+        opened: bool,
+    },
+    ChangesRequested(Vec<crate::coordinator::forge::ReviewComment>),
+    Closed(u64),
+}
+
+enum ForgeGateOutcome {
+    /// Nothing to do this cycle (review pending or poll not due).
+    Wait,
+    /// The registry changed without running the integrate phase.
+    Handled,
+    /// Approved (or already merged on the forge): run the integrate phase.
+    Proceed,
+}
+
+/// Publishes the task branch when the forge has not seen its head yet, otherwise polls the
+/// pull request review (at most once per `poll_seconds`). Runs on the blocking pool.
+async fn forge_review_gate(
+    repo_root: &Path,
+    forge: &crate::coordinator::forge::Forge,
+    polled_at: &mut HashMap<String, Instant>,
+    task: &serde_json::Value,
+) -> Result<ForgeGate> {
+    use crate::coordinator::forge::{self, ReviewDecision};
+
+    let task_id = task
+        .get("id")
+        .and_then(serde_json::Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    let needs_publish = forge::task_needs_publish(repo_root, task);
+    if !needs_publish
+        && polled_at
+            .get(&task_id)
+            .map(|last| last.elapsed() < forge.poll_interval)
+            .unwrap_or(false)
+    {
+        return Ok(ForgeGate::Wait);
+    }
+    polled_at.insert(task_id, Instant::now());
+    let forge = forge.clone();
+    let repo = repo_root.to_path_buf();
+    let task = task.clone();
+    tokio::task::spawn_blocking(move || {
+        let number = forge::task_pull_request_number(&task);
+        if needs_publish {
+            let (pr, head) = forge::publish_task(&forge, &repo, &task)?;
+            return Ok(ForgeGate::Published {
+                pr,
+                head,
+                opened: number.is_none(),
+            });
+        }
+        let number = number.unwrap_or_default();
+        let since = task
+            .get("forge")
+            .and_then(|f| f.get("pushed_at"))
+            .and_then(serde_json::Value::as_str);
+        Ok(match forge.backend.review_status(number)?.decision(since) {
+            ReviewDecision::Pending => ForgeGate::Wait,
+            ReviewDecision::Approved | ReviewDecision::Merged => ForgeGate::Proceed,
+            ReviewDecision::ChangesRequested(comments) => ForgeGate::ChangesRequested(comments),
+            ReviewDecision::Closed => ForgeGate::Closed(number),
+        })
+    })
+    .await
+    .map_err(|e| MaccError::Validation(format!("Forge worker failed: {}", e)))?
+}
+
+#[allow(clippy::too_many_arguments)]
+fn apply_forge_gate(
+    repo_root: &Path,
+    registry: &mut serde_json::Value,
+    task_id: &str,
+    forge: &crate::coordinator::forge::Forge,
+    gate: Result<ForgeGate>,
+    transition: coordinator_engine::PhaseTransition,
+    now: &str,
+    logger: Option<&dyn CoordinatorLog>,
+) -> Result<ForgeGateOutcome> {
+    let gate = match gate {
+        Ok(gate) => gate,
+        Err(err) => {
+            // Forge outages are retried on the next poll instead of failing the task.
+            let message = format!("Forge {} request failed: {}", forge.backend.kind(), err);
+            let _ = append_coordinator_event_with_severity(
+                repo_root,
+                "forge_error",
+                task_id,
+                "pr",
+                "failed",
+                &message,
+                "warning",
+            );
+            if let Some(log) = logger {
+                let _ = log.note(format!("- {} task={}", message, task_id));
+            }
+            return Ok(ForgeGateOutcome::Wait);
+        }
+    };
+    match gate {
+        ForgeGate::Wait => Ok(ForgeGateOutcome::Wait),
+        ForgeGate::Proceed => {
+            let _ = append_coordinator_event(
+                repo_root,
+                "forge_approved",
+                task_id,
+                "pr",
+                "done",
+                &format!("Pull request approved for task {}", task_id),
+            );
+            Ok(ForgeGateOutcome::Proceed)
+        }
+        ForgeGate::Published { pr, head, opened } => {
+            coordinator_engine::apply_forge_publish_in_registry(
+                registry,
+                task_id,
+                forge.backend.kind(),
+                &pr,
+                &head,
+                now,
+            )?;
+            record_task_mutation_at(
+                repo_root,
+                task_id,
+                &TaskMutation::ForgePublished {
+                    forge: forge.backend.kind().to_string(),
+                    pr: pr.clone(),
+                    pushed_head: head.clone(),
+                },
+                now,
+            );
+            let event_type = if opened {
+                "forge_pr_opened"
+            } else {
+                "forge_pr_updated"
+            };
+            let _ = crate::coordinator::helpers::append_coordinator_event_with_payload(
+                repo_root,
+                event_type,
+                task_id,
+                "pr",
+                "started",
+                "info",
+                serde_json::json!({
+                    "message": format!("Pull request #{} {}", pr.number, pr.url),
+                    "number": pr.number,
+                    "url": pr.url,
+                    "head": head,
+                }),
+            );
+            if let Some(log) = logger {
+                let _ = log.note(format!(
+                    "- Forge {} task={} pr={}",
+                    event_type, task_id, pr.url
+                ));
+            }
+            Ok(ForgeGateOutcome::Handled)
+        }
+        ForgeGate::ChangesRequested(comments) => {
+            coordinator_engine::apply_forge_changes_requested_in_registry(
+                registry, task_id, &comments, now,
+            )?;
+            record_task_mutation_at(
+                repo_root,
+                task_id,
+                &TaskMutation::ForgeChangesRequested {
+                    comments: comments.clone(),
+                },
+                now,
+            );
+            let _ = crate::coordinator::helpers::append_coordinator_event_with_payload(
+                repo_root,
+                "forge_changes_requested",
+                task_id,
+                "pr",
+                "changes_requested",
+                "info",
+                serde_json::json!({
+                    "message": format!(
+                        "Changes requested on the pull request of task {} ({} comment(s))",
+                        task_id,
+                        comments.len()
+                    ),
+                    "comments": comments.len(),
+                }),
+            );
+            Ok(ForgeGateOutcome::Handled)
+        }
+        ForgeGate::Closed(number) => {
+            let reason = format!("pull request #{} was closed without merging", number);
+            apply_recorded_phase_outcome(
+                repo_root,
+                registry,
+                task_id,
+                "integrate",
+                transition,
+                None,
+                Some(&reason),
+                now,
+            )?;
+            let _ = append_coordinator_event_with_severity(
+                repo_root,
+                "forge_pr_closed",
+                task_id,
+                "pr",
+                "failed",
+                &reason,
+                "blocking",
+            );
+            Ok(ForgeGateOutcome::Handled)
+        }
+    }
 }
 
 pub async fn monitor_active_jobs_native(
@@ -723,24 +1515,26 @@ pub async fn monitor_active_jobs_native(
                     repo_root,
                     &BTreeMap::new(),
                 )?;
+                let completion_input = coordinator_engine::JobCompletionInput {
+                    success: evt.success,
+                    attempt: job.attempt,
+                    max_attempts: max_attempts.max(1),
+                    timed_out: evt.timed_out,
+                    phase_timeout_seconds,
+                    elapsed_seconds: job.started_at.elapsed().as_secs(),
+                    status_text: evt.status_text.clone(),
+                    error_code: evt.error_code.clone(),
+                    error_origin: evt.error_origin.clone(),
+                    error_message: evt.error_message.clone(),
+                    auto_retry_error_codes: retry_codes.clone(),
+                    auto_retry_max: retry_max,
+                };
+                let completed_at = now_iso_coordinator();
                 let completion = coordinator_engine::apply_job_completion_in_registry(
                     &mut registry,
                     &evt.task_id,
-                    &coordinator_engine::JobCompletionInput {
-                        success: evt.success,
-                        attempt: job.attempt,
-                        max_attempts: max_attempts.max(1),
-                        timed_out: evt.timed_out,
-                        phase_timeout_seconds,
-                        elapsed_seconds: job.started_at.elapsed().as_secs(),
-                        status_text: evt.status_text.clone(),
-                        error_code: evt.error_code.clone(),
-                        error_origin: evt.error_origin.clone(),
-                        error_message: evt.error_message.clone(),
-                        auto_retry_error_codes: retry_codes.clone(),
-                        auto_retry_max: retry_max,
-                    },
-                    &now_iso_coordinator(),
+                    &completion_input,
+                    &completed_at,
                 )?;
                 recompute_resource_locks_from_tasks(&mut registry);
                 set_registry_updated_at(&mut registry);
@@ -749,6 +1543,21 @@ pub async fn monitor_active_jobs_native(
                     &BTreeMap::new(),
                     &registry,
                 )?;
+                record_task_mutation_at(
+                    repo_root,
+                    &evt.task_id,
+                    &TaskMutation::JobCompleted {
+                        input: completion_input,
+                    },
+                    &completed_at,
+                );
+                let _ = crate::coordinator::metrics::record_phase_duration(
+                    repo_root,
+                    &evt.task_id,
+                    "dev",
+                    if evt.success { "done" } else { "failed" },
+                    job.started_at.elapsed().as_secs_f64(),
+                );
                 if !completion.should_retry && completion.status_label == "phase_done" {
                     let sealed = crate::coordinator::session_manager::seal_worktree_scoped_session(
                         repo_root,
@@ -767,6 +1576,45 @@ pub async fn monitor_active_jobs_native(
                         }
                     }
                 }
+                if completion.status_label == "throttled" {
+                    // Halve the concurrency that hit the provider limit.
+                    let running = 1 + state
+                        .active_jobs
+                        .values()
+                        .filter(|other| other.tool == job.tool)
+                        .count();
+                    let mut throttle = ToolThrottleState::load(repo_root);
+                    let cooldown = throttle.record_throttle(
+                        &job.tool,
+                        evt.retry_after_seconds,
+                        running,
+                        chrono::Utc::now().timestamp(),
+                        evt.error_message.as_deref().unwrap_or("throttled"),
+                    );
+                    throttle.save(repo_root)?;
+                    let tool_state = &throttle.tools[&job.tool];
+                    let msg = format!(
+                        "tool {} throttled; cooldown={}s strikes={} max_parallel={}",
+                        job.tool, cooldown, tool_state.strikes, tool_state.max_parallel
+                    );
+                    let _ = append_coordinator_event_with_severity(
+                        repo_root,
+                        "tool_throttled",
+                        &evt.task_id,
+                        "dev",
+                        "queued",
+                        &msg,
+                        "warning",
+                    );
+                    if let Some(log) = logger {
+                        let _ = log.note(format!("- Task {} requeued: {}", evt.task_id, msg));
+                    }
+                } else if evt.success {
+                    let mut throttle = ToolThrottleState::load(repo_root);
+                    if throttle.record_success(&job.tool) {
+                        throttle.save(repo_root)?;
+                    }
+                }
                 if completion.status_label == "auto_retry" {
                     if let Some(log) = logger {
                         let _ = log.note(format!(
@@ -790,6 +1638,9 @@ pub async fn monitor_active_jobs_native(
                         &state.event_tx,
                         &mut state.join_set,
                         phase_timeout_seconds,
+                        job.limits,
+                        state.config_profile.as_deref(),
+                        &state.child_env,
                     )?;
                     state.active_jobs.insert(
                         task_id,
@@ -799,6 +1650,7 @@ pub async fn monitor_active_jobs_native(
                             attempt: job.attempt + 1,
                             started_at: std::time::Instant::now(),
                             pid: retry_pid,
+                            limits: job.limits,
                         },
                     );
                     if let Some(log) = logger {
@@ -876,6 +1728,8 @@ fn consume_heartbeat_events(
     let project_paths = crate::ProjectPaths::from_root(repo_root);
     let mut heartbeat_updates: HashMap<String, String> = HashMap::new();
     let mut terminal_success_sources: HashSet<(String, String)> = HashSet::new();
+    let mut usage_samples = Vec::new();
+    let mut resource_samples = Vec::new();
     for line in buf.lines() {
         let trimmed = line.trim();
         if trimmed.is_empty() {
@@ -938,7 +1792,20 @@ fn consume_heartbeat_events(
             continue;
         }
         // Ingest performer/runtime events into SQLite source-of-truth.
-        let _ = crate::coordinator_storage::append_event_sqlite(&project_paths, &event)?;
+        let inserted = crate::coordinator_storage::append_event_sqlite(&project_paths, &event)?;
+        if inserted {
+            if let Some(sample) =
+                crate::coordinator::usage::usage_sample(&event, &state.token_prices)
+            {
+                state.run_usage.add(&sample.totals);
+                usage_samples.push(sample);
+                continue;
+            }
+            if let Some(sample) = crate::coordinator::limits::resource_sample(&event) {
+                resource_samples.push(sample);
+                continue;
+            }
+        }
         if event_type != "heartbeat" {
             continue;
         }
@@ -955,13 +1822,14 @@ fn consume_heartbeat_events(
         }
         heartbeat_updates.insert(task_id.to_string(), ts.to_string());
     }
-    if heartbeat_updates.is_empty() {
+    if heartbeat_updates.is_empty() && usage_samples.is_empty() && resource_samples.is_empty() {
         return Ok(0);
     }
 
     let mut registry =
         crate::coordinator::state::coordinator_state_registry_load(repo_root, &BTreeMap::new())?;
     let mut updated = 0usize;
+    let mut usage_applied = 0usize;
     if let Some(tasks) = registry
         .get_mut("tasks")
         .and_then(serde_json::Value::as_array_mut)
@@ -971,15 +1839,34 @@ fn consume_heartbeat_events(
                 .get("id")
                 .and_then(serde_json::Value::as_str)
                 .unwrap_or_default();
-            let Some(ts) = heartbeat_updates.get(id) else {
+            let samples = usage_samples
+                .iter()
+                .filter(|sample| sample.task_id == id)
+                .collect::<Vec<_>>();
+            let resources = resource_samples
+                .iter()
+                .filter(|sample| sample.task_id == id)
+                .collect::<Vec<_>>();
+            let heartbeat = heartbeat_updates.get(id).cloned();
+            if heartbeat.is_none() && samples.is_empty() && resources.is_empty() {
                 continue;
-            };
+            }
             coordinator_engine::ensure_runtime_object(task);
-            task["task_runtime"]["last_heartbeat"] = serde_json::Value::String(ts.clone());
-            updated += 1;
+            for sample in samples {
+                crate::coordinator::usage::apply_usage_to_task(task, sample);
+                usage_applied += 1;
+            }
+            for sample in resources {
+                crate::coordinator::limits::apply_resource_sample(task, sample);
+                usage_applied += 1;
+            }
+            if let Some(ts) = heartbeat {
+                task["task_runtime"]["last_heartbeat"] = serde_json::Value::String(ts);
+                updated += 1;
+            }
         }
     }
-    if updated > 0 {
+    if updated > 0 || usage_applied > 0 {
         set_registry_updated_at(&mut registry);
         crate::coordinator::state::coordinator_state_registry_save(
             repo_root,
@@ -1005,13 +1892,45 @@ fn consume_heartbeat_events(
     Ok(updated)
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum StaleHeartbeatAction {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StaleHeartbeatAction {
     Retry,
     Block,
     Requeue,
 }
 
+/// Applies the stale heartbeat policy to one running task.
+pub fn apply_stale_heartbeat_action(
+    task: &mut serde_json::Value,
+    action: StaleHeartbeatAction,
+    detail: &str,
+    now: &str,
+) {
+    match action {
+        StaleHeartbeatAction::Block => {
+            task["task_runtime"]["status"] = serde_json::Value::String("stale".to_string());
+            task["task_runtime"]["pid"] = serde_json::Value::Null;
+            task["task_runtime"]["last_error"] = serde_json::Value::String(detail.to_string());
+            task["state"] = serde_json::Value::String("blocked".to_string());
+        }
+        StaleHeartbeatAction::Requeue | StaleHeartbeatAction::Retry => {
+            if action == StaleHeartbeatAction::Retry {
+                increment_runtime_retries(task);
+            }
+            crate::coordinator::state::reset_runtime_to_idle(task);
+            task["task_runtime"]["last_error"] = serde_json::Value::String(detail.to_string());
+            task["state"] = serde_json::Value::String("todo".to_string());
+            task["assignee"] = serde_json::Value::Null;
+            task["claimed_at"] = serde_json::Value::Null;
+            task["worktree"] = serde_json::Value::Null;
+        }
+    }
+
+    task["updated_at"] = serde_json::Value::String(now.to_string());
+    task["state_changed_at"] = serde_json::Value::String(now.to_string());
+}
+
 fn apply_stale_heartbeat_policy(
     repo_root: &Path,
     env_cfg: &CoordinatorEnvConfig,
@@ -1087,35 +2006,8 @@ fn apply_stale_heartbeat_policy(
             }
         );
 
-        match action {
-            StaleHeartbeatAction::Block => {
-                task["task_runtime"]["status"] = serde_json::Value::String("stale".to_string());
-                task["task_runtime"]["pid"] = serde_json::Value::Null;
-                task["task_runtime"]["last_error"] = serde_json::Value::String(detail.clone());
-                task["state"] = serde_json::Value::String("blocked".to_string());
-            }
-            StaleHeartbeatAction::Requeue => {
-                crate::coordinator::state::reset_runtime_to_idle(task);
-                task["task_runtime"]["last_error"] = serde_json::Value::String(detail.clone());
-                task["state"] = serde_json::Value::String("todo".to_string());
-                task["assignee"] = serde_json::Value::Null;
-                task["claimed_at"] = serde_json::Value::Null;
-                task["worktree"] = serde_json::Value::Null;
-            }
-            StaleHeartbeatAction::Retry => {
-                increment_runtime_retries(task);
-                crate::coordinator::state::reset_runtime_to_idle(task);
-                task["task_runtime"]["last_error"] = serde_json::Value::String(detail.clone());
-                task["state"] = serde_json::Value::String("todo".to_string());
-                task["assignee"] = serde_json::Value::Null;
-                task["claimed_at"] = serde_json::Value::Null;
-                task["worktree"] = serde_json::Value::Null;
-            }
-        }
-
-        task["updated_at"] = serde_json::Value::String(now_iso.clone());
-        task["state_changed_at"] = serde_json::Value::String(now_iso.clone());
-        stale_ids.push((task_id, phase));
+        apply_stale_heartbeat_action(task, action, &detail, &now_iso);
+        stale_ids.push((task_id, phase, detail));
     }
 
     if stale_ids.is_empty() {
@@ -1130,7 +2022,16 @@ fn apply_stale_heartbeat_policy(
         &registry,
     )?;
 
-    for (task_id, phase) in &stale_ids {
+    for (task_id, phase, detail) in &stale_ids {
+        record_task_mutation_at(
+            repo_root,
+            task_id,
+            &TaskMutation::StaleHeartbeat {
+                action,
+                detail: detail.clone(),
+            },
+            &now_iso,
+        );
         let _ = append_coordinator_event(
             repo_root,
             "task_runtime_stale",
@@ -1171,7 +2072,7 @@ fn apply_stale_heartbeat_policy(
     Ok(stale_ids.len())
 }
 
-fn resolve_stale_heartbeat_seconds(_env_cfg: &CoordinatorEnvConfig) -> usize {
+pub(crate) fn resolve_stale_heartbeat_seconds(_env_cfg: &CoordinatorEnvConfig) -> usize {
     if let Ok(raw) = std::env::var("STALE_HEARTBEAT_SECONDS") {
         if let Ok(value) = raw.trim().parse::<usize>() {
             return value;
@@ -1180,7 +2081,7 @@ fn resolve_stale_heartbeat_seconds(_env_cfg: &CoordinatorEnvConfig) -> usize {
     0
 }
 
-fn resolve_stale_heartbeat_action(
+pub(crate) fn resolve_stale_heartbeat_action(
     _env_cfg: &CoordinatorEnvConfig,
     logger: Option<&dyn CoordinatorLog>,
 ) -> StaleHeartbeatAction {
@@ -1222,7 +2123,7 @@ fn increment_runtime_retries(task: &mut serde_json::Value) {
     task["task_runtime"]["retries"] = serde_json::Value::from(next);
 }
 
-fn resolve_error_code_retry_list(env_cfg: &CoordinatorEnvConfig) -> Vec<String> {
+pub fn resolve_error_code_retry_list(env_cfg: &CoordinatorEnvConfig) -> Vec<String> {
     let raw = env_cfg
         .error_code_retry_list
         .clone()
@@ -1233,7 +2134,7 @@ fn resolve_error_code_retry_list(env_cfg: &CoordinatorEnvConfig) -> Vec<String>
         .collect()
 }
 
-fn resolve_error_code_retry_max(env_cfg: &CoordinatorEnvConfig) -> usize {
+pub fn resolve_error_code_retry_max(env_cfg: &CoordinatorEnvConfig) -> usize {
     env_cfg.error_code_retry_max.unwrap_or(2)
 }
 
@@ -1247,10 +2148,18 @@ pub async fn monitor_merge_jobs_native(
         match state.merge_event_rx.try_recv() {
             Ok(evt) => {
                 let maybe_job = state.active_merge_jobs.remove(&evt.task_id);
-                let elapsed = maybe_job
+                let elapsed_secs = maybe_job
                     .as_ref()
-                    .map(|j| j.started_at.elapsed().as_secs())
-                    .unwrap_or(0);
+                    .map(|j| j.started_at.elapsed().as_secs_f64())
+                    .unwrap_or(0.0);
+                let elapsed = elapsed_secs as u64;
+                let _ = crate::coordinator::metrics::record_phase_duration(
+                    repo_root,
+                    &evt.task_id,
+                    "merge",
+                    if evt.success { "done" } else { "failed" },
+                    elapsed_secs,
+                );
                 let mut registry = crate::coordinator::state::coordinator_state_registry_load(
                     repo_root,
                     &BTreeMap::new(),
@@ -1263,6 +2172,15 @@ pub async fn monitor_merge_jobs_native(
                     &evt.reason,
                     &now,
                 )?;
+                record_task_mutation_at(
+                    repo_root,
+                    &evt.task_id,
+                    &TaskMutation::MergeResult {
+                        success: evt.success,
+                        reason: evt.reason.clone(),
+                    },
+                    &now,
+                );
                 if evt.success {
                     if let Some(task_snapshot) = registry
                         .get("tasks")
@@ -1326,6 +2244,14 @@ pub async fn monitor_merge_jobs_native(
                         ));
                     }
                 } else {
+                    let _ = append_coordinator_event(
+                        repo_root,
+                        "local_merge_failed",
+                        &evt.task_id,
+                        "integrate",
+                        "failed",
+                        &evt.reason,
+                    );
                     blocked_merge = Some((evt.task_id.clone(), evt.reason.clone()));
                     if let Some(log) = logger {
                         let _ = log.note(format!(
@@ -1352,6 +2278,114 @@ pub async fn monitor_merge_jobs_native(
     Ok(blocked_merge)
 }
 
+/// Task selector settings of a dispatch pass: CLI/env overrides first, then
+/// `automation.coordinator`.
+pub fn task_selector_config(
+    canonical: &crate::config::CanonicalConfig,
+    coordinator: Option<&crate::config::CoordinatorConfig>,
+    env_cfg: &CoordinatorEnvConfig,
+    max_parallel: usize,
+) -> crate::coordinator::task_selector::TaskSelectorConfig {
+    crate::coordinator::task_selector::TaskSelectorConfig {
+        enabled_tools: canonical.tools.enabled.clone(),
+        tool_priority: env_cfg
+            .tool_priority
+            .clone()
+            .map(|csv| {
+                csv.split(',')
+                    .map(|v| v.trim().to_string())
+                    .filter(|v| !v.is_empty())
+                    .collect::<Vec<_>>()
+            })
+            .or_else(|| coordinator.map(|c| c.tool_priority.clone()))
+            .unwrap_or_default(),
+        max_parallel_per_tool: env_cfg
+            .max_parallel_per_tool_json
+            .clone()
+            .and_then(|raw| serde_json::from_str::<HashMap<String, usize>>(&raw).ok())
+            .or_else(|| {
+                coordinator.map(|c| {
+                    c.max_parallel_per_tool
+                        .clone()
+                        .into_iter()
+                        .collect::<HashMap<_, _>>()
+                })
+            })
+            .unwrap_or_default(),
+        tool_specializations: env_cfg
+            .tool_specializations_json
+            .clone()
+            .and_then(|raw| serde_json::from_str::<HashMap<String, Vec<String>>>(&raw).ok())
+            .or_else(|| {
+                coordinator.map(|c| {
+                    c.tool_specializations
+                        .clone()
+                        .into_iter()
+                        .collect::<HashMap<_, _>>()
+                })
+            })
+            .unwrap_or_default(),
+        max_parallel,
+        default_tool: canonical
+            .tools
+            .enabled
+            .first()
+            .cloned()
+            .unwrap_or_else(|| "codex".to_string()),
+        default_base_branch: env_cfg
+            .reference_branch
+            .clone()
+            .or_else(|| coordinator.and_then(|c| c.reference_branch.clone()))
+            .unwrap_or_else(|| "master".to_string()),
+        avoid_scope_overlap: coordinator
+            .and_then(|c| c.avoid_scope_overlap)
+            .unwrap_or(false),
+        touched_files: HashMap::new(),
+    }
+}
+
+/// Files already changed on each active task branch, keyed by task id. Branches that cannot
+/// be diffed are left out.
+pub fn active_touched_files(
+    repo_root: &Path,
+    registry: &serde_json::Value,
+) -> HashMap<String, Vec<String>> {
+    let mut out = HashMap::new();
+    let tasks = registry
+        .get("tasks")
+        .and_then(serde_json::Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    for task in tasks {
+        let state = task
+            .get("state")
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or("todo");
+        if !crate::coordinator::task_selector::is_active_state(state) {
+            continue;
+        }
+        let Some(task_id) = task.get("id").and_then(serde_json::Value::as_str) else {
+            continue;
+        };
+        let worktree = task.get("worktree");
+        let branch = worktree
+            .and_then(|w| w.get("branch"))
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or_default();
+        if branch.is_empty() {
+            continue;
+        }
+        let base = worktree
+            .and_then(|w| w.get("base_branch"))
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or("master");
+        if let Ok(files) = crate::coordinator::approval::changed_files(repo_root, base, branch) {
+            out.insert(task_id.to_string(), files);
+        }
+    }
+    out
+}
+
 pub async fn dispatch_ready_tasks_native(
     repo_root: &Path,
     canonical: &crate::config::CanonicalConfig,
@@ -1398,12 +2432,56 @@ pub async fn dispatch_ready_tasks_native(
         }
         return Ok(0);
     }
+    if let Some(budget_usd) = coordinator.and_then(|c| c.budget_usd).filter(|b| *b > 0.0) {
+        if state.run_usage.cost_usd >= budget_usd {
+            if !state.budget_event_emitted {
+                let msg = format!(
+                    "budget exceeded run_cost_usd={:.4} budget_usd={:.4}; dispatch paused",
+                    state.run_usage.cost_usd, budget_usd
+                );
+                crate::coordinator::state_runtime::write_coordinator_pause_file(
+                    repo_root, "global", "dev", &msg,
+                )?;
+                let _ = append_coordinator_event_with_severity(
+                    repo_root,
+                    "budget_exceeded",
+                    "-",
+                    "dev",
+                    "paused",
+                    &msg,
+                    "warning",
+                );
+                if let Some(log) = logger {
+                    let _ = log.note(format!("- {}", msg));
+                }
+                state.budget_event_emitted = true;
+                return Ok(0);
+            }
+            // `macc coordinator resume` clears the pause and lets the run go over budget.
+            if crate::coordinator::state_runtime::coordinator_pause_file_path(repo_root).exists() {
+                return Ok(0);
+            }
+        }
+    }
     let remaining_budget = if max_dispatch_total == 0 {
         usize::MAX
     } else {
         max_dispatch_total.saturating_sub(state.dispatched_total_run)
     };
 
+    let touched_files = if coordinator
+        .and_then(|c| c.predict_scope_overlap)
+        .unwrap_or(false)
+    {
+        let registry = crate::coordinator::state::coordinator_state_registry_load(
+            repo_root,
+            &BTreeMap::new(),
+        )?;
+        active_touched_files(repo_root, &registry)
+    } else {
+        HashMap::new()
+    };
+
     while dispatched < remaining_budget {
         if max_parallel > 0 && state.active_jobs.len() >= max_parallel {
             break;
@@ -1413,58 +2491,12 @@ pub async fn dispatch_ready_tasks_native(
             repo_root,
             &BTreeMap::new(),
         )?;
-        let config = crate::coordinator::task_selector::TaskSelectorConfig {
-            enabled_tools: canonical.tools.enabled.clone(),
-            tool_priority: env_cfg
-                .tool_priority
-                .clone()
-                .map(|csv| {
-                    csv.split(',')
-                        .map(|v| v.trim().to_string())
-                        .filter(|v| !v.is_empty())
-                        .collect::<Vec<_>>()
-                })
-                .or_else(|| coordinator.map(|c| c.tool_priority.clone()))
-                .unwrap_or_default(),
-            max_parallel_per_tool: env_cfg
-                .max_parallel_per_tool_json
-                .clone()
-                .and_then(|raw| serde_json::from_str::<HashMap<String, usize>>(&raw).ok())
-                .or_else(|| {
-                    coordinator.map(|c| {
-                        c.max_parallel_per_tool
-                            .clone()
-                            .into_iter()
-                            .collect::<HashMap<_, _>>()
-                    })
-                })
-                .unwrap_or_default(),
-            tool_specializations: env_cfg
-                .tool_specializations_json
-                .clone()
-                .and_then(|raw| serde_json::from_str::<HashMap<String, Vec<String>>>(&raw).ok())
-                .or_else(|| {
-                    coordinator.map(|c| {
-                        c.tool_specializations
-                            .clone()
-                            .into_iter()
-                            .collect::<HashMap<_, _>>()
-                    })
-                })
-                .unwrap_or_default(),
-            max_parallel,
-            default_tool: canonical
-                .tools
-                .enabled
-                .first()
-                .cloned()
-                .unwrap_or_else(|| "codex".to_string()),
-            default_base_branch: env_cfg
-                .reference_branch
-                .clone()
-                .or_else(|| coordinator.and_then(|c| c.reference_branch.clone()))
-                .unwrap_or_else(|| "master".to_string()),
-        };
+        let mut config = task_selector_config(canonical, coordinator, env_cfg, max_parallel);
+        config.touched_files = touched_files.clone();
+        ToolThrottleState::load(repo_root).apply_caps(
+            &mut config.max_parallel_per_tool,
+            chrono::Utc::now().timestamp(),
+        );
 
         let Some(selected) =
             crate::coordinator::task_selector::select_next_ready_task(&registry, &config)
@@ -1668,6 +2700,48 @@ pub async fn dispatch_ready_tasks_native(
             }
             (created.path, created.branch, last_commit)
         };
+        if let Some(pool) = coordinator.and_then(|c| c.worktree.as_ref()) {
+            if let Err(e) = crate::coordinator::worktree_pool::prepare_slot(
+                repo_root,
+                &worktree_path,
+                pool,
+                &selected.id,
+            )
+            .await
+            {
+                let msg = format!(
+                    "dispatch failed for task {}: worktree setup failed ({})",
+                    selected.id, e
+                );
+                let _ = append_coordinator_event_with_severity(
+                    repo_root,
+                    "dispatch_failed",
+                    &selected.id,
+                    "dev",
+                    "failed",
+                    &msg,
+                    "warning",
+                );
+                if let Some(log) = logger {
+                    let _ = log.note(format!("- {}", msg));
+                }
+                emit_dispatch_skipped(
+                    repo_root,
+                    logger,
+                    &selected.id,
+                    "worktree_setup_failed",
+                    &e.to_string(),
+                );
+                if cooldown_seconds > 0 {
+                    state.dispatch_retry_not_before.insert(
+                        selected.id.clone(),
+                        Instant::now() + Duration::from_secs(cooldown_seconds),
+                    );
+                }
+                dispatch_failed_this_cycle.insert(selected.id.clone());
+                break;
+            }
+        }
         let dispatch_now = now_iso_coordinator();
         let dispatch_session_id = format!("coordinator-{}-{}", selected.id, dispatch_now);
         let claim_update = coordinator_engine::DispatchClaimUpdate {
@@ -1690,6 +2764,14 @@ pub async fn dispatch_ready_tasks_native(
             &BTreeMap::new(),
             &registry,
         )?;
+        record_task_mutation_at(
+            repo_root,
+            &selected.id,
+            &TaskMutation::Claimed {
+                update: claim_update.clone(),
+            },
+            &dispatch_now,
+        );
         if let Some(log) = logger {
             let _ = log.note(format!(
                 "- Lifecycle task={} stage=claim persisted session_id={}",
@@ -1702,6 +2784,7 @@ pub async fn dispatch_ready_tasks_native(
                 repo_root,
                 &BTreeMap::new(),
             )?;
+            let rolled_back_at = now_iso_coordinator();
             if let Some(tasks) = rollback_registry
                 .get_mut("tasks")
                 .and_then(serde_json::Value::as_array_mut)
@@ -1713,19 +2796,7 @@ pub async fn dispatch_ready_tasks_native(
                         .unwrap_or_default()
                         == selected.id
                     {
-                        task["state"] = serde_json::Value::String("todo".to_string());
-                        task["assignee"] = serde_json::Value::Null;
-                        task["claimed_at"] = serde_json::Value::Null;
-                        task["worktree"] = serde_json::Value::Null;
-                        coordinator_engine::ensure_runtime_object(task);
-                        task["task_runtime"]["status"] =
-                            serde_json::Value::String("idle".to_string());
-                        task["task_runtime"]["pid"] = serde_json::Value::Null;
-                        task["task_runtime"]["current_phase"] = serde_json::Value::Null;
-                        task["task_runtime"]["last_error"] =
-                            serde_json::Value::String(detail.to_string());
-                        task["updated_at"] = serde_json::Value::String(now_iso_coordinator());
-                        task["state_changed_at"] = serde_json::Value::String(now_iso_coordinator());
+                        coordinator_engine::apply_claim_rollback(task, detail, &rolled_back_at);
                         break;
                     }
                 }
@@ -1736,7 +2807,16 @@ pub async fn dispatch_ready_tasks_native(
                 repo_root,
                 &BTreeMap::new(),
                 &rollback_registry,
-            )
+            )?;
+            record_task_mutation_at(
+                repo_root,
+                &selected.id,
+                &TaskMutation::ClaimRolledBack {
+                    detail: detail.to_string(),
+                },
+                &rolled_back_at,
+            );
+            Ok(())
         };
 
         if let Some(log) = logger {
@@ -1898,6 +2978,9 @@ pub async fn dispatch_ready_tasks_native(
         let mut apply_cmd = tokio::process::Command::new(std::env::current_exe().map_err(|e| {
             MaccError::Validation(format!("Failed to resolve current executable path: {}", e))
         })?);
+        if let Some(profile) = &state.config_profile {
+            apply_cmd.env(crate::config::layers::PROFILE_ENV, profile);
+        }
         apply_cmd
             .current_dir(repo_root)
             .arg("--cwd")
@@ -1970,6 +3053,19 @@ pub async fn dispatch_ready_tasks_native(
         let current_exe = std::env::current_exe().map_err(|e| {
             MaccError::Validation(format!("Failed to resolve current executable path: {}", e))
         })?;
+        let limits = crate::coordinator::limits::resolve_resource_limits(
+            coordinator,
+            &selected.tool,
+            registry
+                .get("tasks")
+                .and_then(serde_json::Value::as_array)
+                .and_then(|tasks| {
+                    tasks.iter().find(|t| {
+                        t.get("id").and_then(serde_json::Value::as_str) == Some(&selected.id)
+                    })
+                })
+                .unwrap_or(&serde_json::Value::Null),
+        );
         let pid = match coordinator_runtime::spawn_performer_job(
             &current_exe,
             repo_root,
@@ -1978,6 +3074,9 @@ pub async fn dispatch_ready_tasks_native(
             &state.event_tx,
             &mut state.join_set,
             phase_timeout_seconds,
+            limits,
+            state.config_profile.as_deref(),
+            &state.child_env,
         ) {
             Ok(pid) => pid,
             Err(err) => {
@@ -2026,6 +3125,7 @@ pub async fn dispatch_ready_tasks_native(
             &BTreeMap::new(),
             &registry,
         )?;
+        record_task_mutation(repo_root, &selected.id, &TaskMutation::Pid { pid });
         if let Some(log) = logger {
             let _ = log.note(format!(
                 "- Lifecycle task={} stage=run pid_persisted={}",
@@ -2043,6 +3143,7 @@ pub async fn dispatch_ready_tasks_native(
                 attempt: 1,
                 started_at: std::time::Instant::now(),
                 pid,
+                limits,
             },
         );
         if let Some(log) = logger {
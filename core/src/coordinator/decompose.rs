@@ -0,0 +1,238 @@
+use crate::config::DecomposeConfig;
+use crate::coordinator::prd::validate_prd_tasks;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+/// Reply the coordinator tool returns to a decomposition prompt.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Decomposition {
+    pub subtasks: Vec<Subtask>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Subtask {
+    /// Id local to this reply, referenced by sibling `dependencies`.
+    pub id: String,
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub objective: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub steps: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<String>,
+    /// Local ids of sibling subtasks, or ids of existing registry tasks.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dependencies: Vec<String>,
+    /// Path globs the subtask is expected to change.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scope: Vec<String>,
+}
+
+/// Fields a subtask inherits from its parent when the reply leaves them out.
+const INHERITED_FIELDS: &[&str] = &[
+    "category",
+    "priority",
+    "base_branch",
+    "project",
+    "merge_strategy",
+];
+
+/// JSON schema of [`Decomposition`], quoted in the decomposition prompt.
+pub fn reply_schema() -> String {
+    serde_json::to_string_pretty(&schemars::schema_for!(Decomposition)).unwrap_or_default()
+}
+
+/// A parent waiting for its subtasks; the selector never dispatches it.
+pub fn is_tracking_task(task: &Value) -> bool {
+    task.get("subtasks")
+        .and_then(Value::as_array)
+        .is_some_and(|subtasks| !subtasks.is_empty())
+}
+
+/// Why a blocked task could be decomposed: `size_limit` after the size guard blocked it,
+/// `failure` after a failed phase. Merge conflicts and stale runtimes do not qualify.
+pub fn trigger(task: &Value) -> Option<&'static str> {
+    if task.get("state").and_then(Value::as_str) != Some("blocked") {
+        return None;
+    }
+    let runtime = task.get("task_runtime");
+    if runtime
+        .and_then(|r| r.get("status"))
+        .and_then(Value::as_str)
+        != Some("failed")
+    {
+        return None;
+    }
+    let last_error = runtime
+        .and_then(|r| r.get("last_error"))
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    if last_error.starts_with("size limit exceeded") {
+        Some("size_limit")
+    } else {
+        Some("failure")
+    }
+}
+
+/// Blocked tasks are decomposed once; subtasks are not decomposed again.
+pub fn decomposition_trigger(task: &Value, config: &DecomposeConfig) -> Option<&'static str> {
+    if task.get("decomposition").is_some_and(|d| !d.is_null())
+        || task.get("parent").is_some_and(|p| !p.is_null())
+        || is_tracking_task(task)
+    {
+        return None;
+    }
+    trigger(task).filter(|trigger| config.triggered_by(trigger))
+}
+
+/// Task entry handed to the decomposition prompt, carrying the request parameters.
+pub fn decomposition_request(task: &Value, config: &DecomposeConfig, trigger: &str) -> Value {
+    let mut request = task.clone();
+    request["decomposition"] = serde_json::json!({
+        "trigger": trigger,
+        "max_subtasks": config.max_subtasks(),
+    });
+    request
+}
+
+/// Parses the last ```json block of the reply (or its outermost braces) as a
+/// [`Decomposition`] of 2 to `max_subtasks` subtasks with distinct ids.
+pub fn parse_reply(output: &str, max_subtasks: usize) -> Result<Decomposition, String> {
+    let json = fenced_json(output)
+        .or_else(|| {
+            let start = output.find('{')?;
+            let end = output.rfind('}')?;
+            (start < end).then(|| &output[start..=end])
+        })
+        .ok_or_else(|| "decomposition reply contains no JSON object".to_string())?;
+    let decomposition: Decomposition = serde_json::from_str(json)
+        .map_err(|e| format!("decomposition reply does not match the schema: {}", e))?;
+    let count = decomposition.subtasks.len();
+    if count < 2 || count > max_subtasks {
+        return Err(format!(
+            "decomposition reply has {} subtasks (expected 2 to {})",
+            count, max_subtasks
+        ));
+    }
+    let mut ids = HashSet::new();
+    for subtask in &decomposition.subtasks {
+        if subtask.id.trim().is_empty() || !ids.insert(subtask.id.trim()) {
+            return Err(format!(
+                "decomposition reply has an empty or duplicate subtask id '{}'",
+                subtask.id
+            ));
+        }
+    }
+    Ok(decomposition)
+}
+
+fn fenced_json(output: &str) -> Option<&str> {
+    let start = output.rfind("```json")? + "```json".len();
+    let end = output[start..].find("```")? + start;
+    Some(output[start..end].trim())
+}
+
+/// PRD definitions of the subtasks: ids `<parent>.<n>`, local dependencies renamed, unset
+/// fields inherited from the parent and a `parent` link. Validated like PRD tasks.
+pub fn subtask_definitions(
+    parent: &Value,
+    decomposition: &Decomposition,
+    existing_ids: &HashSet<String>,
+) -> Result<Vec<Value>, String> {
+    let parent_id = parent.get("id").and_then(Value::as_str).unwrap_or_default();
+    let ids: HashMap<&str, String> = decomposition
+        .subtasks
+        .iter()
+        .enumerate()
+        .map(|(index, subtask)| (subtask.id.trim(), format!("{}.{}", parent_id, index + 1)))
+        .collect();
+    let mut definitions = Vec::new();
+    for subtask in &decomposition.subtasks {
+        let mut definition = serde_json::to_value(subtask).map_err(|e| e.to_string())?;
+        definition["id"] = Value::String(ids[subtask.id.trim()].clone());
+        definition["dependencies"] = Value::from(
+            subtask
+                .dependencies
+                .iter()
+                .map(|dependency| {
+                    ids.get(dependency.trim())
+                        .cloned()
+                        .unwrap_or_else(|| dependency.trim().to_string())
+                })
+                .collect::<Vec<_>>(),
+        );
+        for field in INHERITED_FIELDS {
+            if definition.get(*field).is_none() {
+                if let Some(value) = parent.get(*field).filter(|v| !v.is_null()) {
+                    definition[*field] = value.clone();
+                }
+            }
+        }
+        definition["parent"] = Value::String(parent_id.to_string());
+        definitions.push(definition);
+    }
+    validate_prd_tasks(&definitions, existing_ids).map_err(|e| e.to_string())?;
+    Ok(definitions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_reply_is_parsed_strictly() {
+        let reply = "Plan below.\n```json\n{\"subtasks\": [{\"id\": \"a\", \"title\": \"A\"}, {\"id\": \"b\", \"title\": \"B\", \"dependencies\": [\"a\"]}]}\n```\n";
+        let decomposition = parse_reply(reply, 5).unwrap();
+        assert_eq!(decomposition.subtasks.len(), 2);
+        assert_eq!(decomposition.subtasks[1].dependencies, vec!["a"]);
+
+        let unknown =
+            r#"{"subtasks": [{"id": "a", "title": "A", "owner": "x"}, {"id": "b", "title": "B"}]}"#;
+        assert!(parse_reply(unknown, 5).unwrap_err().contains("schema"));
+        let single = r#"{"subtasks": [{"id": "a", "title": "A"}]}"#;
+        assert!(parse_reply(single, 5).unwrap_err().contains("1 subtasks"));
+        assert!(parse_reply("no idea", 5).is_err());
+    }
+
+    #[test]
+    fn test_definitions_rename_ids_and_inherit_parent_fields() {
+        let parent = json!({"id": "T1", "category": "backend", "base_branch": "main"});
+        let decomposition = parse_reply(
+            r#"{"subtasks": [
+                {"id": "api", "title": "API", "dependencies": ["T0"]},
+                {"id": "ui", "title": "UI", "category": "frontend", "dependencies": ["api"]}
+            ]}"#,
+            5,
+        )
+        .unwrap();
+        let existing = HashSet::from(["T0".to_string(), "T1".to_string()]);
+        let definitions = subtask_definitions(&parent, &decomposition, &existing).unwrap();
+        assert_eq!(definitions[0]["id"], "T1.1");
+        assert_eq!(definitions[0]["category"], "backend");
+        assert_eq!(definitions[0]["dependencies"], json!(["T0"]));
+        assert_eq!(definitions[1]["id"], "T1.2");
+        assert_eq!(definitions[1]["category"], "frontend");
+        assert_eq!(definitions[1]["dependencies"], json!(["T1.1"]));
+        assert_eq!(definitions[1]["base_branch"], "main");
+        assert_eq!(definitions[1]["parent"], "T1");
+
+        let dangling = parse_reply(
+            r#"{"subtasks": [{"id": "a", "title": "A", "dependencies": ["T7"]}, {"id": "b", "title": "B"}]}"#,
+            5,
+        )
+        .unwrap();
+        let err = subtask_definitions(&parent, &dangling, &existing).unwrap_err();
+        assert!(err.contains("unknown dependency T7"), "{}", err);
+    }
+}
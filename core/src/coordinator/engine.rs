@@ -1,9 +1,11 @@
 use super::{RuntimeStatus, WorkflowState};
 use crate::config::{CanonicalConfig, CoordinatorConfig};
 use crate::coordinator::control_plane::CoordinatorLog;
+use crate::coordinator::forge::{PullRequest, ReviewComment};
 use crate::coordinator::runtime::{
     process_branch_cleanup_queue, terminate_active_jobs, CoordinatorRunState,
 };
+use crate::coordinator::size_guard::{self, SizeCheck};
 use crate::coordinator::state_runtime::{
     cleanup_dead_runtime_tasks, clear_coordinator_pause_file, coordinator_pause_file_path,
     resume_paused_task_integrate, set_task_paused_for_integrate, write_coordinator_pause_file,
@@ -12,8 +14,10 @@ use crate::coordinator_storage::{
     coordinator_storage_bootstrap_sqlite_from_json, coordinator_storage_export_sqlite_to_json,
     CoordinatorStorageMode,
 };
+use crate::service::interaction::InteractionRequest;
 use crate::{MaccError, Result};
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
@@ -33,7 +37,8 @@ pub enum AdvancePlan {
     Noop,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ReviewVerdict {
     Ok,
     ChangesRequested,
@@ -44,6 +49,8 @@ enum WorkflowEvent {
     PhaseSucceeded(&'static str),
     PhaseFailed(&'static str),
     ReviewChangesRequested,
+    SizeLimitExceeded,
+    Decomposed,
     MergeSucceeded,
     MergeFailed,
 }
@@ -63,7 +70,7 @@ pub struct CoordinatorCounts {
     pub merged: usize,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DispatchClaimUpdate {
     pub task_id: String,
     pub tool: String,
@@ -77,7 +84,7 @@ pub struct DispatchClaimUpdate {
     pub now: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct JobCompletionInput {
     pub success: bool,
     pub attempt: usize,
@@ -189,7 +196,9 @@ fn transition_workflow_state(from: WorkflowState, event: WorkflowEvent) -> Resul
         (WorkflowState::InProgress, WorkflowEvent::PhaseSucceeded("review")) => {
             WorkflowState::PrOpen
         }
-        (WorkflowState::InProgress, WorkflowEvent::ReviewChangesRequested) => {
+        (WorkflowState::InProgress, WorkflowEvent::ReviewChangesRequested)
+        | (WorkflowState::PrOpen, WorkflowEvent::ReviewChangesRequested)
+        | (WorkflowState::Queued, WorkflowEvent::ReviewChangesRequested) => {
             WorkflowState::ChangesRequested
         }
         (WorkflowState::PrOpen, WorkflowEvent::PhaseSucceeded("integrate")) => {
@@ -201,7 +210,10 @@ fn transition_workflow_state(from: WorkflowState, event: WorkflowEvent) -> Resul
         (WorkflowState::InProgress, WorkflowEvent::PhaseFailed("review"))
         | (WorkflowState::PrOpen, WorkflowEvent::PhaseFailed("integrate"))
         | (WorkflowState::ChangesRequested, WorkflowEvent::PhaseFailed("fix"))
-        | (WorkflowState::Queued, WorkflowEvent::MergeFailed) => WorkflowState::Blocked,
+        | (WorkflowState::Queued, WorkflowEvent::MergeFailed)
+        | (WorkflowState::InProgress, WorkflowEvent::SizeLimitExceeded)
+        | (WorkflowState::PrOpen, WorkflowEvent::SizeLimitExceeded) => WorkflowState::Blocked,
+        (WorkflowState::Blocked, WorkflowEvent::Decomposed) => WorkflowState::Todo,
         (WorkflowState::Queued, WorkflowEvent::MergeSucceeded) => WorkflowState::Merged,
         _ => {
             return Err(MaccError::Validation(format!(
@@ -387,6 +399,357 @@ pub fn apply_phase_outcome_in_registry(
     Ok(())
 }
 
+/// Records the pull request a forge publish opened or updated, and the commit it pushed.
+pub fn apply_forge_publish_in_registry(
+    registry: &mut Value,
+    task_id: &str,
+    kind: &str,
+    pr: &PullRequest,
+    pushed_head: &str,
+    now: &str,
+) -> Result<()> {
+    let task = find_task_mut(registry, task_id)?;
+    task["pr_url"] = Value::String(pr.url.clone());
+    task["forge"] = json!({
+        "kind": kind,
+        "number": pr.number,
+        "url": pr.url,
+        "pushed_head": pushed_head,
+        "pushed_at": now,
+    });
+    task["updated_at"] = Value::String(now.to_string());
+    Ok(())
+}
+
+/// Moves a `pr_open` task to `changes_requested` after a change request on the forge; the
+/// review comments are kept on the task for the fix phase prompt.
+pub fn apply_forge_changes_requested_in_registry(
+    registry: &mut Value,
+    task_id: &str,
+    comments: &[ReviewComment],
+    now: &str,
+) -> Result<()> {
+    let task = find_task_mut(registry, task_id)?;
+    let from = task_workflow_state(task)?;
+    let to = transition_workflow_state(from, WorkflowEvent::ReviewChangesRequested)?;
+    task["state"] = Value::String(to.as_str().to_string());
+    ensure_runtime_object(task);
+    task["task_runtime"]["status"] = Value::String(RuntimeStatus::PhaseDone.as_str().to_string());
+    task["task_runtime"]["current_phase"] = Value::String("review".to_string());
+    task["task_runtime"]["pid"] = Value::Null;
+    task["review_comments"] = serde_json::to_value(comments).unwrap_or(Value::Null);
+    task["state_changed_at"] = Value::String(now.to_string());
+    Ok(())
+}
+
+/// Stores the measured diff in `task_runtime.metrics.diff`. An oversized task is blocked, or
+/// sent to `changes_requested` with a split-or-reduce instruction as review feedback.
+pub fn apply_size_check_in_registry(
+    registry: &mut Value,
+    task_id: &str,
+    check: &SizeCheck,
+    now: &str,
+) -> Result<()> {
+    let task = find_task_mut(registry, task_id)?;
+    // Resolve the transition first so a task that cannot move is left untouched.
+    let to = if check.violations.is_empty() {
+        None
+    } else {
+        let event = if check.block {
+            WorkflowEvent::SizeLimitExceeded
+        } else {
+            WorkflowEvent::ReviewChangesRequested
+        };
+        Some(transition_workflow_state(
+            task_workflow_state(task)?,
+            event,
+        )?)
+    };
+    ensure_runtime_object(task);
+    if !task["task_runtime"]["metrics"].is_object() {
+        task["task_runtime"]["metrics"] = json!({});
+    }
+    task["task_runtime"]["metrics"]["diff"] = json!({
+        "files": check.stats.files,
+        "insertions": check.stats.insertions,
+        "deletions": check.stats.deletions,
+        "lines": check.stats.lines(),
+    });
+    let Some(to) = to else {
+        return Ok(());
+    };
+    let phase = task["task_runtime"]["current_phase"].clone();
+    if check.block {
+        task["state"] = Value::String(to.as_str().to_string());
+        task["task_runtime"]["status"] = Value::String(RuntimeStatus::Failed.as_str().to_string());
+        task["task_runtime"]["last_error"] = Value::String(format!(
+            "size limit exceeded: {}",
+            check.violations.join("; ")
+        ));
+    } else {
+        task["state"] = Value::String(to.as_str().to_string());
+        task["task_runtime"]["status"] =
+            Value::String(RuntimeStatus::PhaseDone.as_str().to_string());
+        task["task_runtime"]["metrics"]["size_rejections"] =
+            Value::from(size_guard::size_rejections(task) + 1);
+        let feedback = ReviewComment {
+            author: "macc".to_string(),
+            body: size_guard::split_instruction(&check.violations),
+            path: None,
+            line: None,
+        };
+        task["review_comments"] = serde_json::to_value([feedback]).unwrap_or(Value::Null);
+    }
+    task["task_runtime"]["current_phase"] = phase;
+    task["task_runtime"]["pid"] = Value::Null;
+    task["state_changed_at"] = Value::String(now.to_string());
+    Ok(())
+}
+
+/// Registry entry of a task the coordinator has not touched yet.
+pub fn new_registry_task(id: &str) -> Value {
+    json!({
+        "id": id,
+        "state": "todo",
+        "dependencies": [],
+        "exclusive_resources": [],
+        "task_runtime": {
+            "status": "idle",
+            "pid": null,
+            "current_phase": null,
+            "merge_result_pending": false,
+            "merge_result_file": null
+        }
+    })
+}
+
+/// Turns a blocked task into a tracking task for `subtasks` (PRD definitions linked to it by
+/// `parent`): the parent returns to `todo` without its worktree and the subtasks are inserted
+/// after it as new tasks.
+pub fn apply_decomposition_in_registry(
+    registry: &mut Value,
+    task_id: &str,
+    subtasks: &[Value],
+    now: &str,
+) -> Result<()> {
+    let task = find_task_mut(registry, task_id)?;
+    let from = task_workflow_state(task)?;
+    let to = transition_workflow_state(from, WorkflowEvent::Decomposed)?;
+    let ids: Vec<String> = subtasks
+        .iter()
+        .filter_map(|subtask| subtask.get("id").and_then(Value::as_str))
+        .map(str::to_string)
+        .collect();
+    task["decomposition"] = json!({
+        "status": "decomposed",
+        "reason": task["task_runtime"]["last_error"],
+        "decomposed_at": now,
+    });
+    task["subtasks"] = json!(ids);
+    task["state"] = Value::String(to.as_str().to_string());
+    task["assignee"] = Value::Null;
+    task["claimed_at"] = Value::Null;
+    task["worktree"] = Value::Null;
+    ensure_runtime_object(task);
+    task["task_runtime"]["status"] = Value::String(RuntimeStatus::Idle.as_str().to_string());
+    task["task_runtime"]["current_phase"] = Value::Null;
+    task["task_runtime"]["pid"] = Value::Null;
+    task["state_changed_at"] = Value::String(now.to_string());
+
+    let entries: Vec<Value> = subtasks
+        .iter()
+        .map(|subtask| {
+            let id = subtask
+                .get("id")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            let mut entry = new_registry_task(id);
+            if let Some(fields) = subtask.as_object() {
+                for (key, value) in fields {
+                    entry[key] = value.clone();
+                }
+            }
+            entry["updated_at"] = Value::String(now.to_string());
+            entry
+        })
+        .collect();
+    let tasks = tasks_array_mut(registry)?;
+    let at = tasks
+        .iter()
+        .position(|t| t.get("id").and_then(Value::as_str) == Some(task_id))
+        .map(|index| index + 1)
+        .unwrap_or(tasks.len());
+    tasks.splice(at..at, entries);
+    Ok(())
+}
+
+/// Records a decomposition that produced no usable subtasks; the task stays blocked.
+pub fn apply_decomposition_failure_in_registry(
+    registry: &mut Value,
+    task_id: &str,
+    error: &str,
+    now: &str,
+) -> Result<()> {
+    let task = find_task_mut(registry, task_id)?;
+    task["decomposition"] = json!({
+        "status": "failed",
+        "error": error,
+        "decomposed_at": now,
+    });
+    Ok(())
+}
+
+/// Tracking tasks in `todo` whose subtasks have all merged.
+pub fn tracking_tasks_done(registry: &Value) -> Vec<String> {
+    let tasks = registry
+        .get("tasks")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let merged: HashSet<&str> = tasks
+        .iter()
+        .filter(|t| t.get("state").and_then(Value::as_str) == Some("merged"))
+        .filter_map(|t| t.get("id").and_then(Value::as_str))
+        .collect();
+    tasks
+        .iter()
+        .filter(|t| t.get("state").and_then(Value::as_str) == Some("todo"))
+        .filter(|t| crate::coordinator::decompose::is_tracking_task(t))
+        .filter(|t| {
+            t["subtasks"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .all(|id| id.as_str().is_some_and(|id| merged.contains(id)))
+        })
+        .filter_map(|t| t.get("id").and_then(Value::as_str).map(str::to_string))
+        .collect()
+}
+
+/// Marks a tracking task merged once its subtasks are. It never ran, so it skips the
+/// claimed..queued path the workflow table requires of dispatched tasks.
+pub fn apply_subtasks_merged_in_registry(
+    registry: &mut Value,
+    task_id: &str,
+    now: &str,
+) -> Result<()> {
+    let task = find_task_mut(registry, task_id)?;
+    if task_workflow_state(task)? != WorkflowState::Todo
+        || !crate::coordinator::decompose::is_tracking_task(task)
+    {
+        return Err(MaccError::Validation(format!(
+            "Task '{}' is not a tracking task waiting for its subtasks",
+            task_id
+        )));
+    }
+    task["state"] = Value::String(WorkflowState::Merged.as_str().to_string());
+    ensure_runtime_object(task);
+    task["task_runtime"]["status"] = Value::String(RuntimeStatus::Idle.as_str().to_string());
+    task["task_runtime"]["pid"] = Value::Null;
+    task["state_changed_at"] = Value::String(now.to_string());
+    Ok(())
+}
+
+/// Holds a `queued` task for human approval: runtime `waiting_for_user` and a pending
+/// `approval` record carrying the interaction request and the branch head it is about.
+pub fn apply_approval_request_in_registry(
+    registry: &mut Value,
+    task_id: &str,
+    request: &InteractionRequest,
+    reasons: &[String],
+    head: Option<&str>,
+    now: &str,
+) -> Result<()> {
+    let task = find_task_mut(registry, task_id)?;
+    ensure_runtime_object(task);
+    task["task_runtime"]["status"] =
+        Value::String(RuntimeStatus::WaitingForUser.as_str().to_string());
+    task["task_runtime"]["current_phase"] = Value::String("integrate".to_string());
+    task["task_runtime"]["pid"] = Value::Null;
+    task["approval"] = json!({
+        "status": "pending",
+        "request": request,
+        "reasons": reasons,
+        "head": head,
+        "requested_at": now,
+    });
+    task["updated_at"] = Value::String(now.to_string());
+    Ok(())
+}
+
+/// Voids a pending or granted approval whose task left `queued`: the decision was about a
+/// merge that is no longer next. A task still waiting for the decision goes back to idle.
+pub fn apply_approval_void_in_registry(
+    registry: &mut Value,
+    task_id: &str,
+    reason: &str,
+    now: &str,
+) -> Result<()> {
+    let task = find_task_mut(registry, task_id)?;
+    if !task.get("approval").is_some_and(Value::is_object) {
+        return Ok(());
+    }
+    task["approval"]["status"] = Value::String("void".into());
+    task["approval"]["voided_at"] = Value::String(now.to_string());
+    task["approval"]["void_reason"] = Value::String(reason.to_string());
+    ensure_runtime_object(task);
+    if task["task_runtime"]["status"].as_str() == Some(RuntimeStatus::WaitingForUser.as_str()) {
+        task["task_runtime"]["status"] = Value::String(RuntimeStatus::Idle.as_str().to_string());
+    }
+    task["updated_at"] = Value::String(now.to_string());
+    Ok(())
+}
+
+/// Records a human decision on a pending approval. Approval releases the task to merge;
+/// rejection moves it to `changes_requested` with the comment as review feedback.
+pub fn apply_approval_decision_in_registry(
+    registry: &mut Value,
+    task_id: &str,
+    approved: bool,
+    user: &str,
+    comment: Option<&str>,
+    now: &str,
+) -> Result<()> {
+    let task = find_task_mut(registry, task_id)?;
+    let pending = task
+        .get("approval")
+        .and_then(|a| a.get("status"))
+        .and_then(Value::as_str)
+        == Some("pending");
+    if task_workflow_state(task)? != WorkflowState::Queued || !pending {
+        return Err(MaccError::Validation(format!(
+            "Task '{}' is not waiting for approval",
+            task_id
+        )));
+    }
+    task["approval"]["status"] =
+        Value::String(if approved { "approved" } else { "rejected" }.into());
+    task["approval"]["decided_by"] = Value::String(user.to_string());
+    task["approval"]["decided_at"] = Value::String(now.to_string());
+    task["approval"]["comment"] = comment.map(Value::from).unwrap_or(Value::Null);
+    ensure_runtime_object(task);
+    task["task_runtime"]["status"] = Value::String(RuntimeStatus::Idle.as_str().to_string());
+    task["updated_at"] = Value::String(now.to_string());
+    if approved {
+        return Ok(());
+    }
+    let to =
+        transition_workflow_state(WorkflowState::Queued, WorkflowEvent::ReviewChangesRequested)?;
+    task["state"] = Value::String(to.as_str().to_string());
+    task["task_runtime"]["current_phase"] = Value::String("review".to_string());
+    let feedback = ReviewComment {
+        author: user.to_string(),
+        body: comment
+            .map(str::to_string)
+            .unwrap_or_else(|| "Merge rejected by a human reviewer.".to_string()),
+        path: None,
+        line: None,
+    };
+    task["review_comments"] = serde_json::to_value([feedback]).unwrap_or(Value::Null);
+    task["state_changed_at"] = Value::String(now.to_string());
+    Ok(())
+}
+
 pub fn apply_job_completion_in_registry(
     registry: &mut Value,
     task_id: &str,
@@ -445,6 +808,21 @@ pub fn apply_dispatch_pid(task: &mut Value, pid: Option<i64>) {
     task["task_runtime"]["pid"] = pid.map(Value::from).unwrap_or(Value::Null);
 }
 
+/// Returns a claimed task to `todo` after its worktree or performer could not be set up.
+pub fn apply_claim_rollback(task: &mut Value, detail: &str, now: &str) {
+    task["state"] = Value::String(WorkflowState::Todo.as_str().to_string());
+    task["assignee"] = Value::Null;
+    task["claimed_at"] = Value::Null;
+    task["worktree"] = Value::Null;
+    ensure_runtime_object(task);
+    task["task_runtime"]["status"] = Value::String(RuntimeStatus::Idle.as_str().to_string());
+    task["task_runtime"]["pid"] = Value::Null;
+    task["task_runtime"]["current_phase"] = Value::Null;
+    task["task_runtime"]["last_error"] = Value::String(detail.to_string());
+    task["updated_at"] = Value::String(now.to_string());
+    task["state_changed_at"] = Value::String(now.to_string());
+}
+
 pub fn apply_phase_success(task: &mut Value, transition: PhaseTransition, now: &str) -> Result<()> {
     let from = task_workflow_state(task)?;
     let to = transition_workflow_state(from, WorkflowEvent::PhaseSucceeded(transition.mode))?;
@@ -596,6 +974,38 @@ pub fn apply_job_completion(
         };
     }
 
+    let throttled = task["task_runtime"]["metrics"]["throttled"]
+        .as_u64()
+        .unwrap_or(0);
+    if error_code == crate::performer::throttle::THROTTLE_ERROR_CODE
+        && throttled < crate::performer::throttle::MAX_THROTTLE_REQUEUES
+    {
+        // Throttling says nothing about the task: requeue it without spending a retry and
+        // let the per-tool cooldown decide when it runs again. Past the cap the failure is
+        // treated like any other, so a misclassified error cannot requeue forever.
+        if !task["task_runtime"]["metrics"].is_object() {
+            task["task_runtime"]["metrics"] = json!({});
+        }
+        task["task_runtime"]["metrics"]["throttled"] = Value::from(throttled + 1);
+        task["state"] = Value::String(WorkflowState::Todo.as_str().to_string());
+        task["assignee"] = Value::Null;
+        task["claimed_at"] = Value::Null;
+        task["worktree"] = Value::Null;
+        task["task_runtime"]["status"] = Value::String(RuntimeStatus::Idle.as_str().to_string());
+        task["task_runtime"]["pid"] = Value::Null;
+        task["task_runtime"]["current_phase"] = Value::String("dev".to_string());
+        task["task_runtime"]["last_error_code"] = Value::String(error_code.clone());
+        task["task_runtime"]["last_error_origin"] = Value::String(error_origin);
+        task["task_runtime"]["last_error_message"] = Value::String(error_message.clone());
+        task["task_runtime"]["last_error"] = Value::String(error_message.clone());
+        task["state_changed_at"] = Value::String(now.to_string());
+        return JobCompletionResult {
+            should_retry: false,
+            status_label: "throttled",
+            detail: format!("provider throttled; task requeued: {}", error_message),
+        };
+    }
+
     if input.attempt < input.max_attempts {
         task["state"] = Value::String(WorkflowState::Claimed.as_str().to_string());
         task["task_runtime"]["status"] = Value::String(RuntimeStatus::Running.as_str().to_string());
@@ -771,21 +1181,7 @@ where
             .and_then(Value::as_str)
             .unwrap_or(WorkflowState::Todo.as_str())
             .to_string();
-
-        task["task_runtime"]["pid"] = Value::Null;
-        task["task_runtime"]["status"] = Value::String(RuntimeStatus::Stale.as_str().to_string());
-        task["task_runtime"]["last_error"] =
-            Value::String(format!("runtime pid {} is not running; auto-reset", pid));
-        task["updated_at"] = Value::String(now.to_string());
-        task["state_changed_at"] = Value::String(now.to_string());
-        let new_state = if old_state == WorkflowState::Claimed.as_str() && phase == "dev" {
-            task["state"] = Value::String(WorkflowState::Todo.as_str().to_string());
-            task["assignee"] = Value::Null;
-            WorkflowState::Todo.as_str().to_string()
-        } else {
-            task["state"] = Value::String(WorkflowState::Blocked.as_str().to_string());
-            WorkflowState::Blocked.as_str().to_string()
-        };
+        let new_state = apply_dead_runtime_reset(task, pid, now);
 
         cleaned.push(DeadRuntimeCleanupEntry {
             task_id,
@@ -798,6 +1194,32 @@ where
     Ok(cleaned)
 }
 
+/// Resets a task whose runtime pid died: a claimed dev task returns to `todo`, anything else
+/// is blocked. Returns the new workflow state.
+pub fn apply_dead_runtime_reset(task: &mut Value, pid: i64, now: &str) -> String {
+    ensure_runtime_object(task);
+    let phase = task["task_runtime"]["current_phase"]
+        .as_str()
+        .unwrap_or("dev")
+        .to_string();
+    let claimed =
+        task.get("state").and_then(Value::as_str) == Some(WorkflowState::Claimed.as_str());
+    task["task_runtime"]["pid"] = Value::Null;
+    task["task_runtime"]["status"] = Value::String(RuntimeStatus::Stale.as_str().to_string());
+    task["task_runtime"]["last_error"] =
+        Value::String(format!("runtime pid {} is not running; auto-reset", pid));
+    task["updated_at"] = Value::String(now.to_string());
+    task["state_changed_at"] = Value::String(now.to_string());
+    if claimed && phase == "dev" {
+        task["state"] = Value::String(WorkflowState::Todo.as_str().to_string());
+        task["assignee"] = Value::Null;
+        WorkflowState::Todo.as_str().to_string()
+    } else {
+        task["state"] = Value::String(WorkflowState::Blocked.as_str().to_string());
+        WorkflowState::Blocked.as_str().to_string()
+    }
+}
+
 impl CoordinatorRunController {
     pub fn new(cfg: ControlPlaneLoopConfig) -> Self {
         Self {
@@ -848,6 +1270,38 @@ impl CoordinatorRunController {
     }
 }
 
+/// Workflow counts the run controller decides on.
+pub fn coordinator_counts(registry: &Value) -> CoordinatorCounts {
+    let tasks = registry
+        .get("tasks")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let mut counts = CoordinatorCounts {
+        total: tasks.len(),
+        todo: 0,
+        active: 0,
+        blocked: 0,
+        merged: 0,
+    };
+    for task in tasks {
+        match task
+            .get("state")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+        {
+            "todo" => counts.todo += 1,
+            "blocked" => counts.blocked += 1,
+            "merged" => counts.merged += 1,
+            "claimed" | "in_progress" | "pr_open" | "changes_requested" | "queued" => {
+                counts.active += 1
+            }
+            _ => {}
+        }
+    }
+    counts
+}
+
 pub async fn run_control_plane<B: ControlPlaneBackend + ?Sized>(
     backend: &mut B,
     cfg: ControlPlaneLoopConfig,
@@ -906,11 +1360,13 @@ struct NativeControlPlaneBackend<'a> {
     coordinator_tool_override: Option<String>,
     phase_timeout_seconds: usize,
     last_logged_counts: Option<CoordinatorCounts>,
+    lease: &'a crate::coordinator::lease::CoordinatorLease,
 }
 
 #[async_trait]
 impl ControlPlaneBackend for NativeControlPlaneBackend<'_> {
     async fn on_cycle_start(&mut self, _cycle: usize) -> Result<()> {
+        self.lease.ensure_held()?;
         crate::coordinator::control_plane::sync_registry_from_prd_native(
             self.repo_root,
             &self.prd_file,
@@ -1037,35 +1493,7 @@ impl ControlPlaneBackend for NativeControlPlaneBackend<'_> {
             self.repo_root,
             &std::collections::BTreeMap::new(),
         )?;
-        let tasks = snapshot
-            .registry
-            .get("tasks")
-            .and_then(|v| v.as_array())
-            .cloned()
-            .unwrap_or_default();
-
-        let mut counts = CoordinatorCounts {
-            total: tasks.len(),
-            todo: 0,
-            active: 0,
-            blocked: 0,
-            merged: 0,
-        };
-        for task in tasks {
-            let state = task
-                .get("state")
-                .and_then(|s| s.as_str())
-                .unwrap_or_default();
-            match state {
-                "todo" => counts.todo += 1,
-                "blocked" => counts.blocked += 1,
-                "merged" => counts.merged += 1,
-                "claimed" | "in_progress" | "pr_open" | "changes_requested" | "queued" => {
-                    counts.active += 1
-                }
-                _ => {}
-            }
-        }
+        let counts = coordinator_counts(&snapshot.registry);
         self.last_logged_counts = Some(counts);
         Ok(counts)
     }
@@ -1162,6 +1590,8 @@ pub async fn run_native_control_plane(
         std::env::set_var("COORDINATOR_RUN_ID", &generated);
         generated
     };
+    // Held until the run returns: a second coordinator on this project refuses to start.
+    let lease = crate::coordinator::lease::CoordinatorLease::acquire(repo_root, &run_id, "run")?;
 
     let _ = crate::coordinator::helpers::append_coordinator_event_with_severity(
         repo_root,
@@ -1224,6 +1654,83 @@ pub async fn run_native_control_plane(
         }
     }
 
+    if let Some(pool) = coordinator.and_then(|c| c.worktree.as_ref()) {
+        let max_parallel = env_cfg
+            .max_parallel
+            .or_else(|| coordinator.and_then(|c| c.max_parallel))
+            .unwrap_or(3);
+        let tool = canonical.tools.enabled.first().cloned().unwrap_or_default();
+        let base_branch = env_cfg
+            .reference_branch
+            .clone()
+            .or_else(|| coordinator.and_then(|c| c.reference_branch.clone()))
+            .unwrap_or_else(|| "master".to_string());
+        match crate::coordinator::worktree_pool::prewarm_pool(
+            repo_root,
+            pool,
+            max_parallel,
+            &tool,
+            &base_branch,
+        )
+        .await
+        {
+            Ok(created) if created > 0 => {
+                if let Some(log) = logger {
+                    let _ = log.note(format!("- Worktree pool pre-warmed slots={}", created));
+                }
+            }
+            Ok(_) => {}
+            Err(err) => {
+                // Dispatch creates and prepares slots lazily, so a failed pre-warm is not fatal.
+                if let Some(log) = logger {
+                    let _ = log.note(format!("- Worktree pool pre-warm stopped: {}", err));
+                }
+            }
+        }
+    }
+
+    // Held for the whole run; dropping it closes the endpoint.
+    let _metrics_server = match env_cfg.metrics_listen.as_deref() {
+        Some(listen) if !listen.trim().is_empty() => {
+            let server =
+                crate::coordinator::metrics::MetricsServer::start(repo_root, listen.trim())?;
+            if let Some(log) = logger {
+                let _ = log.note(format!(
+                    "- Metrics endpoint http://{}/metrics",
+                    server.local_addr()
+                ));
+            }
+            Some(server)
+        }
+        _ => None,
+    };
+
+    let mut run_state = CoordinatorRunState::new();
+    run_state.config_profile = env_cfg.config_profile.clone();
+    if let Some(cfg) = coordinator {
+        run_state.token_prices = cfg.token_prices.clone();
+        run_state.approval = cfg.approval.clone();
+        run_state.merge = cfg.merge.clone();
+        run_state.size_guard = cfg.size_guard.clone();
+        run_state.decompose = cfg.decompose.clone();
+        if let Some(pool) = &cfg.worktree {
+            run_state.child_env = crate::coordinator::worktree_pool::pool_env(repo_root, pool);
+        }
+        if let Some(forge_cfg) = &cfg.forge {
+            let forge = crate::coordinator::forge::Forge::from_config(repo_root, forge_cfg)?;
+            if let Some(log) = logger {
+                let _ = log.note(format!(
+                    "- Forge {} (remote={}, merge={})",
+                    forge.backend.kind(),
+                    forge.remote,
+                    forge.merge_method.as_str()
+                ));
+            }
+            run_state.forge = Some(forge);
+        }
+    }
+    run_state.run_usage =
+        crate::coordinator::usage::recorded_run_usage(repo_root, &run_id, &run_state.token_prices);
     let mut backend = NativeControlPlaneBackend {
         repo_root,
         canonical,
@@ -1231,11 +1738,12 @@ pub async fn run_native_control_plane(
         env_cfg,
         logger,
         prd_file,
-        run_state: CoordinatorRunState::new(),
+        run_state,
         phase_runner_max_attempts,
         coordinator_tool_override,
         phase_timeout_seconds,
         last_logged_counts: None,
+        lease: &lease,
     };
 
     let timeout_seconds = env_cfg
@@ -1263,6 +1771,7 @@ pub async fn run_native_control_plane(
                 "blocking",
             );
         }
+        archive_run_history(repo_root, &run_id, "failed", logger);
         for (_tid, _pid) in terminate_active_jobs(&backend.run_state) {}
         backend.run_state.active_jobs.clear();
         backend.run_state.join_set.abort_all();
@@ -1280,9 +1789,27 @@ pub async fn run_native_control_plane(
         "Coordinator run complete",
         "info",
     );
+    archive_run_history(repo_root, &run_id, "done", logger);
     Ok(())
 }
 
+/// Archives the run under `.macc/runs/`; a failed archive only costs the history entry.
+fn archive_run_history(
+    repo_root: &Path,
+    run_id: &str,
+    status: &str,
+    logger: Option<&dyn CoordinatorLog>,
+) {
+    match crate::coordinator::runs::archive_run(repo_root, run_id, status) {
+        Ok(dir) => {
+            if let Some(log) = logger {
+                let _ = log.note(format!("- Run archived: {}", dir.display()));
+            }
+        }
+        Err(err) => tracing::warn!("Failed to archive coordinator run {}: {}", run_id, err),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1372,6 +1899,78 @@ mod tests {
         assert!(task["task_runtime"]["pid"].is_null());
     }
 
+    #[test]
+    fn apply_job_completion_throttled_requeues_without_retry() {
+        let mut task = json!({
+            "id":"T5",
+            "state":"claimed",
+            "worktree":{"worktree_path":"/tmp/wt"},
+            "task_runtime":{"status":"running","pid":7,"retries":2}
+        });
+        let out = apply_job_completion(
+            &mut task,
+            &JobCompletionInput {
+                success: false,
+                attempt: 1,
+                max_attempts: 3,
+                timed_out: false,
+                phase_timeout_seconds: 0,
+                elapsed_seconds: 2,
+                status_text: "exit status: 1".to_string(),
+                error_code: Some("E104".to_string()),
+                error_origin: Some("provider".to_string()),
+                error_message: Some("provider throttled: 429".to_string()),
+                auto_retry_error_codes: vec!["E104".to_string()],
+                auto_retry_max: 2,
+            },
+            "2026-02-21T00:00:00Z",
+        );
+        assert!(!out.should_retry);
+        assert_eq!(out.status_label, "throttled");
+        assert_eq!(task["state"], "todo");
+        assert!(task["worktree"].is_null());
+        assert_eq!(task["task_runtime"]["retries"], 2);
+        assert_eq!(task["task_runtime"]["metrics"]["throttled"], 1);
+        assert_eq!(task["task_runtime"]["last_error_code"], "E104");
+    }
+
+    #[test]
+    fn apply_job_completion_throttle_requeues_are_capped() {
+        let mut task = json!({
+            "id":"T6",
+            "state":"claimed",
+            "task_runtime":{
+                "status":"running",
+                "pid":7,
+                "metrics":{"throttled": crate::performer::throttle::MAX_THROTTLE_REQUEUES}
+            }
+        });
+        let out = apply_job_completion(
+            &mut task,
+            &JobCompletionInput {
+                success: false,
+                attempt: 1,
+                max_attempts: 1,
+                timed_out: false,
+                phase_timeout_seconds: 0,
+                elapsed_seconds: 2,
+                status_text: "exit status: 1".to_string(),
+                error_code: Some("E104".to_string()),
+                error_origin: Some("provider".to_string()),
+                error_message: Some("provider throttled: 429".to_string()),
+                auto_retry_error_codes: Vec::new(),
+                auto_retry_max: 0,
+            },
+            "2026-02-21T00:00:00Z",
+        );
+        assert_eq!(out.status_label, "failed");
+        assert_eq!(task["state"], "blocked");
+        assert_eq!(
+            task["task_runtime"]["metrics"]["throttled"],
+            crate::performer::throttle::MAX_THROTTLE_REQUEUES
+        );
+    }
+
     #[test]
     fn cleanup_dead_runtime_tasks_resets_claimed_dev_to_todo() {
         let mut registry = json!({
@@ -1448,4 +2047,29 @@ mod tests {
             err
         );
     }
+
+    #[test]
+    fn size_check_leaves_task_untouched_when_it_cannot_transition() {
+        let mut registry = json!({
+            "tasks": [{
+                "id": "T6",
+                "state": "changes_requested",
+                "task_runtime": {"status": "phase_done", "current_phase": "review"}
+            }]
+        });
+        let check = SizeCheck {
+            stats: size_guard::DiffStats {
+                files: 3,
+                insertions: 80,
+                deletions: 0,
+            },
+            violations: vec!["80 lines changed (max 50)".to_string()],
+            block: false,
+        };
+        apply_size_check_in_registry(&mut registry, "T6", &check, "2026-02-21T00:00:00Z")
+            .unwrap_err();
+        let task = &registry["tasks"][0];
+        assert_eq!(task["state"], "changes_requested");
+        assert!(task["task_runtime"]["metrics"].is_null());
+    }
 }
@@ -0,0 +1,573 @@
+//! Forge backends: real pull requests for coordinator tasks.
+//!
+//! Without `automation.coordinator.forge`, a reviewed task gets a `local://<branch>` PR URL and
+//! the coordinator merges the branch itself. With a forge, the control plane pushes the task
+//! branch and opens a pull request once the task reaches `pr_open`, polls its reviews (a
+//! "request changes" review moves the task to `changes_requested` with the review comments on
+//! the task, an approval lets it continue to `integrate` and `queued`), pushes and updates the
+//! pull request again after a fix phase, and merges through the forge API.
+
+mod file;
+mod gitea;
+mod gitlab;
+
+pub use file::{FileForge, FilePullRequest};
+pub use gitea::GiteaForge;
+pub use gitlab::GitLabForge;
+
+use crate::config::ForgeConfig;
+use crate::{MaccError, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+pub const DEFAULT_TOKEN_ENV: &str = "MACC_FORGE_TOKEN";
+pub const DEFAULT_REMOTE: &str = "origin";
+pub const DEFAULT_FILE_FORGE_DIR: &str = ".macc/forge";
+pub const DEFAULT_POLL_SECONDS: u64 = 30;
+const HTTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PullRequestState {
+    Open,
+    Merged,
+    Closed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PullRequestSpec {
+    pub head: String,
+    pub base: String,
+    pub title: String,
+    pub body: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PullRequest {
+    pub number: u64,
+    pub url: String,
+    pub state: PullRequestState,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewState {
+    Approved,
+    ChangesRequested,
+    Commented,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReviewComment {
+    pub author: String,
+    pub body: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Review {
+    pub author: String,
+    pub state: ReviewState,
+    #[serde(default)]
+    pub body: String,
+    /// RFC 3339 submission time; reviews without one are always considered current.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub submitted_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub comments: Vec<ReviewComment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReviewStatus {
+    pub pull_request: PullRequest,
+    pub reviews: Vec<Review>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReviewDecision {
+    Pending,
+    Approved,
+    ChangesRequested(Vec<ReviewComment>),
+    Merged,
+    Closed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MergeMethod {
+    #[default]
+    Merge,
+    Squash,
+    Rebase,
+}
+
+impl MergeMethod {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MergeMethod::Merge => "merge",
+            MergeMethod::Squash => "squash",
+            MergeMethod::Rebase => "rebase",
+        }
+    }
+}
+
+impl FromStr for MergeMethod {
+    type Err = String;
+
+    fn from_str(value: &str) -> std::result::Result<Self, Self::Err> {
+        match value {
+            "merge" => Ok(MergeMethod::Merge),
+            "squash" => Ok(MergeMethod::Squash),
+            "rebase" => Ok(MergeMethod::Rebase),
+            other => Err(format!(
+                "Unknown forge merge method '{}' (expected merge, squash or rebase)",
+                other
+            )),
+        }
+    }
+}
+
+/// Pull request operations the coordinator needs from a code forge.
+///
+/// Calls block; the control plane runs them on the blocking pool.
+pub trait ForgeBackend: Send + Sync {
+    fn kind(&self) -> &'static str;
+    fn push_branch(&self, repo_root: &Path, branch: &str) -> Result<()>;
+    fn open_pr(&self, spec: &PullRequestSpec) -> Result<PullRequest>;
+    fn update_pr(&self, number: u64, spec: &PullRequestSpec) -> Result<PullRequest>;
+    fn review_status(&self, number: u64) -> Result<ReviewStatus>;
+    /// `Ok(Err(reason))` when the forge refuses the merge (conflicts, missing approvals, checks).
+    fn merge(&self, number: u64, method: MergeMethod) -> Result<std::result::Result<(), String>>;
+}
+
+/// A configured forge: the backend plus the coordinator-side settings.
+#[derive(Clone)]
+pub struct Forge {
+    pub backend: Arc<dyn ForgeBackend>,
+    pub remote: String,
+    pub merge_method: MergeMethod,
+    pub poll_interval: Duration,
+}
+
+impl Forge {
+    pub fn from_config(repo_root: &Path, cfg: &ForgeConfig) -> Result<Self> {
+        cfg.validate("automation.coordinator.forge")?;
+        let remote = cfg
+            .remote
+            .clone()
+            .filter(|v| !v.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_REMOTE.to_string());
+        let merge_method = cfg
+            .merge_method
+            .as_deref()
+            .map(MergeMethod::from_str)
+            .transpose()
+            .map_err(MaccError::Validation)?
+            .unwrap_or_default();
+        let token_env = cfg.token_env.as_deref().unwrap_or(DEFAULT_TOKEN_ENV);
+        let token = std::env::var(token_env)
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+        let backend: Arc<dyn ForgeBackend> = match cfg.kind.as_str() {
+            "gitea" => Arc::new(GiteaForge::new(
+                cfg.url.as_deref().unwrap_or_default(),
+                cfg.repo.as_deref().unwrap_or_default(),
+                token,
+                &remote,
+            )?),
+            "gitlab" => {
+                if merge_method == MergeMethod::Rebase {
+                    return Err(MaccError::Validation(
+                        "automation.coordinator.forge: merge_method 'rebase' is not supported for gitlab; set the project merge method instead".into(),
+                    ));
+                }
+                Arc::new(GitLabForge::new(
+                    cfg.url.as_deref().unwrap_or_default(),
+                    cfg.repo.as_deref().unwrap_or_default(),
+                    token,
+                    &remote,
+                )?)
+            }
+            _ => {
+                let dir = cfg
+                    .url
+                    .as_deref()
+                    .filter(|v| !v.trim().is_empty())
+                    .unwrap_or(DEFAULT_FILE_FORGE_DIR);
+                let dir = Path::new(dir);
+                Arc::new(FileForge::new(if dir.is_absolute() {
+                    dir.to_path_buf()
+                } else {
+                    repo_root.join(dir)
+                }))
+            }
+        };
+        Ok(Self {
+            backend,
+            remote,
+            merge_method,
+            poll_interval: Duration::from_secs(cfg.poll_seconds.unwrap_or(DEFAULT_POLL_SECONDS)),
+        })
+    }
+}
+
+impl ReviewStatus {
+    /// Review outcome from the reviews submitted at or after `since` (the last push of the
+    /// task branch), so feedback on an older revision does not trigger another fix. Each
+    /// reviewer's latest approval or change request counts; one change request wins.
+    pub fn decision(&self, since: Option<&str>) -> ReviewDecision {
+        match self.pull_request.state {
+            PullRequestState::Merged => return ReviewDecision::Merged,
+            PullRequestState::Closed => return ReviewDecision::Closed,
+            PullRequestState::Open => {}
+        }
+        let since = since.and_then(parse_time);
+        let mut current = self
+            .reviews
+            .iter()
+            .filter(|review| match (since, review.submitted_at.as_deref()) {
+                (Some(since), Some(at)) => parse_time(at).map(|at| at >= since).unwrap_or(true),
+                _ => true,
+            })
+            .collect::<Vec<_>>();
+        current.sort_by_key(|review| review.submitted_at.as_deref().and_then(parse_time));
+        let mut latest = BTreeMap::new();
+        for review in &current {
+            if review.state != ReviewState::Commented {
+                latest.insert(review.author.as_str(), review.state);
+            }
+        }
+        if latest
+            .values()
+            .any(|state| *state == ReviewState::ChangesRequested)
+        {
+            let mut comments = Vec::new();
+            for review in current {
+                if review.state != ReviewState::Approved && !review.body.trim().is_empty() {
+                    comments.push(ReviewComment {
+                        author: review.author.clone(),
+                        body: review.body.clone(),
+                        path: None,
+                        line: None,
+                    });
+                }
+                comments.extend(review.comments.iter().cloned());
+            }
+            return ReviewDecision::ChangesRequested(comments);
+        }
+        if latest.values().any(|state| *state == ReviewState::Approved) {
+            return ReviewDecision::Approved;
+        }
+        ReviewDecision::Pending
+    }
+}
+
+fn parse_time(raw: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    chrono::DateTime::parse_from_rfc3339(raw)
+        .ok()
+        .map(|dt| dt.with_timezone(&chrono::Utc))
+}
+
+/// Pull request for a task branch, or `None` when the task has no worktree branch yet.
+pub fn task_pull_request_spec(task: &Value) -> Option<PullRequestSpec> {
+    let worktree = task.get("worktree")?;
+    let head = worktree
+        .get("branch")
+        .and_then(Value::as_str)
+        .filter(|v| !v.is_empty())?;
+    let base = worktree
+        .get("base_branch")
+        .and_then(Value::as_str)
+        .unwrap_or("master");
+    let id = task.get("id").and_then(Value::as_str).unwrap_or("task");
+    let title = task
+        .get("title")
+        .and_then(Value::as_str)
+        .filter(|v| !v.trim().is_empty())
+        .map(|title| format!("{}: {}", id, title))
+        .unwrap_or_else(|| id.to_string());
+    let mut body = String::new();
+    for key in ["description", "objective"] {
+        if let Some(text) = task
+            .get(key)
+            .and_then(Value::as_str)
+            .filter(|v| !v.trim().is_empty())
+        {
+            body.push_str(text.trim());
+            body.push_str("\n\n");
+        }
+    }
+    body.push_str(&format!("Coordinator task `{}` (branch `{}`).", id, head));
+    Some(PullRequestSpec {
+        head: head.to_string(),
+        base: base.to_string(),
+        title,
+        body,
+    })
+}
+
+/// Pull request number recorded on the task by a previous publish.
+pub fn task_pull_request_number(task: &Value) -> Option<u64> {
+    task.get("forge")
+        .and_then(|forge| forge.get("number"))
+        .and_then(Value::as_u64)
+}
+
+/// True when the task branch has commits the forge has not seen yet (or no pull request).
+pub fn task_needs_publish(repo_root: &Path, task: &Value) -> bool {
+    if task_pull_request_number(task).is_none() {
+        return true;
+    }
+    let Some(branch) = task
+        .get("worktree")
+        .and_then(|w| w.get("branch"))
+        .and_then(Value::as_str)
+    else {
+        return false;
+    };
+    let pushed = task
+        .get("forge")
+        .and_then(|forge| forge.get("pushed_head"))
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    branch_head(repo_root, branch)
+        .map(|head| head != pushed)
+        .unwrap_or(false)
+}
+
+/// Pushes the task branch and opens its pull request, or updates the existing one.
+/// Returns the pull request and the pushed commit.
+pub fn publish_task(
+    forge: &Forge,
+    repo_root: &Path,
+    task: &Value,
+) -> Result<(PullRequest, String)> {
+    let spec = task_pull_request_spec(task).ok_or_else(|| {
+        MaccError::Validation(format!(
+            "Task '{}' has no worktree branch to publish",
+            task.get("id").and_then(Value::as_str).unwrap_or_default()
+        ))
+    })?;
+    let head = branch_head(repo_root, &spec.head)?;
+    forge.backend.push_branch(repo_root, &spec.head)?;
+    let pr = match task_pull_request_number(task) {
+        Some(number) => forge.backend.update_pr(number, &spec)?,
+        None => forge.backend.open_pr(&spec)?,
+    };
+    Ok((pr, head))
+}
+
+/// Merges the task pull request. A pull request already merged on the forge counts as merged;
+/// afterwards the local base branch is fast-forwarded from the remote (best effort).
+pub fn merge_task_pull_request(
+    forge: &Forge,
+    repo_root: &Path,
+    number: u64,
+    base: &str,
+) -> Result<std::result::Result<(), String>> {
+    let status = forge.backend.review_status(number)?;
+    let outcome = match status.pull_request.state {
+        PullRequestState::Merged => Ok(()),
+        PullRequestState::Closed => Err(format!(
+            "failure:forge_merge step=state pr={} reason=\"pull request is closed\"",
+            number
+        )),
+        PullRequestState::Open => {
+            forge
+                .backend
+                .merge(number, forge.merge_method)?
+                .map_err(|reason| {
+                    format!(
+                        "failure:forge_merge step=merge pr={} method={} reason=\"{}\"",
+                        number,
+                        forge.merge_method.as_str(),
+                        reason
+                    )
+                })
+        }
+    };
+    if outcome.is_ok() {
+        if let Err(err) = sync_base_branch(repo_root, &forge.remote, base) {
+            tracing::warn!("Failed to update {} from {}: {}", base, forge.remote, err);
+        }
+    }
+    Ok(outcome)
+}
+
+pub(crate) fn branch_head(repo_root: &Path, branch: &str) -> Result<String> {
+    let output = crate::git::run_git_output_mapped(
+        repo_root,
+        &["rev-parse", "--verify", &format!("refs/heads/{}", branch)],
+        "resolve task branch head",
+    )?;
+    if !output.status.success() {
+        return Err(MaccError::Validation(format!(
+            "Task branch '{}' does not exist",
+            branch
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// `git push` of one branch, shared by the HTTP backends.
+fn push_branch_to_remote(repo_root: &Path, remote: &str, branch: &str) -> Result<()> {
+    let refspec = format!("refs/heads/{0}:refs/heads/{0}", branch);
+    let output = crate::git::run_git_output_mapped(
+        repo_root,
+        &["push", "--force-with-lease", remote, &refspec],
+        "push task branch",
+    )?;
+    if !output.status.success() {
+        return Err(MaccError::Validation(format!(
+            "git push {} {} failed: {}",
+            remote,
+            branch,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+fn sync_base_branch(repo_root: &Path, remote: &str, base: &str) -> Result<()> {
+    let has_remote = crate::git::run_git_output_mapped(
+        repo_root,
+        &["remote", "get-url", remote],
+        "resolve git remote",
+    )?
+    .status
+    .success();
+    if !has_remote {
+        return Ok(());
+    }
+    if crate::git::current_branch(repo_root)? == base {
+        if crate::git::fetch(repo_root, remote)? && !crate::git::is_dirty(repo_root)? {
+            crate::git::merge_ff_only(repo_root, &format!("{}/{}", remote, base))?;
+        }
+        return Ok(());
+    }
+    let refspec = format!("refs/heads/{0}:refs/heads/{0}", base);
+    crate::git::run_git_output_mapped(repo_root, &["fetch", remote, &refspec], "fetch base")?;
+    Ok(())
+}
+
+fn http_client() -> Result<reqwest::blocking::Client> {
+    reqwest::blocking::Client::builder()
+        .timeout(HTTP_TIMEOUT)
+        .user_agent("macc-coordinator")
+        .build()
+        .map_err(|e| MaccError::Validation(format!("Failed to build forge HTTP client: {}", e)))
+}
+
+/// Sends a request and returns the status with the body; transport errors are errors.
+fn send(request: reqwest::blocking::RequestBuilder, action: &str) -> Result<(u16, String)> {
+    let response = request
+        .send()
+        .map_err(|e| MaccError::Validation(format!("Forge request failed ({}): {}", action, e)))?;
+    let status = response.status().as_u16();
+    let body = response.text().unwrap_or_default();
+    Ok((status, body))
+}
+
+/// Like [`send`], but non-2xx responses are errors and the body is parsed as JSON.
+fn send_json(request: reqwest::blocking::RequestBuilder, action: &str) -> Result<Value> {
+    let (status, body) = send(request, action)?;
+    if !(200..300).contains(&status) {
+        return Err(MaccError::Validation(format!(
+            "Forge request failed ({}): HTTP {} {}",
+            action,
+            status,
+            body.trim()
+        )));
+    }
+    if body.trim().is_empty() {
+        return Ok(Value::Null);
+    }
+    serde_json::from_str(&body).map_err(|e| {
+        MaccError::Validation(format!("Forge returned invalid JSON ({}): {}", action, e))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn review(author: &str, state: ReviewState, at: &str, body: &str) -> Review {
+        Review {
+            author: author.into(),
+            state,
+            body: body.into(),
+            submitted_at: Some(at.into()),
+            comments: Vec::new(),
+        }
+    }
+
+    fn status(reviews: Vec<Review>) -> ReviewStatus {
+        ReviewStatus {
+            pull_request: PullRequest {
+                number: 1,
+                url: "https://forge.example/pr/1".into(),
+                state: PullRequestState::Open,
+            },
+            reviews,
+        }
+    }
+
+    #[test]
+    fn test_decision_uses_latest_review_per_author_since_last_push() {
+        let reviews = status(vec![
+            review(
+                "alice",
+                ReviewState::ChangesRequested,
+                "2026-01-01T10:00:00Z",
+                "rename the flag",
+            ),
+            review("alice", ReviewState::Approved, "2026-01-01T12:00:00Z", ""),
+        ]);
+        assert_eq!(reviews.decision(None), ReviewDecision::Approved);
+
+        let reviews = status(vec![
+            review("alice", ReviewState::Approved, "2026-01-01T10:00:00Z", ""),
+            review(
+                "bob",
+                ReviewState::ChangesRequested,
+                "2026-01-01T11:00:00Z",
+                "missing test",
+            ),
+        ]);
+        match reviews.decision(Some("2026-01-01T09:00:00Z")) {
+            ReviewDecision::ChangesRequested(comments) => {
+                assert_eq!(comments.len(), 1);
+                assert_eq!(comments[0].author, "bob");
+            }
+            other => panic!("unexpected decision {:?}", other),
+        }
+        // Feedback older than the last push belongs to the previous revision.
+        assert_eq!(
+            reviews.decision(Some("2026-01-01T11:30:00Z")),
+            ReviewDecision::Pending
+        );
+    }
+
+    #[test]
+    fn test_task_pull_request_spec_uses_branch_and_title() {
+        let task = serde_json::json!({
+            "id": "T1",
+            "title": "Add login",
+            "description": "Login form",
+            "worktree": {"branch": "ai/T1", "base_branch": "main"}
+        });
+        let spec = task_pull_request_spec(&task).unwrap();
+        assert_eq!(spec.head, "ai/T1");
+        assert_eq!(spec.base, "main");
+        assert_eq!(spec.title, "T1: Add login");
+        assert!(spec.body.starts_with("Login form"));
+        assert!(task_pull_request_spec(&serde_json::json!({"id": "T2"})).is_none());
+    }
+}
@@ -0,0 +1,198 @@
+//! Directory-backed fake forge: one JSON file per pull request under `<dir>/pulls/`, pushes
+//! appended to `<dir>/pushes.jsonl`. Tests (or a person) add reviews by editing the files.
+
+use super::{
+    ForgeBackend, MergeMethod, PullRequest, PullRequestSpec, PullRequestState, Review, ReviewStatus,
+};
+use crate::{MaccError, Result};
+use serde::{Deserialize, Serialize};
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FilePullRequest {
+    pub number: u64,
+    pub head: String,
+    pub base: String,
+    pub title: String,
+    #[serde(default)]
+    pub body: String,
+    pub state: PullRequestState,
+    /// Set to `false` to make merges fail like a conflicting pull request.
+    #[serde(default = "default_true")]
+    pub mergeable: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merge_method: Option<String>,
+    #[serde(default)]
+    pub reviews: Vec<Review>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+pub struct FileForge {
+    dir: PathBuf,
+}
+
+impl FileForge {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    pub fn pull_request(&self, number: u64) -> Result<FilePullRequest> {
+        let path = self.pull_path(number);
+        let raw = std::fs::read_to_string(&path).map_err(|e| MaccError::Io {
+            path: path.to_string_lossy().into(),
+            action: "read fake forge pull request".into(),
+            source: e,
+        })?;
+        serde_json::from_str(&raw).map_err(|e| {
+            MaccError::Validation(format!(
+                "Invalid fake forge pull request {}: {}",
+                path.display(),
+                e
+            ))
+        })
+    }
+
+    pub fn save_pull_request(&self, pr: &FilePullRequest) -> Result<()> {
+        let path = self.pull_path(pr.number);
+        let dir = self.dir.join("pulls");
+        std::fs::create_dir_all(&dir).map_err(|e| MaccError::Io {
+            path: dir.to_string_lossy().into(),
+            action: "create fake forge directory".into(),
+            source: e,
+        })?;
+        let raw = serde_json::to_string_pretty(pr).map_err(|e| {
+            MaccError::Validation(format!("Failed to serialize pull request: {}", e))
+        })?;
+        std::fs::write(&path, raw).map_err(|e| MaccError::Io {
+            path: path.to_string_lossy().into(),
+            action: "write fake forge pull request".into(),
+            source: e,
+        })
+    }
+
+    pub fn add_review(&self, number: u64, review: Review) -> Result<()> {
+        let mut pr = self.pull_request(number)?;
+        pr.reviews.push(review);
+        self.save_pull_request(&pr)
+    }
+
+    pub fn pull_requests(&self) -> Result<Vec<FilePullRequest>> {
+        let dir = self.dir.join("pulls");
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            return Ok(Vec::new());
+        };
+        let mut pulls = Vec::new();
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if let Some(number) = name
+                .strip_suffix(".json")
+                .and_then(|n| n.parse::<u64>().ok())
+            {
+                pulls.push(self.pull_request(number)?);
+            }
+        }
+        pulls.sort_by_key(|pr| pr.number);
+        Ok(pulls)
+    }
+
+    fn pull_path(&self, number: u64) -> PathBuf {
+        self.dir.join("pulls").join(format!("{}.json", number))
+    }
+
+    fn to_pull_request(&self, pr: &FilePullRequest) -> PullRequest {
+        PullRequest {
+            number: pr.number,
+            url: format!("file://{}", self.pull_path(pr.number).display()),
+            state: pr.state,
+        }
+    }
+}
+
+impl ForgeBackend for FileForge {
+    fn kind(&self) -> &'static str {
+        "file"
+    }
+
+    fn push_branch(&self, repo_root: &Path, branch: &str) -> Result<()> {
+        let commit = super::branch_head(repo_root, branch)?;
+        std::fs::create_dir_all(&self.dir).map_err(|e| MaccError::Io {
+            path: self.dir.to_string_lossy().into(),
+            action: "create fake forge directory".into(),
+            source: e,
+        })?;
+        let path = self.dir.join("pushes.jsonl");
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| MaccError::Io {
+                path: path.to_string_lossy().into(),
+                action: "open fake forge push log".into(),
+                source: e,
+            })?;
+        writeln!(
+            file,
+            "{}",
+            serde_json::json!({ "branch": branch, "commit": commit })
+        )
+        .map_err(|e| MaccError::Io {
+            path: path.to_string_lossy().into(),
+            action: "append fake forge push log".into(),
+            source: e,
+        })
+    }
+
+    fn open_pr(&self, spec: &PullRequestSpec) -> Result<PullRequest> {
+        let pulls = self.pull_requests()?;
+        if let Some(existing) = pulls
+            .iter()
+            .find(|pr| pr.head == spec.head && pr.state == PullRequestState::Open)
+        {
+            return Ok(self.to_pull_request(existing));
+        }
+        let pr = FilePullRequest {
+            number: pulls.last().map(|pr| pr.number + 1).unwrap_or(1),
+            head: spec.head.clone(),
+            base: spec.base.clone(),
+            title: spec.title.clone(),
+            body: spec.body.clone(),
+            state: PullRequestState::Open,
+            mergeable: true,
+            merge_method: None,
+            reviews: Vec::new(),
+        };
+        self.save_pull_request(&pr)?;
+        Ok(self.to_pull_request(&pr))
+    }
+
+    fn update_pr(&self, number: u64, spec: &PullRequestSpec) -> Result<PullRequest> {
+        let mut pr = self.pull_request(number)?;
+        pr.title = spec.title.clone();
+        pr.body = spec.body.clone();
+        self.save_pull_request(&pr)?;
+        Ok(self.to_pull_request(&pr))
+    }
+
+    fn review_status(&self, number: u64) -> Result<ReviewStatus> {
+        let pr = self.pull_request(number)?;
+        Ok(ReviewStatus {
+            pull_request: self.to_pull_request(&pr),
+            reviews: pr.reviews,
+        })
+    }
+
+    fn merge(&self, number: u64, method: MergeMethod) -> Result<std::result::Result<(), String>> {
+        let mut pr = self.pull_request(number)?;
+        if !pr.mergeable {
+            return Ok(Err("pull request is not mergeable".into()));
+        }
+        pr.state = PullRequestState::Merged;
+        pr.merge_method = Some(method.as_str().to_string());
+        self.save_pull_request(&pr)?;
+        Ok(Ok(()))
+    }
+}
@@ -0,0 +1,269 @@
+//! Gitea (and Forgejo) pull requests over the `/api/v1` REST API.
+
+use super::{
+    ForgeBackend, MergeMethod, PullRequest, PullRequestSpec, PullRequestState, Review,
+    ReviewComment, ReviewState, ReviewStatus,
+};
+use crate::{MaccError, Result};
+use serde_json::{json, Value};
+use std::path::Path;
+
+pub struct GiteaForge {
+    client: reqwest::blocking::Client,
+    /// `<url>/api/v1/repos/<owner>/<name>`
+    api: String,
+    token: Option<String>,
+    remote: String,
+}
+
+impl GiteaForge {
+    pub fn new(url: &str, repo: &str, token: Option<String>, remote: &str) -> Result<Self> {
+        let repo = repo.trim().trim_matches('/');
+        if repo.split('/').count() != 2 {
+            return Err(MaccError::Validation(format!(
+                "Gitea repo must be 'owner/name' (got '{}')",
+                repo
+            )));
+        }
+        Ok(Self {
+            client: super::http_client()?,
+            api: format!("{}/api/v1/repos/{}", url.trim_end_matches('/'), repo),
+            token,
+            remote: remote.to_string(),
+        })
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::blocking::RequestBuilder {
+        let request = self
+            .client
+            .request(method, format!("{}{}", self.api, path))
+            .header("Accept", "application/json");
+        match &self.token {
+            Some(token) => request.header("Authorization", format!("token {}", token)),
+            None => request,
+        }
+    }
+
+    /// GETs every page of a list endpoint; Gitea caps each page server-side.
+    fn list(&self, path: &str, action: &str) -> Result<Vec<Value>> {
+        let mut items = Vec::new();
+        for page in 1.. {
+            let value = super::send_json(
+                self.request(
+                    reqwest::Method::GET,
+                    &format!("{}?page={}&limit={}", path, page, PAGE_SIZE),
+                ),
+                action,
+            )?;
+            let batch = match value {
+                Value::Array(batch) => batch,
+                _ => break,
+            };
+            let last = batch.len() < PAGE_SIZE;
+            items.extend(batch);
+            if last {
+                break;
+            }
+        }
+        Ok(items)
+    }
+}
+
+const PAGE_SIZE: usize = 50;
+
+fn parse_pull_request(value: &Value) -> Result<PullRequest> {
+    let number = value
+        .get("number")
+        .and_then(Value::as_u64)
+        .ok_or_else(|| MaccError::Validation("Gitea pull request without number".into()))?;
+    let state = if value
+        .get("merged")
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+    {
+        PullRequestState::Merged
+    } else if value.get("state").and_then(Value::as_str) == Some("closed") {
+        PullRequestState::Closed
+    } else {
+        PullRequestState::Open
+    };
+    Ok(PullRequest {
+        number,
+        url: value
+            .get("html_url")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string(),
+        state,
+    })
+}
+
+/// File line a review comment is anchored to. Newer Gitea/Forgejo report it
+/// as `line`; older ones only have `position` (new side) and
+/// `original_position` (old side), with 0 meaning "not on that side".
+fn comment_line(comment: &Value) -> Option<u64> {
+    ["line", "position", "original_position"]
+        .iter()
+        .filter_map(|field| comment.get(*field).and_then(Value::as_u64))
+        .find(|line| *line > 0)
+}
+
+fn login(value: &Value) -> String {
+    value
+        .get("user")
+        .and_then(|u| u.get("login"))
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string()
+}
+
+impl ForgeBackend for GiteaForge {
+    fn kind(&self) -> &'static str {
+        "gitea"
+    }
+
+    fn push_branch(&self, repo_root: &Path, branch: &str) -> Result<()> {
+        super::push_branch_to_remote(repo_root, &self.remote, branch)
+    }
+
+    fn open_pr(&self, spec: &PullRequestSpec) -> Result<PullRequest> {
+        let body = json!({
+            "head": spec.head,
+            "base": spec.base,
+            "title": spec.title,
+            "body": spec.body,
+        });
+        let value = super::send_json(
+            self.request(reqwest::Method::POST, "/pulls").json(&body),
+            "open pull request",
+        )?;
+        parse_pull_request(&value)
+    }
+
+    fn update_pr(&self, number: u64, spec: &PullRequestSpec) -> Result<PullRequest> {
+        let body = json!({ "title": spec.title, "body": spec.body });
+        let value = super::send_json(
+            self.request(reqwest::Method::PATCH, &format!("/pulls/{}", number))
+                .json(&body),
+            "update pull request",
+        )?;
+        parse_pull_request(&value)
+    }
+
+    fn review_status(&self, number: u64) -> Result<ReviewStatus> {
+        let pull_request = parse_pull_request(&super::send_json(
+            self.request(reqwest::Method::GET, &format!("/pulls/{}", number)),
+            "read pull request",
+        )?)?;
+        let raw_reviews = self.list(
+            &format!("/pulls/{}/reviews", number),
+            "list pull request reviews",
+        )?;
+        let mut reviews = Vec::new();
+        for raw in &raw_reviews {
+            if raw
+                .get("dismissed")
+                .and_then(Value::as_bool)
+                .unwrap_or(false)
+            {
+                continue;
+            }
+            let state = match raw.get("state").and_then(Value::as_str).unwrap_or_default() {
+                "APPROVED" => ReviewState::Approved,
+                "REQUEST_CHANGES" => ReviewState::ChangesRequested,
+                "COMMENT" => ReviewState::Commented,
+                // PENDING (draft) and REQUEST_REVIEW carry no verdict.
+                _ => continue,
+            };
+            let mut comments = Vec::new();
+            let has_comments = raw
+                .get("comments_count")
+                .and_then(Value::as_u64)
+                .unwrap_or(0)
+                > 0;
+            if let (true, Some(id)) = (has_comments, raw.get("id").and_then(Value::as_u64)) {
+                let raw_comments = self.list(
+                    &format!("/pulls/{}/reviews/{}/comments", number, id),
+                    "list review comments",
+                )?;
+                for comment in &raw_comments {
+                    comments.push(ReviewComment {
+                        author: login(comment),
+                        body: comment
+                            .get("body")
+                            .and_then(Value::as_str)
+                            .unwrap_or_default()
+                            .to_string(),
+                        path: comment
+                            .get("path")
+                            .and_then(Value::as_str)
+                            .map(str::to_string),
+                        line: comment_line(comment),
+                    });
+                }
+            }
+            reviews.push(Review {
+                author: login(raw),
+                state,
+                body: raw
+                    .get("body")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+                submitted_at: raw
+                    .get("submitted_at")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+                comments,
+            });
+        }
+        Ok(ReviewStatus {
+            pull_request,
+            reviews,
+        })
+    }
+
+    fn merge(&self, number: u64, method: MergeMethod) -> Result<std::result::Result<(), String>> {
+        let body = json!({ "Do": method.as_str() });
+        let (status, response) = super::send(
+            self.request(reqwest::Method::POST, &format!("/pulls/{}/merge", number))
+                .json(&body),
+            "merge pull request",
+        )?;
+        match status {
+            200..=299 => Ok(Ok(())),
+            // Not mergeable (conflicts, required approvals or checks) or already merging.
+            405 | 409 => Ok(Err(format!("HTTP {} {}", status, response.trim()))),
+            _ => Err(MaccError::Validation(format!(
+                "Forge request failed (merge pull request): HTTP {} {}",
+                status,
+                response.trim()
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_comment_line_falls_back_to_the_old_side() {
+        assert_eq!(
+            comment_line(&json!({ "line": 12, "position": 3 })),
+            Some(12)
+        );
+        assert_eq!(
+            comment_line(&json!({ "position": 7, "original_position": 4 })),
+            Some(7)
+        );
+        assert_eq!(
+            comment_line(&json!({ "position": 0, "original_position": 4 })),
+            Some(4)
+        );
+        assert_eq!(
+            comment_line(&json!({ "position": 0, "original_position": 0 })),
+            None
+        );
+    }
+}
@@ -0,0 +1,259 @@
+//! GitLab merge requests over the `/api/v4` REST API.
+//!
+//! GitLab has no review objects, so reviews are read from the merge request notes: approval
+//! and "requested changes" system notes carry verdicts, unresolved discussion threads count as
+//! change requests and other human notes as comments.
+
+use super::{
+    ForgeBackend, MergeMethod, PullRequest, PullRequestSpec, PullRequestState, Review,
+    ReviewComment, ReviewState, ReviewStatus,
+};
+use crate::{MaccError, Result};
+use serde_json::{json, Value};
+use std::path::Path;
+
+pub struct GitLabForge {
+    client: reqwest::blocking::Client,
+    /// `<url>/api/v4/projects/<url-encoded path or id>`
+    api: String,
+    token: Option<String>,
+    remote: String,
+}
+
+impl GitLabForge {
+    pub fn new(url: &str, project: &str, token: Option<String>, remote: &str) -> Result<Self> {
+        let project = project.trim().trim_matches('/');
+        if project.is_empty() {
+            return Err(MaccError::Validation(
+                "GitLab repo must be a project path or id".into(),
+            ));
+        }
+        Ok(Self {
+            client: super::http_client()?,
+            api: format!(
+                "{}/api/v4/projects/{}",
+                url.trim_end_matches('/'),
+                encode_project(project)
+            ),
+            token,
+            remote: remote.to_string(),
+        })
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::blocking::RequestBuilder {
+        let request = self
+            .client
+            .request(method, format!("{}{}", self.api, path))
+            .header("Accept", "application/json");
+        match &self.token {
+            Some(token) => request.header("PRIVATE-TOKEN", token),
+            None => request,
+        }
+    }
+}
+
+fn encode_project(project: &str) -> String {
+    let mut out = String::new();
+    for byte in project.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    out
+}
+
+fn parse_merge_request(value: &Value) -> Result<PullRequest> {
+    let number = value
+        .get("iid")
+        .and_then(Value::as_u64)
+        .ok_or_else(|| MaccError::Validation("GitLab merge request without iid".into()))?;
+    let state = match value
+        .get("state")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+    {
+        "merged" => PullRequestState::Merged,
+        "closed" | "locked" => PullRequestState::Closed,
+        _ => PullRequestState::Open,
+    };
+    Ok(PullRequest {
+        number,
+        url: value
+            .get("web_url")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string(),
+        state,
+    })
+}
+
+fn note_review(note: &Value) -> Option<Review> {
+    let author = note
+        .get("author")
+        .and_then(|a| a.get("username"))
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    let body = note
+        .get("body")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    let submitted_at = note
+        .get("created_at")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    if note.get("system").and_then(Value::as_bool).unwrap_or(false) {
+        let state = if body.starts_with("approved this merge request") {
+            ReviewState::Approved
+        } else if body.starts_with("requested changes") {
+            ReviewState::ChangesRequested
+        } else {
+            return None;
+        };
+        return Some(Review {
+            author,
+            state,
+            body: String::new(),
+            submitted_at,
+            comments: Vec::new(),
+        });
+    }
+    let unresolved = note
+        .get("resolvable")
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+        && !note
+            .get("resolved")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+    let position = note.get("position");
+    Some(Review {
+        author: author.clone(),
+        state: if unresolved {
+            ReviewState::ChangesRequested
+        } else {
+            ReviewState::Commented
+        },
+        body: String::new(),
+        submitted_at,
+        comments: vec![ReviewComment {
+            author,
+            body,
+            path: position
+                .and_then(|p| p.get("new_path"))
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            line: position
+                .and_then(|p| p.get("new_line"))
+                .and_then(Value::as_u64),
+        }],
+    })
+}
+
+impl ForgeBackend for GitLabForge {
+    fn kind(&self) -> &'static str {
+        "gitlab"
+    }
+
+    fn push_branch(&self, repo_root: &Path, branch: &str) -> Result<()> {
+        super::push_branch_to_remote(repo_root, &self.remote, branch)
+    }
+
+    fn open_pr(&self, spec: &PullRequestSpec) -> Result<PullRequest> {
+        let body = json!({
+            "source_branch": spec.head,
+            "target_branch": spec.base,
+            "title": spec.title,
+            "description": spec.body,
+        });
+        let value = super::send_json(
+            self.request(reqwest::Method::POST, "/merge_requests")
+                .json(&body),
+            "open merge request",
+        )?;
+        parse_merge_request(&value)
+    }
+
+    fn update_pr(&self, number: u64, spec: &PullRequestSpec) -> Result<PullRequest> {
+        let body = json!({ "title": spec.title, "description": spec.body });
+        let value = super::send_json(
+            self.request(reqwest::Method::PUT, &format!("/merge_requests/{}", number))
+                .json(&body),
+            "update merge request",
+        )?;
+        parse_merge_request(&value)
+    }
+
+    fn review_status(&self, number: u64) -> Result<ReviewStatus> {
+        let pull_request = parse_merge_request(&super::send_json(
+            self.request(reqwest::Method::GET, &format!("/merge_requests/{}", number)),
+            "read merge request",
+        )?)?;
+        let notes = super::send_json(
+            self.request(
+                reqwest::Method::GET,
+                &format!(
+                    "/merge_requests/{}/notes?sort=asc&order_by=created_at&per_page=100",
+                    number
+                ),
+            ),
+            "list merge request notes",
+        )?;
+        Ok(ReviewStatus {
+            pull_request,
+            reviews: notes
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(note_review)
+                .collect(),
+        })
+    }
+
+    fn merge(&self, number: u64, method: MergeMethod) -> Result<std::result::Result<(), String>> {
+        let body = json!({ "squash": method == MergeMethod::Squash });
+        let (status, response) = super::send(
+            self.request(
+                reqwest::Method::PUT,
+                &format!("/merge_requests/{}/merge", number),
+            )
+            .json(&body),
+            "merge merge request",
+        )?;
+        match status {
+            200..=299 => Ok(Ok(())),
+            // Not mergeable: draft, conflicts, unresolved threads, failed pipeline, stale sha.
+            405 | 406 | 409 | 422 => Ok(Err(format!("HTTP {} {}", status, response.trim()))),
+            _ => Err(MaccError::Validation(format!(
+                "Forge request failed (merge merge request): HTTP {} {}",
+                status,
+                response.trim()
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notes_map_to_reviews() {
+        let approved = json!({"system": true, "body": "approved this merge request",
+            "author": {"username": "alice"}, "created_at": "2026-01-01T10:00:00Z"});
+        assert_eq!(note_review(&approved).unwrap().state, ReviewState::Approved);
+        let thread = json!({"system": false, "body": "use a constant", "resolvable": true,
+            "resolved": false, "author": {"username": "bob"},
+            "position": {"new_path": "src/lib.rs", "new_line": 12}});
+        let review = note_review(&thread).unwrap();
+        assert_eq!(review.state, ReviewState::ChangesRequested);
+        assert_eq!(review.comments[0].path.as_deref(), Some("src/lib.rs"));
+        assert_eq!(review.comments[0].line, Some(12));
+        let other = json!({"system": true, "body": "added 1 commit"});
+        assert!(note_review(&other).is_none());
+        assert_eq!(encode_project("group/sub/app"), "group%2Fsub%2Fapp");
+    }
+}
@@ -0,0 +1,127 @@
+//! Repository path globs shared by approval rules and task scopes.
+
+/// Glob match of a repository-relative path, the one rule for approval `paths` and task
+/// `scope`: `*` and `?` stay within one path segment and `**` spans any number of segments. A
+/// wildcard pattern without `/` (`*.sql`) matches the file name at any depth, while a plain name
+/// (`Cargo.toml`) is the root-level path.
+pub fn path_matches(pattern: &str, path: &str) -> bool {
+    let pattern = normalize(pattern);
+    let path = path.trim_start_matches("./");
+    if is_root_name(pattern) {
+        return path == pattern;
+    }
+    let path_segments = path.split('/').collect::<Vec<_>>();
+    if !pattern.contains('/') {
+        return path_segments
+            .last()
+            .map(|name| segment_matches(pattern, name))
+            .unwrap_or(false);
+    }
+    let pattern_segments = pattern.split('/').collect::<Vec<_>>();
+    wildcard_match(
+        &pattern_segments,
+        &path_segments,
+        |p| *p == "**",
+        |p, segment| segment_matches(p, segment),
+    )
+}
+
+/// Pattern without `/` that matches at any depth (it has a wildcard).
+pub fn matches_any_depth(pattern: &str) -> bool {
+    let pattern = normalize(pattern);
+    !pattern.contains('/') && has_wildcard(pattern)
+}
+
+pub fn has_wildcard(pattern: &str) -> bool {
+    pattern.contains(['*', '?'])
+}
+
+fn is_root_name(pattern: &str) -> bool {
+    !pattern.contains('/') && !has_wildcard(pattern)
+}
+
+pub(crate) fn normalize(pattern: &str) -> &str {
+    pattern
+        .trim()
+        .trim_start_matches("./")
+        .trim_start_matches('/')
+}
+
+fn segment_matches(pattern: &str, name: &str) -> bool {
+    wildcard_match(
+        pattern.as_bytes(),
+        name.as_bytes(),
+        |p| *p == b'*',
+        |p, c| *p == b'?' || p == c,
+    )
+}
+
+/// Iterative wildcard match: on a mismatch only the most recent star is widened by one item,
+/// which bounds the work to `pattern.len() * text.len()` steps instead of backtracking
+/// through every star.
+fn wildcard_match<P, T>(
+    pattern: &[P],
+    text: &[T],
+    is_star: impl Fn(&P) -> bool,
+    matches_one: impl Fn(&P, &T) -> bool,
+) -> bool {
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && is_star(&pattern[p]) {
+            star = Some((p, t));
+            p += 1;
+        } else if p < pattern.len() && matches_one(&pattern[p], &text[t]) {
+            p += 1;
+            t += 1;
+        } else if let Some((star_p, star_t)) = star {
+            star = Some((star_p, star_t + 1));
+            p = star_p + 1;
+            t = star_t + 1;
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(is_star)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_globs_match_segments() {
+        assert!(path_matches("migrations/**", "migrations/2026/001.sql"));
+        assert!(path_matches("**/*.sql", "db/schema.sql"));
+        assert!(path_matches("**/*.sql", "schema.sql"));
+        assert!(path_matches("*.sql", "db/nested/schema.sql"));
+        assert!(path_matches("a*b*c", "axxbyyc"));
+        assert!(!path_matches("a*b*c", "axxbyy"));
+        // Would take exponential time with a backtracking matcher.
+        assert!(!path_matches(&"*a".repeat(20), &"a".repeat(19)));
+        assert!(!path_matches(
+            &format!("{}b", "**/".repeat(20)),
+            &format!("{}c", "a/".repeat(30))
+        ));
+        assert!(path_matches("**/x/**/y/*.rs", "a/x/b/c/y/z.rs"));
+        assert!(path_matches("src/?.rs", "src/a.rs"));
+        assert!(!path_matches("src/*.rs", "src/auth/mod.rs"));
+        assert!(!path_matches("migrations/**", "src/migrations.rs"));
+        assert!(path_matches(
+            ".github/workflows/*",
+            ".github/workflows/ci.yml"
+        ));
+    }
+
+    #[test]
+    fn test_names_without_wildcards_are_root_paths() {
+        assert!(path_matches("Cargo.toml", "Cargo.toml"));
+        assert!(path_matches("./Cargo.toml", "./Cargo.toml"));
+        assert!(!path_matches("Cargo.toml", "crates/a/Cargo.toml"));
+        assert!(path_matches("*.toml", "crates/a/Cargo.toml"));
+        assert!(path_matches("src/**", "src/api/users.rs"));
+        assert!(matches_any_depth("*.md"));
+        assert!(!matches_any_depth("README.md"));
+        assert!(!matches_any_depth("docs/*.md"));
+    }
+}
@@ -158,6 +158,23 @@ fn can_reuse_worktree_slot(registry: &serde_json::Value, worktree_path: &Path) -
     seen && all_merged
 }
 
+/// Pre-warmed pool slot that no task has been assigned to yet.
+fn is_unused_prewarmed_slot(registry: &serde_json::Value, worktree_path: &Path) -> bool {
+    let key = worktree_path.to_string_lossy();
+    let assigned = registry
+        .get("tasks")
+        .and_then(serde_json::Value::as_array)
+        .is_some_and(|tasks| {
+            tasks.iter().any(|task| {
+                task.get("worktree")
+                    .and_then(|w| w.get("worktree_path"))
+                    .and_then(serde_json::Value::as_str)
+                    == Some(key.as_ref())
+            })
+        });
+    !assigned && crate::coordinator::worktree_pool::is_prewarmed_slot(worktree_path)
+}
+
 fn has_in_progress_or_queued_on_worktree(
     registry: &serde_json::Value,
     worktree_path: &Path,
@@ -293,7 +310,9 @@ pub fn find_reusable_worktree_native(
         if active_paths.contains(&key) {
             continue;
         }
-        if !can_reuse_worktree_slot(registry, &entry.path) {
+        if !can_reuse_worktree_slot(registry, &entry.path)
+            && !is_unused_prewarmed_slot(registry, &entry.path)
+        {
             continue;
         }
         let dirty_before = !is_worktree_clean(&entry.path)?;
@@ -479,6 +498,28 @@ pub fn append_coordinator_event_with_severity(
     status: &str,
     message: &str,
     severity: &str,
+) -> Result<()> {
+    append_coordinator_event_with_payload(
+        repo_root,
+        event_type,
+        task_id,
+        phase,
+        status,
+        severity,
+        serde_json::json!({"message": message}),
+    )
+}
+
+/// Like [`append_coordinator_event_with_severity`] with a structured payload; `payload` should
+/// carry a `message` for the log views.
+pub fn append_coordinator_event_with_payload(
+    repo_root: &Path,
+    event_type: &str,
+    task_id: &str,
+    phase: &str,
+    status: &str,
+    severity: &str,
+    payload: serde_json::Value,
 ) -> Result<()> {
     let run_id = ensure_coordinator_run_id();
     let now = now_iso_coordinator();
@@ -495,7 +536,7 @@ pub fn append_coordinator_event_with_severity(
         "phase": phase,
         "status": status,
         "severity": severity,
-        "payload": {"message": message}
+        "payload": payload
     });
     let project_paths = crate::ProjectPaths::from_root(repo_root);
     let _ = append_event_sqlite(&project_paths, &payload)?;
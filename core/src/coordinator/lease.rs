@@ -0,0 +1,535 @@
+//! Project-level single-instance lease for the coordinator.
+//!
+//! The control plane holds `.macc/automation/task/coordinator.lease.json` (pid, hostname, run id,
+//! heartbeat) for the whole run, refreshed by a background thread. A second instance refuses
+//! to start while the owner is alive. A lease is stale when there was no heartbeat for
+//! [`LEASE_STALE_SECONDS`] or, on the same host, when its pid is gone (which also covers a hung
+//! owner or a recycled pid); a stale lease is taken over and a `coordinator_lease_takeover`
+//! event records the previous owner.
+
+use crate::coordinator::helpers::{append_coordinator_event_with_payload, now_iso_coordinator};
+use crate::{MaccError, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How often the owner refreshes `heartbeat_at`.
+pub const LEASE_HEARTBEAT_SECONDS: u64 = 5;
+/// Heartbeat age after which a lease held from another host counts as abandoned.
+pub const LEASE_STALE_SECONDS: u64 = 60;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LeaseRecord {
+    pub pid: u32,
+    pub hostname: String,
+    pub run_id: String,
+    /// Coordinator action holding the lease (`run`, `dispatch`, ...).
+    #[serde(default)]
+    pub action: String,
+    pub acquired_at: String,
+    pub heartbeat_at: String,
+}
+
+impl LeaseRecord {
+    /// Seconds since the last heartbeat (0 when the timestamp cannot be read).
+    pub fn heartbeat_age_seconds(&self) -> u64 {
+        chrono::DateTime::parse_from_rfc3339(&self.heartbeat_at)
+            .map(|at| {
+                (chrono::Utc::now() - at.with_timezone(&chrono::Utc))
+                    .num_seconds()
+                    .max(0) as u64
+            })
+            .unwrap_or(0)
+    }
+
+    pub fn describe(&self) -> String {
+        format!(
+            "pid {} on {} (run {}, {}, heartbeat {}s ago)",
+            self.pid,
+            self.hostname,
+            self.run_id,
+            if self.action.is_empty() {
+                "run"
+            } else {
+                &self.action
+            },
+            self.heartbeat_age_seconds()
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LeaseStatus {
+    Free,
+    /// Owned by a live (or unverifiable) coordinator.
+    Held(LeaseRecord),
+    /// The owner is dead; the next coordinator takes the lease over.
+    Stale {
+        record: LeaseRecord,
+        reason: String,
+    },
+}
+
+impl LeaseStatus {
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Free => "free".to_string(),
+            Self::Held(record) => format!("held by {}", record.describe()),
+            Self::Stale { record, reason } => {
+                format!("stale, {} ({})", reason, record.describe())
+            }
+        }
+    }
+}
+
+pub fn lease_path(repo_root: &Path) -> PathBuf {
+    repo_root.join(crate::coordinator::COORDINATOR_LEASE_FILE_REL_PATH)
+}
+
+pub fn read_lease(repo_root: &Path) -> Result<Option<LeaseRecord>> {
+    let path = lease_path(repo_root);
+    let raw = match std::fs::read_to_string(&path) {
+        Ok(raw) => raw,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => {
+            return Err(MaccError::Io {
+                path: path.to_string_lossy().into(),
+                action: "read coordinator lease".into(),
+                source: e,
+            })
+        }
+    };
+    serde_json::from_str(&raw).map(Some).map_err(|e| {
+        MaccError::Validation(format!(
+            "Failed to parse coordinator lease '{}': {}",
+            path.display(),
+            e
+        ))
+    })
+}
+
+pub fn lease_status(repo_root: &Path) -> Result<LeaseStatus> {
+    Ok(match read_lease(repo_root)? {
+        None => LeaseStatus::Free,
+        Some(record) => classify(record, &hostname()),
+    })
+}
+
+fn classify(record: LeaseRecord, host: &str) -> LeaseStatus {
+    if record.hostname == host && !pid_is_alive(record.pid) {
+        let reason = format!("owner pid {} is not running", record.pid);
+        return LeaseStatus::Stale { record, reason };
+    }
+    // A live pid may be a hung owner or a recycled pid, and the pid of another host cannot be
+    // checked at all: a long-silent heartbeat makes the lease stale either way.
+    let age = record.heartbeat_age_seconds();
+    if age > LEASE_STALE_SECONDS {
+        let reason = format!("no heartbeat from {} for {}s", record.hostname, age);
+        return LeaseStatus::Stale { record, reason };
+    }
+    LeaseStatus::Held(record)
+}
+
+/// Fails when another live coordinator holds the lease.
+pub fn ensure_lease_available(repo_root: &Path) -> Result<()> {
+    match lease_status(repo_root)? {
+        LeaseStatus::Held(record) if !is_own(&record) => Err(already_running(repo_root, &record)),
+        _ => Ok(()),
+    }
+}
+
+/// Removes a lease whose owner is dead; `false` when there was none to remove.
+pub fn clear_stale_lease(repo_root: &Path) -> Result<bool> {
+    match lease_status(repo_root)? {
+        LeaseStatus::Stale { record, .. } => remove_if_unchanged(&lease_path(repo_root), &record),
+        _ => Ok(false),
+    }
+}
+
+/// Lease held for the lifetime of the value; dropping it stops the heartbeat and releases it.
+pub struct CoordinatorLease {
+    path: PathBuf,
+    record: LeaseRecord,
+    /// False when this process already held the lease (nested actions of one run).
+    owned: bool,
+    lost: Arc<AtomicBool>,
+    stop: Arc<AtomicBool>,
+    heartbeat: Option<std::thread::JoinHandle<()>>,
+}
+
+impl CoordinatorLease {
+    pub fn acquire(repo_root: &Path, run_id: &str, action: &str) -> Result<Self> {
+        let path = lease_path(repo_root);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| MaccError::Io {
+                path: parent.to_string_lossy().into(),
+                action: "create coordinator lease directory".into(),
+                source: e,
+            })?;
+        }
+        let now = now_iso_coordinator();
+        let record = LeaseRecord {
+            pid: std::process::id(),
+            hostname: hostname(),
+            run_id: run_id.to_string(),
+            action: action.to_string(),
+            acquired_at: now.clone(),
+            heartbeat_at: now,
+        };
+        // Two rounds: the second follows the removal of a stale lease.
+        for _ in 0..2 {
+            if create_exclusive(&path, &record)? {
+                return Ok(Self::start(path, record, true));
+            }
+            match lease_status(repo_root)? {
+                LeaseStatus::Free => continue,
+                LeaseStatus::Held(current) if is_own(&current) => {
+                    return Ok(Self::start(path, current, false));
+                }
+                LeaseStatus::Held(current) => return Err(already_running(repo_root, &current)),
+                LeaseStatus::Stale {
+                    record: stale,
+                    reason,
+                } => {
+                    if remove_if_unchanged(&path, &stale)? {
+                        let _ = append_coordinator_event_with_payload(
+                            repo_root,
+                            "coordinator_lease_takeover",
+                            "-",
+                            "run",
+                            "taken_over",
+                            "warning",
+                            serde_json::json!({
+                                "message": format!("Took over stale coordinator lease: {}", reason),
+                                "previous": stale,
+                            }),
+                        );
+                    }
+                }
+            }
+        }
+        Err(MaccError::Validation(format!(
+            "Could not acquire the coordinator lease {}; another coordinator is starting",
+            path.display()
+        )))
+    }
+
+    pub fn record(&self) -> &LeaseRecord {
+        &self.record
+    }
+
+    /// Fails once another coordinator has replaced this lease.
+    pub fn ensure_held(&self) -> Result<()> {
+        if !self.lost.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+        let owner = read_lease_at(&self.path)
+            .map(|r| r.describe())
+            .unwrap_or_else(|| "nobody".to_string());
+        Err(MaccError::Validation(format!(
+            "Coordinator lease lost (now held by {}); stopping this run",
+            owner
+        )))
+    }
+
+    fn start(path: PathBuf, record: LeaseRecord, owned: bool) -> Self {
+        let lost = Arc::new(AtomicBool::new(false));
+        let stop = Arc::new(AtomicBool::new(false));
+        let heartbeat = owned.then(|| {
+            let (path, mut record) = (path.clone(), record.clone());
+            let (lost, stop) = (lost.clone(), stop.clone());
+            std::thread::spawn(move || {
+                let tick = Duration::from_millis(200);
+                let mut waited = Duration::ZERO;
+                while !stop.load(Ordering::SeqCst) {
+                    std::thread::sleep(tick);
+                    waited += tick;
+                    if waited < Duration::from_secs(LEASE_HEARTBEAT_SECONDS) {
+                        continue;
+                    }
+                    waited = Duration::ZERO;
+                    if !read_lease_at(&path)
+                        .map(|current| same_owner(&current, &record))
+                        .unwrap_or(false)
+                    {
+                        lost.store(true, Ordering::SeqCst);
+                        return;
+                    }
+                    record.heartbeat_at = now_iso_coordinator();
+                    let _ = write_replace(&path, &record);
+                }
+            })
+        });
+        Self {
+            path,
+            record,
+            owned,
+            lost,
+            stop,
+            heartbeat,
+        }
+    }
+}
+
+impl Drop for CoordinatorLease {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.heartbeat.take() {
+            let _ = handle.join();
+        }
+        if self.owned
+            && read_lease_at(&self.path)
+                .map(|current| same_owner(&current, &self.record))
+                .unwrap_or(false)
+        {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+fn already_running(repo_root: &Path, record: &LeaseRecord) -> MaccError {
+    MaccError::Validation(format!(
+        "Another coordinator is running for this project: {}. Watch it with `macc coordinator status` or stop it with `macc coordinator stop`; if that process is gone, remove {}.",
+        record.describe(),
+        lease_path(repo_root).display()
+    ))
+}
+
+fn is_own(record: &LeaseRecord) -> bool {
+    record.pid == std::process::id() && record.hostname == hostname()
+}
+
+fn same_owner(a: &LeaseRecord, b: &LeaseRecord) -> bool {
+    a.pid == b.pid && a.hostname == b.hostname && a.run_id == b.run_id
+}
+
+fn read_lease_at(path: &Path) -> Option<LeaseRecord> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+}
+
+fn temp_path(path: &Path) -> PathBuf {
+    path.with_extension(format!("json.{}.tmp", std::process::id()))
+}
+
+fn write_temp(path: &Path, record: &LeaseRecord) -> Result<PathBuf> {
+    let tmp = temp_path(path);
+    let body = serde_json::to_string_pretty(record)
+        .map_err(|e| MaccError::Validation(format!("Failed to encode coordinator lease: {}", e)))?;
+    std::fs::write(&tmp, body).map_err(|e| MaccError::Io {
+        path: tmp.to_string_lossy().into(),
+        action: "write coordinator lease".into(),
+        source: e,
+    })?;
+    Ok(tmp)
+}
+
+/// Creates the lease with its content in one step; `false` when a lease already exists.
+fn create_exclusive(path: &Path, record: &LeaseRecord) -> Result<bool> {
+    let tmp = write_temp(path, record)?;
+    let linked = std::fs::hard_link(&tmp, path);
+    let _ = std::fs::remove_file(&tmp);
+    match linked {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => Ok(false),
+        Err(e) => Err(MaccError::Io {
+            path: path.to_string_lossy().into(),
+            action: "create coordinator lease".into(),
+            source: e,
+        }),
+    }
+}
+
+fn write_replace(path: &Path, record: &LeaseRecord) -> Result<()> {
+    let tmp = write_temp(path, record)?;
+    std::fs::rename(&tmp, path).map_err(|e| MaccError::Io {
+        path: path.to_string_lossy().into(),
+        action: "refresh coordinator lease".into(),
+        source: e,
+    })
+}
+
+/// Removes the lease only if it still is the stale record that was inspected.
+///
+/// The lease is first moved aside, so two instances taking over at once cannot both remove a
+/// fresh lease; a moved lease that changed in between is put back.
+fn remove_if_unchanged(path: &Path, stale: &LeaseRecord) -> Result<bool> {
+    let aside = path.with_extension(format!("json.{}.stale", std::process::id()));
+    if std::fs::rename(path, &aside).is_err() {
+        return Ok(false);
+    }
+    if read_lease_at(&aside).as_ref() == Some(stale) {
+        let _ = std::fs::remove_file(&aside);
+        return Ok(true);
+    }
+    if std::fs::hard_link(&aside, path).is_ok() {
+        let _ = std::fs::remove_file(&aside);
+    }
+    Ok(false)
+}
+
+fn hostname() -> String {
+    #[cfg(unix)]
+    {
+        let mut buf = [0u8; 256];
+        // SAFETY: the buffer is valid for its length; gethostname NUL-terminates on success.
+        if unsafe { libc::gethostname(buf.as_mut_ptr().cast(), buf.len()) } == 0 {
+            let end = buf.iter().position(|b| *b == 0).unwrap_or(buf.len());
+            let name = String::from_utf8_lossy(&buf[..end]).trim().to_string();
+            if !name.is_empty() {
+                return name;
+            }
+        }
+    }
+    ["HOSTNAME", "COMPUTERNAME"]
+        .iter()
+        .find_map(|key| std::env::var(key).ok().filter(|v| !v.trim().is_empty()))
+        .unwrap_or_else(|| "localhost".to_string())
+}
+
+fn pid_is_alive(pid: u32) -> bool {
+    #[cfg(unix)]
+    {
+        let Ok(pid) = i32::try_from(pid) else {
+            return false;
+        };
+        if pid <= 0 {
+            return false;
+        }
+        // SAFETY: signal 0 only checks that the process exists.
+        if unsafe { libc::kill(pid, 0) } == 0 {
+            return true;
+        }
+        std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+    }
+    #[cfg(not(unix))]
+    {
+        // Without a liveness check the lease is trusted until its owner removes it.
+        let _ = pid;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_root(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!(
+            "macc_lease_{}_{}",
+            name,
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        std::fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    fn dead_pid() -> u32 {
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        let pid = child.id();
+        child.wait().unwrap();
+        pid
+    }
+
+    #[test]
+    fn test_classify_checks_pid_on_same_host_and_heartbeat_elsewhere() {
+        let record = LeaseRecord {
+            pid: std::process::id(),
+            hostname: "host-a".into(),
+            run_id: "run-1".into(),
+            action: "run".into(),
+            acquired_at: "2026-01-01T00:00:00Z".into(),
+            heartbeat_at: now_iso_coordinator(),
+        };
+        assert!(matches!(
+            classify(record.clone(), "host-a"),
+            LeaseStatus::Held(_)
+        ));
+        assert!(matches!(
+            classify(record.clone(), "host-b"),
+            LeaseStatus::Held(_)
+        ));
+        let dead = LeaseRecord {
+            pid: dead_pid(),
+            ..record.clone()
+        };
+        assert!(matches!(
+            classify(dead, "host-a"),
+            LeaseStatus::Stale { .. }
+        ));
+        // A silent heartbeat is stale on any host, even with the pid still alive.
+        let silent = LeaseRecord {
+            heartbeat_at: "2026-01-01T00:00:00Z".into(),
+            ..record
+        };
+        assert!(matches!(
+            classify(silent.clone(), "host-a"),
+            LeaseStatus::Stale { .. }
+        ));
+        assert!(matches!(
+            classify(silent, "host-b"),
+            LeaseStatus::Stale { .. }
+        ));
+    }
+
+    #[test]
+    fn test_acquire_takes_over_dead_owner_and_releases_on_drop() {
+        let root = temp_root("takeover");
+        let stale = LeaseRecord {
+            pid: dead_pid(),
+            hostname: hostname(),
+            run_id: "run-old".into(),
+            action: "run".into(),
+            acquired_at: now_iso_coordinator(),
+            heartbeat_at: now_iso_coordinator(),
+        };
+        std::fs::create_dir_all(lease_path(&root).parent().unwrap()).unwrap();
+        assert!(create_exclusive(&lease_path(&root), &stale).unwrap());
+        assert!(matches!(
+            lease_status(&root).unwrap(),
+            LeaseStatus::Stale { .. }
+        ));
+
+        let lease = CoordinatorLease::acquire(&root, "run-new", "run").unwrap();
+        assert_eq!(read_lease(&root).unwrap().unwrap().run_id, "run-new");
+        lease.ensure_held().unwrap();
+        // A nested action of the same process shares the lease without releasing it.
+        drop(CoordinatorLease::acquire(&root, "run-new", "dispatch").unwrap());
+        assert!(read_lease(&root).unwrap().is_some());
+        drop(lease);
+        assert_eq!(lease_status(&root).unwrap(), LeaseStatus::Free);
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_acquire_refuses_lease_of_live_process() {
+        let root = temp_root("held");
+        let mut owner = std::process::Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .unwrap();
+        let held = LeaseRecord {
+            pid: owner.id(),
+            hostname: hostname(),
+            run_id: "run-other".into(),
+            action: "run".into(),
+            acquired_at: now_iso_coordinator(),
+            heartbeat_at: now_iso_coordinator(),
+        };
+        std::fs::create_dir_all(lease_path(&root).parent().unwrap()).unwrap();
+        assert!(create_exclusive(&lease_path(&root), &held).unwrap());
+
+        let Err(err) = CoordinatorLease::acquire(&root, "run-new", "run") else {
+            panic!("lease of a live process was taken over");
+        };
+        assert!(err.to_string().contains("run-other"), "{}", err);
+        assert!(ensure_lease_available(&root).is_err());
+        assert_eq!(read_lease(&root).unwrap().unwrap().run_id, "run-other");
+        let _ = owner.kill();
+        let _ = owner.wait();
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,485 @@
+//! Resource limits for performer processes (`automation.coordinator.resource_limits` and the
+//! task `resource_limits` field).
+//!
+//! Limits resolve field by field: task over tool entry over `default`. When the coordinator runs
+//! in a cgroup v2 subtree it may write to (a delegated cgroup with the `memory` and `pids`
+//! controllers), each performer or phase tool call gets its own child cgroup with `memory.max`
+//! and `pids.max`, which covers the whole tool process tree. cgroup v2 only lets a cgroup without
+//! member processes enable controllers for its children, so the first limited call moves the
+//! processes of the coordinator's cgroup into a `macc-coordinator` leaf next to the task cgroups.
+//! Without a writable cgroup, memory falls back to `RLIMIT_DATA` inherited by every tool process
+//! and `max_processes` is skipped (`RLIMIT_NPROC` counts every process of the user, not the
+//! task's tree); the downgrade is reported as a `resource_limits_downgraded` event. CPU time is
+//! always an `RLIMIT_CPU` per process.
+//!
+//! Peak RSS and CPU time reported by performers (`progress` events with
+//! `payload.activity == "resource_usage"`) are folded into `task_runtime.metrics`.
+
+use crate::config::{CoordinatorConfig, ResourceLimits};
+use crate::performer::limits::{CgroupEvents, LimitViolation, PerformerResourceLimits};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Seconds between the `RLIMIT_CPU` soft limit (`SIGXCPU`) and the hard limit (`SIGKILL`).
+const CPU_GRACE_SECONDS: u64 = 5;
+
+const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
+/// Leaf cgroup holding the coordinator's processes so its parent can delegate controllers.
+const COORDINATOR_LEAF: &str = "macc-coordinator";
+
+/// Effective limits for `task` dispatched to `tool`.
+pub fn resolve_resource_limits(
+    coordinator: Option<&CoordinatorConfig>,
+    tool: &str,
+    task: &Value,
+) -> ResourceLimits {
+    let mut limits = ResourceLimits::default();
+    if let Some(coordinator) = coordinator {
+        for key in ["default", tool] {
+            if let Some(entry) = coordinator.resource_limits.get(key) {
+                limits = limits.overlay(entry);
+            }
+        }
+    }
+    match task.get("resource_limits").map(|raw| {
+        serde_json::from_value::<ResourceLimits>(raw.clone())
+            .map_err(|e| e.to_string())
+            .and_then(|v| {
+                v.validate("resource_limits")
+                    .map(|_| v)
+                    .map_err(|e| e.to_string())
+            })
+    }) {
+        Some(Ok(task_limits)) => limits.overlay(&task_limits),
+        Some(Err(err)) => {
+            tracing::warn!(
+                "ignoring resource_limits of task {}: {}",
+                task.get("id")
+                    .and_then(serde_json::Value::as_str)
+                    .unwrap_or("-"),
+                err
+            );
+            limits
+        }
+        None => limits,
+    }
+}
+
+/// Limits applied to one performer process; removes its cgroup when dropped.
+#[derive(Debug)]
+pub struct PerformerLimitGuard {
+    resources: PerformerResourceLimits,
+    /// What could not be enforced as configured because no task cgroup was available.
+    downgrade: Option<String>,
+}
+
+impl PerformerLimitGuard {
+    /// Creates the task cgroup when memory or process limits are set and cgroup v2 is writable.
+    /// Without that cgroup memory falls back to `RLIMIT_DATA` and `max_processes` is dropped.
+    pub fn prepare(task_id: &str, mut limits: ResourceLimits) -> Self {
+        let cgroup = if limits.memory_mb.is_some() || limits.max_processes.is_some() {
+            create_cgroup(task_id, &limits)
+        } else {
+            None
+        };
+        let mut downgrade = Vec::new();
+        if cgroup.is_none() {
+            if let Some(mb) = limits.memory_mb {
+                downgrade.push(format!("memory_mb={} enforced as RLIMIT_DATA", mb));
+            }
+            if let Some(count) = limits.max_processes.take() {
+                downgrade.push(format!("max_processes={} skipped", count));
+            }
+        }
+        let downgrade = (!downgrade.is_empty()).then(|| {
+            let message = format!(
+                "{} for task {}: no delegated cgroup v2 with the memory and pids controllers",
+                downgrade.join(", "),
+                task_id
+            );
+            tracing::warn!("{}", message);
+            message
+        });
+        Self {
+            resources: PerformerResourceLimits { limits, cgroup },
+            downgrade,
+        }
+    }
+
+    /// Records a `resource_limits_downgraded` coordinator event when limits could not be
+    /// enforced as configured, so operators see it outside the coordinator log.
+    pub fn report_downgrade(&self, repo_root: &Path, task_id: &str, phase: &str) {
+        let Some(message) = &self.downgrade else {
+            return;
+        };
+        let _ = crate::coordinator::helpers::append_coordinator_event_with_payload(
+            repo_root,
+            "resource_limits_downgraded",
+            task_id,
+            phase,
+            "downgraded",
+            "warning",
+            json!({ "message": message }),
+        );
+    }
+
+    /// Limits in effect, for classifying failed tool calls.
+    pub fn resources(&self) -> &PerformerResourceLimits {
+        &self.resources
+    }
+
+    /// Exports the limits to the process and sets them on it before `exec`.
+    pub fn apply(&self, cmd: &mut std::process::Command) {
+        for (name, value) in self.resources.env() {
+            cmd.env(name, value);
+        }
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+            let limits = self.resources.limits;
+            let rlimits = RlimitPlan {
+                cpu_seconds: limits.cpu_seconds,
+                data_bytes: match &self.resources.cgroup {
+                    Some(_) => None,
+                    None => limits.memory_mb.map(|mb| mb.saturating_mul(1024 * 1024)),
+                },
+                cgroup_procs: self.resources.cgroup.as_ref().and_then(|dir| {
+                    use std::os::unix::ffi::OsStrExt;
+                    std::ffi::CString::new(dir.join("cgroup.procs").as_os_str().as_bytes()).ok()
+                }),
+            };
+            if rlimits.is_empty() {
+                return;
+            }
+            // SAFETY: the closure only calls async-signal-safe libc functions.
+            unsafe {
+                cmd.pre_exec(move || rlimits.apply());
+            }
+        }
+    }
+
+    /// Limit that killed the performer process itself, for exits without a `failed` event.
+    pub fn violation(&self, status: &std::process::ExitStatus) -> Option<LimitViolation> {
+        if status.success() {
+            return None;
+        }
+        self.resources.detect(
+            &CgroupEvents::default(),
+            exit_signal(status),
+            status.code(),
+            "",
+        )
+    }
+}
+
+impl Drop for PerformerLimitGuard {
+    fn drop(&mut self) {
+        let Some(dir) = &self.resources.cgroup else {
+            return;
+        };
+        // Tool processes left behind by the performer would keep the cgroup busy.
+        let _ = std::fs::write(dir.join("cgroup.kill"), "1");
+        for _ in 0..20 {
+            if std::fs::remove_dir(dir).is_ok() || !dir.exists() {
+                return;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(50));
+        }
+        tracing::warn!("could not remove performer cgroup {}", dir.display());
+    }
+}
+
+#[cfg(unix)]
+struct RlimitPlan {
+    cpu_seconds: Option<u64>,
+    data_bytes: Option<u64>,
+    cgroup_procs: Option<std::ffi::CString>,
+}
+
+#[cfg(unix)]
+impl RlimitPlan {
+    fn is_empty(&self) -> bool {
+        self.cpu_seconds.is_none() && self.data_bytes.is_none() && self.cgroup_procs.is_none()
+    }
+
+    /// Runs in the forked child: joins the cgroup, then lowers the rlimits.
+    fn apply(&self) -> std::io::Result<()> {
+        if let Some(procs) = &self.cgroup_procs {
+            // SAFETY: open/write/close on a valid C string and a stack buffer.
+            let written = unsafe {
+                let fd = libc::open(procs.as_ptr(), libc::O_WRONLY);
+                if fd < 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                let n = libc::write(fd, b"0".as_ptr().cast(), 1);
+                libc::close(fd);
+                n
+            };
+            if written != 1 {
+                return Err(std::io::Error::last_os_error());
+            }
+        }
+        if let Some(seconds) = self.cpu_seconds {
+            set_rlimit(
+                libc::RLIMIT_CPU,
+                seconds,
+                seconds.saturating_add(CPU_GRACE_SECONDS),
+            )?;
+        }
+        if let Some(bytes) = self.data_bytes {
+            set_rlimit(libc::RLIMIT_DATA, bytes, bytes)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+type RlimitResource = libc::__rlimit_resource_t;
+
+#[cfg(all(unix, not(all(target_os = "linux", target_env = "gnu"))))]
+type RlimitResource = libc::c_int;
+
+#[cfg(unix)]
+fn set_rlimit(
+    resource: RlimitResource,
+    soft: libc::rlim_t,
+    hard: libc::rlim_t,
+) -> std::io::Result<()> {
+    let mut current = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+    // SAFETY: getrlimit/setrlimit only access the provided struct.
+    unsafe {
+        if libc::getrlimit(resource, &mut current) != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        // An unprivileged process cannot raise its hard limit; never try.
+        let hard = if current.rlim_max == libc::RLIM_INFINITY {
+            hard
+        } else {
+            hard.min(current.rlim_max)
+        };
+        let limit = libc::rlimit {
+            rlim_cur: soft.min(hard),
+            rlim_max: hard,
+        };
+        if libc::setrlimit(resource, &limit) != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+/// cgroup the task cgroups are created in, set up once per process; `None` when the hierarchy
+/// is not cgroup v2 or not delegated to this user.
+fn cgroup_parent() -> Option<&'static PathBuf> {
+    static PARENT: OnceLock<Option<PathBuf>> = OnceLock::new();
+    PARENT.get_or_init(prepare_cgroup_parent).as_ref()
+}
+
+/// Enables the `memory` and `pids` controllers for children of the coordinator's cgroup. The
+/// kernel refuses (`EBUSY`) while that cgroup has member processes, which is the normal case for
+/// a systemd service or scope, so they are moved into the [`COORDINATOR_LEAF`] child first.
+fn prepare_cgroup_parent() -> Option<PathBuf> {
+    let own = std::fs::read_to_string("/proc/self/cgroup").ok()?;
+    let relative = own.lines().find_map(|line| line.strip_prefix("0::"))?;
+    let mut parent = Path::new(CGROUP_ROOT).join(relative.trim().trim_start_matches('/'));
+    // Started from a process an earlier coordinator already moved into the leaf.
+    if parent
+        .file_name()
+        .is_some_and(|name| name == COORDINATOR_LEAF)
+    {
+        parent.pop();
+    }
+    let available = std::fs::read_to_string(parent.join("cgroup.controllers")).ok()?;
+    let controllers: Vec<&str> = available
+        .split_whitespace()
+        .filter(|c| matches!(*c, "memory" | "pids"))
+        .collect();
+    if controllers.is_empty() {
+        return None;
+    }
+    if enable_controllers(&parent, &controllers).is_err() {
+        let leaf = parent.join(COORDINATOR_LEAF);
+        if !leaf.is_dir() {
+            std::fs::create_dir(&leaf).ok()?;
+        }
+        let members = std::fs::read_to_string(parent.join("cgroup.procs")).ok()?;
+        for pid in members.split_whitespace() {
+            // A process may exit in between; whatever is left makes the retry below fail.
+            let _ = std::fs::write(leaf.join("cgroup.procs"), pid);
+        }
+        if let Err(e) = enable_controllers(&parent, &controllers) {
+            tracing::warn!(
+                "cannot enable cgroup controllers in {}: {}",
+                parent.display(),
+                e
+            );
+            return None;
+        }
+    }
+    Some(parent)
+}
+
+fn enable_controllers(parent: &Path, controllers: &[&str]) -> std::io::Result<()> {
+    let enabled = std::fs::read_to_string(parent.join("cgroup.subtree_control"))?;
+    for controller in controllers {
+        if !enabled.split_whitespace().any(|c| c == *controller) {
+            std::fs::write(
+                parent.join("cgroup.subtree_control"),
+                format!("+{}", controller),
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Task cgroup with `memory.max` / `pids.max` under [`cgroup_parent`], or `None` when that is
+/// unavailable or lacks a needed controller.
+fn create_cgroup(task_id: &str, limits: &ResourceLimits) -> Option<PathBuf> {
+    let parent = cgroup_parent()?;
+    let enabled = std::fs::read_to_string(parent.join("cgroup.subtree_control")).ok()?;
+    let needed = [
+        ("memory", limits.memory_mb.is_some()),
+        ("pids", limits.max_processes.is_some()),
+    ];
+    if needed
+        .iter()
+        .any(|(controller, used)| *used && !enabled.split_whitespace().any(|c| c == *controller))
+    {
+        return None;
+    }
+
+    let name: String = task_id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let dir = parent.join(format!(
+        "macc-{}-{}",
+        name,
+        chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+    ));
+    std::fs::create_dir(&dir).ok()?;
+    let mut settings = Vec::new();
+    if let Some(mb) = limits.memory_mb {
+        settings.push(("memory.max", mb.saturating_mul(1024 * 1024).to_string()));
+    }
+    if let Some(count) = limits.max_processes {
+        settings.push(("pids.max", count.to_string()));
+    }
+    for (file, value) in settings {
+        if std::fs::write(dir.join(file), value).is_err() {
+            let _ = std::fs::remove_dir(&dir);
+            return None;
+        }
+    }
+    if limits.memory_mb.is_some() {
+        // Without this the kernel swaps instead of enforcing the limit; not every host has it.
+        let _ = std::fs::write(dir.join("memory.swap.max"), "0");
+    }
+    Some(dir)
+}
+
+#[cfg(unix)]
+fn exit_signal(status: &std::process::ExitStatus) -> Option<i32> {
+    use std::os::unix::process::ExitStatusExt;
+    status.signal()
+}
+
+#[cfg(not(unix))]
+fn exit_signal(_status: &std::process::ExitStatus) -> Option<i32> {
+    None
+}
+
+/// Resource usage reported by one performer tool call.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResourceSample {
+    pub task_id: String,
+    pub peak_rss_kb: u64,
+    pub cpu_seconds: f64,
+}
+
+/// Resource usage carried by a coordinator event.
+pub fn resource_sample(event: &Value) -> Option<ResourceSample> {
+    let event_type = event
+        .get("type")
+        .or_else(|| event.get("event"))
+        .and_then(Value::as_str)?;
+    let payload = event.get("payload")?;
+    if event_type != "progress" || payload["activity"].as_str() != Some("resource_usage") {
+        return None;
+    }
+    Some(ResourceSample {
+        task_id: event["task_id"]
+            .as_str()
+            .or_else(|| payload["task_id"].as_str())
+            .unwrap_or_default()
+            .to_string(),
+        peak_rss_kb: payload["peak_rss_kb"].as_u64().unwrap_or(0),
+        cpu_seconds: payload["cpu_seconds"].as_f64().unwrap_or(0.0),
+    })
+}
+
+/// Keeps the highest `peak_rss_kb` and adds up `cpu_seconds` in `task_runtime.metrics`.
+pub fn apply_resource_sample(task: &mut Value, sample: &ResourceSample) {
+    if !task["task_runtime"]["metrics"].is_object() {
+        task["task_runtime"]["metrics"] = json!({});
+    }
+    let metrics = &mut task["task_runtime"]["metrics"];
+    let peak = metrics["peak_rss_kb"].as_u64().unwrap_or(0);
+    metrics["peak_rss_kb"] = json!(peak.max(sample.peak_rss_kb));
+    let cpu = metrics["cpu_seconds"].as_f64().unwrap_or(0.0) + sample.cpu_seconds;
+    metrics["cpu_seconds"] = json!((cpu * 1000.0).round() / 1000.0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_limits_and_fold_usage() {
+        let mut coordinator = CoordinatorConfig::default();
+        coordinator.resource_limits.insert(
+            "default".into(),
+            ResourceLimits {
+                memory_mb: Some(2048),
+                cpu_seconds: Some(600),
+                ..Default::default()
+            },
+        );
+        coordinator.resource_limits.insert(
+            "tool-a".into(),
+            ResourceLimits {
+                cpu_seconds: Some(1200),
+                ..Default::default()
+            },
+        );
+        let task = json!({ "id": "T1", "resource_limits": { "memory_mb": 512 } });
+        let limits = resolve_resource_limits(Some(&coordinator), "tool-a", &task);
+        assert_eq!(limits.memory_mb, Some(512));
+        assert_eq!(limits.cpu_seconds, Some(1200));
+        assert_eq!(limits.max_processes, None);
+        let invalid = json!({ "id": "T2", "resource_limits": { "memory_mb": 0 } });
+        assert_eq!(
+            resolve_resource_limits(Some(&coordinator), "tool-b", &invalid).memory_mb,
+            Some(2048)
+        );
+
+        let mut task = json!({ "id": "T1", "task_runtime": { "metrics": { "retries": 1 } } });
+        for (rss, cpu) in [(2048, 1.5), (1024, 0.25)] {
+            let event = json!({
+                "type": "progress",
+                "task_id": "T1",
+                "payload": { "activity": "resource_usage", "peak_rss_kb": rss, "cpu_seconds": cpu },
+            });
+            apply_resource_sample(&mut task, &resource_sample(&event).unwrap());
+        }
+        let metrics = &task["task_runtime"]["metrics"];
+        assert_eq!(metrics["peak_rss_kb"], 2048);
+        assert_eq!(metrics["cpu_seconds"], 1.75);
+        assert_eq!(metrics["retries"], 1);
+    }
+}
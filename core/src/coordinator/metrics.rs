@@ -0,0 +1,471 @@
+//! OpenMetrics exporter for coordinator state.
+//!
+//! Metrics are rendered from the coordinator snapshot (registry + event log), so the file
+//! written by `macc coordinator metrics` and the `--metrics-listen` endpoint of a running
+//! coordinator report the same values. Phase durations come from `task_phase_duration_seconds`
+//! events recorded by the control plane when a phase finishes; merge failures from
+//! `local_merge_failed` events. Names are documented in `docs/COORDINATOR_METRICS.md` and are
+//! part of the public interface: rename only with a changelog entry.
+
+use crate::coordinator::{RuntimeStatus, WorkflowState};
+use crate::coordinator_storage::{
+    CoordinatorSnapshot, CoordinatorStorage, CoordinatorStoragePaths, JsonStorage, SqliteStorage,
+};
+use crate::{MaccError, ProjectPaths, Result};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader, Write as _};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+pub const METRICS_REL_PATH: &str = ".macc/log/coordinator/metrics.prom";
+pub const OPENMETRICS_CONTENT_TYPE: &str =
+    "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+const PHASE_DURATION_EVENT: &str = "task_phase_duration_seconds";
+const MERGE_FAILED_EVENT: &str = "local_merge_failed";
+const PHASE_DURATION_BUCKETS: &[f64] = &[
+    5.0, 15.0, 30.0, 60.0, 120.0, 300.0, 600.0, 1200.0, 1800.0, 3600.0,
+];
+
+const WORKFLOW_STATES: &[WorkflowState] = &[
+    WorkflowState::Todo,
+    WorkflowState::Claimed,
+    WorkflowState::InProgress,
+    WorkflowState::PrOpen,
+    WorkflowState::ChangesRequested,
+    WorkflowState::Queued,
+    WorkflowState::Merged,
+    WorkflowState::Blocked,
+    WorkflowState::Abandoned,
+];
+
+const RUNTIME_STATUSES: &[RuntimeStatus] = &[
+    RuntimeStatus::Idle,
+    RuntimeStatus::Dispatched,
+    RuntimeStatus::Running,
+    RuntimeStatus::WaitingForUser,
+    RuntimeStatus::PhaseDone,
+    RuntimeStatus::Failed,
+    RuntimeStatus::Stale,
+    RuntimeStatus::Paused,
+];
+
+#[derive(Debug, Clone, Default)]
+struct Histogram {
+    /// Cumulative counts, one per entry of [`PHASE_DURATION_BUCKETS`].
+    buckets: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, value: f64) {
+        if self.buckets.is_empty() {
+            self.buckets = vec![0; PHASE_DURATION_BUCKETS.len()];
+        }
+        for (bucket, bound) in self.buckets.iter_mut().zip(PHASE_DURATION_BUCKETS) {
+            if value <= *bound {
+                *bucket += 1;
+            }
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+}
+
+/// Event recording how long `phase` of `task_id` took.
+pub fn record_phase_duration(
+    repo_root: &Path,
+    task_id: &str,
+    phase: &str,
+    status: &str,
+    seconds: f64,
+) -> Result<()> {
+    let seconds = (seconds.max(0.0) * 1000.0).round() / 1000.0;
+    crate::coordinator::helpers::append_coordinator_event_with_payload(
+        repo_root,
+        PHASE_DURATION_EVENT,
+        task_id,
+        phase,
+        status,
+        "info",
+        serde_json::json!({
+            "seconds": seconds,
+            "message": format!("phase {} {} in {:.1}s", phase, status, seconds),
+        }),
+    )
+}
+
+/// OpenMetrics text for a registry and its event log.
+pub fn render_openmetrics(registry: &Value, events: &[Value]) -> String {
+    let tasks = registry
+        .get("tasks")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+
+    let mut by_state: BTreeMap<String, u64> = WORKFLOW_STATES
+        .iter()
+        .map(|state| (state.as_str().to_string(), 0))
+        .collect();
+    let mut by_status: BTreeMap<String, u64> = RUNTIME_STATUSES
+        .iter()
+        .map(|status| (status.as_str().to_string(), 0))
+        .collect();
+    let mut active_by_tool: BTreeMap<String, u64> = BTreeMap::new();
+    let mut retries = 0u64;
+    for task in tasks {
+        let state = task.get("state").and_then(Value::as_str).unwrap_or("todo");
+        *by_state.entry(state.to_string()).or_default() += 1;
+        let status = task["task_runtime"]["status"].as_str().unwrap_or("idle");
+        *by_status.entry(status.to_string()).or_default() += 1;
+        if matches!(status, "running" | "dispatched") {
+            let tool = task.get("tool").and_then(Value::as_str).unwrap_or("-");
+            *active_by_tool.entry(tool.to_string()).or_default() += 1;
+        }
+        retries += task["task_runtime"]["metrics"]["retries"]
+            .as_u64()
+            .unwrap_or(0);
+    }
+
+    let mut phases: BTreeMap<String, Histogram> = BTreeMap::new();
+    let mut merge_failures = 0u64;
+    for event in events {
+        match event.get("type").and_then(Value::as_str) {
+            Some(PHASE_DURATION_EVENT) => {
+                let Some(seconds) = event["payload"]["seconds"].as_f64() else {
+                    continue;
+                };
+                let phase = event.get("phase").and_then(Value::as_str).unwrap_or("-");
+                phases
+                    .entry(phase.to_string())
+                    .or_default()
+                    .observe(seconds);
+            }
+            Some(MERGE_FAILED_EVENT) => merge_failures += 1,
+            _ => {}
+        }
+    }
+
+    let mut out = String::new();
+    family(
+        &mut out,
+        "macc_coordinator_tasks",
+        "gauge",
+        "Tasks in the registry by workflow state.",
+    );
+    for (state, count) in &by_state {
+        sample(
+            &mut out,
+            "macc_coordinator_tasks",
+            &[("state", state)],
+            *count,
+        );
+    }
+    family(
+        &mut out,
+        "macc_coordinator_task_runtime",
+        "gauge",
+        "Tasks in the registry by runtime status.",
+    );
+    for (status, count) in &by_status {
+        sample(
+            &mut out,
+            "macc_coordinator_task_runtime",
+            &[("status", status)],
+            *count,
+        );
+    }
+    family(
+        &mut out,
+        "macc_coordinator_active_jobs",
+        "gauge",
+        "Dispatched or running performer jobs per tool.",
+    );
+    for (tool, count) in &active_by_tool {
+        sample(
+            &mut out,
+            "macc_coordinator_active_jobs",
+            &[("tool", tool)],
+            *count,
+        );
+    }
+    family(
+        &mut out,
+        "macc_coordinator_task_retries",
+        "gauge",
+        "Phase retries recorded on current registry tasks.",
+    );
+    sample(&mut out, "macc_coordinator_task_retries", &[], retries);
+    family(
+        &mut out,
+        "macc_coordinator_merge_failures",
+        "counter",
+        "Local merges that failed.",
+    );
+    sample(
+        &mut out,
+        "macc_coordinator_merge_failures_total",
+        &[],
+        merge_failures,
+    );
+    family(
+        &mut out,
+        "macc_coordinator_phase_duration_seconds",
+        "histogram",
+        "Duration of finished task phases.",
+    );
+    for (phase, histogram) in &phases {
+        for (bound, count) in PHASE_DURATION_BUCKETS.iter().zip(&histogram.buckets) {
+            sample(
+                &mut out,
+                "macc_coordinator_phase_duration_seconds_bucket",
+                &[("phase", phase), ("le", &format_float(*bound))],
+                *count,
+            );
+        }
+        sample(
+            &mut out,
+            "macc_coordinator_phase_duration_seconds_bucket",
+            &[("phase", phase), ("le", "+Inf")],
+            histogram.count,
+        );
+        let _ = writeln!(
+            out,
+            "macc_coordinator_phase_duration_seconds_sum{{phase=\"{}\"}} {}",
+            escape_label(phase),
+            format_float(histogram.sum)
+        );
+        sample(
+            &mut out,
+            "macc_coordinator_phase_duration_seconds_count",
+            &[("phase", phase)],
+            histogram.count,
+        );
+    }
+    out.push_str("# EOF\n");
+    out
+}
+
+/// Renders the current coordinator state of `repo_root`.
+pub fn collect_openmetrics(repo_root: &Path) -> Result<String> {
+    let paths = ProjectPaths::from_root(repo_root);
+    let storage_paths = CoordinatorStoragePaths::from_project_paths(&paths);
+    let sqlite = SqliteStorage::new(storage_paths.clone());
+    let snapshot: CoordinatorSnapshot = if sqlite.has_snapshot_data()? {
+        sqlite.load_snapshot()?
+    } else {
+        JsonStorage::new(storage_paths).load_snapshot()?
+    };
+    Ok(render_openmetrics(&snapshot.registry, &snapshot.events))
+}
+
+/// Writes the metrics to `out` (default [`METRICS_REL_PATH`]) and returns the path.
+pub fn write_metrics_file(repo_root: &Path, out: Option<&Path>) -> Result<PathBuf> {
+    let path = out
+        .map(|p| {
+            if p.is_absolute() {
+                p.to_path_buf()
+            } else {
+                repo_root.join(p)
+            }
+        })
+        .unwrap_or_else(|| repo_root.join(METRICS_REL_PATH));
+    let body = collect_openmetrics(repo_root)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| MaccError::Io {
+            path: parent.to_string_lossy().into(),
+            action: "create metrics dir".into(),
+            source: e,
+        })?;
+    }
+    std::fs::write(&path, body).map_err(|e| MaccError::Io {
+        path: path.to_string_lossy().into(),
+        action: "write coordinator metrics".into(),
+        source: e,
+    })?;
+    Ok(path)
+}
+
+/// `GET /metrics` endpoint of a running coordinator; stops when dropped.
+pub struct MetricsServer {
+    addr: std::net::SocketAddr,
+    stop: Arc<AtomicBool>,
+    handle: Option<std::thread::JoinHandle<()>>,
+}
+
+impl MetricsServer {
+    pub fn start(repo_root: &Path, listen: &str) -> Result<Self> {
+        let listener = std::net::TcpListener::bind(listen).map_err(|e| MaccError::Io {
+            path: listen.to_string(),
+            action: "bind coordinator metrics listener".into(),
+            source: e,
+        })?;
+        let addr = listener.local_addr().map_err(|e| MaccError::Io {
+            path: listen.to_string(),
+            action: "resolve coordinator metrics listener".into(),
+            source: e,
+        })?;
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_flag = stop.clone();
+        let repo_root = repo_root.to_path_buf();
+        let handle = std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                if stop_flag.load(Ordering::SeqCst) {
+                    break;
+                }
+                if let Ok(stream) = stream {
+                    if let Err(err) = serve_connection(&repo_root, stream) {
+                        tracing::debug!("metrics request failed: {}", err);
+                    }
+                }
+            }
+        });
+        Ok(Self {
+            addr,
+            stop,
+            handle: Some(handle),
+        })
+    }
+
+    pub fn local_addr(&self) -> std::net::SocketAddr {
+        self.addr
+    }
+}
+
+impl Drop for MetricsServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        // Wake the accept loop so it sees the flag.
+        let _ = std::net::TcpStream::connect(self.addr);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn serve_connection(repo_root: &Path, stream: std::net::TcpStream) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(std::time::Duration::from_secs(5)))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Drain headers; the request has no body we care about.
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 0 && !header.trim_end().is_empty() {
+        header.clear();
+    }
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let target = parts.next().unwrap_or_default();
+    let path = target.split('?').next().unwrap_or_default();
+    let (status, content_type, body) = if method != "GET" {
+        (
+            "405 Method Not Allowed",
+            "text/plain",
+            "method not allowed\n".to_string(),
+        )
+    } else if path != "/metrics" {
+        ("404 Not Found", "text/plain", "not found\n".to_string())
+    } else {
+        match collect_openmetrics(repo_root) {
+            Ok(body) => ("200 OK", OPENMETRICS_CONTENT_TYPE, body),
+            Err(err) => (
+                "500 Internal Server Error",
+                "text/plain",
+                format!("{}\n", err),
+            ),
+        }
+    };
+    let mut stream = stream;
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+fn family(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+}
+
+fn sample(out: &mut String, name: &str, labels: &[(&str, &str)], value: u64) {
+    if labels.is_empty() {
+        let _ = writeln!(out, "{} {}", name, value);
+        return;
+    }
+    let labels = labels
+        .iter()
+        .map(|(key, value)| format!("{}=\"{}\"", key, escape_label(value)))
+        .collect::<Vec<_>>()
+        .join(",");
+    let _ = writeln!(out, "{}{{{}}} {}", name, labels, value);
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn format_float(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{:.1}", value)
+    } else {
+        format!("{}", value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_render_openmetrics_counts_states_jobs_and_phases() {
+        let registry = json!({"tasks": [
+            {"id": "T1", "state": "in_progress", "tool": "tool-a",
+             "task_runtime": {"status": "running", "metrics": {"retries": 2}}},
+            {"id": "T2", "state": "merged", "tool": "tool-a",
+             "task_runtime": {"status": "idle"}},
+            {"id": "T3", "state": "queued", "tool": "tool-b",
+             "task_runtime": {"status": "dispatched", "metrics": {"retries": 1}}},
+        ]});
+        let events = vec![
+            json!({"type": "task_phase_duration_seconds", "phase": "dev", "payload": {"seconds": 42.5}}),
+            json!({"type": "task_phase_duration_seconds", "phase": "dev", "payload": {"seconds": 700}}),
+            json!({"type": "task_phase_duration_seconds", "phase": "review", "payload": {"seconds": 3}}),
+            json!({"type": "local_merge_failed", "phase": "integrate"}),
+            json!({"type": "heartbeat"}),
+        ];
+        let text = render_openmetrics(&registry, &events);
+
+        assert!(text.contains("macc_coordinator_tasks{state=\"in_progress\"} 1\n"));
+        assert!(text.contains("macc_coordinator_tasks{state=\"todo\"} 0\n"));
+        assert!(text.contains("macc_coordinator_task_runtime{status=\"running\"} 1\n"));
+        assert!(text.contains("macc_coordinator_active_jobs{tool=\"tool-a\"} 1\n"));
+        assert!(text.contains("macc_coordinator_active_jobs{tool=\"tool-b\"} 1\n"));
+        assert!(text.contains("# TYPE macc_coordinator_task_retries gauge\n"));
+        assert!(text.contains("macc_coordinator_task_retries 3\n"));
+        assert!(text.contains("macc_coordinator_merge_failures_total 1\n"));
+        assert!(text.contains(
+            "macc_coordinator_phase_duration_seconds_bucket{phase=\"dev\",le=\"60.0\"} 1\n"
+        ));
+        assert!(text.contains(
+            "macc_coordinator_phase_duration_seconds_bucket{phase=\"dev\",le=\"+Inf\"} 2\n"
+        ));
+        assert!(text.contains("macc_coordinator_phase_duration_seconds_sum{phase=\"dev\"} 742.5\n"));
+        assert!(
+            text.contains("macc_coordinator_phase_duration_seconds_count{phase=\"review\"} 1\n")
+        );
+        assert!(text.ends_with("# EOF\n"));
+    }
+}
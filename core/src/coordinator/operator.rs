@@ -0,0 +1,646 @@
+//! Operator commands on single tasks (`macc coordinator task ...`).
+//!
+//! Workflow moves (requeue, abandon, block, unblock) are checked with
+//! [`is_valid_workflow_transition`] and saved in the same registry write as their operator
+//! stamp, then recorded as an `operator_action` event. Field edits (priority, pinned tool, extra dependencies) are kept under
+//! `task.operator` so the PRD sync of every cycle re-applies them instead of reverting them.
+//! Each change bumps `operator.revision`; the control plane keeps the newer operator copy when it
+//! saves a registry it loaded before the change. Merge approval decisions are stamped the same
+//! way.
+
+use crate::coordinator::helpers::{
+    append_coordinator_event_with_payload, now_iso_coordinator, recompute_resource_locks_from_tasks,
+};
+use crate::coordinator::replay::{record_task_mutation, TaskMutation};
+use crate::coordinator::state::{
+    apply_transition_to_task, coordinator_state_registry_load, coordinator_state_registry_save,
+};
+use crate::coordinator::{is_valid_workflow_transition, WorkflowState};
+use crate::coordinator_storage::TransitionMutation;
+use crate::{MaccError, Result};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskCommand {
+    List,
+    Show(String),
+    SetPriority { task_id: String, priority: String },
+    SetTool { task_id: String, tool: String },
+    Requeue(String),
+    Abandon(String),
+    Block(String),
+    Unblock(String),
+    AddDependency { task_id: String, dependency: String },
+}
+
+impl TaskCommand {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::List => "list",
+            Self::Show(_) => "show",
+            Self::SetPriority { .. } => "set-priority",
+            Self::SetTool { .. } => "set-tool",
+            Self::Requeue(_) => "requeue",
+            Self::Abandon(_) => "abandon",
+            Self::Block(_) => "block",
+            Self::Unblock(_) => "unblock",
+            Self::AddDependency { .. } => "add-dependency",
+        }
+    }
+
+    fn task_id(&self) -> &str {
+        match self {
+            Self::List => "",
+            Self::Show(id)
+            | Self::Requeue(id)
+            | Self::Abandon(id)
+            | Self::Block(id)
+            | Self::Unblock(id) => id,
+            Self::SetPriority { task_id, .. }
+            | Self::SetTool { task_id, .. }
+            | Self::AddDependency { task_id, .. } => task_id,
+        }
+    }
+}
+
+/// One row of `macc coordinator task list`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskSummary {
+    pub id: String,
+    pub title: String,
+    pub state: String,
+    pub priority: String,
+    pub tool: String,
+    pub runtime: String,
+    pub dependencies: Vec<String>,
+    /// Whether an operator changed the task.
+    pub operator: bool,
+}
+
+#[derive(Debug, Clone)]
+pub enum TaskCommandOutput {
+    List(Vec<TaskSummary>),
+    Show(Value),
+    Changed { task_id: String, message: String },
+}
+
+/// Actions that move the task to another workflow state (or release it to merge, for
+/// `approve`): a newer copy replaces the whole task.
+const TRANSITION_ACTIONS: [&str; 6] = [
+    "requeue", "abandon", "block", "unblock", "approve", "reject",
+];
+
+pub fn task_summaries(registry: &Value) -> Vec<TaskSummary> {
+    let text = |task: &Value, key: &str| match task.get(key) {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Number(n)) => n.to_string(),
+        _ => String::new(),
+    };
+    tasks(registry)
+        .iter()
+        .map(|task| TaskSummary {
+            id: text(task, "id"),
+            title: text(task, "title"),
+            state: task
+                .get("state")
+                .and_then(Value::as_str)
+                .unwrap_or("todo")
+                .to_string(),
+            priority: text(task, "priority"),
+            tool: text(task, "tool"),
+            runtime: task
+                .get("task_runtime")
+                .and_then(|r| r.get("status"))
+                .and_then(Value::as_str)
+                .unwrap_or("idle")
+                .to_string(),
+            dependencies: dependency_ids(task),
+            operator: task.get("operator").map(Value::is_object).unwrap_or(false),
+        })
+        .collect()
+}
+
+/// Runs `command` against the task registry, recording changes as `operator_action` events.
+pub fn run_task_command(
+    repo_root: &Path,
+    command: &TaskCommand,
+    user: &str,
+    comment: Option<&str>,
+) -> Result<TaskCommandOutput> {
+    let mut registry = coordinator_state_registry_load(repo_root, &BTreeMap::new())?;
+    let task_id = command.task_id();
+    let now = now_iso_coordinator();
+    let target = match command {
+        TaskCommand::List => return Ok(TaskCommandOutput::List(task_summaries(&registry))),
+        TaskCommand::Show(_) => {
+            return Ok(TaskCommandOutput::Show(
+                find_task(&registry, task_id)?.clone(),
+            ))
+        }
+        TaskCommand::Requeue(_) => WorkflowState::Todo,
+        TaskCommand::Abandon(_) => WorkflowState::Abandoned,
+        TaskCommand::Block(_) => WorkflowState::Blocked,
+        TaskCommand::Unblock(_) => unblock_target(find_task(&registry, task_id)?),
+        _ => {
+            let message = apply_operator_edit(&mut registry, command, user, &now)?;
+            coordinator_state_registry_save(repo_root, &BTreeMap::new(), &registry)?;
+            record_operator(repo_root, &registry, task_id);
+            append_coordinator_event_with_payload(
+                repo_root,
+                "operator_action",
+                task_id,
+                "operator",
+                "updated",
+                "info",
+                event_payload(command, &message, user, comment, &[]),
+            )?;
+            return Ok(TaskCommandOutput::Changed {
+                task_id: task_id.to_string(),
+                message,
+            });
+        }
+    };
+
+    let from = check_operator_transition(find_task(&registry, task_id)?, target)?;
+    let message = format!(
+        "Task {} {}: {} -> {} (by {})",
+        task_id,
+        command.name(),
+        from.as_str(),
+        target.as_str(),
+        user
+    );
+    let payload = event_payload(
+        command,
+        &message,
+        user,
+        comment,
+        &[("from", from.as_str()), ("to", target.as_str())],
+    );
+    // The move, its operator stamp and the revision bump land in one registry write, so a
+    // control plane saving a stale registry either keeps all of them or none.
+    let change = TransitionMutation {
+        task_id: task_id.to_string(),
+        new_state: target.as_str().to_string(),
+        pr_url: String::new(),
+        reviewer: String::new(),
+        reason: String::new(),
+        now: now.clone(),
+    };
+    let task = find_task_mut(&mut registry, task_id)?;
+    apply_transition_to_task(task, &change);
+    stamp(task, command.name(), user, &now);
+    if target == WorkflowState::Blocked {
+        task["operator"]["blocked_from"] = Value::String(from.as_str().to_string());
+    } else if let Some(operator) = task["operator"].as_object_mut() {
+        operator.remove("blocked_from");
+    }
+    let voided =
+        crate::coordinator::approval::void_departed_approval(&mut registry, task_id, &now)?;
+    recompute_resource_locks_from_tasks(&mut registry);
+    registry["updated_at"] = Value::String(now.clone());
+    coordinator_state_registry_save(repo_root, &BTreeMap::new(), &registry)?;
+    record_task_mutation(repo_root, task_id, &TaskMutation::Transition(change));
+    record_operator(repo_root, &registry, task_id);
+    if let Some(reason) = voided {
+        crate::coordinator::approval::record_voided_approval(repo_root, task_id, &reason, &now);
+    }
+    append_coordinator_event_with_payload(
+        repo_root,
+        "operator_action",
+        task_id,
+        "operator",
+        target.as_str(),
+        "info",
+        payload,
+    )?;
+    Ok(TaskCommandOutput::Changed {
+        task_id: task_id.to_string(),
+        message,
+    })
+}
+
+/// Records the operator record of `task_id` for registry replay.
+pub(crate) fn record_operator(repo_root: &Path, registry: &Value, task_id: &str) {
+    if let Ok(task) = find_task(registry, task_id) {
+        record_task_mutation(
+            repo_root,
+            task_id,
+            &TaskMutation::Operator {
+                operator: task["operator"].clone(),
+            },
+        );
+    }
+}
+
+/// Checks an operator move of `task` to `to` and returns the current state.
+///
+/// Tasks with a phase in flight are refused: the running performer would otherwise report
+/// into a task that no longer expects it.
+pub fn check_operator_transition(task: &Value, to: WorkflowState) -> Result<WorkflowState> {
+    let id = task.get("id").and_then(Value::as_str).unwrap_or_default();
+    let from = task
+        .get("state")
+        .and_then(Value::as_str)
+        .unwrap_or("todo")
+        .parse::<WorkflowState>()
+        .map_err(MaccError::Validation)?;
+    let runtime = task
+        .get("task_runtime")
+        .and_then(|r| r.get("status"))
+        .and_then(Value::as_str)
+        .unwrap_or("idle");
+    if matches!(runtime, "running" | "dispatched") {
+        return Err(MaccError::Validation(format!(
+            "Task {} has a {} phase in flight; wait for it to finish or stop the coordinator first",
+            id, runtime
+        )));
+    }
+    if !is_valid_workflow_transition(from, to) {
+        return Err(MaccError::Validation(format!(
+            "Task {} cannot move from {} to {}",
+            id,
+            from.as_str(),
+            to.as_str()
+        )));
+    }
+    Ok(from)
+}
+
+/// Applies a field edit (`set-priority`, `set-tool`, `add-dependency`) and returns a summary.
+pub fn apply_operator_edit(
+    registry: &mut Value,
+    command: &TaskCommand,
+    user: &str,
+    now: &str,
+) -> Result<String> {
+    let task_id = command.task_id();
+    let known: Vec<String> = tasks(registry)
+        .iter()
+        .filter_map(|t| t.get("id").and_then(Value::as_str).map(str::to_string))
+        .collect();
+    let cycle = match command {
+        TaskCommand::AddDependency { dependency, .. } => {
+            if !known.contains(dependency) {
+                return Err(MaccError::Validation(format!(
+                    "Dependency '{}' not found in registry",
+                    dependency
+                )));
+            }
+            dependency == task_id || depends_on(registry, dependency, task_id)
+        }
+        _ => false,
+    };
+    let task = find_task_mut(registry, task_id)?;
+    if task.get("state").and_then(Value::as_str) == Some("merged") {
+        return Err(MaccError::Validation(format!(
+            "Task {} is already merged",
+            task_id
+        )));
+    }
+    let message = match command {
+        TaskCommand::SetPriority { priority, .. } => {
+            let priority = normalize_priority(priority).ok_or_else(|| {
+                MaccError::Validation(format!(
+                    "Priority must be p0..p4 or a number (got '{}')",
+                    priority
+                ))
+            })?;
+            task["priority"] = Value::String(priority.clone());
+            ensure_operator(task);
+            task["operator"]["priority"] = Value::String(priority.clone());
+            format!(
+                "Task {} priority set to {} (by {})",
+                task_id, priority, user
+            )
+        }
+        TaskCommand::SetTool { tool, .. } => {
+            if task.get("worktree").map(|w| !w.is_null()).unwrap_or(false) {
+                return Err(MaccError::Validation(format!(
+                    "Task {} is already dispatched; requeue it before changing its tool",
+                    task_id
+                )));
+            }
+            task["tool"] = Value::String(tool.clone());
+            ensure_operator(task);
+            task["operator"]["tool"] = Value::String(tool.clone());
+            format!("Task {} pinned to tool {} (by {})", task_id, tool, user)
+        }
+        TaskCommand::AddDependency { dependency, .. } => {
+            if cycle {
+                return Err(MaccError::Validation(format!(
+                    "Task {} cannot depend on {}: that would create a dependency cycle",
+                    task_id, dependency
+                )));
+            }
+            if dependency_ids(task).contains(dependency) {
+                return Err(MaccError::Validation(format!(
+                    "Task {} already depends on {}",
+                    task_id, dependency
+                )));
+            }
+            ensure_operator(task);
+            push_unique(&mut task["dependencies"], dependency);
+            push_unique(&mut task["operator"]["dependencies"], dependency);
+            format!(
+                "Task {} now depends on {} (by {})",
+                task_id, dependency, user
+            )
+        }
+        other => {
+            return Err(MaccError::Validation(format!(
+                "'{}' is not a task field edit",
+                other.name()
+            )))
+        }
+    };
+    stamp(task, command.name(), user, now);
+    Ok(message)
+}
+
+/// Re-applies operator overrides after the PRD fields were copied onto `task`.
+pub fn apply_operator_overrides(task: &mut Value) {
+    let Some(operator) = task.get("operator").filter(|o| o.is_object()).cloned() else {
+        return;
+    };
+    for key in ["priority", "tool"] {
+        if let Some(value) = operator.get(key).filter(|v| v.is_string()) {
+            task[key] = value.clone();
+        }
+    }
+    for dependency in dependency_ids(&operator) {
+        push_unique(&mut task["dependencies"], &dependency);
+    }
+}
+
+/// Tool pinned with `set-tool`, which dispatch uses instead of routing rules.
+pub fn pinned_tool(task: &Value) -> Option<&str> {
+    task.get("operator")
+        .and_then(|o| o.get("tool"))
+        .and_then(Value::as_str)
+        .filter(|t| !t.is_empty())
+}
+
+/// Carries operator changes made since `registry` was loaded over from `latest`.
+///
+/// After a workflow move the operator copy replaces the task; after a field edit only the
+/// edited fields are taken, so the caller's progress on the task is kept.
+pub fn keep_operator_updates(registry: &mut Value, latest: &Value) {
+    let Some(current) = registry.get_mut("tasks").and_then(Value::as_array_mut) else {
+        return;
+    };
+    for task in current.iter_mut() {
+        let id = task.get("id").and_then(Value::as_str).unwrap_or_default();
+        let Some(newer) = tasks(latest)
+            .iter()
+            .find(|t| t.get("id").and_then(Value::as_str) == Some(id))
+            .filter(|t| revision(t) > revision(task))
+        else {
+            continue;
+        };
+        let action = newer["operator"]["last_action"]
+            .as_str()
+            .unwrap_or_default();
+        if TRANSITION_ACTIONS.contains(&action) {
+            *task = newer.clone();
+            continue;
+        }
+        for key in ["priority", "tool", "dependencies", "operator"] {
+            if let Some(value) = newer.get(key) {
+                task[key] = value.clone();
+            }
+        }
+    }
+}
+
+fn unblock_target(task: &Value) -> WorkflowState {
+    task.get("operator")
+        .and_then(|o| o.get("blocked_from"))
+        .and_then(Value::as_str)
+        .and_then(|s| s.parse::<WorkflowState>().ok())
+        .unwrap_or(WorkflowState::Todo)
+}
+
+fn event_payload(
+    command: &TaskCommand,
+    message: &str,
+    user: &str,
+    comment: Option<&str>,
+    extra: &[(&str, &str)],
+) -> Value {
+    let mut payload = json!({
+        "message": message,
+        "action": command.name(),
+        "user": user,
+        "comment": comment,
+    });
+    for (key, value) in extra {
+        payload[*key] = Value::String(value.to_string());
+    }
+    payload
+}
+
+fn normalize_priority(raw: &str) -> Option<String> {
+    let value = raw.trim().to_ascii_lowercase();
+    let digits = value.strip_prefix('p').unwrap_or(&value);
+    (!digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())).then_some(value)
+}
+
+/// Stamps an operator change made outside this module, such as an approval decision.
+pub(crate) fn stamp_task(
+    registry: &mut Value,
+    task_id: &str,
+    action: &str,
+    user: &str,
+    now: &str,
+) -> Result<()> {
+    stamp(find_task_mut(registry, task_id)?, action, user, now);
+    Ok(())
+}
+
+fn stamp(task: &mut Value, action: &str, user: &str, now: &str) {
+    ensure_operator(task);
+    let next = revision(task) + 1;
+    let operator = &mut task["operator"];
+    operator["revision"] = Value::from(next);
+    operator["last_action"] = Value::String(action.to_string());
+    operator["updated_by"] = Value::String(user.to_string());
+    operator["updated_at"] = Value::String(now.to_string());
+    task["updated_at"] = Value::String(now.to_string());
+}
+
+fn revision(task: &Value) -> u64 {
+    task.get("operator")
+        .and_then(|o| o.get("revision"))
+        .and_then(Value::as_u64)
+        .unwrap_or(0)
+}
+
+fn ensure_operator(task: &mut Value) {
+    if !task.get("operator").map(Value::is_object).unwrap_or(false) {
+        task["operator"] = json!({});
+    }
+}
+
+fn push_unique(list: &mut Value, id: &str) {
+    if !list.is_array() {
+        *list = Value::Array(Vec::new());
+    }
+    let items = list.as_array_mut().expect("array");
+    if !items.iter().any(|v| v.as_str() == Some(id)) {
+        items.push(Value::String(id.to_string()));
+    }
+}
+
+fn dependency_ids(task: &Value) -> Vec<String> {
+    task.get("dependencies")
+        .and_then(Value::as_array)
+        .map(|deps| {
+            deps.iter()
+                .filter_map(|d| {
+                    d.as_str()
+                        .map(str::to_string)
+                        .or_else(|| d.as_i64().map(|n| n.to_string()))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Whether `from` reaches `to` through dependencies.
+fn depends_on(registry: &Value, from: &str, to: &str) -> bool {
+    let mut stack = vec![from.to_string()];
+    let mut seen = std::collections::HashSet::new();
+    while let Some(id) = stack.pop() {
+        if id == to {
+            return true;
+        }
+        if !seen.insert(id.clone()) {
+            continue;
+        }
+        if let Ok(task) = find_task(registry, &id) {
+            stack.extend(dependency_ids(task));
+        }
+    }
+    false
+}
+
+fn tasks(registry: &Value) -> &[Value] {
+    registry
+        .get("tasks")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+fn find_task<'a>(registry: &'a Value, task_id: &str) -> Result<&'a Value> {
+    tasks(registry)
+        .iter()
+        .find(|t| t.get("id").and_then(Value::as_str) == Some(task_id))
+        .ok_or_else(|| MaccError::Validation(format!("Task '{}' not found in registry", task_id)))
+}
+
+fn find_task_mut<'a>(registry: &'a mut Value, task_id: &str) -> Result<&'a mut Value> {
+    registry
+        .get_mut("tasks")
+        .and_then(Value::as_array_mut)
+        .and_then(|tasks| {
+            tasks
+                .iter_mut()
+                .find(|t| t.get("id").and_then(Value::as_str) == Some(task_id))
+        })
+        .ok_or_else(|| MaccError::Validation(format!("Task '{}' not found in registry", task_id)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry() -> Value {
+        json!({"tasks": [
+            {"id": "T1", "state": "todo", "priority": "p2", "dependencies": []},
+            {"id": "T2", "state": "todo", "dependencies": ["T1"]},
+            {"id": "T3", "state": "in_progress", "task_runtime": {"status": "running"}},
+        ]})
+    }
+
+    #[test]
+    fn edits_validate_and_record_overrides() {
+        let mut registry = registry();
+        let set = TaskCommand::SetPriority {
+            task_id: "T2".into(),
+            priority: "P0".into(),
+        };
+        apply_operator_edit(&mut registry, &set, "alice", "now").unwrap();
+        let task = &registry["tasks"][1];
+        assert_eq!(task["priority"], "p0");
+        assert_eq!(task["operator"]["priority"], "p0");
+        assert_eq!(task["operator"]["updated_by"], "alice");
+        assert_eq!(task["operator"]["revision"], 1);
+
+        let bad = TaskCommand::SetPriority {
+            task_id: "T2".into(),
+            priority: "urgent".into(),
+        };
+        assert!(apply_operator_edit(&mut registry, &bad, "alice", "now").is_err());
+        let cycle = TaskCommand::AddDependency {
+            task_id: "T1".into(),
+            dependency: "T2".into(),
+        };
+        assert!(apply_operator_edit(&mut registry, &cycle, "alice", "now").is_err());
+        let add = TaskCommand::AddDependency {
+            task_id: "T3".into(),
+            dependency: "T2".into(),
+        };
+        apply_operator_edit(&mut registry, &add, "alice", "now").unwrap();
+        assert_eq!(registry["tasks"][2]["dependencies"], json!(["T2"]));
+
+        // The PRD sync overwrites the fields; the overrides come back on top.
+        let mut synced = registry["tasks"][1].clone();
+        synced["priority"] = json!("p3");
+        synced["dependencies"] = json!(["T1"]);
+        apply_operator_overrides(&mut synced);
+        assert_eq!(synced["priority"], "p0");
+    }
+
+    #[test]
+    fn transitions_follow_the_workflow_rules() {
+        let registry = registry();
+        let todo = &registry["tasks"][0];
+        assert_eq!(
+            check_operator_transition(todo, WorkflowState::PrOpen)
+                .unwrap_err()
+                .to_string(),
+            "Validation error: Task T1 cannot move from todo to pr_open"
+        );
+        assert!(check_operator_transition(&registry["tasks"][2], WorkflowState::Blocked).is_err());
+        let blocked =
+            json!({"id": "T4", "state": "blocked", "operator": {"blocked_from": "pr_open"}});
+        assert_eq!(unblock_target(&blocked), WorkflowState::PrOpen);
+        assert_eq!(
+            check_operator_transition(&blocked, WorkflowState::PrOpen).unwrap(),
+            WorkflowState::Blocked
+        );
+    }
+
+    #[test]
+    fn newer_operator_changes_survive_a_stale_save() {
+        let mut stale = registry();
+        stale["tasks"][1]["state"] = json!("claimed");
+        let mut latest = registry();
+        let set = TaskCommand::SetPriority {
+            task_id: "T2".into(),
+            priority: "p1".into(),
+        };
+        apply_operator_edit(&mut latest, &set, "alice", "now").unwrap();
+        latest["tasks"][0]["state"] = json!("abandoned");
+        stamp(&mut latest["tasks"][0], "abandon", "alice", "now");
+
+        keep_operator_updates(&mut stale, &latest);
+        assert_eq!(stale["tasks"][0]["state"], "abandoned");
+        assert_eq!(stale["tasks"][1]["state"], "claimed");
+        assert_eq!(stale["tasks"][1]["priority"], "p1");
+    }
+}
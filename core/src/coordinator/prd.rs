@@ -0,0 +1,192 @@
+use crate::config::MERGE_STRATEGIES;
+use crate::{MaccError, Result};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+
+const STRING_FIELDS: &[&str] = &[
+    "title",
+    "description",
+    "objective",
+    "result",
+    "category",
+    "base_branch",
+    "project",
+    "merge_strategy",
+];
+const STRING_LIST_FIELDS: &[&str] = &["steps", "dependencies", "exclusive_resources"];
+
+/// PRD task id; the PRD sync accepts strings and integers.
+pub fn prd_task_id(task: &Value) -> Option<String> {
+    match task.get("id") {
+        Some(Value::String(id)) if !id.trim().is_empty() => Some(id.clone()),
+        Some(Value::Number(id)) => id.as_i64().map(|id| id.to_string()),
+        _ => None,
+    }
+}
+
+/// Validates PRD task definitions: ids are unique (also against `existing_ids`), titles are
+/// set, fields have the types the coordinator reads, and dependencies name known tasks
+/// without forming a cycle. All problems are reported at once.
+pub fn validate_prd_tasks(tasks: &[Value], existing_ids: &HashSet<String>) -> Result<()> {
+    let mut issues = Vec::new();
+    let mut ids = HashSet::new();
+    for (index, task) in tasks.iter().enumerate() {
+        let Some(id) = prd_task_id(task) else {
+            issues.push(format!("tasks[{}]: missing id", index));
+            continue;
+        };
+        if existing_ids.contains(&id) || !ids.insert(id.clone()) {
+            issues.push(format!("task {}: duplicate id", id));
+        }
+    }
+
+    let mut graph: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (index, task) in tasks.iter().enumerate() {
+        let Some(object) = task.as_object() else {
+            issues.push(format!("tasks[{}]: not an object", index));
+            continue;
+        };
+        let label = prd_task_id(task)
+            .map(|id| format!("task {}", id))
+            .unwrap_or_else(|| format!("tasks[{}]", index));
+        let title = object.get("title").and_then(Value::as_str).unwrap_or("");
+        if title.trim().is_empty() {
+            issues.push(format!("{}: missing title", label));
+        }
+        for field in STRING_FIELDS {
+            if object.get(*field).is_some_and(|v| !v.is_string()) {
+                issues.push(format!("{}: {} must be a string", label, field));
+            }
+        }
+        for field in STRING_LIST_FIELDS {
+            if object.get(*field).is_some_and(|v| !is_string_list(v)) {
+                issues.push(format!("{}: {} must be a list of strings", label, field));
+            }
+        }
+        if object
+            .get("scope")
+            .is_some_and(|v| !v.is_string() && !is_string_list(v))
+        {
+            issues.push(format!(
+                "{}: scope must be a string or a list of strings",
+                label
+            ));
+        }
+        if let Some(strategy) = object.get("merge_strategy").and_then(Value::as_str) {
+            if !MERGE_STRATEGIES.contains(&strategy) {
+                issues.push(format!(
+                    "{}: merge_strategy must be one of {} (got '{}')",
+                    label,
+                    MERGE_STRATEGIES.join(", "),
+                    strategy
+                ));
+            }
+        }
+        let Some(id) = prd_task_id(task) else {
+            continue;
+        };
+        let dependencies: Vec<String> = object
+            .get("dependencies")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect();
+        for dependency in &dependencies {
+            if *dependency == id {
+                issues.push(format!("{}: depends on itself", label));
+            } else if !ids.contains(dependency) && !existing_ids.contains(dependency) {
+                issues.push(format!("{}: unknown dependency {}", label, dependency));
+            }
+        }
+        graph.insert(id, dependencies);
+    }
+    if let Some(cycle) = dependency_cycle(&graph) {
+        issues.push(format!("dependency cycle: {}", cycle.join(" -> ")));
+    }
+
+    if issues.is_empty() {
+        Ok(())
+    } else {
+        Err(MaccError::Validation(format!(
+            "Invalid PRD tasks: {}",
+            issues.join("; ")
+        )))
+    }
+}
+
+fn is_string_list(value: &Value) -> bool {
+    value
+        .as_array()
+        .is_some_and(|items| items.iter().all(Value::is_string))
+}
+
+/// First dependency cycle among the tasks of `graph`, as the ids along it.
+fn dependency_cycle(graph: &BTreeMap<String, Vec<String>>) -> Option<Vec<String>> {
+    fn visit(
+        id: &str,
+        graph: &BTreeMap<String, Vec<String>>,
+        done: &mut HashSet<String>,
+        path: &mut Vec<String>,
+    ) -> Option<Vec<String>> {
+        if let Some(start) = path.iter().position(|p| p == id) {
+            let mut cycle = path[start..].to_vec();
+            cycle.push(id.to_string());
+            return Some(cycle);
+        }
+        if done.contains(id) {
+            return None;
+        }
+        path.push(id.to_string());
+        for dependency in graph.get(id).into_iter().flatten() {
+            if graph.contains_key(dependency) {
+                if let Some(cycle) = visit(dependency, graph, done, path) {
+                    return Some(cycle);
+                }
+            }
+        }
+        path.pop();
+        done.insert(id.to_string());
+        None
+    }
+
+    let mut done = HashSet::new();
+    graph
+        .keys()
+        .find_map(|id| visit(id, graph, &mut done, &mut Vec::new()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reports_every_problem() {
+        let existing = HashSet::from(["T0".to_string()]);
+        let tasks = vec![
+            json!({"id": "T1", "title": "One", "dependencies": ["T0", "T2"]}),
+            json!({"id": "T2", "title": "Two", "dependencies": ["T1"], "scope": 3}),
+            json!({"id": "T0", "title": "", "dependencies": ["T9"]}),
+        ];
+        let err = validate_prd_tasks(&tasks, &existing)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("task T0: duplicate id"), "{}", err);
+        assert!(err.contains("task T0: missing title"), "{}", err);
+        assert!(err.contains("task T0: unknown dependency T9"), "{}", err);
+        assert!(err.contains("task T2: scope must be"), "{}", err);
+        assert!(err.contains("dependency cycle: T1 -> T2 -> T1"), "{}", err);
+    }
+
+    #[test]
+    fn accepts_dependencies_on_existing_tasks() {
+        let existing = HashSet::from(["T0".to_string()]);
+        let tasks = vec![
+            json!({"id": "T1", "title": "One", "dependencies": ["T0"], "scope": "src/**"}),
+            json!({"id": 2, "title": "Two", "dependencies": ["T1"], "merge_strategy": "squash"}),
+        ];
+        validate_prd_tasks(&tasks, &existing).unwrap();
+    }
+}
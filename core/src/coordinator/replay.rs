@@ -0,0 +1,720 @@
+//! Event-sourced rebuild of the task registry (`macc coordinator replay`).
+//!
+//! Every change the coordinator makes to a task's workflow or runtime state is recorded as a
+//! `task_mutation` event carrying the inputs of the transition. Replaying folds those events,
+//! in log order, through the same transition functions the engine used, starting from an
+//! empty registry. `verify` compares the result with the live registry task by task.
+//!
+//! Heartbeats, token usage and resource samples are not part of the replayed state. Events
+//! written before `task_mutation` recording existed carry no mutation, so older runs cannot be
+//! rebuilt.
+
+use crate::coordinator::control_plane::{
+    apply_stale_heartbeat_action, merge_prd_definitions, StaleHeartbeatAction,
+};
+use crate::coordinator::engine::{
+    self as coordinator_engine, AdvancePlan, DispatchClaimUpdate, JobCompletionInput, ReviewVerdict,
+};
+use crate::coordinator::forge::{PullRequest, ReviewComment};
+use crate::coordinator::helpers::{
+    append_coordinator_event_with_payload, recompute_resource_locks_from_tasks,
+};
+use crate::coordinator::size_guard::SizeCheck;
+use crate::coordinator::state::{apply_runtime_to_task, apply_transition_to_task};
+use crate::coordinator::WorkflowState;
+use crate::coordinator_storage::{
+    CoordinatorStorage, CoordinatorStoragePaths, RuntimeMutation, SqliteStorage, TransitionMutation,
+};
+use crate::service::interaction::InteractionRequest;
+use crate::{MaccError, ProjectPaths, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+pub const TASK_MUTATION_EVENT: &str = "task_mutation";
+
+/// One recorded change to a task, replayable with [`apply_task_mutation`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TaskMutation {
+    /// PRD sync; carries the definition fields of every task after the sync (task id `-`).
+    Synced {
+        definitions: Vec<Value>,
+    },
+    Claimed {
+        update: DispatchClaimUpdate,
+    },
+    ClaimRolledBack {
+        detail: String,
+    },
+    Pid {
+        pid: Option<i64>,
+    },
+    PhaseOutcome {
+        mode: String,
+        verdict: Option<ReviewVerdict>,
+        error: Option<String>,
+    },
+    JobCompleted {
+        input: JobCompletionInput,
+    },
+    MergeResult {
+        success: bool,
+        reason: String,
+    },
+    ForgePublished {
+        forge: String,
+        pr: PullRequest,
+        pushed_head: String,
+    },
+    ForgeChangesRequested {
+        comments: Vec<ReviewComment>,
+    },
+    ApprovalRequested {
+        request: InteractionRequest,
+        reasons: Vec<String>,
+        #[serde(default)]
+        head: Option<String>,
+    },
+    ApprovalDecided {
+        approved: bool,
+        user: String,
+        comment: Option<String>,
+    },
+    ApprovalVoided {
+        reason: String,
+    },
+    SizeChecked {
+        check: SizeCheck,
+    },
+    /// Decomposition into subtasks, carrying their PRD definitions.
+    Decomposed {
+        subtasks: Vec<Value>,
+    },
+    DecompositionFailed {
+        error: String,
+    },
+    SubtasksMerged,
+    Transition(TransitionMutation),
+    Runtime(RuntimeMutation),
+    StaleHeartbeat {
+        action: StaleHeartbeatAction,
+        detail: String,
+    },
+    DeadRuntime {
+        pid: i64,
+    },
+    /// Operator record after a `macc coordinator task` change.
+    Operator {
+        operator: Value,
+    },
+}
+
+impl TaskMutation {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Synced { .. } => "synced",
+            Self::Claimed { .. } => "claimed",
+            Self::ClaimRolledBack { .. } => "claim_rolled_back",
+            Self::Pid { .. } => "pid",
+            Self::PhaseOutcome { .. } => "phase_outcome",
+            Self::JobCompleted { .. } => "job_completed",
+            Self::MergeResult { .. } => "merge_result",
+            Self::ForgePublished { .. } => "forge_published",
+            Self::ForgeChangesRequested { .. } => "forge_changes_requested",
+            Self::ApprovalRequested { .. } => "approval_requested",
+            Self::ApprovalDecided { .. } => "approval_decided",
+            Self::ApprovalVoided { .. } => "approval_voided",
+            Self::SizeChecked { .. } => "size_checked",
+            Self::Decomposed { .. } => "decomposed",
+            Self::DecompositionFailed { .. } => "decomposition_failed",
+            Self::SubtasksMerged => "subtasks_merged",
+            Self::Transition(_) => "transition",
+            Self::Runtime(_) => "runtime",
+            Self::StaleHeartbeat { .. } => "stale_heartbeat",
+            Self::DeadRuntime { .. } => "dead_runtime",
+            Self::Operator { .. } => "operator",
+        }
+    }
+}
+
+/// Appends `mutation` to the event log. A failed append is logged rather than returned, like
+/// other coordinator events; replay then diverges from the live registry for that task.
+pub fn record_task_mutation(repo_root: &Path, task_id: &str, mutation: &TaskMutation) {
+    record_task_mutation_at(
+        repo_root,
+        task_id,
+        mutation,
+        &crate::coordinator::helpers::now_iso_coordinator(),
+    );
+}
+
+/// Like [`record_task_mutation`] with the timestamp the transition used.
+pub fn record_task_mutation_at(repo_root: &Path, task_id: &str, mutation: &TaskMutation, at: &str) {
+    let recorded = append_coordinator_event_with_payload(
+        repo_root,
+        TASK_MUTATION_EVENT,
+        task_id,
+        "registry",
+        mutation.kind(),
+        "info",
+        json!({
+            "message": format!("Task {} {}", task_id, mutation.kind()),
+            "at": at,
+            "mutation": mutation,
+        }),
+    );
+    if let Err(err) = recorded {
+        tracing::warn!(
+            "failed to record {} mutation of task {}; replay will diverge: {}",
+            mutation.kind(),
+            task_id,
+            err
+        );
+    }
+}
+
+/// Applies a recorded mutation to `registry` with the engine transition functions.
+pub fn apply_task_mutation(
+    registry: &mut Value,
+    task_id: &str,
+    mutation: &TaskMutation,
+    now: &str,
+) -> Result<()> {
+    if let TaskMutation::Synced { definitions } = mutation {
+        merge_prd_definitions(registry, definitions, now);
+        return Ok(());
+    }
+    match mutation {
+        TaskMutation::Claimed { update } => {
+            coordinator_engine::apply_dispatch_claim_in_registry(registry, update)
+        }
+        TaskMutation::Pid { pid } => {
+            coordinator_engine::apply_dispatch_pid_in_registry(registry, task_id, *pid)
+        }
+        TaskMutation::PhaseOutcome {
+            mode,
+            verdict,
+            error,
+        } => {
+            let state = task_state(registry, task_id)?;
+            let AdvancePlan::RunPhase(transition) = coordinator_engine::plan_advance(state) else {
+                return Err(MaccError::Validation(format!(
+                    "Task {} in state {} has no phase to complete",
+                    task_id,
+                    state.as_str()
+                )));
+            };
+            if transition.mode != mode {
+                return Err(MaccError::Validation(format!(
+                    "Task {} in state {} runs phase {}, not {}",
+                    task_id,
+                    state.as_str(),
+                    transition.mode,
+                    mode
+                )));
+            }
+            coordinator_engine::apply_phase_outcome_in_registry(
+                registry,
+                task_id,
+                transition.mode,
+                transition,
+                *verdict,
+                error.as_deref(),
+                now,
+            )
+        }
+        TaskMutation::JobCompleted { input } => {
+            coordinator_engine::apply_job_completion_in_registry(registry, task_id, input, now)
+                .map(|_| ())
+        }
+        TaskMutation::MergeResult { success, reason } => {
+            coordinator_engine::apply_merge_result_in_registry(
+                registry, task_id, *success, reason, now,
+            )
+        }
+        TaskMutation::ForgePublished {
+            forge,
+            pr,
+            pushed_head,
+        } => coordinator_engine::apply_forge_publish_in_registry(
+            registry,
+            task_id,
+            forge,
+            pr,
+            pushed_head,
+            now,
+        ),
+        TaskMutation::ForgeChangesRequested { comments } => {
+            coordinator_engine::apply_forge_changes_requested_in_registry(
+                registry, task_id, comments, now,
+            )
+        }
+        TaskMutation::ApprovalRequested {
+            request,
+            reasons,
+            head,
+        } => coordinator_engine::apply_approval_request_in_registry(
+            registry,
+            task_id,
+            request,
+            reasons,
+            head.as_deref(),
+            now,
+        ),
+        TaskMutation::ApprovalDecided {
+            approved,
+            user,
+            comment,
+        } => coordinator_engine::apply_approval_decision_in_registry(
+            registry,
+            task_id,
+            *approved,
+            user,
+            comment.as_deref(),
+            now,
+        ),
+        TaskMutation::ApprovalVoided { reason } => {
+            coordinator_engine::apply_approval_void_in_registry(registry, task_id, reason, now)
+        }
+        TaskMutation::SizeChecked { check } => {
+            coordinator_engine::apply_size_check_in_registry(registry, task_id, check, now)
+        }
+        TaskMutation::Decomposed { subtasks } => {
+            coordinator_engine::apply_decomposition_in_registry(registry, task_id, subtasks, now)
+        }
+        TaskMutation::DecompositionFailed { error } => {
+            coordinator_engine::apply_decomposition_failure_in_registry(
+                registry, task_id, error, now,
+            )
+        }
+        TaskMutation::SubtasksMerged => {
+            coordinator_engine::apply_subtasks_merged_in_registry(registry, task_id, now)
+        }
+        _ => {
+            let task = task_mut(registry, task_id)?;
+            match mutation {
+                TaskMutation::ClaimRolledBack { detail } => {
+                    coordinator_engine::apply_claim_rollback(task, detail, now)
+                }
+                TaskMutation::Transition(change) => apply_transition_to_task(task, change),
+                TaskMutation::Runtime(change) => apply_runtime_to_task(task, change),
+                TaskMutation::StaleHeartbeat { action, detail } => {
+                    apply_stale_heartbeat_action(task, *action, detail, now)
+                }
+                TaskMutation::DeadRuntime { pid } => {
+                    coordinator_engine::apply_dead_runtime_reset(task, *pid, now);
+                }
+                TaskMutation::Operator { operator } => {
+                    task["operator"] = operator.clone();
+                    crate::coordinator::operator::apply_operator_overrides(task);
+                }
+                _ => unreachable!("registry-level mutations are handled above"),
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Where a replay stops (inclusive).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplayUntil {
+    Timestamp(chrono::DateTime<chrono::Utc>),
+    EventId(String),
+}
+
+impl std::str::FromStr for ReplayUntil {
+    type Err = MaccError;
+
+    fn from_str(raw: &str) -> Result<Self> {
+        let raw = raw.trim();
+        if raw.is_empty() {
+            return Err(MaccError::Validation(
+                "--until expects an RFC 3339 timestamp or an event id".into(),
+            ));
+        }
+        Ok(match chrono::DateTime::parse_from_rfc3339(raw) {
+            Ok(ts) => Self::Timestamp(ts.with_timezone(&chrono::Utc)),
+            Err(_) => Self::EventId(raw.to_string()),
+        })
+    }
+}
+
+/// A mutation event that could not be applied.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayError {
+    pub event_id: String,
+    pub task_id: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ReplayOutcome {
+    pub registry: Value,
+    /// Mutation events applied.
+    pub applied: usize,
+    /// Id and timestamp of the last event taken into account.
+    pub last_event: Option<(String, String)>,
+    pub errors: Vec<ReplayError>,
+}
+
+/// Rebuilds the registry from `events` (in log order), stopping after `until`.
+pub fn replay_events(events: &[Value], until: Option<&ReplayUntil>) -> Result<ReplayOutcome> {
+    let mut outcome = ReplayOutcome {
+        registry: json!({"tasks": []}),
+        ..Default::default()
+    };
+    let mut reached = false;
+    for event in events {
+        let event_id = text(event, "event_id");
+        let ts = text(event, "ts");
+        if let Some(ReplayUntil::Timestamp(limit)) = until {
+            let after = chrono::DateTime::parse_from_rfc3339(&ts)
+                .map(|parsed| parsed.with_timezone(&chrono::Utc) > *limit)
+                .unwrap_or(false);
+            if after {
+                reached = true;
+                break;
+            }
+        }
+        outcome.last_event = Some((event_id.clone(), ts.clone()));
+        if text(event, "type") == TASK_MUTATION_EVENT {
+            let task_id = text(event, "task_id");
+            let payload = event.get("payload").cloned().unwrap_or(Value::Null);
+            let at = payload
+                .get("at")
+                .and_then(Value::as_str)
+                .unwrap_or(&ts)
+                .to_string();
+            let applied = serde_json::from_value::<TaskMutation>(
+                payload.get("mutation").cloned().unwrap_or(Value::Null),
+            )
+            .map_err(|e| MaccError::Validation(format!("unreadable mutation: {}", e)))
+            .and_then(|mutation| {
+                apply_task_mutation(&mut outcome.registry, &task_id, &mutation, &at)
+            });
+            match applied {
+                Ok(()) => {
+                    outcome.applied += 1;
+                    outcome.registry["updated_at"] = Value::String(at);
+                }
+                Err(err) => outcome.errors.push(ReplayError {
+                    event_id: event_id.clone(),
+                    task_id,
+                    message: err.to_string(),
+                }),
+            }
+        }
+        if matches!(until, Some(ReplayUntil::EventId(id)) if *id == event_id) {
+            reached = true;
+            break;
+        }
+    }
+    if let Some(ReplayUntil::EventId(id)) = until {
+        if !reached {
+            return Err(MaccError::Validation(format!(
+                "Event '{}' not found in the coordinator event log",
+                id
+            )));
+        }
+    }
+    recompute_resource_locks_from_tasks(&mut outcome.registry);
+    Ok(outcome)
+}
+
+/// Fields compared by `replay verify`.
+const VERIFIED_FIELDS: [&str; 9] = [
+    "state",
+    "tool",
+    "priority",
+    "dependencies",
+    "pr_url",
+    "worktree.branch",
+    "approval.status",
+    "task_runtime.status",
+    "task_runtime.current_phase",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldDifference {
+    pub field: String,
+    pub replayed: Value,
+    pub live: Value,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskDivergence {
+    pub task_id: String,
+    pub differences: Vec<FieldDifference>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ReplayVerification {
+    pub outcome: ReplayOutcome,
+    pub tasks_checked: usize,
+    pub divergences: Vec<TaskDivergence>,
+}
+
+/// Compares the replayed registry with the live one, per task.
+pub fn diff_registries(replayed: &Value, live: &Value) -> Vec<TaskDivergence> {
+    let index = |registry: &Value| -> BTreeMap<String, Value> {
+        registry
+            .get("tasks")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .map(|task| (text(task, "id"), task.clone()))
+            .filter(|(id, _)| !id.is_empty())
+            .collect()
+    };
+    let replayed = index(replayed);
+    let live = index(live);
+    let mut ids = replayed
+        .keys()
+        .chain(live.keys())
+        .cloned()
+        .collect::<Vec<_>>();
+    ids.sort();
+    ids.dedup();
+    let mut divergences = Vec::new();
+    for id in ids {
+        let differences = match (replayed.get(&id), live.get(&id)) {
+            (Some(r), Some(l)) => VERIFIED_FIELDS
+                .iter()
+                .filter_map(|field| {
+                    let (r, l) = (field_value(r, field), field_value(l, field));
+                    (r != l).then(|| FieldDifference {
+                        field: field.to_string(),
+                        replayed: r,
+                        live: l,
+                    })
+                })
+                .collect(),
+            (r, l) => vec![FieldDifference {
+                field: "task".into(),
+                replayed: Value::Bool(r.is_some()),
+                live: Value::Bool(l.is_some()),
+            }],
+        };
+        if !differences.is_empty() {
+            divergences.push(TaskDivergence {
+                task_id: id,
+                differences,
+            });
+        }
+    }
+    divergences
+}
+
+/// Replays the project's event log, stopping after `until`.
+pub fn replay_registry(repo_root: &Path, until: Option<&ReplayUntil>) -> Result<ReplayOutcome> {
+    // Events are appended to SQLite whatever the registry storage mode.
+    let paths = CoordinatorStoragePaths::from_project_paths(&ProjectPaths::from_root(repo_root));
+    let snapshot = SqliteStorage::new(paths).load_snapshot()?;
+    replay_events(&snapshot.events, until)
+}
+
+/// Replays the full event log and compares it with the live registry.
+pub fn verify_registry(repo_root: &Path) -> Result<ReplayVerification> {
+    let outcome = replay_registry(repo_root, None)?;
+    let live =
+        crate::coordinator::state::coordinator_state_registry_load(repo_root, &BTreeMap::new())?;
+    let tasks_checked = live
+        .get("tasks")
+        .and_then(Value::as_array)
+        .map(Vec::len)
+        .unwrap_or(0);
+    let divergences = diff_registries(&outcome.registry, &live);
+    Ok(ReplayVerification {
+        outcome,
+        tasks_checked,
+        divergences,
+    })
+}
+
+fn field_value(task: &Value, path: &str) -> Value {
+    let value = path
+        .split('.')
+        .try_fold(task, |node, key| node.get(key))
+        .cloned()
+        .unwrap_or(Value::Null);
+    match value {
+        // Absent and empty mean the same to the coordinator.
+        Value::String(s) if s.is_empty() => Value::Null,
+        Value::Array(a) if a.is_empty() => Value::Null,
+        other => other,
+    }
+}
+
+fn text(value: &Value, key: &str) -> String {
+    match value.get(key) {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Number(n)) => n.to_string(),
+        _ => String::new(),
+    }
+}
+
+fn task_state(registry: &Value, task_id: &str) -> Result<WorkflowState> {
+    registry
+        .get("tasks")
+        .and_then(Value::as_array)
+        .and_then(|tasks| tasks.iter().find(|t| text(t, "id") == task_id))
+        .ok_or_else(|| MaccError::Validation(format!("Task '{}' not found in registry", task_id)))?
+        .get("state")
+        .and_then(Value::as_str)
+        .unwrap_or("todo")
+        .parse::<WorkflowState>()
+        .map_err(MaccError::Validation)
+}
+
+fn task_mut<'a>(registry: &'a mut Value, task_id: &str) -> Result<&'a mut Value> {
+    registry
+        .get_mut("tasks")
+        .and_then(Value::as_array_mut)
+        .and_then(|tasks| tasks.iter_mut().find(|t| text(t, "id") == task_id))
+        .ok_or_else(|| MaccError::Validation(format!("Task '{}' not found in registry", task_id)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(id: &str, ts: &str, task_id: &str, mutation: TaskMutation) -> Value {
+        json!({
+            "event_id": id,
+            "ts": ts,
+            "type": TASK_MUTATION_EVENT,
+            "task_id": task_id,
+            "payload": {"at": ts, "mutation": mutation},
+        })
+    }
+
+    fn phase(mode: &str, verdict: Option<ReviewVerdict>) -> TaskMutation {
+        TaskMutation::PhaseOutcome {
+            mode: mode.into(),
+            verdict,
+            error: None,
+        }
+    }
+
+    fn lifecycle() -> Vec<Value> {
+        let claim = DispatchClaimUpdate {
+            task_id: "T1".into(),
+            tool: "tool-a".into(),
+            worktree_path: "/tmp/wt-1".into(),
+            branch: "ai/T1".into(),
+            base_branch: "main".into(),
+            last_commit: "abc".into(),
+            session_id: "s-1".into(),
+            pid: None,
+            phase: "dev".into(),
+            now: "2026-01-01T10:00:00Z".into(),
+        };
+        let done = JobCompletionInput {
+            success: true,
+            attempt: 1,
+            max_attempts: 3,
+            timed_out: false,
+            phase_timeout_seconds: 0,
+            elapsed_seconds: 5,
+            status_text: "done".into(),
+            error_code: None,
+            error_origin: None,
+            error_message: None,
+            auto_retry_error_codes: Vec::new(),
+            auto_retry_max: 0,
+        };
+        vec![
+            event(
+                "e1",
+                "2026-01-01T09:00:00Z",
+                "-",
+                TaskMutation::Synced {
+                    definitions: vec![json!({"id": "T1", "priority": "p1"}), json!({"id": "T2"})],
+                },
+            ),
+            event(
+                "e2",
+                "2026-01-01T10:00:00Z",
+                "T1",
+                TaskMutation::Claimed { update: claim },
+            ),
+            json!({"event_id": "h1", "ts": "2026-01-01T10:01:00Z", "type": "heartbeat", "task_id": "T1"}),
+            event(
+                "e3",
+                "2026-01-01T10:05:00Z",
+                "T1",
+                TaskMutation::JobCompleted { input: done },
+            ),
+            event(
+                "e4",
+                "2026-01-01T10:06:00Z",
+                "T1",
+                phase("review", Some(ReviewVerdict::Ok)),
+            ),
+            event("e5", "2026-01-01T10:07:00Z", "T1", phase("integrate", None)),
+            event(
+                "e6",
+                "2026-01-01T10:08:00Z",
+                "T1",
+                TaskMutation::MergeResult {
+                    success: true,
+                    reason: String::new(),
+                },
+            ),
+        ]
+    }
+
+    fn state(registry: &Value, index: usize) -> &str {
+        registry["tasks"][index]["state"].as_str().unwrap()
+    }
+
+    #[test]
+    fn replay_follows_the_engine_transitions() {
+        let outcome = replay_events(&lifecycle(), None).unwrap();
+        assert!(outcome.errors.is_empty(), "{:?}", outcome.errors);
+        assert_eq!(outcome.applied, 6);
+        assert_eq!(state(&outcome.registry, 0), "merged");
+        assert_eq!(state(&outcome.registry, 1), "todo");
+        assert_eq!(outcome.registry["tasks"][0]["pr_url"], "local://ai/T1");
+        assert_eq!(outcome.registry["updated_at"], "2026-01-01T10:08:00Z");
+
+        let until_id = replay_events(&lifecycle(), Some(&"e4".parse().unwrap())).unwrap();
+        assert_eq!(state(&until_id.registry, 0), "pr_open");
+        let until_ts =
+            replay_events(&lifecycle(), Some(&"2026-01-01T10:05:30Z".parse().unwrap())).unwrap();
+        assert_eq!(state(&until_ts.registry, 0), "in_progress");
+        assert_eq!(until_ts.last_event.unwrap().0, "e3");
+        assert!(replay_events(&lifecycle(), Some(&"missing".parse().unwrap())).is_err());
+    }
+
+    #[test]
+    fn replay_reports_mutations_that_do_not_apply_and_diffs_per_task() {
+        let mut events = lifecycle();
+        events.push(event(
+            "e7",
+            "2026-01-01T10:09:00Z",
+            "T1",
+            phase("review", None),
+        ));
+        let outcome = replay_events(&events, None).unwrap();
+        assert_eq!(outcome.errors.len(), 1);
+        assert_eq!(outcome.errors[0].event_id, "e7");
+
+        let mut live = outcome.registry.clone();
+        live["tasks"][0]["state"] = json!("blocked");
+        live["tasks"][1]["priority"] = json!("");
+        live["tasks"]
+            .as_array_mut()
+            .unwrap()
+            .push(json!({"id": "T3"}));
+        let divergences = diff_registries(&outcome.registry, &live);
+        assert_eq!(divergences.len(), 2);
+        assert_eq!(divergences[0].task_id, "T1");
+        assert_eq!(divergences[0].differences[0].field, "state");
+        assert_eq!(divergences[0].differences[0].live, json!("blocked"));
+        assert_eq!(divergences[1].task_id, "T3");
+        assert_eq!(divergences[1].differences[0].field, "task");
+    }
+}
@@ -0,0 +1,772 @@
+//! Run history: per-run archives under `.macc/runs/<run-id>/` and post-run reports.
+//!
+//! When a control-plane run ends, the coordinator stores the registry snapshot, the events of
+//! that run and pointers to the coordinator and performer logs. `macc coordinator runs` lists
+//! the archives and `macc coordinator report` summarizes one; a run that has not been archived
+//! yet (still running, or older than the archive) is reported from the live storage.
+
+use crate::coordinator_storage::{
+    CoordinatorSnapshot, CoordinatorStorage, CoordinatorStoragePaths, JsonStorage, SqliteStorage,
+};
+use crate::{MaccError, ProjectPaths, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+pub const RUNS_REL_DIR: &str = ".macc/runs";
+const MANIFEST_FILE: &str = "run.json";
+const REGISTRY_FILE: &str = "registry.json";
+const EVENTS_FILE: &str = "events.jsonl";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogPointer {
+    /// `coordinator` or `performer`.
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<String>,
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunManifest {
+    pub run_id: String,
+    /// `done`, `failed`, or `unarchived` for a run read from live storage.
+    pub status: String,
+    #[serde(default)]
+    pub started_at: Option<String>,
+    #[serde(default)]
+    pub ended_at: Option<String>,
+    #[serde(default)]
+    pub event_count: usize,
+    /// Workflow state counts of the tasks the run touched.
+    #[serde(default)]
+    pub task_states: BTreeMap<String, usize>,
+    #[serde(default)]
+    pub logs: Vec<LogPointer>,
+}
+
+/// Archived (or live) data of one run.
+#[derive(Debug, Clone)]
+pub struct RunRecord {
+    pub manifest: RunManifest,
+    pub registry: Value,
+    pub events: Vec<Value>,
+}
+
+pub fn runs_dir(repo_root: &Path) -> PathBuf {
+    repo_root.join(RUNS_REL_DIR)
+}
+
+pub(crate) fn load_live_snapshot(repo_root: &Path) -> Result<CoordinatorSnapshot> {
+    let paths = ProjectPaths::from_root(repo_root);
+    let storage_paths = CoordinatorStoragePaths::from_project_paths(&paths);
+    let sqlite = SqliteStorage::new(storage_paths.clone());
+    if sqlite.has_snapshot_data()? {
+        sqlite.load_snapshot()
+    } else {
+        JsonStorage::new(storage_paths).load_snapshot()
+    }
+}
+
+fn run_events(events: &[Value], run_id: &str) -> Vec<Value> {
+    events
+        .iter()
+        .filter(|event| event.get("run_id").and_then(Value::as_str) == Some(run_id))
+        .cloned()
+        .collect()
+}
+
+/// Task ids with at least one event in `events`.
+fn touched_tasks(events: &[Value]) -> BTreeSet<String> {
+    events
+        .iter()
+        .filter_map(|event| event.get("task_id").and_then(Value::as_str))
+        .filter(|task_id| !task_id.is_empty() && *task_id != "-")
+        .map(str::to_string)
+        .collect()
+}
+
+fn registry_tasks(registry: &Value) -> &[Value] {
+    registry
+        .get("tasks")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+fn build_manifest(
+    repo_root: &Path,
+    run_id: &str,
+    status: &str,
+    registry: &Value,
+    events: &[Value],
+) -> RunManifest {
+    let touched = touched_tasks(events);
+    let mut task_states: BTreeMap<String, usize> = BTreeMap::new();
+    let mut logs = Vec::new();
+    for task in registry_tasks(registry) {
+        let Some(task_id) = task.get("id").and_then(Value::as_str) else {
+            continue;
+        };
+        if !touched.contains(task_id) {
+            continue;
+        }
+        let state = task.get("state").and_then(Value::as_str).unwrap_or("todo");
+        *task_states.entry(state.to_string()).or_default() += 1;
+        if let Some(worktree) = task["worktree"]["worktree_path"].as_str() {
+            let performer_log = Path::new(worktree)
+                .join(".macc")
+                .join("log")
+                .join("performer")
+                .join(format!("{}.md", task_id));
+            if performer_log.is_file() {
+                logs.push(LogPointer {
+                    kind: "performer".to_string(),
+                    task_id: Some(task_id.to_string()),
+                    path: performer_log.to_string_lossy().to_string(),
+                });
+            }
+        }
+    }
+    let timestamps = events
+        .iter()
+        .filter_map(|event| event.get("ts").and_then(Value::as_str))
+        .collect::<Vec<_>>();
+    let started_at = timestamps.iter().min().map(|ts| ts.to_string());
+    let ended_at = timestamps.iter().max().map(|ts| ts.to_string());
+    logs.extend(coordinator_logs_since(repo_root, started_at.as_deref()));
+    RunManifest {
+        run_id: run_id.to_string(),
+        status: status.to_string(),
+        started_at,
+        ended_at,
+        event_count: events.len(),
+        task_states,
+        logs,
+    }
+}
+
+/// Coordinator log files written since `started_at`.
+fn coordinator_logs_since(repo_root: &Path, started_at: Option<&str>) -> Vec<LogPointer> {
+    let Some(started) = started_at
+        .and_then(|ts| chrono::DateTime::parse_from_rfc3339(ts).ok())
+        .map(|ts| std::time::SystemTime::from(ts.with_timezone(&chrono::Utc)))
+    else {
+        return Vec::new();
+    };
+    let dir = repo_root.join(".macc").join("log").join("coordinator");
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+    let mut logs = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("md"))
+        .filter(|path| {
+            std::fs::metadata(path)
+                .and_then(|meta| meta.modified())
+                .map(|modified| modified >= started)
+                .unwrap_or(false)
+        })
+        .map(|path| LogPointer {
+            kind: "coordinator".to_string(),
+            task_id: None,
+            path: path.to_string_lossy().to_string(),
+        })
+        .collect::<Vec<_>>();
+    logs.sort_by(|a, b| a.path.cmp(&b.path));
+    logs
+}
+
+/// Archives `run_id` under `.macc/runs/<run-id>/` and returns the directory.
+pub fn archive_run(repo_root: &Path, run_id: &str, status: &str) -> Result<PathBuf> {
+    let snapshot = load_live_snapshot(repo_root)?;
+    let events = run_events(&snapshot.events, run_id);
+    let manifest = build_manifest(repo_root, run_id, status, &snapshot.registry, &events);
+    let dir = runs_dir(repo_root).join(run_id);
+    std::fs::create_dir_all(&dir).map_err(|e| MaccError::Io {
+        path: dir.to_string_lossy().into(),
+        action: "create run archive dir".into(),
+        source: e,
+    })?;
+    let mut events_body = String::new();
+    for event in &events {
+        events_body.push_str(&event.to_string());
+        events_body.push('\n');
+    }
+    for (file, body) in [
+        (MANIFEST_FILE, to_pretty(&manifest)?),
+        (REGISTRY_FILE, to_pretty(&snapshot.registry)?),
+        (EVENTS_FILE, events_body),
+    ] {
+        let path = dir.join(file);
+        std::fs::write(&path, body).map_err(|e| MaccError::Io {
+            path: path.to_string_lossy().into(),
+            action: "write run archive".into(),
+            source: e,
+        })?;
+    }
+    Ok(dir)
+}
+
+fn to_pretty<T: Serialize>(value: &T) -> Result<String> {
+    serde_json::to_string_pretty(value)
+        .map_err(|e| MaccError::Validation(format!("Failed to serialize run archive: {}", e)))
+}
+
+/// Archived runs, most recent first.
+pub fn list_runs(repo_root: &Path) -> Result<Vec<RunManifest>> {
+    let dir = runs_dir(repo_root);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let entries = std::fs::read_dir(&dir).map_err(|e| MaccError::Io {
+        path: dir.to_string_lossy().into(),
+        action: "read run archive dir".into(),
+        source: e,
+    })?;
+    let mut runs = Vec::new();
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let manifest_path = entry.path().join(MANIFEST_FILE);
+        let Ok(raw) = std::fs::read_to_string(&manifest_path) else {
+            continue;
+        };
+        match serde_json::from_str::<RunManifest>(&raw) {
+            Ok(manifest) => runs.push(manifest),
+            Err(err) => tracing::warn!(
+                "Skipping unreadable run manifest {}: {}",
+                manifest_path.display(),
+                err
+            ),
+        }
+    }
+    runs.sort_by(|a, b| {
+        b.started_at
+            .cmp(&a.started_at)
+            .then_with(|| b.run_id.cmp(&a.run_id))
+    });
+    Ok(runs)
+}
+
+/// Archive of `run_id` (default: latest archived run), or the live storage when the run has
+/// no archive yet.
+pub fn load_run(repo_root: &Path, run_id: Option<&str>) -> Result<RunRecord> {
+    let run_id = match run_id {
+        Some(run_id) => run_id.to_string(),
+        None => match list_runs(repo_root)?.into_iter().next() {
+            Some(latest) => latest.run_id,
+            None => {
+                let snapshot = load_live_snapshot(repo_root)?;
+                crate::coordinator::trace::latest_run_id(&snapshot.events)
+                    .ok_or_else(|| MaccError::Validation("No coordinator run found".into()))?
+            }
+        },
+    };
+    let dir = runs_dir(repo_root).join(&run_id);
+    if dir.join(MANIFEST_FILE).is_file() {
+        let read = |file: &str| {
+            let path = dir.join(file);
+            std::fs::read_to_string(&path).map_err(|e| MaccError::Io {
+                path: path.to_string_lossy().into(),
+                action: "read run archive".into(),
+                source: e,
+            })
+        };
+        let parse = |file: &str, raw: &str| {
+            serde_json::from_str::<Value>(raw).map_err(|e| {
+                MaccError::Validation(format!("Invalid run archive {}/{}: {}", run_id, file, e))
+            })
+        };
+        let manifest = serde_json::from_value(parse(MANIFEST_FILE, &read(MANIFEST_FILE)?)?)
+            .map_err(|e| MaccError::Validation(format!("Invalid run manifest: {}", e)))?;
+        let registry = parse(REGISTRY_FILE, &read(REGISTRY_FILE)?)?;
+        let events = read(EVENTS_FILE)?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| parse(EVENTS_FILE, line))
+            .collect::<Result<Vec<_>>>()?;
+        return Ok(RunRecord {
+            manifest,
+            registry,
+            events,
+        });
+    }
+    let snapshot = load_live_snapshot(repo_root)?;
+    let events = run_events(&snapshot.events, &run_id);
+    if events.is_empty() {
+        return Err(MaccError::Validation(format!(
+            "Coordinator run '{}' not found",
+            run_id
+        )));
+    }
+    let manifest = build_manifest(
+        repo_root,
+        &run_id,
+        "unarchived",
+        &snapshot.registry,
+        &events,
+    );
+    Ok(RunRecord {
+        manifest,
+        registry: snapshot.registry,
+        events,
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Markdown,
+    Json,
+    Html,
+}
+
+impl FromStr for ReportFormat {
+    type Err = MaccError;
+
+    fn from_str(value: &str) -> std::result::Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "md" | "markdown" => Ok(ReportFormat::Markdown),
+            "json" => Ok(ReportFormat::Json),
+            "html" => Ok(ReportFormat::Html),
+            other => Err(MaccError::Validation(format!(
+                "Unknown report format '{}' (expected md, json or html)",
+                other
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PhaseStats {
+    pub phase: String,
+    pub count: u64,
+    pub failed: u64,
+    pub total_seconds: f64,
+    pub max_seconds: f64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct TaskSummary {
+    pub id: String,
+    pub title: String,
+    pub state: String,
+    pub tool: String,
+    pub retries: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error_code: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct MergeConflict {
+    pub task_id: String,
+    pub ts: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RunReport {
+    pub run_id: String,
+    pub status: String,
+    pub started_at: Option<String>,
+    pub ended_at: Option<String>,
+    pub duration_seconds: Option<i64>,
+    pub merged: usize,
+    pub blocked: usize,
+    pub abandoned: usize,
+    pub other: usize,
+    pub tasks: Vec<TaskSummary>,
+    pub phases: Vec<PhaseStats>,
+    /// Failed attempts per error code.
+    pub failures_by_error_code: BTreeMap<String, u64>,
+    /// Tasks per tool.
+    pub tools: BTreeMap<String, usize>,
+    pub merge_conflicts: Vec<MergeConflict>,
+    pub logs: Vec<LogPointer>,
+}
+
+impl RunReport {
+    pub fn from_record(record: &RunRecord) -> Self {
+        let manifest = &record.manifest;
+        let touched = touched_tasks(&record.events);
+        let mut report = RunReport {
+            run_id: manifest.run_id.clone(),
+            status: manifest.status.clone(),
+            started_at: manifest.started_at.clone(),
+            ended_at: manifest.ended_at.clone(),
+            logs: manifest.logs.clone(),
+            ..Default::default()
+        };
+        if let (Some(start), Some(end)) = (&manifest.started_at, &manifest.ended_at) {
+            if let (Ok(start), Ok(end)) = (
+                chrono::DateTime::parse_from_rfc3339(start),
+                chrono::DateTime::parse_from_rfc3339(end),
+            ) {
+                report.duration_seconds = Some((end - start).num_seconds());
+            }
+        }
+
+        for task in registry_tasks(&record.registry) {
+            let id = task.get("id").and_then(Value::as_str).unwrap_or_default();
+            if !touched.contains(id) {
+                continue;
+            }
+            let state = task.get("state").and_then(Value::as_str).unwrap_or("todo");
+            match state {
+                "merged" => report.merged += 1,
+                "blocked" => report.blocked += 1,
+                "abandoned" => report.abandoned += 1,
+                _ => report.other += 1,
+            }
+            let tool = task.get("tool").and_then(Value::as_str).unwrap_or_default();
+            if !tool.is_empty() {
+                *report.tools.entry(tool.to_string()).or_default() += 1;
+            }
+            report.tasks.push(TaskSummary {
+                id: id.to_string(),
+                title: task
+                    .get("title")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+                state: state.to_string(),
+                tool: tool.to_string(),
+                retries: task["task_runtime"]["metrics"]["retries"]
+                    .as_u64()
+                    .unwrap_or(0),
+                last_error_code: task["task_runtime"]["last_error_code"]
+                    .as_str()
+                    .filter(|code| !code.is_empty())
+                    .map(str::to_string),
+            });
+        }
+
+        let mut phases: BTreeMap<String, PhaseStats> = BTreeMap::new();
+        for event in &record.events {
+            let event_type = event
+                .get("type")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            let status = event
+                .get("status")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            let task_id = event
+                .get("task_id")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            match event_type {
+                "task_phase_duration_seconds" => {
+                    let Some(seconds) = event["payload"]["seconds"].as_f64() else {
+                        continue;
+                    };
+                    let phase = event.get("phase").and_then(Value::as_str).unwrap_or("-");
+                    let stats = phases
+                        .entry(phase.to_string())
+                        .or_insert_with(|| PhaseStats {
+                            phase: phase.to_string(),
+                            ..Default::default()
+                        });
+                    stats.count += 1;
+                    if status == "failed" {
+                        stats.failed += 1;
+                    }
+                    stats.total_seconds += seconds;
+                    stats.max_seconds = stats.max_seconds.max(seconds);
+                }
+                "phase_result" if status == "failed" => {
+                    let code = event["payload"]["error_code"].as_str().unwrap_or("unknown");
+                    *report
+                        .failures_by_error_code
+                        .entry(code.to_string())
+                        .or_default() += 1;
+                }
+                "local_merge_failed" => report.merge_conflicts.push(MergeConflict {
+                    task_id: task_id.to_string(),
+                    ts: event
+                        .get("ts")
+                        .and_then(Value::as_str)
+                        .unwrap_or_default()
+                        .to_string(),
+                    reason: event["payload"]["message"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                }),
+                _ => {}
+            }
+        }
+        report.phases = phases.into_values().collect();
+        report
+    }
+
+    pub fn render(&self, format: ReportFormat) -> Result<String> {
+        match format {
+            ReportFormat::Markdown => Ok(self.to_markdown()),
+            ReportFormat::Json => serde_json::to_string_pretty(self)
+                .map_err(|e| MaccError::Validation(format!("Failed to serialize report: {}", e))),
+            ReportFormat::Html => Ok(self.to_html()),
+        }
+    }
+
+    fn sections(&self) -> Vec<(String, Vec<String>, Vec<Vec<String>>)> {
+        let mut sections = vec![(
+            "Tasks".to_string(),
+            vec!["Task", "Title", "State", "Tool", "Retries", "Last error"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            self.tasks
+                .iter()
+                .map(|task| {
+                    vec![
+                        task.id.clone(),
+                        task.title.clone(),
+                        task.state.clone(),
+                        task.tool.clone(),
+                        task.retries.to_string(),
+                        task.last_error_code.clone().unwrap_or_default(),
+                    ]
+                })
+                .collect(),
+        )];
+        sections.push((
+            "Phase durations".to_string(),
+            vec![
+                "Phase",
+                "Runs",
+                "Failed",
+                "Total (s)",
+                "Mean (s)",
+                "Max (s)",
+            ]
+            .into_iter()
+            .map(String::from)
+            .collect(),
+            self.phases
+                .iter()
+                .map(|stats| {
+                    vec![
+                        stats.phase.clone(),
+                        stats.count.to_string(),
+                        stats.failed.to_string(),
+                        format!("{:.1}", stats.total_seconds),
+                        format!("{:.1}", stats.total_seconds / stats.count.max(1) as f64),
+                        format!("{:.1}", stats.max_seconds),
+                    ]
+                })
+                .collect(),
+        ));
+        sections.push((
+            "Failed attempts by error code".to_string(),
+            vec!["Error code".to_string(), "Count".to_string()],
+            self.failures_by_error_code
+                .iter()
+                .map(|(code, count)| vec![code.clone(), count.to_string()])
+                .collect(),
+        ));
+        sections.push((
+            "Tools".to_string(),
+            vec!["Tool".to_string(), "Tasks".to_string()],
+            self.tools
+                .iter()
+                .map(|(tool, count)| vec![tool.clone(), count.to_string()])
+                .collect(),
+        ));
+        sections.push((
+            "Merge conflicts".to_string(),
+            vec!["Task".to_string(), "Time".to_string(), "Reason".to_string()],
+            self.merge_conflicts
+                .iter()
+                .map(|conflict| {
+                    vec![
+                        conflict.task_id.clone(),
+                        conflict.ts.clone(),
+                        conflict.reason.clone(),
+                    ]
+                })
+                .collect(),
+        ));
+        sections.push((
+            "Logs".to_string(),
+            vec!["Kind".to_string(), "Task".to_string(), "Path".to_string()],
+            self.logs
+                .iter()
+                .map(|log| {
+                    vec![
+                        log.kind.clone(),
+                        log.task_id.clone().unwrap_or_default(),
+                        log.path.clone(),
+                    ]
+                })
+                .collect(),
+        ));
+        sections
+    }
+
+    fn summary_lines(&self) -> Vec<String> {
+        vec![
+            format!("Status: {}", self.status),
+            format!(
+                "Started: {} / Ended: {} ({})",
+                self.started_at.as_deref().unwrap_or("-"),
+                self.ended_at.as_deref().unwrap_or("-"),
+                self.duration_seconds
+                    .map(|s| format!("{}s", s))
+                    .unwrap_or_else(|| "-".to_string())
+            ),
+            format!(
+                "Tasks: {} merged, {} blocked, {} abandoned, {} other",
+                self.merged, self.blocked, self.abandoned, self.other
+            ),
+        ]
+    }
+
+    fn to_markdown(&self) -> String {
+        let mut out = format!("# Coordinator run {}\n\n", self.run_id);
+        for line in self.summary_lines() {
+            let _ = writeln!(out, "- {}", line);
+        }
+        for (title, header, rows) in self.sections() {
+            let _ = write!(out, "\n## {}\n\n", title);
+            if rows.is_empty() {
+                out.push_str("None.\n");
+                continue;
+            }
+            let _ = writeln!(out, "| {} |", header.join(" | "));
+            let _ = writeln!(out, "|{}", "---|".repeat(header.len()));
+            for row in rows {
+                let cells = row
+                    .iter()
+                    .map(|cell| cell.replace('|', "\\|").replace('\n', " "))
+                    .collect::<Vec<_>>();
+                let _ = writeln!(out, "| {} |", cells.join(" | "));
+            }
+        }
+        out
+    }
+
+    fn to_html(&self) -> String {
+        let title = format!("Coordinator run {}", html_escape(&self.run_id));
+        let mut out = format!(
+            "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{0}</title>\
+             <style>body{{font-family:sans-serif}}table{{border-collapse:collapse}}\
+             td,th{{border:1px solid #ccc;padding:4px 8px;text-align:left}}</style>\
+             </head><body>\n<h1>{0}</h1>\n<ul>\n",
+            title
+        );
+        for line in self.summary_lines() {
+            let _ = writeln!(out, "<li>{}</li>", html_escape(&line));
+        }
+        out.push_str("</ul>\n");
+        for (section, header, rows) in self.sections() {
+            let _ = writeln!(out, "<h2>{}</h2>", html_escape(&section));
+            if rows.is_empty() {
+                out.push_str("<p>None.</p>\n");
+                continue;
+            }
+            out.push_str("<table>\n<tr>");
+            for cell in &header {
+                let _ = write!(out, "<th>{}</th>", html_escape(cell));
+            }
+            out.push_str("</tr>\n");
+            for row in rows {
+                out.push_str("<tr>");
+                for cell in &row {
+                    let _ = write!(out, "<td>{}</td>", html_escape(cell));
+                }
+                out.push_str("</tr>\n");
+            }
+            out.push_str("</table>\n");
+        }
+        out.push_str("</body></html>\n");
+        out
+    }
+}
+
+fn html_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_archive_and_report_run() {
+        let root = std::env::temp_dir().join(format!(
+            "macc_runs_{}",
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        let paths = ProjectPaths::from_root(&root);
+        let storage_paths = CoordinatorStoragePaths::from_project_paths(&paths);
+        std::fs::create_dir_all(storage_paths.registry_json_path.parent().unwrap()).unwrap();
+        std::fs::create_dir_all(storage_paths.events_jsonl_path.parent().unwrap()).unwrap();
+        std::fs::write(
+            &storage_paths.registry_json_path,
+            json!({"tasks": [
+                {"id": "T1", "title": "One", "state": "merged", "tool": "tool-a",
+                 "task_runtime": {"metrics": {"retries": 1}}},
+                {"id": "T2", "title": "Two", "state": "blocked", "tool": "tool-b",
+                 "task_runtime": {"last_error_code": "E101"}},
+                {"id": "T3", "title": "Untouched", "state": "todo"},
+            ]})
+            .to_string(),
+        )
+        .unwrap();
+        let events = [
+            json!({"run_id": "run-old", "ts": "2026-01-01T09:00:00Z", "type": "started", "task_id": "T3"}),
+            json!({"run_id": "run-1", "ts": "2026-01-01T10:00:00Z", "type": "command_start", "task_id": "-", "phase": "run", "status": "started"}),
+            json!({"run_id": "run-1", "ts": "2026-01-01T10:01:00Z", "type": "phase_result", "task_id": "T2", "phase": "dev", "status": "failed", "payload": {"error_code": "E101"}}),
+            json!({"run_id": "run-1", "ts": "2026-01-01T10:02:00Z", "type": "task_phase_duration_seconds", "task_id": "T1", "phase": "dev", "status": "done", "payload": {"seconds": 90.0}}),
+            json!({"run_id": "run-1", "ts": "2026-01-01T10:03:00Z", "type": "task_phase_duration_seconds", "task_id": "T2", "phase": "dev", "status": "failed", "payload": {"seconds": 30.0}}),
+            json!({"run_id": "run-1", "ts": "2026-01-01T10:04:00Z", "type": "local_merge_failed", "task_id": "T2", "phase": "integrate", "status": "failed", "payload": {"message": "conflict in a.rs"}}),
+        ];
+        let body = events
+            .iter()
+            .map(|event| event.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        std::fs::write(&storage_paths.events_jsonl_path, body).unwrap();
+
+        let dir = archive_run(&root, "run-1", "done").unwrap();
+        assert!(dir.join(REGISTRY_FILE).is_file());
+        let archived_events = std::fs::read_to_string(dir.join(EVENTS_FILE)).unwrap();
+        assert_eq!(archived_events.lines().count(), 5);
+
+        let runs = list_runs(&root).unwrap();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].task_states.get("merged"), Some(&1));
+        assert_eq!(runs[0].started_at.as_deref(), Some("2026-01-01T10:00:00Z"));
+
+        let report = RunReport::from_record(&load_run(&root, None).unwrap());
+        assert_eq!((report.merged, report.blocked, report.other), (1, 1, 0));
+        assert_eq!(report.duration_seconds, Some(240));
+        assert_eq!(report.failures_by_error_code.get("E101"), Some(&1));
+        assert_eq!(report.tools.len(), 2);
+        assert_eq!(report.phases[0].count, 2);
+        assert_eq!(report.phases[0].failed, 1);
+        assert_eq!(report.merge_conflicts[0].reason, "conflict in a.rs");
+
+        let markdown = report.render(ReportFormat::Markdown).unwrap();
+        assert!(markdown.contains("| dev | 2 | 1 | 120.0 | 60.0 | 90.0 |"));
+        let html = report.render(ReportFormat::Html).unwrap();
+        assert!(html.contains("<td>conflict in a.rs</td>"));
+        let parsed: Value =
+            serde_json::from_str(&report.render(ReportFormat::Json).unwrap()).unwrap();
+        assert_eq!(parsed["merged"], json!(1));
+
+        // Runs without an archive are read from live storage.
+        let live = load_run(&root, Some("run-old")).unwrap();
+        assert_eq!(live.manifest.status, "unarchived");
+        assert!(load_run(&root, Some("run-missing")).is_err());
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
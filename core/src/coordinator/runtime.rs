@@ -13,6 +13,8 @@ pub struct CoordinatorJob {
     pub attempt: usize,
     pub started_at: std::time::Instant,
     pub pid: Option<i64>,
+    /// Resource limits the performer was started with (reused for retries).
+    pub limits: crate::config::ResourceLimits,
 }
 
 #[derive(Debug, Clone)]
@@ -29,6 +31,7 @@ pub struct CoordinatorJobEvent {
     pub error_code: Option<String>,
     pub error_origin: Option<String>,
     pub error_message: Option<String>,
+    pub retry_after_seconds: Option<u64>,
 }
 
 #[derive(Debug, Clone)]
@@ -53,6 +56,27 @@ pub struct CoordinatorRunState {
     pub dispatch_retry_not_before: HashMap<String, std::time::Instant>,
     pub dispatched_total_run: usize,
     pub dispatch_limit_event_emitted: bool,
+    /// Prices from `automation.coordinator.token_prices`, keyed by tool id.
+    pub token_prices: std::collections::BTreeMap<String, crate::config::TokenPrice>,
+    /// Usage ingested by this run so far (checked against `budget_usd`).
+    pub run_usage: crate::coordinator::usage::UsageTotals,
+    pub budget_event_emitted: bool,
+    /// Pull request backend from `automation.coordinator.forge`; `None` keeps PRs local.
+    pub forge: Option<crate::coordinator::forge::Forge>,
+    /// Last review status poll per task, throttled by the forge `poll_seconds`.
+    pub forge_polled_at: HashMap<String, std::time::Instant>,
+    /// Human approval policy from `automation.coordinator.approval`, checked before merges.
+    pub approval: Option<crate::config::ApprovalPolicy>,
+    /// Local merge strategy, message and signing from `automation.coordinator.merge`.
+    pub merge: Option<crate::config::MergeConfig>,
+    /// Small-PR limits from `automation.coordinator.size_guard`, checked after dev and fix.
+    pub size_guard: Option<crate::config::SizeGuardConfig>,
+    /// Decomposition of blocked tasks from `automation.coordinator.decompose`.
+    pub decompose: Option<crate::config::DecomposeConfig>,
+    /// Config profile (`--profile`), exported as `MACC_PROFILE` to spawned `macc` processes.
+    pub config_profile: Option<String>,
+    /// Worktree pool `env`, set on spawned performers.
+    pub child_env: std::collections::BTreeMap<String, String>,
 }
 
 pub trait PhaseExecutor {
@@ -84,6 +108,17 @@ impl CoordinatorRunState {
             dispatch_retry_not_before: HashMap::new(),
             dispatched_total_run: 0,
             dispatch_limit_event_emitted: false,
+            token_prices: std::collections::BTreeMap::new(),
+            run_usage: Default::default(),
+            budget_event_emitted: false,
+            forge: None,
+            forge_polled_at: HashMap::new(),
+            approval: None,
+            merge: None,
+            size_guard: None,
+            decompose: None,
+            config_profile: None,
+            child_env: std::collections::BTreeMap::new(),
         }
     }
 }
@@ -212,51 +247,6 @@ pub fn run_review_phase<E: PhaseExecutor>(
     Ok(Ok(verdict))
 }
 
-pub fn resolve_phase_runner(
-    repo_root: &Path,
-    worktree_path: &Path,
-    tool: &str,
-) -> Result<Option<PathBuf>> {
-    let explicit = worktree_path
-        .join(".macc")
-        .join("automation")
-        .join("runners")
-        .join(format!("{}.performer.sh", tool));
-    if explicit.exists() {
-        return Ok(Some(explicit));
-    }
-    let tool_json_path = worktree_path.join(".macc").join("tool.json");
-    if !tool_json_path.exists() {
-        return Ok(None);
-    }
-    let raw = std::fs::read_to_string(&tool_json_path).map_err(|e| MaccError::Io {
-        path: tool_json_path.to_string_lossy().into(),
-        action: "read tool.json for phase runner".into(),
-        source: e,
-    })?;
-    let value: serde_json::Value = serde_json::from_str(&raw).map_err(|e| {
-        MaccError::Validation(format!(
-            "Failed to parse tool.json for phase runner {}: {}",
-            tool_json_path.display(),
-            e
-        ))
-    })?;
-    let runner = value
-        .get("performer")
-        .and_then(|v| v.get("runner"))
-        .and_then(serde_json::Value::as_str)
-        .unwrap_or_default();
-    if runner.is_empty() {
-        return Ok(None);
-    }
-    let path = if Path::new(runner).is_absolute() {
-        PathBuf::from(runner)
-    } else {
-        repo_root.join(runner)
-    };
-    Ok(Some(path))
-}
-
 pub fn build_phase_prompt(
     mode: &str,
     task_id: &str,
@@ -269,18 +259,58 @@ pub fn build_phase_prompt(
             mode, task_id, e
         ))
     })?;
+    let project_note = task_json
+        .get("project")
+        .and_then(serde_json::Value::as_str)
+        .and_then(crate::monorepo::normalize_project_path)
+        .map(|project| {
+            format!(
+                "Sub-project: {} (work inside this directory; its MACC standards apply)\n",
+                project.display()
+            )
+        })
+        .unwrap_or_default();
     if mode == "review" {
         return Ok(format!(
-            "You are the assigned {} performer running inside a MACC worktree.\n\nMode: {}\nTask ID: {}\n\nTask registry entry (JSON):\n{}\n\nInstructions:\n1) Execute the review phase only.\n2) Review the already committed task changes and produce a verdict.\n3) Do not modify files, do not create commits, and do not modify task registry state.\n4) Return exactly one final verdict line at the end of your response:\n   - REVIEW_VERDICT: OK\n   - REVIEW_VERDICT: CHANGES_REQUESTED\n",
-            tool, mode, task_id, task_payload
+            "You are the assigned {} performer running inside a MACC worktree.\n\nMode: {}\nTask ID: {}\n{}\nTask registry entry (JSON):\n{}\n\nInstructions:\n1) Execute the review phase only.\n2) Review the already committed task changes and produce a verdict.\n3) Do not modify files, do not create commits, and do not modify task registry state.\n4) Return exactly one final verdict line at the end of your response:\n   - REVIEW_VERDICT: OK\n   - REVIEW_VERDICT: CHANGES_REQUESTED\n",
+            tool, mode, task_id, project_note, task_payload
+        ));
+    }
+    if mode == "decompose" {
+        let max_subtasks = task_json
+            .get("decomposition")
+            .and_then(|d| d.get("max_subtasks"))
+            .and_then(serde_json::Value::as_u64)
+            .unwrap_or(crate::config::DEFAULT_MAX_SUBTASKS as u64);
+        return Ok(format!(
+            "You are the assigned {} coordinator running inside a MACC worktree.\n\nMode: {}\nTask ID: {}\n{}\nTask registry entry (JSON):\n{}\n\nInstructions:\n1) The task is blocked (see `task_runtime.last_error` and `decomposition.trigger`). Split it into 2 to {} smaller subtasks that together deliver it, each small enough for one focused change.\n2) Do not modify files, do not create commits, and do not modify task registry state.\n3) Give each subtask a short local `id`; `dependencies` may name sibling ids or existing task ids. Unset `category`, `priority` and branch settings are inherited from this task.\n4) End your response with exactly one ```json fenced block holding an object that matches this JSON schema (no other keys):\n{}\n",
+            tool,
+            mode,
+            task_id,
+            project_note,
+            task_payload,
+            max_subtasks,
+            crate::coordinator::decompose::reply_schema()
         ));
     }
+    let review_note = if mode == "fix"
+        && task_json
+            .get("review_comments")
+            .and_then(serde_json::Value::as_array)
+            .map(|comments| !comments.is_empty())
+            .unwrap_or(false)
+    {
+        "5) Address every entry of `review_comments` (pull request review feedback).\n"
+    } else {
+        ""
+    };
     Ok(format!(
-        "You are the assigned {} performer running inside a MACC worktree.\n\nMode: {}\nTask ID: {}\n\nTask registry entry (JSON):\n{}\n\nInstructions:\n1) Execute the {} phase only.\n2) Keep changes minimal and focused on this task.\n3) Update code/tests/docs as needed for this phase.\n4) Do not modify task registry state directly.\n",
-        tool, mode, task_id, task_payload, mode
+        "You are the assigned {} performer running inside a MACC worktree.\n\nMode: {}\nTask ID: {}\n{}\nTask registry entry (JSON):\n{}\n\nInstructions:\n1) Execute the {} phase only.\n2) Keep changes minimal and focused on this task.\n3) Update code/tests/docs as needed for this phase.\n4) Do not modify task registry state directly.\n{}",
+        tool, mode, task_id, project_note, task_payload, mode, review_note
     ))
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn spawn_performer_job(
     executable_path: &Path,
     repo_root: &Path,
@@ -289,8 +319,15 @@ pub fn spawn_performer_job(
     event_tx: &tokio::sync::mpsc::UnboundedSender<CoordinatorJobEvent>,
     join_set: &mut tokio::task::JoinSet<()>,
     phase_timeout_seconds: usize,
+    limits: crate::config::ResourceLimits,
+    config_profile: Option<&str>,
+    env: &std::collections::BTreeMap<String, String>,
 ) -> Result<Option<i64>> {
     let mut run_cmd = tokio::process::Command::new(executable_path);
+    run_cmd.envs(env);
+    if let Some(profile) = config_profile {
+        run_cmd.env(crate::config::layers::PROFILE_ENV, profile);
+    }
     let event_source = format!(
         "coordinator-worktree:{}:{}",
         task_id,
@@ -321,9 +358,13 @@ pub fn spawn_performer_job(
         .env("MACC_EVENT_TASK_ID", task_id)
         .arg("--cwd")
         .arg(repo_root)
-        .arg("worktree")
+        .arg("performer")
         .arg("run")
+        .arg("--worktree")
         .arg(worktree_path.to_string_lossy().to_string());
+    let limit_guard = crate::coordinator::limits::PerformerLimitGuard::prepare(task_id, limits);
+    limit_guard.report_downgrade(repo_root, task_id, "dev");
+    limit_guard.apply(run_cmd.as_std_mut());
     let mut child = run_cmd.spawn().map_err(|e| MaccError::Io {
         path: worktree_path.to_string_lossy().into(),
         action: "spawn performer process".into(),
@@ -333,30 +374,33 @@ pub fn spawn_performer_job(
     let task_id_owned = task_id.to_string();
     let event_source_owned = event_source.clone();
     let tx = event_tx.clone();
+    let repo_root_owned = repo_root.to_path_buf();
     join_set.spawn(async move {
-        let (success, status_text, timed_out) = if phase_timeout_seconds > 0 {
+        let (exit_status, status_text, timed_out) = if phase_timeout_seconds > 0 {
             match tokio::time::timeout(
                 std::time::Duration::from_secs(phase_timeout_seconds as u64),
                 child.wait(),
             )
             .await
             {
-                Ok(Ok(status)) => (status.success(), status.to_string(), false),
-                Ok(Err(err)) => (false, err.to_string(), false),
+                Ok(Ok(status)) => (Some(status), status.to_string(), false),
+                Ok(Err(err)) => (None, err.to_string(), false),
                 Err(_) => {
                     let _ = child.kill().await;
-                    (false, "timeout".to_string(), true)
+                    (None, "timeout".to_string(), true)
                 }
             }
         } else {
             match child.wait().await {
-                Ok(status) => (status.success(), status.to_string(), false),
-                Err(err) => (false, err.to_string(), false),
+                Ok(status) => (Some(status), status.to_string(), false),
+                Err(err) => (None, err.to_string(), false),
             }
         };
+        let success = exit_status.is_some_and(|status| status.success());
         let mut error_code = None;
         let mut error_origin = None;
         let mut error_message = None;
+        let mut retry_after_seconds = None;
         if !success {
             if let Some(details) =
                 read_last_error_details(&events_file, &task_id_owned, &event_source_owned)
@@ -364,8 +408,28 @@ pub fn spawn_performer_job(
                 error_code = details.error_code;
                 error_origin = details.error_origin;
                 error_message = details.error_message;
+                retry_after_seconds = details.retry_after_seconds;
             }
         }
+        // The performer itself was killed by a limit before it could report a failure.
+        if let (None, Some(violation)) = (
+            &error_code,
+            exit_status.and_then(|status| limit_guard.violation(&status)),
+        ) {
+            let _ = crate::coordinator::helpers::append_coordinator_event_with_severity(
+                &repo_root_owned,
+                "resource_limit_exceeded",
+                &task_id_owned,
+                "dev",
+                "failed",
+                &violation.message(),
+                "warning",
+            );
+            error_code = Some(crate::performer::limits::RESOURCE_LIMIT_ERROR_CODE.to_string());
+            error_origin = Some("resource_limit".to_string());
+            error_message = Some(violation.message());
+        }
+        drop(limit_guard);
         let _ = tx.send(CoordinatorJobEvent {
             task_id: task_id_owned,
             success,
@@ -374,6 +438,7 @@ pub fn spawn_performer_job(
             error_code,
             error_origin,
             error_message,
+            retry_after_seconds,
         });
     });
     Ok(pid)
@@ -384,6 +449,7 @@ struct ErrorDetails {
     error_code: Option<String>,
     error_origin: Option<String>,
     error_message: Option<String>,
+    retry_after_seconds: Option<u64>,
 }
 
 fn read_last_error_details(
@@ -461,6 +527,9 @@ fn read_last_error_details(
                 error_code,
                 error_origin,
                 error_message,
+                retry_after_seconds: payload
+                    .get("retry_after_seconds")
+                    .and_then(serde_json::Value::as_u64),
             });
         }
     }
@@ -703,11 +772,240 @@ fn run_merge_hook_with_timeout(
     })
 }
 
+/// How one task branch lands on its base branch: `automation.coordinator.merge` with the PRD
+/// task `merge_strategy` override and the message template rendered for the task.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskMergeOptions {
+    pub strategy: String,
+    pub message: String,
+    pub signing: Option<String>,
+    pub signing_key: Option<String>,
+    /// `false` when the task's tool is sandboxed: the merge-fix hook would run it unsandboxed.
+    pub allow_ai_fix: bool,
+}
+
+impl TaskMergeOptions {
+    pub fn resolve(
+        merge: Option<&crate::config::MergeConfig>,
+        task: &serde_json::Value,
+    ) -> std::result::Result<Self, String> {
+        let field = |key: &str| {
+            task.get(key)
+                .and_then(serde_json::Value::as_str)
+                .unwrap_or_default()
+        };
+        let strategy = Some(field("merge_strategy"))
+            .filter(|s| !s.trim().is_empty())
+            .or_else(|| merge.and_then(|m| m.strategy.as_deref()))
+            .unwrap_or("no-ff")
+            .trim()
+            .to_string();
+        if !crate::config::MERGE_STRATEGIES.contains(&strategy.as_str()) {
+            return Err(format!(
+                "failure:local_merge step=strategy merge_strategy={} expected one of {}",
+                strategy,
+                crate::config::MERGE_STRATEGIES.join(", ")
+            ));
+        }
+        let message = merge
+            .and_then(|m| m.message.as_deref())
+            .unwrap_or("macc: merge task {task_id}")
+            .replace("{task_id}", field("id"))
+            .replace("{title}", field("title"))
+            .replace("{category}", field("category"));
+        Ok(Self {
+            strategy,
+            message,
+            signing: merge.and_then(|m| m.signing.clone()),
+            signing_key: merge.and_then(|m| m.signing_key.clone()),
+            allow_ai_fix: true,
+        })
+    }
+
+    fn suggestion(&self, branch: &str, base: &str) -> String {
+        match self.strategy.as_str() {
+            "squash" => format!(
+                "git checkout {} && git merge --squash {} && git commit",
+                base, branch
+            ),
+            "rebase" => format!(
+                "git rebase {} {} && git checkout {} && git merge --ff-only {}",
+                base, branch, base, branch
+            ),
+            "ff-only" => format!("git checkout {} && git merge --ff-only {}", base, branch),
+            _ => format!("git checkout {} && git merge {}", base, branch),
+        }
+    }
+}
+
+/// Runs a git command that creates commits, with `--gpg-sign` and the signature format when
+/// signing is configured.
+fn run_git_signed(
+    repo_root: &Path,
+    options: &TaskMergeOptions,
+    args: &[&str],
+    action: &str,
+) -> Result<std::process::Output> {
+    let Some(signing) = options.signing.as_deref() else {
+        return git::run_git_output_mapped(repo_root, args, action);
+    };
+    let format = if signing == "ssh" { "ssh" } else { "openpgp" };
+    let mut full = vec![
+        "-c".to_string(),
+        format!("gpg.format={}", format),
+        args[0].to_string(),
+    ];
+    full.push(match &options.signing_key {
+        Some(key) => format!("--gpg-sign={}", key),
+        None => "--gpg-sign".to_string(),
+    });
+    full.extend(args[1..].iter().map(|a| a.to_string()));
+    let full: Vec<&str> = full.iter().map(String::as_str).collect();
+    git::run_git_output_mapped(repo_root, &full, action)
+}
+
+fn run_merge_strategy(
+    repo_root: &Path,
+    branch: &str,
+    base: &str,
+    options: &TaskMergeOptions,
+) -> Result<std::process::Output> {
+    match options.strategy.as_str() {
+        "squash" => {
+            let squash = git::run_git_output_mapped(
+                repo_root,
+                &["merge", "--squash", branch],
+                "run squash merge",
+            )?;
+            if !squash.status.success() {
+                return Ok(squash);
+            }
+            // Nothing staged: the branch adds nothing on top of the base.
+            let staged = git::run_git_output_mapped(
+                repo_root,
+                &["diff", "--cached", "--quiet"],
+                "check squashed changes",
+            )?;
+            if staged.status.success() {
+                return Ok(squash);
+            }
+            run_git_signed(
+                repo_root,
+                options,
+                &["commit", "-m", &options.message],
+                "commit squash merge",
+            )
+        }
+        "rebase" => {
+            // Rebase a detached copy: the task branch may still be checked out in its worktree.
+            let detach = git::run_git_output_mapped(
+                repo_root,
+                &["checkout", "--detach", branch],
+                "detach task branch for rebase",
+            )?;
+            if !detach.status.success() {
+                let _ = git::checkout(repo_root, base, false);
+                return Ok(detach);
+            }
+            let rebase = run_git_signed(repo_root, options, &["rebase", base], "rebase task")?;
+            if !rebase.status.success() {
+                return Ok(rebase);
+            }
+            let head =
+                git::run_git_output_mapped(repo_root, &["rev-parse", "HEAD"], "read rebased head")?;
+            let head = String::from_utf8_lossy(&head.stdout).trim().to_string();
+            let _ = git::checkout(repo_root, base, false);
+            git::run_git_output_mapped(
+                repo_root,
+                &["merge", "--ff-only", &head],
+                "fast-forward to rebased task",
+            )
+        }
+        "ff-only" => git::run_git_output_mapped(
+            repo_root,
+            &["merge", "--ff-only", branch],
+            "run fast-forward merge",
+        ),
+        _ => run_git_signed(
+            repo_root,
+            options,
+            &["merge", "--no-ff", "-m", &options.message, branch],
+            "run local merge",
+        ),
+    }
+}
+
+/// Leaves the repository on `base` with no merge, squash or rebase in progress.
+fn abort_merge_strategy(repo_root: &Path, base: &str, options: &TaskMergeOptions) {
+    match options.strategy.as_str() {
+        "squash" => {
+            let _ = git::run_git_output_mapped(
+                repo_root,
+                &["reset", "--merge"],
+                "reset conflicted squash merge",
+            );
+        }
+        "rebase" => {
+            if git::rev_parse_verify(repo_root, "REBASE_HEAD").unwrap_or(false) {
+                let _ = git::run_git_output_mapped(
+                    repo_root,
+                    &["rebase", "--abort"],
+                    "abort conflicted rebase",
+                );
+            }
+            let _ = git::checkout(repo_root, base, false);
+        }
+        _ => {
+            if git::rev_parse_verify(repo_root, "MERGE_HEAD").unwrap_or(false) {
+                let _ = git::run_git_output_mapped(
+                    repo_root,
+                    &["merge", "--abort"],
+                    "abort conflicted merge",
+                );
+            }
+        }
+    }
+}
+
+fn rev_parse_commit(repo_root: &Path, rev: &str) -> Option<String> {
+    let output =
+        git::run_git_output_mapped(repo_root, &["rev-parse", rev], "resolve revision").ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Squash and rebase merges land new commits, so the task branch would never
+/// look merged into base and its pool slot could not be reused. Point the
+/// branch at the landed commit instead; `update-ref` works even while a slot
+/// worktree still has the branch checked out.
+fn move_branch_to_landed_commit(
+    repo_root: &Path,
+    branch: &str,
+    old_tip: Option<&str>,
+) -> std::result::Result<(), String> {
+    let landed = git::head_commit(repo_root).map_err(|e| e.to_string())?;
+    let reference = format!("refs/heads/{}", branch);
+    let mut args = vec!["update-ref", reference.as_str(), landed.as_str()];
+    if let Some(old_tip) = old_tip {
+        args.push(old_tip);
+    }
+    let output = git::run_git_output_mapped(repo_root, &args, "move task branch")
+        .map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
 pub fn merge_task_with_policy_native<FE>(
     repo_root: &Path,
     task_id: &str,
     branch: &str,
     base: &str,
+    options: &TaskMergeOptions,
     mut emit_event: FE,
 ) -> Result<std::result::Result<(), String>>
 where
@@ -719,7 +1017,7 @@ where
         action: "create coordinator log dir".into(),
         source: e,
     })?;
-    let suggestion = format!("git checkout {} && git merge {}", base, branch);
+    let suggestion = options.suggestion(branch, base);
 
     if !git::rev_parse_verify(repo_root, branch).unwrap_or(false) {
         return Ok(Err(format!(
@@ -741,14 +1039,26 @@ where
         )));
     }
 
+    let branch_tip = rev_parse_commit(repo_root, branch);
     let _ = git::checkout(repo_root, base, false);
-    let merge_msg = format!("macc: merge task {}", task_id);
-    let merge = git::run_git_output_mapped(
-        repo_root,
-        &["merge", "--no-ff", "-m", &merge_msg, branch],
-        "run local merge",
-    )?;
+    let merge = run_merge_strategy(repo_root, branch, base, options)?;
     if merge.status.success() {
+        if options.strategy == "squash" || options.strategy == "rebase" {
+            if let Err(err) = move_branch_to_landed_commit(repo_root, branch, branch_tip.as_deref())
+            {
+                emit_event(
+                    "merge_strategy",
+                    task_id,
+                    "integrate",
+                    "branch_not_moved",
+                    &format!(
+                        "task={} strategy={} merged but branch {} was not moved: {}",
+                        task_id, options.strategy, branch, err
+                    ),
+                    "warning",
+                );
+            }
+        }
         return Ok(Ok(()));
     }
 
@@ -767,8 +1077,23 @@ where
     .unwrap_or_default();
 
     let mut hook_output = String::new();
-    let allow_ai_fix = is_truthy_env("COORDINATOR_MERGE_AI_FIX", false);
-    if allow_ai_fix {
+    // The merge-fix hook resolves an in-progress `git merge`, which only no-ff leaves behind.
+    let allow_ai_fix =
+        is_truthy_env("COORDINATOR_MERGE_AI_FIX", false) && options.strategy == "no-ff";
+    if allow_ai_fix && !options.allow_ai_fix {
+        emit_event(
+            "merge_hook",
+            task_id,
+            "integrate",
+            "skipped",
+            &format!(
+                "merge-fix hook skipped task={}: the task tool runs sandboxed",
+                task_id
+            ),
+            "warning",
+        );
+    }
+    if allow_ai_fix && options.allow_ai_fix {
         if let Some(hook) = coordinator_merge_fix_hook(repo_root) {
             let hook_timeout_seconds = std::env::var("COORDINATOR_MERGE_HOOK_TIMEOUT_SECONDS")
                 .ok()
@@ -841,11 +1166,7 @@ where
         }
     }
 
-    let in_merge = git::rev_parse_verify(repo_root, "MERGE_HEAD").unwrap_or(false);
-    if in_merge {
-        let _ =
-            git::run_git_output_mapped(repo_root, &["merge", "--abort"], "abort conflicted merge");
-    }
+    abort_merge_strategy(repo_root, base, options);
 
     let report_file = log_dir.join(format!(
         "merge-fail-{}-{}.md",
@@ -853,10 +1174,11 @@ where
         chrono::Utc::now().format("%Y%m%dT%H%M%SZ")
     ));
     let report = format!(
-        "# Local merge failure report\n\n- Task: {}\n- Branch: {}\n- Base: {}\n- UTC: {}\n\n## Conflicts\n\n{}\n\n## Suggested manual command\n\n`cd \"{}\" && {}`\n\n## Merge stdout/stderr\n\n```text\n{}\n```\n\n## Merge-fix hook output\n\n```text\n{}\n```\n",
+        "# Local merge failure report\n\n- Task: {}\n- Branch: {}\n- Base: {}\n- Strategy: {}\n- UTC: {}\n\n## Conflicts\n\n{}\n\n## Suggested manual command\n\n`cd \"{}\" && {}`\n\n## Merge stdout/stderr\n\n```text\n{}\n```\n\n## Merge-fix hook output\n\n```text\n{}\n```\n",
         task_id,
         branch,
         base,
+        options.strategy,
         chrono::Utc::now().to_rfc3339(),
         if conflicts.is_empty() { "none" } else { &conflicts },
         repo_root.display(),
@@ -866,7 +1188,8 @@ where
     );
     let _ = std::fs::write(&report_file, report);
     let err = format!(
-        "failure:local_merge step=merge branch={} base={} conflicts=[{}] git_output=\"{}\" suggestion=\"{}\" report=\"{}\"",
+        "failure:local_merge step=merge strategy={} branch={} base={} conflicts=[{}] git_output=\"{}\" suggestion=\"{}\" report=\"{}\"",
+        options.strategy,
         branch,
         base,
         if conflicts.is_empty() { "none" } else { &conflicts },
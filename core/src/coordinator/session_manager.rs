@@ -4,7 +4,7 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 
-const TOOL_SESSIONS_REL_PATH: &str = ".macc/state/tool-sessions.json";
+pub(crate) const TOOL_SESSIONS_REL_PATH: &str = ".macc/state/tool-sessions.json";
 
 #[derive(Debug, Clone, Default)]
 pub struct SessionSealOutcome {
@@ -143,7 +143,7 @@ fn read_session_scope(worktree_path: &Path) -> Option<String> {
     )
 }
 
-fn persist_sessions_file(path: &Path, value: &Value) -> Result<()> {
+pub(crate) fn persist_sessions_file(path: &Path, value: &Value) -> Result<()> {
     let mut body = serde_json::to_string_pretty(value).map_err(|e| {
         MaccError::Validation(format!(
             "serialize sessions file '{}': {}",
@@ -178,7 +178,7 @@ fn worktree_key_candidates(worktree_path: &Path) -> Vec<String> {
     keys
 }
 
-fn acquire_lock(lock_dir: &PathBuf) -> Result<()> {
+pub(crate) fn acquire_lock(lock_dir: &PathBuf) -> Result<()> {
     for _ in 0..80 {
         match fs::create_dir(lock_dir) {
             Ok(()) => return Ok(()),
@@ -200,7 +200,7 @@ fn acquire_lock(lock_dir: &PathBuf) -> Result<()> {
     )))
 }
 
-fn release_lock(lock_dir: &PathBuf) {
+pub(crate) fn release_lock(lock_dir: &PathBuf) {
     let _ = fs::remove_dir(lock_dir);
 }
 
@@ -0,0 +1,1242 @@
+//! Deterministic dry run of the control plane (`macc coordinator simulate`).
+//!
+//! The simulation drives [`run_control_plane`] (and with it the run controller, the task
+//! selector and the engine transitions) over an in-memory registry built from the PRD.
+//! Performers, phase hooks and merges are replaced by draws from a seeded profile, and time
+//! is simulated: between cycles the clock jumps to the next job that is due. Nothing in the
+//! project is written.
+//!
+//! Staleness follows a real run: `stale_in_progress_seconds` kills a performer as the phase
+//! timeout and `stale_heartbeat_seconds` applies the stale heartbeat action to a performer
+//! that hangs (`hang_rate`). `stale_claimed_seconds` and `stale_changes_requested_seconds`
+//! have nothing to act on, since a simulated claim starts its performer at once and a change
+//! request goes straight to the fix phase.
+//!
+//! The simulated backend replaces the native one, so the policies implemented there are not
+//! simulated: tool throttling and its cooldowns, the budget pause (no usage is produced),
+//! approval before merge, the size guard, the forge gate, task decomposition, scope overlap
+//! predicted from changed files, resource limits and the sandbox. Merges land after their
+//! drawn duration and a failed merge leaves the task blocked instead of pausing for the
+//! operator.
+
+use crate::config::{CanonicalConfig, CoordinatorConfig};
+use crate::coordinator::control_plane::{
+    apply_stale_heartbeat_action, merge_prd_definitions, resolve_error_code_retry_list,
+    resolve_error_code_retry_max, resolve_stale_heartbeat_action, resolve_stale_heartbeat_seconds,
+    task_selector_config, StaleHeartbeatAction,
+};
+use crate::coordinator::engine::{
+    self as coordinator_engine, AdvanceResult, AdvanceTaskAction, ControlPlaneBackend,
+    ControlPlaneLoopConfig, CoordinatorCounts, DispatchClaimUpdate, JobCompletionInput,
+    ReviewVerdict,
+};
+use crate::coordinator::helpers::recompute_resource_locks_from_tasks;
+use crate::coordinator::task_selector::{select_next_ready_task, TaskSelectorConfig};
+use crate::coordinator::types::CoordinatorEnvConfig;
+use crate::{MaccError, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::Path;
+
+/// A run without active work whose counts stay put cannot move again: stop it quickly.
+const MAX_NO_PROGRESS_CYCLES: usize = 3;
+
+/// Simulated duration of a job, in seconds. Draws below one second count as one second.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "distribution", rename_all = "snake_case")]
+pub enum DurationDistribution {
+    Fixed { seconds: f64 },
+    Uniform { min: f64, max: f64 },
+    Normal { mean: f64, stddev: f64 },
+    Exponential { mean: f64 },
+}
+
+impl DurationDistribution {
+    fn fixed(seconds: f64) -> Self {
+        Self::Fixed { seconds }
+    }
+
+    fn sample(&self, rng: &mut SimRng) -> f64 {
+        let seconds = match *self {
+            Self::Fixed { seconds } => seconds,
+            Self::Uniform { min, max } => min + (max - min) * rng.next_f64(),
+            Self::Normal { mean, stddev } => mean + stddev * rng.standard_normal(),
+            Self::Exponential { mean } => -mean * (1.0 - rng.next_f64()).ln(),
+        };
+        seconds.max(1.0)
+    }
+
+    fn validate(&self, what: &str) -> Result<()> {
+        let valid = match *self {
+            Self::Fixed { seconds } => seconds >= 0.0,
+            Self::Uniform { min, max } => min >= 0.0 && min <= max,
+            Self::Normal { mean, stddev } => mean >= 0.0 && stddev >= 0.0,
+            Self::Exponential { mean } => mean > 0.0,
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(MaccError::Validation(format!(
+                "Invalid simulation profile: {} duration {:?}",
+                what, self
+            )))
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PhaseDurations {
+    pub dev: DurationDistribution,
+    pub review: DurationDistribution,
+    pub integrate: DurationDistribution,
+    pub fix: DurationDistribution,
+}
+
+impl Default for PhaseDurations {
+    fn default() -> Self {
+        Self {
+            dev: DurationDistribution::fixed(600.0),
+            review: DurationDistribution::fixed(120.0),
+            integrate: DurationDistribution::fixed(60.0),
+            fix: DurationDistribution::fixed(300.0),
+        }
+    }
+}
+
+impl PhaseDurations {
+    fn for_mode(&self, mode: &str) -> &DurationDistribution {
+        match mode {
+            "review" => &self.review,
+            "integrate" => &self.integrate,
+            "fix" => &self.fix,
+            _ => &self.dev,
+        }
+    }
+}
+
+/// How a simulated tool behaves. Tools without an entry use `defaults`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SimulatedTool {
+    pub durations: PhaseDurations,
+    /// Probability that a performer attempt fails.
+    pub failure_rate: f64,
+    /// Probability that a performer attempt hangs: it stops heartbeating and only ends at the
+    /// phase timeout or through the stale heartbeat policy.
+    pub hang_rate: f64,
+    /// Relative weights of the error codes a failed attempt reports (`E101` when empty).
+    pub error_codes: BTreeMap<String, f64>,
+    /// Probability that a review, integrate or fix phase fails.
+    pub phase_failure_rate: f64,
+    /// Probability that a successful review asks for changes.
+    pub changes_requested_rate: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SimulatedMerge {
+    pub duration: DurationDistribution,
+    pub failure_rate: f64,
+}
+
+impl Default for SimulatedMerge {
+    fn default() -> Self {
+        Self {
+            duration: DurationDistribution::fixed(30.0),
+            failure_rate: 0.0,
+        }
+    }
+}
+
+/// Coordinator settings under test. They replace `automation.coordinator` values; CLI flags
+/// still win over them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SimulatedCoordinator {
+    pub max_parallel: Option<usize>,
+    pub max_parallel_per_tool: BTreeMap<String, usize>,
+    pub tool_priority: Vec<String>,
+    pub max_dispatch: Option<usize>,
+    pub phase_runner_max_attempts: Option<usize>,
+    /// Performer timeout, as `stale_in_progress_seconds` in a real run.
+    pub stale_in_progress_seconds: Option<usize>,
+    /// Heartbeat silence after which a hung performer is stale (`STALE_HEARTBEAT_SECONDS`).
+    pub stale_heartbeat_seconds: Option<usize>,
+    /// What happens to a stale task (`STALE_HEARTBEAT_ACTION`): `retry`, `block` or `requeue`.
+    pub stale_heartbeat_action: Option<StaleHeartbeatAction>,
+    pub error_code_retry_list: Vec<String>,
+    pub error_code_retry_max: Option<usize>,
+}
+
+/// `--profile` file of `macc coordinator simulate` (YAML).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SimulationProfile {
+    pub seed: u64,
+    /// Simulated seconds between cycles when no job is due.
+    pub cycle_seconds: f64,
+    /// Safety stop for profiles that never converge.
+    pub max_cycles: usize,
+    pub coordinator: SimulatedCoordinator,
+    pub defaults: SimulatedTool,
+    pub tools: BTreeMap<String, SimulatedTool>,
+    pub merge: SimulatedMerge,
+}
+
+impl Default for SimulationProfile {
+    fn default() -> Self {
+        Self {
+            seed: 1,
+            cycle_seconds: 5.0,
+            max_cycles: 100_000,
+            coordinator: SimulatedCoordinator::default(),
+            defaults: SimulatedTool::default(),
+            tools: BTreeMap::new(),
+            merge: SimulatedMerge::default(),
+        }
+    }
+}
+
+impl SimulationProfile {
+    pub fn load(path: &Path) -> Result<Self> {
+        let raw = std::fs::read_to_string(path).map_err(|e| MaccError::Io {
+            path: path.to_string_lossy().into(),
+            action: "read simulation profile".into(),
+            source: e,
+        })?;
+        let profile: Self = serde_yaml::from_str(&raw).map_err(|e| {
+            MaccError::Validation(format!(
+                "Failed to parse simulation profile {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        profile.validate()?;
+        Ok(profile)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.cycle_seconds <= 0.0 {
+            return Err(MaccError::Validation(
+                "Invalid simulation profile: cycle_seconds must be positive".into(),
+            ));
+        }
+        let tools = std::iter::once(("defaults", &self.defaults))
+            .chain(self.tools.iter().map(|(id, tool)| (id.as_str(), tool)));
+        for (id, tool) in tools {
+            for (mode, duration) in [
+                ("dev", &tool.durations.dev),
+                ("review", &tool.durations.review),
+                ("integrate", &tool.durations.integrate),
+                ("fix", &tool.durations.fix),
+            ] {
+                duration.validate(&format!("{} {}", id, mode))?;
+            }
+            for (name, rate) in [
+                ("failure_rate", tool.failure_rate),
+                ("hang_rate", tool.hang_rate),
+                ("phase_failure_rate", tool.phase_failure_rate),
+                ("changes_requested_rate", tool.changes_requested_rate),
+            ] {
+                validate_rate(&format!("{}.{}", id, name), rate)?;
+            }
+            if tool.error_codes.values().any(|weight| *weight < 0.0) {
+                return Err(MaccError::Validation(format!(
+                    "Invalid simulation profile: {}.error_codes weights must not be negative",
+                    id
+                )));
+            }
+        }
+        self.merge.duration.validate("merge")?;
+        validate_rate("merge.failure_rate", self.merge.failure_rate)
+    }
+
+    fn tool(&self, tool: &str) -> &SimulatedTool {
+        self.tools.get(tool).unwrap_or(&self.defaults)
+    }
+
+    /// Layers the profile's coordinator settings over the project ones.
+    fn coordinator_config(&self, project: Option<&CoordinatorConfig>) -> CoordinatorConfig {
+        let mut cfg = project.cloned().unwrap_or_default();
+        let sim = &self.coordinator;
+        cfg.max_parallel = sim.max_parallel.or(cfg.max_parallel);
+        if !sim.max_parallel_per_tool.is_empty() {
+            cfg.max_parallel_per_tool = sim.max_parallel_per_tool.clone();
+        }
+        if !sim.tool_priority.is_empty() {
+            cfg.tool_priority = sim.tool_priority.clone();
+        }
+        cfg.max_dispatch = sim.max_dispatch.or(cfg.max_dispatch);
+        cfg.phase_runner_max_attempts = sim
+            .phase_runner_max_attempts
+            .or(cfg.phase_runner_max_attempts);
+        cfg.stale_in_progress_seconds = sim
+            .stale_in_progress_seconds
+            .or(cfg.stale_in_progress_seconds);
+        cfg
+    }
+}
+
+fn validate_rate(what: &str, rate: f64) -> Result<()> {
+    if (0.0..=1.0).contains(&rate) {
+        Ok(())
+    } else {
+        Err(MaccError::Validation(format!(
+            "Invalid simulation profile: {} must be between 0 and 1 (got {})",
+            what, rate
+        )))
+    }
+}
+
+/// SplitMix64: seedable and stable across platforms and releases.
+struct SimRng(u64);
+
+impl SimRng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `[0, 1)`.
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn chance(&mut self, probability: f64) -> bool {
+        probability > 0.0 && self.next_f64() < probability
+    }
+
+    fn standard_normal(&mut self) -> f64 {
+        let u1 = 1.0 - self.next_f64();
+        let u2 = self.next_f64();
+        (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+    }
+
+    fn weighted<'a>(&mut self, weights: &'a BTreeMap<String, f64>) -> Option<&'a str> {
+        let total: f64 = weights.values().sum();
+        if total <= 0.0 {
+            return None;
+        }
+        let mut target = self.next_f64() * total;
+        for (code, weight) in weights {
+            if target < *weight {
+                return Some(code);
+            }
+            target -= weight;
+        }
+        weights.keys().next_back().map(String::as_str)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolUsage {
+    pub tool: String,
+    /// Concurrent jobs the selector allows for the tool.
+    pub capacity: usize,
+    pub attempts: usize,
+    pub failed_attempts: usize,
+    /// Simulated seconds spent in performer attempts and phase hooks.
+    pub busy_seconds: f64,
+    /// `busy_seconds` over `makespan × capacity`.
+    pub utilization: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskOutcome {
+    pub id: String,
+    pub tool: Option<String>,
+    pub state: String,
+    pub attempts: usize,
+    pub started_at_seconds: Option<f64>,
+    pub finished_at_seconds: Option<f64>,
+    pub last_error: Option<String>,
+}
+
+/// A blocked task and the unfinished tasks that depend on it, directly or not.
+#[derive(Debug, Clone, Serialize)]
+pub struct FailurePropagation {
+    pub task_id: String,
+    pub phase: String,
+    pub error: String,
+    pub stranded: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SimulationReport {
+    pub seed: u64,
+    /// Why the run controller stopped the run; `None` when it completed.
+    pub stopped: Option<String>,
+    pub cycles: usize,
+    pub makespan_seconds: f64,
+    pub max_parallel: usize,
+    /// Performer busy time over `makespan × max_parallel`.
+    pub utilization: f64,
+    pub total: usize,
+    pub merged: usize,
+    pub blocked: usize,
+    pub unfinished: usize,
+    pub retries: usize,
+    pub error_codes: BTreeMap<String, usize>,
+    pub tools: Vec<ToolUsage>,
+    pub failure_propagation: Vec<FailurePropagation>,
+    pub tasks: Vec<TaskOutcome>,
+}
+
+struct SimJob {
+    tool: String,
+    attempt: usize,
+    started_at: f64,
+    ends_at: f64,
+    success: bool,
+    timed_out: bool,
+    /// The performer stopped heartbeating at `started_at`.
+    hung: bool,
+    error_code: Option<String>,
+}
+
+struct SimMerge {
+    ends_at: f64,
+    success: bool,
+}
+
+#[derive(Default)]
+struct ToolStats {
+    attempts: usize,
+    failed_attempts: usize,
+    busy_seconds: f64,
+}
+
+#[derive(Default)]
+struct TaskStats {
+    attempts: usize,
+    started_at: Option<f64>,
+    finished_at: Option<f64>,
+    failed_phase: Option<String>,
+}
+
+struct SimulatedBackend<'a> {
+    profile: &'a SimulationProfile,
+    rng: SimRng,
+    registry: Value,
+    clock: f64,
+    cycles: usize,
+    selector: TaskSelectorConfig,
+    max_dispatch: usize,
+    max_attempts: usize,
+    phase_timeout_seconds: usize,
+    stale_heartbeat_seconds: usize,
+    stale_heartbeat_action: StaleHeartbeatAction,
+    retry_codes: Vec<String>,
+    retry_max: usize,
+    coordinator_tool: Option<String>,
+    dispatched_total: usize,
+    jobs: BTreeMap<String, SimJob>,
+    merges: BTreeMap<String, SimMerge>,
+    tool_stats: BTreeMap<String, ToolStats>,
+    task_stats: BTreeMap<String, TaskStats>,
+    error_codes: BTreeMap<String, usize>,
+    retries: usize,
+}
+
+impl SimulatedBackend<'_> {
+    fn now(&self) -> String {
+        chrono::DateTime::from_timestamp_millis((self.clock * 1000.0) as i64)
+            .unwrap_or_default()
+            .to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+    }
+
+    fn start_job(&mut self, task_id: &str, tool: &str, attempt: usize) {
+        let spec = self.profile.tool(tool);
+        let mut duration = spec.durations.dev.sample(&mut self.rng);
+        let failed = self.rng.chance(spec.failure_rate);
+        let mut error_code = failed.then(|| {
+            self.rng
+                .weighted(&spec.error_codes)
+                .unwrap_or("E101")
+                .to_string()
+        });
+        let hung = self.rng.chance(spec.hang_rate);
+        if hung {
+            duration = f64::INFINITY;
+        }
+        // The performer is killed at the phase timeout and reports no error code.
+        let timeout = self.phase_timeout_seconds as f64;
+        let timed_out = timeout > 0.0 && duration > timeout;
+        if timed_out {
+            duration = timeout;
+            error_code = None;
+        }
+        self.jobs.insert(
+            task_id.to_string(),
+            SimJob {
+                tool: tool.to_string(),
+                attempt,
+                started_at: self.clock,
+                ends_at: self.clock + duration,
+                success: !failed && !timed_out && !hung,
+                timed_out,
+                hung,
+                error_code,
+            },
+        );
+        let task = self.task_stats.entry(task_id.to_string()).or_default();
+        task.attempts += 1;
+        task.started_at.get_or_insert(self.clock);
+    }
+
+    /// When the stale heartbeat policy catches a hung job, if it does before the job ends.
+    fn stale_at(&self, job: &SimJob) -> Option<f64> {
+        if !job.hung || self.stale_heartbeat_seconds == 0 {
+            return None;
+        }
+        // A real run only acts once the heartbeat age exceeds the threshold in whole seconds.
+        let at = job.started_at + self.stale_heartbeat_seconds as f64 + 1.0;
+        (at < job.ends_at).then_some(at)
+    }
+
+    fn apply_stale_heartbeats(&mut self) {
+        let stale: Vec<String> = self
+            .jobs
+            .iter()
+            .filter(|(_, job)| self.stale_at(job).is_some_and(|at| at <= self.clock))
+            .map(|(id, _)| id.clone())
+            .collect();
+        for task_id in stale {
+            let Some(job) = self.jobs.remove(&task_id) else {
+                continue;
+            };
+            let action = self.stale_heartbeat_action;
+            let detail = format!(
+                "stale heartbeat: age={}s threshold={}s action={:?}",
+                (self.clock - job.started_at) as u64,
+                self.stale_heartbeat_seconds,
+                action
+            );
+            let now = self.now();
+            if let Some(task) = find_task_mut(&mut self.registry, &task_id) {
+                apply_stale_heartbeat_action(task, action, &detail, &now);
+            }
+            let stats = self.tool_stats.entry(job.tool.clone()).or_default();
+            stats.attempts += 1;
+            stats.failed_attempts += 1;
+            stats.busy_seconds += self.clock - job.started_at;
+            *self
+                .error_codes
+                .entry("stale_heartbeat".into())
+                .or_default() += 1;
+            match action {
+                StaleHeartbeatAction::Retry => self.retries += 1,
+                StaleHeartbeatAction::Block => {
+                    self.task_stats.entry(task_id).or_default().failed_phase = Some("dev".into());
+                }
+                StaleHeartbeatAction::Requeue => {}
+            }
+        }
+    }
+
+    fn task_tool(&self, task_id: &str) -> String {
+        self.coordinator_tool.clone().unwrap_or_else(|| {
+            find_task(&self.registry, task_id)
+                .and_then(|task| task.get("tool"))
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .unwrap_or_else(|| self.selector.default_tool.clone())
+        })
+    }
+
+    fn busy(&mut self, tool: &str, seconds: f64) {
+        self.tool_stats
+            .entry(tool.to_string())
+            .or_default()
+            .busy_seconds += seconds;
+    }
+
+    fn report(&self, stopped: Option<String>) -> SimulationReport {
+        let counts = coordinator_engine::coordinator_counts(&self.registry);
+        let makespan = self.clock;
+        let max_parallel = self.selector.max_parallel;
+        let capacity_of = |tool: &str| {
+            let cap = self
+                .selector
+                .max_parallel_per_tool
+                .get(tool)
+                .copied()
+                .unwrap_or(max_parallel);
+            if max_parallel > 0 && (cap == 0 || cap > max_parallel) {
+                max_parallel
+            } else {
+                cap
+            }
+        };
+        let ratio = |busy: f64, capacity: usize| {
+            if makespan > 0.0 && capacity > 0 {
+                busy / (makespan * capacity as f64)
+            } else {
+                0.0
+            }
+        };
+        let tools: Vec<ToolUsage> = self
+            .tool_stats
+            .iter()
+            .map(|(tool, stats)| {
+                let capacity = capacity_of(tool);
+                ToolUsage {
+                    tool: tool.clone(),
+                    capacity,
+                    attempts: stats.attempts,
+                    failed_attempts: stats.failed_attempts,
+                    busy_seconds: stats.busy_seconds,
+                    utilization: ratio(stats.busy_seconds, capacity),
+                }
+            })
+            .collect();
+        let busy_total = tools.iter().map(|tool| tool.busy_seconds).sum();
+
+        let tasks: Vec<&Value> = self
+            .registry
+            .get("tasks")
+            .and_then(Value::as_array)
+            .map(|tasks| tasks.iter().collect())
+            .unwrap_or_default();
+        let outcomes: Vec<TaskOutcome> = tasks
+            .iter()
+            .map(|task| {
+                let id = str_field(task, "id").unwrap_or_default();
+                let stats = self.task_stats.get(&id);
+                TaskOutcome {
+                    tool: str_field(task, "tool"),
+                    state: str_field(task, "state").unwrap_or_else(|| "todo".into()),
+                    attempts: stats.map(|s| s.attempts).unwrap_or(0),
+                    started_at_seconds: stats.and_then(|s| s.started_at),
+                    finished_at_seconds: stats.and_then(|s| s.finished_at),
+                    last_error: task
+                        .get("task_runtime")
+                        .and_then(|runtime| str_field(runtime, "last_error")),
+                    id,
+                }
+            })
+            .collect();
+        let failure_propagation = outcomes
+            .iter()
+            .filter(|task| task.state == "blocked")
+            .map(|task| FailurePropagation {
+                task_id: task.id.clone(),
+                phase: self
+                    .task_stats
+                    .get(&task.id)
+                    .and_then(|s| s.failed_phase.clone())
+                    .unwrap_or_else(|| "dev".into()),
+                error: task.last_error.clone().unwrap_or_default(),
+                stranded: stranded_by(&tasks, &task.id),
+            })
+            .collect();
+
+        SimulationReport {
+            seed: self.profile.seed,
+            stopped,
+            cycles: self.cycles,
+            makespan_seconds: makespan,
+            max_parallel,
+            utilization: ratio(busy_total, max_parallel),
+            total: counts.total,
+            merged: counts.merged,
+            blocked: counts.blocked,
+            unfinished: counts.total - counts.merged - counts.blocked,
+            retries: self.retries,
+            error_codes: self.error_codes.clone(),
+            tools,
+            failure_propagation,
+            tasks: outcomes,
+        }
+    }
+}
+
+#[async_trait]
+impl ControlPlaneBackend for SimulatedBackend<'_> {
+    async fn on_cycle_start(&mut self, cycle: usize) -> Result<()> {
+        self.cycles = cycle;
+        if cycle > self.profile.max_cycles {
+            return Err(MaccError::Validation(format!(
+                "Simulation stopped after max_cycles={}",
+                self.profile.max_cycles
+            )));
+        }
+        Ok(())
+    }
+
+    async fn monitor_active_jobs(&mut self) -> Result<()> {
+        self.apply_stale_heartbeats();
+        let mut due: Vec<(f64, String)> = self
+            .jobs
+            .iter()
+            .filter(|(_, job)| job.ends_at <= self.clock)
+            .map(|(id, job)| (job.ends_at, id.clone()))
+            .collect();
+        due.sort_by(|a, b| a.0.total_cmp(&b.0).then_with(|| a.1.cmp(&b.1)));
+        for (_, task_id) in due {
+            let Some(job) = self.jobs.remove(&task_id) else {
+                continue;
+            };
+            let success = job.success;
+            let input = JobCompletionInput {
+                success,
+                attempt: job.attempt,
+                max_attempts: self.max_attempts,
+                timed_out: job.timed_out,
+                phase_timeout_seconds: self.phase_timeout_seconds,
+                elapsed_seconds: (job.ends_at - job.started_at) as u64,
+                status_text: if job.timed_out {
+                    "timeout".into()
+                } else if success {
+                    "exit status: 0".into()
+                } else {
+                    "exit status: 1".into()
+                },
+                error_code: job.error_code.clone(),
+                error_origin: job.error_code.as_ref().map(|_| "performer".to_string()),
+                error_message: None,
+                auto_retry_error_codes: self.retry_codes.clone(),
+                auto_retry_max: self.retry_max,
+            };
+            let now = self.now();
+            let completion = coordinator_engine::apply_job_completion_in_registry(
+                &mut self.registry,
+                &task_id,
+                &input,
+                &now,
+            )?;
+            let stats = self.tool_stats.entry(job.tool.clone()).or_default();
+            stats.attempts += 1;
+            stats.busy_seconds += job.ends_at - job.started_at;
+            if !success {
+                stats.failed_attempts += 1;
+                let code = input.error_code.clone().unwrap_or_else(|| "E101".into());
+                *self.error_codes.entry(code).or_default() += 1;
+            }
+            if completion.status_label == "auto_retry" {
+                self.retries += 1;
+            } else if completion.should_retry {
+                self.retries += 1;
+                self.start_job(&task_id, &job.tool, job.attempt + 1);
+            }
+        }
+        recompute_resource_locks_from_tasks(&mut self.registry);
+        Ok(())
+    }
+
+    async fn monitor_merge_jobs(&mut self) -> Result<Option<(String, String)>> {
+        let due: Vec<String> = self
+            .merges
+            .iter()
+            .filter(|(_, merge)| merge.ends_at <= self.clock)
+            .map(|(id, _)| id.clone())
+            .collect();
+        let mut blocked_merge = None;
+        for task_id in due {
+            let Some(merge) = self.merges.remove(&task_id) else {
+                continue;
+            };
+            let reason = if merge.success {
+                String::new()
+            } else {
+                "simulated merge failure".to_string()
+            };
+            let now = self.now();
+            coordinator_engine::apply_merge_result_in_registry(
+                &mut self.registry,
+                &task_id,
+                merge.success,
+                &reason,
+                &now,
+            )?;
+            let stats = self.task_stats.entry(task_id.clone()).or_default();
+            if merge.success {
+                stats.finished_at = Some(self.clock);
+            } else {
+                stats.failed_phase = Some("merge".into());
+                blocked_merge = Some((task_id, reason));
+            }
+        }
+        recompute_resource_locks_from_tasks(&mut self.registry);
+        Ok(blocked_merge)
+    }
+
+    async fn on_blocked_merge(&mut self, _task_id: &str, _reason: &str) -> Result<()> {
+        // A real run pauses for the operator here; the simulation leaves the task blocked.
+        Ok(())
+    }
+
+    async fn advance_tasks(&mut self) -> Result<AdvanceResult> {
+        let active_merges: HashSet<String> = self.merges.keys().cloned().collect();
+        let actions = coordinator_engine::build_advance_actions(&self.registry, &active_merges)?;
+        let mut progressed = false;
+        for action in actions {
+            match action {
+                AdvanceTaskAction::RunPhase {
+                    task_id,
+                    mode,
+                    transition,
+                } => {
+                    // Phase hooks run inline in the control plane, so the whole loop waits.
+                    let tool = self.task_tool(&task_id);
+                    let spec = self.profile.tool(&tool);
+                    let duration = spec.durations.for_mode(mode).sample(&mut self.rng);
+                    let failed = self.rng.chance(spec.phase_failure_rate);
+                    let verdict = (mode == "review" && !failed).then(|| {
+                        if self.rng.chance(spec.changes_requested_rate) {
+                            ReviewVerdict::ChangesRequested
+                        } else {
+                            ReviewVerdict::Ok
+                        }
+                    });
+                    self.clock += duration;
+                    self.busy(&tool, duration);
+                    let error = failed.then(|| format!("simulated {} phase failure", mode));
+                    let now = self.now();
+                    coordinator_engine::apply_phase_outcome_in_registry(
+                        &mut self.registry,
+                        &task_id,
+                        mode,
+                        transition,
+                        verdict,
+                        error.as_deref(),
+                        &now,
+                    )?;
+                    if failed {
+                        self.task_stats.entry(task_id).or_default().failed_phase =
+                            Some(mode.to_string());
+                    }
+                    progressed = true;
+                }
+                AdvanceTaskAction::QueueMerge { task_id, .. } => {
+                    let duration = self.profile.merge.duration.sample(&mut self.rng);
+                    let success = !self.rng.chance(self.profile.merge.failure_rate);
+                    self.merges.insert(
+                        task_id,
+                        SimMerge {
+                            ends_at: self.clock + duration,
+                            success,
+                        },
+                    );
+                    progressed = true;
+                }
+            }
+        }
+        recompute_resource_locks_from_tasks(&mut self.registry);
+        Ok(AdvanceResult {
+            progressed,
+            blocked_merge: None,
+        })
+    }
+
+    async fn dispatch_ready_tasks(&mut self) -> Result<usize> {
+        let remaining = if self.max_dispatch == 0 {
+            usize::MAX
+        } else {
+            self.max_dispatch.saturating_sub(self.dispatched_total)
+        };
+        let mut dispatched = 0;
+        while dispatched < remaining {
+            if self.selector.max_parallel > 0 && self.jobs.len() >= self.selector.max_parallel {
+                break;
+            }
+            let Some(selected) = select_next_ready_task(&self.registry, &self.selector) else {
+                break;
+            };
+            let update = DispatchClaimUpdate {
+                task_id: selected.id.clone(),
+                tool: selected.tool.clone(),
+                worktree_path: format!("simulated/{}", selected.id),
+                branch: format!("simulated/{}", selected.id),
+                base_branch: selected.base_branch.clone(),
+                last_commit: String::new(),
+                session_id: String::new(),
+                pid: None,
+                phase: "dev".into(),
+                now: self.now(),
+            };
+            coordinator_engine::apply_dispatch_claim_in_registry(&mut self.registry, &update)?;
+            recompute_resource_locks_from_tasks(&mut self.registry);
+            self.start_job(&selected.id, &selected.tool, 1);
+            dispatched += 1;
+            self.dispatched_total += 1;
+        }
+        Ok(dispatched)
+    }
+
+    async fn on_cycle_end(
+        &mut self,
+        _cycle: usize,
+        _advance: &AdvanceResult,
+        _dispatched: usize,
+    ) -> Result<CoordinatorCounts> {
+        Ok(coordinator_engine::coordinator_counts(&self.registry))
+    }
+
+    async fn sleep_between_cycles(&mut self) -> Result<()> {
+        let next_due = self
+            .jobs
+            .values()
+            .map(|job| self.stale_at(job).unwrap_or(job.ends_at))
+            .chain(self.merges.values().map(|merge| merge.ends_at))
+            .min_by(f64::total_cmp);
+        self.clock = match next_due {
+            Some(at) if at > self.clock => at,
+            Some(_) => self.clock,
+            None => self.clock + self.profile.cycle_seconds,
+        };
+        Ok(())
+    }
+
+    fn should_terminate_run(&self, counts: &CoordinatorCounts) -> bool {
+        self.max_dispatch > 0
+            && self.dispatched_total >= self.max_dispatch
+            && counts.active == 0
+            && self.jobs.is_empty()
+            && self.merges.is_empty()
+    }
+}
+
+fn find_task<'a>(registry: &'a Value, task_id: &str) -> Option<&'a Value> {
+    registry
+        .get("tasks")?
+        .as_array()?
+        .iter()
+        .find(|task| task.get("id").and_then(Value::as_str) == Some(task_id))
+}
+
+fn find_task_mut<'a>(registry: &'a mut Value, task_id: &str) -> Option<&'a mut Value> {
+    registry
+        .get_mut("tasks")?
+        .as_array_mut()?
+        .iter_mut()
+        .find(|task| task.get("id").and_then(Value::as_str) == Some(task_id))
+}
+
+fn str_field(value: &Value, key: &str) -> Option<String> {
+    value
+        .get(key)
+        .and_then(Value::as_str)
+        .filter(|raw| !raw.is_empty())
+        .map(str::to_string)
+}
+
+/// Unfinished tasks that wait on `blocked`, directly or through other tasks.
+fn stranded_by(tasks: &[&Value], blocked: &str) -> Vec<String> {
+    let mut stranded = BTreeSet::new();
+    let mut frontier = vec![blocked.to_string()];
+    while let Some(current) = frontier.pop() {
+        for task in tasks {
+            let Some(id) = str_field(task, "id") else {
+                continue;
+            };
+            let depends = task
+                .get("dependencies")
+                .and_then(Value::as_array)
+                .is_some_and(|deps| deps.iter().any(|dep| dep.as_str() == Some(&current)));
+            let state = str_field(task, "state").unwrap_or_else(|| "todo".into());
+            if depends && state != "merged" && stranded.insert(id.clone()) {
+                frontier.push(id);
+            }
+        }
+    }
+    stranded.into_iter().collect()
+}
+
+/// Runs the control plane over `prd_tasks` with simulated performers.
+pub async fn simulate(
+    prd_tasks: &[Value],
+    profile: &SimulationProfile,
+    canonical: &CanonicalConfig,
+    coordinator: Option<&CoordinatorConfig>,
+    env_cfg: &CoordinatorEnvConfig,
+) -> Result<SimulationReport> {
+    profile.validate()?;
+    let coordinator = profile.coordinator_config(coordinator);
+    let mut env_cfg = env_cfg.clone();
+    if env_cfg.error_code_retry_list.is_none()
+        && !profile.coordinator.error_code_retry_list.is_empty()
+    {
+        env_cfg.error_code_retry_list = Some(profile.coordinator.error_code_retry_list.join(","));
+    }
+    env_cfg.error_code_retry_max = env_cfg
+        .error_code_retry_max
+        .or(profile.coordinator.error_code_retry_max);
+
+    let max_parallel = env_cfg
+        .max_parallel
+        .or(coordinator.max_parallel)
+        .unwrap_or(3);
+    let phase_timeout_seconds = env_cfg
+        .stale_in_progress_seconds
+        .or(coordinator.stale_in_progress_seconds)
+        .unwrap_or(0);
+    let stale_heartbeat_seconds = profile
+        .coordinator
+        .stale_heartbeat_seconds
+        .unwrap_or_else(|| resolve_stale_heartbeat_seconds(&env_cfg));
+    let hangs = std::iter::once(&profile.defaults)
+        .chain(profile.tools.values())
+        .any(|tool| tool.hang_rate > 0.0);
+    if hangs && phase_timeout_seconds == 0 && stale_heartbeat_seconds == 0 {
+        return Err(MaccError::Validation(
+            "Invalid simulation profile: hang_rate needs stale_in_progress_seconds or \
+             stale_heartbeat_seconds, or hung performers never end"
+                .into(),
+        ));
+    }
+    let mut registry = json!({"tasks": []});
+    let epoch = chrono::DateTime::from_timestamp(0, 0)
+        .unwrap_or_default()
+        .to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+    merge_prd_definitions(&mut registry, prd_tasks, &epoch);
+    recompute_resource_locks_from_tasks(&mut registry);
+
+    let mut backend = SimulatedBackend {
+        profile,
+        rng: SimRng(profile.seed),
+        registry,
+        clock: 0.0,
+        cycles: 0,
+        selector: task_selector_config(canonical, Some(&coordinator), &env_cfg, max_parallel),
+        max_dispatch: env_cfg
+            .max_dispatch
+            .or(coordinator.max_dispatch)
+            .unwrap_or(10),
+        max_attempts: env_cfg
+            .phase_runner_max_attempts
+            .or(coordinator.phase_runner_max_attempts)
+            .unwrap_or(1)
+            .max(1),
+        phase_timeout_seconds,
+        stale_heartbeat_seconds,
+        stale_heartbeat_action: profile
+            .coordinator
+            .stale_heartbeat_action
+            .unwrap_or_else(|| resolve_stale_heartbeat_action(&env_cfg, None)),
+        retry_codes: resolve_error_code_retry_list(&env_cfg),
+        retry_max: resolve_error_code_retry_max(&env_cfg),
+        coordinator_tool: env_cfg
+            .coordinator_tool
+            .clone()
+            .or_else(|| coordinator.coordinator_tool.clone()),
+        dispatched_total: 0,
+        jobs: BTreeMap::new(),
+        merges: BTreeMap::new(),
+        tool_stats: BTreeMap::new(),
+        task_stats: BTreeMap::new(),
+        error_codes: BTreeMap::new(),
+        retries: 0,
+    };
+    let outcome = coordinator_engine::run_control_plane(
+        &mut backend,
+        ControlPlaneLoopConfig {
+            timeout: None,
+            max_no_progress_cycles: MAX_NO_PROGRESS_CYCLES,
+        },
+    )
+    .await;
+    Ok(backend.report(outcome.err().map(|err| err.to_string())))
+}
+
+/// Reads the PRD and profile files and runs [`simulate`] on a single-threaded runtime.
+pub fn simulate_files(
+    prd_file: &Path,
+    profile_file: &Path,
+    canonical: &CanonicalConfig,
+    coordinator: Option<&CoordinatorConfig>,
+    env_cfg: &CoordinatorEnvConfig,
+) -> Result<SimulationReport> {
+    let raw = std::fs::read_to_string(prd_file).map_err(|e| MaccError::Io {
+        path: prd_file.to_string_lossy().into(),
+        action: "read coordinator prd".into(),
+        source: e,
+    })?;
+    let prd: Value = serde_json::from_str(&raw).map_err(|e| {
+        MaccError::Validation(format!("Failed to parse PRD {}: {}", prd_file.display(), e))
+    })?;
+    let prd_tasks = prd
+        .get("tasks")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+    let profile = SimulationProfile::load(profile_file)?;
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .map_err(|e| MaccError::Validation(format!("Failed to initialize tokio runtime: {}", e)))?;
+    runtime.block_on(simulate(
+        &prd_tasks,
+        &profile,
+        canonical,
+        coordinator,
+        env_cfg,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(prd_tasks: Value, profile: &SimulationProfile) -> SimulationReport {
+        let canonical =
+            CanonicalConfig::from_yaml("version: v1\ntools:\n  enabled: [tool-a]\n").unwrap();
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(simulate(
+                prd_tasks.as_array().unwrap(),
+                profile,
+                &canonical,
+                None,
+                &CoordinatorEnvConfig::default(),
+            ))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_simulate_runs_the_task_lifecycle_in_simulated_time() {
+        let report = run(json!([{"id": "T1"}]), &SimulationProfile::default());
+        assert_eq!(report.stopped, None);
+        assert_eq!(report.merged, 1);
+        // dev 600s, review 120s, one idle cycle, integrate 60s, one idle cycle, merge 30s.
+        assert_eq!(report.makespan_seconds, 820.0);
+        assert_eq!(report.cycles, 5);
+        assert_eq!(report.tools[0].tool, "tool-a");
+        assert_eq!(report.tools[0].busy_seconds, 780.0);
+        assert_eq!(report.tasks[0].finished_at_seconds, Some(820.0));
+    }
+
+    #[test]
+    fn test_simulate_reports_failures_and_the_tasks_they_strand() {
+        let profile = SimulationProfile {
+            defaults: SimulatedTool {
+                failure_rate: 1.0,
+                error_codes: BTreeMap::from([("E999".to_string(), 1.0)]),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let report = run(
+            json!([
+                {"id": "T1"},
+                {"id": "T2", "dependencies": ["T1"]},
+                {"id": "T3", "dependencies": ["T2"]}
+            ]),
+            &profile,
+        );
+        assert!(report.stopped.unwrap().contains("no progress"));
+        assert_eq!((report.blocked, report.unfinished), (1, 2));
+        assert_eq!(
+            report.error_codes,
+            BTreeMap::from([("E999".to_string(), 1)])
+        );
+        assert_eq!(report.failure_propagation.len(), 1);
+        let failure = &report.failure_propagation[0];
+        assert_eq!(
+            (failure.task_id.as_str(), failure.phase.as_str()),
+            ("T1", "dev")
+        );
+        assert_eq!(failure.stranded, vec!["T2", "T3"]);
+    }
+
+    #[test]
+    fn test_simulate_applies_staleness_thresholds_to_hung_performers() {
+        let mut profile = SimulationProfile {
+            coordinator: SimulatedCoordinator {
+                stale_heartbeat_seconds: Some(100),
+                stale_heartbeat_action: Some(StaleHeartbeatAction::Block),
+                ..Default::default()
+            },
+            defaults: SimulatedTool {
+                hang_rate: 1.0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let report = run(json!([{"id": "T1"}]), &profile);
+        assert_eq!(report.blocked, 1);
+        assert_eq!(report.makespan_seconds, 101.0);
+        assert_eq!(
+            report.error_codes,
+            BTreeMap::from([("stale_heartbeat".to_string(), 1)])
+        );
+        assert!(report.tasks[0]
+            .last_error
+            .as_deref()
+            .unwrap()
+            .contains("stale heartbeat"));
+
+        // The phase timeout kills the performer before its heartbeat is stale.
+        profile.coordinator.stale_in_progress_seconds = Some(50);
+        let report = run(json!([{"id": "T1"}]), &profile);
+        assert_eq!(report.tools[0].busy_seconds, 50.0);
+        assert!(!report.error_codes.contains_key("stale_heartbeat"));
+        assert!(report.tasks[0]
+            .last_error
+            .as_deref()
+            .unwrap()
+            .contains("timed out after 50s"));
+
+        // Without either threshold a hung performer would never end.
+        profile.coordinator.stale_in_progress_seconds = None;
+        profile.coordinator.stale_heartbeat_seconds = Some(0);
+        let canonical =
+            CanonicalConfig::from_yaml("version: v1\ntools:\n  enabled: [tool-a]\n").unwrap();
+        let err = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(simulate(
+                &[json!({"id": "T1"})],
+                &profile,
+                &canonical,
+                None,
+                &CoordinatorEnvConfig::default(),
+            ))
+            .unwrap_err();
+        assert!(err.to_string().contains("hang_rate"), "{}", err);
+    }
+
+    #[test]
+    fn test_simulate_is_deterministic_for_a_seed() {
+        let profile: SimulationProfile = serde_yaml::from_str(
+            r#"
+seed: 7
+coordinator:
+  max_parallel: 2
+defaults:
+  durations:
+    dev: {distribution: uniform, min: 300, max: 900}
+    review: {distribution: exponential, mean: 90}
+  changes_requested_rate: 0.5
+merge:
+  duration: {distribution: normal, mean: 30, stddev: 10}
+"#,
+        )
+        .unwrap();
+        profile.validate().unwrap();
+        let prd = json!([{"id": "T1"}, {"id": "T2"}, {"id": "T3"}, {"id": "T4"}]);
+        let first = run(prd.clone(), &profile);
+        let second = run(prd, &profile);
+        assert_eq!(first.merged, 4);
+        assert_eq!(first.max_parallel, 2);
+        assert_eq!(
+            serde_json::to_value(&first).unwrap(),
+            serde_json::to_value(&second).unwrap()
+        );
+
+        let invalid = SimulationProfile {
+            merge: SimulatedMerge {
+                failure_rate: 1.5,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(invalid.validate().is_err());
+    }
+}
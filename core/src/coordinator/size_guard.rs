@@ -0,0 +1,172 @@
+use crate::config::{SizeGuardConfig, SizeLimits};
+use crate::{MaccError, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+
+/// Size of a task branch against its base (`git diff --numstat base...branch`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct DiffStats {
+    pub files: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+impl DiffStats {
+    pub fn lines(&self) -> usize {
+        self.insertions + self.deletions
+    }
+}
+
+/// Outcome of the size guard for one task, applied by the engine and replayed from events.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SizeCheck {
+    pub stats: DiffStats,
+    /// Exceeded limits, e.g. `14 files changed (max 10)`; empty when the task is within them.
+    pub violations: Vec<String>,
+    /// Block the task instead of sending it back.
+    pub block: bool,
+}
+
+pub fn diff_stats(repo_root: &Path, base: &str, branch: &str) -> Result<DiffStats> {
+    let range = format!("{}...{}", base, branch);
+    let output = crate::git::run_git_output_mapped(
+        repo_root,
+        &["diff", "--numstat", &range],
+        "measure task diff",
+    )?;
+    if !output.status.success() {
+        return Err(MaccError::Validation(format!(
+            "git diff --numstat {} failed: {}",
+            range,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(parse_numstat(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Binary files (`-` counts) count as changed files without lines.
+fn parse_numstat(text: &str) -> DiffStats {
+    let mut stats = DiffStats::default();
+    for line in text.lines().filter(|l| !l.trim().is_empty()) {
+        let mut fields = line.split('\t');
+        let added = fields.next().and_then(|v| v.parse::<usize>().ok());
+        let deleted = fields.next().and_then(|v| v.parse::<usize>().ok());
+        stats.files += 1;
+        stats.insertions += added.unwrap_or(0);
+        stats.deletions += deleted.unwrap_or(0);
+    }
+    stats
+}
+
+pub fn violations(stats: &DiffStats, limits: &SizeLimits) -> Vec<String> {
+    let mut out = Vec::new();
+    if let Some(max) = limits.max_files.filter(|max| stats.files > *max) {
+        out.push(format!("{} files changed (max {})", stats.files, max));
+    }
+    if let Some(max) = limits.max_lines.filter(|max| stats.lines() > *max) {
+        out.push(format!("{} lines changed (max {})", stats.lines(), max));
+    }
+    out
+}
+
+/// Times the guard already sent the task back.
+pub fn size_rejections(task: &Value) -> u64 {
+    task.get("task_runtime")
+        .and_then(|r| r.get("metrics"))
+        .and_then(|m| m.get("size_rejections"))
+        .and_then(Value::as_u64)
+        .unwrap_or(0)
+}
+
+/// Measures the task branch and checks it against the limits of its category. A task that is
+/// still too large after being sent back once is blocked. `None` without a task branch.
+pub fn check_task(
+    repo_root: &Path,
+    config: &SizeGuardConfig,
+    task: &Value,
+) -> Result<Option<SizeCheck>> {
+    let worktree = task.get("worktree");
+    let branch = worktree
+        .and_then(|w| w.get("branch"))
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    if branch.is_empty() {
+        return Ok(None);
+    }
+    let base = worktree
+        .and_then(|w| w.get("base_branch"))
+        .and_then(Value::as_str)
+        .unwrap_or("master");
+    let stats = diff_stats(repo_root, base, branch)?;
+    let limits = config.limits_for(task.get("category").and_then(Value::as_str));
+    let violations = violations(&stats, &limits);
+    let block = !violations.is_empty()
+        && (config.action.as_deref() == Some("blocked") || size_rejections(task) > 0);
+    Ok(Some(SizeCheck {
+        stats,
+        violations,
+        block,
+    }))
+}
+
+/// Review feedback that sends an oversized task back to the fix phase.
+pub fn split_instruction(violations: &[String]) -> String {
+    format!(
+        "Split or reduce this change: {}. Keep this task's diff within the limits and leave the remaining work for a follow-up task.",
+        violations.join("; ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_numstat_counts_files_and_lines() {
+        let stats = parse_numstat("10\t2\tsrc/a.rs\n-\t-\tassets/logo.png\n0\t7\tREADME.md\n");
+        assert_eq!(
+            stats,
+            DiffStats {
+                files: 3,
+                insertions: 10,
+                deletions: 9
+            }
+        );
+        assert_eq!(stats.lines(), 19);
+    }
+
+    #[test]
+    fn test_category_limits_replace_globals_field_by_field() {
+        let config = SizeGuardConfig {
+            max_files: Some(20),
+            max_lines: Some(300),
+            categories: BTreeMap::from([(
+                "docs".to_string(),
+                SizeLimits {
+                    max_files: None,
+                    max_lines: Some(1000),
+                },
+            )]),
+            ..SizeGuardConfig::default()
+        };
+        let stats = DiffStats {
+            files: 21,
+            insertions: 400,
+            deletions: 0,
+        };
+        assert_eq!(
+            violations(&stats, &config.limits_for(Some("backend"))),
+            vec![
+                "21 files changed (max 20)".to_string(),
+                "400 lines changed (max 300)".to_string()
+            ]
+        );
+        assert_eq!(
+            violations(&stats, &config.limits_for(Some("Docs"))),
+            vec!["21 files changed (max 20)".to_string()]
+        );
+        assert!(violations(&stats, &SizeLimits::default()).is_empty());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::coordinator::replay::{record_task_mutation, TaskMutation};
 use crate::coordinator_storage::{
     apply_transition_sqlite_with_event, coordinator_storage_export_sqlite_to_json,
     increment_retries_sqlite, set_merge_pending_sqlite, set_merge_processed_sqlite,
@@ -20,82 +21,67 @@ pub fn coordinator_state_apply_transition(
 ) -> Result<()> {
     let task_id = required_arg(args, "task-id")?;
     let new_state = required_arg(args, "state")?;
-    let pr_url = args.get("pr-url").cloned().unwrap_or_default();
-    let reviewer = args.get("reviewer").cloned().unwrap_or_default();
-    let reason = args.get("reason").cloned().unwrap_or_default();
-    let now = now_iso();
+    let change = TransitionMutation {
+        task_id: task_id.clone(),
+        new_state: new_state.clone(),
+        pr_url: args.get("pr-url").cloned().unwrap_or_default(),
+        reviewer: args.get("reviewer").cloned().unwrap_or_default(),
+        reason: args.get("reason").cloned().unwrap_or_default(),
+        now: now_iso(),
+    };
     let storage_mode = resolve_storage_mode(args)?;
     if storage_mode != CoordinatorStorageMode::Json {
         let paths = ProjectPaths::from_root(repo_root);
         let event = parse_optional_event_mutation(args, &task_id, &new_state)?;
-        let change = TransitionMutation {
-            task_id: task_id.clone(),
-            new_state: new_state.clone(),
-            pr_url,
-            reviewer,
-            reason,
-            now,
-        };
         apply_transition_sqlite_with_event(&paths, &change, event.as_ref())?;
         maybe_mirror_json(&paths, args)?;
+        record_task_mutation(repo_root, &task_id, &TaskMutation::Transition(change));
         return Ok(());
     }
 
     let registry_path = coordinator_registry_path(repo_root);
     let mut registry = load_registry(&registry_path)?;
-    let tasks = registry
-        .get_mut("tasks")
-        .and_then(Value::as_array_mut)
-        .ok_or_else(|| MaccError::Validation("Registry missing tasks array".into()))?;
+    let task = registry_task_mut(&mut registry, &task_id)?;
+    apply_transition_to_task(task, &change);
 
-    let mut found = false;
-    for task in tasks.iter_mut() {
-        let id = task.get("id").and_then(Value::as_str).unwrap_or_default();
-        if id != task_id {
-            continue;
-        }
-        found = true;
-        task["state"] = Value::String(new_state.clone());
-        task["updated_at"] = Value::String(now.clone());
-        task["state_changed_at"] = Value::String(now.clone());
+    let locks = recompute_resource_locks(&registry, &change.now);
+    registry["resource_locks"] = locks;
+    registry["updated_at"] = Value::String(change.now.clone());
+    save_registry(&registry_path, &registry)?;
+    record_task_mutation(repo_root, &task_id, &TaskMutation::Transition(change));
+    Ok(())
+}
 
-        if new_state == "pr_open" && !pr_url.is_empty() {
-            task["pr_url"] = Value::String(pr_url.clone());
-        }
-        if new_state == "changes_requested" {
-            ensure_object(task, "review");
-            task["review"]["changed"] = Value::Bool(true);
-            task["review"]["last_reviewed_at"] = Value::String(now.clone());
-            if !reviewer.is_empty() {
-                task["review"]["reviewer"] = Value::String(reviewer.clone());
-            }
-            if !reason.is_empty() {
-                task["review"]["reason"] = Value::String(reason.clone());
-            }
-        }
+/// Applies a workflow state change to one task of a JSON registry.
+pub fn apply_transition_to_task(task: &mut Value, change: &TransitionMutation) {
+    let new_state = change.new_state.as_str();
+    let now = &change.now;
+    task["state"] = Value::String(new_state.to_string());
+    task["updated_at"] = Value::String(now.clone());
+    task["state_changed_at"] = Value::String(now.clone());
 
-        if matches!(new_state.as_str(), "merged" | "abandoned" | "todo") {
-            task["assignee"] = Value::Null;
-            task["claimed_at"] = Value::Null;
-            task["worktree"] = Value::Null;
-            ensure_object(task, "task_runtime");
-            reset_runtime_to_idle(task);
+    if new_state == "pr_open" && !change.pr_url.is_empty() {
+        task["pr_url"] = Value::String(change.pr_url.clone());
+    }
+    if new_state == "changes_requested" {
+        ensure_object(task, "review");
+        task["review"]["changed"] = Value::Bool(true);
+        task["review"]["last_reviewed_at"] = Value::String(now.clone());
+        if !change.reviewer.is_empty() {
+            task["review"]["reviewer"] = Value::String(change.reviewer.clone());
+        }
+        if !change.reason.is_empty() {
+            task["review"]["reason"] = Value::String(change.reason.clone());
         }
-        break;
     }
 
-    if !found {
-        return Err(MaccError::Validation(format!(
-            "Task not found in registry: {}",
-            task_id
-        )));
+    if matches!(new_state, "merged" | "abandoned" | "todo") {
+        task["assignee"] = Value::Null;
+        task["claimed_at"] = Value::Null;
+        task["worktree"] = Value::Null;
+        ensure_object(task, "task_runtime");
+        reset_runtime_to_idle(task);
     }
-
-    let locks = recompute_resource_locks(&registry, &now);
-    registry["resource_locks"] = locks;
-    registry["updated_at"] = Value::String(now);
-    save_registry(&registry_path, &registry)?;
-    Ok(())
 }
 
 pub fn coordinator_state_set_runtime(
@@ -104,135 +90,118 @@ pub fn coordinator_state_set_runtime(
 ) -> Result<()> {
     let task_id = required_arg(args, "task-id")?;
     let runtime_status = required_arg(args, "runtime-status")?;
-    let phase = args.get("phase").cloned().unwrap_or_default();
     let pid = args.get("pid").cloned().unwrap_or_default();
-    let last_error = args.get("last-error").cloned().unwrap_or_default();
-    let heartbeat_ts = args.get("heartbeat-ts").cloned().unwrap_or_default();
     let attempt = args.get("attempt").cloned().unwrap_or_default();
-    let now = now_iso();
+    let change = RuntimeMutation {
+        task_id: task_id.clone(),
+        runtime_status: runtime_status.clone(),
+        phase: args.get("phase").cloned().unwrap_or_default(),
+        pid: if pid.is_empty() {
+            None
+        } else {
+            pid.parse::<i64>().ok()
+        },
+        last_error: args.get("last-error").cloned().unwrap_or_default(),
+        heartbeat_ts: args.get("heartbeat-ts").cloned().unwrap_or_default(),
+        attempt: if attempt.is_empty() {
+            None
+        } else {
+            attempt.parse::<i64>().ok()
+        },
+        now: now_iso(),
+    };
     let storage_mode = resolve_storage_mode(args)?;
     if storage_mode != CoordinatorStorageMode::Json {
         let paths = ProjectPaths::from_root(repo_root);
         let event = parse_optional_event_mutation(args, &task_id, &runtime_status)?;
-        let change = RuntimeMutation {
-            task_id: task_id.clone(),
-            runtime_status: runtime_status.clone(),
-            phase,
-            pid: if pid.is_empty() {
-                None
-            } else {
-                pid.parse::<i64>().ok()
-            },
-            last_error,
-            heartbeat_ts,
-            attempt: if attempt.is_empty() {
-                None
-            } else {
-                attempt.parse::<i64>().ok()
-            },
-            now,
-        };
         set_runtime_sqlite_with_event(&paths, &change, event.as_ref())?;
         maybe_mirror_json(&paths, args)?;
+        record_task_mutation(repo_root, &task_id, &TaskMutation::Runtime(change));
         return Ok(());
     }
 
     let registry_path = coordinator_registry_path(repo_root);
     let mut registry = load_registry(&registry_path)?;
-    let tasks = registry
-        .get_mut("tasks")
-        .and_then(Value::as_array_mut)
-        .ok_or_else(|| MaccError::Validation("Registry missing tasks array".into()))?;
+    let task = registry_task_mut(&mut registry, &task_id)?;
+    apply_runtime_to_task(task, &change);
 
-    let mut found = false;
-    for task in tasks.iter_mut() {
-        let id = task.get("id").and_then(Value::as_str).unwrap_or_default();
-        if id != task_id {
-            continue;
-        }
-        found = true;
-        ensure_object(task, "task_runtime");
-        ensure_object(&mut task["task_runtime"], "metrics");
-        ensure_object(&mut task["task_runtime"], "slo_warnings");
+    registry["updated_at"] = Value::String(change.now.clone());
+    save_registry(&registry_path, &registry)?;
+    record_task_mutation(repo_root, &task_id, &TaskMutation::Runtime(change));
+    Ok(())
+}
 
-        let old_status = task["task_runtime"]["status"]
-            .as_str()
-            .unwrap_or("idle")
-            .to_string();
-        let old_phase = task["task_runtime"]["current_phase"]
-            .as_str()
-            .unwrap_or("")
-            .to_string();
+/// Applies a runtime status update to one task of a JSON registry.
+pub fn apply_runtime_to_task(task: &mut Value, change: &RuntimeMutation) {
+    let runtime_status = change.runtime_status.as_str();
+    let phase = change.phase.as_str();
+    let now = &change.now;
+    ensure_object(task, "task_runtime");
+    ensure_object(&mut task["task_runtime"], "metrics");
+    ensure_object(&mut task["task_runtime"], "slo_warnings");
 
-        task["task_runtime"]["status"] = Value::String(runtime_status.clone());
-        if !phase.is_empty() {
-            task["task_runtime"]["current_phase"] = Value::String(phase.clone());
-        }
-        if !pid.is_empty() {
-            let parsed = pid
-                .parse::<i64>()
-                .ok()
-                .map(Value::from)
-                .unwrap_or(Value::Null);
-            task["task_runtime"]["pid"] = parsed;
-        } else if matches!(
-            runtime_status.as_str(),
-            "idle" | "phase_done" | "failed" | "stale"
-        ) {
-            task["task_runtime"]["pid"] = Value::Null;
-        }
-        if !last_error.is_empty() {
-            task["task_runtime"]["last_error"] = Value::String(last_error.clone());
-        }
-        if !heartbeat_ts.is_empty() {
-            task["task_runtime"]["last_heartbeat"] = Value::String(heartbeat_ts);
-        }
-        if !attempt.is_empty() {
-            if let Ok(parsed) = attempt.parse::<i64>() {
-                task["task_runtime"]["attempt"] = Value::from(parsed);
-            }
-        }
+    let old_status = task["task_runtime"]["status"]
+        .as_str()
+        .unwrap_or("idle")
+        .to_string();
+    let old_phase = task["task_runtime"]["current_phase"]
+        .as_str()
+        .unwrap_or("")
+        .to_string();
 
-        if runtime_status == "running"
-            && task["task_runtime"]["started_at"]
-                .as_str()
-                .unwrap_or_default()
-                .is_empty()
-        {
-            task["task_runtime"]["started_at"] = Value::String(now.clone());
-        }
+    task["task_runtime"]["status"] = Value::String(runtime_status.to_string());
+    if !phase.is_empty() {
+        task["task_runtime"]["current_phase"] = Value::String(phase.to_string());
+    }
+    if let Some(pid) = change.pid {
+        task["task_runtime"]["pid"] = Value::from(pid);
+    } else if matches!(runtime_status, "idle" | "phase_done" | "failed" | "stale") {
+        task["task_runtime"]["pid"] = Value::Null;
+    }
+    if !change.last_error.is_empty() {
+        task["task_runtime"]["last_error"] = Value::String(change.last_error.clone());
+    }
+    if !change.heartbeat_ts.is_empty() {
+        task["task_runtime"]["last_heartbeat"] = Value::String(change.heartbeat_ts.clone());
+    }
+    if let Some(attempt) = change.attempt {
+        task["task_runtime"]["attempt"] = Value::from(attempt);
+    }
 
-        let phase_changed = !phase.is_empty() && phase != old_phase;
-        let status_became_running = old_status != "running" && runtime_status == "running";
-        let missing_phase_started = task["task_runtime"]["phase_started_at"]
+    if runtime_status == "running"
+        && task["task_runtime"]["started_at"]
             .as_str()
             .unwrap_or_default()
-            .is_empty();
-        if runtime_status == "running"
-            && (phase_changed || status_became_running || missing_phase_started)
-        {
-            task["task_runtime"]["phase_started_at"] = Value::String(now.clone());
-        } else if matches!(
-            runtime_status.as_str(),
-            "idle" | "phase_done" | "failed" | "stale"
-        ) {
-            task["task_runtime"]["phase_started_at"] = Value::Null;
-        }
-
-        task["updated_at"] = Value::String(now.clone());
-        break;
+            .is_empty()
+    {
+        task["task_runtime"]["started_at"] = Value::String(now.clone());
     }
 
-    if !found {
-        return Err(MaccError::Validation(format!(
-            "Task not found in registry: {}",
-            task_id
-        )));
+    let phase_changed = !phase.is_empty() && phase != old_phase;
+    let status_became_running = old_status != "running" && runtime_status == "running";
+    let missing_phase_started = task["task_runtime"]["phase_started_at"]
+        .as_str()
+        .unwrap_or_default()
+        .is_empty();
+    if runtime_status == "running"
+        && (phase_changed || status_became_running || missing_phase_started)
+    {
+        task["task_runtime"]["phase_started_at"] = Value::String(now.clone());
+    } else if matches!(runtime_status, "idle" | "phase_done" | "failed" | "stale") {
+        task["task_runtime"]["phase_started_at"] = Value::Null;
     }
 
-    registry["updated_at"] = Value::String(now);
-    save_registry(&registry_path, &registry)?;
-    Ok(())
+    task["updated_at"] = Value::String(now.clone());
+}
+
+fn registry_task_mut<'a>(registry: &'a mut Value, task_id: &str) -> Result<&'a mut Value> {
+    registry
+        .get_mut("tasks")
+        .and_then(Value::as_array_mut)
+        .ok_or_else(|| MaccError::Validation("Registry missing tasks array".into()))?
+        .iter_mut()
+        .find(|task| task.get("id").and_then(Value::as_str) == Some(task_id))
+        .ok_or_else(|| MaccError::Validation(format!("Task not found in registry: {}", task_id)))
 }
 
 pub fn coordinator_state_task_field(
@@ -719,7 +688,9 @@ fn now_iso() -> String {
     chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
 }
 
-fn resolve_storage_mode(args: &BTreeMap<String, String>) -> Result<CoordinatorStorageMode> {
+pub(crate) fn resolve_storage_mode(
+    args: &BTreeMap<String, String>,
+) -> Result<CoordinatorStorageMode> {
     let raw = args
         .get("storage-mode")
         .cloned()
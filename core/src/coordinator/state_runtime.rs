@@ -160,6 +160,14 @@ pub fn cleanup_dead_runtime_tasks_in_registry(
     )?;
     let fixed = cleaned.len();
     for entry in cleaned {
+        if let Some(root) = repo_root {
+            crate::coordinator::replay::record_task_mutation_at(
+                root,
+                &entry.task_id,
+                &crate::coordinator::replay::TaskMutation::DeadRuntime { pid: entry.pid },
+                &now,
+            );
+        }
         if let Some(log) = logger {
             log(format!(
                 "- Runtime ghost cleanup task={} state={} phase={} pid={} -> {} ({})",
@@ -1,3 +1,5 @@
+use crate::coordinator::decompose::is_tracking_task;
+use crate::coordinator::glob::{has_wildcard, matches_any_depth, normalize, path_matches};
 use serde_json::Value;
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
@@ -10,6 +12,10 @@ pub struct TaskSelectorConfig {
     pub max_parallel: usize,
     pub default_tool: String,
     pub default_base_branch: String,
+    /// Hold back tasks whose declared `scope` overlaps the scope of an active task.
+    pub avoid_scope_overlap: bool,
+    /// Files already changed on active task branches, keyed by task id.
+    pub touched_files: HashMap<String, Vec<String>>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -20,6 +26,14 @@ pub struct SelectedTask {
     pub base_branch: String,
 }
 
+/// A ready task held back because its `scope` overlaps work already in flight.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScopeOverlap {
+    pub task_id: String,
+    pub active_task_id: String,
+    pub reason: String,
+}
+
 pub fn select_next_ready_task(
     registry: &Value,
     config: &TaskSelectorConfig,
@@ -41,7 +55,7 @@ pub fn select_next_ready_task(
         .collect();
 
     let mut active_by_tool: HashMap<String, usize> = HashMap::new();
-    for task in active_tasks {
+    for task in &active_tasks {
         if let Some(tool) = task.get("tool").and_then(Value::as_str) {
             *active_by_tool.entry(tool.to_string()).or_insert(0) += 1;
         }
@@ -62,6 +76,10 @@ pub fn select_next_ready_task(
         if task.get("worktree").is_some() && !task.get("worktree").unwrap().is_null() {
             continue;
         }
+        // A decomposed task merges with its subtasks and never runs itself.
+        if is_tracking_task(task) {
+            continue;
+        }
 
         let task_id = task.get("id").and_then(Value::as_str).unwrap_or_default();
         if task_id.is_empty() {
@@ -74,6 +92,17 @@ pub fn select_next_ready_task(
         if !resources_available(task, task_id, &resource_locks) {
             continue;
         }
+        if scope_overlap(
+            task,
+            task_id,
+            &active_tasks,
+            config.avoid_scope_overlap,
+            &config.touched_files,
+        )
+        .is_some()
+        {
+            continue;
+        }
 
         let Some(tool) = pick_tool(task, config, &active_by_tool) else {
             continue;
@@ -114,18 +143,149 @@ pub fn select_next_ready_task(
     candidates.into_iter().next().map(|(_, _, _, s)| s)
 }
 
+/// Ready `todo` tasks that the selector holds back because their scope overlaps an active task.
+pub fn scope_waits(
+    registry: &Value,
+    avoid_scope_overlap: bool,
+    touched_files: &HashMap<String, Vec<String>>,
+) -> Vec<ScopeOverlap> {
+    let tasks = registry
+        .get("tasks")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let active_tasks: Vec<&Value> = tasks
+        .iter()
+        .filter(|t| is_active_state(task_state(t)))
+        .collect();
+    let merged_ids: HashSet<String> = tasks
+        .iter()
+        .filter(|t| task_state(t) == "merged")
+        .filter_map(|t| t.get("id").and_then(Value::as_str).map(ToOwned::to_owned))
+        .collect();
+    tasks
+        .iter()
+        .filter(|t| task_state(t) == "todo")
+        .filter(|t| t.get("worktree").map(Value::is_null).unwrap_or(true))
+        .filter(|t| !is_tracking_task(t))
+        .filter(|t| dependencies_ready(t, &merged_ids))
+        .filter_map(|t| {
+            let task_id = t.get("id").and_then(Value::as_str).unwrap_or_default();
+            if task_id.is_empty() {
+                return None;
+            }
+            scope_overlap(
+                t,
+                task_id,
+                &active_tasks,
+                avoid_scope_overlap,
+                touched_files,
+            )
+        })
+        .collect()
+}
+
+/// Path globs of a task `scope`, given as a list or a comma/newline separated string.
+pub fn task_scope(task: &Value) -> Vec<String> {
+    let raw: Vec<String> = match task.get("scope") {
+        Some(Value::String(s)) => s.split([',', '\n']).map(ToOwned::to_owned).collect(),
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(Value::as_str)
+            .map(ToOwned::to_owned)
+            .collect(),
+        _ => Vec::new(),
+    };
+    dedup_and_clean(raw)
+}
+
+/// Whether two scope globs may match a common path. Errs towards overlap: it never misses a
+/// shared path, but `src/a/*.rs` and `src/a/*.md` count as overlapping.
+pub fn globs_overlap(a: &str, b: &str) -> bool {
+    let a = normalize(a);
+    let b = normalize(b);
+    if a.is_empty() || b.is_empty() {
+        return false;
+    }
+    if path_matches(a, b) || path_matches(b, a) {
+        return true;
+    }
+    let a_prefix = literal_prefix(a);
+    let b_prefix = literal_prefix(b);
+    let common = a_prefix.len().min(b_prefix.len());
+    a_prefix[..common] == b_prefix[..common]
+}
+
+/// Leading segments without wildcards. A wildcard pattern without `/` (`*.md`) matches at any
+/// depth, so it has no fixed prefix.
+fn literal_prefix(pattern: &str) -> Vec<&str> {
+    if matches_any_depth(pattern) {
+        return Vec::new();
+    }
+    pattern
+        .split('/')
+        .take_while(|segment| !has_wildcard(segment))
+        .collect()
+}
+
+fn scope_overlap(
+    task: &Value,
+    task_id: &str,
+    active_tasks: &[&Value],
+    declared: bool,
+    touched_files: &HashMap<String, Vec<String>>,
+) -> Option<ScopeOverlap> {
+    let scope = task_scope(task);
+    if scope.is_empty() {
+        return None;
+    }
+    for active in active_tasks {
+        let active_id = active.get("id").and_then(Value::as_str).unwrap_or_default();
+        if active_id.is_empty() || active_id == task_id {
+            continue;
+        }
+        let overlap = |reason: String| ScopeOverlap {
+            task_id: task_id.to_string(),
+            active_task_id: active_id.to_string(),
+            reason,
+        };
+        let active_scope = if declared {
+            task_scope(active)
+        } else {
+            Vec::new()
+        };
+        for active_pattern in active_scope {
+            if let Some(pattern) = scope.iter().find(|p| globs_overlap(p, &active_pattern)) {
+                return Some(overlap(format!(
+                    "scope {} overlaps {} scope {}",
+                    pattern, active_id, active_pattern
+                )));
+            }
+        }
+        for file in touched_files.get(active_id).into_iter().flatten() {
+            if let Some(pattern) = scope.iter().find(|p| path_matches(p, file)) {
+                return Some(overlap(format!(
+                    "scope {} matches {} already changed by {}",
+                    pattern, file, active_id
+                )));
+            }
+        }
+    }
+    None
+}
+
 fn task_state(task: &Value) -> &str {
     task.get("state").and_then(Value::as_str).unwrap_or("todo")
 }
 
-fn is_active_state(state: &str) -> bool {
+pub(crate) fn is_active_state(state: &str) -> bool {
     matches!(
         state,
         "claimed" | "in_progress" | "pr_open" | "changes_requested" | "queued"
     )
 }
 
-fn parse_priority(priority: Option<&Value>) -> i32 {
+pub(crate) fn parse_priority(priority: Option<&Value>) -> i32 {
     match priority {
         Some(Value::Number(n)) => n.as_i64().unwrap_or(99) as i32,
         Some(Value::String(s)) => {
@@ -188,7 +348,12 @@ fn pick_tool(
     active_by_tool: &HashMap<String, usize>,
 ) -> Option<String> {
     let preference = preference_list(task, config);
-    let fallback = fallback_pool(task, config, &preference);
+    // A tool pinned by an operator waits for its own capacity instead of falling back.
+    let fallback = if crate::coordinator::operator::pinned_tool(task).is_some() {
+        Vec::new()
+    } else {
+        fallback_pool(task, config, &preference)
+    };
 
     let mut combined = Vec::new();
     combined.extend(preference.iter().cloned());
@@ -237,6 +402,9 @@ fn pick_tool(
 }
 
 fn preference_list(task: &Value, config: &TaskSelectorConfig) -> Vec<String> {
+    if let Some(pinned) = crate::coordinator::operator::pinned_tool(task) {
+        return vec![pinned.to_string()];
+    }
     let mut out = Vec::new();
     if let Some(category) = task.get("category").and_then(Value::as_str) {
         if let Some(tools) = config.tool_specializations.get(category) {
@@ -339,4 +507,96 @@ mod tests {
         let selected = select_next_ready_task(&registry, &cfg).expect("selected task");
         assert_eq!(selected.id, "DEP");
     }
+
+    #[test]
+    fn operator_pinned_tool_beats_routing_and_waits_for_capacity() {
+        let mut registry = json!({
+          "tasks": [
+            {"id":"UI","title":"ui","state":"todo","category":"frontend","dependencies":[],
+             "operator":{"tool":"tool-b"}},
+            {"id":"RUN","title":"run","state":"in_progress","tool":"tool-b"}
+          ],
+          "resource_locks": {}
+        });
+        let cfg = TaskSelectorConfig {
+            default_tool: "tool-a".into(),
+            default_base_branch: "main".into(),
+            tool_specializations: HashMap::from([("frontend".into(), vec!["tool-a".into()])]),
+            max_parallel_per_tool: HashMap::from([("tool-b".into(), 1)]),
+            ..TaskSelectorConfig::default()
+        };
+        assert!(select_next_ready_task(&registry, &cfg).is_none());
+        registry["tasks"][1]["state"] = json!("merged");
+        let selected = select_next_ready_task(&registry, &cfg).expect("selected task");
+        assert_eq!(selected.tool, "tool-b");
+    }
+
+    #[test]
+    fn scope_globs_overlap_when_they_can_share_a_path() {
+        assert!(globs_overlap("src/**", "src/api/*.rs"));
+        assert!(globs_overlap("src/api/users.rs", "src/api/*"));
+        assert!(globs_overlap("*.md", "docs/guide/**"));
+        assert!(!globs_overlap("src/api/**", "src/ui/**"));
+        assert!(!globs_overlap("src/main.rs", "src/lib.rs"));
+        assert!(!globs_overlap("Cargo.toml", "src/api/**"));
+        assert!(!globs_overlap("Cargo.toml", "README.md"));
+        assert!(globs_overlap("Cargo.toml", "*.toml"));
+        assert!(globs_overlap("Cargo.toml", "Cargo.toml"));
+        assert_eq!(
+            task_scope(&json!({"scope": "src/api/**, docs/*.md"})),
+            vec!["src/api/**".to_string(), "docs/*.md".to_string()]
+        );
+        assert_eq!(
+            task_scope(&json!({"scope": ["web/**"]})),
+            vec!["web/**".to_string()]
+        );
+    }
+
+    #[test]
+    fn overlapping_scope_waits_for_the_active_task() {
+        let mut registry = json!({
+          "tasks": [
+            {"id":"API","title":"api","state":"in_progress","scope":"src/api/**","dependencies":[]},
+            {"id":"USERS","title":"users","state":"todo","priority":"1","scope":"src/api/users.rs","dependencies":[]},
+            {"id":"UI","title":"ui","state":"todo","priority":"2","scope":["web/**"],"dependencies":[]}
+          ],
+          "resource_locks": {}
+        });
+        let mut cfg = TaskSelectorConfig {
+            default_tool: "tool-a".into(),
+            default_base_branch: "main".into(),
+            ..TaskSelectorConfig::default()
+        };
+        // Declared scopes only hold tasks back when enabled.
+        let selected = select_next_ready_task(&registry, &cfg).expect("selected task");
+        assert_eq!(selected.id, "USERS");
+        assert!(scope_waits(&registry, false, &cfg.touched_files).is_empty());
+
+        cfg.avoid_scope_overlap = true;
+        let selected = select_next_ready_task(&registry, &cfg).expect("selected task");
+        assert_eq!(selected.id, "UI");
+        let waits = scope_waits(&registry, true, &cfg.touched_files);
+        assert_eq!(waits.len(), 1);
+        assert_eq!(waits[0].task_id, "USERS");
+        assert_eq!(waits[0].active_task_id, "API");
+        assert!(waits[0].reason.contains("src/api/**"));
+
+        // Files changed on the active branch count even outside its declared scope.
+        registry["tasks"][0]["scope"] = json!("");
+        cfg.touched_files = HashMap::from([("API".into(), vec!["web/index.html".into()])]);
+        let selected = select_next_ready_task(&registry, &cfg).expect("selected task");
+        assert_eq!(selected.id, "USERS");
+        let waits = scope_waits(&registry, true, &cfg.touched_files);
+        assert_eq!(waits[0].task_id, "UI");
+        assert!(waits[0].reason.contains("web/index.html"));
+
+        // A plain name in a scope is the root-level file, not the name at any depth.
+        registry["tasks"][2]["scope"] = json!("index.html");
+        assert!(scope_waits(&registry, true, &cfg.touched_files).is_empty());
+        cfg.touched_files = HashMap::from([("API".into(), vec!["index.html".into()])]);
+        assert_eq!(
+            scope_waits(&registry, true, &cfg.touched_files)[0].task_id,
+            "UI"
+        );
+    }
 }
@@ -0,0 +1,169 @@
+//! Per-tool backoff after provider throttling (`E104`).
+//!
+//! A throttled tool gets a cooldown window (the provider's retry-after, or exponential backoff)
+//! during which no task is dispatched to it. After the window its parallelism stays halved per
+//! strike until one of its jobs succeeds. State lives in `.macc/state/tool-throttle.json` so
+//! `macc coordinator status` can show it.
+
+use crate::{MaccError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+pub const TOOL_THROTTLE_REL_PATH: &str = ".macc/state/tool-throttle.json";
+
+const BASE_COOLDOWN_SECONDS: u64 = 30;
+const MAX_BACKOFF_SECONDS: u64 = 900;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolThrottle {
+    pub strikes: u32,
+    /// Unix time (seconds) until which nothing is dispatched to the tool.
+    pub cooldown_until: i64,
+    /// Parallelism allowed once the cooldown is over, until a job succeeds.
+    pub max_parallel: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_after_seconds: Option<u64>,
+    #[serde(default)]
+    pub reason: String,
+}
+
+impl ToolThrottle {
+    pub fn cooling(&self, now: i64) -> bool {
+        now < self.cooldown_until
+    }
+
+    /// Dispatch cap for the tool at `now`.
+    pub fn effective_max_parallel(&self, now: i64) -> usize {
+        if self.cooling(now) {
+            0
+        } else {
+            self.max_parallel
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThrottleState {
+    #[serde(default)]
+    pub tools: BTreeMap<String, ToolThrottle>,
+}
+
+impl ThrottleState {
+    pub fn path(repo_root: &Path) -> PathBuf {
+        repo_root.join(TOOL_THROTTLE_REL_PATH)
+    }
+
+    /// Saved state, or an empty one when the file is missing or unreadable.
+    pub fn load(repo_root: &Path) -> Self {
+        std::fs::read_to_string(Self::path(repo_root))
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, repo_root: &Path) -> Result<()> {
+        let path = Self::path(repo_root);
+        if self.tools.is_empty() {
+            if path.exists() {
+                std::fs::remove_file(&path).map_err(|e| MaccError::Io {
+                    path: path.to_string_lossy().into(),
+                    action: "remove tool throttle state".into(),
+                    source: e,
+                })?;
+            }
+            return Ok(());
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| MaccError::Io {
+                path: parent.to_string_lossy().into(),
+                action: "create tool throttle state dir".into(),
+                source: e,
+            })?;
+        }
+        let body = serde_json::to_string_pretty(self).map_err(|e| {
+            MaccError::Validation(format!("Failed to serialize tool throttle state: {}", e))
+        })?;
+        std::fs::write(&path, body).map_err(|e| MaccError::Io {
+            path: path.to_string_lossy().into(),
+            action: "write tool throttle state".into(),
+            source: e,
+        })
+    }
+
+    /// Records a throttled job and returns the cooldown in seconds.
+    ///
+    /// Failures arriving while the tool is already cooling down (parallel jobs hitting the same
+    /// limit) extend the window but do not add strikes.
+    pub fn record_throttle(
+        &mut self,
+        tool: &str,
+        retry_after_seconds: Option<u64>,
+        configured_max_parallel: usize,
+        now: i64,
+        reason: &str,
+    ) -> u64 {
+        let entry = self.tools.entry(tool.to_string()).or_default();
+        if !entry.cooling(now) {
+            entry.strikes += 1;
+        }
+        let backoff = BASE_COOLDOWN_SECONDS
+            .saturating_mul(1u64 << (entry.strikes.saturating_sub(1)).min(16))
+            .min(MAX_BACKOFF_SECONDS);
+        let cooldown = retry_after_seconds.unwrap_or(backoff);
+        entry.cooldown_until = entry.cooldown_until.max(now + cooldown as i64);
+        entry.max_parallel = (configured_max_parallel.max(1) >> entry.strikes.min(16)).max(1);
+        entry.retry_after_seconds = retry_after_seconds;
+        entry.reason = reason.to_string();
+        cooldown
+    }
+
+    /// Clears the tool's backoff after a successful job; returns whether it was throttled.
+    pub fn record_success(&mut self, tool: &str) -> bool {
+        self.tools.remove(tool).is_some()
+    }
+
+    /// Lowers per-tool dispatch caps for throttled tools.
+    pub fn apply_caps(&self, caps: &mut HashMap<String, usize>, now: i64) {
+        for (tool, throttle) in &self.tools {
+            let limit = throttle.effective_max_parallel(now);
+            let cap = caps.entry(tool.clone()).or_insert(limit);
+            *cap = (*cap).min(limit);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_cooldown_and_reduced_parallelism() {
+        let mut state = ThrottleState::default();
+        assert_eq!(state.record_throttle("tool-a", None, 4, 1_000, "429"), 30);
+        // A second job throttled during the window does not add a strike.
+        assert_eq!(
+            state.record_throttle("tool-a", Some(10), 4, 1_005, "429"),
+            10
+        );
+        let throttle = &state.tools["tool-a"];
+        assert_eq!(throttle.strikes, 1);
+        assert_eq!(throttle.cooldown_until, 1_030);
+        assert_eq!(throttle.max_parallel, 2);
+
+        let mut caps = HashMap::from([("tool-b".to_string(), 3)]);
+        state.apply_caps(&mut caps, 1_010);
+        assert_eq!(caps["tool-a"], 0);
+        assert_eq!(caps["tool-b"], 3);
+        state.apply_caps(&mut caps, 1_030);
+        assert_eq!(caps["tool-a"], 0);
+        let mut caps = HashMap::new();
+        state.apply_caps(&mut caps, 1_030);
+        assert_eq!(caps["tool-a"], 2);
+
+        assert_eq!(state.record_throttle("tool-a", None, 4, 1_040, "429"), 60);
+        assert_eq!(state.tools["tool-a"].max_parallel, 1);
+        assert!(state.record_success("tool-a"));
+        assert!(state.tools.is_empty());
+    }
+}
@@ -0,0 +1,582 @@
+//! Trace export of a coordinator run, derived offline from the event log.
+//!
+//! The run is the root span, each task a child span, and each phase of a task (`dev`, `review`,
+//! `fix`, `integrate`, `merge`) a grandchild span carrying tool, attempt and error-code
+//! attributes. Phase spans come from `task_phase_duration_seconds` events when the control plane
+//! recorded them, otherwise from the first and last event of the phase. Event timestamps have
+//! second precision, so very short phases show up as zero-length spans.
+
+use crate::coordinator_storage::{
+    CoordinatorSnapshot, CoordinatorStorage, CoordinatorStoragePaths, JsonStorage, SqliteStorage,
+};
+use crate::{MaccError, ProjectPaths, Result};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+const PHASE_DURATION_EVENT: &str = "task_phase_duration_seconds";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceFormat {
+    /// OTLP/JSON (`ExportTraceServiceRequest`), accepted by OpenTelemetry collectors.
+    OtlpJson,
+    /// Chrome trace event format, opens in `chrome://tracing` or Perfetto.
+    Chrome,
+}
+
+impl TraceFormat {
+    fn file_suffix(self) -> &'static str {
+        match self {
+            TraceFormat::OtlpJson => "otlp.json",
+            TraceFormat::Chrome => "chrome.json",
+        }
+    }
+}
+
+impl FromStr for TraceFormat {
+    type Err = MaccError;
+
+    fn from_str(value: &str) -> std::result::Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "otlp-json" | "otlp" => Ok(TraceFormat::OtlpJson),
+            "chrome" => Ok(TraceFormat::Chrome),
+            other => Err(MaccError::Validation(format!(
+                "Unknown trace format '{}' (expected otlp-json or chrome)",
+                other
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceSpan {
+    pub span_id: String,
+    pub parent_span_id: Option<String>,
+    pub name: String,
+    pub start_unix_nano: i64,
+    pub end_unix_nano: i64,
+    pub failed: bool,
+    pub attributes: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunTrace {
+    pub run_id: String,
+    pub trace_id: String,
+    /// Root span first, then each task followed by its phases.
+    pub spans: Vec<TraceSpan>,
+}
+
+struct TimedEvent<'a> {
+    nanos: i64,
+    event: &'a Value,
+}
+
+/// Most recent run id in the event log.
+pub fn latest_run_id(events: &[Value]) -> Option<String> {
+    events
+        .iter()
+        .rev()
+        .filter_map(|event| event.get("run_id").and_then(Value::as_str))
+        .find(|run_id| !run_id.is_empty())
+        .map(str::to_string)
+}
+
+/// Builds the span tree of `run_id` from the event log.
+pub fn build_run_trace(events: &[Value], run_id: &str) -> Result<RunTrace> {
+    let mut run_events = events
+        .iter()
+        .filter(|event| event.get("run_id").and_then(Value::as_str) == Some(run_id))
+        .filter_map(|event| {
+            let ts = event.get("ts").and_then(Value::as_str)?;
+            let nanos = chrono::DateTime::parse_from_rfc3339(ts)
+                .ok()?
+                .timestamp_nanos_opt()?;
+            Some(TimedEvent { nanos, event })
+        })
+        .collect::<Vec<_>>();
+    if run_events.is_empty() {
+        return Err(MaccError::Validation(format!(
+            "No events found for coordinator run '{}'",
+            run_id
+        )));
+    }
+    run_events.sort_by_key(|timed| {
+        (
+            timed.nanos,
+            timed.event.get("seq").and_then(Value::as_u64).unwrap_or(0),
+        )
+    });
+
+    let trace_id = hex_digest(run_id, 32);
+    let root_id = hex_digest(&format!("{}|run", run_id), 16);
+    let mut by_task: BTreeMap<&str, Vec<&TimedEvent>> = BTreeMap::new();
+    for timed in &run_events {
+        let task_id = timed
+            .event
+            .get("task_id")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        if !task_id.is_empty() && task_id != "-" {
+            by_task.entry(task_id).or_default().push(timed);
+        }
+    }
+
+    let mut spans = vec![TraceSpan {
+        span_id: root_id.clone(),
+        parent_span_id: None,
+        name: "coordinator.run".to_string(),
+        start_unix_nano: run_events[0].nanos,
+        end_unix_nano: run_events[run_events.len() - 1].nanos,
+        failed: false,
+        attributes: BTreeMap::from([
+            ("macc.run_id".to_string(), json!(run_id)),
+            ("macc.task_count".to_string(), json!(by_task.len())),
+            ("macc.event_count".to_string(), json!(run_events.len())),
+        ]),
+    }];
+
+    for (task_id, task_events) in &by_task {
+        let task_span_id = hex_digest(&format!("{}|task|{}", run_id, task_id), 16);
+        let mut phase_spans = phase_spans(run_id, task_id, &task_span_id, task_events);
+        let mut task_attributes = BTreeMap::from([("macc.task_id".to_string(), json!(task_id))]);
+        for (key, value) in summarize(task_events.iter().copied()) {
+            task_attributes.insert(key, value);
+        }
+        let start = phase_spans
+            .iter()
+            .map(|span| span.start_unix_nano)
+            .chain(task_events.first().map(|timed| timed.nanos))
+            .min()
+            .unwrap_or_default();
+        let end = task_events.last().map(|timed| timed.nanos).unwrap_or(start);
+        spans.push(TraceSpan {
+            span_id: task_span_id,
+            parent_span_id: Some(root_id.clone()),
+            name: format!("task {}", task_id),
+            start_unix_nano: start,
+            end_unix_nano: end,
+            failed: phase_spans.last().map(|span| span.failed).unwrap_or(false),
+            attributes: task_attributes,
+        });
+        spans.append(&mut phase_spans);
+    }
+    if let Some(start) = spans.iter().map(|span| span.start_unix_nano).min() {
+        spans[0].start_unix_nano = start;
+    }
+
+    Ok(RunTrace {
+        run_id: run_id.to_string(),
+        trace_id,
+        spans,
+    })
+}
+
+fn phase_spans(
+    run_id: &str,
+    task_id: &str,
+    parent: &str,
+    task_events: &[&TimedEvent],
+) -> Vec<TraceSpan> {
+    let mut by_phase: Vec<(&str, Vec<&TimedEvent>)> = Vec::new();
+    for timed in task_events {
+        let phase = timed
+            .event
+            .get("phase")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        if phase.is_empty() || phase == "-" {
+            continue;
+        }
+        match by_phase.iter_mut().find(|(name, _)| *name == phase) {
+            Some((_, events)) => events.push(timed),
+            None => by_phase.push((phase, vec![timed])),
+        }
+    }
+
+    let mut spans = Vec::new();
+    for (phase, events) in by_phase {
+        let timed_phases = events
+            .iter()
+            .filter(|timed| {
+                timed.event.get("type").and_then(Value::as_str) == Some(PHASE_DURATION_EVENT)
+            })
+            .collect::<Vec<_>>();
+        // One window per recorded phase run; phases without duration events get a single
+        // window from their first to their last event.
+        let windows = if timed_phases.is_empty() {
+            vec![(
+                events[0].nanos,
+                events[events.len() - 1].nanos,
+                events[events.len() - 1]
+                    .event
+                    .get("status")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+            )]
+        } else {
+            timed_phases
+                .iter()
+                .map(|timed| {
+                    let seconds = timed.event["payload"]["seconds"].as_f64().unwrap_or(0.0);
+                    (
+                        timed.nanos - (seconds * 1_000_000_000.0) as i64,
+                        timed.nanos,
+                        timed
+                            .event
+                            .get("status")
+                            .and_then(Value::as_str)
+                            .unwrap_or_default()
+                            .to_string(),
+                    )
+                })
+                .collect()
+        };
+        let mut previous_end = i64::MIN;
+        for (index, (start, end, status)) in windows.into_iter().enumerate() {
+            let in_window = events
+                .iter()
+                .copied()
+                .filter(|timed| timed.nanos > previous_end && timed.nanos <= end);
+            let mut attributes = summarize(in_window);
+            attributes.insert("macc.phase".to_string(), json!(phase));
+            if !status.is_empty() {
+                attributes.insert("macc.status".to_string(), json!(status));
+            }
+            attributes
+                .entry("macc.attempt".to_string())
+                .or_insert_with(|| json!(index + 1));
+            spans.push(TraceSpan {
+                span_id: hex_digest(
+                    &format!("{}|phase|{}|{}|{}", run_id, task_id, phase, index),
+                    16,
+                ),
+                parent_span_id: Some(parent.to_string()),
+                name: phase.to_string(),
+                start_unix_nano: start,
+                end_unix_nano: end,
+                failed: matches!(status.as_str(), "failed" | "error"),
+                attributes,
+            });
+            previous_end = end;
+        }
+    }
+    spans.sort_by_key(|span| span.start_unix_nano);
+    spans
+}
+
+/// Tool, highest attempt and last error code seen in `events`.
+fn summarize<'a>(events: impl Iterator<Item = &'a TimedEvent<'a>>) -> BTreeMap<String, Value> {
+    let mut attributes = BTreeMap::new();
+    for timed in events {
+        let payload = &timed.event["payload"];
+        if let Some(tool) = payload.get("tool").and_then(Value::as_str) {
+            attributes.insert("macc.tool".to_string(), json!(tool));
+        }
+        let attempt = payload
+            .get("attempt")
+            .or_else(|| timed.event.get("attempt"))
+            .and_then(Value::as_u64);
+        if let Some(attempt) = attempt {
+            let current = attributes
+                .get("macc.attempt")
+                .and_then(Value::as_u64)
+                .unwrap_or(0);
+            attributes.insert("macc.attempt".to_string(), json!(current.max(attempt)));
+        }
+        if let Some(code) = payload.get("error_code").and_then(Value::as_str) {
+            attributes.insert("macc.error_code".to_string(), json!(code));
+        }
+    }
+    attributes
+}
+
+impl RunTrace {
+    /// OTLP/JSON `ExportTraceServiceRequest` with one resource and scope.
+    pub fn to_otlp_json(&self) -> Value {
+        let spans = self
+            .spans
+            .iter()
+            .map(|span| {
+                let mut otlp = json!({
+                    "traceId": self.trace_id,
+                    "spanId": span.span_id,
+                    "name": span.name,
+                    "kind": 1,
+                    "startTimeUnixNano": span.start_unix_nano.to_string(),
+                    "endTimeUnixNano": span.end_unix_nano.to_string(),
+                    "attributes": span
+                        .attributes
+                        .iter()
+                        .map(|(key, value)| json!({"key": key, "value": otlp_value(value)}))
+                        .collect::<Vec<_>>(),
+                    "status": {"code": if span.failed { 2 } else { 1 }},
+                });
+                if let Some(parent) = &span.parent_span_id {
+                    otlp["parentSpanId"] = json!(parent);
+                }
+                otlp
+            })
+            .collect::<Vec<_>>();
+        json!({
+            "resourceSpans": [{
+                "resource": {"attributes": [
+                    {"key": "service.name", "value": {"stringValue": "macc-coordinator"}},
+                    {"key": "macc.run_id", "value": {"stringValue": self.run_id}},
+                ]},
+                "scopeSpans": [{
+                    "scope": {"name": "macc.coordinator"},
+                    "spans": spans,
+                }],
+            }],
+        })
+    }
+
+    /// Chrome trace event format: one thread per task, complete (`X`) events per span.
+    pub fn to_chrome(&self) -> Value {
+        let origin = self
+            .spans
+            .first()
+            .map(|span| span.start_unix_nano)
+            .unwrap_or_default();
+        let micros = |nanos: i64| (nanos - origin) / 1_000;
+        let mut threads: BTreeMap<&str, usize> = BTreeMap::new();
+        let mut trace_events = vec![json!({
+            "name": "process_name", "ph": "M", "pid": 1, "tid": 0,
+            "args": {"name": format!("macc coordinator {}", self.run_id)},
+        })];
+        for span in &self.spans {
+            // Root on thread 0, each task and its phases on their own thread.
+            let tid = if span.parent_span_id.is_none() {
+                0
+            } else {
+                let task_key = if span.parent_span_id.as_deref() == Some(self.root_span_id()) {
+                    span.span_id.as_str()
+                } else {
+                    span.parent_span_id.as_deref().unwrap_or_default()
+                };
+                let next = threads.len() + 1;
+                let tid = *threads.entry(task_key).or_insert(next);
+                if tid == next {
+                    trace_events.push(json!({
+                        "name": "thread_name", "ph": "M", "pid": 1, "tid": tid,
+                        "args": {"name": span.name},
+                    }));
+                }
+                tid
+            };
+            let mut args = span
+                .attributes
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect::<serde_json::Map<_, _>>();
+            if span.failed {
+                args.insert("error".to_string(), json!(true));
+            }
+            trace_events.push(json!({
+                "name": span.name,
+                "cat": if span.parent_span_id.is_none() { "run" } else { "task" },
+                "ph": "X",
+                "pid": 1,
+                "tid": tid,
+                "ts": micros(span.start_unix_nano),
+                "dur": (span.end_unix_nano - span.start_unix_nano).max(0) / 1_000,
+                "args": args,
+            }));
+        }
+        json!({"traceEvents": trace_events, "displayTimeUnit": "ms"})
+    }
+
+    fn root_span_id(&self) -> &str {
+        self.spans
+            .first()
+            .map(|span| span.span_id.as_str())
+            .unwrap_or_default()
+    }
+}
+
+fn otlp_value(value: &Value) -> Value {
+    match value {
+        Value::Number(n) if n.is_i64() || n.is_u64() => json!({"intValue": n.to_string()}),
+        Value::Number(n) => json!({"doubleValue": n.as_f64().unwrap_or_default()}),
+        Value::Bool(b) => json!({"boolValue": b}),
+        Value::String(s) => json!({"stringValue": s}),
+        other => json!({"stringValue": other.to_string()}),
+    }
+}
+
+fn hex_digest(input: &str, len: usize) -> String {
+    use sha2::{Digest, Sha256};
+    let mut hasher = Sha256::new();
+    hasher.update(input.as_bytes());
+    let mut hex = format!("{:x}", hasher.finalize());
+    hex.truncate(len);
+    hex
+}
+
+/// Writes the trace of `run_id` (default: latest run) and returns the path.
+pub fn export_run_trace(
+    repo_root: &Path,
+    run_id: Option<&str>,
+    format: TraceFormat,
+    out: Option<&Path>,
+) -> Result<PathBuf> {
+    let paths = ProjectPaths::from_root(repo_root);
+    let storage_paths = CoordinatorStoragePaths::from_project_paths(&paths);
+    let sqlite = SqliteStorage::new(storage_paths.clone());
+    let snapshot: CoordinatorSnapshot = if sqlite.has_snapshot_data()? {
+        sqlite.load_snapshot()?
+    } else {
+        JsonStorage::new(storage_paths).load_snapshot()?
+    };
+    let run_id = match run_id {
+        Some(run_id) => run_id.to_string(),
+        None => latest_run_id(&snapshot.events).ok_or_else(|| {
+            MaccError::Validation("No coordinator run found in the event log".into())
+        })?,
+    };
+    let trace = build_run_trace(&snapshot.events, &run_id)?;
+    let document = match format {
+        TraceFormat::OtlpJson => trace.to_otlp_json(),
+        TraceFormat::Chrome => trace.to_chrome(),
+    };
+    let path = match out {
+        Some(p) if p.is_absolute() => p.to_path_buf(),
+        Some(p) => repo_root.join(p),
+        None => repo_root
+            .join(".macc")
+            .join("log")
+            .join("coordinator")
+            .join(format!("trace-{}.{}", run_id, format.file_suffix())),
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| MaccError::Io {
+            path: parent.to_string_lossy().into(),
+            action: "create trace dir".into(),
+            source: e,
+        })?;
+    }
+    let body = serde_json::to_string_pretty(&document)
+        .map_err(|e| MaccError::Validation(format!("Failed to serialize trace: {}", e)))?;
+    std::fs::write(&path, body).map_err(|e| MaccError::Io {
+        path: path.to_string_lossy().into(),
+        action: "write coordinator trace".into(),
+        source: e,
+    })?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(seq: u64, ts: &str, kind: &str, task: &str, phase: &str, status: &str) -> Value {
+        json!({
+            "run_id": "run-1", "seq": seq, "ts": ts, "type": kind,
+            "task_id": task, "phase": phase, "status": status, "payload": {},
+        })
+    }
+
+    #[test]
+    fn test_build_run_trace_nests_tasks_and_phases() {
+        let mut started = event(1, "2026-01-01T10:00:00Z", "started", "T1", "dev", "started");
+        started["payload"]["tool"] = json!("tool-a");
+        let mut failed = event(
+            2,
+            "2026-01-01T10:01:00Z",
+            "phase_result",
+            "T1",
+            "dev",
+            "failed",
+        );
+        failed["payload"] = json!({"attempt": 2, "error_code": "E101"});
+        let mut dev = event(
+            3,
+            "2026-01-01T10:01:00Z",
+            "task_phase_duration_seconds",
+            "T1",
+            "dev",
+            "failed",
+        );
+        dev["payload"]["seconds"] = json!(60.0);
+        let mut review = event(
+            4,
+            "2026-01-01T10:03:00Z",
+            "task_phase_duration_seconds",
+            "T1",
+            "review",
+            "done",
+        );
+        review["payload"]["seconds"] = json!(30.0);
+        let events = vec![
+            event(
+                0,
+                "2026-01-01T09:59:59Z",
+                "command_start",
+                "-",
+                "run",
+                "started",
+            ),
+            started,
+            failed,
+            dev,
+            review,
+            event(5, "2026-01-01T10:05:00Z", "heartbeat", "T9", "", "running"),
+            json!({"run_id": "run-0", "ts": "2026-01-01T08:00:00Z", "type": "heartbeat"}),
+        ];
+
+        let trace = build_run_trace(&events, "run-1").unwrap();
+        assert_eq!(trace.trace_id.len(), 32);
+        let names = trace
+            .spans
+            .iter()
+            .map(|span| span.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec!["coordinator.run", "task T1", "dev", "review", "task T9"]
+        );
+        let root = &trace.spans[0];
+        assert_eq!(root.attributes["macc.task_count"], json!(2));
+        let dev = &trace.spans[2];
+        assert_eq!(
+            dev.parent_span_id.as_deref(),
+            Some(trace.spans[1].span_id.as_str())
+        );
+        assert!(dev.failed);
+        assert_eq!(dev.attributes["macc.tool"], json!("tool-a"));
+        assert_eq!(dev.attributes["macc.attempt"], json!(2));
+        assert_eq!(dev.attributes["macc.error_code"], json!("E101"));
+        assert_eq!(dev.end_unix_nano - dev.start_unix_nano, 60_000_000_000);
+        let review = &trace.spans[3];
+        assert_eq!(
+            review.end_unix_nano - review.start_unix_nano,
+            30_000_000_000
+        );
+        assert!(!review.failed);
+
+        let otlp = trace.to_otlp_json();
+        let spans = otlp["resourceSpans"][0]["scopeSpans"][0]["spans"]
+            .as_array()
+            .unwrap();
+        assert_eq!(spans.len(), 5);
+        assert!(spans[0].get("parentSpanId").is_none());
+        assert_eq!(spans[2]["status"]["code"], json!(2));
+
+        let chrome = trace.to_chrome();
+        let complete = chrome["traceEvents"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|e| e["ph"] == "X")
+            .collect::<Vec<_>>();
+        assert_eq!(complete.len(), 5);
+        assert_eq!(complete[0]["ts"], json!(0));
+        assert_eq!(complete[2]["dur"], json!(60_000_000));
+        assert_eq!(complete[1]["tid"], complete[2]["tid"]);
+
+        assert!(build_run_trace(&events, "run-missing").is_err());
+    }
+}
@@ -20,4 +20,8 @@ pub struct CoordinatorEnvConfig {
     pub storage_mode: Option<String>,
     pub error_code_retry_list: Option<String>,
     pub error_code_retry_max: Option<usize>,
+    /// Config profile (`--profile`) handed to the `macc` processes the coordinator spawns.
+    pub config_profile: Option<String>,
+    /// `host:port` the control plane serves OpenMetrics on (`--metrics-listen`).
+    pub metrics_listen: Option<String>,
 }
@@ -0,0 +1,268 @@
+//! Token and cost accounting from `token_usage` progress events.
+//!
+//! Performers and coordinator phases emit one `progress` event per tool call that reported
+//! usage (`payload.activity == "token_usage"`). The control plane folds them into
+//! `task_runtime.metrics` per task and tool; run totals are rolled up from the event log by
+//! `run_id`.
+
+use crate::config::TokenPrice;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct UsageTotals {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cached_input_tokens: u64,
+    pub cost_usd: f64,
+    pub calls: u64,
+}
+
+impl UsageTotals {
+    pub fn add(&mut self, other: &UsageTotals) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cached_input_tokens += other.cached_input_tokens;
+        self.cost_usd += other.cost_usd;
+        self.calls += other.calls;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.calls == 0
+    }
+
+    /// Reads totals stored by [`UsageTotals::to_value`] (missing fields count as zero).
+    pub fn from_value(value: &Value) -> Self {
+        Self {
+            input_tokens: value["input_tokens"].as_u64().unwrap_or(0),
+            output_tokens: value["output_tokens"].as_u64().unwrap_or(0),
+            cached_input_tokens: value["cached_input_tokens"].as_u64().unwrap_or(0),
+            cost_usd: value["cost_usd"].as_f64().unwrap_or(0.0),
+            calls: value["usage_calls"].as_u64().unwrap_or(0),
+        }
+    }
+
+    pub fn to_value(&self) -> Value {
+        json!({
+            "input_tokens": self.input_tokens,
+            "output_tokens": self.output_tokens,
+            "cached_input_tokens": self.cached_input_tokens,
+            "cost_usd": round_cost(self.cost_usd),
+            "usage_calls": self.calls,
+        })
+    }
+
+    /// One-line summary used by `coordinator status` and the TUI.
+    pub fn summary(&self) -> String {
+        format!(
+            "tokens in={} out={} cached={} cost=${:.4}",
+            self.input_tokens, self.output_tokens, self.cached_input_tokens, self.cost_usd
+        )
+    }
+}
+
+/// Usage reported by one tool call.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsageSample {
+    pub task_id: String,
+    pub tool: String,
+    pub run_id: Option<String>,
+    pub totals: UsageTotals,
+}
+
+/// Estimated cost in USD; tools without a price cost nothing.
+pub fn estimate_cost(
+    price: Option<&TokenPrice>,
+    input_tokens: u64,
+    output_tokens: u64,
+    cached_input_tokens: u64,
+) -> f64 {
+    let Some(price) = price else {
+        return 0.0;
+    };
+    let cached_price = price
+        .cached_input_per_million
+        .unwrap_or(price.input_per_million);
+    (input_tokens as f64 * price.input_per_million
+        + output_tokens as f64 * price.output_per_million
+        + cached_input_tokens as f64 * cached_price)
+        / 1_000_000.0
+}
+
+/// Usage carried by a coordinator event, priced with `prices`.
+pub fn usage_sample(event: &Value, prices: &BTreeMap<String, TokenPrice>) -> Option<UsageSample> {
+    let event_type = event
+        .get("type")
+        .or_else(|| event.get("event"))
+        .and_then(Value::as_str)?;
+    let payload = event.get("payload")?;
+    if event_type != "progress" || payload["activity"].as_str() != Some("token_usage") {
+        return None;
+    }
+    let task_id = event["task_id"]
+        .as_str()
+        .or_else(|| payload["task_id"].as_str())
+        .unwrap_or_default()
+        .to_string();
+    let tool = payload["tool"].as_str().unwrap_or("unknown").to_string();
+    let input_tokens = payload["input_tokens"].as_u64().unwrap_or(0);
+    let output_tokens = payload["output_tokens"].as_u64().unwrap_or(0);
+    let cached_input_tokens = payload["cached_input_tokens"].as_u64().unwrap_or(0);
+    Some(UsageSample {
+        totals: UsageTotals {
+            input_tokens,
+            output_tokens,
+            cached_input_tokens,
+            cost_usd: estimate_cost(
+                prices.get(&tool),
+                input_tokens,
+                output_tokens,
+                cached_input_tokens,
+            ),
+            calls: 1,
+        },
+        task_id,
+        tool,
+        run_id: event["run_id"].as_str().map(str::to_string),
+    })
+}
+
+/// Adds a sample to `task_runtime.metrics` (task totals plus `usage_by_tool.<tool>`).
+pub fn apply_usage_to_task(task: &mut Value, sample: &UsageSample) {
+    super::engine::ensure_runtime_object(task);
+    if !task["task_runtime"]["metrics"].is_object() {
+        task["task_runtime"]["metrics"] = json!({});
+    }
+    let metrics = &mut task["task_runtime"]["metrics"];
+    let mut totals = UsageTotals::from_value(metrics);
+    totals.add(&sample.totals);
+    for (key, value) in totals.to_value().as_object().into_iter().flatten() {
+        metrics[key] = value.clone();
+    }
+    if !metrics["usage_by_tool"].is_object() {
+        metrics["usage_by_tool"] = json!({});
+    }
+    let mut per_tool = UsageTotals::from_value(&metrics["usage_by_tool"][&sample.tool]);
+    per_tool.add(&sample.totals);
+    metrics["usage_by_tool"][&sample.tool] = per_tool.to_value();
+}
+
+/// Usage stored on a task by [`apply_usage_to_task`].
+pub fn task_usage(task: &Value) -> UsageTotals {
+    UsageTotals::from_value(&task["task_runtime"]["metrics"])
+}
+
+/// Totals per run id from an event log (events without a run id are grouped under `-`).
+pub fn run_usage(
+    events: &[Value],
+    prices: &BTreeMap<String, TokenPrice>,
+) -> BTreeMap<String, UsageTotals> {
+    let mut runs: BTreeMap<String, UsageTotals> = BTreeMap::new();
+    for sample in events
+        .iter()
+        .filter_map(|event| usage_sample(event, prices))
+    {
+        runs.entry(sample.run_id.unwrap_or_else(|| "-".to_string()))
+            .or_default()
+            .add(&sample.totals);
+    }
+    runs
+}
+
+/// Totals already recorded for `run_id` in the coordinator event history, so a coordinator
+/// restarted with the same run id keeps counting against its budget.
+pub fn recorded_run_usage(
+    repo_root: &Path,
+    run_id: &str,
+    prices: &BTreeMap<String, TokenPrice>,
+) -> UsageTotals {
+    match crate::coordinator::runs::load_live_snapshot(repo_root) {
+        Ok(snapshot) => run_usage(&snapshot.events, prices)
+            .remove(run_id)
+            .unwrap_or_default(),
+        Err(err) => {
+            tracing::warn!("Failed to read usage recorded for run {}: {}", run_id, err);
+            UsageTotals::default()
+        }
+    }
+}
+
+fn round_cost(cost: f64) -> f64 {
+    (cost * 1_000_000.0).round() / 1_000_000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage_event(run_id: &str, task_id: &str, tool: &str, input: u64, output: u64) -> Value {
+        json!({
+            "type": "progress",
+            "run_id": run_id,
+            "task_id": task_id,
+            "payload": {
+                "activity": "token_usage",
+                "tool": tool,
+                "input_tokens": input,
+                "output_tokens": output,
+                "cached_input_tokens": 0,
+            }
+        })
+    }
+
+    #[test]
+    fn test_usage_is_priced_and_rolled_up_per_task_and_run() {
+        let mut prices = BTreeMap::new();
+        prices.insert(
+            "tool-a".to_string(),
+            TokenPrice {
+                input_per_million: 2.0,
+                output_per_million: 10.0,
+                cached_input_per_million: None,
+            },
+        );
+        let events = vec![
+            usage_event("run-1", "T1", "tool-a", 500_000, 100_000),
+            usage_event("run-1", "T1", "tool-b", 1_000, 10),
+            usage_event("run-2", "T1", "tool-a", 1_000_000, 0),
+            json!({ "type": "heartbeat", "run_id": "run-1", "task_id": "T1" }),
+        ];
+
+        let mut task = json!({ "id": "T1", "task_runtime": { "metrics": { "retries": 1 } } });
+        for event in &events {
+            if let Some(sample) = usage_sample(event, &prices) {
+                apply_usage_to_task(&mut task, &sample);
+            }
+        }
+        let totals = task_usage(&task);
+        assert_eq!(totals.input_tokens, 1_501_000);
+        assert_eq!(totals.calls, 3);
+        assert!((totals.cost_usd - 4.0).abs() < 1e-9);
+        let metrics = &task["task_runtime"]["metrics"];
+        assert_eq!(metrics["retries"], 1);
+        assert_eq!(metrics["usage_by_tool"]["tool-b"]["cost_usd"], 0.0);
+        assert_eq!(metrics["usage_by_tool"]["tool-a"]["usage_calls"], 2);
+
+        let runs = run_usage(&events, &prices);
+        assert!((runs["run-1"].cost_usd - 2.0).abs() < 1e-9);
+        assert_eq!(runs["run-2"].input_tokens, 1_000_000);
+
+        let root = std::env::temp_dir().join(format!(
+            "macc_run_usage_{}",
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        let log_dir = root.join(".macc").join("log").join("coordinator");
+        std::fs::create_dir_all(&log_dir).unwrap();
+        let lines = events
+            .iter()
+            .map(|event| event.to_string() + "\n")
+            .collect::<String>();
+        std::fs::write(log_dir.join("events.jsonl"), lines).unwrap();
+        let recorded = recorded_run_usage(&root, "run-1", &prices);
+        assert_eq!(recorded.calls, 2);
+        assert!((recorded.cost_usd - 2.0).abs() < 1e-9);
+        assert!(recorded_run_usage(&root, "run-3", &prices).is_empty());
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
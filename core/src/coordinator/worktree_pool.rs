@@ -0,0 +1,409 @@
+//! Worktree pool preparation (`automation.coordinator.worktree`).
+//!
+//! Slots under `.macc/worktree/` can be created ahead of dispatch (`prewarm`), get a setup hook
+//! run once (tracked in `<slot>/.macc/worktree-setup.json`) and get dependency directories
+//! copied from `.macc/cache/worktree/` (reflinked where the filesystem supports it).
+
+use crate::config::WorktreePoolConfig;
+use crate::coordinator::helpers::{
+    append_coordinator_event_with_severity, build_non_task_worker_slug, count_pool_worktrees,
+    now_iso_coordinator,
+};
+use crate::{MaccError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+pub const WORKTREE_CACHE_REL_PATH: &str = ".macc/cache/worktree";
+
+const SETUP_MARKER_FILE: &str = "worktree-setup.json";
+const DEFAULT_SETUP_TIMEOUT_SECONDS: u64 = 900;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlotSetupMarker {
+    /// Slot created by pre-warming; reusable before any task ran in it.
+    #[serde(default)]
+    pub prewarmed: bool,
+    /// Setup command that completed in this slot.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub setup: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<String>,
+    #[serde(default)]
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SlotPreparation {
+    pub ran_setup: bool,
+    pub duration_ms: u64,
+    /// Shared directories copied into the slot from the cache.
+    pub copied_dirs: usize,
+}
+
+pub fn cache_dir(repo_root: &Path) -> PathBuf {
+    repo_root.join(WORKTREE_CACHE_REL_PATH)
+}
+
+pub fn read_slot_marker(slot: &Path) -> Option<SlotSetupMarker> {
+    let raw = std::fs::read_to_string(slot.join(".macc").join(SETUP_MARKER_FILE)).ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+fn write_slot_marker(slot: &Path, marker: &SlotSetupMarker) -> Result<()> {
+    let path = slot.join(".macc").join(SETUP_MARKER_FILE);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| MaccError::Io {
+            path: parent.to_string_lossy().into(),
+            action: "create worktree .macc directory".into(),
+            source: e,
+        })?;
+    }
+    let body = serde_json::to_string_pretty(marker).map_err(|e| {
+        MaccError::Validation(format!("Failed to serialize worktree setup marker: {}", e))
+    })?;
+    std::fs::write(&path, body).map_err(|e| MaccError::Io {
+        path: path.to_string_lossy().into(),
+        action: "write worktree setup marker".into(),
+        source: e,
+    })
+}
+
+pub fn is_prewarmed_slot(slot: &Path) -> bool {
+    read_slot_marker(slot).is_some_and(|marker| marker.prewarmed)
+}
+
+/// `env` with `{cache_dir}` expanded, for setup hooks, performers and phase tool calls.
+pub fn pool_env(repo_root: &Path, config: &WorktreePoolConfig) -> BTreeMap<String, String> {
+    let cache = cache_dir(repo_root);
+    config
+        .env
+        .iter()
+        .map(|(key, value)| {
+            (
+                key.clone(),
+                value.replace("{cache_dir}", &cache.to_string_lossy()),
+            )
+        })
+        .collect()
+}
+
+/// Copies shared directories into `slot`, runs the setup hook if it has not completed there yet,
+/// then seeds the cache from the slot for directories the cache lacks.
+pub async fn prepare_slot(
+    repo_root: &Path,
+    slot: &Path,
+    config: &WorktreePoolConfig,
+    task_id: &str,
+) -> Result<SlotPreparation> {
+    let cache = cache_dir(repo_root);
+    let mut outcome = SlotPreparation::default();
+    for dir in &config.shared_dirs {
+        let shared = cache.join(dir);
+        let target = slot.join(dir);
+        if shared.is_dir() && !target.exists() {
+            copy_tree(&shared, &target)?;
+            outcome.copied_dirs += 1;
+        }
+    }
+
+    let mut marker = read_slot_marker(slot).unwrap_or_default();
+    if let Some(command) = config
+        .setup
+        .as_deref()
+        .filter(|command| marker.setup.as_deref() != Some(*command))
+    {
+        let slot_name = slot
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or("worker");
+        let started = Instant::now();
+        let result = run_setup_hook(repo_root, slot, slot_name, command, config).await;
+        outcome.duration_ms = started.elapsed().as_millis() as u64;
+        if let Err(err) = result {
+            let _ = append_coordinator_event_with_severity(
+                repo_root,
+                "worktree_setup",
+                task_id,
+                "dev",
+                "failed",
+                &format!(
+                    "worktree setup failed slot={} duration_ms={}: {}",
+                    slot_name, outcome.duration_ms, err
+                ),
+                "warning",
+            );
+            return Err(err);
+        }
+        outcome.ran_setup = true;
+        marker.setup = Some(command.to_string());
+        marker.completed_at = Some(now_iso_coordinator());
+        marker.duration_ms = outcome.duration_ms;
+        write_slot_marker(slot, &marker)?;
+        let _ = append_coordinator_event_with_severity(
+            repo_root,
+            "worktree_setup",
+            task_id,
+            "dev",
+            "success",
+            &format!(
+                "worktree setup done slot={} duration_ms={} copied_dirs={}",
+                slot_name, outcome.duration_ms, outcome.copied_dirs
+            ),
+            "info",
+        );
+    }
+
+    for dir in &config.shared_dirs {
+        let source = slot.join(dir);
+        let shared = cache.join(dir);
+        if source.is_dir() && !shared.exists() {
+            let staging = cache.join(format!(
+                ".{}.tmp-{}",
+                dir.replace('/', "_"),
+                std::process::id()
+            ));
+            let _ = std::fs::remove_dir_all(&staging);
+            copy_tree(&source, &staging)?;
+            if let Some(parent) = shared.parent() {
+                let _ = std::fs::create_dir_all(parent);
+            }
+            if std::fs::rename(&staging, &shared).is_err() {
+                let _ = std::fs::remove_dir_all(&staging);
+            }
+        }
+    }
+    Ok(outcome)
+}
+
+async fn run_setup_hook(
+    repo_root: &Path,
+    slot: &Path,
+    slot_name: &str,
+    command: &str,
+    config: &WorktreePoolConfig,
+) -> Result<()> {
+    let log_dir = repo_root.join(".macc").join("log").join("coordinator");
+    std::fs::create_dir_all(&log_dir).map_err(|e| MaccError::Io {
+        path: log_dir.to_string_lossy().into(),
+        action: "create coordinator log dir".into(),
+        source: e,
+    })?;
+    let log_path = log_dir.join(format!("worktree-setup-{}.log", slot_name));
+    let log = std::fs::File::create(&log_path).map_err(|e| MaccError::Io {
+        path: log_path.to_string_lossy().into(),
+        action: "create worktree setup log".into(),
+        source: e,
+    })?;
+    let log_err = log.try_clone().map_err(|e| MaccError::Io {
+        path: log_path.to_string_lossy().into(),
+        action: "clone worktree setup log handle".into(),
+        source: e,
+    })?;
+    let mut child = tokio::process::Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .current_dir(slot)
+        .env("MACC_REPO_ROOT", repo_root)
+        .env("MACC_WORKTREE_PATH", slot)
+        .env("MACC_CACHE_DIR", cache_dir(repo_root))
+        .envs(pool_env(repo_root, config))
+        .stdin(std::process::Stdio::null())
+        .stdout(log)
+        .stderr(log_err)
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| MaccError::Io {
+            path: slot.to_string_lossy().into(),
+            action: "spawn worktree setup hook".into(),
+            source: e,
+        })?;
+    let timeout = config
+        .setup_timeout_seconds
+        .unwrap_or(DEFAULT_SETUP_TIMEOUT_SECONDS);
+    let status = match tokio::time::timeout(Duration::from_secs(timeout), child.wait()).await {
+        Ok(status) => status.map_err(|e| MaccError::Io {
+            path: slot.to_string_lossy().into(),
+            action: "wait for worktree setup hook".into(),
+            source: e,
+        })?,
+        Err(_) => {
+            let _ = child.kill().await;
+            return Err(MaccError::Validation(format!(
+                "worktree setup hook timed out after {}s (log: {})",
+                timeout,
+                log_path.display()
+            )));
+        }
+    };
+    if !status.success() {
+        return Err(MaccError::Validation(format!(
+            "worktree setup hook exited with {} (log: {})",
+            status,
+            log_path.display()
+        )));
+    }
+    Ok(())
+}
+
+/// Creates pool slots up to `max_parallel` and prepares them; returns how many were created.
+pub async fn prewarm_pool(
+    repo_root: &Path,
+    config: &WorktreePoolConfig,
+    max_parallel: usize,
+    tool: &str,
+    base_branch: &str,
+) -> Result<usize> {
+    if !config.prewarm || max_parallel == 0 {
+        return Ok(0);
+    }
+    let mut pool_count = count_pool_worktrees(repo_root)?;
+    let mut created = 0usize;
+    while pool_count < max_parallel {
+        let spec = crate::WorktreeCreateSpec {
+            slug: build_non_task_worker_slug(pool_count),
+            tool: tool.to_string(),
+            count: 1,
+            base: base_branch.to_string(),
+            dir: PathBuf::from(".macc/worktree"),
+            scope: None,
+            feature: None,
+        };
+        let slot = crate::create_worktrees(repo_root, &spec)?
+            .pop()
+            .ok_or_else(|| MaccError::Validation("No worktree created".into()))?;
+        write_slot_marker(
+            &slot.path,
+            &SlotSetupMarker {
+                prewarmed: true,
+                ..SlotSetupMarker::default()
+            },
+        )?;
+        pool_count += 1;
+        created += 1;
+        prepare_slot(repo_root, &slot.path, config, "-").await?;
+    }
+    Ok(created)
+}
+
+/// Recreates `src` under `dst` with copied files; returns the number of files. Slots never share
+/// an inode with the cache, so a tool writing into one slot cannot change the others. The copy
+/// goes through `copy_file_range`, so reflink-capable filesystems share extents copy-on-write.
+pub fn copy_tree(src: &Path, dst: &Path) -> Result<usize> {
+    let io_err = |path: &Path, action: &str, e: std::io::Error| MaccError::Io {
+        path: path.to_string_lossy().into(),
+        action: action.into(),
+        source: e,
+    };
+    std::fs::create_dir_all(dst).map_err(|e| io_err(dst, "create shared directory", e))?;
+    let mut files = 0usize;
+    let entries = std::fs::read_dir(src).map_err(|e| io_err(src, "read shared directory", e))?;
+    for entry in entries {
+        let entry = entry.map_err(|e| io_err(src, "read shared directory entry", e))?;
+        let source = entry.path();
+        let target = dst.join(entry.file_name());
+        let file_type = entry
+            .file_type()
+            .map_err(|e| io_err(&source, "read file type", e))?;
+        if file_type.is_dir() {
+            files += copy_tree(&source, &target)?;
+        } else if file_type.is_symlink() {
+            let link = std::fs::read_link(&source).map_err(|e| io_err(&source, "read link", e))?;
+            #[cfg(unix)]
+            std::os::unix::fs::symlink(&link, &target)
+                .map_err(|e| io_err(&target, "create symlink", e))?;
+            #[cfg(not(unix))]
+            let _ = link;
+        } else {
+            std::fs::copy(&source, &target).map_err(|e| io_err(&target, "copy file", e))?;
+            files += 1;
+        }
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_root(label: &str) -> PathBuf {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("macc_worktree_pool_{}_{}", label, nanos));
+        std::fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    #[test]
+    fn test_prepare_slot_runs_setup_once_and_shares_dirs() {
+        let root = temp_root("prepare");
+        let slot_a = root.join(".macc/worktree/worker-01");
+        let slot_b = root.join(".macc/worktree/worker-02");
+        std::fs::create_dir_all(&slot_a).unwrap();
+        std::fs::create_dir_all(&slot_b).unwrap();
+        let config = WorktreePoolConfig {
+            setup: Some(
+                "mkdir -p deps && echo x >> deps/lib.txt && echo run >> runs && \
+                 echo \"$POOL_TARGET\" > target-dir"
+                    .into(),
+            ),
+            shared_dirs: vec!["deps".into()],
+            env: BTreeMap::from([("POOL_TARGET".to_string(), "{cache_dir}/target".to_string())]),
+            ..WorktreePoolConfig::default()
+        };
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        let first = runtime
+            .block_on(prepare_slot(&root, &slot_a, &config, "T1"))
+            .unwrap();
+        assert!(first.ran_setup);
+        assert_eq!(first.copied_dirs, 0);
+        assert!(cache_dir(&root).join("deps/lib.txt").is_file());
+        assert_eq!(
+            std::fs::read_to_string(slot_a.join("target-dir")).unwrap(),
+            format!("{}/target\n", cache_dir(&root).display())
+        );
+        assert!(std::env::var("POOL_TARGET").is_err());
+        let again = runtime
+            .block_on(prepare_slot(&root, &slot_a, &config, "T1"))
+            .unwrap();
+        assert!(!again.ran_setup);
+        assert_eq!(
+            std::fs::read_to_string(slot_a.join("runs")).unwrap(),
+            "run\n"
+        );
+
+        let second = runtime
+            .block_on(prepare_slot(&root, &slot_b, &config, "T2"))
+            .unwrap();
+        assert_eq!(second.copied_dirs, 1);
+        // The hook appended to its own copy; the cache and the other slot are unchanged.
+        assert_eq!(
+            std::fs::read_to_string(slot_b.join("deps/lib.txt")).unwrap(),
+            "x\nx\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(slot_a.join("deps/lib.txt")).unwrap(),
+            "x\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(cache_dir(&root).join("deps/lib.txt")).unwrap(),
+            "x\n"
+        );
+
+        let failing = WorktreePoolConfig {
+            setup: Some("exit 3".into()),
+            ..WorktreePoolConfig::default()
+        };
+        assert!(runtime
+            .block_on(prepare_slot(&root, &slot_a, &failing, "T3"))
+            .is_err());
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
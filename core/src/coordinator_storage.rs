@@ -133,7 +133,7 @@ pub trait CoordinatorStorage {
     fn save_snapshot(&self, snapshot: &CoordinatorSnapshot) -> Result<()>;
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct TransitionMutation {
     pub task_id: String,
     pub new_state: String,
@@ -143,7 +143,7 @@ pub struct TransitionMutation {
     pub now: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct RuntimeMutation {
     pub task_id: String,
     pub runtime_status: String,
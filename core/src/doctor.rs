@@ -1,3 +1,4 @@
+use crate::config::SandboxConfig;
 use crate::tool::spec::{CheckSeverity, DoctorCheckKind, ToolSpec};
 use std::path::Path;
 use std::process::Command;
@@ -93,11 +94,60 @@ pub fn checks_for_enabled_tools(specs: &[ToolSpec]) -> Vec<ToolCheck> {
                 severity: CheckSeverity::Warning,
             });
         }
+        if let Some(sandbox) = spec.performer.as_ref().and_then(|p| p.sandbox.as_ref()) {
+            checks.extend(sandbox_check(&spec.id, &spec.display_name, sandbox));
+        }
     }
 
     checks
 }
 
+/// Checks that the binary behind a performer sandbox mode is installed.
+pub fn sandbox_check(
+    tool_id: &str,
+    display_name: &str,
+    sandbox: &SandboxConfig,
+) -> Option<ToolCheck> {
+    let binary = match sandbox.mode.as_str() {
+        "bwrap" => "bwrap",
+        "unshare" => "unshare",
+        _ => return None,
+    };
+    Some(ToolCheck {
+        name: format!("{} sandbox", display_name),
+        tool_id: Some(tool_id.to_string()),
+        check_target: binary.to_string(),
+        kind: DoctorCheckKind::Which,
+        status: ToolStatus::Missing,
+        severity: CheckSeverity::Error,
+    })
+}
+
+/// Reports the single-instance coordinator lease; a lease left by a dead owner is a warning.
+pub fn coordinator_lease_check(repo_root: &Path) -> ToolCheck {
+    use crate::coordinator::lease::{lease_path, lease_status, LeaseStatus};
+    let (status, target) = match lease_status(repo_root) {
+        Ok(LeaseStatus::Free) => (ToolStatus::Installed, "free".to_string()),
+        Ok(held @ LeaseStatus::Held(_)) => (ToolStatus::Installed, held.describe()),
+        Ok(stale @ LeaseStatus::Stale { .. }) => (
+            ToolStatus::Error("stale lease".to_string()),
+            stale.describe(),
+        ),
+        Err(err) => (
+            ToolStatus::Error("unreadable".to_string()),
+            format!("{}: {}", lease_path(repo_root).display(), err),
+        ),
+    };
+    ToolCheck {
+        name: "Coordinator lease".to_string(),
+        tool_id: None,
+        check_target: target,
+        kind: DoctorCheckKind::Custom,
+        status,
+        severity: CheckSeverity::Warning,
+    }
+}
+
 pub fn run_checks(checks: &mut [ToolCheck]) {
     let runner = SystemRunner;
     for check in checks {
@@ -164,6 +214,7 @@ mod tests {
             install: None,
             update: None,
             version_check: None,
+            rollback: None,
             defaults: None,
         };
 
@@ -154,10 +154,6 @@ pub fn ensure_tool_json(repo_root: &Path, worktree_path: &Path, tool_id: &str) -
     write_tool_json(repo_root, worktree_path, tool_id)
 }
 
-pub fn ensure_performer(worktree_path: &Path) -> Result<PathBuf> {
-    crate::ensure_performer(worktree_path)
-}
-
 pub fn resolve_worktree_task_context(
     repo_root: &Path,
     worktree_path: &Path,
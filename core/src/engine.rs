@@ -116,6 +116,14 @@ pub trait Engine {
         crate::service::tooling::show_outdated_tools(paths, only, reporter)
     }
 
+    fn tooling_history(
+        &self,
+        paths: &ProjectPaths,
+        tool_id: Option<&str>,
+    ) -> Result<Vec<crate::service::tooling::ToolUpdateRecord>> {
+        crate::service::tooling::read_tool_history(paths, tool_id)
+    }
+
     fn context_generate(
         &self,
         paths: &ProjectPaths,
@@ -229,6 +237,21 @@ pub trait Engine {
         crate::service::task_runner::worktree_run_task(paths, id)
     }
 
+    fn performer_request(
+        &self,
+        paths: &ProjectPaths,
+        worktree: &str,
+    ) -> Result<crate::performer::PerformerRequest> {
+        crate::service::task_runner::performer_request_for_worktree(paths, worktree)
+    }
+
+    fn performer_run(
+        &self,
+        request: &crate::performer::PerformerRequest,
+    ) -> Result<crate::performer::PerformerOutcome> {
+        crate::performer::run_performer(request)
+    }
+
     fn worktree_exec_task(&self, paths: &ProjectPaths, id: &str, cmd: &[String]) -> Result<()> {
         crate::service::task_runner::worktree_exec(paths, id, cmd)
     }
@@ -1229,7 +1252,21 @@ impl Engine for MaccEngine {
         let (specs, _) = loader.load_all_with_embedded();
 
         let mut checks = doctor::checks_for_enabled_tools(&specs);
+        let sandbox_overrides = crate::config::load_effective_config(paths)
+            .ok()
+            .and_then(|config| config.automation.coordinator)
+            .map(|coordinator| coordinator.sandbox)
+            .unwrap_or_default();
+        for (tool_id, sandbox) in &sandbox_overrides {
+            let name = specs
+                .iter()
+                .find(|spec| &spec.id == tool_id)
+                .map(|spec| spec.display_name.as_str())
+                .unwrap_or(tool_id);
+            checks.extend(doctor::sandbox_check(tool_id, name, sandbox));
+        }
         doctor::run_checks(&mut checks);
+        checks.push(doctor::coordinator_lease_check(&paths.root));
         checks
     }
 
@@ -1241,8 +1278,12 @@ impl Engine for MaccEngine {
         materialized_units: &[MaterializedFetchUnit],
         overrides: &CliOverrides,
     ) -> Result<ActionPlan> {
-        let resolved = resolve::resolve(config, overrides);
-        crate::build_plan(paths, &resolved, materialized_units, &self.registry)
+        let (config, sensitive_values) =
+            crate::config::interpolate::prepare_config_for_plan(paths, config)?;
+        let resolved = resolve::resolve(&config, overrides);
+        let mut plan = crate::build_plan(paths, &resolved, materialized_units, &self.registry)?;
+        plan.sensitive_values = sensitive_values;
+        Ok(plan)
     }
 
     /// Produces a list of deterministic operations from a plan, suitable for UI preview or diff view.
@@ -1367,6 +1408,7 @@ impl TestEngine {
             install: None,
             update: None,
             version_check: None,
+            rollback: None,
             defaults: None,
         };
 
@@ -1414,6 +1456,7 @@ impl TestEngine {
             install: None,
             update: None,
             version_check: None,
+            rollback: None,
             defaults: None,
         };
 
@@ -1501,8 +1544,12 @@ impl Engine for TestEngine {
         materialized_units: &[MaterializedFetchUnit],
         overrides: &CliOverrides,
     ) -> Result<ActionPlan> {
-        let resolved = resolve::resolve(config, overrides);
-        crate::build_plan(paths, &resolved, materialized_units, &self.registry)
+        let (config, sensitive_values) =
+            crate::config::interpolate::prepare_config_for_plan(paths, config)?;
+        let resolved = resolve::resolve(&config, overrides);
+        let mut plan = crate::build_plan(paths, &resolved, materialized_units, &self.registry)?;
+        plan.sensitive_values = sensitive_values;
+        Ok(plan)
     }
 
     /// Produces a list of deterministic operations.
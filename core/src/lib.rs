@@ -9,9 +9,12 @@ pub mod engine;
 pub mod git;
 pub use config::migrate;
 pub mod mcp_json;
+pub mod monorepo;
 pub mod packages;
+pub mod performer;
 pub mod plan;
 pub mod resolve;
+pub mod schema;
 pub mod security;
 pub mod service;
 pub mod skills;
@@ -20,10 +23,10 @@ pub mod tool;
 pub mod user_backup;
 pub mod worktree;
 
-pub use automation::{embedded_runner_path_for_ref, ensure_embedded_automation_scripts};
+pub use automation::ensure_embedded_automation_scripts;
 pub use catalog::{McpCatalog, McpEntry, Selector, SkillEntry, SkillsCatalog, Source, SourceKind};
 use chrono::Local;
-pub use config::load_canonical_config;
+pub use config::{load_canonical_config, load_effective_config};
 pub use engine::{Engine, MaccEngine, TestEngine};
 pub use resolve::{resolve, CliOverrides, ResolvedConfig};
 pub use security::Finding;
@@ -35,10 +38,10 @@ use thiserror::Error;
 pub use tool::{FieldKind, ToolAdapter, ToolDescriptor, ToolField, ToolRegistry};
 pub use user_backup::{find_user_home, UserBackupEntry, UserBackupManager, UserBackupReport};
 pub use worktree::{
-    collect_context_targets, create_worktrees, current_worktree, ensure_performer, list_worktrees,
-    prune_worktrees, read_worktree_metadata, remove_worktree, resolve_worktree_task_context,
-    sync_context_files_from_root, write_tool_json, WorktreeCreateResult, WorktreeCreateSpec,
-    WorktreeEntry, WorktreeMetadata,
+    collect_context_targets, create_worktrees, current_worktree, list_worktrees, prune_worktrees,
+    read_worktree_metadata, remove_worktree, resolve_worktree_task_context,
+    sync_context_files_from_root, tool_runtime_config, write_tool_json, WorktreeCreateResult,
+    WorktreeCreateSpec, WorktreeEntry, WorktreeMetadata,
 };
 
 #[derive(Error, Debug)]
@@ -151,6 +154,8 @@ pub struct ProjectPaths {
     pub tmp_dir: PathBuf,
     pub catalog_dir: PathBuf,
     pub cache_dir: PathBuf,
+    /// Config profile selected with `--profile`; `None` falls back to `MACC_PROFILE`.
+    pub profile: Option<String>,
 }
 
 impl ProjectPaths {
@@ -168,9 +173,15 @@ impl ProjectPaths {
             catalog_dir,
             cache_dir: macc_dir.join("cache"),
             macc_dir,
+            profile: None,
         }
     }
 
+    pub fn with_profile(mut self, profile: Option<String>) -> Self {
+        self.profile = profile;
+        self
+    }
+
     pub fn skills_catalog_path(&self) -> PathBuf {
         self.catalog_dir.join("skills.catalog.json")
     }
@@ -207,14 +218,6 @@ impl ProjectPaths {
         self.macc_dir.join("automation")
     }
 
-    pub fn automation_runner_dir(&self) -> PathBuf {
-        self.automation_dir().join("runners")
-    }
-
-    pub fn automation_performer_path(&self) -> PathBuf {
-        self.automation_dir().join("performer.sh")
-    }
-
     pub fn automation_coordinator_path(&self) -> PathBuf {
         self.automation_dir().join("coordinator.sh")
     }
@@ -227,11 +230,6 @@ impl ProjectPaths {
         self.automation_dir().join("hooks").join("ai-merge-fix.sh")
     }
 
-    pub fn automation_runner_path(&self, tool_id: &str) -> PathBuf {
-        self.automation_runner_dir()
-            .join(format!("{}.performer.sh", tool_id))
-    }
-
     pub fn managed_paths_state_path(&self) -> PathBuf {
         self.macc_dir.join("state").join("managed_paths.json")
     }
@@ -380,7 +378,6 @@ pub fn init(paths: &ProjectPaths, force: bool) -> Result<()> {
     let skills_dir = paths.macc_dir.join("skills");
     let project_catalog_dir = paths.project_catalog_dir();
     let automation_dir = paths.automation_dir();
-    let automation_runner_dir = paths.automation_runner_dir();
     let dirs_to_create = [
         &paths.macc_dir,
         &paths.backups_dir,
@@ -388,7 +385,6 @@ pub fn init(paths: &ProjectPaths, force: bool) -> Result<()> {
         &paths.catalog_dir,
         &project_catalog_dir,
         &automation_dir,
-        &automation_runner_dir,
         &skills_dir,
     ];
     let mut created_paths: Vec<String> = Vec::new();
@@ -493,7 +489,8 @@ pub fn plan(
     materialized_units: &[resolve::MaterializedFetchUnit],
     registry: &ToolRegistry,
 ) -> Result<()> {
-    let canonical = load_canonical_config(&paths.config_path)?;
+    let (canonical, sensitive_values) =
+        config::interpolate::prepare_config_for_plan(paths, &load_effective_config(paths)?)?;
     let allowed_tools = registry.list_ids();
     let overrides = if let Some(tools_csv) = tools {
         CliOverrides::from_tools_csv(tools_csv, &allowed_tools)?
@@ -509,7 +506,8 @@ pub fn plan(
         resolved.tools.enabled
     );
 
-    let total_plan = build_plan(paths, &resolved, materialized_units, registry)?;
+    let mut total_plan = build_plan(paths, &resolved, materialized_units, registry)?;
+    total_plan.sensitive_values = sensitive_values;
     preview_plan(&total_plan, paths)?;
 
     println!("Core: Total actions planned: {}", total_plan.actions.len());
@@ -681,6 +679,32 @@ pub fn validate_plan(plan: &plan::ActionPlan, allow_user_scope: bool) -> Result<
             )));
         }
 
+        // Values resolved from config references may only land in user-scope files.
+        if action.scope() == plan::Scope::Project {
+            let content = match action {
+                plan::Action::WriteFile { content, .. } => Some(content.clone()),
+                plan::Action::MergeJson { patch, .. } => serde_json::to_vec(patch).ok(),
+                _ => None,
+            };
+            if let Some(content) = content {
+                let findings = security::scan_sensitive_values(
+                    action.path(),
+                    &content,
+                    &plan.sensitive_values,
+                );
+                if !findings.is_empty() {
+                    return Err(MaccError::SecretDetected {
+                        path: action.path().to_string(),
+                        details: format!(
+                            "{} ({} occurrence(s)); resolved references are only allowed in user-scope files",
+                            security::secret_scan::SENSITIVE_VALUE_PATTERN,
+                            findings.len()
+                        ),
+                    });
+                }
+            }
+        }
+
         match action {
             plan::Action::WriteFile { path, content, .. } => {
                 // Scan for secrets
@@ -796,11 +820,12 @@ pub fn preview_plan(plan: &plan::ActionPlan, paths: &ProjectPaths) -> Result<()>
                         s = status
                     );
                 } else {
-                    let indented_diff = diff
-                        .lines()
-                        .map(|line| format!("      {}", line))
-                        .collect::<Vec<_>>()
-                        .join("\n");
+                    let indented_diff =
+                        security::redact_sensitive_values(&diff, &plan.sensitive_values)
+                            .lines()
+                            .map(|line| format!("      {}", line))
+                            .collect::<Vec<_>>()
+                            .join("\n");
                     println!(
                         "    [MERGE] {:?}{s} {}\n{}",
                         scope,
@@ -857,11 +882,12 @@ pub fn preview_plan(plan: &plan::ActionPlan, paths: &ProjectPaths) -> Result<()>
                         );
                     } else {
                         // Add indentation to diff
-                        let indented_diff = diff
-                            .lines()
-                            .map(|line| format!("      {}", line))
-                            .collect::<Vec<_>>()
-                            .join("\n");
+                        let indented_diff =
+                            security::redact_sensitive_values(&diff, &plan.sensitive_values)
+                                .lines()
+                                .map(|line| format!("      {}", line))
+                                .collect::<Vec<_>>()
+                                .join("\n");
                         println!(
                             "    [WRITE] {:?}{s} {}\n{}",
                             scope,
@@ -919,7 +945,8 @@ pub fn apply(
     allow_user_scope: bool,
     registry: &ToolRegistry,
 ) -> Result<ApplyReport> {
-    let canonical = load_canonical_config(&paths.config_path)?;
+    let (canonical, sensitive_values) =
+        config::interpolate::prepare_config_for_plan(paths, &load_effective_config(paths)?)?;
     let allowed_tools = registry.list_ids();
     let overrides = if let Some(tools_csv) = tools {
         CliOverrides::from_tools_csv(tools_csv, &allowed_tools)?
@@ -946,6 +973,7 @@ pub fn apply(
     );
 
     let mut total_plan = build_plan(paths, &resolved, materialized_units, registry)?;
+    total_plan.sensitive_values = sensitive_values;
 
     // Pre-flight check before any side effects
     validate_plan(&total_plan, allow_user_scope)?;
@@ -1141,7 +1169,7 @@ where
 }
 
 fn load_protected_context_paths(paths: &ProjectPaths) -> Result<HashSet<String>> {
-    let canonical = load_canonical_config(&paths.config_path)?;
+    let canonical = load_effective_config(paths)?;
     let loader = crate::tool::ToolSpecLoader::new(
         crate::tool::ToolSpecLoader::default_search_paths(&paths.root),
     );
@@ -2158,6 +2186,37 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_validate_plan_rejects_resolved_reference_in_project_scope() -> Result<()> {
+        let resolved = format!("resolved-ref-{}", uuid_v4_like());
+
+        let mut plan = plan::ActionPlan::new();
+        plan.sensitive_values.insert(resolved.clone());
+        plan.add_action(plan::Action::WriteFile {
+            path: "NOTES.md".into(),
+            content: format!("owner: {}\n", resolved).into_bytes(),
+            scope: plan::Scope::Project,
+        });
+        let result = validate_plan(&plan, true);
+        match result {
+            Err(MaccError::SecretDetected { path, details }) => {
+                assert_eq!(path, "NOTES.md");
+                assert!(!details.contains(&resolved));
+            }
+            other => panic!("Expected SecretDetected error, got {:?}", other),
+        }
+
+        let mut user_plan = plan::ActionPlan::new();
+        user_plan.sensitive_values.insert(resolved.clone());
+        user_plan.add_action(plan::Action::MergeJson {
+            path: "/tmp/user-settings.json".into(),
+            patch: serde_json::json!({ "token": resolved }),
+            scope: plan::Scope::User,
+        });
+        validate_plan(&user_plan, true)?;
+        Ok(())
+    }
+
     #[test]
     fn test_is_sensitive_file() {
         assert!(is_sensitive_file(".env"));
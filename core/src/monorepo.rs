@@ -0,0 +1,189 @@
+//! Nested MACC projects inside one git repository.
+//!
+//! A sub-project is any directory below the repository root with its own `.macc/macc.yaml`.
+//! It inherits every ancestor project's config (outermost first) up to the repository root.
+
+use crate::ProjectPaths;
+use std::path::{Component, Path, PathBuf};
+
+/// Directory names never descended into while discovering sub-projects.
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", "vendor", "dist", "build"];
+
+fn has_project_config(dir: &Path) -> bool {
+    dir.join(".macc").join("macc.yaml").is_file()
+}
+
+/// Nearest ancestor of `start` (inclusive) that contains `.git` (directory or worktree file).
+pub fn repository_root(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .map(Path::to_path_buf)
+}
+
+/// Roots of the projects `paths` inherits from, outermost first.
+///
+/// Inheritance never crosses the repository root, and nothing is inherited outside a git
+/// repository, so a stray `~/.macc/macc.yaml` is never picked up.
+pub fn parent_project_roots(paths: &ProjectPaths) -> Vec<PathBuf> {
+    let Some(repo_root) = repository_root(&paths.root) else {
+        return Vec::new();
+    };
+    if paths.root == repo_root {
+        return Vec::new();
+    }
+    let mut parents: Vec<PathBuf> = paths
+        .root
+        .ancestors()
+        .skip(1)
+        .take_while(|dir| dir.starts_with(&repo_root))
+        .filter(|dir| has_project_config(dir))
+        .map(Path::to_path_buf)
+        .collect();
+    parents.reverse();
+    parents
+}
+
+/// All MACC projects at or below `root`, parents before children.
+///
+/// Hidden directories (including `.macc/worktree`) and common build/dependency
+/// directories are skipped.
+pub fn discover_projects(root: &Path) -> Vec<ProjectPaths> {
+    let mut found = Vec::new();
+    let mut stack = vec![root.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        if has_project_config(&dir) {
+            found.push(dir.clone());
+        }
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.filter_map(|e| e.ok()) {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if !file_type.is_dir() {
+                continue;
+            }
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if name.starts_with('.') || SKIPPED_DIRS.contains(&name.as_ref()) {
+                continue;
+            }
+            stack.push(entry.path());
+        }
+    }
+    found.sort();
+    found.into_iter().map(ProjectPaths::from_root).collect()
+}
+
+/// Top-level directory to walk for `--all`: the repository root, or the outermost project.
+pub fn projects_walk_root(paths: &ProjectPaths) -> PathBuf {
+    repository_root(&paths.root)
+        .or_else(|| parent_project_roots(paths).into_iter().next())
+        .unwrap_or_else(|| paths.root.clone())
+}
+
+/// Path of `project_root` relative to `repo_root`, using `.` for the root itself.
+pub fn relative_project_path(repo_root: &Path, project_root: &Path) -> String {
+    match project_root.strip_prefix(repo_root) {
+        Ok(rel) if rel.as_os_str().is_empty() => ".".to_string(),
+        Ok(rel) => rel.to_string_lossy().replace('\\', "/"),
+        Err(_) => project_root.to_string_lossy().to_string(),
+    }
+}
+
+/// Validates a task `project` value: a relative path without `..` components.
+pub fn normalize_project_path(raw: &str) -> Option<PathBuf> {
+    let trimmed = raw.trim().trim_end_matches('/');
+    if trimmed.is_empty() || trimmed == "." {
+        return None;
+    }
+    let path = Path::new(trimmed);
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => normalized.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    if normalized.as_os_str().is_empty() {
+        None
+    } else {
+        Some(normalized)
+    }
+}
+
+/// Directory a performer should work in for `task`: `<worktree>/<task.project>` when the
+/// task is scoped to an existing sub-project, the worktree root otherwise.
+pub fn task_project_dir(worktree: &Path, task: &serde_json::Value) -> PathBuf {
+    task.get("project")
+        .and_then(serde_json::Value::as_str)
+        .and_then(normalize_project_path)
+        .map(|rel| worktree.join(rel))
+        .filter(|dir| dir.is_dir())
+        .unwrap_or_else(|| worktree.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn temp_repo() -> PathBuf {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("macc_monorepo_{}", nanos));
+        fs::create_dir_all(root.join(".git")).unwrap();
+        for dir in [
+            ".",
+            "services/api",
+            "web",
+            "web/node_modules/pkg",
+            ".macc/worktree/w1",
+        ] {
+            let macc = root.join(dir).join(".macc");
+            fs::create_dir_all(&macc).unwrap();
+            fs::write(macc.join("macc.yaml"), "tools:\n  enabled: []\n").unwrap();
+        }
+        fs::create_dir_all(root.join("services/api/src")).unwrap();
+        root
+    }
+
+    #[test]
+    fn test_discover_projects_skips_hidden_and_dependency_dirs() {
+        let root = temp_repo();
+        let found: Vec<String> = discover_projects(&root)
+            .iter()
+            .map(|p| relative_project_path(&root, &p.root))
+            .collect();
+        assert_eq!(found, vec![".", "services/api", "web"]);
+        fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_parent_project_roots_stop_at_repository_root() {
+        let root = temp_repo();
+        let api = ProjectPaths::from_root(root.join("services/api"));
+        assert_eq!(parent_project_roots(&api), vec![root.clone()]);
+        assert!(parent_project_roots(&ProjectPaths::from_root(&root)).is_empty());
+        fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_task_project_dir_rejects_escaping_paths() {
+        let root = temp_repo();
+        let task = |project: &str| serde_json::json!({ "id": "T1", "project": project });
+        assert_eq!(
+            task_project_dir(&root, &task("services/api")),
+            root.join("services/api")
+        );
+        assert_eq!(task_project_dir(&root, &task("../outside")), root);
+        assert_eq!(task_project_dir(&root, &task("missing")), root);
+        assert_eq!(task_project_dir(&root, &serde_json::json!({})), root);
+        fs::remove_dir_all(&root).ok();
+    }
+}
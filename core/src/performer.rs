@@ -0,0 +1,1097 @@
+//! Native worktree performer.
+//!
+//! Works through the pending tasks of a worktree PRD: builds a prompt per task, calls the
+//! tool described by the worktree's `.macc/tool.json` (the ToolSpec `performer` section),
+//! retries failed calls, marks tasks as passed and commits the result. Progress is reported
+//! as typed [`CoordinatorEvent`]s appended to the coordinator event log.
+
+pub mod invoke;
+pub mod limits;
+pub mod output;
+pub mod sandbox;
+pub mod session;
+pub mod throttle;
+
+use crate::coordinator::CoordinatorEvent;
+use crate::tool::ToolRuntimeConfig;
+use crate::{MaccError, Result};
+use serde_json::{json, Value};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+pub use invoke::{invoke_tool, ToolCallResult, ToolInvocation};
+pub use session::SessionStore;
+
+/// Files the performer never commits.
+const PROTECTED_FILES: &[&str] = &["performer.sh", "worktree.prd.json"];
+
+/// Loop and retry limits, overridable through `PERFORMER_*` environment variables.
+#[derive(Debug, Clone)]
+pub struct PerformerLimits {
+    pub max_iterations: usize,
+    pub tool_max_attempts: usize,
+    pub sleep_between_tasks: Duration,
+    pub heartbeat_interval: Duration,
+    pub session_lease_ttl_seconds: u64,
+    /// Process resource limits set by the coordinator (`MACC_LIMIT_*`).
+    pub resources: limits::PerformerResourceLimits,
+}
+
+impl Default for PerformerLimits {
+    fn default() -> Self {
+        Self {
+            max_iterations: 50,
+            tool_max_attempts: 2,
+            sleep_between_tasks: Duration::from_secs(2),
+            heartbeat_interval: Duration::from_secs(2),
+            session_lease_ttl_seconds: 1800,
+            resources: limits::PerformerResourceLimits::default(),
+        }
+    }
+}
+
+impl PerformerLimits {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.trim().parse::<u64>().ok())
+        };
+        Self {
+            max_iterations: var("PERFORMER_MAX_ITERATIONS")
+                .map(|v| v as usize)
+                .unwrap_or(defaults.max_iterations),
+            tool_max_attempts: var("PERFORMER_TOOL_MAX_ATTEMPTS")
+                .map(|v| v.max(1) as usize)
+                .unwrap_or(defaults.tool_max_attempts),
+            sleep_between_tasks: var("PERFORMER_SLEEP_SECONDS")
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.sleep_between_tasks),
+            heartbeat_interval: defaults.heartbeat_interval,
+            session_lease_ttl_seconds: var("SESSION_LEASE_TTL_SECONDS")
+                .unwrap_or(defaults.session_lease_ttl_seconds),
+            resources: limits::PerformerResourceLimits::from_env(),
+        }
+    }
+}
+
+/// Everything a performer run needs.
+#[derive(Debug, Clone)]
+pub struct PerformerRequest {
+    pub repo: PathBuf,
+    pub worktree: PathBuf,
+    pub task_id: String,
+    pub tool: String,
+    pub prd: PathBuf,
+    /// Coordinator `events.jsonl`; no events are written when unset.
+    pub events_file: Option<PathBuf>,
+    pub run_id: String,
+    pub event_source: String,
+    pub limits: PerformerLimits,
+}
+
+impl PerformerRequest {
+    /// Request with event settings taken from `COORD_EVENTS_FILE`, `COORDINATOR_RUN_ID`,
+    /// `MACC_EVENT_SOURCE` and limits from the environment.
+    pub fn from_env(
+        repo: PathBuf,
+        worktree: PathBuf,
+        task_id: String,
+        tool: String,
+        prd: PathBuf,
+    ) -> Self {
+        let env = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+        let run_id = env("COORDINATOR_RUN_ID").unwrap_or_else(|| {
+            format!(
+                "{}-{}",
+                chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default(),
+                std::process::id()
+            )
+        });
+        let event_source =
+            env("MACC_EVENT_SOURCE").unwrap_or_else(|| format!("performer:{}:{}", tool, run_id));
+        let task_id = env("MACC_EVENT_TASK_ID").unwrap_or(task_id);
+        Self {
+            repo,
+            worktree,
+            task_id,
+            tool,
+            prd,
+            events_file: env("COORD_EVENTS_FILE").map(PathBuf::from),
+            run_id,
+            event_source,
+            limits: PerformerLimits::from_env(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PerformerOutcome {
+    pub completed_tasks: Vec<String>,
+    pub commit: Option<String>,
+}
+
+/// A performer failure with the stable error code reported in `failed` events.
+#[derive(Debug, Clone)]
+pub struct PerformerFailure {
+    pub code: &'static str,
+    pub origin: &'static str,
+    pub message: String,
+    /// Provider-advertised wait for throttling failures (`E104`).
+    pub retry_after_seconds: Option<u64>,
+}
+
+impl PerformerFailure {
+    fn new(code: &'static str, origin: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            origin,
+            message: message.into(),
+            retry_after_seconds: None,
+        }
+    }
+
+    fn payload(&self) -> Value {
+        let mut payload = json!({
+            "exit_code": 1,
+            "error_code": self.code,
+            "origin": self.origin,
+            "message": self.message,
+        });
+        if let Some(seconds) = self.retry_after_seconds {
+            payload["retry_after_seconds"] = json!(seconds);
+        }
+        payload
+    }
+}
+
+impl From<PerformerFailure> for MaccError {
+    fn from(failure: PerformerFailure) -> Self {
+        MaccError::Validation(format!("{}: {}", failure.code, failure.message))
+    }
+}
+
+/// Appends typed events with a per-run sequence number.
+pub struct EventSink {
+    file: Option<PathBuf>,
+    run_id: Option<String>,
+    source: String,
+    task_id: String,
+    phase: String,
+    tool: Option<String>,
+    seq: AtomicU64,
+    write_lock: Mutex<()>,
+}
+
+impl EventSink {
+    pub fn new(
+        file: Option<PathBuf>,
+        run_id: Option<String>,
+        source: impl Into<String>,
+        task_id: impl Into<String>,
+        phase: impl Into<String>,
+    ) -> Self {
+        Self {
+            file,
+            run_id,
+            source: source.into(),
+            task_id: task_id.into(),
+            phase: phase.into(),
+            tool: None,
+            seq: AtomicU64::new(0),
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    /// Sink for a performer run (`dev` phase).
+    pub fn for_request(request: &PerformerRequest) -> Self {
+        Self::new(
+            request.events_file.clone(),
+            Some(request.run_id.clone()),
+            request.event_source.clone(),
+            request.task_id.clone(),
+            "dev",
+        )
+        .with_tool(request.tool.clone())
+    }
+
+    /// Tags activity events with the tool that produced them (used for usage accounting).
+    pub fn with_tool(mut self, tool: impl Into<String>) -> Self {
+        self.tool = Some(tool.into());
+        self
+    }
+
+    /// Emits one event per activity parsed from a tool output line.
+    pub fn emit_output_activity(&self, format: output::OutputFormat, line: &str) {
+        for activity in output::parse_line(format, line) {
+            self.emit_activity(&activity);
+        }
+    }
+
+    pub fn emit_activity(&self, activity: &output::OutputActivity) {
+        let mut payload = activity.payload();
+        payload["task_id"] = json!(self.task_id);
+        if let Some(tool) = &self.tool {
+            payload["tool"] = json!(tool);
+        }
+        self.emit(activity.event_type(), "running", payload);
+    }
+
+    pub fn emit(&self, event_type: &str, status: &str, payload: Value) {
+        let Some(file) = &self.file else {
+            return;
+        };
+        let seq = self.seq.fetch_add(1, Ordering::SeqCst) + 1;
+        let now = chrono::Utc::now();
+        let event = CoordinatorEvent {
+            schema_version: "1".into(),
+            event_id: format!(
+                "{}-{}-{}",
+                self.task_id,
+                seq,
+                now.timestamp_nanos_opt().unwrap_or_default()
+            ),
+            run_id: self.run_id.clone(),
+            seq,
+            ts: now.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+            source: self.source.clone(),
+            task_id: Some(self.task_id.clone()),
+            event_type: event_type.to_string(),
+            phase: Some(self.phase.clone()),
+            status: status.to_string(),
+            payload,
+        };
+        let Ok(mut value) = serde_json::to_value(&event) else {
+            return;
+        };
+        // Legacy readers still look at `event`/`state`.
+        value["event"] = json!(event_type);
+        value["state"] = json!(status);
+        let _guard = self.write_lock.lock();
+        if let Some(parent) = file.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        if let Ok(mut out) = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(file)
+        {
+            let _ = writeln!(out, "{}", value);
+        }
+    }
+}
+
+struct Heartbeat {
+    stop: Arc<AtomicBool>,
+    handle: Option<std::thread::JoinHandle<()>>,
+}
+
+impl Heartbeat {
+    fn start(events: Arc<EventSink>, interval: Duration) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let flag = stop.clone();
+        let handle = std::thread::spawn(move || {
+            let tick = Duration::from_millis(100).min(interval);
+            let mut elapsed = interval;
+            while !flag.load(Ordering::SeqCst) {
+                if elapsed >= interval {
+                    events.emit("heartbeat", "running", json!({}));
+                    elapsed = Duration::ZERO;
+                }
+                std::thread::sleep(tick);
+                elapsed += tick;
+            }
+        });
+        Self {
+            stop,
+            handle: Some(handle),
+        }
+    }
+}
+
+impl Drop for Heartbeat {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Runs the performer to completion and emits a terminal `phase_result` or `failed` event.
+pub fn run_performer(request: &PerformerRequest) -> Result<PerformerOutcome> {
+    let events = Arc::new(EventSink::for_request(request));
+    let result = Performer::new(request, events.clone()).and_then(|performer| performer.run());
+    match result {
+        Ok(outcome) => Ok(outcome),
+        Err(failure) => {
+            events.emit("failed", "failed", failure.payload());
+            Err(failure.into())
+        }
+    }
+}
+
+/// `automation.coordinator.sandbox.<tool>` from the project config, which takes precedence over
+/// the ToolSpec `performer.sandbox`.
+pub(crate) fn coordinator_sandbox_override(
+    repo: &Path,
+    tool_id: &str,
+) -> Option<crate::config::SandboxConfig> {
+    crate::config::load_effective_config(&crate::ProjectPaths::from_root(repo))
+        .ok()?
+        .automation
+        .coordinator?
+        .sandbox
+        .remove(tool_id)
+}
+
+struct Performer<'a> {
+    request: &'a PerformerRequest,
+    runtime: ToolRuntimeConfig,
+    events: Arc<EventSink>,
+    log_dir: PathBuf,
+}
+
+impl<'a> Performer<'a> {
+    fn new(
+        request: &'a PerformerRequest,
+        events: Arc<EventSink>,
+    ) -> std::result::Result<Self, PerformerFailure> {
+        if !request.worktree.is_dir() {
+            return Err(PerformerFailure::new(
+                "E301",
+                "performer",
+                format!(
+                    "worktree path does not exist: {}",
+                    request.worktree.display()
+                ),
+            ));
+        }
+        if !request.prd.is_file() {
+            return Err(PerformerFailure::new(
+                "E302",
+                "performer",
+                format!("PRD file not found: {}", request.prd.display()),
+            ));
+        }
+        let tool_json = request.worktree.join(".macc").join("tool.json");
+        let runtime = std::fs::read_to_string(&tool_json)
+            .map_err(|_| {
+                PerformerFailure::new(
+                    "E303",
+                    "performer",
+                    format!("tool.json not found in worktree: {}", tool_json.display()),
+                )
+            })
+            .and_then(|raw| {
+                serde_json::from_str::<ToolRuntimeConfig>(&raw).map_err(|e| {
+                    PerformerFailure::new(
+                        "E303",
+                        "performer",
+                        format!("invalid tool.json {}: {}", tool_json.display(), e),
+                    )
+                })
+            })?;
+        let mut runtime = runtime;
+        if let Some(sandbox) = coordinator_sandbox_override(&request.repo, &runtime.id) {
+            runtime.performer.sandbox = Some(sandbox);
+        }
+        Ok(Self {
+            log_dir: request.worktree.join(".macc").join("log").join("performer"),
+            request,
+            runtime,
+            events,
+        })
+    }
+
+    fn run(&self) -> std::result::Result<PerformerOutcome, PerformerFailure> {
+        let request = self.request;
+        self.events.emit(
+            "started",
+            "started",
+            json!({ "tool": request.tool, "worktree": request.worktree.to_string_lossy() }),
+        );
+        let _heartbeat = Heartbeat::start(self.events.clone(), request.limits.heartbeat_interval);
+        let mut outcome = PerformerOutcome::default();
+
+        for _ in 0..request.limits.max_iterations {
+            let prd = self.read_prd()?;
+            let Some(task) = pending_tasks(&prd).into_iter().next() else {
+                outcome.commit = self.commit_changes(outcome.completed_tasks.last())?;
+                self.events.emit("phase_result", "done", json!({}));
+                return Ok(outcome);
+            };
+            let id = task_id_of(&task);
+            self.run_task(&task, &id)?;
+            self.mark_task_passed(&id)?;
+            self.log(
+                &id,
+                &format!(
+                    "- Marked as passed in worktree PRD: {}\n- Completed: {}\n",
+                    id,
+                    now_iso()
+                ),
+            );
+            outcome.completed_tasks.push(id.clone());
+
+            if pending_tasks(&self.read_prd()?).is_empty() {
+                outcome.commit = self.commit_changes(Some(&id))?;
+                self.events.emit(
+                    "phase_result",
+                    "done",
+                    json!({ "task_id": id, "final": true }),
+                );
+                return Ok(outcome);
+            }
+            std::thread::sleep(request.limits.sleep_between_tasks);
+        }
+        Err(PerformerFailure::new(
+            "E101",
+            "performer",
+            format!("max iterations reached ({})", request.limits.max_iterations),
+        ))
+    }
+
+    fn run_task(&self, task: &Value, id: &str) -> std::result::Result<(), PerformerFailure> {
+        let request = self.request;
+        let title = task.get("title").and_then(Value::as_str).unwrap_or("");
+        let task_dir = crate::monorepo::task_project_dir(&request.worktree, task);
+        let log_path = self.log_path(id);
+        if !log_path.exists() {
+            self.log(
+                id,
+                &format!(
+                    "# Performer log for task {}\n\n- Tool: {}\n- Worktree: {}\n- PRD: {}\n",
+                    id,
+                    request.tool,
+                    request.worktree.display(),
+                    request.prd.display()
+                ),
+            );
+        }
+        self.log(
+            id,
+            &format!(
+                "## Processing task {}\n\n- Title: {}\n- Started: {}\n",
+                id,
+                title,
+                now_iso()
+            ),
+        );
+        tracing::info!("Performer: task {} ({})", id, request.tool);
+        self.events.emit(
+            "progress",
+            "running",
+            json!({ "task_id": id, "title": title }),
+        );
+
+        let prompt = self.build_prompt(task, id, title, &task_dir);
+        self.log(id, &format!("### Prompt\n\n```text\n{}```\n", prompt));
+
+        let max_attempts = request.limits.tool_max_attempts.max(1);
+        let output_format = output::OutputFormat::of(&self.runtime.performer);
+        let mut last_failure = None;
+        for attempt in 1..=max_attempts {
+            self.log(
+                id,
+                &format!(
+                    "## Attempt {}/{}\n\n- Command: `{}`\n- Started: {}\n\n```text",
+                    attempt,
+                    max_attempts,
+                    self.runtime.performer.command,
+                    now_iso()
+                ),
+            );
+            self.events.emit(
+                "progress",
+                "running",
+                json!({ "attempt": attempt, "max_attempts": max_attempts }),
+            );
+            let invocation = ToolInvocation {
+                tool_id: &self.runtime.id,
+                spec: &self.runtime.performer,
+                repo: &request.repo,
+                worktree: &request.worktree,
+                cwd: &task_dir,
+                task_id: &request.task_id,
+                attempt,
+                lease_ttl_seconds: request.limits.session_lease_ttl_seconds,
+                limits: None,
+                env: None,
+            };
+            let log_path = self.log_path(id);
+            let mut log_file = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&log_path)
+                .ok();
+            let resources = &request.limits.resources;
+            let usage_before = limits::children_usage();
+            let cgroup_before = resources.cgroup_events();
+            let result = invoke_tool(&invocation, &prompt, &mut |line| {
+                if let Some(file) = log_file.as_mut() {
+                    let _ = writeln!(file, "{}", line);
+                }
+                self.events.emit_output_activity(output_format, line);
+            });
+            drop(log_file);
+            let mut payload = limits::children_usage().payload_since(&usage_before);
+            payload["task_id"] = json!(id);
+            payload["tool"] = json!(request.tool);
+            self.events.emit("progress", "running", payload);
+            if let (Ok(call), Some(usage)) = (&result, &self.runtime.performer.usage) {
+                if let Some(activity) = output::usage_from_text(usage, &call.output) {
+                    self.events.emit_activity(&activity);
+                }
+            }
+            let failure = match result {
+                Ok(call) if call.success => {
+                    self.log(id, "```\n\n- Exit status: 0\n");
+                    let mut payload = json!({ "attempt": attempt });
+                    if let Some(sid) = call.session_id {
+                        payload["session_id"] = json!(sid);
+                    }
+                    self.events.emit("phase_result", "done", payload);
+                    return Ok(());
+                }
+                Ok(call) => {
+                    self.log(
+                        id,
+                        &format!(
+                            "```\n\n- Exit status: {}\n",
+                            call.exit_code
+                                .map(|c| c.to_string())
+                                .or_else(|| call.signal.map(|s| format!("signal {}", s)))
+                                .unwrap_or("signal".into())
+                        ),
+                    );
+                    if let Some(violation) =
+                        resources.detect(&cgroup_before, call.signal, call.exit_code, &call.output)
+                    {
+                        let failure = PerformerFailure::new(
+                            limits::RESOURCE_LIMIT_ERROR_CODE,
+                            "resource_limit",
+                            violation.message(),
+                        );
+                        let mut event = violation.payload();
+                        event["attempt"] = json!(attempt);
+                        self.events.emit("resource_limit_exceeded", "failed", event);
+                        let mut payload = failure.payload();
+                        payload["attempt"] = json!(attempt);
+                        payload["exit_status"] = json!(call.exit_code);
+                        self.events.emit("phase_result", "failed", payload);
+                        // The next attempt would run under the same limits.
+                        tracing::warn!("Task {} {}", id, violation.message());
+                        return Err(PerformerFailure {
+                            message: format!(
+                                "tool execution failed for task {}: {}",
+                                id, failure.message
+                            ),
+                            ..failure
+                        });
+                    }
+                    let throttled =
+                        throttle::detect_throttle(&self.runtime.performer, &call.output);
+                    let failure = match &throttled {
+                        Some(signal) => PerformerFailure {
+                            retry_after_seconds: signal.retry_after_seconds,
+                            ..PerformerFailure::new(
+                                throttle::THROTTLE_ERROR_CODE,
+                                "provider",
+                                format!("provider throttled: {}", signal.line),
+                            )
+                        },
+                        None => PerformerFailure::new("E101", "runner", "tool exited non-zero"),
+                    };
+                    let mut payload = failure.payload();
+                    payload["attempt"] = json!(attempt);
+                    payload["exit_status"] = json!(call.exit_code);
+                    self.events.emit("phase_result", "failed", payload);
+                    if throttled.is_some() {
+                        // Retrying now would hit the same limit; leave the backoff to the coordinator.
+                        tracing::warn!("Provider throttled task {} (attempt {})", id, attempt);
+                        return Err(PerformerFailure {
+                            message: format!(
+                                "tool execution failed for task {}: {}",
+                                id, failure.message
+                            ),
+                            ..failure
+                        });
+                    }
+                    failure
+                }
+                Err(err) => {
+                    self.log(id, &format!("```\n\n- Error: {}\n", err));
+                    let failure = PerformerFailure::new("E102", "performer", err.to_string());
+                    let mut payload = failure.payload();
+                    payload["attempt"] = json!(attempt);
+                    self.events.emit("phase_result", "failed", payload);
+                    failure
+                }
+            };
+            tracing::warn!(
+                "Tool failed for task {} (attempt {}/{})",
+                id,
+                attempt,
+                max_attempts
+            );
+            last_failure = Some(failure);
+        }
+        let failure = last_failure
+            .unwrap_or_else(|| PerformerFailure::new("E101", "runner", "tool execution failed"));
+        Err(PerformerFailure::new(
+            failure.code,
+            failure.origin,
+            format!("tool execution failed for task {}: {}", id, failure.message),
+        ))
+    }
+
+    fn build_prompt(&self, task: &Value, id: &str, title: &str, task_dir: &Path) -> String {
+        let prd = self.request.prd.display();
+        format!(
+            "You are an autonomous coding agent working inside a MACC worktree.\n\n\
+             Context:\n\
+             - Worktree: {}\n\
+             - Working directory: {}\n\
+             - Task file: {}\n\
+             - Task ID: {}\n\
+             - Task Title: {}\n\n\
+             Task (JSON):\n{}\n\n\
+             Instructions:\n\
+             1) Implement ONLY the task above.\n\
+             2) Do NOT edit {}; the runner will update it.\n\
+             3) Do NOT commit; the runner will commit if all tasks are done.\n\
+             4) Keep output concise; avoid dumping large files.\n\n\
+             Now implement the task.\n",
+            self.request.worktree.display(),
+            task_dir.display(),
+            prd,
+            id,
+            title,
+            task,
+            prd
+        )
+    }
+
+    fn read_prd(&self) -> std::result::Result<Value, PerformerFailure> {
+        std::fs::read_to_string(&self.request.prd)
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .ok_or_else(|| {
+                PerformerFailure::new(
+                    "E302",
+                    "performer",
+                    format!("PRD file unreadable: {}", self.request.prd.display()),
+                )
+            })
+    }
+
+    fn mark_task_passed(&self, id: &str) -> std::result::Result<(), PerformerFailure> {
+        let mut prd = self.read_prd()?;
+        mark_passed(&mut prd, id);
+        let body = serde_json::to_string_pretty(&prd).unwrap_or_default();
+        std::fs::write(&self.request.prd, body + "\n").map_err(|e| {
+            PerformerFailure::new(
+                "E302",
+                "performer",
+                format!("write PRD {}: {}", self.request.prd.display(), e),
+            )
+        })
+    }
+
+    fn commit_changes(
+        &self,
+        last_id: Option<&String>,
+    ) -> std::result::Result<Option<String>, PerformerFailure> {
+        let worktree = &self.request.worktree;
+        let git = |args: &[&str]| {
+            crate::git::run_git_output_mapped(worktree, args, "run performer git command")
+                .map_err(|e| PerformerFailure::new("E202", "git", e.to_string()))
+        };
+        let status = git(&["status", "--porcelain"])?;
+        if String::from_utf8_lossy(&status.stdout).trim().is_empty() {
+            tracing::info!("No changes to commit.");
+            return Ok(None);
+        }
+        let add = git(&["add", "-A"])?;
+        if !add.status.success() {
+            return Err(PerformerFailure::new(
+                "E202",
+                "git",
+                format!("git add failed: {}", git_error(&add)),
+            ));
+        }
+        let mut reset = vec!["reset", "-q", "HEAD", "--"];
+        reset.extend_from_slice(PROTECTED_FILES);
+        let _ = git(&reset);
+        if git(&["diff", "--cached", "--quiet"])?.status.success() {
+            tracing::info!(
+                "No committable changes (protected files excluded: {}).",
+                PROTECTED_FILES.join(", ")
+            );
+            return Ok(None);
+        }
+
+        let last_id = last_id.map(String::as_str).unwrap_or("");
+        let title = find_task(&self.read_prd()?, last_id)
+            .and_then(|task| {
+                task.get("title")
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+            })
+            .unwrap_or_default();
+        let message = if title.is_empty() {
+            format!("feat: {}", last_id)
+        } else {
+            format!("feat: {} - {}", last_id, title)
+        };
+        let commit = git(&["commit", "-m", &message])?;
+        if !commit.status.success() {
+            return Err(PerformerFailure::new(
+                "E201",
+                "git",
+                format!("git commit failed: {}", git_error(&commit)),
+            ));
+        }
+        let sha = crate::git::head_commit(worktree).unwrap_or_default();
+        self.events.emit(
+            "commit_created",
+            "done",
+            json!({ "sha": sha, "message": message }),
+        );
+        tracing::info!("Committed changes: {}", message);
+        Ok(Some(sha))
+    }
+
+    fn log_path(&self, id: &str) -> PathBuf {
+        let safe: String = id
+            .chars()
+            .map(|c| if c.is_whitespace() { '-' } else { c })
+            .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'))
+            .collect();
+        let name = if safe.is_empty() { "task".into() } else { safe };
+        self.log_dir.join(format!("{}.md", name))
+    }
+
+    fn log(&self, id: &str, text: &str) {
+        let _ = std::fs::create_dir_all(&self.log_dir);
+        if let Ok(mut file) = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.log_path(id))
+        {
+            let _ = writeln!(file, "{}", text);
+        }
+    }
+}
+
+fn git_error(output: &std::process::Output) -> String {
+    let mut text = String::from_utf8_lossy(&output.stderr).to_string();
+    text.push_str(&String::from_utf8_lossy(&output.stdout));
+    text.replace('\n', " ").chars().take(240).collect()
+}
+
+fn now_iso() -> String {
+    chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string()
+}
+
+/// Task list of a PRD: a bare array, or `tasks` / `userStories` of an object.
+fn task_items(prd: &Value) -> Vec<Value> {
+    match prd {
+        Value::Array(items) => items.clone(),
+        Value::Object(map) => map
+            .get("tasks")
+            .or_else(|| map.get("userStories"))
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+fn pending_tasks(prd: &Value) -> Vec<Value> {
+    task_items(prd)
+        .into_iter()
+        .filter(|task| task.get("passes").and_then(Value::as_bool) != Some(true))
+        .collect()
+}
+
+fn find_task(prd: &Value, id: &str) -> Option<Value> {
+    task_items(prd)
+        .into_iter()
+        .find(|task| task_id_of(task) == id)
+}
+
+fn task_id_of(task: &Value) -> String {
+    match task.get("id") {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Number(n)) => n.to_string(),
+        _ => String::new(),
+    }
+}
+
+fn mark_passed(prd: &mut Value, id: &str) {
+    let mark = |items: &mut Vec<Value>| {
+        for task in items.iter_mut() {
+            if task_id_of(task) == id {
+                task["passes"] = json!(true);
+            }
+        }
+    };
+    match prd {
+        Value::Array(items) => mark(items),
+        Value::Object(map) => {
+            for key in ["tasks", "userStories"] {
+                if let Some(Value::Array(items)) = map.get_mut(key) {
+                    mark(items);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn temp_dir(prefix: &str) -> PathBuf {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("{}_{}", prefix, nanos));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Git worktree with an executable fake tool (outside the worktree) and its tool.json.
+    fn harness(prefix: &str, script: &str, performer: Value) -> (PathBuf, PathBuf) {
+        let root = temp_dir(prefix);
+        let worktree = root.join("wt");
+        fs::create_dir_all(worktree.join(".macc")).unwrap();
+        let git = |args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .args(args)
+                .current_dir(&worktree)
+                .output()
+                .unwrap()
+                .status;
+            assert!(status.success(), "git {:?}", args);
+        };
+        git(&["init", "-q"]);
+        git(&["config", "user.email", "performer@example.com"]);
+        git(&["config", "user.name", "Performer Test"]);
+        git(&["config", "commit.gpgsign", "false"]);
+        fs::write(worktree.join(".gitignore"), ".macc/\n").unwrap();
+        git(&["add", ".gitignore"]);
+        git(&["commit", "-q", "-m", "init"]);
+
+        let tool = root.join("fake-tool.sh");
+        fs::write(
+            &tool,
+            format!(
+                "#!/bin/sh\nCALLS='{}'\n{}",
+                root.join("calls.log").display(),
+                script
+            ),
+        )
+        .unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&tool, fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        let mut performer = performer;
+        let tool_path = tool.to_string_lossy().to_string();
+        performer["command"] = json!(tool_path);
+        for key in ["retry", "session"] {
+            if let Some(section) = performer.get_mut(key) {
+                for cmd in ["resume", "discover"] {
+                    if let Some(c) = section.get_mut(cmd) {
+                        c["command"] = json!(tool_path);
+                    }
+                }
+                if key == "retry" {
+                    section["command"] = json!(tool_path);
+                }
+            }
+        }
+        let runtime = json!({
+            "api_version": "v1",
+            "id": "fake",
+            "display_name": "Fake",
+            "performer": performer,
+        });
+        fs::write(
+            worktree.join(".macc/tool.json"),
+            serde_json::to_string_pretty(&runtime).unwrap(),
+        )
+        .unwrap();
+        (root, worktree)
+    }
+
+    fn calls(root: &Path) -> Vec<String> {
+        fs::read_to_string(root.join("calls.log"))
+            .unwrap_or_default()
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_performer_retries_with_retry_command_and_commits() {
+        let script = r#"
+if [ "$1" = "--retry" ]; then
+  echo "retry" >> "$CALLS"
+  cat > /dev/null
+  echo "done" > feature.txt
+  echo "tokens used: 1,234"
+  exit 0
+fi
+echo "first" >> "$CALLS"
+echo "transient failure"
+exit 3
+"#;
+        let (root, worktree) = harness(
+            "macc_performer_retry",
+            script,
+            json!({
+                "runner": "unused",
+                "command": "",
+                "args": ["--first"],
+                "retry": { "command": "", "args": ["--retry"] },
+                "usage": { "input_tokens_regex": "tokens used: ([\\d,]+)" },
+            }),
+        );
+        let prd = worktree.join("worktree.prd.json");
+        fs::write(&prd, r#"{"tasks":[{"id":"T1","title":"Add feature"}]}"#).unwrap();
+        let events_file = root.join("events.jsonl");
+        let request = PerformerRequest {
+            repo: worktree.clone(),
+            worktree: worktree.clone(),
+            task_id: "T1".into(),
+            tool: "fake".into(),
+            prd: prd.clone(),
+            events_file: Some(events_file.clone()),
+            run_id: "run-test".into(),
+            event_source: "performer:test".into(),
+            limits: PerformerLimits {
+                sleep_between_tasks: Duration::ZERO,
+                heartbeat_interval: Duration::from_secs(3600),
+                ..PerformerLimits::default()
+            },
+        };
+
+        let outcome = run_performer(&request).expect("performer run");
+        assert_eq!(outcome.completed_tasks, vec!["T1".to_string()]);
+        assert!(outcome.commit.is_some());
+        assert_eq!(calls(&root), vec!["first", "retry"]);
+
+        let prd_json: Value = serde_json::from_str(&fs::read_to_string(&prd).unwrap()).unwrap();
+        assert_eq!(prd_json["tasks"][0]["passes"], json!(true));
+
+        let events: Vec<CoordinatorEvent> = fs::read_to_string(&events_file)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let results: Vec<(&str, &str)> = events
+            .iter()
+            .filter(|e| matches!(e.event_type.as_str(), "phase_result" | "commit_created"))
+            .map(|e| (e.event_type.as_str(), e.status.as_str()))
+            .collect();
+        assert_eq!(
+            results,
+            vec![
+                ("phase_result", "failed"),
+                ("phase_result", "done"),
+                ("commit_created", "done"),
+                ("phase_result", "done"),
+            ]
+        );
+        assert_eq!(events[0].event_type, "started");
+        let usage: Vec<&Value> = events
+            .iter()
+            .filter(|e| e.payload["activity"] == "token_usage")
+            .map(|e| &e.payload)
+            .collect();
+        assert_eq!(usage.len(), 1);
+        assert_eq!(usage[0]["input_tokens"], 1234);
+        assert_eq!(usage[0]["tool"], "fake");
+        assert!(events.windows(2).all(|w| w[0].seq < w[1].seq));
+        assert!(events
+            .iter()
+            .all(|e| e.run_id.as_deref() == Some("run-test")));
+        fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_performer_discovers_session_then_resumes_it() {
+        let script = r#"
+case "$1" in
+  resume) echo "resume $2" >> "$CALLS"; exit 0 ;;
+  discover) echo "listing sessions"; echo "sess-42"; exit 0 ;;
+esac
+cat > /dev/null
+echo "fresh" >> "$CALLS"
+echo "no id printed here"
+"#;
+        let (root, worktree) = harness(
+            "macc_performer_session",
+            script,
+            json!({
+                "runner": "unused",
+                "command": "",
+                "session": {
+                    "enabled": true,
+                    "id_strategy": "discovered",
+                    "resume": { "command": "", "args": ["resume", "{session_id}"] },
+                    "discover": { "command": "", "args": ["discover"] },
+                },
+            }),
+        );
+        let raw = fs::read_to_string(worktree.join(".macc/tool.json")).unwrap();
+        let runtime: ToolRuntimeConfig = serde_json::from_str(&raw).unwrap();
+        let invocation = |attempt| ToolInvocation {
+            tool_id: "fake",
+            spec: &runtime.performer,
+            repo: &worktree,
+            worktree: &worktree,
+            cwd: &worktree,
+            task_id: "T1",
+            attempt,
+            lease_ttl_seconds: 1800,
+            limits: None,
+            env: None,
+        };
+
+        let first = invoke_tool(&invocation(1), "prompt", &mut |_| {}).unwrap();
+        assert!(first.success);
+        assert_eq!(first.session_id.as_deref(), Some("sess-42"));
+
+        let state: Value = serde_json::from_str(
+            &fs::read_to_string(worktree.join(".macc/state/tool-sessions.json")).unwrap(),
+        )
+        .unwrap();
+        let key = worktree.to_string_lossy().to_string();
+        assert_eq!(
+            state["tools"]["fake"]["sessions"][&key]["session_id"],
+            json!("sess-42")
+        );
+        assert_eq!(
+            state["tools"]["fake"]["leases"]["sess-42"]["status"],
+            json!("released")
+        );
+
+        let second = invoke_tool(&invocation(1), "prompt", &mut |_| {}).unwrap();
+        assert!(second.success);
+        assert_eq!(calls(&root), vec!["fresh", "resume sess-42"]);
+        fs::remove_dir_all(&root).ok();
+    }
+}
@@ -0,0 +1,323 @@
+//! One tool call driven by the ToolSpec `performer` section: command/args, `retry` on later
+//! attempts, prompt delivery (`stdin` or `arg`) and optional session resume/discovery.
+
+use super::sandbox::SandboxLaunch;
+use super::session::SessionStore;
+use crate::coordinator::limits::PerformerLimitGuard;
+use crate::tool::ToolPerformerSpec;
+use crate::{MaccError, Result};
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+
+const DEFAULT_EXTRACT_REGEX: &str = r"session[[:space:]]+id:[[:space:]]*([[:alnum:]-]+)";
+
+/// Inputs for a single tool call.
+pub struct ToolInvocation<'a> {
+    pub tool_id: &'a str,
+    pub spec: &'a ToolPerformerSpec,
+    pub repo: &'a Path,
+    pub worktree: &'a Path,
+    /// Directory the tool runs in (the task's sub-project or the worktree).
+    pub cwd: &'a Path,
+    pub task_id: &'a str,
+    pub attempt: usize,
+    pub lease_ttl_seconds: u64,
+    /// Resource limits set on the tool process; `None` when the caller is already limited.
+    pub limits: Option<&'a PerformerLimitGuard>,
+    /// Extra environment for the tool process (the coordinator's worktree pool `env`).
+    pub env: Option<&'a BTreeMap<String, String>>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ToolCallResult {
+    pub success: bool,
+    pub exit_code: Option<i32>,
+    /// Signal that terminated the tool, when it did not exit normally.
+    pub signal: Option<i32>,
+    pub output: String,
+    /// Session leased to this worktree after the call, if sessions are enabled.
+    pub session_id: Option<String>,
+}
+
+/// Runs the tool once. Output lines (stdout and stderr) are passed to `on_line` as they arrive.
+pub fn invoke_tool(
+    inv: &ToolInvocation<'_>,
+    prompt: &str,
+    on_line: &mut dyn FnMut(&str),
+) -> Result<ToolCallResult> {
+    let spec = inv.spec;
+    let (command, args) = match (&spec.retry, inv.attempt > 1) {
+        (Some(retry), true) => (retry.command.as_str(), retry.args.clone()),
+        _ => (spec.command.as_str(), spec.args.clone()),
+    };
+    if resolve_command(command).is_none() {
+        return Err(MaccError::Validation(format!(
+            "tool command not found in PATH: {}",
+            command
+        )));
+    }
+    let prompt_mode = spec
+        .prompt
+        .as_ref()
+        .map(|p| p.mode.as_str())
+        .unwrap_or("stdin");
+    let prompt_arg = spec
+        .prompt
+        .as_ref()
+        .and_then(|p| p.arg.as_deref())
+        .filter(|arg| !arg.is_empty());
+    if prompt_mode == "arg" && prompt_arg.is_none() {
+        return Err(MaccError::Validation(
+            "performer.prompt.arg required for arg mode".into(),
+        ));
+    }
+    let sandbox = match &spec.sandbox {
+        Some(config) => {
+            let commands = std::iter::once(command)
+                .chain(spec.session.iter().flat_map(|s| {
+                    s.resume
+                        .iter()
+                        .chain(s.discover.iter())
+                        .map(|c| c.command.as_str())
+                }))
+                .filter_map(resolve_command)
+                .collect::<Vec<_>>();
+            SandboxLaunch::prepare(config, inv.repo, inv.worktree, inv.cwd, &commands)?
+        }
+        None => None,
+    };
+    let sandbox = sandbox.as_ref();
+    let default_call = |on_line: &mut dyn FnMut(&str)| {
+        let mut call_args = args.clone();
+        if prompt_mode == "arg" {
+            call_args.push(prompt_arg.unwrap_or_default().to_string());
+            call_args.push(prompt.to_string());
+            run_captured(inv, command, &call_args, None, sandbox, on_line)
+        } else {
+            run_captured(inv, command, &call_args, Some(prompt), sandbox, on_line)
+        }
+    };
+
+    let session = spec
+        .session
+        .as_ref()
+        .filter(|s| s.enabled)
+        .and_then(|s| s.resume.as_ref().map(|resume| (s, resume)));
+    let Some((session, resume)) = session else {
+        let (status, output) = default_call(on_line)?;
+        return Ok(ToolCallResult {
+            success: status.success(),
+            exit_code: status.code(),
+            signal: exit_signal(&status),
+            output,
+            session_id: None,
+        });
+    };
+
+    let strategy = session.id_strategy.as_deref().unwrap_or("discovered");
+    let store = SessionStore::new(
+        inv.repo,
+        inv.tool_id,
+        session.scope.as_deref().unwrap_or("worktree"),
+        inv.worktree,
+        inv.task_id,
+        inv.lease_ttl_seconds,
+    );
+    let mut sid = store.claim_stored()?;
+    if sid.is_none() && strategy == "generated" {
+        sid = store.reserve_generated()?;
+    }
+
+    let (mut status, mut output) = match &sid {
+        Some(sid) => {
+            let mut resume_args: Vec<String> = resume
+                .args
+                .iter()
+                .map(|arg| arg.replace("{session_id}", sid))
+                .collect();
+            if prompt_mode == "arg" {
+                resume_args.push(prompt_arg.unwrap_or_default().to_string());
+            }
+            resume_args.push(prompt.to_string());
+            run_captured(inv, &resume.command, &resume_args, None, sandbox, on_line)?
+        }
+        None => default_call(on_line)?,
+    };
+    // A stale session falls back to a fresh call on the first attempt only.
+    if sid.is_some() && !status.success() && inv.attempt == 1 {
+        (status, output) = default_call(on_line)?;
+    }
+
+    let extract = session
+        .extract_regex
+        .as_deref()
+        .unwrap_or(DEFAULT_EXTRACT_REGEX);
+    let regex = regex::Regex::new(&format!("(?i){}", extract)).map_err(|e| {
+        MaccError::Validation(format!(
+            "Invalid performer.session.extract_regex '{}': {}",
+            extract, e
+        ))
+    })?;
+    let mut new_sid = extract_session_id(&regex, &output);
+    if new_sid.is_none() && inv.attempt == 1 && strategy == "discovered" {
+        if let Some(discover) = &session.discover {
+            let (_, discovered) = run_captured(
+                inv,
+                &discover.command,
+                &discover.args,
+                None,
+                sandbox,
+                &mut |_| {},
+            )
+            .unwrap_or_default();
+            new_sid = extract_session_id(&regex, &discovered).or_else(|| {
+                discovered
+                    .lines()
+                    .map(str::trim)
+                    .rev()
+                    .find(|line| !line.is_empty())
+                    .map(str::to_string)
+            });
+        }
+    }
+    if new_sid.is_none() && strategy == "generated" {
+        new_sid = sid.clone();
+    }
+
+    let mut leased = sid;
+    if let Some(new_sid) = new_sid {
+        if store.claim(&new_sid)? {
+            leased = Some(new_sid);
+        }
+    }
+    // Leases only cover the call itself; the stored session stays resumable.
+    if let Some(leased) = &leased {
+        store.release(leased)?;
+    }
+    Ok(ToolCallResult {
+        success: status.success(),
+        exit_code: status.code(),
+        signal: exit_signal(&status),
+        output,
+        session_id: leased,
+    })
+}
+
+fn extract_session_id(regex: &regex::Regex, output: &str) -> Option<String> {
+    output
+        .lines()
+        .rev()
+        .filter_map(|line| regex.captures(line))
+        .find_map(|caps| caps.get(1).map(|m| m.as_str().to_string()))
+}
+
+/// Absolute path for `command`, searching `PATH` for bare names.
+pub fn resolve_command(command: &str) -> Option<PathBuf> {
+    if command.contains('/') {
+        let path = PathBuf::from(command);
+        return path.is_file().then_some(path);
+    }
+    std::env::var_os("PATH").and_then(|paths| {
+        std::env::split_paths(&paths)
+            .map(|dir| dir.join(command))
+            .find(|candidate| candidate.is_file())
+    })
+}
+
+/// Runs `command` (inside `sandbox` and under `limits` when set), streaming merged
+/// stdout/stderr lines to `on_line` and returning them.
+fn run_captured(
+    inv: &ToolInvocation<'_>,
+    command: &str,
+    args: &[String],
+    stdin: Option<&str>,
+    sandbox: Option<&SandboxLaunch>,
+    on_line: &mut dyn FnMut(&str),
+) -> Result<(ExitStatus, String)> {
+    let mut cmd = match sandbox {
+        Some(sandbox) => sandbox.command(command, args),
+        None => {
+            let mut cmd = Command::new(command);
+            cmd.args(args);
+            cmd
+        }
+    };
+    if let Some(limits) = inv.limits {
+        limits.apply(&mut cmd);
+    }
+    if let Some(env) = inv.env {
+        cmd.envs(env);
+    }
+    let mut child = cmd
+        .current_dir(inv.cwd)
+        .stdin(if stdin.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| MaccError::Io {
+            path: command.to_string(),
+            action: "spawn performer tool".into(),
+            source: e,
+        })?;
+
+    if let (Some(text), Some(mut pipe)) = (stdin, child.stdin.take()) {
+        let text = text.to_string();
+        std::thread::spawn(move || {
+            let _ = pipe.write_all(text.as_bytes());
+        });
+    }
+    let (tx, rx) = std::sync::mpsc::channel::<String>();
+    let mut readers = Vec::new();
+    let stdout = child
+        .stdout
+        .take()
+        .map(|s| Box::new(s) as Box<dyn std::io::Read + Send>);
+    let stderr = child
+        .stderr
+        .take()
+        .map(|s| Box::new(s) as Box<dyn std::io::Read + Send>);
+    for stream in [stdout, stderr].into_iter().flatten() {
+        let tx = tx.clone();
+        readers.push(std::thread::spawn(move || {
+            for line in BufReader::new(stream).lines().map_while(|l| l.ok()) {
+                if tx.send(line).is_err() {
+                    break;
+                }
+            }
+        }));
+    }
+    drop(tx);
+
+    let mut output = String::new();
+    for line in rx {
+        on_line(&line);
+        output.push_str(&line);
+        output.push('\n');
+    }
+    for reader in readers {
+        let _ = reader.join();
+    }
+    let status = child.wait().map_err(|e| MaccError::Io {
+        path: command.to_string(),
+        action: "wait for performer tool".into(),
+        source: e,
+    })?;
+    Ok((status, output))
+}
+
+#[cfg(unix)]
+fn exit_signal(status: &ExitStatus) -> Option<i32> {
+    use std::os::unix::process::ExitStatusExt;
+    status.signal()
+}
+
+#[cfg(not(unix))]
+fn exit_signal(_status: &ExitStatus) -> Option<i32> {
+    None
+}
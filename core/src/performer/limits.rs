@@ -0,0 +1,308 @@
+//! Resource limits seen from inside a performer process.
+//!
+//! The coordinator applies `automation.coordinator.resource_limits` (a task cgroup or rlimits,
+//! see `coordinator::limits`) and passes the effective values through `MACC_LIMIT_*`. The
+//! performer reports peak RSS and CPU time of its tool calls and classifies a call that died on
+//! a limit as `E105`.
+
+use crate::config::ResourceLimits;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+
+pub const RESOURCE_LIMIT_ERROR_CODE: &str = "E105";
+
+pub const ENV_CPU_SECONDS: &str = "MACC_LIMIT_CPU_SECONDS";
+pub const ENV_MEMORY_MB: &str = "MACC_LIMIT_MEMORY_MB";
+pub const ENV_MAX_PROCESSES: &str = "MACC_LIMIT_MAX_PROCESSES";
+/// Task cgroup directory when the limits are enforced through cgroup v2.
+pub const ENV_CGROUP: &str = "MACC_LIMIT_CGROUP";
+
+const SIGKILL: i32 = 9;
+const SIGXCPU: i32 = 24;
+
+const MEMORY_PATTERNS: &[&str] = &[
+    "out of memory",
+    "cannot allocate memory",
+    "memory allocation of",
+    "heap out of memory",
+    "std::bad_alloc",
+    "memoryerror",
+];
+
+/// The limit a process ran into.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LimitViolation {
+    /// `cpu_seconds`, `memory_mb` or `max_processes`.
+    pub limit: &'static str,
+    pub value: u64,
+}
+
+impl LimitViolation {
+    pub fn message(&self) -> String {
+        format!("resource limit exceeded: {}={}", self.limit, self.value)
+    }
+
+    /// Payload of the `resource_limit_exceeded` event.
+    pub fn payload(&self) -> Value {
+        json!({
+            "limit": self.limit,
+            "value": self.value,
+            "error_code": RESOURCE_LIMIT_ERROR_CODE,
+            "message": self.message(),
+        })
+    }
+}
+
+/// Usage of the finished child processes so far.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ResourceUsage {
+    pub peak_rss_kb: u64,
+    pub cpu_seconds: f64,
+}
+
+impl ResourceUsage {
+    /// Payload of the `progress` event reporting one tool call; `before` is the usage taken
+    /// when the call started, so `cpu_seconds` only covers this call.
+    pub fn payload_since(&self, before: &ResourceUsage) -> Value {
+        let cpu_seconds = (self.cpu_seconds - before.cpu_seconds).max(0.0);
+        json!({
+            "activity": "resource_usage",
+            "peak_rss_kb": self.peak_rss_kb,
+            "cpu_seconds": (cpu_seconds * 1000.0).round() / 1000.0,
+            "message": format!(
+                "peak rss={}MB cpu={:.1}s",
+                self.peak_rss_kb / 1024,
+                cpu_seconds
+            ),
+        })
+    }
+}
+
+/// Kill counters of the task cgroup (`memory.events` and `pids.events`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CgroupEvents {
+    pub oom_kill: u64,
+    pub pids_max: u64,
+}
+
+/// Limits in effect for this process tree.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PerformerResourceLimits {
+    pub limits: ResourceLimits,
+    pub cgroup: Option<PathBuf>,
+}
+
+impl PerformerResourceLimits {
+    pub fn from_env() -> Self {
+        let var = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.trim().parse::<u64>().ok())
+                .filter(|v| *v > 0)
+        };
+        Self {
+            limits: ResourceLimits {
+                cpu_seconds: var(ENV_CPU_SECONDS),
+                memory_mb: var(ENV_MEMORY_MB),
+                max_processes: var(ENV_MAX_PROCESSES),
+            },
+            cgroup: std::env::var_os(ENV_CGROUP)
+                .filter(|v| !v.is_empty())
+                .map(PathBuf::from),
+        }
+    }
+
+    /// Variables read back by [`PerformerResourceLimits::from_env`].
+    pub fn env(&self) -> Vec<(&'static str, String)> {
+        let mut vars = Vec::new();
+        for (name, value) in [
+            (ENV_CPU_SECONDS, self.limits.cpu_seconds),
+            (ENV_MEMORY_MB, self.limits.memory_mb),
+            (ENV_MAX_PROCESSES, self.limits.max_processes),
+        ] {
+            if let Some(value) = value {
+                vars.push((name, value.to_string()));
+            }
+        }
+        if let Some(cgroup) = &self.cgroup {
+            vars.push((ENV_CGROUP, cgroup.to_string_lossy().to_string()));
+        }
+        vars
+    }
+
+    pub fn cgroup_events(&self) -> CgroupEvents {
+        self.cgroup
+            .as_deref()
+            .map(read_cgroup_events)
+            .unwrap_or_default()
+    }
+
+    /// Limit behind a failed process exit. `before` are the cgroup counters from when the
+    /// process started; `output` is matched for allocation failures. Fork failures only count
+    /// when the task cgroup's `pids.max` was hit, since they may come from anywhere else.
+    pub fn detect(
+        &self,
+        before: &CgroupEvents,
+        signal: Option<i32>,
+        exit_code: Option<i32>,
+        output: &str,
+    ) -> Option<LimitViolation> {
+        let limits = &self.limits;
+        let after = self.cgroup_events();
+        if let Some(value) = limits.cpu_seconds {
+            if signal == Some(SIGXCPU) || exit_code == Some(128 + SIGXCPU) {
+                return Some(LimitViolation {
+                    limit: "cpu_seconds",
+                    value,
+                });
+            }
+        }
+        let output = output.to_ascii_lowercase();
+        if let Some(value) = limits.memory_mb {
+            let oom_killed = after.oom_kill > before.oom_kill
+                && (signal == Some(SIGKILL) || exit_code == Some(128 + SIGKILL));
+            if oom_killed || MEMORY_PATTERNS.iter().any(|p| output.contains(p)) {
+                return Some(LimitViolation {
+                    limit: "memory_mb",
+                    value,
+                });
+            }
+        }
+        if let Some(value) = limits.max_processes {
+            if after.pids_max > before.pids_max {
+                return Some(LimitViolation {
+                    limit: "max_processes",
+                    value,
+                });
+            }
+        }
+        None
+    }
+}
+
+fn read_cgroup_events(dir: &Path) -> CgroupEvents {
+    let counter = |file: &str, key: &str| {
+        std::fs::read_to_string(dir.join(file))
+            .ok()
+            .and_then(|raw| {
+                raw.lines().find_map(|line| {
+                    let (name, value) = line.split_once(' ')?;
+                    if name == key {
+                        value.trim().parse::<u64>().ok()
+                    } else {
+                        None
+                    }
+                })
+            })
+            .unwrap_or(0)
+    };
+    CgroupEvents {
+        oom_kill: counter("memory.events", "oom_kill"),
+        pids_max: counter("pids.events", "max"),
+    }
+}
+
+/// Peak RSS and CPU time of all waited-for children of this process.
+#[cfg(unix)]
+pub fn children_usage() -> ResourceUsage {
+    let mut usage = std::mem::MaybeUninit::<libc::rusage>::zeroed();
+    // SAFETY: getrusage only writes into the provided struct.
+    let rc = unsafe { libc::getrusage(libc::RUSAGE_CHILDREN, usage.as_mut_ptr()) };
+    if rc != 0 {
+        return ResourceUsage::default();
+    }
+    // SAFETY: initialised by the successful call above.
+    let usage = unsafe { usage.assume_init() };
+    let seconds = |tv: libc::timeval| tv.tv_sec as f64 + tv.tv_usec as f64 / 1_000_000.0;
+    // macOS reports ru_maxrss in bytes, Linux in KiB.
+    let peak_rss_kb = if cfg!(target_os = "macos") {
+        usage.ru_maxrss as u64 / 1024
+    } else {
+        usage.ru_maxrss as u64
+    };
+    ResourceUsage {
+        peak_rss_kb,
+        cpu_seconds: seconds(usage.ru_utime) + seconds(usage.ru_stime),
+    }
+}
+
+#[cfg(not(unix))]
+pub fn children_usage() -> ResourceUsage {
+    ResourceUsage::default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_classifies_limit_exits() {
+        let root = std::env::temp_dir().join(format!(
+            "macc_limits_{}",
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(
+            root.join("memory.events"),
+            "low 0\nhigh 0\noom 1\noom_kill 1\n",
+        )
+        .unwrap();
+        let limits = PerformerResourceLimits {
+            limits: ResourceLimits {
+                cpu_seconds: Some(60),
+                memory_mb: Some(512),
+                max_processes: Some(64),
+            },
+            cgroup: Some(root.clone()),
+        };
+        let before = CgroupEvents::default();
+        assert_eq!(
+            limits
+                .detect(&before, Some(SIGXCPU), None, "")
+                .unwrap()
+                .limit,
+            "cpu_seconds"
+        );
+        assert_eq!(
+            limits
+                .detect(&before, Some(SIGKILL), None, "")
+                .unwrap()
+                .limit,
+            "memory_mb"
+        );
+        assert_eq!(
+            limits
+                .detect(&limits.cgroup_events(), Some(SIGKILL), None, "")
+                .map(|v| v.limit),
+            None
+        );
+        let fork_failure = "sh: fork: retry: Resource temporarily unavailable";
+        assert_eq!(
+            limits.detect(&limits.cgroup_events(), None, Some(1), fork_failure),
+            None
+        );
+        let before = limits.cgroup_events();
+        std::fs::write(root.join("pids.events"), "max 1\n").unwrap();
+        assert_eq!(
+            limits
+                .detect(&before, None, Some(1), fork_failure)
+                .unwrap()
+                .limit,
+            "max_processes"
+        );
+        let before = CgroupEvents::default();
+        assert_eq!(
+            limits.detect(&limits.cgroup_events(), None, Some(1), "test failed"),
+            None
+        );
+
+        let unlimited = PerformerResourceLimits::default();
+        assert_eq!(
+            unlimited.detect(&before, Some(SIGXCPU), None, "out of memory"),
+            None
+        );
+        assert!(unlimited.env().is_empty());
+        assert_eq!(limits.env().len(), 4);
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,395 @@
+//! Parsers for structured tool output selected by ToolSpec `performer.output.format`.
+//!
+//! - `text`: plain output, nothing is parsed.
+//! - `stream-json`: one JSON message per line (`assistant` messages carrying `tool_use`
+//!   content blocks, and a final `result` message with `usage` and the answer text).
+//! - `json-events`: one JSON event per line (`item.started` / `item.completed` items such as
+//!   `command_execution`, `file_change`, `mcp_tool_call`, `agent_message`, and
+//!   `turn.completed` with `usage`).
+
+use serde_json::{json, Value};
+use std::str::FromStr;
+
+pub const OUTPUT_FORMATS: &[&str] = &["text", "stream-json", "json-events"];
+
+/// Tool-use names that edit files in `stream-json` output.
+const FILE_EDIT_TOOLS: &[&str] = &["Edit", "MultiEdit", "Write", "NotebookEdit"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    StreamJson,
+    JsonEvents,
+}
+
+impl OutputFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            OutputFormat::Text => "text",
+            OutputFormat::StreamJson => "stream-json",
+            OutputFormat::JsonEvents => "json-events",
+        }
+    }
+
+    /// Format declared in a tool runtime config (`text` when absent or unknown).
+    pub fn of(spec: &crate::tool::ToolPerformerSpec) -> Self {
+        spec.output
+            .as_ref()
+            .and_then(|output| output.format.parse().ok())
+            .unwrap_or_default()
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim() {
+            "text" => Ok(OutputFormat::Text),
+            "stream-json" => Ok(OutputFormat::StreamJson),
+            "json-events" => Ok(OutputFormat::JsonEvents),
+            other => Err(format!("unknown output format '{}'", other)),
+        }
+    }
+}
+
+/// Something the tool did, extracted from one output line.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OutputActivity {
+    ToolCall {
+        name: String,
+        detail: Option<String>,
+    },
+    FileEdit {
+        path: String,
+        action: String,
+    },
+    TokenUsage {
+        input_tokens: u64,
+        output_tokens: u64,
+        cached_input_tokens: u64,
+    },
+}
+
+impl OutputActivity {
+    /// Coordinator event type: file edits are `artifact`s, everything else is `progress`.
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            OutputActivity::FileEdit { .. } => "artifact",
+            _ => "progress",
+        }
+    }
+
+    /// Short human-readable line, also used as the event `message`.
+    pub fn summary(&self) -> String {
+        match self {
+            OutputActivity::ToolCall { name, detail } => match detail {
+                Some(detail) => format!("{}: {}", name, truncate(detail, 120)),
+                None => name.clone(),
+            },
+            OutputActivity::FileEdit { path, action } => format!("{} {}", action, path),
+            OutputActivity::TokenUsage {
+                input_tokens,
+                output_tokens,
+                cached_input_tokens,
+            } => format!(
+                "tokens in={} out={} cached={}",
+                input_tokens, output_tokens, cached_input_tokens
+            ),
+        }
+    }
+
+    pub fn payload(&self) -> Value {
+        let mut payload = match self {
+            OutputActivity::ToolCall { name, detail } => {
+                json!({ "activity": "tool_call", "name": name, "detail": detail })
+            }
+            OutputActivity::FileEdit { path, action } => {
+                json!({ "activity": "file_edit", "kind": "file_edit", "path": path, "action": action })
+            }
+            OutputActivity::TokenUsage {
+                input_tokens,
+                output_tokens,
+                cached_input_tokens,
+            } => json!({
+                "activity": "token_usage",
+                "input_tokens": input_tokens,
+                "output_tokens": output_tokens,
+                "cached_input_tokens": cached_input_tokens,
+            }),
+        };
+        payload["message"] = json!(self.summary());
+        payload
+    }
+}
+
+/// Activities found in one line of output. Lines that are not JSON are ignored.
+pub fn parse_line(format: OutputFormat, line: &str) -> Vec<OutputActivity> {
+    if format == OutputFormat::Text {
+        return Vec::new();
+    }
+    let Ok(value) = serde_json::from_str::<Value>(line.trim()) else {
+        return Vec::new();
+    };
+    match format {
+        OutputFormat::Text => Vec::new(),
+        OutputFormat::StreamJson => parse_stream_json(&value),
+        OutputFormat::JsonEvents => parse_json_events(&value),
+    }
+}
+
+/// Plain answer text of a tool run: the final result/message for structured formats,
+/// the output itself for `text` (or when no final message is found).
+pub fn final_text(format: OutputFormat, output: &str) -> String {
+    let messages = output
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line.trim()).ok());
+    let text = match format {
+        OutputFormat::Text => None,
+        OutputFormat::StreamJson => messages
+            .rev()
+            .filter(|v| v["type"] == "result")
+            .find_map(|v| v["result"].as_str().map(str::to_string)),
+        OutputFormat::JsonEvents => messages
+            .rev()
+            .filter(|v| v["type"] == "item.completed" && v["item"]["type"] == "agent_message")
+            .find_map(|v| v["item"]["text"].as_str().map(str::to_string)),
+    };
+    text.unwrap_or_else(|| output.to_string())
+}
+
+/// Token usage read from a call's output with the ToolSpec `performer.usage` regexes.
+/// Returns `None` when no regex matched.
+pub fn usage_from_text(
+    usage: &crate::tool::ToolPerformerUsage,
+    output: &str,
+) -> Option<OutputActivity> {
+    let count = |pattern: &Option<String>| -> Option<u64> {
+        let pattern = pattern.as_deref()?;
+        // Also checked at ToolSpec load; warn here so a bad pattern never reads as zero tokens.
+        let regex = match regex::Regex::new(pattern) {
+            Ok(regex) => regex,
+            Err(e) => {
+                tracing::warn!("ignoring invalid usage regex '{}': {}", pattern, e);
+                return None;
+            }
+        };
+        let mut matched = false;
+        let total = regex
+            .captures_iter(output)
+            .filter_map(|caps| {
+                matched = true;
+                caps.get(1)?
+                    .as_str()
+                    .replace([',', '_'], "")
+                    .trim()
+                    .parse::<u64>()
+                    .ok()
+            })
+            .sum();
+        matched.then_some(total)
+    };
+    let input = count(&usage.input_tokens_regex);
+    let output_tokens = count(&usage.output_tokens_regex);
+    let cached = count(&usage.cached_input_tokens_regex);
+    if input.is_none() && output_tokens.is_none() && cached.is_none() {
+        return None;
+    }
+    Some(OutputActivity::TokenUsage {
+        input_tokens: input.unwrap_or(0),
+        output_tokens: output_tokens.unwrap_or(0),
+        cached_input_tokens: cached.unwrap_or(0),
+    })
+}
+
+fn parse_stream_json(value: &Value) -> Vec<OutputActivity> {
+    match value["type"].as_str() {
+        Some("assistant") => value["message"]["content"]
+            .as_array()
+            .map(|blocks| {
+                blocks
+                    .iter()
+                    .filter(|block| block["type"] == "tool_use")
+                    .map(|block| {
+                        let name = block["name"].as_str().unwrap_or("tool").to_string();
+                        let input = &block["input"];
+                        let path = input["file_path"]
+                            .as_str()
+                            .or_else(|| input["notebook_path"].as_str());
+                        match path {
+                            Some(path) if FILE_EDIT_TOOLS.contains(&name.as_str()) => {
+                                OutputActivity::FileEdit {
+                                    path: path.to_string(),
+                                    action: if name == "Write" { "write" } else { "edit" }
+                                        .to_string(),
+                                }
+                            }
+                            _ => OutputActivity::ToolCall {
+                                detail: ["command", "file_path", "pattern", "url", "description"]
+                                    .iter()
+                                    .find_map(|key| input[*key].as_str())
+                                    .map(str::to_string),
+                                name,
+                            },
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+        Some("result") => usage_activity(
+            &value["usage"],
+            "input_tokens",
+            "output_tokens",
+            "cache_read_input_tokens",
+        )
+        .into_iter()
+        .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn parse_json_events(value: &Value) -> Vec<OutputActivity> {
+    let item = &value["item"];
+    match (value["type"].as_str(), item["type"].as_str()) {
+        (Some("item.started"), Some("command_execution")) => vec![OutputActivity::ToolCall {
+            name: "shell".into(),
+            detail: item["command"].as_str().map(str::to_string),
+        }],
+        (Some("item.started"), Some("mcp_tool_call")) => vec![OutputActivity::ToolCall {
+            name: item["tool"].as_str().unwrap_or("mcp").to_string(),
+            detail: item["server"].as_str().map(str::to_string),
+        }],
+        (Some("item.completed"), Some("file_change")) => item["changes"]
+            .as_array()
+            .map(|changes| {
+                changes
+                    .iter()
+                    .filter_map(|change| {
+                        Some(OutputActivity::FileEdit {
+                            path: change["path"].as_str()?.to_string(),
+                            action: change["kind"].as_str().unwrap_or("update").to_string(),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+        (Some("turn.completed"), _) => usage_activity(
+            &value["usage"],
+            "input_tokens",
+            "output_tokens",
+            "cached_input_tokens",
+        )
+        .into_iter()
+        .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn usage_activity(
+    usage: &Value,
+    input: &str,
+    output: &str,
+    cached: &str,
+) -> Option<OutputActivity> {
+    if !usage.is_object() {
+        return None;
+    }
+    Some(OutputActivity::TokenUsage {
+        input_tokens: usage[input].as_u64().unwrap_or(0),
+        output_tokens: usage[output].as_u64().unwrap_or(0),
+        cached_input_tokens: usage[cached].as_u64().unwrap_or(0),
+    })
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    let line = text.lines().next().unwrap_or_default();
+    if line.chars().count() > max {
+        format!("{}...", line.chars().take(max).collect::<String>())
+    } else {
+        line.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stream_json_tool_use_edits_and_usage() {
+        let edit = r#"{"type":"assistant","message":{"content":[{"type":"text","text":"ok"},{"type":"tool_use","name":"Edit","input":{"file_path":"src/lib.rs"}},{"type":"tool_use","name":"Bash","input":{"command":"cargo test"}}]}}"#;
+        assert_eq!(
+            parse_line(OutputFormat::StreamJson, edit),
+            vec![
+                OutputActivity::FileEdit {
+                    path: "src/lib.rs".into(),
+                    action: "edit".into()
+                },
+                OutputActivity::ToolCall {
+                    name: "Bash".into(),
+                    detail: Some("cargo test".into())
+                },
+            ]
+        );
+        let result = r#"{"type":"result","result":"All done.\nREVIEW_VERDICT: OK","usage":{"input_tokens":120,"output_tokens":30,"cache_read_input_tokens":7}}"#;
+        assert_eq!(
+            parse_line(OutputFormat::StreamJson, result),
+            vec![OutputActivity::TokenUsage {
+                input_tokens: 120,
+                output_tokens: 30,
+                cached_input_tokens: 7
+            }]
+        );
+        let output = format!("{}\n{}\n", edit, result);
+        assert_eq!(
+            final_text(OutputFormat::StreamJson, &output),
+            "All done.\nREVIEW_VERDICT: OK"
+        );
+        assert!(parse_line(OutputFormat::Text, edit).is_empty());
+    }
+
+    #[test]
+    fn test_json_events_commands_file_changes_and_usage() {
+        let lines = [
+            r#"{"type":"thread.started","thread_id":"t1"}"#,
+            r#"{"type":"item.started","item":{"id":"i1","type":"command_execution","command":"ls -la"}}"#,
+            r#"{"type":"item.completed","item":{"id":"i2","type":"file_change","changes":[{"path":"a.rs","kind":"add"},{"path":"b.rs","kind":"update"}]}}"#,
+            r#"{"type":"item.completed","item":{"id":"i3","type":"agent_message","text":"Finished"}}"#,
+            r#"{"type":"turn.completed","usage":{"input_tokens":10,"cached_input_tokens":4,"output_tokens":5}}"#,
+            "not json at all",
+        ];
+        let activities: Vec<OutputActivity> = lines
+            .iter()
+            .flat_map(|line| parse_line(OutputFormat::JsonEvents, line))
+            .collect();
+        let kinds: Vec<&str> = activities.iter().map(|a| a.event_type()).collect();
+        assert_eq!(kinds, vec!["progress", "artifact", "artifact", "progress"]);
+        assert_eq!(activities[0].summary(), "shell: ls -la");
+        assert_eq!(activities[2].payload()["path"], "b.rs");
+        assert_eq!(activities[3].payload()["cached_input_tokens"], 4);
+        assert_eq!(
+            final_text(OutputFormat::JsonEvents, &lines.join("\n")),
+            "Finished"
+        );
+        assert!("xml".parse::<OutputFormat>().is_err());
+    }
+
+    #[test]
+    fn test_usage_from_text_sums_regex_matches() {
+        let usage = crate::tool::ToolPerformerUsage {
+            input_tokens_regex: Some(r"input tokens: ([\d,]+)".into()),
+            output_tokens_regex: Some(r"output tokens: (\d+)".into()),
+            cached_input_tokens_regex: None,
+        };
+        let output = "working...\ninput tokens: 1,200\noutput tokens: 40\ninput tokens: 300\n";
+        assert_eq!(
+            usage_from_text(&usage, output),
+            Some(OutputActivity::TokenUsage {
+                input_tokens: 1500,
+                output_tokens: 40,
+                cached_input_tokens: 0
+            })
+        );
+        assert_eq!(usage_from_text(&usage, "no usage here"), None);
+    }
+}
@@ -0,0 +1,251 @@
+//! Linux sandbox for performer tool processes (`performer.sandbox` /
+//! `automation.coordinator.sandbox.<tool>`).
+//!
+//! `bwrap` builds a fresh mount tree: system directories, the repository `.git` and the
+//! configured `read_only` paths are mounted read-only, the task worktree, its git admin directory
+//! and `read_write` paths are writable, everything else is absent. `unshare` keeps the host tree
+//! visible but remounts it read-only except for the same writable paths. Without `network` the
+//! tool gets an empty network namespace; `network: ["*"]` keeps the host network. Per-host lists
+//! are rejected by [`SandboxConfig::validate`], since nothing here could enforce them.
+
+use crate::config::SandboxConfig;
+use crate::{MaccError, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const SYSTEM_READ_ONLY: &[&str] = &[
+    "/usr",
+    "/bin",
+    "/sbin",
+    "/lib",
+    "/lib32",
+    "/lib64",
+    "/etc",
+    "/opt",
+    "/nix",
+    "/run/systemd/resolve",
+];
+
+/// Sandbox prepared for one tool call; removes its scratch directory when dropped.
+#[derive(Debug)]
+pub struct SandboxLaunch {
+    mode: String,
+    read_only: Vec<PathBuf>,
+    read_write: Vec<PathBuf>,
+    isolate_network: bool,
+    scratch: PathBuf,
+    cwd: PathBuf,
+}
+
+impl SandboxLaunch {
+    /// Plans the sandbox for calls running in `cwd` inside `worktree`; the directories of
+    /// `commands` are mounted read-only. Returns `None` when the mode is `none`.
+    pub fn prepare(
+        config: &SandboxConfig,
+        repo: &Path,
+        worktree: &Path,
+        cwd: &Path,
+        commands: &[PathBuf],
+    ) -> Result<Option<Self>> {
+        if !config.enabled() {
+            return Ok(None);
+        }
+        config.validate("performer")?;
+        let binary = if config.mode == "bwrap" {
+            "bwrap"
+        } else {
+            "unshare"
+        };
+        if super::invoke::resolve_command(binary).is_none() {
+            return Err(MaccError::Validation(format!(
+                "sandbox mode '{}' requires `{}` in PATH",
+                config.mode, binary
+            )));
+        }
+
+        let worktree = absolute(worktree);
+        let scratch = repo.join(".macc").join("tmp").join(format!(
+            "sandbox-{}-{}",
+            std::process::id(),
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        std::fs::create_dir_all(&scratch).map_err(|e| MaccError::Io {
+            path: scratch.to_string_lossy().into(),
+            action: "create sandbox scratch dir".into(),
+            source: e,
+        })?;
+        let scratch = absolute(&scratch);
+
+        let mut read_only: Vec<PathBuf> = SYSTEM_READ_ONLY.iter().map(PathBuf::from).collect();
+        let repo_git = absolute(repo).join(".git");
+        if repo_git.is_dir() {
+            read_only.push(repo_git);
+        }
+        for dir in commands.iter().filter_map(|command| command.parent()) {
+            read_only.push(absolute(dir));
+        }
+        read_only.extend(config.read_only.iter().map(|p| expand_home(p)));
+
+        let mut read_write = vec![worktree.clone()];
+        if let Some(admin) = worktree_git_dir(&worktree) {
+            read_write.push(admin);
+        }
+        read_write.extend(config.read_write.iter().map(|p| expand_home(p)));
+
+        Ok(Some(Self {
+            mode: config.mode.clone(),
+            read_only,
+            read_write,
+            isolate_network: config.network.is_empty(),
+            scratch,
+            cwd: absolute(cwd),
+        }))
+    }
+
+    /// Command running `program args` inside the sandbox.
+    pub fn command(&self, program: &str, args: &[String]) -> Command {
+        if self.mode == "bwrap" {
+            self.bwrap_command(program, args)
+        } else {
+            self.unshare_command(program, args)
+        }
+    }
+
+    fn bwrap_command(&self, program: &str, args: &[String]) -> Command {
+        let mut cmd = Command::new("bwrap");
+        cmd.arg("--die-with-parent").arg("--unshare-all");
+        if !self.isolate_network {
+            cmd.arg("--share-net");
+        }
+        cmd.args(["--proc", "/proc", "--dev", "/dev", "--tmpfs", "/tmp"]);
+        for path in self.read_only.iter().filter(|p| p.exists()) {
+            match std::fs::read_link(path) {
+                Ok(target) if path.parent() == Some(Path::new("/")) => {
+                    cmd.arg("--symlink").arg(target).arg(path);
+                }
+                _ => {
+                    cmd.arg("--ro-bind").arg(path).arg(path);
+                }
+            }
+        }
+        for path in self.read_write.iter().filter(|p| p.exists()) {
+            cmd.arg("--bind").arg(path).arg(path);
+        }
+        cmd.arg("--setenv").arg("TMPDIR").arg("/tmp");
+        cmd.arg("--chdir").arg(&self.cwd);
+        cmd.arg("--").arg(program).args(args);
+        cmd
+    }
+
+    fn unshare_command(&self, program: &str, args: &[String]) -> Command {
+        let mut script = String::from("set -e\nmount --make-rprivate /\n");
+        let writable = self
+            .read_write
+            .iter()
+            .chain(std::iter::once(&self.scratch))
+            .filter(|p| p.exists())
+            .collect::<Vec<_>>();
+        for path in &writable {
+            let path = sh_quote(&path.to_string_lossy());
+            script.push_str(&format!("mount --bind {} {}\n", path, path));
+        }
+        script.push_str(
+            "for m in $(awk '{print $5}' /proc/self/mountinfo); do mount -o remount,bind,ro \"$m\" 2>/dev/null || true; done\n",
+        );
+        for path in &writable {
+            script.push_str(&format!(
+                "mount -o remount,bind,rw {}\n",
+                sh_quote(&path.to_string_lossy())
+            ));
+        }
+        script.push_str(&format!("cd {}\n", sh_quote(&self.cwd.to_string_lossy())));
+        // Leave the namespace's root mapping so the tool runs with the caller's ids.
+        let ids = scratch_owner(&self.scratch);
+        match ids {
+            Some((uid, gid)) if uid != 0 => script.push_str(&format!(
+                "exec unshare --user --map-user={} --map-group={} -- \"$@\"\n",
+                uid, gid
+            )),
+            _ => script.push_str("exec \"$@\"\n"),
+        }
+
+        let mut cmd = Command::new("unshare");
+        cmd.args(["--user", "--map-root-user", "--mount"]);
+        if self.isolate_network {
+            cmd.arg("--net");
+        }
+        cmd.args(["--", "sh", "-c"])
+            .arg(script)
+            .arg("sh")
+            .arg(program)
+            .args(args)
+            .env("TMPDIR", &self.scratch);
+        cmd
+    }
+}
+
+impl Drop for SandboxLaunch {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.scratch);
+    }
+}
+
+/// Git admin directory of a linked worktree (`gitdir:` in its `.git` file).
+fn worktree_git_dir(worktree: &Path) -> Option<PathBuf> {
+    let raw = std::fs::read_to_string(worktree.join(".git")).ok()?;
+    let dir = raw.trim().strip_prefix("gitdir:")?.trim();
+    let dir = PathBuf::from(dir);
+    Some(if dir.is_absolute() {
+        dir
+    } else {
+        absolute(&worktree.join(dir))
+    })
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+fn absolute(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn sh_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+#[cfg(unix)]
+fn scratch_owner(path: &Path) -> Option<(u32, u32)> {
+    use std::os::unix::fs::MetadataExt;
+    std::fs::metadata(path).ok().map(|m| (m.uid(), m.gid()))
+}
+
+#[cfg(not(unix))]
+fn scratch_owner(_path: &Path) -> Option<(u32, u32)> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_worktree_git_dir_and_quoting() {
+        let root = std::env::temp_dir().join(format!(
+            "macc_sandbox_gitdir_{}",
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        std::fs::create_dir_all(root.join("repo/.git/worktrees/w1")).unwrap();
+        std::fs::create_dir_all(root.join("wt")).unwrap();
+        std::fs::write(root.join("wt/.git"), "gitdir: ../repo/.git/worktrees/w1\n").unwrap();
+        assert_eq!(
+            worktree_git_dir(&root.join("wt")),
+            Some(absolute(&root.join("repo/.git/worktrees/w1")))
+        );
+        assert_eq!(sh_quote("it's"), "'it'\\''s'");
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,205 @@
+//! Tool session leases stored in `.macc/state/tool-sessions.json`.
+//!
+//! Layout (shared with the coordinator's session sealing):
+//! `tools.<tool>.sessions.<key>.session_id` maps a scope key (a worktree path, or `project`)
+//! to the session to resume, and `tools.<tool>.leases.<session_id>` records which worktree
+//! currently owns that session.
+
+use crate::coordinator::session_manager::{
+    acquire_lock, persist_sessions_file, release_lock, TOOL_SESSIONS_REL_PATH,
+};
+use crate::{MaccError, Result};
+use serde_json::{json, Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub struct SessionStore {
+    path: PathBuf,
+    lock_dir: PathBuf,
+    tool_id: String,
+    key: String,
+    worktree: String,
+    task_id: String,
+    lease_ttl_seconds: i64,
+}
+
+impl SessionStore {
+    pub fn new(
+        repo_root: &Path,
+        tool_id: &str,
+        scope: &str,
+        worktree: &Path,
+        task_id: &str,
+        lease_ttl_seconds: u64,
+    ) -> Self {
+        let path = repo_root.join(TOOL_SESSIONS_REL_PATH);
+        let worktree = worktree.to_string_lossy().to_string();
+        let key = if scope == "project" {
+            "project".to_string()
+        } else {
+            worktree.clone()
+        };
+        Self {
+            lock_dir: path.with_extension("json.lock"),
+            path,
+            tool_id: tool_id.to_string(),
+            key,
+            worktree,
+            task_id: task_id.to_string(),
+            lease_ttl_seconds: lease_ttl_seconds as i64,
+        }
+    }
+
+    /// Session stored for this scope, leased to this worktree unless another live worktree owns it.
+    pub fn claim_stored(&self) -> Result<Option<String>> {
+        self.locked(|root| {
+            let sid = root["tools"][&self.tool_id]["sessions"][&self.key]["session_id"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string();
+            if sid.is_empty() || self.occupied_by_other(root, &sid) {
+                return None;
+            }
+            self.write_active_lease(root, &sid);
+            Some(sid)
+        })
+    }
+
+    /// Generates a fresh session id (for `id_strategy: generated`) and leases it.
+    pub fn reserve_generated(&self) -> Result<Option<String>> {
+        self.locked(|root| {
+            for _ in 0..10 {
+                let sid = generate_session_id();
+                if sid.is_empty() || self.occupied_by_other(root, &sid) {
+                    continue;
+                }
+                self.write_active_lease(root, &sid);
+                return Some(sid);
+            }
+            None
+        })
+    }
+
+    /// Records `sid` as this scope's session; returns false when another worktree holds it.
+    pub fn claim(&self, sid: &str) -> Result<bool> {
+        self.locked(|root| {
+            if self.occupied_by_other(root, sid) {
+                return false;
+            }
+            self.write_active_lease(root, sid);
+            true
+        })
+    }
+
+    pub fn release(&self, sid: &str) -> Result<()> {
+        if !self.path.exists() {
+            return Ok(());
+        }
+        self.locked(|root| {
+            let now = chrono::Utc::now();
+            let pointer = format!(
+                "/tools/{}/leases/{}",
+                escape_pointer(&self.tool_id),
+                escape_pointer(sid)
+            );
+            if let Some(lease) = root.pointer_mut(&pointer).and_then(Value::as_object_mut) {
+                lease.insert("status".into(), json!("released"));
+                lease.insert("heartbeat_epoch".into(), json!(now.timestamp()));
+                lease.insert("updated_at".into(), json!(iso(now)));
+            }
+        })
+    }
+
+    fn occupied_by_other(&self, root: &Value, sid: &str) -> bool {
+        let lease = &root["tools"][&self.tool_id]["leases"][sid];
+        let owner = lease["owner_worktree"].as_str().unwrap_or_default();
+        if owner.is_empty() || owner == self.worktree {
+            return false;
+        }
+        if lease["status"].as_str() != Some("active") {
+            return false;
+        }
+        let owner_path = Path::new(owner);
+        if !owner_path.is_dir() || !owner_path.join(".git").exists() {
+            return false;
+        }
+        let heartbeat = lease["heartbeat_epoch"].as_i64().unwrap_or(0);
+        chrono::Utc::now().timestamp() - heartbeat <= self.lease_ttl_seconds
+    }
+
+    fn write_active_lease(&self, root: &mut Value, sid: &str) {
+        let now = chrono::Utc::now();
+        let tool = object_entry(object_entry(root, "tools"), &self.tool_id);
+        object_entry(tool, "sessions")[&self.key] =
+            json!({ "session_id": sid, "updated_at": iso(now) });
+        object_entry(tool, "leases")[sid] = json!({
+            "owner_worktree": self.worktree,
+            "owner_task_id": self.task_id,
+            "owner_pid": std::process::id().to_string(),
+            "status": "active",
+            "heartbeat_epoch": now.timestamp(),
+            "updated_at": iso(now),
+        });
+    }
+
+    fn locked<T>(&self, f: impl FnOnce(&mut Value) -> T) -> Result<T> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).map_err(|e| MaccError::Io {
+                path: parent.to_string_lossy().into(),
+                action: "create tool sessions dir".into(),
+                source: e,
+            })?;
+        }
+        acquire_lock(&self.lock_dir)?;
+        let result = (|| {
+            let mut root = match fs::read_to_string(&self.path) {
+                Ok(raw) => serde_json::from_str(&raw).map_err(|e| {
+                    MaccError::Validation(format!(
+                        "Failed to parse sessions file '{}': {}",
+                        self.path.display(),
+                        e
+                    ))
+                })?,
+                Err(_) => json!({}),
+            };
+            if !root.is_object() {
+                root = json!({});
+            }
+            let before = root.clone();
+            let out = f(&mut root);
+            if root != before {
+                persist_sessions_file(&self.path, &root)?;
+            }
+            Ok(out)
+        })();
+        release_lock(&self.lock_dir);
+        result
+    }
+}
+
+fn object_entry<'a>(value: &'a mut Value, key: &str) -> &'a mut Value {
+    let map = value
+        .as_object_mut()
+        .expect("session state node is an object");
+    let entry = map
+        .entry(key.to_string())
+        .or_insert_with(|| Value::Object(Map::new()));
+    if !entry.is_object() {
+        *entry = Value::Object(Map::new());
+    }
+    entry
+}
+
+fn escape_pointer(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+fn iso(now: chrono::DateTime<chrono::Utc>) -> String {
+    now.format("%Y-%m-%dT%H:%M:%SZ").to_string()
+}
+
+fn generate_session_id() -> String {
+    fs::read_to_string("/proc/sys/kernel/random/uuid")
+        .map(|s| s.trim().to_string())
+        .unwrap_or_else(|_| chrono::Utc::now().format("%Y%m%dT%H%M%S%9f").to_string())
+}
@@ -0,0 +1,156 @@
+//! Classifies failed tool calls caused by provider throttling (rate limits, exhausted quota)
+//! and reads the advertised wait, so the coordinator can back off instead of retrying at once.
+
+use crate::tool::ToolPerformerSpec;
+
+/// Error code reported for provider throttling.
+pub const THROTTLE_ERROR_CODE: &str = "E104";
+
+/// Throttled failures a task may be requeued for before it takes the normal retry/block path.
+pub const MAX_THROTTLE_REQUEUES: u64 = 5;
+
+const DEFAULT_PATTERNS: &[&str] = &[
+    r"rate[ _-]?limit",
+    r"too many requests",
+    r"(?:http(?:/[\d.]+)?|status|code)\W{0,3}429\b",
+    r"\b429 too many",
+    r"quota (?:exceeded|exhausted)",
+    r"exceeded (?:your )?(?:current )?quota",
+    r"insufficient_quota",
+    r"resource[ _]exhausted",
+    r"overloaded_error",
+    r"\b(?:model|server|service|api|provider) (?:is )?(?:currently )?overloaded\b",
+];
+
+const UNIT: &str = r"\s*(ms|milliseconds?|s|secs?|seconds?|m|mins?|minutes?|h|hours?)?\b";
+
+const DEFAULT_RETRY_AFTER_PREFIXES: &[&str] = &[
+    r"retry[- _]?after\W{0,3}",
+    r"(?:try again|retry) in (?:about )?",
+    r"resets? in ",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThrottleSignal {
+    /// Output line that matched a throttling pattern.
+    pub line: String,
+    pub retry_after_seconds: Option<u64>,
+}
+
+/// Throttling signal found in a failed call's output, if any.
+pub fn detect_throttle(spec: &ToolPerformerSpec, output: &str) -> Option<ThrottleSignal> {
+    let throttle = spec.throttle.as_ref();
+    let patterns = DEFAULT_PATTERNS
+        .iter()
+        .map(|p| p.to_string())
+        .chain(
+            throttle
+                .into_iter()
+                .flat_map(|t| t.patterns.iter().cloned()),
+        )
+        .filter_map(compile)
+        .collect::<Vec<_>>();
+    let line = output
+        .lines()
+        .rev()
+        .find(|line| patterns.iter().any(|re| re.is_match(line)))?;
+
+    let retry_after_regexes = throttle
+        .and_then(|t| t.retry_after_regex.clone())
+        .into_iter()
+        .chain(
+            DEFAULT_RETRY_AFTER_PREFIXES
+                .iter()
+                .map(|prefix| format!(r"{}(\d+(?:\.\d+)?){}", prefix, UNIT)),
+        )
+        .filter_map(compile)
+        .collect::<Vec<_>>();
+    let retry_after_seconds = output.lines().rev().find_map(|line| {
+        retry_after_regexes.iter().find_map(|re| {
+            let caps = re.captures(line)?;
+            let value = caps.get(1)?.as_str().parse::<f64>().ok()?;
+            Some(to_seconds(value, caps.get(2).map(|m| m.as_str())))
+        })
+    });
+    Some(ThrottleSignal {
+        line: line.trim().to_string(),
+        retry_after_seconds,
+    })
+}
+
+// ToolSpec validation rejects bad patterns at load; a tool.json edited by hand can still carry one.
+fn compile(pattern: String) -> Option<regex::Regex> {
+    match regex::Regex::new(&format!("(?i){}", pattern)) {
+        Ok(re) => Some(re),
+        Err(e) => {
+            tracing::warn!("ignoring invalid throttle regex '{}': {}", pattern, e);
+            None
+        }
+    }
+}
+
+fn to_seconds(value: f64, unit: Option<&str>) -> u64 {
+    let unit = unit.unwrap_or("s").to_ascii_lowercase();
+    let seconds = if unit.starts_with("ms") || unit.starts_with("milli") {
+        value / 1000.0
+    } else if unit.starts_with('m') {
+        value * 60.0
+    } else if unit.starts_with('h') {
+        value * 3600.0
+    } else {
+        value
+    };
+    seconds.ceil().max(1.0) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(throttle: serde_json::Value) -> ToolPerformerSpec {
+        serde_json::from_value(serde_json::json!({
+            "runner": "unused",
+            "command": "tool",
+            "throttle": throttle,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_detects_rate_limits_and_retry_after() {
+        let default = spec(serde_json::Value::Null);
+        let signal = detect_throttle(
+            &default,
+            "working\nError: 429 Too Many Requests\nPlease try again in 1.5m\n",
+        )
+        .expect("throttled");
+        assert_eq!(signal.line, "Error: 429 Too Many Requests");
+        assert_eq!(signal.retry_after_seconds, Some(90));
+
+        let signal = detect_throttle(&default, "rate_limit_error\nRetry-After: 250ms").unwrap();
+        assert_eq!(signal.retry_after_seconds, Some(1));
+        assert_eq!(
+            detect_throttle(&default, "Quota exceeded for model").map(|s| s.retry_after_seconds),
+            Some(None)
+        );
+        assert!(detect_throttle(&default, "compile error: expected `;`").is_none());
+        assert!(detect_throttle(
+            &default,
+            "error[E0308]: mismatched types\n --> src/foo.rs:429:5"
+        )
+        .is_none());
+        assert!(
+            detect_throttle(&default, "no overloaded operator matches these operands").is_none()
+        );
+        assert!(detect_throttle(&default, "HTTP 429: slow down").is_some());
+        assert!(detect_throttle(&default, "API is overloaded, please retry").is_some());
+        assert!(detect_throttle(&default, "{\"type\":\"overloaded_error\"}").is_some());
+
+        let custom = spec(serde_json::json!({
+            "patterns": ["capacity reached"],
+            "retry_after_regex": r"cool down (\d+) (s|m)",
+        }));
+        let signal = detect_throttle(&custom, "Capacity reached, cool down 2 m").unwrap();
+        assert_eq!(signal.retry_after_seconds, Some(120));
+    }
+}
@@ -3,6 +3,7 @@ use crate::{
     plan::diff::{generate_unified_diff, is_text_file, normalize_json},
     security,
 };
+use std::collections::BTreeSet;
 
 const MAX_DIFF_LINES: usize = 600;
 const MAX_DIFF_BYTES: usize = 64 * 1024;
@@ -56,15 +57,17 @@ fn render_json_diff(op: &PlannedOp, after: &[u8]) -> Option<DiffView> {
     }
 
     let normalized_after = normalize_json(after)?;
-    let after_bytes = sanitize_text(&op.path, &normalized_after).into_bytes();
+    let after_bytes = sanitize_text(&op.path, &normalized_after, &op.sensitive_values).into_bytes();
 
     let before_bytes = op.before.as_deref().and_then(|before| {
         normalize_json(before)
-            .map(|normalized| sanitize_text(&op.path, &normalized).into_bytes())
+            .map(|normalized| {
+                sanitize_text(&op.path, &normalized, &op.sensitive_values).into_bytes()
+            })
             .or_else(|| {
                 std::str::from_utf8(before)
                     .ok()
-                    .map(|raw| sanitize_text(&op.path, raw).into_bytes())
+                    .map(|raw| sanitize_text(&op.path, raw, &op.sensitive_values).into_bytes())
             })
     });
 
@@ -85,12 +88,12 @@ fn render_text_diff(op: &PlannedOp, after: &[u8]) -> Option<DiffView> {
     }
 
     let after_str = std::str::from_utf8(after).ok()?;
-    let after_bytes = sanitize_text(&op.path, after_str).into_bytes();
+    let after_bytes = sanitize_text(&op.path, after_str, &op.sensitive_values).into_bytes();
 
     let before_bytes = op.before.as_deref().and_then(|before| {
         std::str::from_utf8(before)
             .ok()
-            .map(|raw| sanitize_text(&op.path, raw).into_bytes())
+            .map(|raw| sanitize_text(&op.path, raw, &op.sensitive_values).into_bytes())
     });
 
     let diff = generate_unified_diff(&op.path, before_bytes.as_deref(), after_bytes.as_slice());
@@ -104,8 +107,13 @@ fn render_text_diff(op: &PlannedOp, after: &[u8]) -> Option<DiffView> {
     })
 }
 
-fn sanitize_text(path: &str, value: &str) -> String {
+fn sanitize_text(path: &str, value: &str, sensitive_values: &BTreeSet<String>) -> String {
     let mut findings = security::scan_bytes(path, value.as_bytes());
+    findings.extend(security::scan_sensitive_values(
+        path,
+        value.as_bytes(),
+        sensitive_values,
+    ));
     if findings.is_empty() {
         return value.to_string();
     }
@@ -204,6 +212,7 @@ mod tests {
             metadata: PlannedOpMetadata::default(),
             before: before.map(|b| b.to_vec()),
             after: after.map(|b| b.to_vec()),
+            sensitive_values: Default::default(),
         }
     }
 
@@ -231,6 +240,7 @@ mod tests {
             metadata: PlannedOpMetadata::default(),
             before: Some(before.to_vec()),
             after: Some(after.to_vec()),
+            sensitive_values: Default::default(),
         };
 
         let view = diff_for(&op);
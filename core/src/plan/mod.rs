@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 
 pub mod builders;
 pub mod diff;
@@ -21,6 +22,10 @@ pub enum Scope {
 #[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq)]
 pub struct ActionPlan {
     pub actions: Vec<Action>,
+    /// Values resolved from config references (`${env:..}`, `${file:..}`, `${cmd:..}`) for this
+    /// plan run: project-scope writes containing them are rejected and previews mask them.
+    #[serde(skip)]
+    pub sensitive_values: BTreeSet<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
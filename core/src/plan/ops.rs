@@ -35,6 +35,9 @@ pub struct PlannedOp {
     pub metadata: PlannedOpMetadata,
     pub before: Option<Vec<u8>>,
     pub after: Option<Vec<u8>>,
+    /// Resolved config references of the plan run, masked by [`crate::plan::render_diff`].
+    #[serde(skip)]
+    pub sensitive_values: BTreeSet<String>,
 }
 
 struct OperationAccumulator {
@@ -120,6 +123,7 @@ impl OperationAccumulator {
             },
             before,
             after,
+            sensitive_values: BTreeSet::new(),
         })
     }
 }
@@ -174,6 +178,10 @@ pub fn collect_plan_operations(paths: &ProjectPaths, plan: &ActionPlan) -> Vec<P
     let mut ops: Vec<PlannedOp> = accumulator
         .into_iter()
         .filter_map(|(path, entry)| entry.into_planned_op(paths, path))
+        .map(|op| PlannedOp {
+            sensitive_values: plan.sensitive_values.clone(),
+            ..op
+        })
         .collect();
 
     ops.sort_by(|a, b| a.path.cmp(&b.path).then(a.kind.cmp(&b.kind)));
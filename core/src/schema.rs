@@ -0,0 +1,648 @@
+//! JSON Schemas for `macc.yaml` and ToolSpec files, generated from the Rust types,
+//! plus a collecting validator that reports every issue with its YAML path and line.
+
+use crate::config::CanonicalConfig;
+use crate::tool::ToolSpec;
+use crate::{MaccError, Result};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+pub const CONFIG_SCHEMA_FILE: &str = "macc-config.schema.json";
+pub const TOOLSPEC_SCHEMA_FILE: &str = "toolspec.schema.json";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaIssue {
+    /// Dotted YAML path (`automation.coordinator.max_parallel`, `fields[0].kind`); empty for the document root.
+    pub path: String,
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl std::fmt::Display for SchemaIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let path = if self.path.is_empty() {
+            "<root>"
+        } else {
+            self.path.as_str()
+        };
+        match self.line {
+            Some(line) => write!(f, "line {}: {}: {}", line, path, self.message),
+            None => write!(f, "{}: {}", path, self.message),
+        }
+    }
+}
+
+pub fn canonical_config_schema() -> Value {
+    let schema = schemars::schema_for!(CanonicalConfig);
+    with_id(
+        serde_json::to_value(schema).unwrap_or(Value::Null),
+        CONFIG_SCHEMA_FILE,
+        "MACC canonical config (macc.yaml)",
+    )
+}
+
+pub fn tool_spec_schema() -> Value {
+    let schema = schemars::schema_for!(ToolSpec);
+    with_id(
+        serde_json::to_value(schema).unwrap_or(Value::Null),
+        TOOLSPEC_SCHEMA_FILE,
+        "MACC ToolSpec",
+    )
+}
+
+fn with_id(mut schema: Value, file: &str, title: &str) -> Value {
+    if let Value::Object(map) = &mut schema {
+        map.insert(
+            "$id".to_string(),
+            Value::String(format!("https://macc.local/schemas/{}", file)),
+        );
+        map.insert("title".to_string(), Value::String(title.to_string()));
+    }
+    schema
+}
+
+pub fn render_schema(schema: &Value) -> String {
+    let mut rendered = serde_json::to_string_pretty(schema).unwrap_or_default();
+    rendered.push('\n');
+    rendered
+}
+
+/// Writes both schemas into `dir` and returns the written paths.
+pub fn write_schemas(dir: &Path) -> Result<Vec<PathBuf>> {
+    std::fs::create_dir_all(dir).map_err(|e| MaccError::Io {
+        path: dir.to_string_lossy().into(),
+        action: "create schema output directory".into(),
+        source: e,
+    })?;
+    let mut written = Vec::new();
+    for (file, schema) in [
+        (CONFIG_SCHEMA_FILE, canonical_config_schema()),
+        (TOOLSPEC_SCHEMA_FILE, tool_spec_schema()),
+    ] {
+        let path = dir.join(file);
+        std::fs::write(&path, render_schema(&schema)).map_err(|e| MaccError::Io {
+            path: path.to_string_lossy().into(),
+            action: "write schema".into(),
+            source: e,
+        })?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+/// Validates one `macc.yaml` layer. Overlays are partial, so `required` is only enforced
+/// when `partial` is false.
+pub fn validate_config_yaml(content: &str, partial: bool) -> Vec<SchemaIssue> {
+    let schema = canonical_config_schema();
+    let mut issues = validate_yaml(content, &schema, partial);
+    if issues.is_empty() && !partial {
+        match CanonicalConfig::from_yaml(content) {
+            Ok(config) => {
+                if let Err(err) = config.validate() {
+                    issues.push(SchemaIssue {
+                        path: String::new(),
+                        line: None,
+                        message: err.to_string(),
+                    });
+                }
+            }
+            Err(err) => issues.push(serde_issue(&err)),
+        }
+    }
+    issues
+}
+
+pub fn validate_tool_spec_yaml(content: &str) -> Vec<SchemaIssue> {
+    let schema = tool_spec_schema();
+    let mut issues = validate_yaml(content, &schema, false);
+    if issues.is_empty() {
+        if let Err(err) = ToolSpec::from_yaml(content) {
+            let (line, message) = match err {
+                MaccError::ToolSpec { line, message, .. } => (line, message),
+                other => (None, other.to_string()),
+            };
+            issues.push(SchemaIssue {
+                path: String::new(),
+                line,
+                message,
+            });
+        }
+    }
+    issues
+}
+
+fn serde_issue(err: &serde_yaml::Error) -> SchemaIssue {
+    SchemaIssue {
+        path: String::new(),
+        line: err.location().map(|loc| loc.line()),
+        message: err.to_string(),
+    }
+}
+
+fn validate_yaml(content: &str, schema: &Value, partial: bool) -> Vec<SchemaIssue> {
+    let yaml: serde_yaml::Value = match serde_yaml::from_str(content) {
+        Ok(value) => value,
+        Err(err) => return vec![serde_issue(&err)],
+    };
+    let instance = if yaml.is_null() {
+        Value::Object(Map::new())
+    } else {
+        match serde_json::to_value(&yaml) {
+            Ok(value) => value,
+            Err(err) => {
+                return vec![SchemaIssue {
+                    path: String::new(),
+                    line: None,
+                    message: format!("unsupported YAML value: {}", err),
+                }]
+            }
+        }
+    };
+
+    let validator = Validator {
+        root: schema,
+        partial,
+    };
+    let mut raw = Vec::new();
+    validator.check(&instance, schema, String::new(), &mut raw);
+
+    let lines = yaml_line_index(content);
+    raw.into_iter()
+        .map(|(path, message)| SchemaIssue {
+            line: lookup_line(&lines, &path),
+            path,
+            message,
+        })
+        .collect()
+}
+
+struct Validator<'a> {
+    root: &'a Value,
+    partial: bool,
+}
+
+impl<'a> Validator<'a> {
+    fn resolve<'s>(&'s self, schema: &'s Value) -> &'s Value {
+        let mut current = schema;
+        for _ in 0..16 {
+            let Some(reference) = current.get("$ref").and_then(Value::as_str) else {
+                break;
+            };
+            let Some(pointer) = reference.strip_prefix('#') else {
+                break;
+            };
+            match self.root.pointer(pointer) {
+                Some(target) => current = target,
+                None => break,
+            }
+        }
+        current
+    }
+
+    fn check(
+        &self,
+        instance: &Value,
+        schema: &Value,
+        path: String,
+        out: &mut Vec<(String, String)>,
+    ) {
+        let schema = self.resolve(schema);
+        if schema.as_bool() == Some(true) || schema.as_object().is_some_and(Map::is_empty) {
+            return;
+        }
+
+        if let Some(all) = schema.get("allOf").and_then(Value::as_array) {
+            for sub in all {
+                self.check(instance, sub, path.clone(), out);
+            }
+        }
+        for key in ["anyOf", "oneOf"] {
+            if let Some(options) = schema.get(key).and_then(Value::as_array) {
+                self.check_alternatives(instance, options, &path, out);
+            }
+        }
+
+        if let Some(expected) = schema.get("type") {
+            if !type_matches(instance, expected) {
+                out.push((
+                    path,
+                    format!(
+                        "expected {}, found {}",
+                        describe_type(expected),
+                        json_type(instance)
+                    ),
+                ));
+                return;
+            }
+        }
+
+        if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+            if !allowed.contains(instance) {
+                let options: Vec<String> = allowed.iter().map(|v| v.to_string()).collect();
+                out.push((
+                    path.clone(),
+                    format!("value {} is not one of [{}]", instance, options.join(", ")),
+                ));
+            }
+        }
+        if let Some(constant) = schema.get("const") {
+            if constant != instance {
+                out.push((path.clone(), format!("expected constant {}", constant)));
+            }
+        }
+        if let (Some(min), Some(n)) = (
+            schema.get("minimum").and_then(Value::as_f64),
+            instance.as_f64(),
+        ) {
+            if n < min {
+                out.push((path.clone(), format!("must be >= {}", min)));
+            }
+        }
+
+        match instance {
+            Value::Object(map) => self.check_object(map, schema, &path, out),
+            Value::Array(items) => {
+                if let Some(item_schema) = schema.get("items") {
+                    for (index, item) in items.iter().enumerate() {
+                        self.check(item, item_schema, format!("{}[{}]", path, index), out);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn check_object(
+        &self,
+        map: &Map<String, Value>,
+        schema: &Value,
+        path: &str,
+        out: &mut Vec<(String, String)>,
+    ) {
+        let properties = schema.get("properties").and_then(Value::as_object);
+        if !self.partial {
+            if let Some(required) = schema.get("required").and_then(Value::as_array) {
+                for key in required.iter().filter_map(Value::as_str) {
+                    if !map.contains_key(key) {
+                        out.push((path.to_string(), format!("missing field `{}`", key)));
+                    }
+                }
+            }
+        }
+        for (key, value) in map {
+            let child = join_path(path, key);
+            if let Some(prop_schema) = properties.and_then(|p| p.get(key)) {
+                self.check(value, prop_schema, child, out);
+                continue;
+            }
+            match schema.get("additionalProperties") {
+                Some(Value::Bool(false)) => {
+                    let mut message = format!("unknown field `{}`", key);
+                    if let Some(props) = properties {
+                        let known: Vec<&str> = props.keys().map(String::as_str).collect();
+                        message.push_str(&format!(", expected one of: {}", known.join(", ")));
+                    }
+                    out.push((child, message));
+                }
+                Some(extra) if extra.is_object() => self.check(value, extra, child, out),
+                _ => {}
+            }
+        }
+    }
+
+    fn check_alternatives(
+        &self,
+        instance: &Value,
+        options: &[Value],
+        path: &str,
+        out: &mut Vec<(String, String)>,
+    ) {
+        // Internally tagged enums: pick the variant by its tag instead of guessing.
+        if let Some((tag, variants)) = self.discriminator(options) {
+            if let Some(value) = instance.as_object().and_then(|map| map.get(&tag)) {
+                match variants.iter().find(|(name, _)| name == value) {
+                    Some((_, option)) => self.check(instance, option, path.to_string(), out),
+                    None => {
+                        let names: Vec<String> =
+                            variants.iter().map(|(name, _)| name.to_string()).collect();
+                        out.push((
+                            join_path(path, &tag),
+                            format!("value {} is not one of [{}]", value, names.join(", ")),
+                        ));
+                    }
+                }
+                return;
+            }
+        }
+
+        // Prefer variants whose top-level type fits, so `Option<T>` reports T's errors
+        // rather than "expected null".
+        let fitting: Vec<&Value> = options
+            .iter()
+            .filter(|option| {
+                self.resolve(option)
+                    .get("type")
+                    .is_none_or(|expected| type_matches(instance, expected))
+            })
+            .collect();
+        let candidates: Vec<&Value> = if fitting.is_empty() {
+            options.iter().collect()
+        } else {
+            fitting
+        };
+
+        let mut best: Option<Vec<(String, String)>> = None;
+        for option in candidates {
+            let mut attempt = Vec::new();
+            self.check(instance, option, path.to_string(), &mut attempt);
+            if attempt.is_empty() {
+                return;
+            }
+            if best.as_ref().is_none_or(|b| attempt.len() < b.len()) {
+                best = Some(attempt);
+            }
+        }
+        if let Some(best) = best {
+            out.extend(best);
+        }
+    }
+
+    /// Returns the tag property shared by all variants and each variant's tag value.
+    fn discriminator<'s>(
+        &'s self,
+        options: &'s [Value],
+    ) -> Option<(String, Vec<(Value, &'s Value)>)> {
+        let first = self
+            .resolve(options.first()?)
+            .get("properties")?
+            .as_object()?;
+        'keys: for key in first.keys() {
+            let mut variants = Vec::new();
+            for option in options {
+                let tag = self
+                    .resolve(option)
+                    .get("properties")
+                    .and_then(|p| p.get(key))
+                    .and_then(|p| p.get("enum"))
+                    .and_then(Value::as_array)
+                    .filter(|values| values.len() == 1);
+                match tag {
+                    Some(values) => variants.push((values[0].clone(), option)),
+                    None => continue 'keys,
+                }
+            }
+            return Some((key.clone(), variants));
+        }
+        None
+    }
+}
+
+fn join_path(parent: &str, key: &str) -> String {
+    if parent.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", parent, key)
+    }
+}
+
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn type_matches(instance: &Value, expected: &Value) -> bool {
+    let matches_one = |name: &str| match name {
+        "number" => instance.is_number(),
+        "integer" => json_type(instance) == "integer",
+        other => json_type(instance) == other,
+    };
+    match expected {
+        Value::String(name) => matches_one(name),
+        Value::Array(names) => names.iter().filter_map(Value::as_str).any(matches_one),
+        _ => true,
+    }
+}
+
+fn describe_type(expected: &Value) -> String {
+    match expected {
+        Value::Array(names) => names
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>()
+            .join(" or "),
+        Value::String(name) => name.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Maps dotted YAML paths to 1-based line numbers for block-style documents.
+///
+/// Flow collections (`[a, b]`, `{a: 1}`) are indexed at the line of their key only.
+pub fn yaml_line_index(content: &str) -> BTreeMap<String, usize> {
+    let mut index = BTreeMap::new();
+    // (indent, path, next sequence index for "- " items directly under this node)
+    let mut stack: Vec<(usize, String, usize)> = Vec::new();
+
+    for (line_no, raw) in content.lines().enumerate() {
+        let line_no = line_no + 1;
+        let trimmed = raw.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with("---") {
+            continue;
+        }
+        let mut indent = raw.len() - trimmed.len();
+        let mut rest = trimmed;
+
+        loop {
+            while stack.last().is_some_and(|(i, _, _)| *i > indent) {
+                stack.pop();
+            }
+            if let Some(item) =
+                rest.strip_prefix("- ")
+                    .or_else(|| if rest == "-" { Some("") } else { None })
+            {
+                // Sequence item: its parent is the nearest node with indent <= current,
+                // which also covers `key:` followed by items at the same indent.
+                let (parent, seq) = match stack.last_mut() {
+                    Some((i, p, seq)) if *i <= indent => {
+                        let current = *seq;
+                        *seq += 1;
+                        (p.clone(), current)
+                    }
+                    _ => (String::new(), 0),
+                };
+                let item_path = format!("{}[{}]", parent, seq);
+                index.entry(item_path.clone()).or_insert(line_no);
+                let offset = rest.len() - item.len();
+                indent += offset;
+                stack.push((indent, item_path, 0));
+                rest = item.trim_start();
+                if rest.is_empty() {
+                    break;
+                }
+                continue;
+            }
+
+            if let Some((key, _)) = split_key(rest) {
+                // Siblings at the same indent are popped, but not the sequence item whose
+                // first key sits on the `- ` line.
+                while stack
+                    .last()
+                    .is_some_and(|(i, p, _)| *i >= indent && !(*i == indent && p.ends_with(']')))
+                {
+                    stack.pop();
+                }
+                let parent = stack.last().map(|(_, p, _)| p.clone()).unwrap_or_default();
+                let path = join_path(&parent, &key);
+                index.entry(path.clone()).or_insert(line_no);
+                stack.push((indent, path, 0));
+            }
+            break;
+        }
+    }
+    index
+}
+
+fn split_key(text: &str) -> Option<(String, &str)> {
+    let bytes = text.as_bytes();
+    let (key, after) = if bytes.first() == Some(&b'"') || bytes.first() == Some(&b'\'') {
+        let quote = bytes[0] as char;
+        let end = text[1..].find(quote)? + 1;
+        (text[1..end].to_string(), &text[end + 1..])
+    } else {
+        let pos = text.find(": ").or_else(|| {
+            if text.ends_with(':') {
+                Some(text.len() - 1)
+            } else {
+                None
+            }
+        })?;
+        (text[..pos].trim().to_string(), &text[pos..])
+    };
+    let after = after.strip_prefix(':')?;
+    if key.is_empty() || key.contains(['{', '[']) {
+        return None;
+    }
+    Some((key, after))
+}
+
+fn lookup_line(index: &BTreeMap<String, usize>, path: &str) -> Option<usize> {
+    let mut current = path.to_string();
+    loop {
+        if let Some(line) = index.get(&current) {
+            return Some(*line);
+        }
+        let cut = current.rfind(['.', '['])?;
+        current.truncate(cut);
+        if current.is_empty() {
+            return None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checked_in_schemas_match_generated() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../docs/schemas");
+        for (file, schema) in [
+            (CONFIG_SCHEMA_FILE, canonical_config_schema()),
+            (TOOLSPEC_SCHEMA_FILE, tool_spec_schema()),
+        ] {
+            let on_disk = std::fs::read_to_string(dir.join(file)).expect("read checked-in schema");
+            assert_eq!(
+                on_disk,
+                render_schema(&schema),
+                "{} is stale; regenerate with `macc config validate --schema-out docs/schemas`",
+                file
+            );
+        }
+    }
+
+    #[test]
+    fn test_config_validation_reports_every_issue_with_lines() {
+        let yaml = "tools:\n  enabled: []\nautomation:\n  coordinator:\n    max_parallel: many\n    bogus_key: 1\nselections:\n  skills: nope\nextra: true\n";
+        let issues = validate_config_yaml(yaml, false);
+        let rendered: Vec<String> = issues.iter().map(ToString::to_string).collect();
+
+        assert_eq!(issues.len(), 4, "{:#?}", rendered);
+        let find = |path: &str| issues.iter().find(|i| i.path == path).cloned();
+        let max_parallel = find("automation.coordinator.max_parallel").unwrap();
+        assert_eq!(max_parallel.line, Some(5));
+        assert!(max_parallel.message.contains("expected integer"));
+        assert_eq!(
+            find("automation.coordinator.bogus_key").unwrap().line,
+            Some(6)
+        );
+        assert_eq!(find("selections.skills").unwrap().line, Some(8));
+        let extra = find("extra").unwrap();
+        assert_eq!(extra.line, Some(9));
+        assert!(extra.message.contains("unknown field `extra`"));
+    }
+
+    #[test]
+    fn test_partial_overlay_skips_required_fields() {
+        let overlay = "automation:\n  coordinator:\n    max_parallel: 1\n";
+        assert!(validate_config_yaml(overlay, true).is_empty());
+        let issues = validate_config_yaml(overlay, false);
+        assert!(issues
+            .iter()
+            .any(|issue| issue.message.contains("missing field `tools`")));
+    }
+
+    #[test]
+    fn test_tool_spec_validation_reports_nested_paths() {
+        let yaml = "api_version: v1\nid: tool-a\ndisplay_name: Tool A\nfields:\n  - id: model\n    label: Model\n    kind:\n      type: enumeration\ndoctor:\n  - kind: which\n    value: tool-a\n    severity: fatal\n";
+        let issues = validate_tool_spec_yaml(yaml);
+        assert!(
+            issues
+                .iter()
+                .any(|i| i.path == "fields[0].kind.type" && i.line == Some(8)),
+            "{:#?}",
+            issues
+        );
+        assert!(
+            issues
+                .iter()
+                .any(|i| i.path == "doctor[0].severity" && i.line == Some(12)),
+            "{:#?}",
+            issues
+        );
+    }
+
+    #[test]
+    fn test_registry_tool_specs_are_valid() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../registry/tools.d");
+        let mut checked = 0;
+        for entry in std::fs::read_dir(&dir).unwrap() {
+            let path = entry.unwrap().path();
+            if !path.to_string_lossy().ends_with(".tool.yaml") {
+                continue;
+            }
+            let issues = validate_tool_spec_yaml(&std::fs::read_to_string(&path).unwrap());
+            assert!(issues.is_empty(), "{}: {:#?}", path.display(), issues);
+            checked += 1;
+        }
+        assert!(checked > 0);
+    }
+
+    #[test]
+    fn test_yaml_line_index_handles_sequences() {
+        let yaml = "a:\n  b: 1\n  list:\n  - x: 1\n    y: 2\n  - x: 3\nc: [1, 2]\n";
+        let index = yaml_line_index(yaml);
+        assert_eq!(index.get("a.b"), Some(&2));
+        assert_eq!(index.get("a.list[0].x"), Some(&4));
+        assert_eq!(index.get("a.list[0].y"), Some(&5));
+        assert_eq!(index.get("a.list[1].x"), Some(&6));
+        assert_eq!(index.get("c"), Some(&7));
+    }
+}
@@ -1,6 +1,9 @@
 pub mod secret_scan;
 
-pub use secret_scan::{scan_bytes, Finding, Severity};
+pub use secret_scan::{
+    redact_sensitive_values, scan_bytes, scan_sensitive_values, Finding, Severity,
+    MIN_SENSITIVE_VALUE_LEN,
+};
 
 /// Standard placeholder strings and patterns.
 pub const RECOMMENDED_PLACEHOLDERS: &[&str] = &[
@@ -1,4 +1,5 @@
 use regex::Regex;
+use std::collections::BTreeSet;
 use std::ops::Range;
 use std::sync::OnceLock;
 
@@ -77,6 +78,79 @@ pub fn scan_bytes(path: &str, bytes: &[u8]) -> Vec<Finding> {
     findings
 }
 
+/// Values shorter than this are too generic to match reliably and are never scanned for;
+/// config interpolation warns when a reference resolves to one.
+pub const MIN_SENSITIVE_VALUE_LEN: usize = 4;
+
+pub const SENSITIVE_VALUE_PATTERN: &str = "Resolved Config Reference";
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Finds occurrences of `values` (resolved config references of one plan run) that stand as
+/// whole tokens, so `main` inside `domain` is not a match. Matches are always fully masked.
+pub fn scan_sensitive_values(path: &str, bytes: &[u8], values: &BTreeSet<String>) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let content = match std::str::from_utf8(bytes) {
+        Ok(s) => s,
+        Err(_) => return findings,
+    };
+    for value in values {
+        let value = value.trim();
+        if value.chars().count() < MIN_SENSITIVE_VALUE_LEN {
+            continue;
+        }
+        let starts_with_word = value.starts_with(is_word_char);
+        let ends_with_word = value.ends_with(is_word_char);
+        for (start, matched) in content.match_indices(value) {
+            let end = start + matched.len();
+            let bounded_before = !starts_with_word
+                || content[..start]
+                    .chars()
+                    .next_back()
+                    .is_none_or(|c| !is_word_char(c));
+            let bounded_after = !ends_with_word
+                || content[end..]
+                    .chars()
+                    .next()
+                    .is_none_or(|c| !is_word_char(c));
+            if bounded_before && bounded_after {
+                findings.push(Finding {
+                    path: path.to_string(),
+                    pattern_name: SENSITIVE_VALUE_PATTERN.to_string(),
+                    redacted_match: "****".to_string(),
+                    range: start..end,
+                    severity: Severity::Error,
+                });
+            }
+        }
+    }
+    findings
+}
+
+/// Replaces occurrences of `values` in `text` for display.
+pub fn redact_sensitive_values(text: &str, values: &BTreeSet<String>) -> String {
+    let findings = scan_sensitive_values("", text.as_bytes(), values);
+    if findings.is_empty() {
+        return text.to_string();
+    }
+    let mut ranges: Vec<Range<usize>> = findings.into_iter().map(|f| f.range).collect();
+    ranges.sort_by_key(|range| range.start);
+    let mut output = String::with_capacity(text.len());
+    let mut cursor = 0;
+    for range in ranges {
+        if range.start < cursor {
+            continue;
+        }
+        output.push_str(&text[cursor..range.start]);
+        output.push_str("****");
+        cursor = range.end;
+    }
+    output.push_str(&text[cursor..]);
+    output
+}
+
 fn redact(s: &str) -> String {
     let chars: Vec<char> = s.chars().collect();
     if chars.len() <= 8 {
@@ -117,6 +191,40 @@ mod tests {
         assert!(!findings[1].redacted_match.contains("123456789012"));
     }
 
+    #[test]
+    fn test_sensitive_value_is_found_and_masked() {
+        let value = "resolved-value-7f3a91";
+        let values = BTreeSet::from([value.to_string(), "ab".to_string(), "4821".to_string()]);
+        assert!(scan_sensitive_values("a.md", value.as_bytes(), &BTreeSet::new()).is_empty());
+
+        let content = format!("token: {} and again {}", value, value);
+        let findings = scan_sensitive_values("a.md", content.as_bytes(), &values);
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].pattern_name, SENSITIVE_VALUE_PATTERN);
+        assert_eq!(findings[0].redacted_match, "****");
+        assert_eq!(&content[findings[0].range.clone()], value);
+        assert!(scan_sensitive_values("b.md", b"tab ab", &values).is_empty());
+        assert_eq!(
+            scan_sensitive_values("b.md", b"pin: 4821", &values).len(),
+            1
+        );
+        assert_eq!(
+            redact_sensitive_values(&format!("x={};", value), &values),
+            "x=****;".to_string()
+        );
+    }
+
+    #[test]
+    fn test_sensitive_value_matches_whole_tokens_only() {
+        let values = BTreeSet::from(["deploybot".to_string()]);
+        assert!(scan_sensitive_values("a.md", b"user: deploybot2", &values).is_empty());
+        assert!(scan_sensitive_values("a.md", b"mydeploybot", &values).is_empty());
+        assert_eq!(
+            scan_sensitive_values("a.md", b"user: deploybot\n", &values).len(),
+            1
+        );
+    }
+
     #[test]
     fn test_scan_no_secrets() {
         let content = b"This is just some normal text with no secrets.";
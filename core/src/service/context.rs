@@ -1,7 +1,7 @@
 use crate::config::CanonicalConfig;
 use crate::service::interaction::InteractionHandler;
 use crate::tool::{ToolPerformerSpec, ToolSpec, ToolSpecLoader};
-use crate::{load_canonical_config, MaccError, ProjectPaths, Result};
+use crate::{load_effective_config, MaccError, ProjectPaths, Result};
 
 pub fn run_generation(
     paths: &ProjectPaths,
@@ -13,7 +13,7 @@ pub fn run_generation(
 ) -> Result<usize> {
     require_apply_before_context(paths)?;
 
-    let canonical = load_canonical_config(&paths.config_path)?;
+    let canonical = load_effective_config(paths)?;
     let loader = ToolSpecLoader::new(ToolSpecLoader::default_search_paths(&paths.root));
     let (specs, diagnostics) = loader.load_all_with_embedded();
     crate::service::project::report_diagnostics(&diagnostics, reporter);
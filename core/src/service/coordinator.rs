@@ -235,6 +235,9 @@ pub fn coordinator_start_action_process(
             source: e,
         })?;
         let mut cmd = Command::new(current_exe);
+        if let Some(profile) = &paths.profile {
+            cmd.arg("--profile").arg(profile);
+        }
         cmd.current_dir(root)
             .arg("--cwd")
             .arg(root)
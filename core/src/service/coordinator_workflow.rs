@@ -48,6 +48,15 @@ pub enum CoordinatorAction {
     StorageImport,
     StorageExport,
     EventsExport,
+    Metrics,
+    Trace,
+    Report,
+    Runs,
+    Replay,
+    Simulate,
+    Approve,
+    Reject,
+    Task,
     StorageVerify,
     SelectReadyTask,
     AggregatePerformerLogs,
@@ -78,6 +87,27 @@ impl CoordinatorAction {
                 | Self::RetryPhase
         )
     }
+
+    /// One-shot actions that drive the registry, named as recorded in the coordinator lease.
+    pub fn one_shot_lease_name(self) -> Option<&'static str> {
+        match self {
+            Self::Dispatch => Some("dispatch"),
+            Self::Advance => Some("advance"),
+            Self::Sync => Some("sync"),
+            Self::Reconcile => Some("reconcile"),
+            Self::Cleanup => Some("cleanup"),
+            Self::RetryPhase => Some("retry-phase"),
+            _ => None,
+        }
+    }
+
+    /// Actions that take words after the action name.
+    pub fn accepts_operands(self) -> bool {
+        matches!(
+            self,
+            Self::Trace | Self::Report | Self::Replay | Self::Approve | Self::Reject | Self::Task
+        )
+    }
 }
 
 impl FromStr for CoordinatorAction {
@@ -105,6 +135,15 @@ impl FromStr for CoordinatorAction {
             "storage-import" => Ok(Self::StorageImport),
             "storage-export" => Ok(Self::StorageExport),
             "events-export" => Ok(Self::EventsExport),
+            "metrics" => Ok(Self::Metrics),
+            "trace" => Ok(Self::Trace),
+            "report" => Ok(Self::Report),
+            "runs" => Ok(Self::Runs),
+            "replay" => Ok(Self::Replay),
+            "simulate" => Ok(Self::Simulate),
+            "approve" => Ok(Self::Approve),
+            "reject" => Ok(Self::Reject),
+            "task" => Ok(Self::Task),
             "storage-verify" => Ok(Self::StorageVerify),
             "select-ready-task" => Ok(Self::SelectReadyTask),
             "aggregate-performer-logs" => Ok(Self::AggregatePerformerLogs),
@@ -138,6 +177,8 @@ pub struct CoordinatorActionRequest<'a> {
     pub coordinator_cfg: Option<&'a CoordinatorConfig>,
     pub env_cfg: &'a CoordinatorEnvConfig,
     pub extra_args: &'a [String],
+    /// Words after the action name (e.g. `export <run-id>` for `trace`).
+    pub operands: &'a [String],
     pub logger: Option<&'a dyn CoordinatorLog>,
     pub graceful: bool,
     pub remove_worktrees: bool,
@@ -151,9 +192,34 @@ pub struct CoordinatorActionResult {
     pub aggregated_performer_logs: Option<usize>,
     pub runtime_status: Option<String>,
     pub exported_events_path: Option<PathBuf>,
+    pub metrics_path: Option<PathBuf>,
+    pub trace_path: Option<PathBuf>,
+    /// Rendered run report when no `--out` was given.
+    pub report: Option<String>,
+    pub report_path: Option<PathBuf>,
+    pub runs: Option<Vec<crate::coordinator::runs::RunManifest>>,
+    /// Registry rebuilt from the event log, with the divergences found by `replay verify`.
+    pub replay: Option<ReplayResult>,
+    /// Report of a `simulate` run and where its JSON copy was written.
+    pub simulation: Option<(
+        crate::coordinator::simulate::SimulationReport,
+        Option<PathBuf>,
+    )>,
+    /// Task id and decision of an `approve`/`reject` action (`true` when approved).
+    pub approval: Option<(String, bool)>,
+    /// Output of a `task` subcommand.
+    pub task: Option<crate::coordinator::operator::TaskCommandOutput>,
     pub removed_worktrees: Option<usize>,
 }
 
+#[derive(Debug, Clone, Default)]
+pub struct ReplayResult {
+    pub outcome: crate::coordinator::replay::ReplayOutcome,
+    /// Set by `replay verify`: live task count and per-task divergences.
+    pub verification: Option<(usize, Vec<crate::coordinator::replay::TaskDivergence>)>,
+    pub out: Option<PathBuf>,
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct CoordinatorStatus {
     pub total: usize,
@@ -167,6 +233,29 @@ pub struct CoordinatorStatus {
     pub pause_phase: Option<String>,
     pub latest_error: Option<String>,
     pub failure_report: Option<crate::service::diagnostic::FailureReport>,
+    /// Token usage summed over all tasks in the registry.
+    pub usage: crate::coordinator::usage::UsageTotals,
+    /// Most recent run that reported usage, with its totals.
+    pub run_usage: Option<(String, crate::coordinator::usage::UsageTotals)>,
+    pub budget_usd: Option<f64>,
+    /// Tools backing off after provider throttling.
+    pub tool_cooldowns: Vec<ToolCooldown>,
+    /// Tasks waiting in `queued` for a merge approval.
+    pub pending_approvals: Vec<String>,
+    /// Single-instance coordinator lease (`None` when the lease file cannot be read).
+    pub lease: Option<crate::coordinator::lease::LeaseStatus>,
+    /// Ready tasks held back because their scope overlaps an active task.
+    pub scope_waits: Vec<crate::coordinator::task_selector::ScopeOverlap>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ToolCooldown {
+    pub tool: String,
+    /// Seconds until dispatch to the tool resumes (0 once the window is over).
+    pub remaining_seconds: u64,
+    pub strikes: u32,
+    pub max_parallel: usize,
+    pub reason: String,
 }
 
 pub fn coordinator_run_cycle<E: crate::engine::Engine + ?Sized>(
@@ -190,6 +279,22 @@ pub fn coordinator_perform_action<E: crate::engine::Engine + ?Sized>(
     request: CoordinatorActionRequest<'_>,
 ) -> Result<CoordinatorActionResult> {
     let mut result = CoordinatorActionResult::default();
+    if !request.operands.is_empty() && !action.accepts_operands() {
+        return Err(MaccError::Validation(format!(
+            "Unexpected argument '{}' for this coordinator action.",
+            request.operands[0]
+        )));
+    }
+    // One-shot actions that drive the registry take the project lease for their duration;
+    // `control-plane-run` holds it for the whole run.
+    let _lease = match action.one_shot_lease_name() {
+        Some(name) => Some(crate::coordinator::lease::CoordinatorLease::acquire(
+            &paths.root,
+            &crate::coordinator::helpers::ensure_coordinator_run_id(),
+            name,
+        )?),
+        None => None,
+    };
     match action {
         CoordinatorAction::Run => {
             if !request.extra_args.is_empty() {
@@ -404,6 +509,181 @@ pub fn coordinator_perform_action<E: crate::engine::Engine + ?Sized>(
                     .join("events.jsonl"),
             );
         }
+        CoordinatorAction::Metrics => {
+            let parsed = crate::coordinator::args::MetricsArgs::try_from(request.extra_args)?;
+            result.metrics_path = Some(crate::coordinator::metrics::write_metrics_file(
+                &paths.root,
+                parsed.out.as_deref(),
+            )?);
+        }
+        CoordinatorAction::Trace => {
+            let parsed = crate::coordinator::args::TraceExportArgs::parse(
+                request.operands,
+                request.extra_args,
+            )?;
+            result.trace_path = Some(crate::coordinator::trace::export_run_trace(
+                &paths.root,
+                parsed.run_id.as_deref(),
+                parsed.format,
+                parsed.out.as_deref(),
+            )?);
+        }
+        CoordinatorAction::Report => {
+            let parsed =
+                crate::coordinator::args::ReportArgs::parse(request.operands, request.extra_args)?;
+            let record = crate::coordinator::runs::load_run(&paths.root, parsed.run_id.as_deref())?;
+            let body =
+                crate::coordinator::runs::RunReport::from_record(&record).render(parsed.format)?;
+            match parsed.out {
+                Some(out) => {
+                    let path = if out.is_absolute() {
+                        out
+                    } else {
+                        paths.root.join(out)
+                    };
+                    std::fs::write(&path, body).map_err(|e| MaccError::Io {
+                        path: path.to_string_lossy().into(),
+                        action: "write coordinator report".into(),
+                        source: e,
+                    })?;
+                    result.report_path = Some(path);
+                }
+                None => result.report = Some(body),
+            }
+        }
+        CoordinatorAction::Runs => {
+            result.runs = Some(crate::coordinator::runs::list_runs(&paths.root)?);
+        }
+        CoordinatorAction::Replay => {
+            let parsed =
+                crate::coordinator::args::ReplayArgs::parse(request.operands, request.extra_args)?;
+            let mut replay = if parsed.verify {
+                let verification = crate::coordinator::replay::verify_registry(&paths.root)?;
+                ReplayResult {
+                    outcome: verification.outcome,
+                    verification: Some((verification.tasks_checked, verification.divergences)),
+                    out: None,
+                }
+            } else {
+                ReplayResult {
+                    outcome: crate::coordinator::replay::replay_registry(
+                        &paths.root,
+                        parsed.until.as_ref(),
+                    )?,
+                    ..Default::default()
+                }
+            };
+            if let Some(out) = parsed.out {
+                let path = if out.is_absolute() {
+                    out
+                } else {
+                    paths.root.join(out)
+                };
+                let body = serde_json::to_string_pretty(&replay.outcome.registry).map_err(|e| {
+                    MaccError::Validation(format!("Failed to serialize replayed registry: {}", e))
+                })?;
+                std::fs::write(&path, body).map_err(|e| MaccError::Io {
+                    path: path.to_string_lossy().into(),
+                    action: "write replayed registry".into(),
+                    source: e,
+                })?;
+                replay.out = Some(path);
+            }
+            result.replay = Some(replay);
+        }
+        CoordinatorAction::Simulate => {
+            let canonical = request.canonical.ok_or_else(|| {
+                MaccError::Validation("simulate requires canonical config".into())
+            })?;
+            let parsed = crate::coordinator::args::SimulateArgs::parse(request.extra_args)?;
+            let resolve = |path: PathBuf| {
+                if path.is_absolute() {
+                    path
+                } else {
+                    paths.root.join(path)
+                }
+            };
+            let prd_file = request
+                .env_cfg
+                .prd
+                .as_ref()
+                .map(PathBuf::from)
+                .or_else(|| {
+                    request
+                        .coordinator_cfg
+                        .and_then(|c| c.prd_file.clone())
+                        .map(PathBuf::from)
+                })
+                .map(resolve)
+                .unwrap_or_else(|| paths.root.join("prd.json"));
+            let report = crate::coordinator::simulate::simulate_files(
+                &prd_file,
+                &resolve(parsed.profile),
+                canonical,
+                request.coordinator_cfg,
+                request.env_cfg,
+            )?;
+            let out = match parsed.out.map(resolve) {
+                Some(path) => {
+                    let body = serde_json::to_string_pretty(&report).map_err(|e| {
+                        MaccError::Validation(format!(
+                            "Failed to serialize simulation report: {}",
+                            e
+                        ))
+                    })?;
+                    std::fs::write(&path, body).map_err(|e| MaccError::Io {
+                        path: path.to_string_lossy().into(),
+                        action: "write simulation report".into(),
+                        source: e,
+                    })?;
+                    Some(path)
+                }
+                None => None,
+            };
+            result.simulation = Some((report, out));
+        }
+        CoordinatorAction::Approve | CoordinatorAction::Reject => {
+            let approved = action == CoordinatorAction::Approve;
+            let parsed = crate::coordinator::args::ApprovalArgs::parse(
+                if approved { "approve" } else { "reject" },
+                request.operands,
+                request.extra_args,
+            )?;
+            crate::coordinator::approval::decide_task_approval(
+                &paths.root,
+                &parsed.task_id,
+                approved,
+                &crate::coordinator::approval::current_user(),
+                parsed.comment.as_deref(),
+            )?;
+            result.approval = Some((parsed.task_id, approved));
+        }
+        CoordinatorAction::Task => {
+            let parsed = crate::coordinator::args::TaskCommandArgs::parse(
+                request.operands,
+                request.extra_args,
+            )?;
+            if let crate::coordinator::operator::TaskCommand::SetTool { tool, .. } = &parsed.command
+            {
+                let enabled = request
+                    .canonical
+                    .map(|c| c.tools.enabled.as_slice())
+                    .unwrap_or_default();
+                if !enabled.is_empty() && !enabled.contains(tool) {
+                    return Err(MaccError::Validation(format!(
+                        "Tool '{}' is not enabled (enabled: {})",
+                        tool,
+                        enabled.join(", ")
+                    )));
+                }
+            }
+            result.task = Some(crate::coordinator::operator::run_task_command(
+                &paths.root,
+                &parsed.command,
+                &crate::coordinator::approval::current_user(),
+                parsed.comment.as_deref(),
+            )?);
+        }
         CoordinatorAction::StorageVerify => {
             engine.coordinator_storage_verify_parity(paths)?;
         }
@@ -484,8 +764,13 @@ pub fn coordinator_run(
     cfg: Option<&CoordinatorConfig>,
     options: &CoordinatorRunOptions,
 ) -> Result<()> {
-    let _ = options.env_cfg;
-    coordinator_start_managed_action_process(paths, "run", &options.extra_args, cfg)?;
+    let mut args = options.extra_args.clone();
+    if let Some(listen) = &options.env_cfg.metrics_listen {
+        args.push("--metrics-listen".to_string());
+        args.push(listen.clone());
+    }
+    crate::coordinator::lease::ensure_lease_available(&paths.root)?;
+    coordinator_start_managed_action_process(paths, "run", &args, cfg)?;
 
     loop {
         match coordinator_poll_managed_action_process(paths)? {
@@ -542,6 +827,61 @@ pub fn get_coordinator_status(paths: &ProjectPaths) -> Result<CoordinatorStatus>
         .unwrap_or_default();
 
     status.total = tasks.len();
+    status.pending_approvals = crate::coordinator::approval::pending_approvals(&snapshot.registry);
+    status.lease = crate::coordinator::lease::lease_status(&paths.root).ok();
+    for task in &tasks {
+        status
+            .usage
+            .add(&crate::coordinator::usage::task_usage(task));
+    }
+    let coordinator_cfg = crate::config::load_effective_config(paths)
+        .ok()
+        .and_then(|config| config.automation.coordinator);
+    let prices = coordinator_cfg
+        .as_ref()
+        .map(|cfg| cfg.token_prices.clone())
+        .unwrap_or_default();
+    let touched_files = if coordinator_cfg
+        .as_ref()
+        .and_then(|cfg| cfg.predict_scope_overlap)
+        .unwrap_or(false)
+    {
+        crate::coordinator::control_plane::active_touched_files(&paths.root, &snapshot.registry)
+    } else {
+        Default::default()
+    };
+    let avoid_scope_overlap = coordinator_cfg
+        .as_ref()
+        .and_then(|cfg| cfg.avoid_scope_overlap)
+        .unwrap_or(false);
+    status.scope_waits = crate::coordinator::task_selector::scope_waits(
+        &snapshot.registry,
+        avoid_scope_overlap,
+        &touched_files,
+    );
+    status.budget_usd = coordinator_cfg.and_then(|cfg| cfg.budget_usd);
+    let latest_run = snapshot
+        .events
+        .iter()
+        .rev()
+        .filter_map(|event| crate::coordinator::usage::usage_sample(event, &prices))
+        .find_map(|sample| sample.run_id);
+    if let Some(run_id) = latest_run {
+        let runs = crate::coordinator::usage::run_usage(&snapshot.events, &prices);
+        status.run_usage = runs.get(&run_id).map(|totals| (run_id, *totals));
+    }
+    let now = chrono::Utc::now().timestamp();
+    status.tool_cooldowns = crate::coordinator::throttle::ThrottleState::load(&paths.root)
+        .tools
+        .into_iter()
+        .map(|(tool, throttle)| ToolCooldown {
+            tool,
+            remaining_seconds: (throttle.cooldown_until - now).max(0) as u64,
+            strikes: throttle.strikes,
+            max_parallel: throttle.max_parallel,
+            reason: throttle.reason,
+        })
+        .collect();
     for task in tasks {
         match task
             .get("state")
@@ -674,6 +1014,7 @@ pub fn coordinator_advance<E: crate::engine::Engine + ?Sized>(
         .map_err(|e| MaccError::Validation(format!("Failed to initialize tokio runtime: {}", e)))?;
     let advance = runtime.block_on(async {
         let mut state = coordinator_runtime::CoordinatorRunState::new();
+        state.approval = coordinator_cfg.and_then(|c| c.approval.clone());
         engine
             .coordinator_advance_tasks_native(
                 &paths.root,
@@ -1034,6 +1375,23 @@ fn retry_dev_phase<E: crate::engine::Engine + ?Sized>(
     let current_exe = std::env::current_exe().map_err(|e| {
         MaccError::Validation(format!("Failed to resolve current executable path: {}", e))
     })?;
+    let tool = task
+        .get("tool")
+        .and_then(serde_json::Value::as_str)
+        .unwrap_or("codex")
+        .to_string();
+    let limits = crate::coordinator::limits::resolve_resource_limits(
+        canonical.automation.coordinator.as_ref(),
+        &tool,
+        task,
+    );
+    let pool_env = canonical
+        .automation
+        .coordinator
+        .as_ref()
+        .and_then(|c| c.worktree.as_ref())
+        .map(|pool| crate::coordinator::worktree_pool::pool_env(&paths.root, pool))
+        .unwrap_or_default();
     let pid = coordinator_runtime::spawn_performer_job(
         &current_exe,
         &paths.root,
@@ -1042,19 +1400,19 @@ fn retry_dev_phase<E: crate::engine::Engine + ?Sized>(
         &state.event_tx,
         &mut state.join_set,
         env_cfg.stale_in_progress_seconds.unwrap_or(0),
+        limits,
+        paths.profile.as_deref(),
+        &pool_env,
     )?;
     state.active_jobs.insert(
         task_id.to_string(),
         coordinator_runtime::CoordinatorJob {
-            tool: task
-                .get("tool")
-                .and_then(serde_json::Value::as_str)
-                .unwrap_or("codex")
-                .to_string(),
+            tool,
             worktree_path: worktree.clone(),
             attempt: 1,
             started_at: std::time::Instant::now(),
             pid,
+            limits,
         },
     );
     let runtime = tokio::runtime::Builder::new_current_thread()
@@ -4,7 +4,7 @@ use crate::resolve::{
     resolve, resolve_fetch_units, CliOverrides, FetchUnit, MaterializedFetchUnit,
 };
 use crate::service::interaction::InteractionHandler;
-use crate::{load_canonical_config, MaccError, ProjectPaths, Result, ToolDescriptor};
+use crate::{load_effective_config, MaccError, ProjectPaths, Result, ToolDescriptor};
 use std::path::Path;
 
 pub trait LifecycleFetchMaterializer {
@@ -46,12 +46,13 @@ pub trait LifecycleUi: InteractionHandler {
 
 pub fn init(
     cwd: &Path,
+    profile: Option<&str>,
     engine: &dyn Engine,
     force: bool,
     wizard: bool,
     ui: &dyn LifecycleUi,
 ) -> Result<()> {
-    let paths = crate::find_project_root(cwd).unwrap_or_else(|_| ProjectPaths::from_root(cwd));
+    let paths = project_paths(cwd, profile);
     crate::init(&paths, force)?;
     if wizard {
         run_init_wizard(&paths, engine, ui)?;
@@ -61,8 +62,10 @@ pub fn init(
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn plan(
     cwd: &Path,
+    profile: Option<&str>,
     engine: &dyn Engine,
     tools: Option<&str>,
     json: bool,
@@ -70,7 +73,7 @@ pub fn plan(
     ui: &dyn LifecycleUi,
     fetch_materializer: &dyn LifecycleFetchMaterializer,
 ) -> Result<()> {
-    let project_ctx = load_project_context(cwd, engine)?;
+    let project_ctx = load_project_context(cwd, profile, engine)?;
     let paths = project_ctx.paths.clone();
     let canonical = project_ctx.canonical.clone();
     let descriptors = project_ctx.descriptors.clone();
@@ -106,8 +109,10 @@ pub fn plan(
     ui.render_plan_preview(&paths, &plan, &ops, json, explain)
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn apply(
     cwd: &Path,
+    profile: Option<&str>,
     engine: &dyn Engine,
     tools: Option<&str>,
     dry_run: bool,
@@ -117,7 +122,7 @@ pub fn apply(
     ui: &dyn LifecycleUi,
     fetch_materializer: &dyn LifecycleFetchMaterializer,
 ) -> Result<()> {
-    let project_ctx = load_project_context(cwd, engine)?;
+    let project_ctx = load_project_context(cwd, profile, engine)?;
     let paths = project_ctx.paths.clone();
     let canonical = project_ctx.canonical.clone();
     let descriptors = project_ctx.descriptors.clone();
@@ -176,8 +181,10 @@ pub fn apply(
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn quickstart(
     cwd: &Path,
+    profile: Option<&str>,
     engine: &dyn Engine,
     assume_yes: bool,
     apply: bool,
@@ -185,7 +192,7 @@ pub fn quickstart(
     ui: &dyn LifecycleUi,
     fetch_materializer: &dyn LifecycleFetchMaterializer,
 ) -> Result<()> {
-    let paths = crate::find_project_root(cwd).unwrap_or_else(|_| ProjectPaths::from_root(cwd));
+    let paths = project_paths(cwd, profile);
 
     let mut missing = Vec::new();
     for cmd in ["git", "curl", "jq"] {
@@ -248,7 +255,7 @@ fn run_plan_then_optional_apply(
     ui: &dyn LifecycleUi,
     fetch_materializer: &dyn LifecycleFetchMaterializer,
 ) -> Result<()> {
-    let canonical = load_canonical_config(&paths.config_path)?;
+    let canonical = load_effective_config(paths)?;
     let (_descriptors, diagnostics) = engine.list_tools(paths);
     crate::service::project::report_diagnostics(&diagnostics, ui);
     let overrides = CliOverrides::default();
@@ -280,7 +287,7 @@ fn run_plan_then_optional_apply(
 
 fn run_init_wizard(paths: &ProjectPaths, engine: &dyn Engine, ui: &dyn LifecycleUi) -> Result<()> {
     ui.info("Init wizard (3 questions)");
-    let mut config = load_canonical_config(&paths.config_path)?;
+    let mut config = load_effective_config(paths)?;
     let (descriptors, diagnostics) = engine.list_tools(paths);
     crate::service::project::report_diagnostics(&diagnostics, ui);
     let tool_ids: Vec<String> = descriptors.iter().map(|d| d.id.clone()).collect();
@@ -333,9 +340,7 @@ fn run_init_wizard(paths: &ProjectPaths, engine: &dyn Engine, ui: &dyn Lifecycle
         selections.mcp.clear();
     }
 
-    let yaml = config
-        .to_yaml()
-        .map_err(|e| MaccError::Validation(format!("Failed to serialize wizard config: {}", e)))?;
+    let yaml = crate::config::layers::project_config_yaml(paths, &config)?;
     crate::atomic_write(paths, &paths.config_path, yaml.as_bytes())?;
     ui.info(&format!("Wizard saved: {}", paths.config_path.display()));
     Ok(())
@@ -402,9 +407,20 @@ struct LoadedProjectContext {
     allowed_tools: Vec<String>,
 }
 
-fn load_project_context(cwd: &Path, engine: &dyn Engine) -> Result<LoadedProjectContext> {
-    let paths = crate::find_project_root(cwd)?;
-    let canonical = load_canonical_config(&paths.config_path)?;
+/// Project paths for `init`/`quickstart`, which may run before `.macc/macc.yaml` exists.
+fn project_paths(cwd: &Path, profile: Option<&str>) -> ProjectPaths {
+    crate::find_project_root(cwd)
+        .unwrap_or_else(|_| ProjectPaths::from_root(cwd))
+        .with_profile(profile.map(str::to_string))
+}
+
+fn load_project_context(
+    cwd: &Path,
+    profile: Option<&str>,
+    engine: &dyn Engine,
+) -> Result<LoadedProjectContext> {
+    let paths = crate::find_project_root(cwd)?.with_profile(profile.map(str::to_string));
+    let canonical = load_effective_config(&paths)?;
     let (descriptors, diagnostics) = engine.list_tools(&paths);
     let allowed_tools: Vec<String> = descriptors.iter().map(|d| d.id.clone()).collect();
     Ok(LoadedProjectContext {
@@ -1,6 +1,6 @@
 use crate::config::CanonicalConfig;
 use crate::service::interaction::InteractionHandler;
-use crate::{MaccError, ProjectPaths, Result};
+use crate::{ProjectPaths, Result};
 
 #[derive(Debug, Clone, Default)]
 pub struct MigrateOutcome {
@@ -23,20 +23,14 @@ pub fn migrate_project(
 
     let mut wrote_config = false;
     let mut preview_yaml = None;
+    // `canonical` is the effective config; only the project layer is rewritten.
+    let yaml = crate::config::layers::project_config_yaml(paths, &result.config)?;
 
     if apply || ui.confirm("Write migrated configuration to disk now [y/N]? ")? {
-        let yaml = result.config.to_yaml().map_err(|e| {
-            MaccError::Validation(format!("Failed to serialize migrated config: {}", e))
-        })?;
         crate::atomic_write(paths, &paths.config_path, yaml.as_bytes())?;
         wrote_config = true;
     } else {
-        preview_yaml = Some(result.config.to_yaml().map_err(|e| {
-            MaccError::Validation(format!(
-                "Failed to serialize migrated config preview: {}",
-                e
-            ))
-        })?);
+        preview_yaml = Some(yaml);
     }
 
     Ok(MigrateOutcome {
@@ -35,8 +35,15 @@ pub fn run_doctor(
         return Err(MaccError::Validation("Doctor checks failed.".into()));
     }
 
-    let any_applied = false;
+    let mut any_applied = false;
     for check in failed {
+        if check.name == "Coordinator lease"
+            && crate::coordinator::lease::clear_stale_lease(&paths.root)?
+        {
+            interaction.info("Removed the stale coordinator lease.");
+            any_applied = true;
+            continue;
+        }
         interaction.info(&format!(
             "No automatic fix registered for doctor check '{}' (target='{}').",
             check.name, check.check_target
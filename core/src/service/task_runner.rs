@@ -1,6 +1,5 @@
 use crate::service::worktree::{
-    coordinator_task_registry_path, ensure_performer, ensure_tool_json, resolve_worktree_path,
-    resolve_worktree_task_context,
+    ensure_tool_json, resolve_worktree_path, resolve_worktree_task_context,
 };
 use crate::{read_worktree_metadata, MaccError, ProjectPaths, Result};
 use std::path::PathBuf;
@@ -14,7 +13,12 @@ fn now_nanos() -> u128 {
         .unwrap_or(0)
 }
 
-pub fn worktree_run_task(paths: &ProjectPaths, id: &str) -> Result<()> {
+/// Performer request for a worktree: tool from `.macc/worktree.json`, task and PRD from
+/// `worktree.prd.json`, events appended to the coordinator event log.
+pub fn performer_request_for_worktree(
+    paths: &ProjectPaths,
+    id: &str,
+) -> Result<crate::performer::PerformerRequest> {
     let worktree_path = resolve_worktree_path(&paths.root, id)?;
     if !worktree_path.exists() {
         return Err(MaccError::Validation(format!(
@@ -28,62 +32,38 @@ pub fn worktree_run_task(paths: &ProjectPaths, id: &str) -> Result<()> {
     ensure_tool_json(&paths.root, &worktree_path, &metadata.tool)?;
     let (task_id, prd_path) =
         resolve_worktree_task_context(&paths.root, &worktree_path, &metadata.id)?;
-    let performer_path = ensure_performer(&worktree_path)?;
-    let registry_path = coordinator_task_registry_path(&paths.root);
-    let events_file = paths
-        .root
-        .join(".macc")
-        .join("log")
-        .join("coordinator")
-        .join("events.jsonl");
-    if let Some(parent) = events_file.parent() {
-        std::fs::create_dir_all(parent).map_err(|e| MaccError::Io {
-            path: parent.to_string_lossy().into(),
-            action: "create coordinator log dir for events".into(),
-            source: e,
-        })?;
+    let mut request = crate::performer::PerformerRequest::from_env(
+        paths.root.clone(),
+        worktree_path,
+        task_id,
+        metadata.tool,
+        prd_path,
+    );
+    if request.events_file.is_none() {
+        request.events_file = Some(
+            paths
+                .root
+                .join(".macc")
+                .join("log")
+                .join("coordinator")
+                .join("events.jsonl"),
+        );
     }
-
-    let status = Command::new(&performer_path)
-        .current_dir(&worktree_path)
-        .env(
-            "COORD_EVENTS_FILE",
-            events_file.to_string_lossy().to_string(),
-        )
-        .env(
-            "COORDINATOR_RUN_ID",
-            crate::service::project::ensure_coordinator_run_id(),
-        )
-        .env(
-            "MACC_EVENT_SOURCE",
-            format!("worktree-run:{}:{}", task_id, now_nanos()),
-        )
-        .env("MACC_EVENT_TASK_ID", &task_id)
-        .arg("--repo")
-        .arg(&paths.root)
-        .arg("--worktree")
-        .arg(&worktree_path)
-        .arg("--task-id")
-        .arg(&task_id)
-        .arg("--tool")
-        .arg(&metadata.tool)
-        .arg("--registry")
-        .arg(&registry_path)
-        .arg("--prd")
-        .arg(&prd_path)
-        .status()
-        .map_err(|e| MaccError::Io {
-            path: performer_path.to_string_lossy().into(),
-            action: "run worktree performer".into(),
-            source: e,
-        })?;
-
-    if !status.success() {
-        return Err(MaccError::Validation(format!(
-            "Performer failed with status: {}. Inspect logs with `macc logs tail --component performer --worktree {}` and if the task is stuck run `macc coordinator unlock --task {}`.",
-            status, metadata.id, task_id
-        )));
+    request.run_id = crate::service::project::ensure_coordinator_run_id();
+    if std::env::var("MACC_EVENT_SOURCE").is_err() {
+        request.event_source = format!("worktree-run:{}:{}", request.task_id, now_nanos());
     }
+    Ok(request)
+}
+
+pub fn worktree_run_task(paths: &ProjectPaths, id: &str) -> Result<()> {
+    let request = performer_request_for_worktree(paths, id)?;
+    crate::performer::run_performer(&request).map_err(|err| {
+        MaccError::Validation(format!(
+            "Performer failed: {}. Inspect logs with `macc logs tail --component performer --worktree {}` and if the task is stuck run `macc coordinator unlock --task {}`.",
+            err, id, request.task_id
+        ))
+    })?;
     Ok(())
 }
 
@@ -2,8 +2,13 @@ use crate::config::CanonicalConfig;
 use crate::doctor::{run_checks, ToolCheck, ToolStatus};
 use crate::service::interaction::InteractionHandler;
 use crate::tool::ToolDiagnostic;
-use crate::tool::{DoctorCheckKind, ToolInstallCommand, ToolSpec, ToolSpecLoader};
-use crate::{load_canonical_config, MaccError, ProjectPaths, Result};
+use crate::tool::{
+    DoctorCheckKind, ToolInstallCommand, ToolRollbackSpec, ToolSpec, ToolSpecLoader,
+};
+use crate::{load_effective_config, MaccError, ProjectPaths, Result};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::PathBuf;
 
 #[derive(Debug, Clone, Copy)]
 pub struct ToolUpdateCommandOptions<'a> {
@@ -56,6 +61,53 @@ pub struct OutdatedToolsReport {
     pub outdated_count: usize,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolUpdateOutcome {
+    Updated,
+    Failed,
+    RolledBack,
+    RollbackFailed,
+}
+
+impl ToolUpdateOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ToolUpdateOutcome::Updated => "updated",
+            ToolUpdateOutcome::Failed => "failed",
+            ToolUpdateOutcome::RolledBack => "rolled_back",
+            ToolUpdateOutcome::RollbackFailed => "rollback_failed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolRollbackRecord {
+    /// `commands` (reinstall previous version) or `restore` (snapshot restore).
+    pub method: String,
+    pub success: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub restored_version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// One line of `.macc/state/tool-history.jsonl`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolUpdateRecord {
+    pub timestamp: String,
+    pub tool_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new_version: Option<String>,
+    pub outcome: ToolUpdateOutcome,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rollback: Option<ToolRollbackRecord>,
+}
+
 pub struct NoopReporter;
 
 impl InteractionHandler for NoopReporter {}
@@ -159,7 +211,7 @@ pub fn update_tools(
     reporter: &dyn UserReporter,
 ) -> Result<ToolUpdateSummary> {
     let specs = load_toolspecs_with_diagnostics(paths, reporter)?;
-    let canonical = load_canonical_config(&paths.config_path)?;
+    let canonical = load_effective_config(paths)?;
     let selected = select_tools_for_update(&specs, &canonical, opts.tool_id, opts.all, opts.only)?;
     if selected.is_empty() {
         return Err(MaccError::Validation(
@@ -257,7 +309,7 @@ pub fn show_outdated_tools(
     reporter: &dyn UserReporter,
 ) -> Result<OutdatedToolsReport> {
     let specs = load_toolspecs_with_diagnostics(paths, reporter)?;
-    let canonical = load_canonical_config(&paths.config_path)?;
+    let canonical = load_effective_config(paths)?;
     let selected = select_tools_for_update(&specs, &canonical, None, true, only)?;
 
     let mut report = OutdatedToolsReport::default();
@@ -536,6 +588,12 @@ fn update_single_tool(
             spec.id
         )));
     }
+    if rollback_on_fail && spec.rollback.is_none() {
+        return Err(MaccError::Validation(format!(
+            "Tool '{}' has no rollback section in its ToolSpec; refusing update with --rollback-on-fail.",
+            spec.id
+        )));
+    }
     if !assume_yes {
         reporter.info(&update_spec.confirm_message.unwrap_or_else(|| {
             format!(
@@ -548,6 +606,31 @@ fn update_single_tool(
         }
     }
 
+    let previous_version = spec
+        .version_check
+        .as_ref()
+        .and_then(|vc| run_version_command(&vc.current));
+    let snapshot_dir = paths
+        .macc_dir
+        .join("state")
+        .join("tool-snapshots")
+        .join(&spec.id)
+        .join(chrono::Utc::now().format("%Y%m%dT%H%M%S%.3fZ").to_string());
+    let vars = RollbackVars {
+        previous_version: previous_version.clone(),
+        snapshot_dir: snapshot_dir.clone(),
+    };
+    let rollback = spec.rollback.as_ref().filter(|_| rollback_on_fail);
+    if let Some(snapshot) = rollback.and_then(|r| r.snapshot.as_ref()) {
+        std::fs::create_dir_all(&snapshot_dir).map_err(|e| MaccError::Io {
+            path: snapshot_dir.to_string_lossy().into(),
+            action: "create tool snapshot directory".into(),
+            source: e,
+        })?;
+        // A failed snapshot aborts before anything is changed.
+        run_install_command(&paths.root, &vars.expand(snapshot), false)?;
+    }
+
     let update_result: Result<()> = (|| {
         for command in &update_spec.commands {
             run_install_command(&paths.root, command, false)?;
@@ -566,12 +649,386 @@ fn update_single_tool(
         Ok(())
     })();
 
-    if update_result.is_ok() || !rollback_on_fail {
-        return update_result;
+    let current_version = || {
+        spec.version_check
+            .as_ref()
+            .and_then(|vc| run_version_command(&vc.current))
+    };
+    let mut record = ToolUpdateRecord {
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        tool_id: spec.id.clone(),
+        previous_version: previous_version.clone(),
+        new_version: None,
+        outcome: ToolUpdateOutcome::Updated,
+        error: None,
+        rollback: None,
+    };
+    let result = match (update_result, rollback) {
+        (Ok(()), _) => {
+            record.new_version = current_version();
+            Ok(())
+        }
+        (Err(err), None) => {
+            record.outcome = ToolUpdateOutcome::Failed;
+            record.new_version = current_version();
+            record.error = Some(err.to_string());
+            Err(err)
+        }
+        (Err(err), Some(rollback)) => {
+            reporter.warn(&format!(
+                "Update of '{}' failed ({}); rolling back.",
+                spec.id, err
+            ));
+            let outcome = run_rollback(paths, spec, rollback, &vars, reporter);
+            record.error = Some(err.to_string());
+            record.new_version = outcome.restored_version.clone();
+            record.outcome = if outcome.success {
+                ToolUpdateOutcome::RolledBack
+            } else {
+                ToolUpdateOutcome::RollbackFailed
+            };
+            let summary = if outcome.success {
+                format!(
+                    "rolled back to {}",
+                    outcome
+                        .restored_version
+                        .as_deref()
+                        .unwrap_or("previous state")
+                )
+            } else {
+                format!(
+                    "rollback failed: {}",
+                    outcome.message.as_deref().unwrap_or("unknown error")
+                )
+            };
+            record.rollback = Some(outcome);
+            Err(MaccError::Validation(format!("{}; {}", err, summary)))
+        }
+    };
+
+    if let Err(err) = append_tool_history(paths, &record) {
+        reporter.warn(&format!("Failed to record tool update history: {}", err));
+    }
+    result
+}
+
+struct RollbackVars {
+    previous_version: Option<String>,
+    snapshot_dir: PathBuf,
+}
+
+impl RollbackVars {
+    fn expand(&self, command: &ToolInstallCommand) -> ToolInstallCommand {
+        let replace = |value: &str| {
+            let mut out = value.replace("{snapshot_dir}", &self.snapshot_dir.to_string_lossy());
+            if let Some(version) = &self.previous_version {
+                out = out.replace("{previous_version}", version);
+            }
+            out
+        };
+        ToolInstallCommand {
+            command: replace(&command.command),
+            args: command.args.iter().map(|arg| replace(arg)).collect(),
+        }
     }
+}
+
+/// Reinstalls the previous version, falling back to the snapshot restore command.
+fn run_rollback(
+    paths: &ProjectPaths,
+    spec: &ToolSpec,
+    rollback: &ToolRollbackSpec,
+    vars: &RollbackVars,
+    reporter: &dyn UserReporter,
+) -> ToolRollbackRecord {
+    let healthy = || {
+        let checks = run_tool_health_checks(spec);
+        reporter.info(&format_checks_table(&checks));
+        checks_all_installed(&checks)
+    };
+    let version_after = || {
+        spec.version_check
+            .as_ref()
+            .and_then(|vc| run_version_command(&vc.current))
+    };
+
+    let failure = if rollback.commands.is_empty() {
+        "no rollback commands defined".to_string()
+    } else if vars.previous_version.is_none() {
+        "previous version unknown".to_string()
+    } else {
+        let ran = rollback
+            .commands
+            .iter()
+            .try_for_each(|command| run_install_command(&paths.root, &vars.expand(command), false));
+        match ran {
+            Ok(()) if healthy() => {
+                return ToolRollbackRecord {
+                    method: "commands".to_string(),
+                    success: true,
+                    restored_version: version_after(),
+                    message: None,
+                };
+            }
+            Ok(()) => "doctor checks still failing after rollback".to_string(),
+            Err(err) => err.to_string(),
+        }
+    };
+
+    let Some(restore) = &rollback.restore else {
+        return ToolRollbackRecord {
+            method: "commands".to_string(),
+            success: false,
+            restored_version: version_after(),
+            message: Some(failure),
+        };
+    };
     reporter.warn(&format!(
-        "Rollback requested for '{}' but no generic rollback contract is defined. Configure tool-specific rollback in ToolSpec before enabling this in production.",
-        spec.id
+        "Rollback commands for '{}' did not succeed ({}); restoring snapshot.",
+        spec.id, failure
     ));
-    update_result
+    let message = match run_install_command(&paths.root, &vars.expand(restore), false) {
+        Ok(()) if healthy() => None,
+        Ok(()) => Some("doctor checks still failing after restore".to_string()),
+        Err(err) => Some(err.to_string()),
+    };
+    ToolRollbackRecord {
+        method: "restore".to_string(),
+        success: message.is_none(),
+        restored_version: version_after(),
+        message,
+    }
+}
+
+pub fn tool_history_path(paths: &ProjectPaths) -> PathBuf {
+    paths.macc_dir.join("state").join("tool-history.jsonl")
+}
+
+pub fn append_tool_history(paths: &ProjectPaths, record: &ToolUpdateRecord) -> Result<()> {
+    let path = tool_history_path(paths);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| MaccError::Io {
+            path: parent.to_string_lossy().into(),
+            action: "create tool history directory".into(),
+            source: e,
+        })?;
+    }
+    let line = serde_json::to_string(record).map_err(|e| {
+        MaccError::Validation(format!("Failed to serialize tool history record: {}", e))
+    })?;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| MaccError::Io {
+            path: path.to_string_lossy().into(),
+            action: "open tool history".into(),
+            source: e,
+        })?;
+    writeln!(file, "{}", line).map_err(|e| MaccError::Io {
+        path: path.to_string_lossy().into(),
+        action: "append tool history".into(),
+        source: e,
+    })
+}
+
+/// Reads update history, oldest first, optionally filtered to one tool.
+/// Unparseable lines are skipped so a truncated write does not hide older records.
+pub fn read_tool_history(
+    paths: &ProjectPaths,
+    tool_id: Option<&str>,
+) -> Result<Vec<ToolUpdateRecord>> {
+    let path = tool_history_path(paths);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = std::fs::read_to_string(&path).map_err(|e| MaccError::Io {
+        path: path.to_string_lossy().into(),
+        action: "read tool history".into(),
+        source: e,
+    })?;
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str::<ToolUpdateRecord>(line).ok())
+        .filter(|record| tool_id.is_none_or(|id| record.tool_id == id))
+        .collect())
+}
+
+pub fn format_tool_history_table(records: &[ToolUpdateRecord]) -> String {
+    let mut output = String::new();
+    output.push_str(&format!(
+        "{:<25} {:<14} {:<16} {:<12} {:<12} {:<20}\n",
+        "TIME", "TOOL", "OUTCOME", "FROM", "TO", "ROLLBACK"
+    ));
+    output.push_str(&format!(
+        "{:-<25} {:-<14} {:-<16} {:-<12} {:-<12} {:-<20}\n",
+        "", "", "", "", "", ""
+    ));
+    for record in records {
+        let rollback = match &record.rollback {
+            Some(rb) if rb.success => format!("{} ok", rb.method),
+            Some(rb) => format!(
+                "{} failed: {}",
+                rb.method,
+                rb.message.as_deref().unwrap_or("-")
+            ),
+            None => "-".to_string(),
+        };
+        output.push_str(&format!(
+            "{:<25} {:<14} {:<16} {:<12} {:<12} {:<20}\n",
+            record.timestamp.chars().take(25).collect::<String>(),
+            record.tool_id,
+            record.outcome.as_str(),
+            record.previous_version.as_deref().unwrap_or("-"),
+            record.new_version.as_deref().unwrap_or("-"),
+            rollback
+        ));
+        if let Some(error) = &record.error {
+            output.push_str(&format!("    error: {}\n", error));
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tool::{CheckSeverity, DoctorCheckSpec, ToolInstallSpec, ToolVersionCheckSpec};
+
+    fn temp_project(label: &str) -> ProjectPaths {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("macc_tool_rollback_{}_{}", label, nanos));
+        std::fs::create_dir_all(root.join(".macc")).unwrap();
+        std::fs::write(root.join("version"), "1.0.0\n").unwrap();
+        std::fs::write(root.join("healthy"), "").unwrap();
+        ProjectPaths::from_root(root)
+    }
+
+    fn sh(script: &str) -> ToolInstallCommand {
+        ToolInstallCommand {
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), script.to_string()],
+        }
+    }
+
+    /// Fake tool whose update breaks the doctor check (`healthy` marker removed).
+    fn broken_update_spec(paths: &ProjectPaths, rollback: Option<ToolRollbackSpec>) -> ToolSpec {
+        ToolSpec {
+            api_version: "v1".to_string(),
+            id: "fake-tool".to_string(),
+            display_name: "Fake Tool".to_string(),
+            description: None,
+            capabilities: vec![],
+            fields: vec![],
+            doctor: Some(vec![DoctorCheckSpec {
+                kind: DoctorCheckKind::PathExists,
+                value: paths.root.join("healthy").to_string_lossy().to_string(),
+                severity: CheckSeverity::Error,
+            }]),
+            gitignore: vec![],
+            performer: None,
+            install: None,
+            update: Some(ToolInstallSpec {
+                commands: vec![sh("echo 2.0.0 > version && rm -f healthy")],
+                post_install: None,
+                confirm_message: None,
+            }),
+            version_check: Some(ToolVersionCheckSpec {
+                current: ToolInstallCommand {
+                    command: "cat".to_string(),
+                    args: vec![paths.root.join("version").to_string_lossy().to_string()],
+                },
+                latest: None,
+            }),
+            rollback,
+            defaults: None,
+        }
+    }
+
+    #[test]
+    fn test_failed_update_rolls_back_to_previous_version_and_records_history() {
+        let paths = temp_project("commands");
+        let spec = broken_update_spec(
+            &paths,
+            Some(ToolRollbackSpec {
+                commands: vec![sh("echo {previous_version} > version && touch healthy")],
+                snapshot: None,
+                restore: None,
+            }),
+        );
+
+        let err = update_single_tool(&paths, &spec, true, true, &NoopReporter).unwrap_err();
+        assert!(err.to_string().contains("rolled back to 1.0.0"), "{}", err);
+        assert_eq!(
+            std::fs::read_to_string(paths.root.join("version"))
+                .unwrap()
+                .trim(),
+            "1.0.0"
+        );
+
+        let history = read_tool_history(&paths, Some("fake-tool")).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].outcome, ToolUpdateOutcome::RolledBack);
+        assert_eq!(history[0].previous_version.as_deref(), Some("1.0.0"));
+        let rollback = history[0].rollback.as_ref().unwrap();
+        assert_eq!(rollback.method, "commands");
+        assert_eq!(rollback.restored_version.as_deref(), Some("1.0.0"));
+
+        // Without --rollback-on-fail the failed update is left in place.
+        std::fs::write(paths.root.join("healthy"), "").unwrap();
+        std::fs::write(paths.root.join("version"), "1.0.0\n").unwrap();
+        assert!(update_single_tool(&paths, &spec, true, false, &NoopReporter).is_err());
+        let history = read_tool_history(&paths, Some("fake-tool")).unwrap();
+        assert_eq!(history.last().unwrap().outcome, ToolUpdateOutcome::Failed);
+        assert_eq!(
+            std::fs::read_to_string(paths.root.join("version"))
+                .unwrap()
+                .trim(),
+            "2.0.0"
+        );
+        std::fs::remove_dir_all(&paths.root).ok();
+    }
+
+    #[test]
+    fn test_restore_from_snapshot_when_rollback_commands_fail() {
+        let paths = temp_project("restore");
+        let spec = broken_update_spec(
+            &paths,
+            Some(ToolRollbackSpec {
+                commands: vec![sh("exit 3")],
+                snapshot: Some(sh("cp version healthy {snapshot_dir}/")),
+                restore: Some(sh("cp {snapshot_dir}/version {snapshot_dir}/healthy .")),
+            }),
+        );
+
+        assert!(update_single_tool(&paths, &spec, true, true, &NoopReporter).is_err());
+        let history = read_tool_history(&paths, None).unwrap();
+        assert_eq!(history[0].outcome, ToolUpdateOutcome::RolledBack);
+        assert_eq!(history[0].rollback.as_ref().unwrap().method, "restore");
+        assert!(paths.root.join("healthy").exists());
+        std::fs::remove_dir_all(&paths.root).ok();
+    }
+
+    #[test]
+    fn test_rollback_on_fail_requires_rollback_section() {
+        let paths = temp_project("required");
+        let spec = broken_update_spec(&paths, None);
+        let err = update_single_tool(&paths, &spec, true, true, &NoopReporter).unwrap_err();
+        assert!(err.to_string().contains("no rollback section"));
+        assert_eq!(
+            std::fs::read_to_string(paths.root.join("version"))
+                .unwrap()
+                .trim(),
+            "1.0.0"
+        );
+
+        assert!(update_single_tool(&paths, &spec, true, false, &NoopReporter).is_err());
+        let history = read_tool_history(&paths, None).unwrap();
+        assert_eq!(history[0].outcome, ToolUpdateOutcome::Failed);
+        assert!(history[0].rollback.is_none());
+        std::fs::remove_dir_all(&paths.root).ok();
+    }
 }
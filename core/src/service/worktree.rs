@@ -1,6 +1,6 @@
 use crate::engine::Engine;
 use crate::resolve::{resolve, resolve_fetch_units, CliOverrides};
-use crate::{load_canonical_config, MaccError, Result};
+use crate::{load_effective_config, MaccError, Result};
 use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
@@ -60,10 +60,6 @@ pub fn ensure_tool_json(repo_root: &Path, worktree_path: &Path, tool_id: &str) -
     crate::domain::worktree::ensure_tool_json(repo_root, worktree_path, tool_id)
 }
 
-pub fn ensure_performer(worktree_path: &Path) -> Result<PathBuf> {
-    crate::domain::worktree::ensure_performer(worktree_path)
-}
-
 pub fn resolve_worktree_task_context(
     repo_root: &Path,
     worktree_path: &Path,
@@ -80,7 +76,7 @@ pub fn apply_worktree(
     allow_user_scope: bool,
 ) -> Result<()> {
     let paths = crate::ProjectPaths::from_root(worktree_root);
-    let canonical = load_canonical_config(&paths.config_path)?;
+    let canonical = load_effective_config(&paths)?;
     let metadata = crate::read_worktree_metadata(worktree_root)?
         .ok_or_else(|| MaccError::Validation("Missing .macc/worktree.json".into()))?;
 
@@ -137,7 +133,7 @@ pub fn setup_worktrees_workflow(
     options: WorktreeSetupOptions,
 ) -> Result<Vec<crate::WorktreeCreateResult>> {
     let repo_paths = crate::ProjectPaths::from_root(repo_root);
-    let canonical = load_canonical_config(&repo_paths.config_path)?;
+    let canonical = load_effective_config(&repo_paths)?;
     let yaml = canonical.to_yaml().map_err(|e| {
         MaccError::Validation(format!("Failed to serialize config for worktree: {}", e))
     })?;
@@ -1,7 +1,8 @@
 use crate::MaccError;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(tag = "action", rename_all = "snake_case")]
 pub enum ActionSpec {
     OpenMcp { target_pointer: String },
@@ -10,7 +11,7 @@ pub enum ActionSpec {
     Custom { target: String },
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum FieldKindSpec {
     Bool,
@@ -21,21 +22,21 @@ pub enum FieldKindSpec {
     Action(ActionSpec),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 pub struct ToolPerformerCommand {
     pub command: String,
     #[serde(default)]
     pub args: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 pub struct ToolPerformerPrompt {
     pub mode: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub arg: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 pub struct ToolPerformerSessionSpec {
     #[serde(default = "default_true")]
     pub enabled: bool,
@@ -53,9 +54,42 @@ pub struct ToolPerformerSessionSpec {
     pub id_strategy: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+/// How the performer reads the tool's stdout.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct ToolPerformerOutput {
+    /// `text` (default), `stream-json` or `json-events`; structured formats are parsed into
+    /// live `progress`/`artifact` coordinator events.
+    pub format: String,
+}
+
+/// Regexes that read token usage from the tool's output; capture group 1 is the count.
+/// Matches are summed per call. Structured output formats report usage on their own.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct ToolPerformerUsage {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_tokens_regex: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_tokens_regex: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cached_input_tokens_regex: Option<String>,
+}
+
+/// Provider throttling detection for failed calls (rate limits, exhausted quota).
+/// `patterns` extend the built-in ones; all regexes are case-insensitive.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct ToolPerformerThrottle {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub patterns: Vec<String>,
+    /// Capture group 1 is the wait in seconds (or `ms`/`m`/`h` when group 2 names the unit).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_after_regex: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 pub struct ToolPerformerSpec {
-    pub runner: String,
+    /// Former per-tool runner script path; ignored since every performer call is native.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub runner: Option<String>,
     pub command: String,
     #[serde(default)]
     pub args: Vec<String>,
@@ -65,16 +99,24 @@ pub struct ToolPerformerSpec {
     pub prompt: Option<ToolPerformerPrompt>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub session: Option<ToolPerformerSessionSpec>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<ToolPerformerOutput>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<ToolPerformerUsage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub throttle: Option<ToolPerformerThrottle>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sandbox: Option<crate::config::SandboxConfig>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 pub struct ToolInstallCommand {
     pub command: String,
     #[serde(default)]
     pub args: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 pub struct ToolInstallSpec {
     #[serde(default)]
     pub commands: Vec<ToolInstallCommand>,
@@ -84,14 +126,31 @@ pub struct ToolInstallSpec {
     pub confirm_message: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+/// Undo contract for `macc tool update`.
+///
+/// Command arguments may contain `{previous_version}` (captured from `version_check.current`
+/// before updating) and `{snapshot_dir}` (a per-update directory under `.macc/state`).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct ToolRollbackSpec {
+    /// Reinstall the previous version; skipped when the previous version is unknown.
+    #[serde(default)]
+    pub commands: Vec<ToolInstallCommand>,
+    /// Runs before the update to save state that `restore` can bring back.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snapshot: Option<ToolInstallCommand>,
+    /// Fallback when `commands` cannot run or fail.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub restore: Option<ToolInstallCommand>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 pub struct ToolVersionCheckSpec {
     pub current: ToolInstallCommand,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub latest: Option<ToolInstallCommand>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 pub struct ToolRuntimeConfig {
     pub api_version: String,
     pub id: String,
@@ -101,7 +160,7 @@ pub struct ToolRuntimeConfig {
     pub defaults: Option<serde_json::Value>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 pub struct FieldSpec {
     pub id: String,
     pub label: String,
@@ -118,7 +177,7 @@ pub struct FieldSpec {
     pub default: Option<serde_json::Value>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum DoctorCheckKind {
     Which,
@@ -126,21 +185,21 @@ pub enum DoctorCheckKind {
     Custom,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum CheckSeverity {
     Error,
     Warning,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 pub struct DoctorCheckSpec {
     pub kind: DoctorCheckKind,
     pub value: String,
     pub severity: CheckSeverity,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 pub struct ToolSpec {
     pub api_version: String,
     pub id: String,
@@ -163,6 +222,8 @@ pub struct ToolSpec {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub version_check: Option<ToolVersionCheckSpec>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub rollback: Option<ToolRollbackSpec>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub defaults: Option<serde_json::Value>,
 }
 
@@ -389,16 +450,31 @@ impl ToolSpec {
             }
         }
 
-        if let Some(performer) = &self.performer {
-            if performer.command.trim().is_empty() {
+        if let Some(rollback) = &self.rollback {
+            if rollback.commands.is_empty() && rollback.restore.is_none() {
                 return Err(MaccError::Validation(format!(
-                    "Performer command must be set for tool '{}'",
+                    "Rollback for tool '{}' must define commands or restore",
+                    self.id
+                )));
+            }
+            if rollback.restore.is_some() && rollback.snapshot.is_none() {
+                return Err(MaccError::Validation(format!(
+                    "Rollback restore for tool '{}' requires a snapshot command",
+                    self.id
+                )));
+            }
+            if !rollback.commands.is_empty() && self.version_check.is_none() {
+                return Err(MaccError::Validation(format!(
+                    "Rollback commands for tool '{}' require version_check to capture the previous version",
                     self.id
                 )));
             }
-            if performer.runner.trim().is_empty() {
+        }
+
+        if let Some(performer) = &self.performer {
+            if performer.command.trim().is_empty() {
                 return Err(MaccError::Validation(format!(
-                    "Performer runner must be set for tool '{}'",
+                    "Performer command must be set for tool '{}'",
                     self.id
                 )));
             }
@@ -425,6 +501,57 @@ impl ToolSpec {
                     )));
                 }
             }
+            if let Some(output) = &performer.output {
+                if output
+                    .format
+                    .parse::<crate::performer::output::OutputFormat>()
+                    .is_err()
+                {
+                    return Err(MaccError::Validation(format!(
+                        "Performer output format must be one of {} for tool '{}'",
+                        crate::performer::output::OUTPUT_FORMATS.join(", "),
+                        self.id
+                    )));
+                }
+            }
+            if let Some(usage) = &performer.usage {
+                for (field, pattern) in [
+                    ("input_tokens_regex", &usage.input_tokens_regex),
+                    ("output_tokens_regex", &usage.output_tokens_regex),
+                    (
+                        "cached_input_tokens_regex",
+                        &usage.cached_input_tokens_regex,
+                    ),
+                ] {
+                    if let Some(pattern) = pattern {
+                        if let Err(e) = regex::Regex::new(pattern) {
+                            return Err(MaccError::Validation(format!(
+                                "Performer usage.{} is not a valid regex for tool '{}': {}",
+                                field, self.id, e
+                            )));
+                        }
+                    }
+                }
+            }
+            if let Some(throttle) = &performer.throttle {
+                let patterns = throttle.patterns.iter().map(|p| ("patterns", p)).chain(
+                    throttle
+                        .retry_after_regex
+                        .iter()
+                        .map(|p| ("retry_after_regex", p)),
+                );
+                for (field, pattern) in patterns {
+                    if let Err(e) = regex::Regex::new(pattern) {
+                        return Err(MaccError::Validation(format!(
+                            "Performer throttle.{} is not a valid regex for tool '{}': {}",
+                            field, self.id, e
+                        )));
+                    }
+                }
+            }
+            if let Some(sandbox) = &performer.sandbox {
+                sandbox.validate(&format!("Performer of tool '{}'", self.id))?;
+            }
             if let Some(session) = &performer.session {
                 if let Some(scope) = &session.scope {
                     if scope != "project" && scope != "worktree" {
@@ -627,6 +754,7 @@ mod tests {
             install: None,
             update: None,
             version_check: None,
+            rollback: None,
             defaults: None,
         };
 
@@ -668,6 +796,7 @@ mod tests {
             install: None,
             update: None,
             version_check: None,
+            rollback: None,
             defaults: None,
         };
 
@@ -696,6 +825,7 @@ mod tests {
             install: None,
             update: None,
             version_check: None,
+            rollback: None,
             defaults: None,
         };
 
@@ -754,6 +884,7 @@ mod tests {
             install: None,
             update: None,
             version_check: None,
+            rollback: None,
             defaults: None,
         };
 
@@ -808,6 +939,7 @@ mod tests {
             install: None,
             update: None,
             version_check: None,
+            rollback: None,
             defaults: None,
         };
 
@@ -878,6 +1010,38 @@ fields:
         assert_eq!(spec.fields.len(), 2);
     }
 
+    #[test]
+    fn test_invalid_throttle_regex_is_rejected() {
+        let json = r#"{
+  "api_version": "v1",
+  "id": "sample-tool",
+  "display_name": "Sample Tool",
+  "fields": [],
+  "performer": {
+    "command": "sample",
+    "throttle": { "patterns": ["capacity (reached"] }
+  }
+}"#;
+        let err = ToolSpec::from_json(json).unwrap_err().to_string();
+        assert!(err.contains("throttle.patterns"), "{}", err);
+    }
+
+    #[test]
+    fn test_invalid_usage_regex_is_rejected() {
+        let json = r#"{
+  "api_version": "v1",
+  "id": "sample-tool",
+  "display_name": "Sample Tool",
+  "fields": [],
+  "performer": {
+    "command": "sample",
+    "usage": { "output_tokens_regex": "output: ([0-9]+" }
+  }
+}"#;
+        let err = ToolSpec::from_json(json).unwrap_err().to_string();
+        assert!(err.contains("usage.output_tokens_regex"), "{}", err);
+    }
+
     #[test]
     fn test_invalid_action_parsing() {
         let yaml = r#"
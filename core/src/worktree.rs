@@ -1,5 +1,5 @@
-use crate::tool::ToolSpecLoader;
-use crate::{config::CanonicalConfig, MaccError, ProjectPaths, Result};
+use crate::tool::{ToolRuntimeConfig, ToolSpecLoader};
+use crate::{config::CanonicalConfig, MaccError, Result};
 use std::collections::{BTreeMap, BTreeSet};
 use std::path::{Path, PathBuf};
 
@@ -194,7 +194,8 @@ pub fn read_worktree_metadata(path: &Path) -> Result<Option<WorktreeMetadata>> {
     Ok(Some(metadata))
 }
 
-pub fn write_tool_json(repo_root: &Path, worktree_path: &Path, tool_id: &str) -> Result<PathBuf> {
+/// Runtime config (`tool.json` contents) for `tool_id` from the project's ToolSpecs.
+pub fn tool_runtime_config(repo_root: &Path, tool_id: &str) -> Result<ToolRuntimeConfig> {
     let search_paths = ToolSpecLoader::default_search_paths(repo_root);
     let loader = ToolSpecLoader::new(search_paths);
     let (specs, _) = loader.load_all_with_embedded();
@@ -203,17 +204,13 @@ pub fn write_tool_json(repo_root: &Path, worktree_path: &Path, tool_id: &str) ->
         .into_iter()
         .find(|spec| spec.id == tool_id)
         .ok_or_else(|| MaccError::Validation(format!("Tool spec not found: {}", tool_id)))?;
-    let mut runtime = spec.to_runtime_config().ok_or_else(|| {
+    spec.to_runtime_config().ok_or_else(|| {
         MaccError::Validation(format!("Tool spec missing performer section: {}", tool_id))
-    })?;
+    })
+}
 
-    let worktree_paths = ProjectPaths::from_root(worktree_path);
-    let _ = crate::ensure_embedded_automation_scripts(&worktree_paths)?;
-    if let Some(runner_path) =
-        crate::embedded_runner_path_for_ref(&worktree_paths, &runtime.performer.runner)?
-    {
-        runtime.performer.runner = runner_path.to_string_lossy().into_owned();
-    }
+pub fn write_tool_json(repo_root: &Path, worktree_path: &Path, tool_id: &str) -> Result<PathBuf> {
+    let runtime = tool_runtime_config(repo_root, tool_id)?;
 
     let macc_dir = worktree_path.join(".macc");
     std::fs::create_dir_all(&macc_dir).map_err(|e| MaccError::Io {
@@ -233,43 +230,6 @@ pub fn write_tool_json(repo_root: &Path, worktree_path: &Path, tool_id: &str) ->
     Ok(tool_json_path)
 }
 
-pub fn ensure_performer(worktree_path: &Path) -> Result<PathBuf> {
-    let target = worktree_path.join("performer.sh");
-    if target.exists() {
-        return Ok(target);
-    }
-
-    let worktree_paths = ProjectPaths::from_root(worktree_path);
-    let _ = crate::ensure_embedded_automation_scripts(&worktree_paths)?;
-    let source = worktree_paths.automation_performer_path();
-
-    std::fs::copy(&source, &target).map_err(|e| MaccError::Io {
-        path: target.to_string_lossy().into(),
-        action: "copy performer.sh".into(),
-        source: e,
-    })?;
-
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        let mut perms = std::fs::metadata(&target)
-            .map_err(|e| MaccError::Io {
-                path: target.to_string_lossy().into(),
-                action: "read performer permissions".into(),
-                source: e,
-            })?
-            .permissions();
-        perms.set_mode(0o755);
-        std::fs::set_permissions(&target, perms).map_err(|e| MaccError::Io {
-            path: target.to_string_lossy().into(),
-            action: "set performer permissions".into(),
-            source: e,
-        })?;
-    }
-
-    Ok(target)
-}
-
 pub fn resolve_worktree_task_context(
     repo_root: &Path,
     worktree_path: &Path,
@@ -314,14 +274,41 @@ pub fn sync_context_files_from_root(
     worktree_root: &Path,
     canonical: &CanonicalConfig,
 ) -> Result<()> {
-    let targets = collect_context_targets(repo_root, canonical);
+    copy_context_files(
+        repo_root,
+        worktree_root,
+        collect_context_targets(repo_root, canonical),
+    )?;
+
+    // Sub-projects keep their generated context files (`*.md` tool outputs) next to
+    // their own `.macc/`, so copy those too.
+    for project in crate::monorepo::discover_projects(repo_root) {
+        if project.root == repo_root {
+            continue;
+        }
+        let Ok(rel) = project.root.strip_prefix(repo_root) else {
+            continue;
+        };
+        let Ok(sub_config) = crate::config::load_effective_config(&project) else {
+            continue;
+        };
+        copy_context_files(
+            &project.root,
+            &worktree_root.join(rel),
+            collect_context_targets(&project.root, &sub_config),
+        )?;
+    }
+    Ok(())
+}
+
+fn copy_context_files(source_root: &Path, dest_root: &Path, targets: Vec<String>) -> Result<()> {
     for rel in targets {
-        let src = repo_root.join(&rel);
+        let src = source_root.join(&rel);
         if !src.is_file() {
             continue;
         }
 
-        let dest = worktree_root.join(&rel);
+        let dest = dest_root.join(&rel);
         if src == dest {
             continue;
         }
@@ -0,0 +1,240 @@
+use macc_core::config::ApprovalPolicy;
+use macc_core::coordinator::approval;
+use macc_core::coordinator::control_plane::advance_tasks_native;
+use macc_core::coordinator::operator::{keep_operator_updates, run_task_command, TaskCommand};
+use macc_core::coordinator::runtime::CoordinatorRunState;
+use macc_core::coordinator::state::{
+    coordinator_state_registry_load, coordinator_state_registry_save,
+};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Keeps test results independent of the developer's `~/.config/macc/macc.yaml`.
+fn isolate_user_config() {
+    std::env::set_var(macc_core::config::layers::NO_USER_CONFIG_ENV, "1");
+}
+
+fn git(root: &Path, args: &[&str]) {
+    let status = std::process::Command::new("git")
+        .args(args)
+        .current_dir(root)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {:?} failed", args);
+}
+
+fn commit(root: &Path, file: &str) {
+    let path = root.join(file);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, file).unwrap();
+    git(root, &["add", file]);
+    git(root, &["commit", "-q", "-m", file]);
+}
+
+fn head(root: &Path, branch: &str) -> String {
+    let output = std::process::Command::new("git")
+        .args(["rev-parse", branch])
+        .current_dir(root)
+        .output()
+        .unwrap();
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+fn task(root: &Path, index: usize) -> serde_json::Value {
+    coordinator_state_registry_load(root, &BTreeMap::new()).unwrap()["tasks"][index].clone()
+}
+
+#[test]
+fn test_approval_policy_holds_queued_tasks_until_decided() {
+    isolate_user_config();
+    let root = std::env::temp_dir().join(format!(
+        "macc_approval_{}",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    ));
+    std::fs::create_dir_all(&root).unwrap();
+    git(&root, &["init", "-q", "-b", "main"]);
+    git(&root, &["config", "user.email", "test@example.com"]);
+    git(&root, &["config", "user.name", "test"]);
+    commit(&root, "README.md");
+    git(&root, &["checkout", "-q", "-b", "ai/T1"]);
+    commit(&root, "migrations/001.sql");
+    git(&root, &["checkout", "-q", "main"]);
+    git(&root, &["checkout", "-q", "-b", "ai/T2"]);
+    commit(&root, "docs/guide.md");
+    git(&root, &["checkout", "-q", "main"]);
+    let queued = |id: &str| {
+        serde_json::json!({
+            "id": id,
+            "title": id,
+            "state": "queued",
+            "tool": "tool-a",
+            "task_runtime": {"status": "phase_done", "current_phase": "integrate"},
+            "worktree": {"branch": format!("ai/{}", id), "base_branch": "main"}
+        })
+    };
+    coordinator_state_registry_save(
+        &root,
+        &BTreeMap::new(),
+        &serde_json::json!({"tasks": [queued("T1"), queued("T2")]}),
+    )
+    .unwrap();
+
+    let mut state = CoordinatorRunState::new();
+    state.approval = Some(ApprovalPolicy {
+        paths: vec!["migrations/**".into()],
+        ..Default::default()
+    });
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .unwrap();
+    let advance = |state: &mut CoordinatorRunState| {
+        runtime
+            .block_on(advance_tasks_native(&root, None, 1, state, None))
+            .unwrap()
+            .progressed
+    };
+
+    // T1 touches a migration and waits; T2 does not match and goes to merge.
+    assert!(advance(&mut state));
+    let waiting = task(&root, 0);
+    assert_eq!(waiting["state"], "queued");
+    assert_eq!(waiting["task_runtime"]["status"], "waiting_for_user");
+    assert_eq!(approval::task_approval_status(&waiting), Some("pending"));
+    assert_eq!(waiting["approval"]["request"]["kind"], "confirm_yes_no");
+    assert!(!state.active_merge_jobs.contains_key("T1"));
+    assert!(state.active_merge_jobs.contains_key("T2"));
+    let registry = coordinator_state_registry_load(&root, &BTreeMap::new()).unwrap();
+    assert_eq!(approval::pending_approvals(&registry), vec!["T1"]);
+
+    // Still pending: nothing new happens for T1.
+    assert!(!advance(&mut state));
+    assert!(!state.active_merge_jobs.contains_key("T1"));
+
+    // A rejection sends the task back to the fix phase with the comment as feedback.
+    approval::decide_task_approval(&root, "T1", false, "alice", Some("Split this migration"))
+        .unwrap();
+    let rejected = task(&root, 0);
+    assert_eq!(rejected["state"], "changes_requested");
+    assert_eq!(rejected["review_comments"][0]["author"], "alice");
+    assert_eq!(
+        rejected["review_comments"][0]["body"],
+        "Split this migration"
+    );
+    assert!(approval::decide_task_approval(&root, "T1", true, "alice", None).is_err());
+
+    // Back in the queue after the fix, the task asks again; approval releases it to merge.
+    let mut registry = coordinator_state_registry_load(&root, &BTreeMap::new()).unwrap();
+    registry["tasks"][0]["state"] = serde_json::Value::String("queued".into());
+    coordinator_state_registry_save(&root, &BTreeMap::new(), &registry).unwrap();
+    assert!(advance(&mut state));
+    assert_eq!(
+        approval::task_approval_status(&task(&root, 0)),
+        Some("pending")
+    );
+    approval::decide_task_approval(&root, "T1", true, "alice", None).unwrap();
+    let approved = task(&root, 0);
+    assert_eq!(approved["approval"]["decided_by"], "alice");
+    assert_eq!(approved["task_runtime"]["status"], "idle");
+    advance(&mut state);
+    assert!(state.active_merge_jobs.contains_key("T1"));
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn test_approval_is_bound_to_the_head_and_survives_a_concurrent_cycle() {
+    isolate_user_config();
+    let root = std::env::temp_dir().join(format!(
+        "macc_approval_head_{}",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    ));
+    std::fs::create_dir_all(&root).unwrap();
+    git(&root, &["init", "-q", "-b", "main"]);
+    git(&root, &["config", "user.email", "test@example.com"]);
+    git(&root, &["config", "user.name", "test"]);
+    commit(&root, "README.md");
+    git(&root, &["checkout", "-q", "-b", "ai/T1"]);
+    commit(&root, "migrations/001.sql");
+    git(&root, &["checkout", "-q", "main"]);
+    coordinator_state_registry_save(
+        &root,
+        &BTreeMap::new(),
+        &serde_json::json!({"tasks": [{
+            "id": "T1",
+            "title": "T1",
+            "state": "queued",
+            "tool": "tool-a",
+            "task_runtime": {"status": "phase_done", "current_phase": "integrate"},
+            "worktree": {"branch": "ai/T1", "base_branch": "main"}
+        }]}),
+    )
+    .unwrap();
+
+    let mut state = CoordinatorRunState::new();
+    state.approval = Some(ApprovalPolicy {
+        paths: vec!["migrations/**".into()],
+        ..Default::default()
+    });
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .unwrap();
+    let advance = |state: &mut CoordinatorRunState| {
+        runtime
+            .block_on(advance_tasks_native(&root, None, 1, state, None))
+            .unwrap()
+            .progressed
+    };
+    let status = || approval::task_approval_status(&task(&root, 0)).map(str::to_string);
+
+    assert!(advance(&mut state));
+    assert_eq!(task(&root, 0)["approval"]["head"], head(&root, "ai/T1"));
+
+    // A cycle that loaded the registry before the decision saves after it.
+    let mut stale = coordinator_state_registry_load(&root, &BTreeMap::new()).unwrap();
+    approval::decide_task_approval(&root, "T1", true, "alice", None).unwrap();
+    let latest = coordinator_state_registry_load(&root, &BTreeMap::new()).unwrap();
+    keep_operator_updates(&mut stale, &latest);
+    coordinator_state_registry_save(&root, &BTreeMap::new(), &stale).unwrap();
+    assert_eq!(status().as_deref(), Some("approved"));
+
+    // New commits on the branch void the approval: the task asks again.
+    git(&root, &["checkout", "-q", "ai/T1"]);
+    commit(&root, "migrations/002.sql");
+    git(&root, &["checkout", "-q", "main"]);
+    assert!(advance(&mut state));
+    assert!(!state.active_merge_jobs.contains_key("T1"));
+    assert_eq!(status().as_deref(), Some("pending"));
+    let requests = approval::pending_approval_requests(
+        &coordinator_state_registry_load(&root, &BTreeMap::new()).unwrap(),
+    );
+    assert!(
+        requests[0].reasons[0].starts_with("branch head moved"),
+        "{:?}",
+        requests
+    );
+    assert_eq!(task(&root, 0)["approval"]["head"], head(&root, "ai/T1"));
+
+    // Leaving the queue voids the approval too.
+    approval::decide_task_approval(&root, "T1", true, "alice", None).unwrap();
+    run_task_command(&root, &TaskCommand::Block("T1".into()), "bob", None).unwrap();
+    assert_eq!(status().as_deref(), Some("void"));
+    run_task_command(&root, &TaskCommand::Unblock("T1".into()), "bob", None).unwrap();
+    assert_eq!(task(&root, 0)["state"], "queued");
+    assert!(advance(&mut state));
+    assert!(!state.active_merge_jobs.contains_key("T1"));
+    assert_eq!(status().as_deref(), Some("pending"));
+
+    approval::decide_task_approval(&root, "T1", true, "alice", None).unwrap();
+    advance(&mut state);
+    assert!(state.active_merge_jobs.contains_key("T1"));
+
+    let _ = std::fs::remove_dir_all(&root);
+}
@@ -0,0 +1,182 @@
+use macc_core::config::DecomposeConfig;
+use macc_core::coordinator::control_plane::{advance_tasks_native, merge_prd_definitions};
+use macc_core::coordinator::runtime::CoordinatorRunState;
+use macc_core::coordinator::state::{
+    coordinator_state_registry_load, coordinator_state_registry_save,
+};
+use macc_core::coordinator::task_selector::{select_next_ready_task, TaskSelectorConfig};
+use std::collections::BTreeMap;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+
+/// Keeps test results independent of the developer's `~/.config/macc/macc.yaml`.
+fn isolate_user_config() {
+    std::env::set_var(macc_core::config::layers::NO_USER_CONFIG_ENV, "1");
+}
+
+/// Worktree whose `tool` prints `reply` whatever the prompt.
+fn worktree(root: &Path, name: &str, tool: &str, reply: &str) -> String {
+    let worktree = root.join("worktrees").join(name);
+    let macc = worktree.join(".macc");
+    std::fs::create_dir_all(&macc).unwrap();
+    let script = macc.join("fake-tool.sh");
+    std::fs::write(&script, format!("#!/bin/sh\ncat <<'EOF'\n{}\nEOF\n", reply)).unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let tool_json = serde_json::json!({
+        "api_version": "v1",
+        "id": tool,
+        "display_name": tool,
+        "performer": { "command": script.to_string_lossy() },
+    });
+    std::fs::write(macc.join("tool.json"), tool_json.to_string()).unwrap();
+    worktree.to_string_lossy().to_string()
+}
+
+fn registry(root: &Path) -> serde_json::Value {
+    coordinator_state_registry_load(root, &BTreeMap::new()).unwrap()
+}
+
+fn task<'a>(registry: &'a serde_json::Value, id: &str) -> &'a serde_json::Value {
+    registry["tasks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|t| t["id"] == id)
+        .unwrap()
+}
+
+fn ids(registry: &serde_json::Value) -> Vec<String> {
+    registry["tasks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| t["id"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn test_blocked_task_is_decomposed_and_merges_with_its_subtasks() {
+    isolate_user_config();
+    let root = std::env::temp_dir().join(format!(
+        "macc_decompose_{}",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    ));
+    std::fs::create_dir_all(&root).unwrap();
+    let plan = worktree(
+        &root,
+        "T1",
+        "tool-a",
+        "Two steps.\n```json\n{\"subtasks\": [\
+         {\"id\": \"api\", \"title\": \"Add the API\", \"scope\": [\"src/api/**\"]},\
+         {\"id\": \"ui\", \"title\": \"Add the UI\", \"category\": \"frontend\", \"dependencies\": [\"api\", \"T0\"]}\
+         ]}\n```",
+    );
+    let no_plan = worktree(&root, "T3", "tool-b", "I cannot split this task.");
+    coordinator_state_registry_save(
+        &root,
+        &BTreeMap::new(),
+        &serde_json::json!({"tasks": [
+            {"id": "T0", "title": "Base", "state": "merged"},
+            {
+                "id": "T1",
+                "title": "Feature",
+                "state": "blocked",
+                "category": "backend",
+                "tool": "tool-a",
+                "task_runtime": {
+                    "status": "failed",
+                    "current_phase": "integrate",
+                    "last_error": "size limit exceeded: 900 lines changed (max 300)"
+                },
+                "worktree": {"worktree_path": plan, "branch": "ai/T1", "base_branch": "main"}
+            },
+            {"id": "T2", "title": "Follow-up", "state": "todo", "dependencies": ["T1"]},
+            {
+                "id": "T3",
+                "title": "Other",
+                "state": "blocked",
+                "tool": "tool-b",
+                "task_runtime": {"status": "failed", "last_error": "performer failed after 3 attempts"},
+                "worktree": {"worktree_path": no_plan, "branch": "ai/T3", "base_branch": "main"}
+            }
+        ]}),
+    )
+    .unwrap();
+
+    let mut state = CoordinatorRunState::new();
+    state.decompose = Some(DecomposeConfig::default());
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .unwrap();
+    let advance = |state: &mut CoordinatorRunState| {
+        runtime
+            .block_on(advance_tasks_native(&root, None, 1, state, None))
+            .unwrap()
+            .progressed
+    };
+
+    assert!(advance(&mut state));
+    let decomposed = registry(&root);
+    assert_eq!(ids(&decomposed), ["T0", "T1", "T1.1", "T1.2", "T2", "T3"]);
+    let parent = task(&decomposed, "T1");
+    assert_eq!(parent["state"], "todo");
+    assert_eq!(parent["subtasks"], serde_json::json!(["T1.1", "T1.2"]));
+    assert_eq!(parent["decomposition"]["status"], "decomposed");
+    assert!(parent["worktree"].is_null());
+    let api = task(&decomposed, "T1.1");
+    assert_eq!(api["state"], "todo");
+    assert_eq!(api["parent"], "T1");
+    assert_eq!(api["category"], "backend");
+    assert_eq!(api["scope"], serde_json::json!(["src/api/**"]));
+    let ui = task(&decomposed, "T1.2");
+    assert_eq!(ui["category"], "frontend");
+    assert_eq!(ui["dependencies"], serde_json::json!(["T1.1", "T0"]));
+
+    // An unusable reply is recorded once and the task stays blocked.
+    let failed = task(&decomposed, "T3");
+    assert_eq!(failed["state"], "blocked");
+    assert_eq!(failed["decomposition"]["status"], "failed");
+    assert!(failed["decomposition"]["error"]
+        .as_str()
+        .unwrap()
+        .contains("no JSON object"));
+
+    // The tracking task is never dispatched; its first subtask is.
+    let selector = TaskSelectorConfig {
+        default_tool: "tool-a".into(),
+        default_base_branch: "main".into(),
+        max_parallel: 3,
+        ..TaskSelectorConfig::default()
+    };
+    assert_eq!(
+        select_next_ready_task(&decomposed, &selector).unwrap().id,
+        "T1.1"
+    );
+
+    // A PRD sync keeps the subtasks, which are not in the PRD.
+    let mut synced = decomposed.clone();
+    let prd: Vec<serde_json::Value> = ["T0", "T1", "T2", "T3"]
+        .iter()
+        .map(|id| serde_json::json!({"id": id, "title": id}))
+        .collect();
+    merge_prd_definitions(&mut synced, &prd, "2026-01-01T00:00:00Z");
+    assert_eq!(ids(&synced), ids(&decomposed));
+
+    let mut registry_now = decomposed.clone();
+    for task in registry_now["tasks"].as_array_mut().unwrap() {
+        if task["parent"] == "T1" {
+            task["state"] = serde_json::Value::String("merged".into());
+        }
+    }
+    coordinator_state_registry_save(&root, &BTreeMap::new(), &registry_now).unwrap();
+    assert!(advance(&mut state));
+    let settled = registry(&root);
+    assert_eq!(task(&settled, "T1")["state"], "merged");
+    assert_eq!(task(&settled, "T3")["state"], "blocked");
+
+    let _ = std::fs::remove_dir_all(&root);
+}
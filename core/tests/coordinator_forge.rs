@@ -0,0 +1,156 @@
+use macc_core::coordinator::control_plane::advance_tasks_native;
+use macc_core::coordinator::forge::{
+    self, FileForge, Forge, MergeMethod, PullRequestState, Review, ReviewComment, ReviewState,
+};
+use macc_core::coordinator::runtime::CoordinatorRunState;
+use macc_core::coordinator::state::{
+    coordinator_state_registry_load, coordinator_state_registry_save,
+};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Keeps test results independent of the developer's `~/.config/macc/macc.yaml`.
+fn isolate_user_config() {
+    std::env::set_var(macc_core::config::layers::NO_USER_CONFIG_ENV, "1");
+}
+
+fn git(root: &Path, args: &[&str]) {
+    let status = std::process::Command::new("git")
+        .args(args)
+        .current_dir(root)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {:?} failed", args);
+}
+
+fn commit(root: &Path, file: &str) {
+    std::fs::write(root.join(file), file).unwrap();
+    git(root, &["add", file]);
+    git(root, &["commit", "-q", "-m", file]);
+}
+
+fn task(root: &Path) -> serde_json::Value {
+    coordinator_state_registry_load(root, &BTreeMap::new()).unwrap()["tasks"][0].clone()
+}
+
+fn set_state(root: &Path, state: &str) {
+    let mut registry = coordinator_state_registry_load(root, &BTreeMap::new()).unwrap();
+    registry["tasks"][0]["state"] = serde_json::Value::String(state.into());
+    coordinator_state_registry_save(root, &BTreeMap::new(), &registry).unwrap();
+}
+
+fn review(state: ReviewState, body: &str) -> Review {
+    Review {
+        author: "reviewer".into(),
+        state,
+        body: body.into(),
+        submitted_at: Some(chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true)),
+        comments: Vec::new(),
+    }
+}
+
+#[test]
+fn test_forge_drives_pr_open_and_changes_requested() {
+    isolate_user_config();
+    let root = std::env::temp_dir().join(format!(
+        "macc_forge_{}",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    ));
+    std::fs::create_dir_all(&root).unwrap();
+    git(&root, &["init", "-q", "-b", "main"]);
+    git(&root, &["config", "user.email", "test@example.com"]);
+    git(&root, &["config", "user.name", "test"]);
+    commit(&root, "README.md");
+    git(&root, &["checkout", "-q", "-b", "ai/T1"]);
+    commit(&root, "feature.txt");
+    git(&root, &["checkout", "-q", "main"]);
+    coordinator_state_registry_save(
+        &root,
+        &BTreeMap::new(),
+        &serde_json::json!({"tasks": [{
+            "id": "T1",
+            "title": "Add feature",
+            "state": "pr_open",
+            "tool": "tool-a",
+            "worktree": {"branch": "ai/T1", "base_branch": "main"}
+        }]}),
+    )
+    .unwrap();
+
+    let file_forge = Arc::new(FileForge::new(root.join(".macc").join("forge")));
+    let mut state = CoordinatorRunState::new();
+    state.forge = Some(Forge {
+        backend: file_forge.clone(),
+        remote: "origin".into(),
+        merge_method: MergeMethod::Squash,
+        poll_interval: Duration::ZERO,
+    });
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .unwrap();
+    let advance = |state: &mut CoordinatorRunState| {
+        runtime
+            .block_on(advance_tasks_native(&root, None, 1, state, None))
+            .unwrap()
+            .progressed
+    };
+
+    // First cycle pushes the branch and opens the pull request.
+    assert!(advance(&mut state));
+    let opened = task(&root);
+    assert_eq!(opened["state"], "pr_open");
+    assert_eq!(forge::task_pull_request_number(&opened), Some(1));
+    assert!(opened["pr_url"].as_str().unwrap().starts_with("file://"));
+    let pr = file_forge.pull_request(1).unwrap();
+    assert_eq!(pr.head, "ai/T1");
+    assert_eq!(pr.title, "T1: Add feature");
+
+    // No review yet: the task waits.
+    assert!(!advance(&mut state));
+    assert_eq!(task(&root)["state"], "pr_open");
+
+    // A human change request moves the task to changes_requested with its comments.
+    let mut change_request = review(ReviewState::ChangesRequested, "Please add a test");
+    change_request.comments.push(ReviewComment {
+        author: "reviewer".into(),
+        body: "typo".into(),
+        path: Some("feature.txt".into()),
+        line: Some(1),
+    });
+    file_forge.add_review(1, change_request).unwrap();
+    assert!(advance(&mut state));
+    let requested = task(&root);
+    assert_eq!(requested["state"], "changes_requested");
+    assert_eq!(requested["review_comments"].as_array().unwrap().len(), 2);
+
+    // After the fix, the new head is pushed and the existing pull request updated; the old
+    // change request no longer counts.
+    git(&root, &["checkout", "-q", "ai/T1"]);
+    commit(&root, "feature_test.txt");
+    git(&root, &["checkout", "-q", "main"]);
+    set_state(&root, "pr_open");
+    std::thread::sleep(Duration::from_millis(1100));
+    assert!(advance(&mut state));
+    assert_eq!(forge::task_pull_request_number(&task(&root)), Some(1));
+    assert_eq!(file_forge.pull_requests().unwrap().len(), 1);
+    assert!(!advance(&mut state));
+    assert_eq!(task(&root)["state"], "pr_open");
+
+    // Merge goes through the forge with the configured method.
+    let forge = state.forge.clone().unwrap();
+    assert_eq!(
+        forge::merge_task_pull_request(&forge, &root, 1, "main").unwrap(),
+        Ok(())
+    );
+    let merged = file_forge.pull_request(1).unwrap();
+    assert_eq!(merged.state, PullRequestState::Merged);
+    assert_eq!(merged.merge_method.as_deref(), Some("squash"));
+
+    let _ = std::fs::remove_dir_all(&root);
+}
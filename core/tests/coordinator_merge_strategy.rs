@@ -0,0 +1,235 @@
+use macc_core::config::MergeConfig;
+use macc_core::coordinator::helpers::find_reusable_worktree_native;
+use macc_core::coordinator::runtime::{merge_task_with_policy_native, TaskMergeOptions};
+use std::path::{Path, PathBuf};
+
+fn git(root: &Path, args: &[&str]) -> String {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .current_dir(root)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "git {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+fn commit(root: &Path, file: &str, content: &str) {
+    let path = root.join(file);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, content).unwrap();
+    git(root, &["add", file]);
+    git(root, &["commit", "-q", "-m", file]);
+}
+
+/// `main` with README.md, and `ai/T1` adding two commits on top of it.
+fn repo(name: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!(
+        "macc_merge_{}_{}",
+        name,
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    ));
+    std::fs::create_dir_all(&root).unwrap();
+    git(&root, &["init", "-q", "-b", "main"]);
+    git(&root, &["config", "user.email", "test@example.com"]);
+    git(&root, &["config", "user.name", "test"]);
+    // Keep merge logs out of `git status` so the clean-tree precheck passes.
+    std::fs::write(root.join(".gitignore"), ".macc/\n").unwrap();
+    git(&root, &["add", ".gitignore"]);
+    commit(&root, "README.md", "readme\n");
+    git(&root, &["checkout", "-q", "-b", "ai/T1"]);
+    commit(&root, "src/a.rs", "a\n");
+    commit(&root, "src/b.rs", "b\n");
+    git(&root, &["checkout", "-q", "main"]);
+    root
+}
+
+fn options(strategy: &str) -> TaskMergeOptions {
+    let merge = MergeConfig {
+        strategy: Some(strategy.to_string()),
+        message: Some("{task_id}: {title} ({category})".to_string()),
+        ..MergeConfig::default()
+    };
+    let task = serde_json::json!({"id": "T1", "title": "Add files", "category": "backend"});
+    TaskMergeOptions::resolve(Some(&merge), &task).unwrap()
+}
+
+fn merge(root: &Path, options: &TaskMergeOptions) -> Result<(), String> {
+    merge_task_with_policy_native(root, "T1", "ai/T1", "main", options, |_, _, _, _, _, _| {})
+        .unwrap()
+}
+
+fn parents(root: &Path, rev: &str) -> usize {
+    git(root, &["rev-list", "--parents", "-n", "1", rev])
+        .split_whitespace()
+        .count()
+        - 1
+}
+
+#[test]
+fn test_no_ff_creates_merge_commit_with_template_message() {
+    let root = repo("noff");
+    merge(&root, &options("no-ff")).unwrap();
+    assert_eq!(parents(&root, "main"), 2);
+    assert_eq!(
+        git(&root, &["log", "-1", "--format=%s", "main"]),
+        "T1: Add files (backend)"
+    );
+    git(&root, &["merge-base", "--is-ancestor", "ai/T1", "main"]);
+}
+
+#[test]
+fn test_squash_lands_one_commit_per_task() {
+    let root = repo("squash");
+    let before = git(&root, &["rev-parse", "main"]);
+    merge(&root, &options("squash")).unwrap();
+    assert_eq!(parents(&root, "main"), 1);
+    assert_eq!(git(&root, &["rev-parse", "main~1"]), before);
+    assert_eq!(
+        git(&root, &["log", "-1", "--format=%s", "main"]),
+        "T1: Add files (backend)"
+    );
+    assert!(root.join("src/a.rs").exists() && root.join("src/b.rs").exists());
+
+    // A conflicting squash leaves `main` untouched and the tree clean.
+    git(&root, &["checkout", "-q", "-b", "ai/T2", &before]);
+    commit(&root, "src/a.rs", "other\n");
+    git(&root, &["checkout", "-q", "main"]);
+    let head = git(&root, &["rev-parse", "main"]);
+    let err = merge_task_with_policy_native(
+        &root,
+        "T2",
+        "ai/T2",
+        "main",
+        &options("squash"),
+        |_, _, _, _, _, _| {},
+    )
+    .unwrap()
+    .unwrap_err();
+    assert!(err.contains("strategy=squash"), "{}", err);
+    assert!(err.contains("src/a.rs"), "{}", err);
+    assert_eq!(git(&root, &["rev-parse", "main"]), head);
+    assert_eq!(git(&root, &["status", "--porcelain"]), "");
+}
+
+#[test]
+fn test_rebase_keeps_history_linear_while_branch_is_checked_out() {
+    let root = repo("rebase");
+    commit(&root, "CHANGELOG.md", "changes\n");
+    let base_tip = git(&root, &["rev-parse", "main"]);
+    let worktree = root.join(".macc").join("wt");
+    git(
+        &root,
+        &["worktree", "add", "-q", worktree.to_str().unwrap(), "ai/T1"],
+    );
+    merge(&root, &options("rebase")).unwrap();
+    assert_eq!(git(&root, &["rev-parse", "--abbrev-ref", "HEAD"]), "main");
+    assert_eq!(git(&root, &["rev-list", "--merges", "main"]), "");
+    assert_eq!(git(&root, &["rev-parse", "main~2"]), base_tip);
+    assert_eq!(
+        git(&root, &["log", "-1", "--format=%s", "main"]),
+        "src/b.rs"
+    );
+    assert_eq!(
+        git(&root, &["rev-parse", "ai/T1"]),
+        git(&root, &["rev-parse", "main"])
+    );
+}
+
+#[test]
+fn test_squash_merged_slot_is_reused() {
+    let root = repo("squash_reuse");
+    let origin = root.to_str().unwrap().to_string();
+    git(&root, &["remote", "add", "origin", &origin]);
+    let slot = root.join(".macc").join("worktree").join("worker-01");
+    git(
+        &root,
+        &["worktree", "add", "-q", slot.to_str().unwrap(), "ai/T1"],
+    );
+    merge(&root, &options("squash")).unwrap();
+    git(&root, &["merge-base", "--is-ancestor", "ai/T1", "main"]);
+
+    let registry = serde_json::json!({
+        "tasks": [{
+            "id": "T1",
+            "state": "merged",
+            "worktree": {"worktree_path": slot.to_string_lossy()}
+        }]
+    });
+    let (reused, error) =
+        find_reusable_worktree_native(&root, &registry, "tool-a", "main").unwrap();
+    let (path, _, _, _, _) = reused.unwrap_or_else(|| panic!("slot not reused: {:?}", error));
+    assert_eq!(path, slot);
+    assert!(slot.join("src/a.rs").exists() && slot.join("src/b.rs").exists());
+}
+
+#[test]
+fn test_ff_only_refuses_a_diverged_base() {
+    let root = repo("ffonly");
+    let branch_tip = git(&root, &["rev-parse", "ai/T1"]);
+    merge(&root, &options("ff-only")).unwrap();
+    assert_eq!(git(&root, &["rev-parse", "main"]), branch_tip);
+
+    let root = repo("ffonly_diverged");
+    commit(&root, "CHANGELOG.md", "changes\n");
+    let head = git(&root, &["rev-parse", "main"]);
+    let err = merge(&root, &options("ff-only")).unwrap_err();
+    assert!(err.contains("strategy=ff-only"), "{}", err);
+    assert_eq!(git(&root, &["rev-parse", "main"]), head);
+    assert_eq!(git(&root, &["status", "--porcelain"]), "");
+}
+
+#[test]
+fn test_task_strategy_overrides_config_and_is_validated() {
+    let merge = MergeConfig {
+        strategy: Some("squash".to_string()),
+        ..MergeConfig::default()
+    };
+    let task = serde_json::json!({"id": "T9", "merge_strategy": "ff-only"});
+    let resolved = TaskMergeOptions::resolve(Some(&merge), &task).unwrap();
+    assert_eq!(resolved.strategy, "ff-only");
+    assert_eq!(resolved.message, "macc: merge task T9");
+    let defaults = TaskMergeOptions::resolve(None, &serde_json::json!({"id": "T9"})).unwrap();
+    assert_eq!(defaults.strategy, "no-ff");
+    let err = TaskMergeOptions::resolve(None, &serde_json::json!({"merge_strategy": "octopus"}))
+        .unwrap_err();
+    assert!(err.contains("octopus"), "{}", err);
+}
+
+#[test]
+fn test_ssh_signing_signs_the_squash_commit() {
+    if std::process::Command::new("ssh-keygen")
+        .arg("-?")
+        .output()
+        .is_err()
+    {
+        return;
+    }
+    let root = repo("signed");
+    let key = root.join(".macc").join("signing_key");
+    std::fs::create_dir_all(key.parent().unwrap()).unwrap();
+    let status = std::process::Command::new("ssh-keygen")
+        .args(["-q", "-t", "ed25519", "-N", "", "-f"])
+        .arg(&key)
+        .status()
+        .unwrap();
+    assert!(status.success());
+    let merge_cfg = MergeConfig {
+        strategy: Some("squash".to_string()),
+        signing: Some("ssh".to_string()),
+        signing_key: Some(key.to_string_lossy().to_string()),
+        ..MergeConfig::default()
+    };
+    let options =
+        TaskMergeOptions::resolve(Some(&merge_cfg), &serde_json::json!({"id": "T1"})).unwrap();
+    merge(&root, &options).unwrap();
+    let raw = git(&root, &["cat-file", "commit", "main"]);
+    assert!(raw.contains("-----BEGIN SSH SIGNATURE-----"), "{}", raw);
+}
@@ -0,0 +1,44 @@
+use macc_core::coordinator::metrics::{MetricsServer, OPENMETRICS_CONTENT_TYPE};
+use std::io::{Read, Write};
+
+fn get(addr: std::net::SocketAddr, path: &str) -> String {
+    let mut stream = std::net::TcpStream::connect(addr).unwrap();
+    write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response
+}
+
+#[test]
+fn test_metrics_endpoint_serves_openmetrics() {
+    let root = std::env::temp_dir().join(format!(
+        "macc_metrics_{}",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    ));
+    let registry_dir = root.join(".macc").join("automation").join("task");
+    std::fs::create_dir_all(&registry_dir).unwrap();
+    std::fs::write(
+        registry_dir.join("task_registry.json"),
+        serde_json::json!({"tasks": [
+            {"id": "T1", "state": "in_progress", "tool": "tool-a",
+             "task_runtime": {"status": "running"}}
+        ]})
+        .to_string(),
+    )
+    .unwrap();
+
+    let server = MetricsServer::start(&root, "127.0.0.1:0").unwrap();
+    let response = get(server.local_addr(), "/metrics");
+    assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+    assert!(response.contains(OPENMETRICS_CONTENT_TYPE));
+    assert!(response.contains("macc_coordinator_tasks{state=\"in_progress\"} 1"));
+    assert!(response.contains("macc_coordinator_active_jobs{tool=\"tool-a\"} 1"));
+    assert!(response.trim_end().ends_with("# EOF"));
+
+    assert!(get(server.local_addr(), "/other").starts_with("HTTP/1.1 404"));
+    drop(server);
+    let _ = std::fs::remove_dir_all(&root);
+}
@@ -0,0 +1,121 @@
+use macc_core::coordinator::control_plane::sync_registry_from_prd_native;
+use macc_core::coordinator::operator::{
+    keep_operator_updates, run_task_command, TaskCommand, TaskCommandOutput,
+};
+use macc_core::coordinator::state::{
+    coordinator_state_registry_load, coordinator_state_registry_save,
+};
+use macc_core::coordinator_storage::{CoordinatorStorage, CoordinatorStoragePaths, SqliteStorage};
+use macc_core::ProjectPaths;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Keeps test results independent of the developer's `~/.config/macc/macc.yaml`.
+fn isolate_user_config() {
+    std::env::set_var(macc_core::config::layers::NO_USER_CONFIG_ENV, "1");
+}
+
+fn task(root: &Path, id: &str) -> serde_json::Value {
+    coordinator_state_registry_load(root, &BTreeMap::new()).unwrap()["tasks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|t| t["id"] == id)
+        .cloned()
+        .unwrap()
+}
+
+fn run(root: &Path, command: TaskCommand) -> macc_core::Result<String> {
+    match run_task_command(root, &command, "alice", Some("manual"))? {
+        TaskCommandOutput::Changed { message, .. } => Ok(message),
+        other => panic!("unexpected output {:?}", other),
+    }
+}
+
+fn exercise(mode: &str) {
+    std::env::set_var("COORDINATOR_STORAGE_MODE", mode);
+    let root = std::env::temp_dir().join(format!(
+        "macc_operator_{}_{}",
+        mode,
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    ));
+    std::fs::create_dir_all(&root).unwrap();
+    let prd = serde_json::json!({"tasks": [
+        {"id": "T1", "title": "one", "priority": "p2", "dependencies": []},
+        {"id": "T2", "title": "two", "priority": "p2", "dependencies": []},
+        {"id": "T3", "title": "three", "priority": "p2", "dependencies": []}
+    ]});
+    let prd_path = root.join("prd.json");
+    std::fs::write(&prd_path, prd.to_string()).unwrap();
+    sync_registry_from_prd_native(&root, &prd_path, None).unwrap();
+    let mut registry = coordinator_state_registry_load(&root, &BTreeMap::new()).unwrap();
+    registry["tasks"][2]["state"] = "in_progress".into();
+    registry["tasks"][2]["task_runtime"]["status"] = "phase_done".into();
+    coordinator_state_registry_save(&root, &BTreeMap::new(), &registry).unwrap();
+
+    // Field edits survive the PRD sync of the next cycle.
+    run(
+        &root,
+        TaskCommand::SetPriority {
+            task_id: "T2".into(),
+            priority: "p0".into(),
+        },
+    )
+    .unwrap();
+    run(
+        &root,
+        TaskCommand::AddDependency {
+            task_id: "T2".into(),
+            dependency: "T1".into(),
+        },
+    )
+    .unwrap();
+    sync_registry_from_prd_native(&root, &prd_path, None).unwrap();
+    let edited = task(&root, "T2");
+    assert_eq!(edited["priority"], "p0");
+    assert_eq!(edited["dependencies"], serde_json::json!(["T1"]));
+    assert_eq!(edited["operator"]["updated_by"], "alice");
+
+    // Workflow moves go through the transition rules.
+    assert!(run(&root, TaskCommand::Requeue("T1".into())).is_err());
+    run(&root, TaskCommand::Block("T3".into())).unwrap();
+    assert_eq!(task(&root, "T3")["state"], "blocked");
+    run(&root, TaskCommand::Unblock("T3".into())).unwrap();
+    assert_eq!(task(&root, "T3")["state"], "in_progress");
+    run(&root, TaskCommand::Block("T1".into())).unwrap();
+    run(&root, TaskCommand::Requeue("T1".into())).unwrap();
+    assert_eq!(task(&root, "T1")["state"], "todo");
+    // A control plane saving a registry it loaded before the move keeps the move.
+    let mut stale = coordinator_state_registry_load(&root, &BTreeMap::new()).unwrap();
+    run(&root, TaskCommand::Abandon("T2".into())).unwrap();
+    let latest = coordinator_state_registry_load(&root, &BTreeMap::new()).unwrap();
+    keep_operator_updates(&mut stale, &latest);
+    coordinator_state_registry_save(&root, &BTreeMap::new(), &stale).unwrap();
+    assert_eq!(task(&root, "T2")["state"], "abandoned");
+    assert_eq!(task(&root, "T2")["operator"]["last_action"], "abandon");
+
+    let paths = CoordinatorStoragePaths::from_project_paths(&ProjectPaths::from_root(&root));
+    let events = SqliteStorage::new(paths).load_snapshot().unwrap().events;
+    let operator: Vec<_> = events
+        .iter()
+        .filter(|e| e["type"] == "operator_action")
+        .collect();
+    assert_eq!(operator.len(), 7, "{} events: {:?}", mode, operator);
+    assert!(operator.iter().all(|e| e["payload"]["user"] == "alice"));
+    assert!(operator
+        .iter()
+        .any(|e| e["task_id"] == "T3" && e["payload"]["to"] == "blocked"));
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn test_operator_task_commands_in_json_and_sqlite_storage() {
+    isolate_user_config();
+    exercise("json");
+    exercise("sqlite");
+    std::env::remove_var("COORDINATOR_STORAGE_MODE");
+}
@@ -0,0 +1,78 @@
+use macc_core::coordinator::control_plane::run_phase_for_task_native;
+use std::os::unix::fs::PermissionsExt;
+
+/// Keeps test results independent of the developer's `~/.config/macc/macc.yaml`.
+fn isolate_user_config() {
+    std::env::set_var(macc_core::config::layers::NO_USER_CONFIG_ENV, "1");
+}
+
+#[test]
+fn test_phase_output_events_are_written_while_the_tool_runs() {
+    isolate_user_config();
+    let root = std::env::temp_dir().join(format!(
+        "macc_phase_output_{}",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    ));
+    let worktree = root.join("worktrees").join("T1");
+    let macc = worktree.join(".macc");
+    std::fs::create_dir_all(&macc).unwrap();
+    let events = root
+        .join(".macc")
+        .join("log")
+        .join("coordinator")
+        .join("events.jsonl");
+
+    // The tool only succeeds once its first tool call is already in the event log.
+    let script = macc.join("fake-tool.sh");
+    std::fs::write(
+        &script,
+        format!(
+            "#!/bin/sh\n\
+             echo '{{\"type\":\"assistant\",\"message\":{{\"content\":[{{\"type\":\"tool_use\",\"name\":\"Bash\",\"input\":{{\"command\":\"cargo test\"}}}}]}}}}'\n\
+             for _ in $(seq 1 100); do\n\
+             grep -q tool_call '{events}' 2>/dev/null && break\n\
+             sleep 0.1\n\
+             done\n\
+             grep -q tool_call '{events}' || exit 1\n\
+             echo '{{\"type\":\"result\",\"result\":\"fixed\"}}'\n",
+            events = events.display()
+        ),
+    )
+    .unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let tool_json = serde_json::json!({
+        "api_version": "v1",
+        "id": "tool-a",
+        "display_name": "Tool A",
+        "performer": {
+            "command": script.to_string_lossy(),
+            "output": { "format": "stream-json" },
+        },
+    });
+    std::fs::write(macc.join("tool.json"), tool_json.to_string()).unwrap();
+
+    let task = serde_json::json!({
+        "id": "T1",
+        "tool": "tool-a",
+        "worktree": { "worktree_path": worktree.to_string_lossy() },
+    });
+    let output = run_phase_for_task_native(&root, &task, "fix", None, 1, None)
+        .unwrap()
+        .expect("phase must see its own progress event before exiting");
+    assert_eq!(output, "fixed");
+
+    let raw = std::fs::read_to_string(&events).unwrap();
+    let progress = raw
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+        .find(|event| event["payload"]["activity"] == "tool_call")
+        .expect("tool call progress event");
+    assert_eq!(progress["type"], "progress");
+    assert_eq!(progress["phase"], "fix");
+    assert_eq!(progress["payload"]["detail"], "cargo test");
+
+    let _ = std::fs::remove_dir_all(&root);
+}
@@ -0,0 +1,118 @@
+use macc_core::coordinator::control_plane::sync_registry_from_prd_native;
+use macc_core::coordinator::operator::{run_task_command, TaskCommand};
+use macc_core::coordinator::replay::{replay_registry, verify_registry, ReplayUntil};
+use macc_core::coordinator::state::{
+    coordinator_state_apply_transition, coordinator_state_registry_load,
+    coordinator_state_registry_save, coordinator_state_set_runtime,
+};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Keeps test results independent of the developer's `~/.config/macc/macc.yaml`.
+fn isolate_user_config() {
+    std::env::set_var(macc_core::config::layers::NO_USER_CONFIG_ENV, "1");
+}
+
+fn args(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+    pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+fn state(registry: &serde_json::Value, id: &str) -> String {
+    registry["tasks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|t| t["id"] == id)
+        .and_then(|t| t["state"].as_str())
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn exercise(mode: &str) {
+    std::env::set_var("COORDINATOR_STORAGE_MODE", mode);
+    let root = std::env::temp_dir().join(format!(
+        "macc_replay_{}_{}",
+        mode,
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    ));
+    std::fs::create_dir_all(&root).unwrap();
+    let prd = serde_json::json!({"tasks": [
+        {"id": "T1", "title": "one", "priority": "p2", "dependencies": []},
+        {"id": "T2", "title": "two", "priority": "p1", "dependencies": ["T1"]}
+    ]});
+    let prd_path = root.join("prd.json");
+    std::fs::write(&prd_path, prd.to_string()).unwrap();
+    sync_registry_from_prd_native(&root, &prd_path, None).unwrap();
+    let synced = replay_registry(&root, None).unwrap();
+    let first_event = synced.last_event.clone().unwrap().0;
+
+    coordinator_state_apply_transition(
+        &root,
+        &args(&[("task-id", "T1"), ("state", "in_progress")]),
+    )
+    .unwrap();
+    coordinator_state_set_runtime(
+        &root,
+        &args(&[
+            ("task-id", "T1"),
+            ("runtime-status", "phase_done"),
+            ("phase", "dev"),
+        ]),
+    )
+    .unwrap();
+    run_task_command(&root, &TaskCommand::Block("T1".into()), "alice", None).unwrap();
+    run_task_command(
+        &root,
+        &TaskCommand::SetPriority {
+            task_id: "T2".into(),
+            priority: "p0".into(),
+        },
+        "alice",
+        None,
+    )
+    .unwrap();
+    sync_registry_from_prd_native(&root, &prd_path, None).unwrap();
+
+    let verification = verify_registry(&root).unwrap();
+    assert_eq!(verification.tasks_checked, 2);
+    assert!(
+        verification.divergences.is_empty(),
+        "{} divergences: {:?}",
+        mode,
+        verification.divergences
+    );
+    assert!(verification.outcome.errors.is_empty());
+    assert_eq!(state(&verification.outcome.registry, "T1"), "blocked");
+    assert_eq!(verification.outcome.registry["tasks"][1]["priority"], "p0");
+
+    // Time travel back to the first sync.
+    let until: ReplayUntil = first_event.parse().unwrap();
+    let earlier = replay_registry(&root, Some(&until)).unwrap();
+    assert_eq!(state(&earlier.registry, "T1"), "todo");
+    assert_eq!(earlier.registry["tasks"][1]["priority"], "p1");
+
+    // An edit that bypassed the event log shows up as a divergence.
+    let mut registry = coordinator_state_registry_load(&root, &BTreeMap::new()).unwrap();
+    registry["tasks"][0]["state"] = "merged".into();
+    coordinator_state_registry_save(&root, &BTreeMap::new(), &registry).unwrap();
+    let divergences = verify_registry(&root).unwrap().divergences;
+    assert_eq!(divergences.len(), 1, "{:?}", divergences);
+    assert_eq!(divergences[0].task_id, "T1");
+    assert_eq!(divergences[0].differences[0].field, "state");
+
+    let _ = std::fs::remove_dir_all(Path::new(&root));
+}
+
+#[test]
+fn test_replay_rebuilds_the_live_registry_in_json_and_sqlite_storage() {
+    isolate_user_config();
+    exercise("json");
+    exercise("sqlite");
+    std::env::remove_var("COORDINATOR_STORAGE_MODE");
+}
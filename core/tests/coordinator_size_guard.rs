@@ -0,0 +1,139 @@
+use macc_core::config::{SizeGuardConfig, SizeLimits};
+use macc_core::coordinator::control_plane::advance_tasks_native;
+use macc_core::coordinator::runtime::CoordinatorRunState;
+use macc_core::coordinator::size_guard;
+use macc_core::coordinator::state::{
+    coordinator_state_registry_load, coordinator_state_registry_save,
+};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Keeps test results independent of the developer's `~/.config/macc/macc.yaml`.
+fn isolate_user_config() {
+    std::env::set_var(macc_core::config::layers::NO_USER_CONFIG_ENV, "1");
+}
+
+fn git(root: &Path, args: &[&str]) {
+    let status = std::process::Command::new("git")
+        .args(args)
+        .current_dir(root)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {:?} failed", args);
+}
+
+fn commit(root: &Path, file: &str, lines: usize) {
+    let path = root.join(file);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, "line\n".repeat(lines)).unwrap();
+    git(root, &["add", file]);
+    git(root, &["commit", "-q", "-m", file]);
+}
+
+fn task(root: &Path, index: usize) -> serde_json::Value {
+    coordinator_state_registry_load(root, &BTreeMap::new()).unwrap()["tasks"][index].clone()
+}
+
+#[test]
+fn test_size_guard_sends_oversized_tasks_back_then_blocks() {
+    isolate_user_config();
+    let root = std::env::temp_dir().join(format!(
+        "macc_size_guard_{}",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    ));
+    std::fs::create_dir_all(&root).unwrap();
+    git(&root, &["init", "-q", "-b", "main"]);
+    git(&root, &["config", "user.email", "test@example.com"]);
+    git(&root, &["config", "user.name", "test"]);
+    commit(&root, "README.md", 1);
+    git(&root, &["checkout", "-q", "-b", "ai/T1"]);
+    commit(&root, "src/a.rs", 40);
+    commit(&root, "src/b.rs", 30);
+    git(&root, &["checkout", "-q", "main"]);
+    git(&root, &["checkout", "-q", "-b", "ai/T2"]);
+    commit(&root, "docs/guide.md", 70);
+    git(&root, &["checkout", "-q", "main"]);
+
+    let config = SizeGuardConfig {
+        max_files: Some(5),
+        max_lines: Some(50),
+        categories: BTreeMap::from([(
+            "docs".to_string(),
+            SizeLimits {
+                max_files: None,
+                max_lines: Some(100),
+            },
+        )]),
+        ..SizeGuardConfig::default()
+    };
+    let docs_task = serde_json::json!({
+        "id": "T2",
+        "category": "docs",
+        "worktree": {"branch": "ai/T2", "base_branch": "main"}
+    });
+    let check = size_guard::check_task(&root, &config, &docs_task)
+        .unwrap()
+        .unwrap();
+    assert_eq!(check.stats.files, 1);
+    assert_eq!(check.stats.lines(), 70);
+    assert!(check.violations.is_empty());
+
+    coordinator_state_registry_save(
+        &root,
+        &BTreeMap::new(),
+        &serde_json::json!({"tasks": [{
+            "id": "T1",
+            "title": "T1",
+            "state": "in_progress",
+            "category": "backend",
+            "tool": "tool-a",
+            "task_runtime": {"status": "phase_done", "current_phase": "dev"},
+            "worktree": {"branch": "ai/T1", "base_branch": "main"}
+        }]}),
+    )
+    .unwrap();
+    let mut state = CoordinatorRunState::new();
+    state.size_guard = Some(config);
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .unwrap();
+    let advance = |state: &mut CoordinatorRunState| {
+        runtime
+            .block_on(advance_tasks_native(&root, None, 1, state, None))
+            .unwrap()
+            .progressed
+    };
+
+    // 70 lines against a 50-line limit: back to the fix phase with a split instruction.
+    assert!(advance(&mut state));
+    let sent_back = task(&root, 0);
+    assert_eq!(sent_back["state"], "changes_requested");
+    assert_eq!(sent_back["task_runtime"]["metrics"]["diff"]["files"], 2);
+    assert_eq!(sent_back["task_runtime"]["metrics"]["diff"]["lines"], 70);
+    assert_eq!(sent_back["task_runtime"]["metrics"]["size_rejections"], 1);
+    let feedback = sent_back["review_comments"][0]["body"].as_str().unwrap();
+    assert!(feedback.starts_with("Split or reduce"), "{}", feedback);
+    assert!(
+        feedback.contains("70 lines changed (max 50)"),
+        "{}",
+        feedback
+    );
+
+    // Still too large when it comes back for integration: blocked.
+    let mut registry = coordinator_state_registry_load(&root, &BTreeMap::new()).unwrap();
+    registry["tasks"][0]["state"] = serde_json::Value::String("pr_open".into());
+    coordinator_state_registry_save(&root, &BTreeMap::new(), &registry).unwrap();
+    assert!(advance(&mut state));
+    let blocked = task(&root, 0);
+    assert_eq!(blocked["state"], "blocked");
+    assert!(blocked["task_runtime"]["last_error"]
+        .as_str()
+        .unwrap()
+        .starts_with("size limit exceeded"));
+
+    let _ = std::fs::remove_dir_all(&root);
+}
@@ -1,8 +1,14 @@
 use macc_core::{init, ProjectPaths, Result, BASELINE_IGNORE_ENTRIES};
 use std::fs;
 
+/// Keeps test results independent of the developer's `~/.config/macc/macc.yaml`.
+fn isolate_user_config() {
+    std::env::set_var(macc_core::config::layers::NO_USER_CONFIG_ENV, "1");
+}
+
 #[test]
 fn test_init_creates_canonical_structure_and_is_idempotent() -> Result<()> {
+    isolate_user_config();
     let temp_dir = std::env::temp_dir().join(format!("macc_integration_init_{}", uuid_v4_like()));
     if temp_dir.exists() {
         fs::remove_dir_all(&temp_dir).unwrap();
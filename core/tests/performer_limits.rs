@@ -0,0 +1,128 @@
+use macc_core::config::ResourceLimits;
+use macc_core::coordinator::limits::PerformerLimitGuard;
+use macc_core::coordinator_storage::{CoordinatorStorage, CoordinatorStoragePaths, SqliteStorage};
+use macc_core::ProjectPaths;
+
+/// Keeps test results independent of the developer's `~/.config/macc/macc.yaml`.
+fn isolate_user_config() {
+    std::env::set_var(macc_core::config::layers::NO_USER_CONFIG_ENV, "1");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_cpu_limit_kills_process_and_is_classified() {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let guard = PerformerLimitGuard::prepare(
+        "T1",
+        ResourceLimits {
+            cpu_seconds: Some(1),
+            ..Default::default()
+        },
+    );
+    let mut cmd = tokio::process::Command::new("sh");
+    cmd.args(["-c", "while :; do :; done"]);
+    guard.apply(cmd.as_std_mut());
+    let status = runtime.block_on(async {
+        let mut child = cmd.spawn().expect("spawn busy loop");
+        tokio::time::timeout(std::time::Duration::from_secs(30), child.wait())
+            .await
+            .expect("cpu limit must stop the process")
+            .unwrap()
+    });
+
+    assert!(!status.success());
+    let violation = guard.violation(&status).expect("limit violation");
+    assert_eq!(violation.limit, "cpu_seconds");
+    assert_eq!(violation.value, 1);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_phase_tool_call_runs_under_resource_limits() {
+    isolate_user_config();
+    let root = std::env::temp_dir().join(format!(
+        "macc_phase_limits_{}",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    ));
+    let worktree = root.join("worktrees").join("T1");
+    std::fs::create_dir_all(worktree.join(".macc")).unwrap();
+    std::fs::create_dir_all(root.join(".macc")).unwrap();
+    std::fs::write(
+        root.join(".macc").join("macc.yaml"),
+        "version: v1\ntools:\n  enabled: [tool-a]\nautomation:\n  coordinator:\n    resource_limits:\n      default:\n        cpu_seconds: 1\n",
+    )
+    .unwrap();
+    let tool_json = serde_json::json!({
+        "api_version": "v1",
+        "id": "tool-a",
+        "display_name": "Tool A",
+        "performer": { "command": "sh", "args": ["-c", "while :; do :; done"] },
+    });
+    std::fs::write(
+        worktree.join(".macc").join("tool.json"),
+        tool_json.to_string(),
+    )
+    .unwrap();
+    let task = serde_json::json!({
+        "id": "T1",
+        "tool": "tool-a",
+        "worktree": { "worktree_path": worktree.to_string_lossy() },
+    });
+
+    let reason = macc_core::coordinator::control_plane::run_phase_for_task_native(
+        &root, &task, "review", None, 3, None,
+    )
+    .unwrap()
+    .expect_err("cpu limit must stop the phase tool");
+
+    assert!(reason.contains("cpu_seconds=1"), "{}", reason);
+    assert!(reason.contains("E105"), "{}", reason);
+    assert!(reason.contains("attempt 1/3"), "{}", reason);
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn test_limit_downgrade_is_reported_as_event() {
+    let root = std::env::temp_dir().join(format!(
+        "macc_limits_downgrade_{}",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    ));
+    std::fs::create_dir_all(root.join(".macc")).unwrap();
+    let guard = PerformerLimitGuard::prepare(
+        "T1",
+        ResourceLimits {
+            max_processes: Some(64),
+            ..Default::default()
+        },
+    );
+    guard.report_downgrade(&root, "T1", "dev");
+
+    let paths = CoordinatorStoragePaths::from_project_paths(&ProjectPaths::from_root(&root));
+    let events = SqliteStorage::new(paths)
+        .load_snapshot()
+        .map(|snapshot| snapshot.events)
+        .unwrap_or_default();
+    let downgraded: Vec<_> = events
+        .iter()
+        .filter(|e| e["type"] == "resource_limits_downgraded")
+        .collect();
+    // Hosts with a delegated cgroup v2 enforce the limit and report nothing.
+    if guard.resources().cgroup.is_some() {
+        assert!(downgraded.is_empty(), "{:?}", downgraded);
+    } else {
+        assert_eq!(guard.resources().limits.max_processes, None);
+        assert_eq!(downgraded.len(), 1, "{:?}", events);
+        let message = downgraded[0]["payload"]["message"].as_str().unwrap();
+        assert!(message.contains("max_processes=64 skipped"), "{}", message);
+    }
+    let _ = std::fs::remove_dir_all(&root);
+}
@@ -0,0 +1,170 @@
+use macc_core::performer::invoke::{invoke_tool, ToolInvocation};
+use macc_core::tool::ToolPerformerSpec;
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Keeps test results independent of the developer's `~/.config/macc/macc.yaml`.
+fn isolate_user_config() {
+    std::env::set_var(macc_core::config::layers::NO_USER_CONFIG_ENV, "1");
+}
+
+fn sandbox_available(mode: &str) -> bool {
+    let probe = match mode {
+        "bwrap" => Command::new("bwrap")
+            .args(["--unshare-all", "--ro-bind", "/", "/", "true"])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status(),
+        _ => Command::new("unshare")
+            .args(["--user", "--map-root-user", "--mount", "true"])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status(),
+    };
+    matches!(probe, Ok(status) if status.success())
+}
+
+fn run_sandboxed(mode: &str, root: &Path) {
+    let repo = root.join("repo");
+    let worktree = repo.join(".macc").join("worktree").join("worker-01");
+    let outside = root.join("outside");
+    fs::create_dir_all(&worktree).unwrap();
+    fs::create_dir_all(&outside).unwrap();
+
+    let script = format!(
+        "touch inside.txt; touch '{}/escape.txt'; touch '{}/repo-root.txt'; echo finished",
+        outside.display(),
+        repo.display()
+    );
+    let spec: ToolPerformerSpec = serde_json::from_value(serde_json::json!({
+        "runner": "unused",
+        "command": "sh",
+        "args": ["-c", script],
+        "sandbox": { "mode": mode },
+    }))
+    .unwrap();
+    let invocation = ToolInvocation {
+        tool_id: "fake",
+        spec: &spec,
+        repo: &repo,
+        worktree: &worktree,
+        cwd: &worktree,
+        task_id: "T1",
+        attempt: 1,
+        lease_ttl_seconds: 1800,
+        limits: None,
+        env: None,
+    };
+    let result = invoke_tool(&invocation, "prompt", &mut |_| {}).unwrap();
+
+    assert!(result.output.contains("finished"), "{}", result.output);
+    assert!(
+        worktree.join("inside.txt").is_file(),
+        "{mode}: worktree must stay writable"
+    );
+    assert!(
+        !outside.join("escape.txt").exists(),
+        "{mode}: write outside the worktree must be denied"
+    );
+    assert!(
+        !repo.join("repo-root.txt").exists(),
+        "{mode}: write to the repository root must be denied"
+    );
+    assert!(
+        !repo
+            .join(".macc")
+            .join("tmp")
+            .read_dir()
+            .is_ok_and(|mut d| d.next().is_some()),
+        "{mode}: sandbox scratch dir must be removed"
+    );
+}
+
+/// Sandbox tests need the binary and unprivileged user namespaces, so they are opt-in
+/// (`make test-sandbox`) and fail instead of passing when the host cannot run them.
+fn require_sandbox(mode: &str) {
+    assert!(
+        sandbox_available(mode),
+        "{mode} sandbox is unavailable on this host (missing binary or user namespaces)"
+    );
+}
+
+fn temp_root(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!(
+        "macc_sandbox_{}_{}",
+        name,
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    ))
+}
+
+#[test]
+#[ignore = "needs unshare with user namespaces; run with `make test-sandbox`"]
+fn test_unshare_sandbox_denies_writes_outside_worktree() {
+    require_sandbox("unshare");
+    let root = temp_root("unshare");
+    run_sandboxed("unshare", &root);
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+#[ignore = "needs bwrap with user namespaces; run with `make test-sandbox`"]
+fn test_bwrap_sandbox_denies_writes_outside_worktree() {
+    require_sandbox("bwrap");
+    let root = temp_root("bwrap");
+    run_sandboxed("bwrap", &root);
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+#[ignore = "needs unshare with user namespaces; run with `make test-sandbox`"]
+fn test_coordinator_phase_runs_in_coordinator_sandbox() {
+    isolate_user_config();
+    require_sandbox("unshare");
+    let root = temp_root("phase");
+    let repo = root.join("repo");
+    let worktree = repo.join(".macc").join("worktree").join("worker-01");
+    let outside = root.join("outside");
+    fs::create_dir_all(worktree.join(".macc")).unwrap();
+    fs::create_dir_all(&outside).unwrap();
+    fs::write(
+        repo.join(".macc").join("macc.yaml"),
+        "version: v1\ntools:\n  enabled: [tool-a]\nautomation:\n  coordinator:\n    sandbox:\n      tool-a:\n        mode: unshare\n",
+    )
+    .unwrap();
+    let tool_json = serde_json::json!({
+        "api_version": "v1",
+        "id": "tool-a",
+        "display_name": "Tool A",
+        "performer": {
+            "command": "sh",
+            "args": ["-c", format!("touch '{}/escape.txt'; echo reviewed", outside.display())],
+        },
+    });
+    fs::write(
+        worktree.join(".macc").join("tool.json"),
+        tool_json.to_string(),
+    )
+    .unwrap();
+    let task = serde_json::json!({
+        "id": "T1",
+        "tool": "tool-a",
+        "worktree": { "worktree_path": worktree.to_string_lossy() },
+    });
+
+    let output = macc_core::coordinator::control_plane::run_phase_for_task_native(
+        &repo, &task, "fix", None, 1, None,
+    )
+    .unwrap()
+    .unwrap();
+
+    assert!(output.contains("reviewed"), "{}", output);
+    assert!(
+        !outside.join("escape.txt").exists(),
+        "phase tool must run in the tool's coordinator sandbox"
+    );
+    let _ = fs::remove_dir_all(&root);
+}
@@ -1,8 +1,14 @@
 use macc_core::{apply, init, plan, plan::ActionStatus, ProjectPaths, Result, ToolRegistry};
 use std::fs;
 
+/// Keeps test results independent of the developer's `~/.config/macc/macc.yaml`.
+fn isolate_user_config() {
+    std::env::set_var(macc_core::config::layers::NO_USER_CONFIG_ENV, "1");
+}
+
 #[test]
 fn test_plan_apply_lifecycle() -> Result<()> {
+    isolate_user_config();
     let temp_dir =
         std::env::temp_dir().join(format!("macc_integration_plan_apply_{}", uuid_v4_like()));
     if temp_dir.exists() {
@@ -133,6 +139,7 @@ fn test_plan_apply_lifecycle() -> Result<()> {
 
 #[test]
 fn test_apply_with_mcp_selection() -> Result<()> {
+    isolate_user_config();
     let temp_dir =
         std::env::temp_dir().join(format!("macc_integration_mcp_selection_{}", uuid_v4_like()));
     if temp_dir.exists() {
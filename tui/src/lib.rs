@@ -24,10 +24,14 @@ use screen::Screen;
 use state::AppState;
 use ui::{compact_help_line, header_lines, panel, theme, wrapped_paragraph, HeaderContext};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum LaunchMode {
     Default,
-    CoordinatorRun,
+    /// Open the Coordinator Live screen and start `run`, serving OpenMetrics on
+    /// `metrics_listen` when set.
+    CoordinatorRun {
+        metrics_listen: Option<String>,
+    },
 }
 
 /// RAII guard to ensure terminal state is restored on drop.
@@ -55,15 +59,19 @@ impl Drop for TerminalGuard {
 }
 
 pub fn run_tui() -> Result<()> {
-    run_tui_with_launch(LaunchMode::Default)
+    run_tui_with_launch(LaunchMode::Default, None)
 }
 
-pub fn run_tui_with_launch(mode: LaunchMode) -> Result<()> {
+/// Runs the TUI; `profile` is the config profile from `--profile`.
+pub fn run_tui_with_launch(mode: LaunchMode, profile: Option<String>) -> Result<()> {
     let mut guard = TerminalGuard::new()?;
     let registry = macc_registry::default_registry();
     let engine = std::sync::Arc::new(macc_core::MaccEngine::new(registry));
-    let mut state = AppState::new(engine);
-    if mode == LaunchMode::CoordinatorRun {
+    let mut state = AppState::with_profile(engine, profile);
+    if let LaunchMode::CoordinatorRun { metrics_listen } = mode {
+        if let Some(listen) = metrics_listen {
+            state.coordinator_run_args = vec!["--metrics-listen".to_string(), listen];
+        }
         state.goto_screen(Screen::CoordinatorLive);
         state.start_coordinator_action("run");
     }
@@ -155,6 +163,25 @@ fn handle_key(state: &mut AppState, key: KeyCode) {
         }
         return;
     }
+    if current_screen == Screen::CoordinatorLive && state.is_coordinator_rejection_editing() {
+        match key {
+            KeyCode::Enter => state.reject_reviewed_coordinator_task(),
+            KeyCode::Esc => state.cancel_coordinator_rejection(),
+            KeyCode::Backspace => state.pop_coordinator_rejection_char(),
+            KeyCode::Char(c) => state.append_coordinator_rejection_char(c),
+            _ => {}
+        }
+        return;
+    }
+    if current_screen == Screen::CoordinatorLive && state.is_coordinator_approval_review_open() {
+        match key {
+            KeyCode::Char('a') => state.approve_reviewed_coordinator_task(),
+            KeyCode::Char('r') => state.begin_coordinator_rejection(),
+            KeyCode::Char('n') | KeyCode::Esc => state.close_coordinator_approval_review(),
+            _ => {}
+        }
+        return;
+    }
     if current_screen == Screen::Tools && state.is_tool_install_confirmation_open() {
         match key {
             KeyCode::Char('y') | KeyCode::Enter => state.confirm_tool_install(),
@@ -287,6 +314,9 @@ fn handle_key(state: &mut AppState, key: KeyCode) {
                 state.stop_coordinator_action();
             }
         }
+        KeyCode::Char('A') if current_screen == Screen::CoordinatorLive => {
+            state.open_coordinator_approval_review();
+        }
         KeyCode::Char('l') => {
             if current_screen == Screen::CoordinatorLive {
                 state.refresh_coordinator_snapshot();
@@ -1179,6 +1209,25 @@ fn ui(f: &mut Frame, state: &AppState, full_clear: bool) {
             } else {
                 "Tasks: unavailable".to_string()
             };
+            let approval_line = state
+                .coordinator_snapshot
+                .as_ref()
+                .filter(|s| !s.pending_approvals.is_empty())
+                .map(|s| {
+                    let ids: Vec<&str> = s
+                        .pending_approvals
+                        .iter()
+                        .map(|p| p.task_id.as_str())
+                        .collect();
+                    format!("Awaiting approval: {}", ids.join(", "))
+                })
+                .unwrap_or_else(|| "Awaiting approval: none".to_string());
+            let usage_line = state
+                .coordinator_snapshot
+                .as_ref()
+                .filter(|s| !s.usage.is_empty())
+                .map(|s| format!("Usage: {}", s.usage.summary()))
+                .unwrap_or_else(|| "Usage: n/a".to_string());
             let refresh_line = state
                 .coordinator_last_refresh
                 .map(|ts| format!("Last refresh: {} ago", format_hms(ts.elapsed().as_secs())))
@@ -1191,6 +1240,10 @@ fn ui(f: &mut Frame, state: &AppState, full_clear: bool) {
                 .coordinator_last_event_age
                 .map(|d| format!("Last event age: {}", format_hms(d.as_secs())))
                 .unwrap_or_else(|| "Last event age: n/a".to_string());
+            let lease_line = format!(
+                "Lease: {}",
+                state.coordinator_lease.as_deref().unwrap_or("n/a")
+            );
             let run_id_line = state
                 .coordinator_current_run_id
                 .as_deref()
@@ -1200,16 +1253,37 @@ fn ui(f: &mut Frame, state: &AppState, full_clear: bool) {
                 .coordinator_last_result
                 .clone()
                 .unwrap_or_else(|| "Last result: n/a".to_string());
-            let runtime = format!(
-                "Coordinator runtime\n\n{}\n{}\n{}\n{}\n{}\n{}\n\n{}\n\nActions:\n- r: run full cycle\n- y: sync registry\n- c: reconcile\n- u: resume paused run\n- k: stop\n- l: refresh status",
+            let mut runtime = format!(
+                "Coordinator runtime\n\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n\n{}\n\nActions:\n- r: run full cycle\n- y: sync registry\n- c: reconcile\n- u: resume paused run\n- A: review next awaiting merge\n- k: stop\n- l: refresh status",
                 status_line,
                 snapshot_line,
+                approval_line,
+                usage_line,
                 refresh_line,
                 events_rate_line,
                 event_age_line,
+                lease_line,
                 run_id_line,
                 result_line
             );
+            if let Some(review) = &state.coordinator_approval_review {
+                runtime.push_str(&format!(
+                    "\n\nApproval request for task {}\n{}\n",
+                    review.request.task_id, review.request.prompt
+                ));
+                for reason in &review.request.reasons {
+                    runtime.push_str(&format!("- {}\n", reason));
+                }
+                match &review.rejection {
+                    Some(comment) => runtime.push_str(&format!(
+                        "Rejection comment: {}_\nEnter to reject, Esc to go back.",
+                        comment
+                    )),
+                    None => runtime.push_str(
+                        "Press 'a' to approve, 'r' to reject with a comment, 'n' to close.",
+                    ),
+                }
+            }
             let runtime_para = wrapped_paragraph(runtime, "Runtime");
             f.render_widget(runtime_para, body_chunks[0]);
 
@@ -1233,6 +1307,12 @@ fn ui(f: &mut Frame, state: &AppState, full_clear: bool) {
                             task.last_heartbeat,
                             task.updated_at
                         ));
+                        if let Some(activity) = state.coordinator_task_activity.get(&task.id) {
+                            active_view.push_str(&format!("    activity: {}\n", activity));
+                        }
+                        if !task.usage.is_empty() {
+                            active_view.push_str(&format!("    usage: {}\n", task.usage.summary()));
+                        }
                         if !task.last_error.is_empty() {
                             active_view.push_str(&format!("    error: {}\n", task.last_error));
                         }
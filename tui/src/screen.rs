@@ -66,6 +66,7 @@ impl Screen {
                 ("y", "Sync Registry"),
                 ("c", "Reconcile"),
                 ("k", "Stop Coordinator"),
+                ("A", "Approve Next Awaiting Merge"),
                 ("l", "Refresh Live Status"),
                 ("r/Enter (on error)", "Retry failed phase + resume"),
                 ("s (on error)", "Skip failed phase + resume"),
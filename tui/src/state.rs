@@ -3,6 +3,8 @@ use chrono::{DateTime, Utc};
 use macc_adapter_shared::fetch::materialize_fetch_units;
 use macc_core::catalog::{Agent, McpEntry, Skill};
 use macc_core::config::{CanonicalConfig, CoordinatorConfig};
+use macc_core::coordinator::approval::PendingApproval;
+use macc_core::coordinator::usage::{task_usage, UsageTotals};
 use macc_core::coordinator_storage::{
     CoordinatorSnapshot, CoordinatorStorage, CoordinatorStoragePaths, JsonStorage, SqliteStorage,
 };
@@ -103,6 +105,18 @@ pub struct CoordinatorTaskSnapshot {
     pub blocked: usize,
     pub merged: usize,
     pub active_tasks: Vec<CoordinatorActiveTask>,
+    /// Token usage and estimated cost summed over all tasks.
+    pub usage: UsageTotals,
+    /// Queued tasks waiting for a merge approval.
+    pub pending_approvals: Vec<PendingApproval>,
+}
+
+/// The approval request open in Coordinator Live, waiting for `a` (approve) or `r` (reject).
+#[derive(Clone)]
+pub struct ApprovalReview {
+    pub request: PendingApproval,
+    /// Rejection comment being typed; `None` until `r` is pressed.
+    pub rejection: Option<String>,
 }
 
 #[derive(Clone)]
@@ -116,6 +130,7 @@ pub struct CoordinatorActiveTask {
     pub current_phase: String,
     pub last_error: String,
     pub last_heartbeat: String,
+    pub usage: UsageTotals,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -126,6 +141,8 @@ enum CoordinatorPauseNextAction {
 
 pub struct AppState {
     pub engine: Arc<dyn Engine>,
+    /// Config profile from `--profile`, attached to the loaded project paths.
+    pub config_profile: Option<String>,
     pub project_paths: Option<ProjectPaths>,
     pub config: Option<CanonicalConfig>,
     pub working_copy: Option<CanonicalConfig>,
@@ -185,7 +202,12 @@ pub struct AppState {
     pub coordinator_events_per_sec: Option<f64>,
     pub coordinator_last_event_age: Option<Duration>,
     pub coordinator_paused: bool,
+    /// Description of the project coordinator lease (owner or stale holder), if any.
+    pub coordinator_lease: Option<String>,
+    pub coordinator_approval_review: Option<ApprovalReview>,
     pub coordinator_current_run_id: Option<String>,
+    /// Latest tool activity (tool call, file edit, token usage) per task in the current run.
+    pub coordinator_task_activity: BTreeMap<String, String>,
     coordinator_events_last_seen_count: usize,
     pub search_query: String,
     pub search_editing: bool,
@@ -193,6 +215,8 @@ pub struct AppState {
     pub redo_stack: Vec<CanonicalConfig>,
     coordinator_running_elapsed_secs: Option<u64>,
     coordinator_pause_next_action: Option<CoordinatorPauseNextAction>,
+    /// Extra `control-plane-run` arguments for every `run` started from this session.
+    pub coordinator_run_args: Vec<String>,
 }
 
 impl AppState {
@@ -205,7 +229,12 @@ impl AppState {
     }
 
     pub fn new(engine: Arc<dyn Engine>) -> Self {
+        Self::with_profile(engine, None)
+    }
+
+    pub fn with_profile(engine: Arc<dyn Engine>, profile: Option<String>) -> Self {
         let mut state = Self::with_engine(engine);
+        state.config_profile = profile;
         state.load_config(None);
         state.refresh_tool_checks();
         state
@@ -214,6 +243,7 @@ impl AppState {
     pub fn with_engine(engine: Arc<dyn Engine>) -> Self {
         let mut state = Self {
             engine,
+            config_profile: None,
             project_paths: None,
             config: None,
             working_copy: None,
@@ -273,7 +303,10 @@ impl AppState {
             coordinator_events_per_sec: None,
             coordinator_last_event_age: None,
             coordinator_paused: false,
+            coordinator_lease: None,
+            coordinator_approval_review: None,
             coordinator_current_run_id: None,
+            coordinator_task_activity: BTreeMap::new(),
             coordinator_events_last_seen_count: 0,
             search_query: String::new(),
             search_editing: false,
@@ -281,6 +314,7 @@ impl AppState {
             redo_stack: Vec::new(),
             coordinator_running_elapsed_secs: None,
             coordinator_pause_next_action: None,
+            coordinator_run_args: Vec::new(),
         };
 
         state.refresh_tools();
@@ -534,6 +568,8 @@ impl AppState {
             blocked: 0,
             merged: 0,
             active_tasks: Vec::new(),
+            usage: UsageTotals::default(),
+            pending_approvals: macc_core::coordinator::approval::pending_approval_requests(root),
         };
         for task in tasks {
             let id = task
@@ -586,6 +622,8 @@ impl AppState {
                 .and_then(|v| v.as_str())
                 .unwrap_or("-")
                 .to_string();
+            let usage = task_usage(task);
+            snapshot.usage.add(&usage);
             let is_live_active = matches!(
                 state.as_str(),
                 "claimed" | "in_progress" | "pr_open" | "changes_requested" | "queued"
@@ -607,6 +645,7 @@ impl AppState {
                         current_phase,
                         last_error,
                         last_heartbeat,
+                        usage,
                     });
                 }
                 "claimed" => {
@@ -647,6 +686,23 @@ impl AppState {
             })
             .unwrap_or(false);
         self.coordinator_paused = paused;
+        self.coordinator_lease = self
+            .project_paths
+            .as_ref()
+            .and_then(|p| macc_core::coordinator::lease::lease_status(&p.root).ok())
+            .map(|status| status.describe());
+    }
+
+    /// Returns the lease owner description when another process already drives the coordinator.
+    fn coordinator_lease_held_elsewhere(root: &std::path::Path) -> Option<String> {
+        match macc_core::coordinator::lease::lease_status(root) {
+            Ok(macc_core::coordinator::lease::LeaseStatus::Held(record))
+                if record.pid != std::process::id() =>
+            {
+                Some(record.describe())
+            }
+            _ => None,
+        }
     }
 
     fn is_essential_coordinator_event(event: &str) -> bool {
@@ -663,6 +719,7 @@ impl AppState {
                 | "dispatch_complete"
                 | "started"
                 | "progress"
+                | "artifact"
                 | "phase_result"
                 | "commit_created"
                 | "review_done"
@@ -678,9 +735,36 @@ impl AppState {
                 | "phase_skipped"
                 | "events_rotated"
                 | "events_compacted"
+                | "budget_exceeded"
+                | "tool_throttled"
+                | "resource_limit_exceeded"
+                | "resource_limits_downgraded"
         )
     }
 
+    fn latest_task_activity(events: &[&CoordinatorEvent]) -> BTreeMap<String, String> {
+        let mut activity = BTreeMap::new();
+        for event in events {
+            if !matches!(event.event_type.as_str(), "progress" | "artifact") {
+                continue;
+            }
+            let Some(payload) = event.raw.get("payload") else {
+                continue;
+            };
+            if payload.get("activity").is_none() {
+                continue;
+            }
+            let (Some(task), Some(message)) = (
+                event.task_id.as_deref(),
+                payload.get("message").and_then(|m| m.as_str()),
+            ) else {
+                continue;
+            };
+            activity.insert(task.to_string(), message.to_string());
+        }
+        activity
+    }
+
     fn resolve_current_run_id(events: &[CoordinatorEvent]) -> Option<String> {
         events
             .iter()
@@ -707,6 +791,7 @@ impl AppState {
             self.coordinator_events_per_sec = None;
             self.coordinator_last_event_age = None;
             self.coordinator_current_run_id = None;
+            self.coordinator_task_activity.clear();
             self.coordinator_events_last_seen_count = 0;
             return;
         };
@@ -717,6 +802,7 @@ impl AppState {
                 self.coordinator_events_per_sec = None;
                 self.coordinator_last_event_age = None;
                 self.coordinator_current_run_id = None;
+                self.coordinator_task_activity.clear();
                 self.coordinator_events_last_seen_count = 0;
                 return;
             }
@@ -728,6 +814,7 @@ impl AppState {
             .iter()
             .filter(|v| Self::event_matches_current_run(v, current_run_id))
             .collect();
+        self.coordinator_task_activity = Self::latest_task_activity(&filtered);
         let mut lines: Vec<String> = filtered
             .iter()
             .filter_map(|event| {
@@ -871,6 +958,19 @@ impl AppState {
             }
             return;
         }
+        if let Some(owner) = Self::coordinator_lease_held_elsewhere(&root) {
+            // Another instance owns the coordinator: follow its state instead of competing.
+            self.refresh_coordinator_snapshot();
+            self.refresh_coordinator_events();
+            self.coordinator_last_result =
+                Some(format!("Attached read-only: coordinator held by {}", owner));
+            self.set_status(
+                UiStatusLevel::Warning,
+                "Coordinator already running in another process; attached read-only.",
+                Some(Duration::from_secs(5)),
+            );
+            return;
+        }
         self.coordinator_pause_error = None;
         self.coordinator_pause_action = None;
         self.coordinator_pause_task_id = None;
@@ -917,7 +1017,153 @@ impl AppState {
 
     pub fn start_coordinator_action(&mut self, action: &str) {
         self.coordinator_pause_next_action = None;
-        self.start_coordinator_action_with_args(action, &[]);
+        let args = if action == "run" {
+            self.coordinator_run_args.clone()
+        } else {
+            Vec::new()
+        };
+        self.start_coordinator_action_with_args(action, &args);
+    }
+
+    /// Opens the first pending approval request for an explicit approve or reject.
+    pub fn open_coordinator_approval_review(&mut self) {
+        if self.project_paths.is_none() {
+            self.set_status(
+                UiStatusLevel::Warning,
+                "No project loaded.",
+                Some(Duration::from_secs(4)),
+            );
+            return;
+        }
+        self.refresh_coordinator_snapshot();
+        let Some(request) = self
+            .coordinator_snapshot
+            .as_ref()
+            .and_then(|s| s.pending_approvals.first().cloned())
+        else {
+            self.set_status(
+                UiStatusLevel::Info,
+                "No task is waiting for approval.",
+                Some(Duration::from_secs(3)),
+            );
+            return;
+        };
+        self.coordinator_approval_review = Some(ApprovalReview {
+            request,
+            rejection: None,
+        });
+    }
+
+    pub fn is_coordinator_approval_review_open(&self) -> bool {
+        self.coordinator_approval_review.is_some()
+    }
+
+    pub fn is_coordinator_rejection_editing(&self) -> bool {
+        self.coordinator_approval_review
+            .as_ref()
+            .is_some_and(|review| review.rejection.is_some())
+    }
+
+    pub fn close_coordinator_approval_review(&mut self) {
+        self.coordinator_approval_review = None;
+    }
+
+    pub fn begin_coordinator_rejection(&mut self) {
+        if let Some(review) = self.coordinator_approval_review.as_mut() {
+            review.rejection.get_or_insert_with(String::new);
+        }
+    }
+
+    /// Leaves the rejection comment and goes back to the request.
+    pub fn cancel_coordinator_rejection(&mut self) {
+        if let Some(review) = self.coordinator_approval_review.as_mut() {
+            review.rejection = None;
+        }
+    }
+
+    pub fn append_coordinator_rejection_char(&mut self, c: char) {
+        if let Some(comment) = self
+            .coordinator_approval_review
+            .as_mut()
+            .and_then(|review| review.rejection.as_mut())
+        {
+            comment.push(c);
+        }
+    }
+
+    pub fn pop_coordinator_rejection_char(&mut self) {
+        if let Some(comment) = self
+            .coordinator_approval_review
+            .as_mut()
+            .and_then(|review| review.rejection.as_mut())
+        {
+            comment.pop();
+        }
+    }
+
+    pub fn approve_reviewed_coordinator_task(&mut self) {
+        self.decide_reviewed_coordinator_task(true, None);
+    }
+
+    /// Rejects with the typed comment, which the fix phase gets as review feedback.
+    pub fn reject_reviewed_coordinator_task(&mut self) {
+        let comment = self
+            .coordinator_approval_review
+            .as_ref()
+            .and_then(|review| review.rejection.as_deref())
+            .map(str::trim)
+            .unwrap_or_default()
+            .to_string();
+        if comment.is_empty() {
+            self.set_status(
+                UiStatusLevel::Warning,
+                "A rejection needs a comment for the fix phase.",
+                Some(Duration::from_secs(4)),
+            );
+            return;
+        }
+        self.decide_reviewed_coordinator_task(false, Some(&comment));
+    }
+
+    fn decide_reviewed_coordinator_task(&mut self, approved: bool, comment: Option<&str>) {
+        let Some(review) = self.coordinator_approval_review.take() else {
+            return;
+        };
+        let Some(root) = self.project_paths.as_ref().map(|p| p.root.clone()) else {
+            return;
+        };
+        let task_id = review.request.task_id;
+        let (verb, done) = if approved {
+            ("approve", "Approved merge of")
+        } else {
+            ("reject", "Rejected merge of")
+        };
+        let user = macc_core::coordinator::approval::current_user();
+        match macc_core::coordinator::approval::decide_task_approval(
+            &root, &task_id, approved, &user, comment,
+        ) {
+            Ok(()) => {
+                self.refresh_coordinator_snapshot();
+                self.refresh_coordinator_events();
+                self.set_status(
+                    UiStatusLevel::Success,
+                    format!("{} task {}.", done, task_id),
+                    Some(Duration::from_secs(4)),
+                );
+            }
+            Err(err) => {
+                self.set_status(
+                    UiStatusLevel::Error,
+                    format!(
+                        "Failed to {} task {}: {}",
+                        verb,
+                        task_id,
+                        format_actionable_error(&err.to_string())
+                    ),
+                    Some(Duration::from_secs(6)),
+                );
+            }
+        }
     }
 
     pub fn stop_coordinator_action(&mut self) {
@@ -985,6 +1231,7 @@ impl AppState {
 
         match find_project_root(&current_dir) {
             Ok(paths) => {
+                let paths = paths.with_profile(self.config_profile.clone());
                 self.project_paths = Some(paths.clone());
                 self.refresh_tools();
                 self.refresh_skills();
@@ -993,7 +1240,8 @@ impl AppState {
                 self.refresh_worktree_status();
                 self.refresh_coordinator_snapshot();
                 self.refresh_coordinator_events();
-                match macc_core::config::load_canonical_config(&paths.config_path) {
+                // Sub-project files are partial overlays: load the merged layers like `plan`.
+                match macc_core::load_effective_config(&paths) {
                     Ok(config) => {
                         self.config = Some(config.clone());
                         self.working_copy = Some(config);
@@ -2665,16 +2913,15 @@ impl AppState {
         self.apply_tool_defaults();
         self.ensure_required_skills_selected();
 
-        let yaml = match self
-            .working_copy
-            .as_ref()
-            .expect("working_copy checked above")
-            .to_yaml()
-        {
+        let yaml = match macc_core::config::layers::project_config_yaml(
+            &paths,
+            self.working_copy
+                .as_ref()
+                .expect("working_copy checked above"),
+        ) {
             Ok(y) => y,
             Err(e) => {
-                self.errors
-                    .push(format!("Failed to serialize config: {}", e));
+                self.errors.push(format!("Failed to save config: {}", e));
                 return;
             }
         };
@@ -3356,6 +3603,11 @@ mod tests {
         macc_core::TestEngine::generate_fixture_ids(2)
     }
 
+    /// Keeps test results independent of the developer's `~/.config/macc/macc.yaml`.
+    fn isolate_user_config() {
+        std::env::set_var(macc_core::config::layers::NO_USER_CONFIG_ENV, "1");
+    }
+
     fn fixture_engine(ids: &[String]) -> Arc<macc_core::TestEngine> {
         Arc::new(macc_core::TestEngine::with_fixtures_for_ids(ids))
     }
@@ -3403,6 +3655,7 @@ mod tests {
 
     #[test]
     fn test_load_config_valid() {
+        isolate_user_config();
         let dir = tempdir().unwrap();
         let macc_dir = dir.path().join(".macc");
         fs::create_dir(&macc_dir).unwrap();
@@ -3425,6 +3678,7 @@ mod tests {
 
     #[test]
     fn test_load_config_missing() {
+        isolate_user_config();
         let dir = tempdir().unwrap();
         let engine = Arc::new(macc_core::TestEngine::with_fixtures());
         let mut state = AppState::with_engine(engine);
@@ -3437,6 +3691,7 @@ mod tests {
 
     #[test]
     fn test_load_config_invalid_yaml() {
+        isolate_user_config();
         let dir = tempdir().unwrap();
         let macc_dir = dir.path().join(".macc");
         fs::create_dir(&macc_dir).unwrap();
@@ -3453,6 +3708,7 @@ mod tests {
 
     #[test]
     fn test_save_config() {
+        isolate_user_config();
         let dir = tempdir().unwrap();
         let macc_dir = dir.path().join(".macc");
         fs::create_dir(&macc_dir).unwrap();
@@ -3491,8 +3747,47 @@ mod tests {
         assert!(state.notices[0].contains("unchanged"));
     }
 
+    #[test]
+    fn test_sub_project_config_loads_layers_and_saves_overlay() {
+        isolate_user_config();
+        let dir = tempdir().unwrap();
+        fs::create_dir(dir.path().join(".git")).unwrap();
+        fs::create_dir(dir.path().join(".macc")).unwrap();
+        let sub = dir.path().join("services").join("api");
+        fs::create_dir_all(sub.join(".macc")).unwrap();
+        let ids = fixture_ids();
+        fs::write(
+            dir.path().join(".macc").join("macc.yaml"),
+            format!(
+                "tools:\n  enabled:\n    - {}\nstandards:\n  style: terse\n",
+                ids[0]
+            ),
+        )
+        .unwrap();
+        let sub_config = sub.join(".macc").join("macc.yaml");
+        fs::write(&sub_config, "standards:\n  language: Rust\n").unwrap();
+
+        let mut state = AppState::with_engine(fixture_engine(&ids));
+        state.load_config(Some(&sub));
+        assert!(state.errors.is_empty(), "{:?}", state.errors);
+        let working_copy = state.working_copy.as_mut().unwrap();
+        assert_eq!(working_copy.tools.enabled, vec![ids[0].clone()]);
+        working_copy
+            .standards
+            .inline
+            .insert("language".into(), "Go".into());
+
+        state.save_config();
+        assert!(state.errors.is_empty(), "{:?}", state.errors);
+        let saved = fs::read_to_string(&sub_config).unwrap();
+        assert!(saved.contains("Go"), "{}", saved);
+        assert!(!saved.contains("terse"), "{}", saved);
+        assert!(!saved.contains(&ids[0]), "{}", saved);
+    }
+
     #[test]
     fn test_tool_selection_and_toggling() {
+        isolate_user_config();
         let ids = fixture_ids();
         let tool_one = ids[0].clone();
         let tool_two = ids[1].clone();
@@ -3604,6 +3899,49 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_latest_task_activity_keeps_last_message_per_task() {
+        let event = |task: &str, event_type: &str, payload: serde_json::Value| CoordinatorEvent {
+            event_id: None,
+            run_id: None,
+            event_type: event_type.to_string(),
+            task_id: Some(task.to_string()),
+            phase: None,
+            status: None,
+            ts: None,
+            message: None,
+            raw: serde_json::json!({ "type": event_type, "payload": payload }),
+        };
+        let events = [
+            event(
+                "T1",
+                "progress",
+                serde_json::json!({"activity":"tool_call","message":"shell: ls"}),
+            ),
+            event(
+                "T1",
+                "artifact",
+                serde_json::json!({"activity":"file_edit","message":"edit src/a.rs"}),
+            ),
+            event("T1", "progress", serde_json::json!({"attempt":1})),
+            event(
+                "T2",
+                "progress",
+                serde_json::json!({"activity":"token_usage","message":"tokens in=1 out=2 cached=0"}),
+            ),
+        ];
+        let refs: Vec<&CoordinatorEvent> = events.iter().collect();
+        let activity = AppState::latest_task_activity(&refs);
+        assert_eq!(
+            activity.get("T1").map(String::as_str),
+            Some("edit src/a.rs")
+        );
+        assert_eq!(
+            activity.get("T2").map(String::as_str),
+            Some("tokens in=1 out=2 cached=0")
+        );
+    }
+
     #[test]
     fn test_event_matches_current_run_filters_legacy_events() {
         let with_run = CoordinatorEvent {
@@ -3660,6 +3998,7 @@ mod tests {
             metadata: PlannedOpMetadata::default(),
             before: Some(b"line\n".to_vec()),
             after: Some(b"line\nnew content\n".to_vec()),
+            sensitive_values: Default::default(),
         };
 
         state.preview_ops = vec![op];
@@ -3674,6 +4013,7 @@ mod tests {
 
     #[test]
     fn test_tool_settings_navigation_and_cycling() {
+        isolate_user_config();
         let ids = fixture_ids();
         let tool_two = ids[1].clone();
         let engine = fixture_engine(&ids);
@@ -3731,6 +4071,7 @@ mod tests {
 
     #[test]
     fn test_skills_selection() {
+        isolate_user_config();
         let ids = fixture_ids();
         let tool_one = ids[0].clone();
         let engine = fixture_engine(&ids);
@@ -3849,6 +4190,7 @@ mod tests {
 
     #[test]
     fn test_agents_selection() {
+        isolate_user_config();
         let ids = fixture_ids();
         let tool_one = ids[0].clone();
         let engine = fixture_engine(&ids);
@@ -3954,6 +4296,7 @@ mod tests {
 
     #[test]
     fn test_mcp_selection_toggle_and_bulk() {
+        isolate_user_config();
         let temp = tempdir().unwrap();
         let paths = ProjectPaths::from_root(temp.path());
         std::fs::create_dir_all(&paths.macc_dir).unwrap();
@@ -4069,6 +4412,7 @@ mod tests {
 
     #[test]
     fn test_unified_navigation() {
+        isolate_user_config();
         let ids = fixture_ids();
         let tool_one = ids[0].clone();
         let engine = fixture_engine(&ids);
@@ -4199,6 +4543,66 @@ mod tests {
         assert!(state.current_automation_field_validation().is_none());
     }
 
+    #[test]
+    fn test_approval_review_shows_the_request_and_needs_a_decision() {
+        isolate_user_config();
+        let temp = tempdir().unwrap();
+        let registry = serde_json::json!({"tasks": [{
+            "id": "T1",
+            "state": "queued",
+            "task_runtime": {"status": "waiting_for_user", "current_phase": "integrate"},
+            "approval": {
+                "status": "pending",
+                "request": {
+                    "request_id": "approval-T1",
+                    "prompt": "Approve merging task T1 into main?",
+                    "kind": "confirm_yes_no"
+                },
+                "reasons": ["path migrations/001.sql (migrations/**)"]
+            }
+        }]});
+        macc_core::coordinator::state::coordinator_state_registry_save(
+            temp.path(),
+            &BTreeMap::new(),
+            &registry,
+        )
+        .unwrap();
+        let engine = Arc::new(MaccEngine::new(ToolRegistry::new()));
+        let mut state = AppState::with_engine(engine);
+        state.project_paths = Some(ProjectPaths::from_root(temp.path()));
+
+        state.open_coordinator_approval_review();
+        let review = state.coordinator_approval_review.clone().unwrap();
+        assert_eq!(review.request.task_id, "T1");
+        assert_eq!(review.request.prompt, "Approve merging task T1 into main?");
+        assert_eq!(
+            review.request.reasons,
+            vec!["path migrations/001.sql (migrations/**)"]
+        );
+
+        // Closing decides nothing; a rejection needs a comment.
+        state.close_coordinator_approval_review();
+        state.open_coordinator_approval_review();
+        state.begin_coordinator_rejection();
+        state.reject_reviewed_coordinator_task();
+        assert!(state.is_coordinator_rejection_editing());
+        for c in "Split it".chars() {
+            state.append_coordinator_rejection_char(c);
+        }
+        state.reject_reviewed_coordinator_task();
+        assert!(!state.is_coordinator_approval_review_open());
+
+        let task = macc_core::coordinator::state::coordinator_state_registry_load(
+            temp.path(),
+            &BTreeMap::new(),
+        )
+        .unwrap()["tasks"][0]
+            .clone();
+        assert_eq!(task["state"], "changes_requested");
+        assert_eq!(task["approval"]["status"], "rejected");
+        assert_eq!(task["review_comments"][0]["body"], "Split it");
+    }
+
     #[test]
     fn test_format_actionable_error_includes_cause_and_fix() {
         let msg = format_actionable_error("invalid registry JSON");